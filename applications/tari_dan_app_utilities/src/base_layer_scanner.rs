@@ -51,7 +51,7 @@ use tari_engine_types::{
 };
 use tari_epoch_manager::{base_layer::EpochManagerHandle, EpochManagerError};
 use tari_shutdown::ShutdownSignal;
-use tari_state_store_sqlite::SqliteStateStore;
+use tari_state_store_backend::AnyStateStore;
 use tari_template_lib::models::{EncryptedData, TemplateAddress, UnclaimedConfidentialOutputAddress};
 use tokio::{task, task::JoinHandle, time};
 
@@ -69,7 +69,7 @@ pub fn spawn(
     template_manager: TemplateManagerHandle,
     shutdown: ShutdownSignal,
     consensus_constants: ConsensusConstants,
-    shard_store: SqliteStateStore<PublicKey>,
+    shard_store: AnyStateStore<PublicKey>,
     scan_base_layer: bool,
     base_layer_scanning_interval: Duration,
 ) -> JoinHandle<anyhow::Result<()>> {
@@ -102,7 +102,7 @@ pub struct BaseLayerScanner {
     template_manager: TemplateManagerHandle,
     shutdown: ShutdownSignal,
     consensus_constants: ConsensusConstants,
-    state_store: SqliteStateStore<PublicKey>,
+    state_store: AnyStateStore<PublicKey>,
     scan_base_layer: bool,
     base_layer_scanning_interval: Duration,
     has_attempted_scan: bool,
@@ -116,7 +116,7 @@ impl BaseLayerScanner {
         template_manager: TemplateManagerHandle,
         shutdown: ShutdownSignal,
         consensus_constants: ConsensusConstants,
-        state_store: SqliteStateStore<PublicKey>,
+        state_store: AnyStateStore<PublicKey>,
         scan_base_layer: bool,
         base_layer_scanning_interval: Duration,
     ) -> Self {
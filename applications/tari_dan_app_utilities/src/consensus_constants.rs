@@ -20,6 +20,8 @@
 //  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 //  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
+use std::time::Duration;
+
 use tari_dan_common_types::NodeHeight;
 
 #[derive(Clone, Debug)]
@@ -27,6 +29,15 @@ pub struct ConsensusConstants {
     pub base_layer_confirmations: u64,
     pub committee_size: u32,
     pub hotstuff_rounds: u64,
+    /// The target time between blocks. All validators on the network must agree on this value.
+    pub block_time: Duration,
+    /// The maximum number of commands in a block
+    pub max_block_commands: usize,
+    /// The maximum total encoded size of the commands in a block, in bytes
+    pub max_block_size_bytes: usize,
+    /// The number of base layer blocks before the end of an epoch at which the outgoing committee stops preparing new
+    /// transactions and begins handing off to the incoming committee
+    pub epoch_transition_blocks: u64,
 }
 
 impl ConsensusConstants {
@@ -35,6 +46,10 @@ impl ConsensusConstants {
             base_layer_confirmations: 3,
             committee_size: 7,
             hotstuff_rounds: 4,
+            block_time: Duration::from_secs(10),
+            max_block_commands: 1000,
+            max_block_size_bytes: 4 * 1024 * 1024,
+            epoch_transition_blocks: 2,
         }
     }
 
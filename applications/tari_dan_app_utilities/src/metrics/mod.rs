@@ -0,0 +1,24 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+//! Metrics shared by the validator node and indexer. All metrics are registered with the default prometheus registry
+//! and are served by the metrics server.
+
+pub mod server;
+
+use lazy_static::lazy_static;
+use prometheus::{IntCounterVec, Opts};
+pub use tari_dan_common_types::metrics::register;
+
+lazy_static! {
+    pub static ref P2P_INBOUND_MESSAGES: IntCounterVec = register(
+        IntCounterVec::new(
+            Opts::new(
+                "p2p_inbound_messages_total",
+                "The number of messages received from peers by message type"
+            ),
+            &["type"]
+        )
+        .unwrap()
+    );
+}
@@ -0,0 +1,45 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use std::net::SocketAddr;
+
+use axum::{
+    http::{header, Response, StatusCode},
+    response::IntoResponse,
+    routing::get,
+    Router,
+};
+use log::{error, info};
+use prometheus::{Encoder, TextEncoder};
+
+const LOG_TARGET: &str = "tari::dan::app_utilities::metrics::server";
+
+/// Serves the metrics in the default prometheus registry on `/metrics` until the server fails
+pub async fn run_metrics_server(address: SocketAddr) -> Result<(), anyhow::Error> {
+    let router = Router::new().route("/metrics", get(metrics_handler));
+
+    let server = axum::Server::try_bind(&address)?.serve(router.into_make_service());
+    info!(target: LOG_TARGET, "📈 Metrics server listening on {}", server.local_addr());
+    server.await?;
+
+    info!(target: LOG_TARGET, "Stopping metrics server");
+    Ok(())
+}
+
+async fn metrics_handler() -> impl IntoResponse {
+    let encoder = TextEncoder::new();
+    let mut buffer = Vec::new();
+    if let Err(err) = encoder.encode(&prometheus::gather(), &mut buffer) {
+        error!(target: LOG_TARGET, "Failed to encode metrics: {}", err);
+        return Response::builder()
+            .status(StatusCode::INTERNAL_SERVER_ERROR)
+            .body(err.to_string())
+            .unwrap();
+    }
+
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, encoder.format_type())
+        .body(String::from_utf8_lossy(&buffer).into_owned())
+        .unwrap()
+}
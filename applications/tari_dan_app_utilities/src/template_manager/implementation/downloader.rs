@@ -22,13 +22,24 @@
 
 use bytes::Bytes;
 use futures::{future::BoxFuture, stream::FuturesUnordered};
+use log::*;
 use prost::bytes;
 use tari_common_types::types::FixedHash;
+use tari_dan_common_types::{committee::Committee, ShardId};
 use tari_dan_storage::global::DbTemplateType;
+use tari_engine_types::calculate_template_binary_hash;
+use tari_epoch_manager::{base_layer::EpochManagerHandle, EpochManagerError, EpochManagerReader};
 use tari_template_lib::models::TemplateAddress;
+use tari_validator_node_rpc::client::{
+    TariCommsValidatorNodeClientFactory,
+    ValidatorNodeClientFactory,
+    ValidatorNodeRpcClient,
+};
 use tokio::{sync::mpsc, task};
 use tokio_stream::StreamExt;
 
+const LOG_TARGET: &str = "tari::template_manager::downloader";
+
 pub struct DownloadRequest {
     pub address: TemplateAddress,
     pub template_type: DbTemplateType,
@@ -40,17 +51,24 @@ pub(super) struct TemplateDownloadWorker {
     download_queue: mpsc::Receiver<DownloadRequest>,
     pending_downloads: FuturesUnordered<BoxFuture<'static, DownloadResult>>,
     completed_downloads: mpsc::Sender<DownloadResult>,
+    peer_downloader: PeerTemplateDownloader,
 }
 
 impl TemplateDownloadWorker {
     pub fn new(
         download_queue: mpsc::Receiver<DownloadRequest>,
         completed_downloads: mpsc::Sender<DownloadResult>,
+        epoch_manager: EpochManagerHandle,
+        client_factory: TariCommsValidatorNodeClientFactory,
     ) -> Self {
         Self {
             download_queue,
             pending_downloads: FuturesUnordered::new(),
             completed_downloads,
+            peer_downloader: PeerTemplateDownloader {
+                epoch_manager,
+                client_factory,
+            },
         }
     }
 
@@ -64,7 +82,7 @@ impl TemplateDownloadWorker {
                 maybe_req = self.download_queue.recv() => {
                     match maybe_req {
                         Some(req)  => {
-                            self.pending_downloads.push(Box::pin(download(req)));
+                            self.pending_downloads.push(Box::pin(download(req, self.peer_downloader.clone())));
                         },
                         None => break,
                     }
@@ -77,25 +95,109 @@ impl TemplateDownloadWorker {
     }
 }
 
-async fn download(req: DownloadRequest) -> DownloadResult {
-    async fn inner(req: DownloadRequest) -> Result<Bytes, TemplateDownloadError> {
-        let resp = reqwest::get(&req.url).await?;
+async fn download(req: DownloadRequest, peer_downloader: PeerTemplateDownloader) -> DownloadResult {
+    async fn inner(req: &DownloadRequest) -> Result<Bytes, TemplateDownloadError> {
+        let resp = reqwest::get(&req.url).await?.error_for_status()?;
         let bytes = resp.bytes().await?;
-        Ok(bytes)
+        check_binary_hash(bytes, &req.expected_binary_hash)
     }
 
+    let result = match inner(&req).await {
+        Ok(bytes) => Ok(bytes),
+        Err(err) => {
+            warn!(
+                target: LOG_TARGET,
+                "Failed to download template {} from {}: {}. Trying validator peers.", req.address, req.url, err
+            );
+            peer_downloader.download(req.address, &req.expected_binary_hash).await
+        },
+    };
+
     DownloadResult {
         template_address: req.address,
-        template_type: req.template_type.clone(),
+        template_type: req.template_type,
         expected_binary_hash: req.expected_binary_hash,
-        result: inner(req).await,
+        result,
+    }
+}
+
+/// Only the hash in the template registration is trusted, so a binary that does not match it is rejected
+fn check_binary_hash(binary: Bytes, expected_binary_hash: &FixedHash) -> Result<Bytes, TemplateDownloadError> {
+    let binary_hash = calculate_template_binary_hash(&binary);
+    if binary_hash != *expected_binary_hash {
+        return Err(TemplateDownloadError::BinaryHashMismatch {
+            expected: *expected_binary_hash,
+            actual: binary_hash,
+        });
     }
+    Ok(binary)
+}
+
+/// Downloads template binaries from the validators in the committee responsible for the template address. This is
+/// used when the binary URL in the template registration is unavailable.
+#[derive(Clone)]
+struct PeerTemplateDownloader {
+    epoch_manager: EpochManagerHandle,
+    client_factory: TariCommsValidatorNodeClientFactory,
+}
+
+impl PeerTemplateDownloader {
+    async fn download(
+        &self,
+        template_address: TemplateAddress,
+        expected_binary_hash: &FixedHash,
+    ) -> Result<Bytes, TemplateDownloadError> {
+        let epoch = self.epoch_manager.current_epoch().await?;
+        let shard = ShardId::from_hash(template_address.as_ref(), 0);
+        let committee = self.epoch_manager.get_committee(epoch, shard).await?;
+
+        download_from_peers(&self.client_factory, &committee, template_address, expected_binary_hash).await
+    }
+}
+
+async fn download_from_peers<TClientFactory: ValidatorNodeClientFactory>(
+    client_factory: &TClientFactory,
+    committee: &Committee<TClientFactory::Addr>,
+    template_address: TemplateAddress,
+    expected_binary_hash: &FixedHash,
+) -> Result<Bytes, TemplateDownloadError> {
+    for member in committee.shuffled() {
+        let mut client = client_factory.create_client(member);
+        match client.get_template_binary(template_address).await {
+            Ok(binary) => match check_binary_hash(binary.into(), expected_binary_hash) {
+                Ok(binary) => {
+                    debug!(target: LOG_TARGET, "Downloaded template {} from peer {}", template_address, member);
+                    return Ok(binary);
+                },
+                Err(err) => {
+                    warn!(
+                        target: LOG_TARGET,
+                        "Peer {} returned an invalid binary for template {}: {}", member, template_address, err
+                    );
+                },
+            },
+            Err(err) => {
+                debug!(
+                    target: LOG_TARGET,
+                    "Failed to download template {} from peer {}: {}", template_address, member, err
+                );
+            },
+        }
+    }
+
+    Err(TemplateDownloadError::NoPeerAvailable { template_address })
 }
 
 #[derive(Debug, thiserror::Error)]
 pub enum TemplateDownloadError {
     #[error("Failed to download template: {0}")]
     DownloadFailed(#[from] reqwest::Error),
+    #[error("Epoch manager error: {0}")]
+    EpochManagerError(#[from] EpochManagerError),
+    #[error("Downloaded binary hash {actual} does not match the registered hash {expected}")]
+    BinaryHashMismatch { expected: FixedHash, actual: FixedHash },
+    #[error("Template {template_address} could not be downloaded from any validator peer")]
+    NoPeerAvailable { template_address: TemplateAddress },
 }
 
 #[derive(Debug)]
@@ -105,3 +207,152 @@ pub struct DownloadResult {
     pub expected_binary_hash: FixedHash,
     pub result: Result<Bytes, TemplateDownloadError>,
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, io};
+
+    use async_trait::async_trait;
+    use tari_dan_common_types::Epoch;
+    use tari_dan_p2p::DanPeer;
+    use tari_dan_storage::consensus_models::{SubstateHistory, SubstateQueryPoint, SubstateRecord};
+    use tari_engine_types::{
+        instruction_result::InstructionResult,
+        substate::SubstateAddress,
+        virtual_substate::{VirtualSubstate, VirtualSubstateAddress},
+    };
+    use tari_template_lib::{args::Arg, models::ComponentAddress};
+    use tari_transaction::{Transaction, TransactionId};
+    use tari_validator_node_rpc::client::{EpochHandoff, SignedDevTemplate, SubstateResult, TransactionResultStatus};
+
+    use super::*;
+
+    /// Serves a fixed template binary for each peer. Peers without a binary fail the request.
+    struct FakeClientFactory {
+        binaries: HashMap<String, Vec<u8>>,
+    }
+
+    impl ValidatorNodeClientFactory for FakeClientFactory {
+        type Addr = String;
+        type Client = FakeClient;
+
+        fn create_client(&self, address: &Self::Addr) -> Self::Client {
+            FakeClient {
+                binary: self.binaries.get(address).cloned(),
+            }
+        }
+    }
+
+    struct FakeClient {
+        binary: Option<Vec<u8>>,
+    }
+
+    #[async_trait]
+    impl ValidatorNodeRpcClient for FakeClient {
+        type Addr = String;
+        type Error = io::Error;
+
+        async fn submit_transaction(&mut self, _: Transaction) -> Result<TransactionId, Self::Error> {
+            unimplemented!()
+        }
+
+        async fn get_finalized_transaction_result(
+            &mut self,
+            _: TransactionId,
+        ) -> Result<TransactionResultStatus, Self::Error> {
+            unimplemented!()
+        }
+
+        async fn get_peers(&mut self) -> Result<Vec<DanPeer<Self::Addr>>, Self::Error> {
+            unimplemented!()
+        }
+
+        async fn get_substate(&mut self, _: ShardId) -> Result<SubstateResult, Self::Error> {
+            unimplemented!()
+        }
+
+        async fn get_virtual_substate(&mut self, _: VirtualSubstateAddress) -> Result<VirtualSubstate, Self::Error> {
+            unimplemented!()
+        }
+
+        async fn get_substate_history(&mut self, _: &SubstateAddress) -> Result<SubstateHistory, Self::Error> {
+            unimplemented!()
+        }
+
+        async fn get_substate_at(
+            &mut self,
+            _: &SubstateAddress,
+            _: SubstateQueryPoint,
+        ) -> Result<Option<SubstateRecord>, Self::Error> {
+            unimplemented!()
+        }
+
+        async fn invoke_read_method(
+            &mut self,
+            _: ComponentAddress,
+            _: String,
+            _: Vec<Arg>,
+        ) -> Result<InstructionResult, Self::Error> {
+            unimplemented!()
+        }
+
+        async fn get_epoch_handoff(&mut self, _: Epoch, _: Option<TransactionId>) -> Result<EpochHandoff, Self::Error> {
+            unimplemented!()
+        }
+
+        async fn submit_dev_template(&mut self, _: SignedDevTemplate) -> Result<TemplateAddress, Self::Error> {
+            unimplemented!()
+        }
+
+        async fn get_template_binary(&mut self, _: TemplateAddress) -> Result<Vec<u8>, Self::Error> {
+            self.binary
+                .clone()
+                .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "template not found"))
+        }
+    }
+
+    fn committee_of(members: &[&str]) -> Committee<String> {
+        Committee::new(members.iter().map(|m| m.to_string()).collect())
+    }
+
+    #[test]
+    fn it_rejects_a_binary_that_does_not_match_the_registered_hash() {
+        let expected = calculate_template_binary_hash(b"template");
+        assert!(check_binary_hash(Bytes::from_static(b"template"), &expected).is_ok());
+        let err = check_binary_hash(Bytes::from_static(b"tampered"), &expected).unwrap_err();
+        assert!(matches!(err, TemplateDownloadError::BinaryHashMismatch { .. }));
+    }
+
+    #[tokio::test]
+    async fn it_skips_peers_that_serve_an_invalid_binary() {
+        let expected = calculate_template_binary_hash(b"template");
+        let client_factory = FakeClientFactory {
+            binaries: [
+                ("tampered".to_string(), b"tampered".to_vec()),
+                ("honest".to_string(), b"template".to_vec()),
+            ]
+            .into_iter()
+            .collect(),
+        };
+        let committee = committee_of(&["tampered", "offline", "honest"]);
+
+        let binary = download_from_peers(&client_factory, &committee, TemplateAddress::default(), &expected)
+            .await
+            .unwrap();
+        assert_eq!(binary.as_ref(), b"template");
+    }
+
+    #[tokio::test]
+    async fn it_fails_when_no_peer_serves_a_valid_binary() {
+        let expected = calculate_template_binary_hash(b"template");
+        let client_factory = FakeClientFactory {
+            binaries: [("tampered".to_string(), b"tampered".to_vec())].into_iter().collect(),
+        };
+        let committee = committee_of(&["tampered", "offline"]);
+
+        let err = download_from_peers(&client_factory, &committee, TemplateAddress::default(), &expected)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, TemplateDownloadError::NoPeerAvailable { .. }));
+    }
+}
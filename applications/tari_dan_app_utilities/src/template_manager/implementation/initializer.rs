@@ -20,7 +20,9 @@
 //  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 //  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
+use tari_epoch_manager::base_layer::EpochManagerHandle;
 use tari_shutdown::ShutdownSignal;
+use tari_validator_node_rpc::client::TariCommsValidatorNodeClientFactory;
 use tokio::{sync::mpsc, task::JoinHandle};
 
 use super::{downloader::TemplateDownloadWorker, service::TemplateManagerService, TemplateManager};
@@ -28,6 +30,8 @@ use crate::template_manager::interface::TemplateManagerHandle;
 
 pub fn spawn(
     manager: TemplateManager,
+    epoch_manager: EpochManagerHandle,
+    client_factory: TariCommsValidatorNodeClientFactory,
     shutdown: ShutdownSignal,
 ) -> (TemplateManagerHandle, JoinHandle<anyhow::Result<()>>) {
     let (tx_request, rx_request) = mpsc::channel(1);
@@ -38,6 +42,6 @@ pub fn spawn(
 
     let join_handle =
         TemplateManagerService::spawn(rx_request, manager, tx_download_queue, rx_completed_downloads, shutdown);
-    TemplateDownloadWorker::new(rx_download_queue, tx_completed_downloads).spawn();
+    TemplateDownloadWorker::new(rx_download_queue, tx_completed_downloads, epoch_manager, client_factory).spawn();
     (handle, join_handle)
 }
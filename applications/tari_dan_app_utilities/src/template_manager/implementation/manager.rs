@@ -39,14 +39,21 @@ use tari_dan_engine::{
 };
 use tari_dan_storage::global::{DbTemplate, DbTemplateType, DbTemplateUpdate, GlobalDb, TemplateStatus};
 use tari_dan_storage_sqlite::global::SqliteGlobalDbAdapter;
-use tari_engine_types::calculate_template_binary_hash;
+use tari_engine_types::{calculate_dev_template_address, calculate_template_binary_hash};
 use tari_template_builtin::{get_template_builtin, ACCOUNT_NFT_TEMPLATE_ADDRESS, ACCOUNT_TEMPLATE_ADDRESS};
 use tari_template_lib::models::TemplateAddress;
 
 use super::TemplateConfig;
 use crate::template_manager::{
-    implementation::cmap_semaphore,
-    interface::{Template, TemplateExecutable, TemplateManagerError, TemplateMetadata, TemplateRegistration},
+    implementation::{cmap_semaphore, metrics},
+    interface::{
+        DevTemplateRegistration,
+        Template,
+        TemplateExecutable,
+        TemplateManagerError,
+        TemplateMetadata,
+        TemplateRegistration,
+    },
 };
 
 const LOG_TARGET: &str = "tari::validator_node::template_manager";
@@ -217,6 +224,45 @@ impl TemplateManager {
         Ok(())
     }
 
+    /// Adds a template that was registered directly with this node on a development network. The binary is provided
+    /// with the registration, so the template is active immediately.
+    pub(super) fn add_dev_template(&self, template: DevTemplateRegistration) -> Result<(), TemplateManagerError> {
+        let binary_hash = calculate_template_binary_hash(&template.binary);
+        let expected_address = calculate_dev_template_address(&binary_hash);
+        if template.template_address != expected_address {
+            return Err(TemplateManagerError::DevTemplateAddressMismatch {
+                expected: expected_address,
+                actual: template.template_address,
+            });
+        }
+        // Check that the binary is a valid template before accepting it
+        WasmModule::load_template_from_code(&template.binary)?;
+
+        let mut tx = self.global_db.create_transaction()?;
+        let mut templates_db = self.global_db.templates(&mut tx);
+        if templates_db.get_template(&template.template_address)?.is_some() {
+            return Ok(());
+        }
+        templates_db.insert_template(DbTemplate {
+            template_name: template.template_name,
+            template_address: template.template_address.into_array().into(),
+            expected_hash: binary_hash,
+            url: String::new(),
+            height: 0,
+            status: TemplateStatus::Active,
+            compiled_code: Some(template.binary),
+            added_at: Utc::now().naive_utc(),
+            template_type: DbTemplateType::Wasm,
+            flow_json: None,
+            manifest: None,
+        })?;
+        tx.commit()?;
+
+        info!(target: LOG_TARGET, "🛠️ Development template {} registered", template.template_address);
+
+        Ok(())
+    }
+
     pub(super) fn update_template(
         &self,
         address: TemplateAddress,
@@ -244,6 +290,7 @@ impl TemplateProvider for TemplateManager {
     fn get_template_module(&self, address: &TemplateAddress) -> Result<Option<Self::Template>, Self::Error> {
         if let Some(template) = self.cache.get(address) {
             debug!(target: LOG_TARGET, "CACHE HIT: Template {}", address);
+            metrics::TEMPLATE_CACHE_HITS.inc();
             return Ok(Some(template));
         }
 
@@ -258,6 +305,7 @@ impl TemplateProvider for TemplateManager {
 
         if let Some(template) = self.cache.get(address) {
             debug!(target: LOG_TARGET, "CACHE HIT: Template {}", address);
+            metrics::TEMPLATE_CACHE_HITS.inc();
             return Ok(Some(template));
         }
 
@@ -265,6 +313,7 @@ impl TemplateProvider for TemplateManager {
             return Ok(None);
         };
         debug!(target: LOG_TARGET, "CACHE MISS: Template {}", address);
+        metrics::TEMPLATE_CACHE_MISSES.inc();
         let loaded = match template.executable {
             TemplateExecutable::CompiledWasm(wasm) => {
                 let module = WasmModule::from_code(wasm);
@@ -0,0 +1,24 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use lazy_static::lazy_static;
+use prometheus::IntCounter;
+
+use crate::metrics::register;
+
+lazy_static! {
+    pub static ref TEMPLATE_CACHE_HITS: IntCounter = register(
+        IntCounter::new(
+            "template_cache_hits_total",
+            "The number of template lookups served from the cache"
+        )
+        .unwrap()
+    );
+    pub static ref TEMPLATE_CACHE_MISSES: IntCounter = register(
+        IntCounter::new(
+            "template_cache_misses_total",
+            "The number of templates loaded because they were not in the cache"
+        )
+        .unwrap()
+    );
+}
@@ -27,6 +27,7 @@ pub use initializer::spawn;
 
 mod manager;
 pub use manager::TemplateManager;
+mod metrics;
 mod service;
 
 mod cmap_semaphore;
@@ -122,6 +122,7 @@ impl TemplateManagerService {
             AddTemplate { template, reply } => {
                 handle(reply, self.handle_add_template(*template).await);
             },
+            AddDevTemplate { template, reply } => handle(reply, self.manager.add_dev_template(*template)),
             GetTemplate { address, reply } => {
                 handle(reply, self.manager.fetch_template(&address));
             },
@@ -49,6 +49,11 @@ pub enum TemplateManagerError {
     PackageError(#[from] PackageError),
     #[error("Unsupported template type")]
     UnsupportedTemplateType,
+    #[error("Template address {actual} does not match the address {expected} derived from the binary")]
+    DevTemplateAddressMismatch {
+        expected: TemplateAddress,
+        actual: TemplateAddress,
+    },
     #[error("The template is not valid UTF-8: {0}")]
     FlowJsonNotValidUtf8(#[from] FromUtf8Error),
     #[error("The flow was not valid JSON: {0}")]
@@ -50,6 +50,18 @@ impl TemplateManagerHandle {
         rx.await.map_err(|_| TemplateManagerError::ChannelClosed)?
     }
 
+    pub async fn add_dev_template(&self, template: DevTemplateRegistration) -> Result<(), TemplateManagerError> {
+        let (tx, rx) = oneshot::channel();
+        self.request_tx
+            .send(TemplateManagerRequest::AddDevTemplate {
+                template: Box::new(template),
+                reply: tx,
+            })
+            .await
+            .map_err(|_| TemplateManagerError::ChannelClosed)?;
+        rx.await.map_err(|_| TemplateManagerError::ChannelClosed)?
+    }
+
     pub async fn get_template(&self, address: TemplateAddress) -> Result<Template, TemplateManagerError> {
         let (tx, rx) = oneshot::channel();
         self.request_tx
@@ -86,3 +98,12 @@ pub struct TemplateRegistration {
     pub mined_height: u64,
     pub mined_hash: FixedHash,
 }
+
+/// A WASM template registered directly with validator nodes on a development network, without a base layer
+/// registration
+#[derive(Debug, Clone)]
+pub struct DevTemplateRegistration {
+    pub template_name: String,
+    pub template_address: TemplateAddress,
+    pub binary: Vec<u8>,
+}
@@ -25,7 +25,7 @@ use tari_template_lib::models::TemplateAddress;
 use tari_validator_node_client::types::TemplateAbi;
 use tokio::sync::oneshot;
 
-use super::{TemplateManagerError, TemplateRegistration};
+use super::{DevTemplateRegistration, TemplateManagerError, TemplateRegistration};
 
 #[derive(Debug, Clone)]
 pub struct TemplateMetadata {
@@ -105,6 +105,10 @@ pub enum TemplateManagerRequest {
         template: Box<TemplateRegistration>,
         reply: oneshot::Sender<Result<(), TemplateManagerError>>,
     },
+    AddDevTemplate {
+        template: Box<DevTemplateRegistration>,
+        reply: oneshot::Sender<Result<(), TemplateManagerError>>,
+    },
     GetTemplate {
         address: TemplateAddress,
         reply: oneshot::Sender<Result<Template, TemplateManagerError>>,
@@ -4,23 +4,38 @@
 use std::{sync::Arc, time::Instant};
 
 use log::*;
-use tari_common_types::types::PublicKey;
-use tari_crypto::tari_utilities::ByteArray;
-use tari_dan_common_types::{services::template_provider::TemplateProvider, ShardId};
+use rand::rngs::OsRng;
+use tari_common_types::types::{PrivateKey, PublicKey};
+use tari_crypto::{keys::SecretKey, tari_utilities::ByteArray};
+use tari_dan_common_types::{optional::Optional, services::template_provider::TemplateProvider, ShardId};
 use tari_dan_engine::{
     fees::{FeeModule, FeeTable},
     packager::LoadedTemplate,
-    runtime::{AuthParams, RuntimeModule, VirtualSubstates},
-    state_store::{memory::MemoryStateStore, StateStoreError},
+    runtime::{AuthParams, ReadOnlyModule, RuntimeModule, VirtualSubstates},
+    state_store::{memory::MemoryStateStore, AtomicDb, StateReader, StateStoreError},
     transaction::{TransactionError, TransactionProcessor},
 };
 use tari_dan_storage::consensus_models::ExecutedTransaction;
-use tari_engine_types::commit_result::{ExecuteResult, FinalizeResult, RejectReason};
-use tari_template_lib::{crypto::RistrettoPublicKeyBytes, prelude::NonFungibleAddress};
+use tari_engine_types::{
+    commit_result::{ExecuteResult, FinalizeResult, RejectReason},
+    instruction_result::InstructionResult,
+    substate::{Substate, SubstateAddress},
+    TemplateAddress,
+};
+use tari_template_lib::{
+    args::Arg,
+    crypto::RistrettoPublicKeyBytes,
+    models::ComponentAddress,
+    prelude::NonFungibleAddress,
+};
 use tari_transaction::Transaction;
 
 const _LOG_TARGET: &str = "tari::dan::transaction_executor";
 
+/// The maximum number of engine runtime calls that a view call may make. View calls are not charged fees, so this
+/// bounds the work that a caller can request.
+const MAX_VIEW_CALL_RUNTIME_CALLS: usize = 1000;
+
 pub trait TransactionExecutor {
     type Error: Send + Sync + 'static;
 
@@ -36,6 +51,7 @@ pub trait TransactionExecutor {
 pub struct TariDanTransactionProcessor<TTemplateProvider> {
     template_provider: Arc<TTemplateProvider>,
     fee_table: FeeTable,
+    execution_trace: bool,
 }
 
 impl<TTemplateProvider> TariDanTransactionProcessor<TTemplateProvider> {
@@ -43,8 +59,15 @@ impl<TTemplateProvider> TariDanTransactionProcessor<TTemplateProvider> {
         Self {
             template_provider: Arc::new(template_provider),
             fee_table,
+            execution_trace: false,
         }
     }
+
+    /// Include an execution trace in the result of each executed transaction
+    pub fn with_execution_trace(mut self, enabled: bool) -> Self {
+        self.execution_trace = enabled;
+        self
+    }
 }
 
 impl<TTemplateProvider> TransactionExecutor for TariDanTransactionProcessor<TTemplateProvider>
@@ -74,7 +97,8 @@ where TTemplateProvider: TemplateProvider<Template = LoadedTemplate>
             auth_params,
             virtual_substates,
             modules,
-        );
+        )
+        .with_execution_trace(self.execution_trace);
         let tx_id = transaction.hash();
         let result = match processor.execute(transaction.clone()) {
             Ok(result) => result,
@@ -98,6 +122,97 @@ where TTemplateProvider: TemplateProvider<Template = LoadedTemplate>
     }
 }
 
+impl<TTemplateProvider> TariDanTransactionProcessor<TTemplateProvider>
+where TTemplateProvider: TemplateProvider<Template = LoadedTemplate>
+{
+    /// Calls a method that does not mutate the component against the given state and returns its result. No fees are
+    /// charged and nothing is committed. Execution fails if the method is declared `&mut self`, if the call creates
+    /// or changes any substate or if it makes more than `MAX_VIEW_CALL_RUNTIME_CALLS` runtime calls.
+    ///
+    /// The state store must contain the component and any substates that the method reads.
+    pub fn execute_view_call(
+        &self,
+        component_address: ComponentAddress,
+        method: String,
+        args: Vec<Arg>,
+        state_store: MemoryStateStore,
+        virtual_substates: VirtualSubstates,
+    ) -> Result<InstructionResult, ViewCallError> {
+        self.check_view_method(&state_store, component_address, &method)?;
+
+        // The call is not submitted anywhere so it is signed by a throwaway key. This means that methods restricted
+        // to a particular owner cannot be called as a view call.
+        let transaction = Transaction::builder()
+            .call_method(component_address, &method, args)
+            .sign(&PrivateKey::random(&mut OsRng))
+            .build();
+
+        let modules: Vec<Arc<dyn RuntimeModule>> = vec![Arc::new(ReadOnlyModule::new(MAX_VIEW_CALL_RUNTIME_CALLS))];
+        let processor = TransactionProcessor::new(
+            self.template_provider.clone(),
+            state_store,
+            AuthParams {
+                initial_ownership_proofs: vec![],
+            },
+            virtual_substates,
+            modules,
+        );
+
+        let result = processor.execute(transaction)?;
+        if let Some(reason) = result.finalize.full_reject() {
+            return Err(ViewCallError::ExecutionFailed(reason.clone()));
+        }
+
+        result
+            .finalize
+            .execution_results
+            .into_iter()
+            .next()
+            .ok_or(ViewCallError::NoResult)
+    }
+
+    fn check_view_method(
+        &self,
+        state_store: &MemoryStateStore,
+        component_address: ComponentAddress,
+        method: &str,
+    ) -> Result<(), ViewCallError> {
+        let address = SubstateAddress::Component(component_address);
+        let substate = state_store
+            .read_access()
+            .map_err(StateStoreError::Custom)?
+            .get_state::<_, Substate>(&address)
+            .optional()?
+            .ok_or(ViewCallError::ComponentNotFound { component_address })?;
+        let template_address = substate
+            .substate_value()
+            .component()
+            .map(|component| component.template_address)
+            .ok_or(ViewCallError::ComponentNotFound { component_address })?;
+
+        let template = self
+            .template_provider
+            .get_template_module(&template_address)
+            .map_err(|e| ViewCallError::TemplateProviderError(e.to_string()))?
+            .ok_or(ViewCallError::TemplateNotFound { template_address })?;
+        let function = template
+            .template_def()
+            .get_function(method)
+            .ok_or_else(|| ViewCallError::MethodNotFound {
+                template_address,
+                method: method.to_string(),
+            })?;
+
+        if function.is_mut {
+            return Err(ViewCallError::MethodIsMutable {
+                method: method.to_string(),
+            });
+        }
+
+        Ok(())
+    }
+}
+
 fn get_auth_token(public_key: &PublicKey) -> NonFungibleAddress {
     let public_key =
         RistrettoPublicKeyBytes::from_bytes(public_key.as_bytes()).expect("Expected public key to be 32 bytes");
@@ -111,3 +226,28 @@ pub enum TransactionProcessorError {
     #[error(transparent)]
     StateStoreError(#[from] StateStoreError),
 }
+
+#[derive(Debug, thiserror::Error)]
+pub enum ViewCallError {
+    #[error(transparent)]
+    TransactionError(#[from] TransactionError),
+    #[error(transparent)]
+    StateStoreError(#[from] StateStoreError),
+    #[error("Component {component_address} not found")]
+    ComponentNotFound { component_address: ComponentAddress },
+    #[error("Template {template_address} not found")]
+    TemplateNotFound { template_address: TemplateAddress },
+    #[error("Template provider error: {0}")]
+    TemplateProviderError(String),
+    #[error("Method '{method}' not found in template {template_address}")]
+    MethodNotFound {
+        template_address: TemplateAddress,
+        method: String,
+    },
+    #[error("Method '{method}' mutates the component and cannot be called as a view call")]
+    MethodIsMutable { method: String },
+    #[error("View call failed: {0}")]
+    ExecutionFailed(RejectReason),
+    #[error("View call did not return a result")]
+    NoResult,
+}
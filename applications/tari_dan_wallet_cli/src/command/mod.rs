@@ -22,7 +22,7 @@
 
 use clap::Subcommand;
 
-use self::{auth::AuthSubcommand, nfts::AccountNftSubcommand, webrtc::WebRtcSubcommand};
+use self::{auth::AuthSubcommand, nfts::AccountNftSubcommand, wallet::WalletSubcommand, webrtc::WebRtcSubcommand};
 use crate::command::{
     account::AccountsSubcommand,
     key::KeysSubcommand,
@@ -38,6 +38,7 @@ mod nfts;
 mod proof;
 pub mod transaction;
 mod validator;
+mod wallet;
 mod webrtc;
 
 #[allow(clippy::large_enum_variant)]
@@ -59,4 +60,6 @@ pub enum Command {
     AccountNft(AccountNftSubcommand),
     #[clap(subcommand)]
     Validator(ValidatorSubcommand),
+    #[clap(subcommand)]
+    Wallet(WalletSubcommand),
 }
@@ -0,0 +1,72 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use clap::{Args, Subcommand};
+use tari_wallet_daemon_client::{
+    types::{AccountInfo, WalletRestoreRequest},
+    WalletDaemonClient,
+};
+
+use crate::{prompt::Prompt, table::Table, table_row};
+
+#[derive(Debug, Subcommand, Clone)]
+pub enum WalletSubcommand {
+    /// Restores the wallet from seed words and discovers the accounts that belong to it
+    Restore(RestoreArgs),
+}
+
+#[derive(Debug, Args, Clone)]
+pub struct RestoreArgs {
+    /// The number of consecutive unused keys after which account discovery stops
+    #[clap(long)]
+    pub gap_limit: Option<u64>,
+}
+
+impl WalletSubcommand {
+    pub async fn handle(self, mut client: WalletDaemonClient) -> Result<(), anyhow::Error> {
+        match self {
+            WalletSubcommand::Restore(args) => handle_restore(args, &mut client).await?,
+        }
+        Ok(())
+    }
+}
+
+async fn handle_restore(args: RestoreArgs, client: &mut WalletDaemonClient) -> Result<(), anyhow::Error> {
+    // The seed words and passphrase are always prompted for so that they do not end up in the shell history or the
+    // process list
+    let seed_words = Prompt::new("Enter the seed words separated by spaces:").ask()?;
+    let seed_words = seed_words.split_whitespace().map(|s| s.to_string()).collect();
+    let seed_passphrase = Prompt::new("Enter the seed passphrase, if any:")
+        .with_default("")
+        .ask()?;
+    let seed_passphrase = Some(seed_passphrase).filter(|s| !s.is_empty());
+
+    println!("Restoring wallet. This may take a while...");
+    let resp = client
+        .restore_wallet(WalletRestoreRequest {
+            seed_words,
+            seed_passphrase,
+            gap_limit: args.gap_limit,
+        })
+        .await?;
+
+    if resp.accounts.is_empty() {
+        println!("Wallet seed restored. No accounts were found.");
+        return Ok(());
+    }
+
+    println!("Wallet seed restored. Found {} account(s):", resp.accounts.len());
+    let mut table = Table::new();
+    table.enable_row_count();
+    table.set_titles(vec!["Name", "Address", "Public Key", "Default"]);
+    for AccountInfo { account, public_key } in resp.accounts {
+        table.add_row(table_row!(
+            account.name,
+            account.address,
+            public_key,
+            if account.is_default { "✅" } else { "" }
+        ));
+    }
+    table.print_stdout();
+    Ok(())
+}
@@ -57,6 +57,7 @@ async fn handle_command(command: Command, client: WalletDaemonClient) -> anyhow:
         Command::Auth(cmd) => cmd.handle(client).await?,
         Command::AccountNft(cmd) => cmd.handle(client).await?,
         Command::Validator(cmd) => cmd.handle(client).await?,
+        Command::Wallet(cmd) => cmd.handle(client).await?,
     }
 
     Ok(())
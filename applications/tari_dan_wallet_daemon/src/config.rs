@@ -25,6 +25,7 @@ use std::{net::SocketAddr, time::Duration};
 use config::Config;
 use serde::{Deserialize, Serialize};
 use tari_common::{configuration::CommonConfig, ConfigurationError, DefaultConfigLoader, SubConfigPath};
+use tari_crypto::tari_utilities::SafePassword;
 use tari_dan_common_types::crypto::create_secret;
 
 #[derive(Debug, Clone)]
@@ -64,6 +65,10 @@ pub struct WalletDaemonConfig {
     pub jwt_secret_key: Option<String>,
     /// The address of the HTTP UI
     pub http_ui_address: Option<SocketAddr>,
+    /// The passphrase used to unlock the wallet secrets. This is never read from the config file and is set from the
+    /// `TARI_DAN_WALLET_PASSWORD` environment variable. If it is not set, it is prompted for in a terminal.
+    #[serde(skip)]
+    pub password: Option<SafePassword>,
 }
 
 impl Default for WalletDaemonConfig {
@@ -78,6 +83,7 @@ impl Default for WalletDaemonConfig {
             jwt_expiry: Some(Duration::from_secs(500 * 60)),
             jwt_secret_key: Some(create_secret()),
             http_ui_address: Some("127.0.0.1:5100".parse().unwrap()),
+            password: None,
         }
     }
 }
@@ -13,6 +13,7 @@ use crate::{
 #[derive(Debug, Clone)]
 pub struct HandlerContext {
     wallet_sdk: DanWalletSdk<SqliteWalletStore, IndexerJsonRpcNetworkInterface>,
+    store: SqliteWalletStore,
     notifier: Notify<WalletEvent>,
     account_monitor: AccountMonitorHandle,
 }
@@ -20,11 +21,13 @@ pub struct HandlerContext {
 impl HandlerContext {
     pub fn new(
         wallet_sdk: DanWalletSdk<SqliteWalletStore, IndexerJsonRpcNetworkInterface>,
+        store: SqliteWalletStore,
         notifier: Notify<WalletEvent>,
         account_monitor: AccountMonitorHandle,
     ) -> Self {
         Self {
             wallet_sdk,
+            store,
             notifier,
             account_monitor,
         }
@@ -38,6 +41,11 @@ impl HandlerContext {
         &self.wallet_sdk
     }
 
+    /// The wallet store, used for operations on the store itself such as changing the wallet passphrase
+    pub fn store(&self) -> &SqliteWalletStore {
+        &self.store
+    }
+
     pub fn account_monitor(&self) -> &AccountMonitorHandle {
         &self.account_monitor
     }
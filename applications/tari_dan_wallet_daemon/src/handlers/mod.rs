@@ -12,6 +12,7 @@ pub mod rpc;
 pub mod settings;
 pub mod transaction;
 pub mod validator;
+pub mod wallet;
 pub mod webrtc;
 
 use std::future::Future;
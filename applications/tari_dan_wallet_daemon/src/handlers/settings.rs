@@ -1,12 +1,22 @@
 //   Copyright 2023 The Tari Project
 //   SPDX-License-Identifier: BSD-3-Clause
 
+use anyhow::anyhow;
+use tari_crypto::tari_utilities::SafePassword;
 use tari_dan_common_types::optional::Optional;
 use tari_dan_wallet_sdk::{
     apis::{config::ConfigKey, jwt::JrpcPermission},
     network::WalletNetworkInterface,
 };
-use tari_wallet_daemon_client::types::{SettingsGetResponse, SettingsSetRequest, SettingsSetResponse};
+use tari_wallet_daemon_client::types::{
+    SettingsChangePassphraseRequest,
+    SettingsChangePassphraseResponse,
+    SettingsGetResponse,
+    SettingsSetPassphraseRequest,
+    SettingsSetPassphraseResponse,
+    SettingsSetRequest,
+    SettingsSetResponse,
+};
 
 use crate::handlers::HandlerContext;
 
@@ -37,3 +47,42 @@ pub async fn handle_set(
     sdk.config_api().set(ConfigKey::IndexerUrl, &req.indexer_url, false)?;
     Ok(SettingsSetResponse {})
 }
+
+pub async fn handle_set_passphrase(
+    context: &HandlerContext,
+    token: Option<String>,
+    req: SettingsSetPassphraseRequest,
+) -> Result<SettingsSetPassphraseResponse, anyhow::Error> {
+    let sdk = context.wallet_sdk();
+    sdk.jwt_api().check_auth(token, &[JrpcPermission::Admin])?;
+    if req.passphrase.is_empty() {
+        return Err(anyhow!("The passphrase must not be empty"));
+    }
+
+    context.store().set_passphrase(
+        &SafePassword::from(req.passphrase),
+        &SafePassword::from(req.confirm_passphrase),
+    )?;
+    Ok(SettingsSetPassphraseResponse {})
+}
+
+pub async fn handle_change_passphrase(
+    context: &HandlerContext,
+    token: Option<String>,
+    req: SettingsChangePassphraseRequest,
+) -> Result<SettingsChangePassphraseResponse, anyhow::Error> {
+    let sdk = context.wallet_sdk();
+    sdk.jwt_api().check_auth(token, &[JrpcPermission::Admin])?;
+    if req.new_passphrase.is_empty() {
+        return Err(anyhow!("The new passphrase must not be empty"));
+    }
+    if req.new_passphrase != req.confirm_new_passphrase {
+        return Err(anyhow!("The new passphrase and confirmation passphrase do not match"));
+    }
+
+    context.store().change_passphrase(
+        &SafePassword::from(req.current_passphrase),
+        &SafePassword::from(req.new_passphrase),
+    )?;
+    Ok(SettingsChangePassphraseResponse {})
+}
@@ -0,0 +1,137 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use log::*;
+use tari_common_types::types::PublicKey;
+use tari_crypto::tari_utilities::{ByteArray, SafePassword};
+use tari_dan_common_types::optional::Optional;
+use tari_dan_wallet_sdk::{
+    apis::{
+        jwt::JrpcPermission,
+        key_manager,
+        key_manager::{cipher_seed_from_seed_words, derive_public_key},
+        substate::ValidatorScanResult,
+    },
+    models::RestoredAccount,
+    CipherSeed,
+};
+use tari_engine_types::{component::new_component_address_from_parts, substate::SubstateAddress};
+use tari_template_builtin::ACCOUNT_TEMPLATE_ADDRESS;
+use tari_template_lib::Hash;
+use tari_wallet_daemon_client::types::{AccountInfo, WalletRestoreRequest, WalletRestoreResponse};
+
+use crate::handlers::{helpers::invalid_params, HandlerContext};
+
+const LOG_TARGET: &str = "tari::dan::wallet_daemon::handlers::wallet";
+
+/// The number of consecutive unused keys after which account discovery stops
+const DEFAULT_GAP_LIMIT: u64 = 20;
+/// Each key in the gap is a network request, so the gap limit is bounded
+const MAX_GAP_LIMIT: u64 = 100;
+
+pub async fn handle_restore(
+    context: &HandlerContext,
+    token: Option<String>,
+    req: WalletRestoreRequest,
+) -> Result<WalletRestoreResponse, anyhow::Error> {
+    let sdk = context.wallet_sdk();
+    sdk.jwt_api().check_auth(token, &[JrpcPermission::Admin])?;
+
+    let gap_limit = req.gap_limit.unwrap_or(DEFAULT_GAP_LIMIT);
+    if gap_limit == 0 || gap_limit > MAX_GAP_LIMIT {
+        return Err(invalid_params(
+            "gap_limit",
+            Some(format!("must be between 1 and {}", MAX_GAP_LIMIT)),
+        ));
+    }
+
+    let cipher_seed = cipher_seed_from_seed_words(req.seed_words, req.seed_passphrase.map(SafePassword::from))?;
+    sdk.check_can_restore(&cipher_seed)?;
+
+    // The seed is only stored once discovery has completed, so that a failed restore leaves the wallet unchanged
+    info!(target: LOG_TARGET, "🌱 Discovering accounts...");
+    let restored = discover_accounts(context, &cipher_seed, gap_limit).await?;
+    sdk.restore_wallet(cipher_seed, &restored)?;
+    info!(target: LOG_TARGET, "🌱 Wallet restored with {} account(s)", restored.len());
+
+    let accounts_api = sdk.accounts_api();
+    let km = sdk.key_manager_api();
+    let mut accounts = Vec::with_capacity(restored.len());
+    for RestoredAccount { key_index, address, .. } in restored {
+        // The account monitor keeps refreshing all accounts, so a failure here is picked up on the next poll
+        if let Err(err) = context.account_monitor().refresh_account(address.address.clone()).await {
+            warn!(
+                target: LOG_TARGET,
+                "🌱 Failed to refresh restored account {}: {}", address.address, err
+            );
+        }
+        let account = accounts_api.get_account_by_address(&address.address)?;
+        let public_key = km.get_public_key(key_manager::TRANSACTION_BRANCH, Some(key_index))?;
+        accounts.push(AccountInfo { account, public_key });
+    }
+
+    Ok(WalletRestoreResponse { accounts })
+}
+
+/// Looks up the accounts owned by keys derived from the given seed. Account components are addressed by the public key
+/// of the owner, so the account address for each key on the transaction branch is looked up until `gap_limit`
+/// consecutive keys are found without an account.
+async fn discover_accounts(
+    context: &HandlerContext,
+    cipher_seed: &CipherSeed,
+    gap_limit: u64,
+) -> Result<Vec<RestoredAccount>, anyhow::Error> {
+    let substate_api = context.wallet_sdk().substate_api();
+
+    let mut restored = Vec::new();
+    let mut num_missing = 0;
+    let mut index = 0;
+    while num_missing < gap_limit {
+        let public_key = derive_public_key(cipher_seed, key_manager::TRANSACTION_BRANCH, index)?;
+        let account_address = account_address_for_public_key(&public_key);
+        let scan_result = substate_api
+            .scan_for_substate(&account_address, None)
+            .await
+            .optional()?;
+        match scan_result {
+            Some(ValidatorScanResult {
+                address,
+                substate,
+                created_by_tx,
+            }) if substate
+                .component()
+                .map_or(false, |c| c.template_address == *ACCOUNT_TEMPLATE_ADDRESS) =>
+            {
+                info!(
+                    target: LOG_TARGET,
+                    "🌱 Found account {} for key {}", address, index
+                );
+                num_missing = 0;
+                restored.push(RestoredAccount {
+                    key_index: index,
+                    address,
+                    created_by_tx,
+                });
+            },
+            Some(_) => {
+                warn!(
+                    target: LOG_TARGET,
+                    "🌱 Substate {} for key {} is not an account. Skipping.", account_address, index
+                );
+                num_missing += 1;
+            },
+            None => {
+                num_missing += 1;
+            },
+        }
+        index += 1;
+    }
+
+    Ok(restored)
+}
+
+fn account_address_for_public_key(public_key: &PublicKey) -> SubstateAddress {
+    // The account template uses the owner public key as the component id
+    let component_id = Hash::try_from(public_key.as_bytes()).expect("Public key is 32 bytes");
+    new_component_address_from_parts(&ACCOUNT_TEMPLATE_ADDRESS, &component_id).into()
+}
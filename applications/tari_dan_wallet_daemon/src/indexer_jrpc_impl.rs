@@ -85,6 +85,7 @@ impl WalletNetworkInterface for IndexerJsonRpcNetworkInterface {
                 transaction,
                 required_substates,
                 is_dry_run: false,
+                include_execution_trace: false,
             })
             .await?;
         Ok(result.transaction_id)
@@ -101,6 +102,7 @@ impl WalletNetworkInterface for IndexerJsonRpcNetworkInterface {
                 transaction,
                 required_substates,
                 is_dry_run: true,
+                include_execution_trace: false,
             })
             .await?;
 
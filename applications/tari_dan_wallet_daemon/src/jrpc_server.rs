@@ -36,6 +36,7 @@ use crate::handlers::{
     settings,
     transaction,
     validator,
+    wallet,
     webrtc,
     Handler,
 };
@@ -105,6 +106,8 @@ async fn handler(
         Some(("settings", method)) => match method {
             "get" => call_handler(context, value, token, settings::handle_get).await,
             "set" => call_handler(context, value, token, settings::handle_set).await,
+            "set_passphrase" => call_handler(context, value, token, settings::handle_set_passphrase).await,
+            "change_passphrase" => call_handler(context, value, token, settings::handle_change_passphrase).await,
             _ => Ok(value.method_not_found(&value.method)),
         },
         Some(("webrtc", "start")) => webrtc::handle_start(context, value, token, shutdown_signal, addresses),
@@ -165,6 +168,10 @@ async fn handler(
             "claim_fees" => call_handler(context, value, token, validator::handle_claim_validator_fees).await,
             _ => Ok(value.method_not_found(&value.method)),
         },
+        Some(("wallet", method)) => match method {
+            "restore" => call_handler(context, value, token, wallet::handle_restore).await,
+            _ => Ok(value.method_not_found(&value.method)),
+        },
         _ => Ok(value.method_not_found(&value.method)),
     }
 }
@@ -30,9 +30,16 @@ mod notify;
 mod services;
 mod webrtc;
 
-use std::{fs, panic, process};
+use std::{
+    fs,
+    io::{self, IsTerminal},
+    panic,
+    process,
+};
 
+use anyhow::anyhow;
 use log::*;
+use tari_crypto::tari_utilities::SafePassword;
 use tari_dan_common_types::optional::Optional;
 use tari_dan_wallet_sdk::{
     apis::{
@@ -58,6 +65,9 @@ use crate::{
 
 const LOG_TARGET: &str = "tari::dan::wallet_daemon";
 
+/// The environment variable that holds the passphrase used to unlock the wallet
+pub const PASSWORD_ENV_VAR: &str = "TARI_DAN_WALLET_PASSWORD";
+
 const DEFAULT_FEE: Amount = Amount::new(1500);
 
 pub async fn run_tari_dan_wallet_daemon(
@@ -69,10 +79,9 @@ pub async fn run_tari_dan_wallet_daemon(
 
     let store = SqliteWalletStore::try_open(config.common.base_path.join("data/wallet.sqlite"))?;
     store.run_migrations()?;
+    unlock_wallet(&store, config.dan_wallet_daemon.password.as_ref())?;
 
     let sdk_config = WalletSdkConfig {
-        // TODO: Configure
-        password: None,
         indexer_jrpc_endpoint: config.dan_wallet_daemon.indexer_node_json_rpc_url,
         jwt_expiry: config.dan_wallet_daemon.jwt_expiry.unwrap(),
         jwt_secret_key: config.dan_wallet_daemon.jwt_secret_key.unwrap(),
@@ -84,7 +93,7 @@ pub async fn run_tari_dan_wallet_daemon(
         sdk_config.indexer_jrpc_endpoint.clone()
     };
     let indexer = IndexerJsonRpcNetworkInterface::new(indexer_jrpc_endpoint);
-    let wallet_sdk = DanWalletSdk::initialize(store, indexer, sdk_config)?;
+    let wallet_sdk = DanWalletSdk::initialize(store.clone(), indexer, sdk_config)?;
     wallet_sdk
         .key_manager_api()
         .get_or_create_initial(key_manager::TRANSACTION_BRANCH)?;
@@ -94,7 +103,12 @@ pub async fn run_tari_dan_wallet_daemon(
 
     let jrpc_address = config.dan_wallet_daemon.json_rpc_address.unwrap();
     let signaling_server_address = config.dan_wallet_daemon.signaling_server_address.unwrap();
-    let handlers = HandlerContext::new(wallet_sdk.clone(), notify, services.account_monitor_handle.clone());
+    let handlers = HandlerContext::new(
+        wallet_sdk.clone(),
+        store,
+        notify,
+        services.account_monitor_handle.clone(),
+    );
     let listen_fut = jrpc_server::listen(jrpc_address, signaling_server_address, handlers, shutdown_signal);
 
     // Run the http ui
@@ -127,3 +141,43 @@ pub async fn run_tari_dan_wallet_daemon(
     }
     Ok(())
 }
+
+/// Unlocks the wallet secrets before the wallet SDK reads them. If the wallet is encrypted and no password was given,
+/// the password is prompted for when running in a terminal. The passphrase is never set here, it is set explicitly with
+/// the `settings.set_passphrase` JSON-RPC method.
+fn unlock_wallet(store: &SqliteWalletStore, password: Option<&SafePassword>) -> Result<(), anyhow::Error> {
+    if !store.is_encrypted()? {
+        if password.is_some() {
+            warn!(
+                target: LOG_TARGET,
+                "⚠️ {} is set but the wallet has no passphrase. Use settings.set_passphrase to set one.",
+                PASSWORD_ENV_VAR
+            );
+        } else {
+            warn!(
+                target: LOG_TARGET,
+                "⚠️ No wallet passphrase has been set. Wallet secrets are stored unencrypted."
+            );
+        }
+        return Ok(());
+    }
+
+    let prompted;
+    let password = match password {
+        Some(password) => password,
+        None if io::stdin().is_terminal() => {
+            prompted = SafePassword::from(rpassword::prompt_password_stdout("Wallet password: ")?);
+            &prompted
+        },
+        None => {
+            return Err(anyhow!(
+                "The wallet is encrypted. Provide the wallet password with the {} environment variable or start the \
+                 daemon in a terminal to be prompted for it.",
+                PASSWORD_ENV_VAR
+            ))
+        },
+    };
+    store.unlock(password)?;
+    info!(target: LOG_TARGET, "🔓 Wallet unlocked");
+    Ok(())
+}
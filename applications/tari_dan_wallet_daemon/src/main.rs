@@ -20,10 +20,11 @@
 // WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use std::{fs, panic, process};
+use std::{env, fs, panic, process};
 
 use tari_common::{initialize_logging, load_configuration};
-use tari_dan_wallet_daemon::{cli::Cli, config::ApplicationConfig, run_tari_dan_wallet_daemon};
+use tari_crypto::tari_utilities::SafePassword;
+use tari_dan_wallet_daemon::{cli::Cli, config::ApplicationConfig, run_tari_dan_wallet_daemon, PASSWORD_ENV_VAR};
 use tari_shutdown::Shutdown;
 
 #[tokio::main]
@@ -39,7 +40,13 @@ async fn main() -> Result<(), anyhow::Error> {
     let cli = Cli::init();
     let config_path = cli.common.config_path();
     let cfg = load_configuration(config_path, true, &cli)?;
-    let config = ApplicationConfig::load_from(&cfg)?;
+    let mut config = ApplicationConfig::load_from(&cfg)?;
+    // The password is read from the environment, or prompted for if the wallet is encrypted, so that it does not appear
+    // in the process list or shell history. The variable is removed once read so that child processes do not inherit
+    // it, but /proc/<pid>/environ still shows the environment the daemon was started with. Prefer the prompt on shared
+    // hosts.
+    config.dan_wallet_daemon.password = env::var(PASSWORD_ENV_VAR).ok().map(SafePassword::from);
+    env::remove_var(PASSWORD_ENV_VAR);
 
     // Remove the file if it was left behind by a previous run
     let _file = fs::remove_file(config.common.base_path.join("pid"));
@@ -28,6 +28,7 @@ use tari_engine_types::{
 use tari_shutdown::ShutdownSignal;
 use tari_template_builtin::ACCOUNT_TEMPLATE_ADDRESS;
 use tari_template_lib::{
+    models::NonFungibleAddress,
     prelude::{NonFungibleId, ResourceAddress},
     resource::TOKEN_SYMBOL,
 };
@@ -275,14 +276,31 @@ where
         }
 
         for id in vault.get_non_fungible_ids() {
+            let fetched_nft;
             let nft = match nfts.get(id) {
                 Some(nft) => *nft,
                 None => {
-                    error!(
-                        target: LOG_TARGET,
-                        "NonFungible ID {} is found in the vault, but not found in substate diff", id
-                    );
-                    continue;
+                    if non_fungibles_api
+                        .non_fungible_token_get_by_nft_id(id.clone())
+                        .optional()?
+                        .is_some()
+                    {
+                        continue;
+                    }
+                    // The NFT was not created in a transaction seen by this wallet (e.g. when restoring)
+                    match self.fetch_non_fungible(*vault.resource_address(), id.clone()).await {
+                        Ok(nft) => {
+                            fetched_nft = nft;
+                            &fetched_nft
+                        },
+                        Err(e) => {
+                            error!(
+                                target: LOG_TARGET,
+                                "NonFungible ID {} is found in the vault, but could not be fetched: {}", id, e
+                            );
+                            continue;
+                        },
+                    }
                 },
             };
 
@@ -430,6 +448,23 @@ where
         Ok(resx)
     }
 
+    async fn fetch_non_fungible(
+        &self,
+        resource_address: ResourceAddress,
+        id: NonFungibleId,
+    ) -> Result<NonFungibleContainer, AccountMonitorError> {
+        let nft_addr = SubstateAddress::NonFungible(NonFungibleAddress::new(resource_address, id));
+        let ValidatorScanResult { substate, .. } = self
+            .wallet_sdk
+            .substate_api()
+            .scan_for_substate(&nft_addr, None)
+            .await?;
+        let nft = substate.into_non_fungible().ok_or_else(|| {
+            AccountMonitorError::UnexpectedSubstate(format!("Expected {} to be a non-fungible.", nft_addr))
+        })?;
+        Ok(nft)
+    }
+
     async fn add_vault_to_account_if_not_exist(
         &self,
         account_addr: &SubstateAddress,
@@ -86,6 +86,7 @@ pub async fn spawn_services(
         EpochManagerConfig {
             base_layer_confirmations: consensus_constants.base_layer_confirmations,
             committee_size: consensus_constants.committee_size,
+            epoch_transition_blocks: consensus_constants.epoch_transition_blocks,
         },
         global_db.clone(),
         base_node_client.clone(),
@@ -95,8 +96,12 @@ pub async fn spawn_services(
 
     // Template manager
     let template_manager = TemplateManager::initialize(global_db.clone(), config.indexer.templates.clone())?;
-    let (template_manager_service, _) =
-        template_manager::implementation::spawn(template_manager.clone(), shutdown.clone());
+    let (template_manager_service, _) = template_manager::implementation::spawn(
+        template_manager.clone(),
+        epoch_manager.clone(),
+        validator_node_client_factory.clone(),
+        shutdown.clone(),
+    );
 
     // Base Node scanner
     base_layer_scanner::spawn(
@@ -110,7 +115,8 @@ pub async fn spawn_services(
         SqliteStateStore::connect(&format!(
             "sqlite://{}",
             config.indexer.data_dir.join("unused-shard-store.sqlite").display()
-        ))?,
+        ))?
+        .into(),
         true,
         config.indexer.base_layer_scanning_interval,
     );
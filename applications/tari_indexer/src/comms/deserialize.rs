@@ -30,6 +30,7 @@ use prost::Message;
 use tari_comms::{message::InboundMessage, types::CommsPublicKey, PeerManager};
 use tari_comms_logging::SqliteMessageLog;
 use tari_crypto::tari_utilities::ByteArray;
+use tari_dan_app_utilities::metrics;
 use tari_dan_p2p::DanMessage;
 use tari_validator_node_rpc::proto;
 use tower::{Service, ServiceExt};
@@ -113,6 +114,9 @@ where
                 .find_by_node_id(&source_peer)
                 .await?
                 .ok_or_else(|| anyhow::anyhow!("Could not find peer with node id {}", source_peer))?;
+            metrics::P2P_INBOUND_MESSAGES
+                .with_label_values(&[msg.as_type_str()])
+                .inc();
             logger.log_inbound_message(peer.public_key.as_bytes(), msg.as_type_str(), &message_tag, &msg);
             let mut svc = next_service.ready_oneshot().await?;
             svc.call((peer.public_key, msg)).await?;
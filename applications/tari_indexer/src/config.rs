@@ -83,6 +83,8 @@ pub struct IndexerConfig {
     pub graphql_address: Option<SocketAddr>,
     /// The address of the HTTP UI
     pub http_ui_address: Option<SocketAddr>,
+    /// The address that prometheus metrics are served on at /metrics. Metrics are not served if not set.
+    pub metrics_address: Option<SocketAddr>,
     /// The jrpc address where the UI should connect (it can be the same as the json_rpc_address, but doesn't have to
     /// be), if this will be None, then the listen_addr will be used.
     pub ui_connect_address: Option<String>,
@@ -132,6 +134,7 @@ impl Default for IndexerConfig {
             json_rpc_address: Some("127.0.0.1:18300".parse().unwrap()),
             graphql_address: Some("127.0.0.1:18301".parse().unwrap()),
             http_ui_address: Some("127.0.0.1:15000".parse().unwrap()),
+            metrics_address: None,
             ui_connect_address: None,
             address_watchlist: vec![],
             dan_layer_scanning_internal: Duration::from_secs(10),
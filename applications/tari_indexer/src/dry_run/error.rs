@@ -21,11 +21,11 @@
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
 use tari_comms::protocol::rpc::RpcStatus;
-use tari_dan_app_utilities::transaction_executor::TransactionProcessorError;
+use tari_dan_app_utilities::transaction_executor::{TransactionProcessorError, ViewCallError};
 use tari_dan_common_types::{Epoch, ShardId};
-use tari_engine_types::substate::SubstateAddress;
+use tari_engine_types::{indexed_value::IndexedValueError, substate::SubstateAddress};
 use tari_epoch_manager::EpochManagerError;
-use tari_indexer_lib::transaction_autofiller::TransactionAutofillerError;
+use tari_indexer_lib::{error::IndexerError, transaction_autofiller::TransactionAutofillerError};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -40,6 +40,12 @@ pub enum DryRunTransactionProcessorError {
     RpcRequestFailed(#[from] RpcStatus),
     #[error("TransactionProcessor error: {0}")]
     PayloadProcessor(#[from] TransactionProcessorError),
+    #[error("View call error: {0}")]
+    ViewCall(#[from] ViewCallError),
+    #[error("Indexer error: {0}")]
+    IndexerError(#[from] IndexerError),
+    #[error("Could not decode the substate: {0}")]
+    IndexedValueError(#[from] IndexedValueError),
     #[error(
         "All validators for epoch {epoch} shard {shard_id} failed to return substate. does_not_exist: \
          {nexist_count}/{committee_size}, substate_down: {err_count}/{committee_size}"
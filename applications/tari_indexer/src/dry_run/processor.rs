@@ -22,7 +22,7 @@
 
 use std::{collections::HashMap, sync::Arc};
 
-use log::info;
+use log::{info, warn};
 use tari_comms::types::CommsPublicKey;
 use tari_dan_app_utilities::{
     template_manager::implementation::TemplateManager,
@@ -37,11 +37,17 @@ use tari_dan_engine::{
 };
 use tari_engine_types::{
     commit_result::ExecuteResult,
+    instruction_result::InstructionResult,
     substate::{Substate, SubstateAddress},
     virtual_substate::{VirtualSubstate, VirtualSubstateAddress},
 };
 use tari_epoch_manager::EpochManagerReader;
-use tari_indexer_lib::{substate_scanner::SubstateScanner, transaction_autofiller::TransactionAutofiller};
+use tari_indexer_lib::{
+    substate_decoder::fetch_substate_and_references,
+    substate_scanner::SubstateScanner,
+    transaction_autofiller::TransactionAutofiller,
+};
+use tari_template_lib::{args::Arg, models::ComponentAddress};
 use tari_transaction::{SubstateRequirement, Transaction};
 use tari_validator_node_rpc::client::{SubstateResult, ValidatorNodeClientFactory, ValidatorNodeRpcClient};
 use tokio::task;
@@ -50,9 +56,14 @@ use crate::dry_run::error::DryRunTransactionProcessorError;
 
 const LOG_TARGET: &str = "tari::indexer::dry_run_transaction_processor";
 
+/// How many levels of references (e.g. component -> vault -> resource) are followed when fetching the substates for a
+/// view call
+const MAX_VIEW_CALL_SUBSTATE_DEPTH: usize = 2;
+
 pub struct DryRunTransactionProcessor<TEpochManager, TClientFactory> {
     epoch_manager: TEpochManager,
     client_provider: TClientFactory,
+    substate_scanner: Arc<SubstateScanner<TEpochManager, TClientFactory>>,
     transaction_autofiller: TransactionAutofiller<TEpochManager, TClientFactory>,
     template_manager: TemplateManager,
 }
@@ -69,11 +80,12 @@ where
         substate_scanner: Arc<SubstateScanner<TEpochManager, TClientFactory>>,
         template_manager: TemplateManager,
     ) -> Self {
-        let transaction_autofiller = TransactionAutofiller::new(substate_scanner);
+        let transaction_autofiller = TransactionAutofiller::new(substate_scanner.clone());
 
         Self {
             epoch_manager,
             client_provider,
+            substate_scanner,
             transaction_autofiller,
             template_manager,
         }
@@ -83,6 +95,7 @@ where
         &self,
         transaction: Transaction,
         substate_requirements: Vec<SubstateRequirement>,
+        include_execution_trace: bool,
     ) -> Result<ExecuteResult, DryRunTransactionProcessorError> {
         info!(target: LOG_TARGET, "process_transaction: {}", transaction.hash());
 
@@ -96,7 +109,9 @@ where
         let epoch = self.epoch_manager.current_epoch().await?;
         found_substates.extend(self.fetch_input_substates(&transaction, epoch).await?);
 
-        let payload_processor = self.build_payload_processor(&transaction);
+        let payload_processor = self
+            .build_payload_processor(&transaction)
+            .with_execution_trace(include_execution_trace);
 
         let virtual_substates = Self::get_virtual_substates(epoch);
 
@@ -109,6 +124,49 @@ where
         Ok(result.into_result())
     }
 
+    /// Calls a non-mutating method on a component against the latest state held by the network. Nothing is submitted
+    /// to the network and the call fails if it attempts to change any state.
+    pub async fn process_view_call(
+        &self,
+        component_address: ComponentAddress,
+        method: String,
+        args: Vec<Arg>,
+    ) -> Result<InstructionResult, DryRunTransactionProcessorError> {
+        info!(target: LOG_TARGET, "process_view_call: {}.{}", component_address, method);
+
+        let found_substates = self.fetch_view_call_substates(component_address.into()).await?;
+        let epoch = self.epoch_manager.current_epoch().await?;
+        let virtual_substates = Self::get_virtual_substates(epoch);
+
+        let mut state_store = new_state_store();
+        state_store.extend(found_substates);
+
+        let payload_processor = TariDanTransactionProcessor::new(self.template_manager.clone(), FeeTable::zero_rated());
+        let result = task::block_in_place(|| {
+            payload_processor.execute_view_call(component_address, method, args, state_store, virtual_substates)
+        })?;
+
+        Ok(result)
+    }
+
+    /// Fetches the latest version of the component and the substates that it references. Substates that are not UP
+    /// are skipped, in which case the view call fails if it needs them.
+    async fn fetch_view_call_substates(
+        &self,
+        component_address: SubstateAddress,
+    ) -> Result<HashMap<SubstateAddress, Substate>, DryRunTransactionProcessorError> {
+        fetch_substate_and_references(component_address, MAX_VIEW_CALL_SUBSTATE_DEPTH, |address| async move {
+            match self.substate_scanner.get_substate(&address, None).await? {
+                SubstateResult::Up { substate, .. } => Ok(Some(substate)),
+                _ => {
+                    warn!(target: LOG_TARGET, "Substate {} for view call is not in UP status, skipping", address);
+                    Ok(None)
+                },
+            }
+        })
+        .await
+    }
+
     fn build_payload_processor(&self, transaction: &Transaction) -> TariDanTransactionProcessor<TemplateManager> {
         // simulate fees if the transaction requires it
         let fee_table = if Self::transaction_includes_fees(transaction) {
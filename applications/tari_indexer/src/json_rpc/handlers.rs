@@ -40,13 +40,18 @@ use tari_comms::{
     NodeIdentity,
 };
 use tari_crypto::tari_utilities::hex::Hex;
-use tari_dan_common_types::{optional::Optional, Epoch};
+use tari_dan_common_types::{
+    optional::{IsNotFoundError, Optional},
+    Epoch,
+};
 use tari_dan_storage::consensus_models::Decision;
 use tari_epoch_manager::{base_layer::EpochManagerHandle, EpochManagerReader};
 use tari_indexer_client::types::{
     AddAddressRequest,
     AddPeerRequest,
     AddPeerResponse,
+    CallViewMethodRequest,
+    CallViewMethodResponse,
     DeleteAddressRequest,
     GetEpochManagerStatsResponse,
     GetIdentityResponse,
@@ -55,6 +60,10 @@ use tari_indexer_client::types::{
     GetNonFungiblesResponse,
     GetRelatedTransactionsRequest,
     GetRelatedTransactionsResponse,
+    GetSubstateAtRequest,
+    GetSubstateAtResponse,
+    GetSubstateHistoryRequest,
+    GetSubstateHistoryResponse,
     GetSubstateRequest,
     GetSubstateResponse,
     GetTransactionResultRequest,
@@ -71,13 +80,14 @@ use tari_validator_node_rpc::client::{SubstateResult, TariCommsValidatorNodeClie
 use super::json_encoding::{
     encode_execute_result_into_json,
     encode_finalized_result_into_json,
+    encode_instruction_result_into_json,
     encode_substate_into_json,
 };
 use crate::{
     bootstrap::Services,
-    dry_run::processor::DryRunTransactionProcessor,
+    dry_run::{error::DryRunTransactionProcessorError, processor::DryRunTransactionProcessor},
     substate_manager::SubstateManager,
-    transaction_manager::TransactionManager,
+    transaction_manager::{TransactionManager, TransactionManagerError},
 };
 
 const LOG_TARGET: &str = "tari::indexer::json_rpc::handlers";
@@ -357,6 +367,55 @@ impl JsonRpcHandlers {
         }
     }
 
+    pub async fn get_substate_at(&self, value: JsonRpcExtractor) -> JrpcResult {
+        let answer_id = value.get_answer_id();
+        let request: GetSubstateAtRequest = value.parse_params()?;
+
+        let substate = match self
+            .transaction_manager
+            .get_substate_at(&request.address, request.at)
+            .await
+        {
+            Ok(substate) => substate,
+            Err(e) if e.is_not_found_error() => return Err(Self::not_found(answer_id, e)),
+            Err(e @ TransactionManagerError::HistoryUnavailable { .. }) => {
+                return Err(Self::error_response(
+                    answer_id,
+                    JsonRpcErrorReason::ApplicationError(410),
+                    e,
+                ));
+            },
+            Err(e) => {
+                warn!(target: LOG_TARGET, "Error getting substate at {:?}: {}", request.at, e);
+                return Err(Self::internal_error(
+                    answer_id,
+                    format!("Error getting substate: {}", e),
+                ));
+            },
+        };
+
+        Ok(JsonRpcResponse::success(answer_id, GetSubstateAtResponse { substate }))
+    }
+
+    pub async fn get_substate_history(&self, value: JsonRpcExtractor) -> JrpcResult {
+        let answer_id = value.get_answer_id();
+        let request: GetSubstateHistoryRequest = value.parse_params()?;
+
+        let history = self
+            .transaction_manager
+            .get_substate_history(&request.address)
+            .await
+            .map_err(|e| {
+                warn!(target: LOG_TARGET, "Error getting substate history: {}", e);
+                Self::internal_error(answer_id, format!("Error getting substate history: {}", e))
+            })?;
+
+        Ok(JsonRpcResponse::success(answer_id, GetSubstateHistoryResponse {
+            versions: history.versions,
+            pruned_before_epoch: history.pruned_before_epoch,
+        }))
+    }
+
     pub async fn inspect_substate(&self, value: JsonRpcExtractor) -> JrpcResult {
         let answer_id = value.get_answer_id();
         let request: InspectSubstateRequest = value.parse_params()?;
@@ -512,6 +571,29 @@ impl JsonRpcHandlers {
         }))
     }
 
+    pub async fn call_view_method(&self, value: JsonRpcExtractor) -> JrpcResult {
+        let answer_id = value.get_answer_id();
+        let request: CallViewMethodRequest = value.parse_params()?;
+
+        let result = self
+            .dry_run_transaction_processor
+            .process_view_call(request.component_address, request.method, request.args)
+            .await
+            .map_err(|e| match e {
+                DryRunTransactionProcessorError::ViewCall(e) => {
+                    Self::error_response(answer_id, JsonRpcErrorReason::InvalidParams, e)
+                },
+                e => Self::internal_error(answer_id, e),
+            })?;
+        let json_result =
+            encode_instruction_result_into_json(&result).map_err(|e| Self::internal_error(answer_id, e))?;
+
+        Ok(JsonRpcResponse::success(answer_id, CallViewMethodResponse {
+            result,
+            json_result,
+        }))
+    }
+
     pub async fn submit_transaction(&self, value: JsonRpcExtractor) -> JrpcResult {
         let answer_id = value.get_answer_id();
         let request: SubmitTransactionRequest = value.parse_params()?;
@@ -520,7 +602,11 @@ impl JsonRpcHandlers {
             let transaction_id = *request.transaction.id();
             let exec_result = self
                 .dry_run_transaction_processor
-                .process_transaction(request.transaction, request.required_substates)
+                .process_transaction(
+                    request.transaction,
+                    request.required_substates,
+                    request.include_execution_trace,
+                )
                 .await
                 .map_err(|e| Self::internal_error(answer_id, e))?;
 
@@ -5,6 +5,7 @@ use serde_json as json;
 use tari_engine_types::{
     commit_result::ExecuteResult,
     component::ComponentHeader,
+    instruction_result::InstructionResult,
     non_fungible::NonFungibleContainer,
     substate::{Substate, SubstateValue},
 };
@@ -35,10 +36,14 @@ pub fn encode_execute_result_into_json(result: &ExecuteResult) -> Result<Vec<jso
         .finalize
         .execution_results
         .iter()
-        .map(|r| serde_json::to_value(r.indexed.value()).map_err(JsonEncodingError::Serde))
+        .map(encode_instruction_result_into_json)
         .collect()
 }
 
+pub fn encode_instruction_result_into_json(result: &InstructionResult) -> Result<json::Value, JsonEncodingError> {
+    serde_json::to_value(result.indexed.value()).map_err(JsonEncodingError::Serde)
+}
+
 pub fn encode_substate_into_json(substate: &Substate) -> Result<json::Value, JsonEncodingError> {
     let substate_cbor = tari_bor::to_value(&substate)?;
     let substate_cbor = fix_invalid_object_keys(&substate_cbor);
@@ -63,6 +63,8 @@ async fn handler(Extension(handlers): Extension<Arc<JsonRpcHandlers>>, value: Js
         "add_peer" => handlers.add_peer(value).await,
         "get_comms_stats" => handlers.get_comms_stats(value).await,
         "get_substate" => handlers.get_substate(value).await,
+        "get_substate_at" => handlers.get_substate_at(value).await,
+        "get_substate_history" => handlers.get_substate_history(value).await,
         "inspect_substate" => handlers.inspect_substate(value).await,
         "get_addresses" => handlers.get_addresses(value).await,
         "add_address" => handlers.add_address(value).await,
@@ -73,6 +75,7 @@ async fn handler(Extension(handlers): Extension<Arc<JsonRpcHandlers>>, value: Js
         "get_non_fungible_count" => handlers.get_non_fungible_count(value).await,
         "get_non_fungibles" => handlers.get_non_fungibles(value).await,
         "submit_transaction" => handlers.submit_transaction(value).await,
+        "call_view_method" => handlers.call_view_method(value).await,
         "get_transaction_result" => handlers.get_transaction_result(value).await,
         "get_substate_transactions" => handlers.get_substate_transactions(value).await,
         "get_epoch_manager_stats" => handlers.get_epoch_manager_stats(value).await,
@@ -51,7 +51,7 @@ use tari_common::{
     exit_codes::{ExitCode, ExitError},
 };
 use tari_comms::peer_manager::PeerFeatures;
-use tari_dan_app_utilities::consensus_constants::ConsensusConstants;
+use tari_dan_app_utilities::{consensus_constants::ConsensusConstants, metrics::server::run_metrics_server};
 use tari_dan_storage::global::DbFactory;
 use tari_dan_storage_sqlite::SqliteDbFactory;
 use tari_indexer_lib::substate_scanner::SubstateScanner;
@@ -155,6 +155,11 @@ pub async fn run_indexer(config: ApplicationConfig, mut shutdown_signal: Shutdow
         task::spawn(run_graphql(address, substate_manager.clone()));
     }
 
+    // Run the metrics server
+    if let Some(address) = config.indexer.metrics_address {
+        task::spawn(run_metrics_server(address));
+    }
+
     // Create pid to allow watchers to know that the process has started
     fs::write(config.common.base_path.join("pid"), std::process::id().to_string())
         .map_err(|e| ExitError::new(ExitCode::IOError, e))?;
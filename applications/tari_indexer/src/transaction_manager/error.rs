@@ -15,6 +15,8 @@ pub enum TransactionManagerError {
     NoCommitteeMembers,
     #[error("{entity} not found: {key}")]
     NotFound { entity: &'static str, key: String },
+    #[error("History unavailable: {details}")]
+    HistoryUnavailable { details: String },
     #[error(transparent)]
     SubstateScanningError(#[from] IndexerError),
     #[error(transparent)]
@@ -21,15 +21,22 @@
 //  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
 mod error;
+use std::{
+    collections::{BTreeMap, HashSet},
+    fmt::Display,
+    future::Future,
+    iter,
+    sync::Arc,
+};
 
-use std::{collections::HashSet, fmt::Display, future::Future, iter, sync::Arc};
-
+pub use error::TransactionManagerError;
 use log::*;
 use tari_dan_common_types::{
     optional::{IsNotFoundError, Optional},
     NodeAddressable,
     ShardId,
 };
+use tari_dan_storage::consensus_models::{SubstateHistory, SubstateQueryPoint, SubstateRecord};
 use tari_engine_types::substate::SubstateAddress;
 use tari_epoch_manager::EpochManagerReader;
 use tari_indexer_lib::{substate_scanner::SubstateScanner, transaction_autofiller::TransactionAutofiller};
@@ -41,8 +48,6 @@ use tari_validator_node_rpc::client::{
     ValidatorNodeRpcClient,
 };
 
-use crate::transaction_manager::error::TransactionManagerError;
-
 const LOG_TARGET: &str = "tari::indexer::transaction_manager";
 
 pub struct TransactionManager<TEpochManager, TClientFactory> {
@@ -139,6 +144,100 @@ where
         .await
     }
 
+    /// Fetches every version of the substate still held by the network, oldest first. Each version is held by the
+    /// committee of its own shard, so the committees are walked version by version until the live (or last known)
+    /// version is reached. The walk stops early at a version that its committee has pruned.
+    pub async fn get_substate_history(
+        &self,
+        substate_address: &SubstateAddress,
+    ) -> Result<SubstateHistory, TransactionManagerError> {
+        let mut versions = BTreeMap::new();
+        let mut pruned_before_epoch = None;
+        let mut next_version = 0;
+        loop {
+            if !versions.contains_key(&next_version) {
+                let shard = ShardId::from_address(substate_address, next_version);
+                let found = self
+                    .try_with_committee(iter::once(shard), |mut client| {
+                        let substate_address = substate_address.clone();
+                        async move { client.get_substate_history(&substate_address).await }
+                    })
+                    .await?;
+                pruned_before_epoch = pruned_before_epoch.max(found.pruned_before_epoch);
+                for substate in found.versions {
+                    versions.entry(substate.version).or_insert(substate);
+                }
+            }
+
+            match versions.get(&next_version) {
+                Some(substate) if substate.is_destroyed() => next_version += 1,
+                _ => break,
+            }
+        }
+
+        Ok(SubstateHistory {
+            versions: versions.into_values().collect(),
+            pruned_before_epoch,
+        })
+    }
+
+    pub async fn get_substate_at(
+        &self,
+        substate_address: &SubstateAddress,
+        at: SubstateQueryPoint,
+    ) -> Result<Option<SubstateRecord>, TransactionManagerError> {
+        let SubstateHistory {
+            versions: history,
+            pruned_before_epoch,
+        } = self.get_substate_history(substate_address).await?;
+        if let SubstateQueryPoint::Epoch(epoch) = at {
+            let live = SubstateRecord::find_live_at_epoch(history, epoch);
+            if live.is_none() && pruned_before_epoch.map_or(false, |pruned| epoch < pruned) {
+                return Err(TransactionManagerError::HistoryUnavailable {
+                    details: format!(
+                        "the version of {} live at {:?} may have been pruned",
+                        substate_address, at
+                    ),
+                });
+            }
+            return Ok(live);
+        }
+
+        // Blocks and heights only have meaning within the chain of a single committee, so the committee holding each
+        // version is asked in turn (newest first) until one of them knows the requested point
+        let mut any_answered = false;
+        for version in history.iter().rev() {
+            let result = self
+                .try_with_committee(iter::once(version.to_shard_id()), |mut client| {
+                    let substate_address = substate_address.clone();
+                    async move { client.get_substate_at(&substate_address, at).await }
+                })
+                .await;
+            match result {
+                Ok(Some(substate)) => return Ok(Some(substate)),
+                Ok(None) => any_answered = true,
+                Err(err) => {
+                    debug!(
+                        target: LOG_TARGET,
+                        "Committee for version {} of {} could not answer query at {:?}: {}",
+                        version.version,
+                        substate_address,
+                        at,
+                        err
+                    );
+                },
+            }
+        }
+
+        if !any_answered && !history.is_empty() {
+            return Err(TransactionManagerError::NotFound {
+                entity: "Committed block",
+                key: format!("{:?}", at),
+            });
+        }
+        Ok(None)
+    }
+
     /// Fetches the committee members for the given shard and calls the given callback with each member until
     /// the callback returns a `Ok` result. If the callback returns an `Err` result, the next committee member is
     /// called.
@@ -0,0 +1,207 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use std::{
+    fs,
+    io,
+    path::{Component, Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use log::*;
+use tari_common_types::types::PublicKey;
+use tari_dan_common_types::{optional::Optional, Epoch};
+use tari_dan_storage::{
+    consensus_models::LockedBlock,
+    global::{DbFactory, MetadataKey},
+    StateStore,
+    StorageError,
+};
+use tari_dan_storage_sqlite::SqliteDbFactory;
+use tari_state_store_backend::{AnyStateStore, StateStoreBackend};
+use tari_validator_node_client::backup::{
+    BackupError,
+    BackupManifest,
+    BACKUP_FORMAT_VERSION,
+    BACKUP_KEYS_DIR,
+    BACKUP_STATE_DIR,
+};
+
+use crate::config::ValidatorNodeConfig;
+
+const LOG_TARGET: &str = "tari::dan::validator_node::backup";
+
+#[derive(Debug, thiserror::Error)]
+pub enum CreateBackupError {
+    #[error("Storage error: {0}")]
+    StorageError(#[from] StorageError),
+    #[error("Backup error: {0}")]
+    BackupError(#[from] BackupError),
+    #[error("IO error: {0}")]
+    IoError(#[from] io::Error),
+    #[error("The state store has no locked block to back up")]
+    NoLockedBlock,
+    #[error("Backup archive {0} already exists")]
+    ArchiveExists(PathBuf),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum BackupPathError {
+    #[error("Path {} is not inside the backup directory {}", path.display(), backup_dir.display())]
+    OutsideBackupDir { path: PathBuf, backup_dir: PathBuf },
+    #[error("IO error: {0}")]
+    IoError(#[from] io::Error),
+}
+
+/// Resolves a path given over JSON-RPC to a path inside `backup_dir`. Relative paths are relative to `backup_dir`.
+/// Paths that contain `..` or that resolve (following symlinks) to outside of `backup_dir` are rejected.
+pub fn resolve_backup_path(backup_dir: &Path, path: &Path) -> Result<PathBuf, BackupPathError> {
+    let outside = || BackupPathError::OutsideBackupDir {
+        path: path.to_path_buf(),
+        backup_dir: backup_dir.to_path_buf(),
+    };
+    if path.components().any(|c| c == Component::ParentDir) {
+        return Err(outside());
+    }
+    let resolved = backup_dir.join(path);
+    if !resolved.starts_with(backup_dir) {
+        return Err(outside());
+    }
+
+    // Check the deepest part of the path that exists, in case it is a symlink to somewhere else
+    fs::create_dir_all(backup_dir)?;
+    let canonical_backup_dir = backup_dir.canonicalize()?;
+    let existing = resolved
+        .ancestors()
+        .find(|p| p.exists())
+        .unwrap_or(backup_dir)
+        .canonicalize()?;
+    if !existing.starts_with(&canonical_backup_dir) {
+        return Err(outside());
+    }
+
+    Ok(resolved)
+}
+
+/// Creates a backup archive of the state store, global database and node keys in a new directory under `output_dir`,
+/// returning the path of the archive. The node can keep running while the backup is taken.
+pub fn create_backup(
+    config: &ValidatorNodeConfig,
+    state_store: &AnyStateStore<PublicKey>,
+    output_dir: &Path,
+) -> Result<(PathBuf, BackupManifest), CreateBackupError> {
+    let created_at = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system time before unix epoch")
+        .as_secs();
+    let archive_dir = output_dir.join(format!("vn-backup-{}", created_at));
+    if archive_dir.exists() {
+        return Err(CreateBackupError::ArchiveExists(archive_dir));
+    }
+
+    match create_archive(config, state_store, &archive_dir, created_at) {
+        Ok(manifest) => Ok((archive_dir, manifest)),
+        Err(err) => {
+            // Don't leave a partial archive behind
+            if let Err(err) = fs::remove_dir_all(&archive_dir) {
+                warn!(target: LOG_TARGET, "Failed to remove partial backup {}: {}", archive_dir.display(), err);
+            }
+            Err(err)
+        },
+    }
+}
+
+fn create_archive(
+    config: &ValidatorNodeConfig,
+    state_store: &AnyStateStore<PublicKey>,
+    archive_dir: &Path,
+    created_at: u64,
+) -> Result<BackupManifest, CreateBackupError> {
+    let state_dir = archive_dir.join(BACKUP_STATE_DIR);
+    let keys_dir = archive_dir.join(BACKUP_KEYS_DIR);
+    fs::create_dir_all(&state_dir)?;
+    fs::create_dir_all(&keys_dir)?;
+
+    let backend = state_store.backend();
+    let state_path = match backend {
+        StateStoreBackend::Sqlite => state_dir.join(file_name(&config.state_db_path())),
+        StateStoreBackend::Lmdb => state_dir.join(file_name(&config.state_lmdb_path())),
+    };
+    state_store.backup_to(&state_path)?;
+    SqliteDbFactory::new(config.data_dir.clone()).backup_global_db_to(&state_dir.join("global_storage.sqlite"))?;
+    // Like the locked block, the epoch manager state is read from the copy
+    let (global_db_epoch, global_db_height) = read_global_db_tip(state_dir.clone())?;
+
+    for key_file in [&config.identity_file, &config.tor_identity_file, &config.shard_key_file] {
+        if key_file.exists() {
+            fs::copy(key_file, keys_dir.join(file_name(key_file)))?;
+        }
+    }
+
+    // The locked block is read from the copy rather than the live store, which may have moved on since
+    let copy = match backend {
+        StateStoreBackend::Sqlite => {
+            AnyStateStore::<PublicKey>::connect_sqlite(&format!("sqlite://{}", state_path.display()))?
+        },
+        StateStoreBackend::Lmdb => AnyStateStore::open_lmdb(&state_path)?,
+    };
+    let locked = copy.with_read_tx(|tx| {
+        let Some(locked) = LockedBlock::get(tx).optional()? else {
+            return Ok(None);
+        };
+        let block = locked.get_block(tx)?;
+        Ok::<_, StorageError>(Some((locked, block.epoch())))
+    })?;
+    drop(copy);
+    if backend == StateStoreBackend::Lmdb {
+        // Opening the copy creates a lock file which is not part of the backup
+        fs::remove_file(state_path.join("lock.mdb"))?;
+    }
+    let (locked, epoch) = locked.ok_or(CreateBackupError::NoLockedBlock)?;
+
+    let manifest = BackupManifest {
+        format_version: BACKUP_FORMAT_VERSION,
+        created_at,
+        state_store_backend: backend.to_string(),
+        locked_block: *locked.block_id(),
+        locked_height: locked.height(),
+        locked_epoch: epoch,
+        global_db_epoch,
+        global_db_height,
+        files: vec![],
+    }
+    .write(archive_dir)?;
+
+    info!(
+        target: LOG_TARGET,
+        "💾 Created backup {} at locked block {} (height: {}, epoch: {}) with global database at epoch {} (base layer \
+         height: {})",
+        archive_dir.display(),
+        manifest.locked_block,
+        manifest.locked_height,
+        manifest.locked_epoch,
+        manifest.global_db_epoch,
+        manifest.global_db_height
+    );
+
+    Ok(manifest)
+}
+
+/// Returns the current epoch and base layer block height recorded by the epoch manager in the global database in
+/// `data_dir`
+fn read_global_db_tip(data_dir: PathBuf) -> Result<(Epoch, u64), StorageError> {
+    let global_db = SqliteDbFactory::new(data_dir).get_or_create_global_db()?;
+    let mut tx = global_db.create_transaction()?;
+    let mut metadata = global_db.metadata(&mut tx);
+    let epoch = metadata
+        .get_metadata(MetadataKey::EpochManagerCurrentEpoch)?
+        .unwrap_or(Epoch(0));
+    let block_height = metadata
+        .get_metadata(MetadataKey::EpochManagerCurrentBlockHeight)?
+        .unwrap_or(0);
+    Ok((epoch, block_height))
+}
+
+fn file_name(path: &Path) -> &Path {
+    path.file_name().map(Path::new).unwrap_or(path)
+}
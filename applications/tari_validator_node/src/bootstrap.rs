@@ -34,6 +34,7 @@ use tari_common::{
 };
 use tari_common_types::types::PublicKey;
 use tari_comms::{protocol::rpc::RpcServer, types::CommsPublicKey, CommsNode, NodeIdentity, UnspawnedCommsNode};
+use tari_consensus::hotstuff::{check_and_recover, ConsensusConfig};
 use tari_core::transactions::transaction_components::ValidatorNodeSignature;
 use tari_dan_app_utilities::{
     base_layer_scanner,
@@ -57,7 +58,7 @@ use tari_engine_types::{resource::Resource, substate::SubstateAddress};
 use tari_epoch_manager::base_layer::{EpochManagerConfig, EpochManagerHandle};
 use tari_indexer_lib::substate_scanner::SubstateScanner;
 use tari_shutdown::ShutdownSignal;
-use tari_state_store_sqlite::SqliteStateStore;
+use tari_state_store_backend::{AnyStateStore, StateStoreBackend};
 use tari_template_lib::{
     auth::ResourceAccessRules,
     constants::{CONFIDENTIAL_TARI_RESOURCE_ADDRESS, PUBLIC_IDENTITY_RESOURCE_ADDRESS},
@@ -73,8 +74,10 @@ use tokio::{sync::mpsc, task::JoinHandle};
 use crate::{
     comms,
     consensus,
-    consensus::ConsensusHandle,
+    consensus::{ConsensusHandle, TariStateManager},
+    dev_templates::DevTemplateRegistrar,
     dry_run_transaction_processor::DryRunTransactionProcessor,
+    epoch_handoff,
     p2p::{
         create_tari_validator_node_rpc_service,
         services::{
@@ -82,6 +85,7 @@ use crate::{
             mempool,
             mempool::{
                 ClaimFeeTransactionValidator,
+                EpochRangeValidator,
                 FeeTransactionValidator,
                 HasInputs,
                 HasInvolvedShards,
@@ -99,6 +103,7 @@ use crate::{
         },
     },
     registration,
+    state_pruning,
     substate_resolver::TariSubstateResolver,
     virtual_substate::VirtualSubstateManager,
     ApplicationConfig,
@@ -159,9 +164,16 @@ pub async fn spawn_services(
     handles.push(join_handle);
 
     // Connect to shard db
-    let state_store =
-        SqliteStateStore::connect(&format!("sqlite://{}", config.validator_node.state_db_path().display()))?;
+    let state_store = match config.validator_node.state_store_backend {
+        StateStoreBackend::Sqlite => {
+            AnyStateStore::connect_sqlite(&format!("sqlite://{}", config.validator_node.state_db_path().display()))?
+        },
+        StateStoreBackend::Lmdb => AnyStateStore::open_lmdb(config.validator_node.state_lmdb_path())?,
+    };
+    info!(target: LOG_TARGET, "State store backend: {}", state_store.backend());
     state_store.with_write_tx(|tx| bootstrap_state(tx))?;
+    // Refuse to start consensus on top of inconsistent state
+    check_and_recover(&state_store, &TariStateManager::new())?;
 
     // Epoch manager
     let (epoch_manager, join_handle) = tari_epoch_manager::base_layer::spawn_service(
@@ -170,6 +182,7 @@ pub async fn spawn_services(
         EpochManagerConfig {
             base_layer_confirmations: consensus_constants.base_layer_confirmations,
             committee_size: consensus_constants.committee_size,
+            epoch_transition_blocks: consensus_constants.epoch_transition_blocks,
         },
         global_db.clone(),
         base_node_client.clone(),
@@ -181,10 +194,16 @@ pub async fn spawn_services(
     // Create registration file
     create_registration_file(config, &epoch_manager, &node_identity).await?;
 
+    let validator_node_client_factory = TariCommsValidatorNodeClientFactory::new(comms.connectivity());
+
     // Template manager
     let template_manager = TemplateManager::initialize(global_db.clone(), config.validator_node.templates.clone())?;
-    let (template_manager_service, join_handle) =
-        template_manager::implementation::spawn(template_manager.clone(), shutdown.clone());
+    let (template_manager_service, join_handle) = template_manager::implementation::spawn(
+        template_manager.clone(),
+        epoch_manager.clone(),
+        validator_node_client_factory.clone(),
+        shutdown.clone(),
+    );
     handles.push(join_handle);
 
     // Payload processor
@@ -198,13 +217,21 @@ pub async fn spawn_services(
             per_log_cost: 1,
         }
     };
-    let payload_processor = TariDanTransactionProcessor::new(template_manager.clone(), fee_table);
-
-    let validator_node_client_factory = TariCommsValidatorNodeClientFactory::new(comms.connectivity());
+    let payload_processor = TariDanTransactionProcessor::new(template_manager.clone(), fee_table)
+        .with_execution_trace(config.validator_node.execution_traces);
 
     // Consensus
+    let consensus_config = ConsensusConfig {
+        max_block_commands: consensus_constants.max_block_commands,
+        max_block_size_bytes: consensus_constants.max_block_size_bytes,
+        max_block_transactions_per_signer: config.validator_node.max_block_transactions_per_signer,
+        block_time: consensus_constants.block_time,
+        leader_timeout: config.validator_node.leader_timeout,
+        max_delta: config.validator_node.max_leader_timeout_delta,
+    };
     let (tx_executed_transaction, rx_executed_transaction) = mpsc::channel(10);
     let (consensus_join_handle, consensus_handle, rx_consensus_to_mempool) = consensus::spawn(
+        consensus_config,
         state_store.clone(),
         node_identity.clone(),
         epoch_manager.clone(),
@@ -212,6 +239,7 @@ pub async fn spawn_services(
         rx_consensus_message,
         outbound_messaging.clone(),
         validator_node_client_factory.clone(),
+        config.validator_node.state_sync_mode,
         shutdown.clone(),
     )
     .await;
@@ -243,6 +271,8 @@ pub async fn spawn_services(
         state_store.clone(),
         rx_consensus_to_mempool,
         consensus_handle.clone(),
+        config.validator_node.mempool.clone(),
+        networking.clone(),
     );
     handles.push(join_handle);
 
@@ -260,6 +290,18 @@ pub async fn spawn_services(
     );
     handles.push(join_handle);
 
+    let dry_run_transaction_processor =
+        DryRunTransactionProcessor::new(epoch_manager.clone(), payload_processor, substate_resolver);
+
+    // Development templates registered directly with this node (local network only)
+    let dev_template_registrar = DevTemplateRegistrar::new(
+        config.network,
+        node_identity.clone(),
+        template_manager_service.clone(),
+        epoch_manager.clone(),
+        validator_node_client_factory.clone(),
+    );
+
     let comms = setup_p2p_rpc(
         config,
         comms,
@@ -267,6 +309,10 @@ pub async fn spawn_services(
         state_store.clone(),
         mempool.clone(),
         virtual_substate_manager,
+        dry_run_transaction_processor.clone(),
+        dev_template_registrar.clone(),
+        template_manager_service.clone(),
+        epoch_manager.clone(),
     );
     let comms = comms::spawn_comms_using_transport(comms, p2p_config.transport.clone())
         .await
@@ -281,6 +327,27 @@ pub async fn spawn_services(
     // changed by comms during initialization when using tor.
     save_identities(config, &comms)?;
 
+    if config.validator_node.state_pruning.enabled {
+        let handle = state_pruning::spawn(
+            config.validator_node.state_pruning.clone(),
+            state_store.clone(),
+            epoch_manager.clone(),
+            shutdown.clone(),
+        );
+        handles.push(handle);
+    } else {
+        info!(target: LOG_TARGET, "✂️ State pruning is disabled");
+    }
+
+    // Pick up pending transactions from the outgoing committee when joining a committee
+    let handle = epoch_handoff::spawn(
+        epoch_manager.clone(),
+        validator_node_client_factory.clone(),
+        mempool.clone(),
+        shutdown.clone(),
+    );
+    handles.push(handle);
+
     // Auto-registration
     if config.validator_node.auto_register {
         let handle = registration::spawn(config.clone(), node_identity.clone(), epoch_manager.clone(), shutdown);
@@ -289,9 +356,6 @@ pub async fn spawn_services(
         info!(target: LOG_TARGET, "♽️ Node auto registration is disabled");
     }
 
-    let dry_run_transaction_processor =
-        DryRunTransactionProcessor::new(epoch_manager.clone(), payload_processor, substate_resolver);
-
     Ok(Services {
         comms,
         networking,
@@ -304,6 +368,7 @@ pub async fn spawn_services(
         dry_run_transaction_processor,
         handles,
         validator_node_client_factory,
+        dev_template_registrar,
     })
 }
 
@@ -364,7 +429,8 @@ pub struct Services {
     pub global_db: GlobalDb<SqliteGlobalDbAdapter>,
     pub dry_run_transaction_processor: DryRunTransactionProcessor,
     pub validator_node_client_factory: TariCommsValidatorNodeClientFactory,
-    pub state_store: SqliteStateStore<PublicKey>,
+    pub state_store: AnyStateStore<PublicKey>,
+    pub dev_template_registrar: DevTemplateRegistrar,
 
     pub handles: Vec<JoinHandle<Result<(), anyhow::Error>>>,
 }
@@ -385,9 +451,13 @@ fn setup_p2p_rpc(
     config: &ApplicationConfig,
     comms: UnspawnedCommsNode,
     peer_provider: CommsPeerProvider,
-    shard_store_store: SqliteStateStore<CommsPublicKey>,
+    shard_store_store: AnyStateStore<CommsPublicKey>,
     mempool: MempoolHandle,
-    virtual_substate_manager: VirtualSubstateManager<SqliteStateStore<PublicKey>, EpochManagerHandle>,
+    virtual_substate_manager: VirtualSubstateManager<AnyStateStore<PublicKey>, EpochManagerHandle>,
+    dry_run_transaction_processor: DryRunTransactionProcessor,
+    dev_template_registrar: DevTemplateRegistrar,
+    template_manager: TemplateManagerHandle,
+    epoch_manager: EpochManagerHandle,
 ) -> UnspawnedCommsNode {
     let rpc_server = RpcServer::builder()
         .with_maximum_simultaneous_sessions(config.validator_node.p2p.rpc_max_simultaneous_sessions)
@@ -397,6 +467,10 @@ fn setup_p2p_rpc(
             shard_store_store,
             mempool,
             virtual_substate_manager,
+            dry_run_transaction_processor,
+            dev_template_registrar,
+            template_manager,
+            epoch_manager,
         ));
 
     comms.add_protocol_extension(rpc_server)
@@ -473,7 +547,9 @@ fn create_mempool_before_execute_validator(
     template_manager: TemplateManager,
     epoch_manager: EpochManagerHandle,
 ) -> impl Validator<Transaction, Error = MempoolError> {
-    let mut validator = TemplateExistsValidator::new(template_manager)
+    // The signature and structure are checked by the mempool before the transaction is stored
+    let mut validator = EpochRangeValidator::new(epoch_manager.clone())
+        .and_then(TemplateExistsValidator::new(template_manager))
         .and_then(ClaimFeeTransactionValidator::new(epoch_manager))
         .boxed();
     if !config.no_fees {
@@ -487,7 +563,7 @@ fn create_mempool_before_execute_validator(
 }
 
 fn create_mempool_after_execute_validator(
-    store: SqliteStateStore<CommsPublicKey>,
+    store: AnyStateStore<CommsPublicKey>,
 ) -> impl Validator<ExecutedTransaction, Error = MempoolError> {
     HasInvolvedShards::new()
         .and_then(InputRefsValidator::new())
@@ -0,0 +1,298 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use std::time::{Duration, Instant};
+
+use futures::future;
+use tari_common_types::types::PublicKey;
+use tari_consensus::hotstuff::{MemberParticipation, Observation};
+use tari_dan_common_types::NodeHeight;
+use tari_dan_storage::{consensus_models::HighQc, StateStore, StorageError};
+use tari_epoch_manager::{base_layer::EpochManagerHandle, EpochManagerError, EpochManagerReader};
+use tari_state_store_backend::AnyStateStore;
+use tari_validator_node_client::types::{
+    CommitteeHealthStatus,
+    CommitteeHealthSummary,
+    CommitteeMemberHealth,
+    ConsensusObservation,
+    GetCommitteeHealthResponse,
+};
+use tari_validator_node_rpc::{
+    client::{TariCommsValidatorNodeClientFactory, ValidatorNodeClientFactory},
+    proto::rpc::GetHighQcRequest,
+};
+use tokio::time;
+
+use crate::consensus::ConsensusHandle;
+
+/// The maximum time to wait for a committee member to respond to a high QC request
+const MEMBER_REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+/// The number of blocks that a member may be behind this node and still be considered in sync
+const IN_SYNC_HEIGHT_TOLERANCE: u64 = 3;
+
+#[derive(Debug, thiserror::Error)]
+pub enum CommitteeHealthError {
+    #[error("Epoch manager error: {0}")]
+    EpochManagerError(#[from] EpochManagerError),
+    #[error("Storage error: {0}")]
+    StorageError(#[from] StorageError),
+}
+
+/// Builds a health report for the local committee by combining the consensus participation observed by this node with
+/// the high QC of each member, requested over p2p RPC.
+pub struct CommitteeHealthReporter<'a> {
+    epoch_manager: &'a EpochManagerHandle,
+    consensus_handle: &'a ConsensusHandle,
+    client_factory: &'a TariCommsValidatorNodeClientFactory,
+    state_store: &'a AnyStateStore<PublicKey>,
+}
+
+impl<'a> CommitteeHealthReporter<'a> {
+    pub fn new(
+        epoch_manager: &'a EpochManagerHandle,
+        consensus_handle: &'a ConsensusHandle,
+        client_factory: &'a TariCommsValidatorNodeClientFactory,
+        state_store: &'a AnyStateStore<PublicKey>,
+    ) -> Self {
+        Self {
+            epoch_manager,
+            consensus_handle,
+            client_factory,
+            state_store,
+        }
+    }
+
+    pub async fn report(&self) -> Result<GetCommitteeHealthResponse, CommitteeHealthError> {
+        let epoch = self.epoch_manager.current_epoch().await?;
+        let committee = self.epoch_manager.get_local_committee(epoch).await?;
+        let local_node = self.epoch_manager.get_our_validator_node(epoch).await?;
+        let local_height = self.state_store.with_read_tx(|tx| HighQc::get(tx))?.block_height();
+
+        let members = future::join_all(
+            committee
+                .members()
+                .iter()
+                .map(|member| self.member_health(member, *member == local_node.address, local_height)),
+        )
+        .await;
+
+        let num_reachable = members.iter().filter(|m| m.error.is_none()).count();
+        let num_in_sync = members.iter().filter(|m| m.is_in_sync).count();
+        let status = committee_status(num_in_sync, committee.len(), committee.quorum_threshold());
+        let unhealthy_members = members
+            .iter()
+            .filter(|m| !is_healthy(m))
+            .map(|m| m.public_key.clone())
+            .collect();
+
+        Ok(GetCommitteeHealthResponse {
+            epoch,
+            local_height,
+            summary: CommitteeHealthSummary {
+                status,
+                committee_size: committee.len(),
+                quorum_threshold: committee.quorum_threshold(),
+                num_reachable,
+                num_in_sync,
+                unhealthy_members,
+            },
+            members,
+        })
+    }
+
+    async fn member_health(
+        &self,
+        member: &PublicKey,
+        is_local_node: bool,
+        local_height: NodeHeight,
+    ) -> CommitteeMemberHealth {
+        let participation = self.consensus_handle.participation().get(member);
+        let mut health = new_member_health(member, is_local_node, participation);
+
+        if is_local_node {
+            health.latency_ms = Some(0);
+            health.high_qc_height = Some(local_height);
+            health.is_in_sync = self.consensus_handle.get_current_state().is_running();
+            return health;
+        }
+
+        match time::timeout(MEMBER_REQUEST_TIMEOUT, self.fetch_high_qc_height(member)).await {
+            Ok(Ok((height, latency))) => {
+                health.latency_ms = Some(latency.as_millis() as u64);
+                health.high_qc_height = height;
+                health.is_in_sync = is_in_sync(height, local_height);
+            },
+            Ok(Err(err)) => {
+                health.error = Some(err);
+            },
+            Err(_) => {
+                health.error = Some(format!("Timed out after {:.2?}", MEMBER_REQUEST_TIMEOUT));
+            },
+        }
+
+        health
+    }
+
+    /// Returns the member's high QC height and the round trip time of the request. The time taken to connect to the
+    /// member is not included, so that a new connection does not count towards its latency.
+    async fn fetch_high_qc_height(&self, member: &PublicKey) -> Result<(Option<NodeHeight>, Duration), String> {
+        let mut rpc_client = self.client_factory.create_client(member);
+        let mut client = rpc_client.client_connection().await.map_err(|e| e.to_string())?;
+        let timer = Instant::now();
+        let resp = client
+            .get_high_qc(GetHighQcRequest {})
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok((resp.high_qc.map(|qc| NodeHeight(qc.block_height)), timer.elapsed()))
+    }
+}
+
+fn new_member_health(
+    member: &PublicKey,
+    is_local_node: bool,
+    participation: MemberParticipation,
+) -> CommitteeMemberHealth {
+    CommitteeMemberHealth {
+        public_key: member.clone(),
+        is_local_node,
+        last_vote: participation.last_vote.map(convert_observation),
+        last_proposal: participation.last_proposal.map(convert_observation),
+        last_missed_proposal: participation.last_missed_proposal,
+        votes_received: participation.votes_received,
+        proposals_received: participation.proposals_received,
+        proposals_missed: participation.proposals_missed,
+        latency_ms: None,
+        high_qc_height: None,
+        is_in_sync: false,
+        error: None,
+    }
+}
+
+fn convert_observation(observation: Observation) -> ConsensusObservation {
+    ConsensusObservation {
+        block_height: observation.block_height,
+        timestamp: observation.timestamp,
+    }
+}
+
+/// The committee is healthy if all members are in sync, and can make progress as long as a quorum is in sync
+fn committee_status(num_in_sync: usize, committee_size: usize, quorum_threshold: usize) -> CommitteeHealthStatus {
+    if num_in_sync == committee_size {
+        CommitteeHealthStatus::Healthy
+    } else if num_in_sync >= quorum_threshold {
+        CommitteeHealthStatus::Degraded
+    } else {
+        CommitteeHealthStatus::Stalled
+    }
+}
+
+fn is_in_sync(high_qc_height: Option<NodeHeight>, local_height: NodeHeight) -> bool {
+    high_qc_height.map_or(false, |h| h.0 + IN_SYNC_HEIGHT_TOLERANCE >= local_height.0)
+}
+
+/// A member is unhealthy if it is unreachable, behind this node, or if its last turn as leader timed out
+fn is_healthy(member: &CommitteeMemberHealth) -> bool {
+    if member.error.is_some() || !member.is_in_sync {
+        return false;
+    }
+
+    match (member.last_missed_proposal, member.last_proposal) {
+        (Some(missed), Some(proposal)) => proposal.timestamp >= missed,
+        (Some(_), None) => false,
+        (None, _) => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::OsRng;
+    use tari_common_types::types::PrivateKey;
+    use tari_consensus::hotstuff::ParticipationTracker;
+    use tari_crypto::keys::{PublicKey as _, SecretKey};
+
+    use super::*;
+
+    fn random_member() -> PublicKey {
+        PublicKey::from_secret_key(&PrivateKey::random(&mut OsRng))
+    }
+
+    fn healthy_member() -> CommitteeMemberHealth {
+        let member = random_member();
+        let mut health = new_member_health(&member, false, MemberParticipation::default());
+        health.is_in_sync = true;
+        health
+    }
+
+    #[test]
+    fn it_reports_the_committee_status_by_the_number_of_members_in_sync() {
+        assert_eq!(committee_status(4, 4, 3), CommitteeHealthStatus::Healthy);
+        assert_eq!(committee_status(3, 4, 3), CommitteeHealthStatus::Degraded);
+        assert_eq!(committee_status(2, 4, 3), CommitteeHealthStatus::Stalled);
+    }
+
+    #[test]
+    fn it_tolerates_members_that_are_a_few_blocks_behind() {
+        let local_height = NodeHeight(10);
+        assert!(is_in_sync(Some(NodeHeight(12)), local_height));
+        assert!(is_in_sync(
+            Some(NodeHeight(10 - IN_SYNC_HEIGHT_TOLERANCE)),
+            local_height
+        ));
+        assert!(!is_in_sync(
+            Some(NodeHeight(10 - IN_SYNC_HEIGHT_TOLERANCE - 1)),
+            local_height
+        ));
+        assert!(!is_in_sync(None, local_height));
+    }
+
+    #[test]
+    fn it_copies_the_observed_participation() {
+        let member = random_member();
+        let tracker = ParticipationTracker::new();
+        tracker.record_vote(&member, NodeHeight(5));
+        tracker.record_proposal(&member, NodeHeight(6));
+        tracker.record_missed_proposal(&member);
+
+        let health = new_member_health(&member, true, tracker.get(&member));
+        assert_eq!(health.public_key, member);
+        assert!(health.is_local_node);
+        assert_eq!(health.last_vote.unwrap().block_height, NodeHeight(5));
+        assert_eq!(health.last_proposal.unwrap().block_height, NodeHeight(6));
+        assert!(health.last_missed_proposal.is_some());
+        assert_eq!(health.votes_received, 1);
+        assert_eq!(health.proposals_received, 1);
+        assert_eq!(health.proposals_missed, 1);
+        assert!(!health.is_in_sync);
+    }
+
+    #[test]
+    fn it_marks_unreachable_and_out_of_sync_members_as_unhealthy() {
+        assert!(is_healthy(&healthy_member()));
+
+        let mut member = healthy_member();
+        member.error = Some("Connection refused".to_string());
+        assert!(!is_healthy(&member));
+
+        let mut member = healthy_member();
+        member.is_in_sync = false;
+        assert!(!is_healthy(&member));
+    }
+
+    #[test]
+    fn it_marks_members_whose_last_turn_as_leader_timed_out_as_unhealthy() {
+        let proposal = |timestamp| ConsensusObservation {
+            block_height: NodeHeight(1),
+            timestamp,
+        };
+
+        let mut member = healthy_member();
+        member.last_missed_proposal = Some(100);
+        assert!(!is_healthy(&member));
+
+        member.last_proposal = Some(proposal(99));
+        assert!(!is_healthy(&member));
+
+        member.last_proposal = Some(proposal(100));
+        assert!(is_healthy(&member));
+    }
+}
@@ -32,6 +32,7 @@ use tari_comms::{message::InboundMessage, types::CommsPublicKey, PeerManager};
 use tari_comms_logging::SqliteMessageLog;
 use tari_consensus::messages::HotstuffMessage;
 use tari_crypto::tari_utilities::ByteArray;
+use tari_dan_app_utilities::metrics;
 use tari_dan_p2p::DanMessage;
 use tari_validator_node_rpc::proto;
 use tower::{Service, ServiceExt};
@@ -121,6 +122,9 @@ where
                 peer.public_key
             );
 
+            metrics::P2P_INBOUND_MESSAGES
+                .with_label_values(&[msg.as_type_str()])
+                .inc();
             logger.log_inbound_message(peer.public_key.as_bytes(), msg.as_type_str(), &message_tag, &msg);
             let mut svc = next_service.ready_oneshot().await?;
             svc.call((peer.public_key, msg)).await?;
@@ -168,6 +172,9 @@ where
                 peer.public_key
             );
 
+            metrics::P2P_INBOUND_MESSAGES
+                .with_label_values(&[msg.as_type_str()])
+                .inc();
             logger.log_inbound_message(peer.public_key.as_bytes(), msg.as_type_str(), "", &msg);
             let mut svc = next_service.ready_oneshot().await?;
             svc.call((peer.public_key, msg)).await?;
@@ -35,9 +35,13 @@ use tari_common::{
     SubConfigPath,
 };
 use tari_comms::multiaddr::Multiaddr;
+use tari_comms_rpc_state_sync::StateSyncMode;
 use tari_crypto::ristretto::RistrettoPublicKey;
 use tari_dan_app_utilities::template_manager::implementation::TemplateConfig;
 use tari_p2p::{P2pConfig, PeerSeedsConfig};
+use tari_state_store_backend::StateStoreBackend;
+
+use crate::{p2p::services::mempool::MempoolConfig, state_pruning::StatePruningConfig};
 
 #[derive(Debug, Clone)]
 pub struct ApplicationConfig {
@@ -94,6 +98,8 @@ pub struct ValidatorNodeConfig {
     pub ui_connect_address: Option<String>,
     /// The address of the HTTP UI
     pub http_ui_address: Option<SocketAddr>,
+    /// The address that prometheus metrics are served on at /metrics. Metrics are not served if not set.
+    pub metrics_address: Option<SocketAddr>,
     /// The node will re-register each epoch
     pub auto_register: bool,
     /// Template config
@@ -104,6 +110,29 @@ pub struct ValidatorNodeConfig {
     pub fee_claim_public_key: RistrettoPublicKey,
     /// Create identity file if not exists
     pub dont_create_id: bool,
+    /// The time to wait for a proposal, in addition to the block time, before the leader is considered to have failed
+    #[serde(with = "serializers::seconds")]
+    pub leader_timeout: Duration,
+    /// The upper bound of the exponential backoff added to the leader timeout after consecutive leader failures
+    #[serde(with = "serializers::seconds")]
+    pub max_leader_timeout_delta: Duration,
+    /// The maximum number of new transactions from a single signer that this node will include in a block it proposes
+    pub max_block_transactions_per_signer: usize,
+    /// The database backend used for the consensus state store (sqlite or lmdb)
+    pub state_store_backend: StateStoreBackend,
+    /// Configuration for pruning consensus state that is no longer needed
+    pub state_pruning: StatePruningConfig,
+    /// How a node without any local state syncs (blocks or snapshot). Snapshot sync fetches a verified substate
+    /// snapshot at a committed checkpoint block and only syncs the blocks after it.
+    pub state_sync_mode: StateSyncMode,
+    /// The directory that backups are written to when no output directory is given. The JSON-RPC backup methods can
+    /// only access paths inside this directory.
+    pub backup_dir: PathBuf,
+    /// Mempool admission limits
+    pub mempool: MempoolConfig,
+    /// Record an execution trace for each transaction executed by this node. Traces are returned by
+    /// get_transaction_result and increase the size of stored transaction results.
+    pub execution_traces: bool,
 }
 
 impl ValidatorNodeConfig {
@@ -111,6 +140,10 @@ impl ValidatorNodeConfig {
         self.data_dir.join("state.db")
     }
 
+    pub fn state_lmdb_path(&self) -> PathBuf {
+        self.data_dir.join("state_lmdb")
+    }
+
     pub fn set_base_path<P: AsRef<Path>>(&mut self, base_path: P) {
         if !self.shard_key_file.is_absolute() {
             self.shard_key_file = base_path.as_ref().join(&self.shard_key_file);
@@ -124,6 +157,9 @@ impl ValidatorNodeConfig {
         if !self.data_dir.is_absolute() {
             self.data_dir = base_path.as_ref().join(&self.data_dir);
         }
+        if !self.backup_dir.is_absolute() {
+            self.backup_dir = base_path.as_ref().join(&self.backup_dir);
+        }
         self.p2p.set_base_path(base_path);
     }
 }
@@ -151,12 +187,22 @@ impl Default for ValidatorNodeConfig {
             json_rpc_address: Some("127.0.0.1:18200".parse().unwrap()),
             ui_connect_address: None,
             http_ui_address: Some("127.0.0.1:5001".parse().unwrap()),
+            metrics_address: None,
             auto_register: true,
             templates: TemplateConfig::default(),
             no_fees: false,
             // Burn your fees
             fee_claim_public_key: RistrettoPublicKey::default(),
             dont_create_id: false,
+            leader_timeout: Duration::from_secs(2),
+            max_leader_timeout_delta: Duration::from_secs(300),
+            max_block_transactions_per_signer: 100,
+            state_store_backend: StateStoreBackend::default(),
+            state_pruning: StatePruningConfig::default(),
+            state_sync_mode: StateSyncMode::default(),
+            backup_dir: PathBuf::from("data/backups"),
+            mempool: MempoolConfig::default(),
+            execution_traces: false,
         }
     }
 }
@@ -1,7 +1,8 @@
 //   Copyright 2023 The Tari Project
 //   SPDX-License-Identifier: BSD-3-Clause
 
-use tari_consensus::hotstuff::{ConsensusCurrentState, HotstuffEvent};
+use tari_common_types::types::PublicKey;
+use tari_consensus::hotstuff::{ConsensusCurrentState, HotstuffEvent, ParticipationTracker};
 use tokio::sync::{broadcast, watch};
 
 use crate::event_subscription::EventSubscription;
@@ -10,16 +11,19 @@ use crate::event_subscription::EventSubscription;
 pub struct ConsensusHandle {
     rx_current_state: watch::Receiver<ConsensusCurrentState>,
     events_subscription: EventSubscription<HotstuffEvent>,
+    participation: ParticipationTracker<PublicKey>,
 }
 
 impl ConsensusHandle {
     pub(super) fn new(
         rx_current_state: watch::Receiver<ConsensusCurrentState>,
         events_subscription: EventSubscription<HotstuffEvent>,
+        participation: ParticipationTracker<PublicKey>,
     ) -> Self {
         Self {
             rx_current_state,
             events_subscription,
+            participation,
         }
     }
 
@@ -30,4 +34,13 @@ impl ConsensusHandle {
     pub fn get_current_state(&self) -> ConsensusCurrentState {
         *self.rx_current_state.borrow()
     }
+
+    pub fn subscribe_to_current_state(&self) -> watch::Receiver<ConsensusCurrentState> {
+        self.rx_current_state.clone()
+    }
+
+    /// The participation of committee members in consensus, as seen by this node
+    pub fn participation(&self) -> &ParticipationTracker<PublicKey> {
+        &self.participation
+    }
 }
@@ -5,9 +5,9 @@ use std::sync::Arc;
 
 use tari_common_types::types::PublicKey;
 use tari_comms::{types::CommsPublicKey, NodeIdentity};
-use tari_comms_rpc_state_sync::CommsRpcStateSyncManager;
+use tari_comms_rpc_state_sync::{CommsRpcStateSyncManager, StateSyncMode};
 use tari_consensus::{
-    hotstuff::{ConsensusWorker, ConsensusWorkerContext, HotstuffWorker},
+    hotstuff::{ConsensusConfig, ConsensusWorker, ConsensusWorkerContext, HotstuffWorker},
     messages::HotstuffMessage,
 };
 use tari_dan_common_types::committee::Committee;
@@ -15,7 +15,7 @@ use tari_dan_p2p::{Message, OutboundService};
 use tari_dan_storage::consensus_models::TransactionPool;
 use tari_epoch_manager::base_layer::EpochManagerHandle;
 use tari_shutdown::ShutdownSignal;
-use tari_state_store_sqlite::SqliteStateStore;
+use tari_state_store_backend::AnyStateStore;
 use tari_transaction::{Transaction, TransactionId};
 use tari_validator_node_rpc::client::TariCommsValidatorNodeClientFactory;
 use tokio::{
@@ -41,15 +41,18 @@ mod spec;
 mod state_manager;
 
 pub use handle::*;
+pub use state_manager::TariStateManager;
 
 pub async fn spawn(
-    store: SqliteStateStore<PublicKey>,
+    config: ConsensusConfig,
+    store: AnyStateStore<PublicKey>,
     node_identity: Arc<NodeIdentity>,
     epoch_manager: EpochManagerHandle,
     rx_new_transactions: mpsc::Receiver<TransactionId>,
     rx_hs_message: mpsc::Receiver<(CommsPublicKey, HotstuffMessage<PublicKey>)>,
     outbound_messaging: OutboundMessaging,
     client_factory: TariCommsValidatorNodeClientFactory,
+    state_sync_mode: StateSyncMode,
     shutdown_signal: ShutdownSignal,
 ) -> (
     JoinHandle<Result<(), anyhow::Error>>,
@@ -68,6 +71,7 @@ pub async fn spawn(
     let (tx_hotstuff_events, _) = broadcast::channel(100);
 
     let hotstuff_worker = HotstuffWorker::<TariConsensusSpec>::new(
+        config,
         validator_addr,
         rx_new_transactions,
         rx_hs_message,
@@ -84,11 +88,17 @@ pub async fn spawn(
         shutdown_signal.clone(),
     );
 
+    let participation = hotstuff_worker.participation_tracker();
+
+    let state_sync = CommsRpcStateSyncManager::new(epoch_manager.clone(), store, client_factory, state_sync_mode);
+    // Substates for a changed shard range are fetched as soon as the epoch changes rather than on the next sync
+    tokio::spawn(state_sync.clone().run_shard_range_handoff_on_epoch_change());
+
     let (tx_current_state, rx_current_state) = watch::channel(Default::default());
     let context = ConsensusWorkerContext {
-        epoch_manager: epoch_manager.clone(),
+        epoch_manager,
         hotstuff: hotstuff_worker,
-        state_sync: CommsRpcStateSyncManager::new(epoch_manager, store, client_factory),
+        state_sync,
         tx_current_state,
     };
 
@@ -103,7 +113,11 @@ pub async fn spawn(
 
     (
         handle,
-        ConsensusHandle::new(rx_current_state, EventSubscription::new(tx_hotstuff_events)),
+        ConsensusHandle::new(
+            rx_current_state,
+            EventSubscription::new(tx_hotstuff_events),
+            participation,
+        ),
         rx_mempool,
     )
 }
@@ -28,6 +28,10 @@ impl ValidatorSignatureService<PublicKey> for TariSignatureService {
     fn public_key(&self) -> &PublicKey {
         self.node_identity.public_key()
     }
+
+    fn verify_signature<M: AsRef<[u8]>>(&self, signature: &ValidatorSignature<PublicKey>, message: M) -> bool {
+        signature.verify(message)
+    }
 }
 
 impl VoteSignatureService<PublicKey> for TariSignatureService {
@@ -5,7 +5,7 @@ use tari_comms::types::CommsPublicKey;
 use tari_comms_rpc_state_sync::CommsRpcStateSyncManager;
 use tari_consensus::traits::ConsensusSpec;
 use tari_epoch_manager::base_layer::EpochManagerHandle;
-use tari_state_store_sqlite::SqliteStateStore;
+use tari_state_store_backend::AnyStateStore;
 
 use crate::consensus::{
     leader_selection::RoundRobinLeaderStrategy,
@@ -21,7 +21,7 @@ impl ConsensusSpec for TariConsensusSpec {
     type EpochManager = EpochManagerHandle;
     type LeaderStrategy = RoundRobinLeaderStrategy;
     type StateManager = TariStateManager;
-    type StateStore = SqliteStateStore<Self::Addr>;
+    type StateStore = AnyStateStore<Self::Addr>;
     type SyncManager = CommsRpcStateSyncManager<Self::EpochManager, Self::StateStore>;
     type VoteSignatureService = TariSignatureService;
 }
@@ -0,0 +1,216 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use std::sync::Arc;
+
+use log::*;
+use tari_common::configuration::Network;
+use tari_common_types::types::{FixedHash, PublicKey};
+use tari_comms::NodeIdentity;
+use tari_dan_app_utilities::template_manager::interface::{
+    DevTemplateRegistration,
+    TemplateManagerError,
+    TemplateManagerHandle,
+};
+use tari_engine_types::{calculate_dev_template_address, calculate_template_binary_hash};
+use tari_epoch_manager::{base_layer::EpochManagerHandle, EpochManagerError, EpochManagerReader};
+use tari_template_lib::models::TemplateAddress;
+use tari_validator_node_rpc::client::{
+    SignedDevTemplate,
+    TariCommsValidatorNodeClientFactory,
+    ValidatorNodeClientFactory,
+    ValidatorNodeRpcClient,
+};
+
+use crate::template_registration_signing::{sign_dev_template_registration, verify_dev_template_registration};
+
+const LOG_TARGET: &str = "tari::dan::validator_node::dev_templates";
+
+#[derive(Debug, thiserror::Error)]
+pub enum DevTemplateError {
+    #[error("Development templates can only be registered on a development network (current network: {network})")]
+    NotDevNetwork { network: Network },
+    #[error("Invalid template registration signature from {signer}")]
+    InvalidSignature { signer: PublicKey },
+    #[error("Template signer {signer} is not a member of the local committee")]
+    SignerNotInCommittee { signer: PublicKey },
+    #[error("Template manager error: {0}")]
+    TemplateManagerError(#[from] TemplateManagerError),
+    #[error("Epoch manager error: {0}")]
+    EpochManagerError(#[from] EpochManagerError),
+}
+
+#[derive(Debug, Clone)]
+pub struct RegisteredDevTemplate {
+    pub template_address: TemplateAddress,
+    pub binary_sha: FixedHash,
+    pub num_peers_notified: usize,
+}
+
+/// Registers WASM templates directly with this node, without a base layer template registration. The template
+/// address is derived from the hash of the binary, so every node that receives the same binary derives the same
+/// address. Templates are signed by this node and propagated to the rest of the local committee, which only accept
+/// templates signed by a committee member. This is only enabled on the local development network.
+#[derive(Clone)]
+pub struct DevTemplateRegistrar {
+    network: Network,
+    node_identity: Arc<NodeIdentity>,
+    template_manager: TemplateManagerHandle,
+    epoch_manager: EpochManagerHandle,
+    client_factory: TariCommsValidatorNodeClientFactory,
+}
+
+impl DevTemplateRegistrar {
+    pub fn new(
+        network: Network,
+        node_identity: Arc<NodeIdentity>,
+        template_manager: TemplateManagerHandle,
+        epoch_manager: EpochManagerHandle,
+        client_factory: TariCommsValidatorNodeClientFactory,
+    ) -> Self {
+        Self {
+            network,
+            node_identity,
+            template_manager,
+            epoch_manager,
+            client_factory,
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        is_dev_network(self.network)
+    }
+
+    /// Registers the template with this node and propagates it to the other members of the local committee
+    pub async fn register(
+        &self,
+        template_name: String,
+        binary: Vec<u8>,
+    ) -> Result<RegisteredDevTemplate, DevTemplateError> {
+        self.check_enabled()?;
+
+        let binary_sha = calculate_template_binary_hash(&binary);
+        let template_address = calculate_dev_template_address(&binary_sha);
+        self.template_manager
+            .add_dev_template(DevTemplateRegistration {
+                template_name: template_name.clone(),
+                template_address,
+                binary: binary.clone(),
+            })
+            .await?;
+
+        let signature =
+            sign_dev_template_registration(self.node_identity.secret_key(), binary_sha.as_slice(), &template_name);
+        let signed = SignedDevTemplate {
+            template_name,
+            binary,
+            signer_public_key: self.node_identity.public_key().clone(),
+            signature,
+        };
+        let num_peers_notified = self.propagate(signed).await?;
+        info!(
+            target: LOG_TARGET,
+            "🛠️ Registered development template {} and notified {} peer(s)", template_address, num_peers_notified
+        );
+
+        Ok(RegisteredDevTemplate {
+            template_address,
+            binary_sha,
+            num_peers_notified,
+        })
+    }
+
+    /// Accepts a template that was registered with another member of the local committee
+    pub async fn accept(&self, template: SignedDevTemplate) -> Result<TemplateAddress, DevTemplateError> {
+        self.check_enabled()?;
+
+        let binary_sha = calculate_template_binary_hash(&template.binary);
+        if !verify_dev_template_registration(
+            &template.signer_public_key,
+            &template.signature,
+            binary_sha.as_slice(),
+            &template.template_name,
+        ) {
+            return Err(DevTemplateError::InvalidSignature {
+                signer: template.signer_public_key,
+            });
+        }
+
+        let epoch = self.epoch_manager.current_epoch().await?;
+        let committee = self.epoch_manager.get_local_committee(epoch).await?;
+        if !committee.contains(&template.signer_public_key) {
+            return Err(DevTemplateError::SignerNotInCommittee {
+                signer: template.signer_public_key,
+            });
+        }
+
+        let template_address = calculate_dev_template_address(&binary_sha);
+        self.template_manager
+            .add_dev_template(DevTemplateRegistration {
+                template_name: template.template_name,
+                template_address,
+                binary: template.binary,
+            })
+            .await?;
+
+        Ok(template_address)
+    }
+
+    async fn propagate(&self, template: SignedDevTemplate) -> Result<usize, DevTemplateError> {
+        let epoch = self.epoch_manager.current_epoch().await?;
+        if !self.epoch_manager.is_this_validator_registered_for_epoch(epoch).await? {
+            warn!(
+                target: LOG_TARGET,
+                "This validator is not registered for epoch {}. The template will not be propagated.", epoch
+            );
+            return Ok(0);
+        }
+
+        let committee = self.epoch_manager.get_local_committee(epoch).await?;
+        let mut num_notified = 0;
+        for member in committee.members() {
+            if member == self.node_identity.public_key() {
+                continue;
+            }
+            let mut client = self.client_factory.create_client(member);
+            match client.submit_dev_template(template.clone()).await {
+                Ok(_) => num_notified += 1,
+                Err(err) => {
+                    warn!(target: LOG_TARGET, "Failed to submit development template to {}: {}", member, err);
+                },
+            }
+        }
+
+        Ok(num_notified)
+    }
+
+    fn check_enabled(&self) -> Result<(), DevTemplateError> {
+        if !self.is_enabled() {
+            return Err(DevTemplateError::NotDevNetwork { network: self.network });
+        }
+        Ok(())
+    }
+}
+
+fn is_dev_network(network: Network) -> bool {
+    network == Network::LocalNet
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_is_only_enabled_on_the_local_network() {
+        assert!(is_dev_network(Network::LocalNet));
+        for network in [
+            Network::MainNet,
+            Network::StageNet,
+            Network::NextNet,
+            Network::Igor,
+            Network::Esmeralda,
+        ] {
+            assert!(!is_dev_network(network), "enabled on {}", network);
+        }
+    }
+}
@@ -20,26 +20,34 @@
 //  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 //  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
+use std::sync::Arc;
+
 use log::info;
 use tari_common_types::types::PublicKey;
 use tari_comms::protocol::rpc::RpcStatus;
 use tari_dan_app_utilities::{
     template_manager::implementation::TemplateManager,
-    transaction_executor::{TariDanTransactionProcessor, TransactionExecutor, TransactionProcessorError},
+    transaction_executor::{
+        TariDanTransactionProcessor,
+        TransactionExecutor,
+        TransactionProcessorError,
+        ViewCallError,
+    },
 };
 use tari_dan_engine::{
     bootstrap_state,
     state_store::{memory::MemoryStateStore, AtomicDb, StateStoreError, StateWriter},
 };
 use tari_dan_storage::StorageError;
-use tari_engine_types::commit_result::ExecuteResult;
+use tari_engine_types::{commit_result::ExecuteResult, instruction_result::InstructionResult};
 use tari_epoch_manager::{base_layer::EpochManagerHandle, EpochManagerError, EpochManagerReader};
-use tari_state_store_sqlite::SqliteStateStore;
+use tari_state_store_backend::AnyStateStore;
+use tari_template_lib::{args::Arg, models::ComponentAddress};
 use tari_transaction::Transaction;
 use tari_validator_node_client::ValidatorNodeClientError;
 use tari_validator_node_rpc::client::TariCommsValidatorNodeClientFactory;
 use thiserror::Error;
-use tokio::task;
+use tokio::{sync::Semaphore, task};
 
 use crate::{
     p2p::services::mempool::SubstateResolver,
@@ -49,6 +57,9 @@ use crate::{
 
 const LOG_TARGET: &str = "tari::dan::validator_node::dry_run_transaction_processor";
 
+/// The maximum number of view calls that are executed at the same time. Further calls are refused until one completes.
+const MAX_CONCURRENT_VIEW_CALLS: usize = 4;
+
 #[derive(Error, Debug)]
 pub enum DryRunTransactionProcessorError {
     #[error("PayloadProcessor error: {0}")]
@@ -67,14 +78,19 @@ pub enum DryRunTransactionProcessorError {
     SubstateResoverError(#[from] SubstateResolverError),
     #[error("Virtual substate error: {0}")]
     VirtualSubstateError(#[from] VirtualSubstateError),
+    #[error("View call error: {0}")]
+    ViewCall(#[from] ViewCallError),
+    #[error("Too many view calls in progress, try again later")]
+    TooManyViewCalls,
 }
 
 #[derive(Clone, Debug)]
 pub struct DryRunTransactionProcessor {
     substate_resolver:
-        TariSubstateResolver<SqliteStateStore<PublicKey>, EpochManagerHandle, TariCommsValidatorNodeClientFactory>,
+        TariSubstateResolver<AnyStateStore<PublicKey>, EpochManagerHandle, TariCommsValidatorNodeClientFactory>,
     epoch_manager: EpochManagerHandle,
     payload_processor: TariDanTransactionProcessor<TemplateManager>,
+    view_calls: Arc<Semaphore>,
 }
 
 impl DryRunTransactionProcessor {
@@ -82,7 +98,7 @@ impl DryRunTransactionProcessor {
         epoch_manager: EpochManagerHandle,
         payload_processor: TariDanTransactionProcessor<TemplateManager>,
         substate_resolver: TariSubstateResolver<
-            SqliteStateStore<PublicKey>,
+            AnyStateStore<PublicKey>,
             EpochManagerHandle,
             TariCommsValidatorNodeClientFactory,
         >,
@@ -91,6 +107,7 @@ impl DryRunTransactionProcessor {
             substate_resolver,
             epoch_manager,
             payload_processor,
+            view_calls: Arc::new(Semaphore::new(MAX_CONCURRENT_VIEW_CALLS)),
         }
     }
 
@@ -99,12 +116,7 @@ impl DryRunTransactionProcessor {
         transaction: Transaction,
     ) -> Result<ExecuteResult, DryRunTransactionProcessorError> {
         // Resolve all local and foreign substates
-        let temp_state_store = MemoryStateStore::new();
-        {
-            let mut tx = temp_state_store.write_access().map_err(StateStoreError::Custom)?;
-            bootstrap_state(&mut tx)?;
-            tx.commit()?;
-        }
+        let temp_state_store = Self::new_state_store()?;
 
         let current_epoch = self.epoch_manager.current_epoch().await?;
         let virtual_substates = self
@@ -127,4 +139,48 @@ impl DryRunTransactionProcessor {
 
         Ok(result)
     }
+
+    /// Calls a non-mutating method on a component against the latest committed state. Nothing is submitted to
+    /// consensus and the call fails if it attempts to change any state.
+    pub async fn process_view_call(
+        &self,
+        component_address: ComponentAddress,
+        method: String,
+        args: Vec<Arg>,
+    ) -> Result<InstructionResult, DryRunTransactionProcessorError> {
+        let _permit = self
+            .view_calls
+            .try_acquire()
+            .map_err(|_| DryRunTransactionProcessorError::TooManyViewCalls)?;
+
+        let temp_state_store = Self::new_state_store()?;
+        self.substate_resolver
+            .resolve_for_view_call(component_address.into(), &temp_state_store)
+            .await?;
+
+        let current_epoch = self.epoch_manager.current_epoch().await?;
+        let virtual_substates = self.substate_resolver.current_epoch_virtual_substates(current_epoch);
+
+        let result = task::block_in_place(|| {
+            self.payload_processor.execute_view_call(
+                component_address,
+                method,
+                args,
+                temp_state_store,
+                virtual_substates,
+            )
+        })?;
+
+        Ok(result)
+    }
+
+    fn new_state_store() -> Result<MemoryStateStore, DryRunTransactionProcessorError> {
+        let state_store = MemoryStateStore::new();
+        {
+            let mut tx = state_store.write_access().map_err(StateStoreError::Custom)?;
+            bootstrap_state(&mut tx)?;
+            tx.commit()?;
+        }
+        Ok(state_store)
+    }
 }
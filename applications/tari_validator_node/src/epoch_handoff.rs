@@ -0,0 +1,138 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use log::*;
+use tari_common_types::types::PublicKey;
+use tari_dan_common_types::{committee::Committee, Epoch};
+use tari_epoch_manager::{base_layer::EpochManagerHandle, EpochManagerError, EpochManagerEvent, EpochManagerReader};
+use tari_shutdown::ShutdownSignal;
+use tari_transaction::TransactionId;
+use tari_validator_node_rpc::client::{
+    EpochHandoff,
+    TariCommsValidatorNodeClientFactory,
+    ValidatorNodeClientFactory,
+    ValidatorNodeRpcClient,
+};
+use tokio::{task, task::JoinHandle};
+
+use crate::p2p::services::mempool::MempoolHandle;
+
+const LOG_TARGET: &str = "tari::dan::validator_node::epoch_handoff";
+
+#[derive(Debug, thiserror::Error)]
+pub enum EpochHandoffError {
+    #[error("Epoch manager error: {0}")]
+    EpochManagerError(#[from] EpochManagerError),
+    #[error("No member of the outgoing committee could provide a handoff for epoch {epoch}")]
+    NoHandoffAvailable { epoch: Epoch },
+}
+
+/// Picks up the pending transactions from the outgoing committee when this node joins a committee at the start of an
+/// epoch and submits them to the local mempool. The outgoing committee stops preparing new transactions near the end
+/// of the epoch (see `EpochManagerEvent::EpochEnding`) and aborts any prepared transactions that are still pending at
+/// the epoch boundary, so no substate locks are handed over.
+pub fn spawn(
+    epoch_manager: EpochManagerHandle,
+    client_factory: TariCommsValidatorNodeClientFactory,
+    mempool: MempoolHandle,
+    shutdown: ShutdownSignal,
+) -> JoinHandle<Result<(), anyhow::Error>> {
+    task::spawn(async move {
+        start(epoch_manager, client_factory, mempool, shutdown).await?;
+        Ok(())
+    })
+}
+
+async fn start(
+    epoch_manager: EpochManagerHandle,
+    client_factory: TariCommsValidatorNodeClientFactory,
+    mempool: MempoolHandle,
+    mut shutdown: ShutdownSignal,
+) -> Result<(), EpochHandoffError> {
+    let mut rx = epoch_manager.subscribe().await?;
+
+    loop {
+        tokio::select! {
+            Ok(event) = rx.recv() => {
+                if let EpochManagerEvent::EpochChanged(epoch) = event {
+                    let result = handle_epoch_changed(&epoch_manager, &client_factory, &mempool, epoch).await;
+                    if let Err(err) = result {
+                        error!(target: LOG_TARGET, "Epoch handoff failed for epoch {}: {}", epoch, err);
+                    }
+                }
+            },
+            _ = shutdown.wait() => break
+        }
+    }
+
+    Ok(())
+}
+
+async fn handle_epoch_changed(
+    epoch_manager: &EpochManagerHandle,
+    client_factory: &TariCommsValidatorNodeClientFactory,
+    mempool: &MempoolHandle,
+    epoch: Epoch,
+) -> Result<(), EpochHandoffError> {
+    let Some(prev_epoch) = epoch.checked_sub(Epoch(1)).filter(|e| !e.is_zero()) else {
+        return Ok(());
+    };
+    if !epoch_manager.is_this_validator_registered_for_epoch(epoch).await? {
+        return Ok(());
+    }
+
+    let validator = epoch_manager.get_our_validator_node(epoch).await?;
+    let outgoing_committee = epoch_manager.get_committee(prev_epoch, validator.shard_key).await?;
+    if outgoing_committee.contains(&validator.address) {
+        debug!(target: LOG_TARGET, "This validator was in the committee for epoch {}", prev_epoch);
+        return Ok(());
+    }
+
+    let mut after = None;
+    let mut num_transactions = 0;
+    loop {
+        let handoff = fetch_handoff_page(client_factory, &outgoing_committee, &validator.address, epoch, after).await?;
+        num_transactions += handoff.pending_transactions.len();
+        for transaction in handoff.pending_transactions {
+            let id = *transaction.id();
+            if let Err(err) = mempool.submit_transaction(transaction).await {
+                warn!(target: LOG_TARGET, "Failed to submit handed off transaction {}: {}", id, err);
+            }
+        }
+        after = handoff.next;
+        if after.is_none() {
+            break;
+        }
+    }
+
+    info!(
+        target: LOG_TARGET,
+        "🤝 Received handoff for epoch {} with {} pending transaction(s)", epoch, num_transactions
+    );
+
+    Ok(())
+}
+
+/// Fetches a single page of the handoff from the first member of the outgoing committee that responds.
+async fn fetch_handoff_page(
+    client_factory: &TariCommsValidatorNodeClientFactory,
+    outgoing_committee: &Committee<PublicKey>,
+    local_address: &PublicKey,
+    epoch: Epoch,
+    after: Option<TransactionId>,
+) -> Result<EpochHandoff, EpochHandoffError> {
+    for member in outgoing_committee.shuffled() {
+        if member == local_address {
+            continue;
+        }
+        let mut client = client_factory.create_client(member);
+        match client.get_epoch_handoff(epoch, after).await {
+            Ok(handoff) => return Ok(handoff),
+            Err(err) => {
+                warn!(target: LOG_TARGET, "Failed to get epoch handoff from {}: {}", member, err);
+            },
+        }
+    }
+
+    Err(EpochHandoffError::NoHandoffAvailable { epoch })
+}
@@ -20,7 +20,10 @@
 //   WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 //   USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use std::sync::Arc;
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 
 use axum_jrpc::{
     error::{JsonRpcError, JsonRpcErrorReason},
@@ -44,60 +47,86 @@ use tari_comms::{
 use tari_comms_logging::SqliteMessageLog;
 use tari_crypto::tari_utilities::hex::Hex;
 use tari_dan_app_utilities::template_manager::interface::TemplateManagerHandle;
-use tari_dan_common_types::{optional::Optional, ShardId};
+use tari_dan_common_types::{
+    optional::{IsNotFoundError, Optional},
+    ShardId,
+};
 use tari_dan_storage::{
     consensus_models::{Block, ExecutedTransaction, LeafBlock, QuorumDecision, SubstateRecord, TransactionRecord},
     Ordering,
     StateStore,
+    StorageError,
 };
 use tari_epoch_manager::{base_layer::EpochManagerHandle, EpochManagerReader};
-use tari_state_store_sqlite::SqliteStateStore;
-use tari_validator_node_client::types::{
-    AddPeerRequest,
-    AddPeerResponse,
-    CommitteeShardInfo,
-    DryRunTransactionFinalizeResult,
-    GetBlockRequest,
-    GetBlockResponse,
-    GetBlocksCountResponse,
-    GetCommitteeRequest,
-    GetEpochManagerStatsResponse,
-    GetIdentityResponse,
-    GetNetworkCommitteeResponse,
-    GetRecentTransactionsResponse,
-    GetShardKey,
-    GetStateRequest,
-    GetStateResponse,
-    GetSubstateRequest,
-    GetSubstateResponse,
-    GetSubstatesByTransactionRequest,
-    GetSubstatesByTransactionResponse,
-    GetTemplateRequest,
-    GetTemplateResponse,
-    GetTemplatesRequest,
-    GetTemplatesResponse,
-    GetTransactionRequest,
-    GetTransactionResponse,
-    GetTransactionResultRequest,
-    GetTransactionResultResponse,
-    GetValidatorFeesRequest,
-    GetValidatorFeesResponse,
-    ListBlocksRequest,
-    ListBlocksResponse,
-    RegisterValidatorNodeRequest,
-    RegisterValidatorNodeResponse,
-    SubmitTransactionRequest,
-    SubmitTransactionResponse,
-    SubstateStatus,
-    TemplateMetadata,
-    TemplateRegistrationRequest,
-    TemplateRegistrationResponse,
+use tari_state_store_backend::AnyStateStore;
+use tari_validator_node_client::{
+    backup,
+    types::{
+        AddPeerRequest,
+        AddPeerResponse,
+        CallViewMethodRequest,
+        CallViewMethodResponse,
+        CommitteeShardInfo,
+        CreateBackupRequest,
+        CreateBackupResponse,
+        DryRunTransactionFinalizeResult,
+        GetBlockRequest,
+        GetBlockResponse,
+        GetBlocksCountResponse,
+        GetCommitteeHealthResponse,
+        GetCommitteeRequest,
+        GetEpochManagerStatsResponse,
+        GetIdentityResponse,
+        GetNetworkCommitteeResponse,
+        GetRecentTransactionsResponse,
+        GetShardKey,
+        GetStateRequest,
+        GetStateResponse,
+        GetSubstateAtRequest,
+        GetSubstateAtResponse,
+        GetSubstateHistoryRequest,
+        GetSubstateHistoryResponse,
+        GetSubstateRequest,
+        GetSubstateResponse,
+        GetSubstatesByTransactionRequest,
+        GetSubstatesByTransactionResponse,
+        GetTemplateRequest,
+        GetTemplateResponse,
+        GetTemplatesRequest,
+        GetTemplatesResponse,
+        GetTransactionRequest,
+        GetTransactionResponse,
+        GetTransactionResultRequest,
+        GetTransactionResultResponse,
+        GetValidatorFeesRequest,
+        GetValidatorFeesResponse,
+        ListBlocksRequest,
+        ListBlocksResponse,
+        RegisterDevTemplateRequest,
+        RegisterDevTemplateResponse,
+        RegisterValidatorNodeRequest,
+        RegisterValidatorNodeResponse,
+        SubmitTransactionRequest,
+        SubmitTransactionResponse,
+        SubstateStatus,
+        TemplateMetadata,
+        TemplateRegistrationRequest,
+        TemplateRegistrationResponse,
+        VerifyBackupRequest,
+        VerifyBackupResponse,
+    },
 };
+use tari_validator_node_rpc::client::TariCommsValidatorNodeClientFactory;
+use tokio::task;
 
 use crate::{
-    dry_run_transaction_processor::DryRunTransactionProcessor,
+    backup::{create_backup, resolve_backup_path},
+    committee_health::CommitteeHealthReporter,
+    consensus::ConsensusHandle,
+    dev_templates::{DevTemplateError, DevTemplateRegistrar},
+    dry_run_transaction_processor::{DryRunTransactionProcessor, DryRunTransactionProcessorError},
     grpc::base_layer_wallet::GrpcWalletClient,
-    json_rpc::jrpc_errors::{internal_error, not_found},
+    json_rpc::jrpc_errors::{history_unavailable, internal_error, invalid_params, not_found},
     p2p::services::mempool::MempoolHandle,
     registration,
     Services,
@@ -114,8 +143,11 @@ pub struct JsonRpcHandlers {
     epoch_manager: EpochManagerHandle,
     comms: CommsNode,
     base_node_client: GrpcBaseNodeClient,
-    state_store: SqliteStateStore<PublicKey>,
+    state_store: AnyStateStore<PublicKey>,
     dry_run_transaction_processor: DryRunTransactionProcessor,
+    consensus_handle: ConsensusHandle,
+    validator_node_client_factory: TariCommsValidatorNodeClientFactory,
+    dev_template_registrar: DevTemplateRegistrar,
     config: ValidatorNodeConfig,
 }
 
@@ -137,6 +169,9 @@ impl JsonRpcHandlers {
             base_node_client,
             state_store: services.state_store.clone(),
             dry_run_transaction_processor: services.dry_run_transaction_processor.clone(),
+            consensus_handle: services.consensus_handle.clone(),
+            validator_node_client_factory: services.validator_node_client_factory.clone(),
+            dev_template_registrar: services.dev_template_registrar.clone(),
         }
     }
 
@@ -220,6 +255,30 @@ impl JsonRpcHandlers {
         }
     }
 
+    pub async fn call_view_method(&self, value: JsonRpcExtractor) -> JrpcResult {
+        let answer_id = value.get_answer_id();
+        let CallViewMethodRequest {
+            component_address,
+            method,
+            args,
+        } = value.parse_params()?;
+
+        let result = self
+            .dry_run_transaction_processor
+            .process_view_call(component_address, method, args)
+            .await
+            .map_err(|e| match e {
+                DryRunTransactionProcessorError::ViewCall(e) => invalid_params(answer_id)(e),
+                e => internal_error(answer_id)(e),
+            })?;
+        let json_result = json::to_value(result.indexed.value()).map_err(internal_error(answer_id))?;
+
+        Ok(JsonRpcResponse::success(answer_id, CallViewMethodResponse {
+            result,
+            json_result,
+        }))
+    }
+
     pub async fn get_state(&self, value: JsonRpcExtractor) -> JrpcResult {
         let answer_id = value.get_answer_id();
         let request: GetStateRequest = value.parse_params()?;
@@ -364,6 +423,76 @@ impl JsonRpcHandlers {
         }
     }
 
+    pub async fn get_substate_at(&self, value: JsonRpcExtractor) -> JrpcResult {
+        let answer_id = value.get_answer_id();
+        let data: GetSubstateAtRequest = value.parse_params()?;
+        let substate = match self
+            .state_store
+            .with_read_tx(|tx| SubstateRecord::get_at(tx, &data.address, data.at))
+        {
+            Ok(substate) => substate,
+            Err(err) if err.is_not_found_error() => return Err(not_found(answer_id, err.to_string())),
+            Err(err @ StorageError::HistoryUnavailable { .. }) => {
+                return Err(history_unavailable(answer_id, err.to_string()));
+            },
+            Err(err) => return Err(internal_error(answer_id)(err)),
+        };
+
+        Ok(JsonRpcResponse::success(answer_id, GetSubstateAtResponse { substate }))
+    }
+
+    pub async fn get_substate_history(&self, value: JsonRpcExtractor) -> JrpcResult {
+        let answer_id = value.get_answer_id();
+        let data: GetSubstateHistoryRequest = value.parse_params()?;
+        let history = self
+            .state_store
+            .with_read_tx(|tx| SubstateRecord::get_history(tx, &data.address))
+            .map_err(internal_error(answer_id))?;
+
+        Ok(JsonRpcResponse::success(answer_id, GetSubstateHistoryResponse {
+            versions: history.versions,
+            pruned_before_epoch: history.pruned_before_epoch,
+        }))
+    }
+
+    pub async fn create_backup(&self, value: JsonRpcExtractor) -> JrpcResult {
+        let answer_id = value.get_answer_id();
+        let data: CreateBackupRequest = value.parse_params()?;
+        let output_dir = match data.output_dir {
+            Some(output_dir) => self.resolve_backup_path(answer_id, &output_dir)?,
+            None => self.config.backup_dir.clone(),
+        };
+        let config = self.config.clone();
+        let state_store = self.state_store.clone();
+        let (path, manifest) = task::spawn_blocking(move || create_backup(&config, &state_store, &output_dir))
+            .await
+            .map_err(internal_error(answer_id))?
+            .map_err(internal_error(answer_id))?;
+
+        Ok(JsonRpcResponse::success(answer_id, CreateBackupResponse {
+            path,
+            manifest,
+        }))
+    }
+
+    pub async fn verify_backup(&self, value: JsonRpcExtractor) -> JrpcResult {
+        let answer_id = value.get_answer_id();
+        let data: VerifyBackupRequest = value.parse_params()?;
+        let path = self.resolve_backup_path(answer_id, &data.path)?;
+        let manifest = task::spawn_blocking(move || backup::verify_backup(&path))
+            .await
+            .map_err(internal_error(answer_id))?
+            .map_err(invalid_params(answer_id))?;
+
+        Ok(JsonRpcResponse::success(answer_id, VerifyBackupResponse { manifest }))
+    }
+
+    /// Paths given to the backup methods are confined to the configured backup directory, so that JSON-RPC clients
+    /// cannot read or write anywhere else on the host
+    fn resolve_backup_path(&self, answer_id: i64, path: &Path) -> Result<PathBuf, JsonRpcResponse> {
+        resolve_backup_path(&self.config.backup_dir, path).map_err(invalid_params(answer_id))
+    }
+
     pub async fn get_substates_created_by_transaction(&self, value: JsonRpcExtractor) -> JrpcResult {
         let answer_id = value.get_answer_id();
         let data: GetSubstatesByTransactionRequest = value.parse_params()?;
@@ -475,6 +604,28 @@ impl JsonRpcHandlers {
         }))
     }
 
+    pub async fn register_dev_template(&self, value: JsonRpcExtractor) -> JrpcResult {
+        let answer_id = value.get_answer_id();
+        let RegisterDevTemplateRequest { template_name, binary } = value.parse_params()?;
+
+        let registered = self
+            .dev_template_registrar
+            .register(template_name, binary)
+            .await
+            .map_err(|e| match e {
+                DevTemplateError::NotDevNetwork { .. } | DevTemplateError::TemplateManagerError(_) => {
+                    invalid_params(answer_id)(e)
+                },
+                _ => internal_error(answer_id)(e),
+            })?;
+
+        Ok(JsonRpcResponse::success(answer_id, RegisterDevTemplateResponse {
+            template_address: registered.template_address,
+            binary_sha: registered.binary_sha.to_vec(),
+            num_peers_notified: registered.num_peers_notified,
+        }))
+    }
+
     pub async fn get_templates(&self, value: JsonRpcExtractor) -> JrpcResult {
         let answer_id = value.get_answer_id();
         let req: GetTemplatesRequest = value.parse_params()?;
@@ -627,6 +778,20 @@ impl JsonRpcHandlers {
         Ok(JsonRpcResponse::success(answer_id, response))
     }
 
+    pub async fn get_committee_health(&self, value: JsonRpcExtractor) -> JrpcResult {
+        let answer_id = value.get_answer_id();
+        let response: GetCommitteeHealthResponse = CommitteeHealthReporter::new(
+            &self.epoch_manager,
+            &self.consensus_handle,
+            &self.validator_node_client_factory,
+            &self.state_store,
+        )
+        .report()
+        .await
+        .map_err(internal_error(answer_id))?;
+        Ok(JsonRpcResponse::success(answer_id, response))
+    }
+
     pub async fn add_peer(&self, value: JsonRpcExtractor) -> JrpcResult {
         let answer_id = value.get_answer_id();
         let AddPeerRequest {
@@ -43,6 +43,19 @@ use axum_jrpc::{
 //     }
 // }
 
+pub fn invalid_params<T: Display>(answer_id: i64) -> impl Fn(T) -> JsonRpcResponse {
+    move |err| {
+        JsonRpcResponse::error(
+            answer_id,
+            JsonRpcError::new(
+                JsonRpcErrorReason::InvalidParams,
+                err.to_string(),
+                serde_json::Value::Null,
+            ),
+        )
+    }
+}
+
 pub fn internal_error<T: Display>(answer_id: i64) -> impl Fn(T) -> JsonRpcResponse {
     move |err| {
         let msg = if cfg!(debug_assertions) || option_env!("CI").is_some() {
@@ -68,3 +81,14 @@ pub fn not_found<T: Into<String>>(answer_id: i64, details: T) -> JsonRpcResponse
         ),
     )
 }
+
+pub fn history_unavailable<T: Into<String>>(answer_id: i64, details: T) -> JsonRpcResponse {
+    JsonRpcResponse::error(
+        answer_id,
+        JsonRpcError::new(
+            JsonRpcErrorReason::ApplicationError(410),
+            details.into(),
+            serde_json::Value::Null,
+        ),
+    )
+}
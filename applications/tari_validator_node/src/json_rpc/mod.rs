@@ -25,5 +25,7 @@ pub use handlers::JsonRpcHandlers;
 
 mod jrpc_errors;
 mod server;
-
 pub use server::spawn_json_rpc;
+
+mod subscriptions;
+pub use subscriptions::JsonRpcSubscriptions;
@@ -22,20 +22,30 @@
 
 use std::{net::SocketAddr, sync::Arc};
 
-use axum::{extract::Extension, routing::post, Router};
+use axum::{
+    extract::Extension,
+    routing::{get, post},
+    Router,
+};
 use axum_jrpc::{JrpcResult, JsonRpcAnswer, JsonRpcExtractor};
 use log::*;
 use tower_http::cors::CorsLayer;
 
-use super::handlers::JsonRpcHandlers;
+use super::{handlers::JsonRpcHandlers, subscriptions::ws_handler, JsonRpcSubscriptions};
 
 const LOG_TARGET: &str = "tari::validator_node::json_rpc";
 
-pub fn spawn_json_rpc(preferred_address: SocketAddr, handlers: JsonRpcHandlers) -> Result<SocketAddr, anyhow::Error> {
+pub fn spawn_json_rpc(
+    preferred_address: SocketAddr,
+    handlers: JsonRpcHandlers,
+    subscriptions: JsonRpcSubscriptions,
+) -> Result<SocketAddr, anyhow::Error> {
     let router = Router::new()
         .route("/", post(handler))
         .route("/json_rpc", post(handler))
+        .route("/ws", get(ws_handler))
         .layer(Extension(Arc::new(handlers)))
+        .layer(Extension(Arc::new(subscriptions)))
         .layer(CorsLayer::permissive());
 
     let server = axum::Server::try_bind(&preferred_address).or_else(|_| {
@@ -60,11 +70,14 @@ async fn handler(Extension(handlers): Extension<Arc<JsonRpcHandlers>>, value: Js
         // Transaction
         // "get_transaction_status" => handlers.get_transaction_status(value).await,
         "submit_transaction" => handlers.submit_transaction(value).await,
+        "call_view_method" => handlers.call_view_method(value).await,
         "get_recent_transactions" => handlers.get_recent_transactions(value).await,
         "get_transaction" => handlers.get_transaction(value).await,
         "get_transaction_result" => handlers.get_transaction_result(value).await,
         "get_state" => handlers.get_state(value).await,
         "get_substate" => handlers.get_substate(value).await,
+        "get_substate_at" => handlers.get_substate_at(value).await,
+        "get_substate_history" => handlers.get_substate_history(value).await,
         "get_substates_created_by_transaction" => handlers.get_substates_created_by_transaction(value).await,
         "get_substates_destroyed_by_transaction" => handlers.get_substates_destroyed_by_transaction(value).await,
         "list_blocks" => handlers.list_blocks(value).await,
@@ -75,11 +88,13 @@ async fn handler(Extension(handlers): Extension<Arc<JsonRpcHandlers>>, value: Js
         "get_template" => handlers.get_template(value).await,
         "get_templates" => handlers.get_templates(value).await,
         "register_template" => handlers.register_template(value).await,
+        "register_dev_template" => handlers.register_dev_template(value).await,
         // Validator Node
         "get_identity" => handlers.get_identity(value),
         "register_validator_node" => handlers.register_validator_node(value).await,
         "get_mempool_stats" => handlers.get_mempool_stats(value).await,
         "get_epoch_manager_stats" => handlers.get_epoch_manager_stats(value).await,
+        "get_committee_health" => handlers.get_committee_health(value).await,
         "get_shard_key" => handlers.get_shard_key(value).await,
         "get_committee" => handlers.get_committee(value).await,
         "get_all_vns" => handlers.get_all_vns(value).await,
@@ -89,6 +104,9 @@ async fn handler(Extension(handlers): Extension<Arc<JsonRpcHandlers>>, value: Js
         "add_peer" => handlers.add_peer(value).await,
         "get_comms_stats" => handlers.get_comms_stats(value).await,
         "get_connections" => handlers.get_connections(value).await,
+        // Backups
+        "create_backup" => handlers.create_backup(value).await,
+        "verify_backup" => handlers.verify_backup(value).await,
         // Debug
         "get_logged_messages" => handlers.get_logged_messages(value).await,
         method => Ok(value.method_not_found(method)),
@@ -0,0 +1,601 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+    time::Duration,
+};
+
+use axum::{
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        Extension,
+    },
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use log::*;
+use serde::Deserialize;
+use serde_json::{self as json, json};
+use tari_common_types::types::PublicKey;
+use tari_consensus::hotstuff::{ConsensusCurrentState, HotstuffEvent};
+use tari_dan_common_types::optional::Optional;
+use tari_dan_storage::{
+    consensus_models::{Block, BlockId, ExecutedTransaction, SubstateRecord},
+    StateStore,
+    StateStoreReadTransaction,
+    StorageError,
+};
+use tari_engine_types::substate::SubstateAddress;
+use tari_state_store_backend::AnyStateStore;
+use tari_transaction::TransactionId;
+use tari_validator_node_client::types::{
+    SubscribeResponse,
+    SubscriptionEvent,
+    SubscriptionNotification,
+    SubscriptionTopic,
+    UnsubscribeRequest,
+    UnsubscribeResponse,
+};
+use tokio::{
+    sync::{broadcast, watch, Semaphore},
+    time,
+};
+
+use crate::consensus::ConsensusHandle;
+
+const LOG_TARGET: &str = "tari::validator_node::json_rpc::subscriptions";
+
+const MAX_CONNECTIONS: usize = 64;
+const MAX_SUBSCRIPTIONS_PER_CONNECTION: usize = 32;
+const MAX_WATCHED_SUBSTATES: usize = 256;
+/// All transaction and substate subscriptions are re-checked at this interval in case a change was not yet visible
+/// when the commit event was handled or commit events were missed
+const RECHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+const PARSE_ERROR: i32 = -32700;
+const METHOD_NOT_FOUND: i32 = -32601;
+const INVALID_PARAMS: i32 = -32602;
+const INTERNAL_ERROR: i32 = -32603;
+const SUBSCRIPTION_LIMIT_EXCEEDED: i32 = -32000;
+
+/// Serves subscriptions to committed blocks, transaction finalization, substate changes and consensus state over a
+/// websocket. Requests are JSON-RPC `subscribe`/`unsubscribe` calls, and events are sent as `subscription`
+/// notifications.
+#[derive(Debug, Clone)]
+pub struct JsonRpcSubscriptions {
+    consensus_handle: ConsensusHandle,
+    state_store: AnyStateStore<PublicKey>,
+    connections: Arc<Semaphore>,
+}
+
+impl JsonRpcSubscriptions {
+    pub fn new(consensus_handle: ConsensusHandle, state_store: AnyStateStore<PublicKey>) -> Self {
+        Self {
+            consensus_handle,
+            state_store,
+            connections: Arc::new(Semaphore::new(MAX_CONNECTIONS)),
+        }
+    }
+
+    fn connect(&self, socket: WebSocket) -> SubscriptionConnection {
+        let mut consensus_handle = self.consensus_handle.clone();
+        let mut current_state = consensus_handle.subscribe_to_current_state();
+        current_state.borrow_and_update();
+        SubscriptionConnection {
+            socket,
+            state_store: self.state_store.clone(),
+            hotstuff_events: consensus_handle.subscribe_to_hotstuff_events(),
+            current_state,
+            subscriptions: HashMap::new(),
+            next_subscription_id: 1,
+        }
+    }
+}
+
+pub async fn ws_handler(
+    Extension(subscriptions): Extension<Arc<JsonRpcSubscriptions>>,
+    ws: WebSocketUpgrade,
+) -> Response {
+    let Ok(permit) = subscriptions.connections.clone().try_acquire_owned() else {
+        warn!(target: LOG_TARGET, "🌐 Refusing subscription connection: {} connections open", MAX_CONNECTIONS);
+        return (StatusCode::SERVICE_UNAVAILABLE, "Too many subscription connections").into_response();
+    };
+    ws.on_upgrade(move |socket| async move {
+        subscriptions.connect(socket).run().await;
+        // The connection slot is released once the connection closes
+        drop(permit);
+    })
+}
+
+#[derive(Debug, Deserialize)]
+struct SubscriptionRequest {
+    #[serde(default)]
+    id: json::Value,
+    method: String,
+    #[serde(default)]
+    params: json::Value,
+}
+
+type RequestError = (i32, String);
+
+/// The version of a substate last sent to the subscriber as `(version, is_destroyed)`, or None if the substate did
+/// not exist
+type SeenSubstate = Option<(u32, bool)>;
+
+#[derive(Debug)]
+enum Subscription {
+    Blocks,
+    Transaction {
+        transaction_id: TransactionId,
+    },
+    Substates {
+        /// None until the current state of the substate has been sent
+        watched: HashMap<SubstateAddress, Option<SeenSubstate>>,
+    },
+    ConsensusState,
+}
+
+struct SubscriptionConnection {
+    socket: WebSocket,
+    state_store: AnyStateStore<PublicKey>,
+    hotstuff_events: broadcast::Receiver<HotstuffEvent>,
+    current_state: watch::Receiver<ConsensusCurrentState>,
+    subscriptions: HashMap<u64, Subscription>,
+    next_subscription_id: u64,
+}
+
+impl SubscriptionConnection {
+    async fn run(mut self) {
+        let mut recheck = time::interval(RECHECK_INTERVAL);
+        recheck.set_missed_tick_behavior(time::MissedTickBehavior::Delay);
+
+        loop {
+            let result = tokio::select! {
+                msg = self.socket.recv() => match msg {
+                    Some(Ok(Message::Text(text))) => self.handle_request(&text).await,
+                    Some(Ok(Message::Close(_))) | None => break,
+                    // Pings are answered by axum
+                    Some(Ok(_)) => Ok(()),
+                    Some(Err(err)) => Err(err),
+                },
+                event = self.hotstuff_events.recv() => match event {
+                    Ok(event) => self.on_hotstuff_event(event).await,
+                    Err(broadcast::error::RecvError::Lagged(missed)) => {
+                        match self.notify_all(&SubscriptionEvent::Lagged { missed }).await {
+                            // Commit events were missed so all substates are checked
+                            Ok(()) => self.recheck_subscriptions(None).await,
+                            Err(err) => Err(err),
+                        }
+                    },
+                    Err(broadcast::error::RecvError::Closed) => break,
+                },
+                changed = self.current_state.changed() => {
+                    if changed.is_err() {
+                        break;
+                    }
+                    self.on_consensus_state_changed().await
+                },
+                _ = recheck.tick() => self.recheck_subscriptions(None).await,
+            };
+
+            if let Err(err) = result {
+                debug!(target: LOG_TARGET, "🌐 Subscription connection closed: {}", err);
+                break;
+            }
+        }
+    }
+
+    async fn handle_request(&mut self, text: &str) -> Result<(), axum::Error> {
+        let request = match json::from_str::<SubscriptionRequest>(text) {
+            Ok(request) => request,
+            Err(err) => {
+                return self
+                    .send_response(json::Value::Null, Err((PARSE_ERROR, err.to_string())))
+                    .await;
+            },
+        };
+        debug!(target: LOG_TARGET, "🌐 Subscription request: {}", request.method);
+
+        match request.method.as_str() {
+            "subscribe" => {
+                let result = self.subscribe(request.params);
+                let is_subscribed = result.is_ok();
+                self.send_response(request.id, result).await?;
+                if is_subscribed {
+                    // Send anything that is already known, e.g. the transaction was finalized before subscribing
+                    self.recheck_subscriptions(None).await?;
+                }
+                Ok(())
+            },
+            "unsubscribe" => {
+                let result = self.unsubscribe(request.params);
+                self.send_response(request.id, result).await
+            },
+            method => {
+                self.send_response(
+                    request.id,
+                    Err((METHOD_NOT_FOUND, format!("Method '{}' not found", method))),
+                )
+                .await
+            },
+        }
+    }
+
+    fn subscribe(&mut self, params: json::Value) -> Result<json::Value, RequestError> {
+        let topic = json::from_value::<SubscriptionTopic>(params).map_err(|e| (INVALID_PARAMS, e.to_string()))?;
+        if self.subscriptions.len() >= MAX_SUBSCRIPTIONS_PER_CONNECTION {
+            return Err((
+                SUBSCRIPTION_LIMIT_EXCEEDED,
+                format!(
+                    "A connection may have at most {} subscriptions",
+                    MAX_SUBSCRIPTIONS_PER_CONNECTION
+                ),
+            ));
+        }
+
+        let subscription = match topic {
+            SubscriptionTopic::Blocks => Subscription::Blocks,
+            SubscriptionTopic::Transaction { transaction_id } => Subscription::Transaction { transaction_id },
+            SubscriptionTopic::Substates { addresses } => {
+                if addresses.is_empty() || addresses.len() > MAX_WATCHED_SUBSTATES {
+                    return Err((
+                        INVALID_PARAMS,
+                        format!(
+                            "Between 1 and {} substate addresses must be given",
+                            MAX_WATCHED_SUBSTATES
+                        ),
+                    ));
+                }
+                Subscription::Substates {
+                    watched: addresses.into_iter().map(|address| (address, None)).collect(),
+                }
+            },
+            SubscriptionTopic::ConsensusState => Subscription::ConsensusState,
+        };
+
+        let subscription_id = self.next_subscription_id;
+        self.next_subscription_id += 1;
+        self.subscriptions.insert(subscription_id, subscription);
+        json::to_value(SubscribeResponse { subscription_id }).map_err(|e| (INTERNAL_ERROR, e.to_string()))
+    }
+
+    fn unsubscribe(&mut self, params: json::Value) -> Result<json::Value, RequestError> {
+        let request = json::from_value::<UnsubscribeRequest>(params).map_err(|e| (INVALID_PARAMS, e.to_string()))?;
+        let was_subscribed = self.subscriptions.remove(&request.subscription_id).is_some();
+        json::to_value(UnsubscribeResponse { was_subscribed }).map_err(|e| (INTERNAL_ERROR, e.to_string()))
+    }
+
+    async fn on_hotstuff_event(&mut self, event: HotstuffEvent) -> Result<(), axum::Error> {
+        match event {
+            HotstuffEvent::BlockCommitted { block_id, .. } => {
+                self.notify_block_committed(&block_id).await?;
+                let changed = match self
+                    .state_store
+                    .with_read_tx(|tx| SubstateRecord::get_all_for_block(tx, &block_id))
+                {
+                    Ok(substates) => substates.into_iter().map(|s| s.substate_address().clone()).collect(),
+                    Err(err) => {
+                        error!(target: LOG_TARGET, "Failed to load substates for block {}: {}", block_id, err);
+                        return Ok(());
+                    },
+                };
+                self.recheck_subscriptions(Some(&changed)).await
+            },
+            HotstuffEvent::Failure { message } => {
+                self.notify_consensus(&SubscriptionEvent::ConsensusFailure { message })
+                    .await
+            },
+            HotstuffEvent::LeaderTimeout { new_view } => {
+                self.notify_consensus(&SubscriptionEvent::LeaderTimeout { new_view })
+                    .await
+            },
+        }
+    }
+
+    async fn on_consensus_state_changed(&mut self) -> Result<(), axum::Error> {
+        let state = *self.current_state.borrow_and_update();
+        self.notify_consensus(&SubscriptionEvent::ConsensusStateChanged {
+            state: state.to_string(),
+        })
+        .await
+    }
+
+    async fn notify_block_committed(&mut self, block_id: &BlockId) -> Result<(), axum::Error> {
+        let ids = self
+            .subscriptions
+            .iter()
+            .filter(|(_, s)| matches!(s, Subscription::Blocks))
+            .map(|(id, _)| *id)
+            .collect::<Vec<_>>();
+        if ids.is_empty() {
+            return Ok(());
+        }
+
+        let block = match self.state_store.with_read_tx(|tx| Block::get(tx, block_id)) {
+            Ok(block) => block,
+            Err(err) => {
+                error!(target: LOG_TARGET, "Failed to load committed block {}: {}", block_id, err);
+                return Ok(());
+            },
+        };
+        let event = SubscriptionEvent::BlockCommitted { block };
+        for id in ids {
+            self.notify(id, &event).await?;
+        }
+        Ok(())
+    }
+
+    async fn notify_consensus(&mut self, event: &SubscriptionEvent) -> Result<(), axum::Error> {
+        let ids = self
+            .subscriptions
+            .iter()
+            .filter(|(_, s)| matches!(s, Subscription::ConsensusState))
+            .map(|(id, _)| *id)
+            .collect::<Vec<_>>();
+        for id in ids {
+            self.notify(id, event).await?;
+        }
+        Ok(())
+    }
+
+    async fn notify_all(&mut self, event: &SubscriptionEvent) -> Result<(), axum::Error> {
+        let ids = self.subscriptions.keys().copied().collect::<Vec<_>>();
+        for id in ids {
+            self.notify(id, event).await?;
+        }
+        Ok(())
+    }
+
+    /// Checks transaction and substate subscriptions against the state store, sending any changes. If `changed` is
+    /// given, only those substates are checked. Transaction subscriptions are closed once the finalized transaction has
+    /// been sent.
+    async fn recheck_subscriptions(&mut self, changed: Option<&HashSet<SubstateAddress>>) -> Result<(), axum::Error> {
+        let result = self
+            .state_store
+            .with_read_tx(|tx| collect_changes(tx, &mut self.subscriptions, changed));
+        let events = match result {
+            Ok(events) => events,
+            Err(err) => {
+                error!(target: LOG_TARGET, "Failed to check subscriptions: {}", err);
+                return Ok(());
+            },
+        };
+
+        for (id, event) in events {
+            if matches!(event, SubscriptionEvent::TransactionFinalized { .. }) {
+                self.subscriptions.remove(&id);
+            }
+            self.notify(id, &event).await?;
+        }
+        Ok(())
+    }
+
+    async fn notify(&mut self, subscription_id: u64, event: &SubscriptionEvent) -> Result<(), axum::Error> {
+        let notification = SubscriptionNotification {
+            subscription_id,
+            event: event.clone(),
+        };
+        let msg = json!({
+            "jsonrpc": "2.0",
+            "method": "subscription",
+            "params": notification,
+        });
+        self.socket.send(Message::Text(msg.to_string())).await
+    }
+
+    async fn send_response(
+        &mut self,
+        id: json::Value,
+        result: Result<json::Value, RequestError>,
+    ) -> Result<(), axum::Error> {
+        let msg = match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err((code, message)) => {
+                json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } })
+            },
+        };
+        self.socket.send(Message::Text(msg.to_string())).await
+    }
+}
+
+/// Returns the events for transaction and substate subscriptions that have changed since they were last checked. If
+/// `changed` is given, only substates in the set and substates that have not been sent yet are checked.
+fn collect_changes<TTx: StateStoreReadTransaction>(
+    tx: &mut TTx,
+    subscriptions: &mut HashMap<u64, Subscription>,
+    changed: Option<&HashSet<SubstateAddress>>,
+) -> Result<Vec<(u64, SubscriptionEvent)>, StorageError> {
+    let mut events = Vec::new();
+    for (id, subscription) in subscriptions {
+        match subscription {
+            Subscription::Transaction { transaction_id } => {
+                let Some(executed) = ExecutedTransaction::get(tx, transaction_id).optional()? else {
+                    continue;
+                };
+                let Some(final_decision) = executed.final_decision() else {
+                    continue;
+                };
+                events.push((*id, SubscriptionEvent::TransactionFinalized {
+                    transaction_id: *transaction_id,
+                    final_decision,
+                    result: executed.into_final_result(),
+                }));
+            },
+            Subscription::Substates { watched } => {
+                for (address, last_seen) in watched.iter_mut() {
+                    let is_changed = changed.map_or(true, |changed| changed.contains(address));
+                    if last_seen.is_some() && !is_changed {
+                        continue;
+                    }
+                    let latest = SubstateRecord::get_latest_version(tx, address).optional()?;
+                    let seen = latest.as_ref().map(|s| (s.version(), s.is_destroyed()));
+                    if *last_seen == Some(seen) {
+                        continue;
+                    }
+                    *last_seen = Some(seen);
+                    events.push((*id, SubscriptionEvent::SubstateChanged {
+                        address: address.clone(),
+                        substate: latest,
+                    }));
+                }
+            },
+            Subscription::Blocks | Subscription::ConsensusState => {},
+        }
+    }
+    Ok(events)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use tari_common_types::types::PrivateKey;
+    use tari_dan_common_types::{Epoch, NodeHeight};
+    use tari_dan_storage::consensus_models::Decision;
+    use tari_engine_types::{
+        commit_result::{ExecuteResult, FinalizeResult, RejectReason},
+        resource::Resource,
+    };
+    use tari_template_lib::{
+        auth::ResourceAccessRules,
+        constants::CONFIDENTIAL_TARI_RESOURCE_ADDRESS,
+        crypto::RistrettoPublicKeyBytes,
+        prelude::{OwnerRule, ResourceType},
+    };
+    use tari_transaction::Transaction;
+
+    use super::*;
+
+    fn create_substate(store: &AnyStateStore<PublicKey>, address: &SubstateAddress, version: u32) {
+        let block = Block::<PublicKey>::zero_block();
+        let resource = Resource::new(
+            ResourceType::Confidential,
+            RistrettoPublicKeyBytes::default(),
+            OwnerRule::None,
+            ResourceAccessRules::new(),
+            Default::default(),
+        );
+        let substate = SubstateRecord::new(
+            address.clone(),
+            version,
+            resource.into(),
+            Epoch(0),
+            NodeHeight(0),
+            *block.id(),
+            TransactionId::new([version as u8; 32]),
+            *block.justify().id(),
+        );
+        store.with_write_tx(|tx| substate.create(tx)).unwrap();
+    }
+
+    fn watch(address: &SubstateAddress) -> HashMap<u64, Subscription> {
+        let watched = [(address.clone(), None)].into_iter().collect();
+        [(1, Subscription::Substates { watched })].into_iter().collect()
+    }
+
+    fn collect(
+        store: &AnyStateStore<PublicKey>,
+        subscriptions: &mut HashMap<u64, Subscription>,
+        changed: Option<&HashSet<SubstateAddress>>,
+    ) -> Vec<(u64, SubscriptionEvent)> {
+        store
+            .with_read_tx(|tx| collect_changes(tx, subscriptions, changed))
+            .unwrap()
+    }
+
+    fn changed_version(event: &SubscriptionEvent) -> Option<u32> {
+        match event {
+            SubscriptionEvent::SubstateChanged { substate, .. } => substate.as_ref().map(|s| s.version()),
+            _ => panic!("Unexpected event {:?}", event),
+        }
+    }
+
+    #[test]
+    fn it_sends_the_current_state_once() {
+        let store = AnyStateStore::<PublicKey>::connect_sqlite(":memory:").unwrap();
+        let address = SubstateAddress::Resource(CONFIDENTIAL_TARI_RESOURCE_ADDRESS);
+        let mut subscriptions = watch(&address);
+
+        let events = collect(&store, &mut subscriptions, None);
+        assert_eq!(events.len(), 1);
+        assert_eq!(changed_version(&events[0].1), None);
+        assert!(collect(&store, &mut subscriptions, None).is_empty());
+
+        create_substate(&store, &address, 0);
+        let events = collect(&store, &mut subscriptions, None);
+        assert_eq!(events.len(), 1);
+        assert_eq!(changed_version(&events[0].1), Some(0));
+        assert!(collect(&store, &mut subscriptions, None).is_empty());
+    }
+
+    #[test]
+    fn it_sends_the_latest_version() {
+        let store = AnyStateStore::<PublicKey>::connect_sqlite(":memory:").unwrap();
+        let address = SubstateAddress::Resource(CONFIDENTIAL_TARI_RESOURCE_ADDRESS);
+        for version in 0..3 {
+            create_substate(&store, &address, version);
+        }
+        let mut subscriptions = watch(&address);
+
+        let events = collect(&store, &mut subscriptions, None);
+        assert_eq!(events.len(), 1);
+        assert_eq!(changed_version(&events[0].1), Some(2));
+    }
+
+    #[test]
+    fn it_only_checks_sent_substates_that_were_changed() {
+        let store = AnyStateStore::<PublicKey>::connect_sqlite(":memory:").unwrap();
+        let address = SubstateAddress::Resource(CONFIDENTIAL_TARI_RESOURCE_ADDRESS);
+        let mut subscriptions = watch(&address);
+
+        // The current state is sent even though the substate was not changed by the block
+        let events = collect(&store, &mut subscriptions, Some(&HashSet::new()));
+        assert_eq!(events.len(), 1);
+
+        create_substate(&store, &address, 0);
+        assert!(collect(&store, &mut subscriptions, Some(&HashSet::new())).is_empty());
+
+        let changed = [address.clone()].into_iter().collect();
+        let events = collect(&store, &mut subscriptions, Some(&changed));
+        assert_eq!(events.len(), 1);
+        assert_eq!(changed_version(&events[0].1), Some(0));
+    }
+
+    #[test]
+    fn it_sends_finalized_transactions() {
+        let store = AnyStateStore::<PublicKey>::connect_sqlite(":memory:").unwrap();
+        let transaction = Transaction::builder().sign(&PrivateKey::default()).build();
+        let transaction_id = *transaction.id();
+        let mut subscriptions = [(1, Subscription::Transaction { transaction_id })]
+            .into_iter()
+            .collect();
+        assert!(collect(&store, &mut subscriptions, None).is_empty());
+
+        let mut executed = ExecutedTransaction::new(
+            transaction,
+            ExecuteResult {
+                finalize: FinalizeResult::new_rejected(
+                    transaction_id.into_array().into(),
+                    RejectReason::ExecutionFailure("Test failure".to_string()),
+                ),
+                fee_receipt: None,
+            },
+            vec![],
+            Duration::from_secs(0),
+        );
+        store.with_write_tx(|tx| executed.insert(tx)).unwrap();
+        // Not sent until the transaction is finalized
+        assert!(collect(&store, &mut subscriptions, None).is_empty());
+
+        executed.set_final_decision(Decision::Abort);
+        store.with_write_tx(|tx| executed.update(tx)).unwrap();
+        let events = collect(&store, &mut subscriptions, None);
+        assert_eq!(events.len(), 1);
+        assert!(matches!(events[0].1, SubscriptionEvent::TransactionFinalized {
+            final_decision: Decision::Abort,
+            ..
+        }));
+    }
+}
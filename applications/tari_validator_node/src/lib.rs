@@ -20,19 +20,25 @@
 // WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
+mod backup;
 mod bootstrap;
 pub mod cli;
+mod committee_health;
 mod comms;
 mod config;
 mod consensus;
 mod dan_node;
+mod dev_templates;
 mod dry_run_transaction_processor;
+mod epoch_handoff;
 mod event_subscription;
 mod grpc;
 mod http_ui;
 mod json_rpc;
+mod metrics;
 mod p2p;
 mod registration;
+mod state_pruning;
 mod substate_resolver;
 mod template_registration_signing;
 mod virtual_substate;
@@ -52,7 +58,7 @@ use tari_common::{
     configuration::bootstrap::{grpc_default_port, ApplicationType},
     exit_codes::{ExitCode, ExitError},
 };
-use tari_dan_app_utilities::consensus_constants::ConsensusConstants;
+use tari_dan_app_utilities::{consensus_constants::ConsensusConstants, metrics::server::run_metrics_server};
 use tari_dan_common_types::ShardId;
 use tari_dan_storage::global::DbFactory;
 use tari_dan_storage_sqlite::SqliteDbFactory;
@@ -65,7 +71,7 @@ use crate::{
     dan_node::DanNode,
     grpc::base_layer_wallet::{GrpcWalletClient, WalletGrpcError},
     http_ui::server::run_http_ui_server,
-    json_rpc::{spawn_json_rpc, JsonRpcHandlers},
+    json_rpc::{spawn_json_rpc, JsonRpcHandlers, JsonRpcSubscriptions},
     p2p::services::networking::DAN_PEER_FEATURES,
 };
 
@@ -143,7 +149,9 @@ pub async fn run_validator_node(config: &ApplicationConfig, shutdown_signal: Shu
             &services,
             config.validator_node.clone(),
         );
-        *jrpc_address = spawn_json_rpc(*jrpc_address, handlers)?;
+        let subscriptions =
+            JsonRpcSubscriptions::new(services.consensus_handle.clone(), services.state_store.clone());
+        *jrpc_address = spawn_json_rpc(*jrpc_address, handlers, subscriptions)?;
         // Run the http ui
         if let Some(address) = config.validator_node.http_ui_address {
             task::spawn(run_http_ui_server(
@@ -157,6 +165,11 @@ pub async fn run_validator_node(config: &ApplicationConfig, shutdown_signal: Shu
         }
     }
 
+    // Run the metrics server
+    if let Some(address) = config.validator_node.metrics_address {
+        task::spawn(run_metrics_server(address));
+    }
+
     fs::write(config.common.base_path.join("pid"), process::id().to_string())
         .map_err(|e| ExitError::new(ExitCode::UnknownError, e))?;
     run_dan_node(services, shutdown_signal).await?;
@@ -0,0 +1,30 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+//! Validator node metrics. Metrics are registered with the default prometheus registry, which also contains the
+//! consensus, state store, template manager and shared application metrics, and are served by the metrics server in
+//! [tari_dan_app_utilities::metrics::server].
+
+use lazy_static::lazy_static;
+use prometheus::{Histogram, HistogramOpts, IntGauge};
+use tari_dan_app_utilities::metrics::register;
+
+lazy_static! {
+    pub static ref MEMPOOL_SIZE: IntGauge = register(
+        IntGauge::new(
+            "mempool_size",
+            "The number of transactions in the mempool awaiting execution"
+        )
+        .unwrap()
+    );
+    pub static ref MEMPOOL_EXECUTION_SECONDS: Histogram = register(
+        Histogram::with_opts(
+            HistogramOpts::new(
+                "mempool_execution_seconds",
+                "The time taken to execute a transaction in the mempool"
+            )
+            .buckets(vec![0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0])
+        )
+        .unwrap()
+    );
+}
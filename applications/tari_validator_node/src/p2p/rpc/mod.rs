@@ -25,18 +25,28 @@ mod sync_task;
 
 pub use service_impl::ValidatorNodeRpcServiceImpl;
 use tari_common_types::types::PublicKey;
+use tari_dan_app_utilities::template_manager::interface::TemplateManagerHandle;
 use tari_dan_p2p::PeerProvider;
 use tari_epoch_manager::base_layer::EpochManagerHandle;
-use tari_state_store_sqlite::SqliteStateStore;
+use tari_state_store_backend::AnyStateStore;
 use tari_validator_node_rpc::rpc_service::ValidatorNodeRpcServer;
 
-use crate::{p2p::services::mempool::MempoolHandle, virtual_substate::VirtualSubstateManager};
+use crate::{
+    dev_templates::DevTemplateRegistrar,
+    dry_run_transaction_processor::DryRunTransactionProcessor,
+    p2p::services::mempool::MempoolHandle,
+    virtual_substate::VirtualSubstateManager,
+};
 
 pub fn create_tari_validator_node_rpc_service<TPeerProvider>(
     peer_provider: TPeerProvider,
-    shard_store_store: SqliteStateStore<PublicKey>,
+    shard_store_store: AnyStateStore<PublicKey>,
     mempool: MempoolHandle,
-    virtual_substate_manager: VirtualSubstateManager<SqliteStateStore<PublicKey>, EpochManagerHandle>,
+    virtual_substate_manager: VirtualSubstateManager<AnyStateStore<PublicKey>, EpochManagerHandle>,
+    dry_run_transaction_processor: DryRunTransactionProcessor,
+    dev_template_registrar: DevTemplateRegistrar,
+    template_manager: TemplateManagerHandle,
+    epoch_manager: EpochManagerHandle,
 ) -> ValidatorNodeRpcServer<ValidatorNodeRpcServiceImpl<TPeerProvider>>
 where
     TPeerProvider: PeerProvider + Clone + Send + Sync + 'static,
@@ -46,5 +56,9 @@ where
         shard_store_store,
         mempool,
         virtual_substate_manager,
+        dry_run_transaction_processor,
+        dev_template_registrar,
+        template_manager,
+        epoch_manager,
     ))
 }
@@ -20,68 +20,192 @@
 // CAUSED AND ON ANY THEORY OF LIABILITY,  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR
 // OTHERWISE) ARISING IN ANY WAY OUT OF THE  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH
 // DAMAGE.
-use std::convert::{TryFrom, TryInto};
+use std::{
+    collections::HashMap,
+    convert::{TryFrom, TryInto},
+    ops::RangeInclusive,
+    sync::Mutex,
+};
 
 use log::*;
 use tari_bor::{decode_exact, encode};
-use tari_common_types::types::PublicKey;
-use tari_comms::protocol::rpc::{Request, Response, RpcStatus, Streaming};
-use tari_dan_common_types::{optional::Optional, NodeAddressable, ShardId};
+use tari_common_types::types::{PublicKey, Signature};
+use tari_comms::{
+    peer_manager::NodeId,
+    protocol::rpc::{Request, Response, RpcStatus, Streaming},
+};
+use tari_crypto::tari_utilities::ByteArray;
+use tari_dan_app_utilities::template_manager::interface::{
+    TemplateExecutable,
+    TemplateManagerError,
+    TemplateManagerHandle,
+};
+use tari_dan_common_types::{
+    optional::{IsNotFoundError, Optional},
+    Epoch,
+    NodeAddressable,
+    ShardId,
+};
 use tari_dan_p2p::PeerProvider;
 use tari_dan_storage::{
-    consensus_models::{Block, BlockId, HighQc, LockedBlock, QuorumCertificate, SubstateRecord, TransactionRecord},
+    consensus_models::{
+        Block,
+        BlockId,
+        HighQc,
+        LastExecuted,
+        LockedBlock,
+        QuorumCertificate,
+        StateSnapshot,
+        StateSnapshotSummary,
+        SubstateRecord,
+        TransactionRecord,
+    },
     StateStore,
+    StateStoreReadTransaction,
+    StorageError,
+};
+use tari_engine_types::{substate::SubstateAddress, virtual_substate::VirtualSubstateAddress};
+use tari_epoch_manager::{base_layer::EpochManagerHandle, EpochManagerReader};
+use tari_state_store_backend::AnyStateStore;
+use tari_template_lib::{
+    args::Arg,
+    models::{ComponentAddress, TemplateAddress},
 };
-use tari_engine_types::virtual_substate::VirtualSubstateAddress;
-use tari_epoch_manager::base_layer::EpochManagerHandle;
-use tari_state_store_sqlite::SqliteStateStore;
 use tari_transaction::{Transaction, TransactionId};
 use tari_validator_node_rpc::{
+    client::SignedDevTemplate,
     proto,
     proto::rpc::{
+        sync_state_snapshot_response::SnapshotData,
+        GetEpochHandoffRequest,
+        GetEpochHandoffResponse,
         GetHighQcRequest,
         GetHighQcResponse,
+        GetStateCommitmentRequest,
+        GetStateCommitmentResponse,
+        GetSubstateAtRequest,
+        GetSubstateAtResponse,
+        GetSubstateHistoryRequest,
+        GetSubstateHistoryResponse,
         GetSubstateRequest,
         GetSubstateResponse,
+        GetTemplateBinaryRequest,
+        GetTemplateBinaryResponse,
         GetTransactionResultRequest,
         GetTransactionResultResponse,
+        InvokeReadMethodRequest,
+        InvokeReadMethodResponse,
         PayloadResultStatus,
+        StateSnapshotCheckpoint,
+        SubmitDevTemplateRequest,
+        SubmitDevTemplateResponse,
         SubstateStatus,
         SyncBlocksRequest,
         SyncBlocksResponse,
+        SyncStateSnapshotRequest,
+        SyncStateSnapshotResponse,
+        SyncSubstatesInRangeRequest,
+        SyncSubstatesInRangeResponse,
     },
     rpc_service::ValidatorNodeRpcService,
 };
 use tokio::{sync::mpsc, task};
 
 use crate::{
+    dev_templates::DevTemplateRegistrar,
+    dry_run_transaction_processor::{DryRunTransactionProcessor, DryRunTransactionProcessorError},
     p2p::{rpc::sync_task::BlockSyncTask, services::mempool::MempoolHandle},
     virtual_substate::VirtualSubstateManager,
 };
 
 const LOG_TARGET: &str = "tari::dan::p2p::rpc";
 
+const DEFAULT_SUBSTATE_PAGE_SIZE: usize = 100;
+const MAX_SUBSTATE_PAGE_SIZE: usize = 1000;
+const EPOCH_HANDOFF_PAGE_SIZE: usize = 100;
+const STATE_SNAPSHOT_PAGE_SIZE: usize = 1000;
+/// The maximum number of substates this node will serve in a state snapshot of a single shard range
+const MAX_STATE_SNAPSHOT_SUBSTATES: u64 = 10_000_000;
+/// The number of shard range state commitments to keep before the cache is cleared
+const STATE_COMMITMENT_CACHE_SIZE: usize = 64;
+
 pub struct ValidatorNodeRpcServiceImpl<TPeerProvider> {
     peer_provider: TPeerProvider,
-    shard_state_store: SqliteStateStore<PublicKey>,
+    shard_state_store: AnyStateStore<PublicKey>,
     mempool: MempoolHandle,
-    virtual_substate_manager: VirtualSubstateManager<SqliteStateStore<PublicKey>, EpochManagerHandle>,
+    virtual_substate_manager: VirtualSubstateManager<AnyStateStore<PublicKey>, EpochManagerHandle>,
+    dry_run_transaction_processor: DryRunTransactionProcessor,
+    dev_template_registrar: DevTemplateRegistrar,
+    template_manager: TemplateManagerHandle,
+    epoch_manager: EpochManagerHandle,
+    state_commitments: Mutex<HashMap<(BlockId, RangeInclusive<ShardId>), StateSnapshotSummary>>,
 }
 
 impl<TPeerProvider: PeerProvider> ValidatorNodeRpcServiceImpl<TPeerProvider> {
     pub fn new(
         peer_provider: TPeerProvider,
-        shard_state_store: SqliteStateStore<PublicKey>,
+        shard_state_store: AnyStateStore<PublicKey>,
         mempool: MempoolHandle,
-        virtual_substate_manager: VirtualSubstateManager<SqliteStateStore<PublicKey>, EpochManagerHandle>,
+        virtual_substate_manager: VirtualSubstateManager<AnyStateStore<PublicKey>, EpochManagerHandle>,
+        dry_run_transaction_processor: DryRunTransactionProcessor,
+        dev_template_registrar: DevTemplateRegistrar,
+        template_manager: TemplateManagerHandle,
+        epoch_manager: EpochManagerHandle,
     ) -> Self {
         Self {
             peer_provider,
             shard_state_store,
             mempool,
             virtual_substate_manager,
+            dry_run_transaction_processor,
+            dev_template_registrar,
+            template_manager,
+            epoch_manager,
+            state_commitments: Mutex::new(HashMap::new()),
         }
     }
+
+    /// Returns the state commitment of the shard range at the checkpoint block. The commitment is calculated once per
+    /// checkpoint and range, since every member of the committee is asked for it when a node syncs.
+    fn get_state_snapshot_summary(
+        &self,
+        checkpoint: &Block<PublicKey>,
+        range: &RangeInclusive<ShardId>,
+    ) -> Result<StateSnapshotSummary, RpcStatus> {
+        let key = (*checkpoint.id(), range.clone());
+        let cached = self
+            .state_commitments
+            .lock()
+            .expect("state commitment cache lock poisoned")
+            .get(&key)
+            .copied();
+        let summary = match cached {
+            Some(summary) => summary,
+            None => {
+                let summary = self
+                    .shard_state_store
+                    .with_read_tx(|tx| StateSnapshot::load_summary(tx, checkpoint, range))
+                    .map_err(RpcStatus::log_internal_error(LOG_TARGET))?;
+                let mut cache = self
+                    .state_commitments
+                    .lock()
+                    .expect("state commitment cache lock poisoned");
+                if cache.len() >= STATE_COMMITMENT_CACHE_SIZE {
+                    cache.clear();
+                }
+                cache.insert(key, summary);
+                summary
+            },
+        };
+
+        if summary.substate_count > MAX_STATE_SNAPSHOT_SUBSTATES {
+            return Err(RpcStatus::bad_request(&format!(
+                "Shard range has {} substates which exceeds the maximum of {} for a snapshot",
+                summary.substate_count, MAX_STATE_SNAPSHOT_SUBSTATES
+            )));
+        }
+        Ok(summary)
+    }
 }
 
 #[tari_comms::async_trait]
@@ -319,4 +443,407 @@ where TPeerProvider: PeerProvider + Clone + Send + Sync + 'static
             high_qc: Some((&high_qc).into()),
         }))
     }
+
+    async fn sync_state_snapshot(
+        &self,
+        request: Request<SyncStateSnapshotRequest>,
+    ) -> Result<Streaming<SyncStateSnapshotResponse>, RpcStatus> {
+        let req = request.into_message();
+        let range = parse_shard_range(req.shard_start, req.shard_end)?;
+
+        let (block, qc) = load_snapshot_checkpoint(&self.shard_state_store, None)?;
+        let summary = self.get_state_snapshot_summary(&block, &range)?;
+        debug!(
+            target: LOG_TARGET,
+            "Sending state snapshot at block {} with {} substate(s) to peer", block, summary.substate_count
+        );
+
+        let store = self.shard_state_store.clone();
+        let (sender, receiver) = mpsc::channel(100);
+        task::spawn(async move {
+            let checkpoint = SyncStateSnapshotResponse {
+                snapshot_data: Some(SnapshotData::Checkpoint(StateSnapshotCheckpoint {
+                    block: Some((&block).into()),
+                    qc: Some((&qc).into()),
+                    state_commitment: summary.state_commitment.as_slice().to_vec(),
+                    substate_count: summary.substate_count,
+                })),
+            };
+            if sender.send(Ok(checkpoint)).await.is_err() {
+                return;
+            }
+
+            let mut after = None;
+            loop {
+                let page = store.with_read_tx(|tx| {
+                    StateSnapshot::load_page(tx, &block, &range, after.as_ref(), STATE_SNAPSHOT_PAGE_SIZE)
+                });
+                let page = match page {
+                    Ok(page) => page,
+                    Err(err) => {
+                        let _ignore = sender.send(Err(RpcStatus::log_internal_error(LOG_TARGET)(err))).await;
+                        return;
+                    },
+                };
+                for substate in &page.substates {
+                    let msg = SyncStateSnapshotResponse {
+                        snapshot_data: Some(SnapshotData::Substate(substate.into())),
+                    };
+                    if sender.send(Ok(msg)).await.is_err() {
+                        debug!(
+                            target: LOG_TARGET,
+                            "Peer stream closed by client before completing. Aborting"
+                        );
+                        return;
+                    }
+                }
+                match page.next {
+                    Some(next) => after = Some(next),
+                    None => return,
+                }
+            }
+        });
+
+        Ok(Streaming::new(receiver))
+    }
+
+    async fn get_state_commitment(
+        &self,
+        request: Request<GetStateCommitmentRequest>,
+    ) -> Result<Response<GetStateCommitmentResponse>, RpcStatus> {
+        let req = request.into_message();
+        let block_id = BlockId::try_from(req.block_id)
+            .map_err(|e| RpcStatus::bad_request(&format!("Invalid encoded block id: {}", e)))?;
+        let range = parse_shard_range(req.shard_start, req.shard_end)?;
+
+        let (block, _) = load_snapshot_checkpoint(&self.shard_state_store, Some(block_id))?;
+        let summary = self.get_state_snapshot_summary(&block, &range)?;
+
+        Ok(Response::new(GetStateCommitmentResponse {
+            state_commitment: summary.state_commitment.as_slice().to_vec(),
+        }))
+    }
+
+    async fn get_substate_history(
+        &self,
+        request: Request<GetSubstateHistoryRequest>,
+    ) -> Result<Response<GetSubstateHistoryResponse>, RpcStatus> {
+        let req = request.into_message();
+        let address = SubstateAddress::from_bytes(&req.address)
+            .map_err(|e| RpcStatus::bad_request(&format!("Invalid encoded substate address: {}", e)))?;
+
+        let history = self
+            .shard_state_store
+            .with_read_tx(|tx| SubstateRecord::get_history(tx, &address))
+            .map_err(RpcStatus::log_internal_error(LOG_TARGET))?;
+
+        Ok(Response::new(GetSubstateHistoryResponse {
+            versions: history.versions.iter().map(Into::into).collect(),
+            pruned_before_epoch: history.pruned_before_epoch.map_or(0, |epoch| epoch.as_u64()),
+        }))
+    }
+
+    async fn get_substate_at(
+        &self,
+        request: Request<GetSubstateAtRequest>,
+    ) -> Result<Response<GetSubstateAtResponse>, RpcStatus> {
+        let req = request.into_message();
+        let address = SubstateAddress::from_bytes(&req.address)
+            .map_err(|e| RpcStatus::bad_request(&format!("Invalid encoded substate address: {}", e)))?;
+        let at = req
+            .at
+            .ok_or_else(|| RpcStatus::bad_request("Query point not provided"))?
+            .try_into()
+            .map_err(|e| RpcStatus::bad_request(&format!("Invalid query point: {}", e)))?;
+
+        let substate = match self
+            .shard_state_store
+            .with_read_tx(|tx| SubstateRecord::get_at(tx, &address, at))
+        {
+            Ok(substate) => substate,
+            Err(err) if err.is_not_found_error() => return Err(RpcStatus::not_found(&err.to_string())),
+            Err(err @ StorageError::HistoryUnavailable { .. }) => return Err(RpcStatus::general(&err.to_string())),
+            Err(err) => return Err(RpcStatus::log_internal_error(LOG_TARGET)(err)),
+        };
+
+        Ok(Response::new(GetSubstateAtResponse {
+            substate: substate.as_ref().map(Into::into),
+        }))
+    }
+
+    async fn sync_substates_in_range(
+        &self,
+        request: Request<SyncSubstatesInRangeRequest>,
+    ) -> Result<Streaming<SyncSubstatesInRangeResponse>, RpcStatus> {
+        let req = request.into_message();
+        let range = parse_shard_range(req.shard_start, req.shard_end)?;
+        let after = if req.after.is_empty() {
+            None
+        } else {
+            let after = ShardId::try_from(req.after)
+                .map_err(|e| RpcStatus::bad_request(&format!("Invalid shard id to resume after: {}", e)))?;
+            Some(after)
+        };
+        let block_id = if req.block_id.is_empty() {
+            None
+        } else {
+            let block_id = BlockId::try_from(req.block_id)
+                .map_err(|e| RpcStatus::bad_request(&format!("Invalid encoded block id: {}", e)))?;
+            Some(block_id)
+        };
+        let page_size = match req.page_size as usize {
+            0 => DEFAULT_SUBSTATE_PAGE_SIZE,
+            n => n.min(MAX_SUBSTATE_PAGE_SIZE),
+        };
+
+        // The substates are read at a committed block so that the client can verify the state commitment of the
+        // whole range with other committee members, and so that a stream can be resumed from another peer
+        let (block, _) = load_snapshot_checkpoint(&self.shard_state_store, block_id)?;
+        let block_id = block.id().as_bytes().to_vec();
+
+        let store = self.shard_state_store.clone();
+        let (sender, receiver) = mpsc::channel(10);
+        task::spawn(async move {
+            let mut after = after;
+            loop {
+                let page =
+                    store.with_read_tx(|tx| StateSnapshot::load_page(tx, &block, &range, after.as_ref(), page_size));
+                let page = match page {
+                    Ok(page) => page,
+                    Err(err) => {
+                        let _ignore = sender.send(Err(RpcStatus::log_internal_error(LOG_TARGET)(err))).await;
+                        return;
+                    },
+                };
+                let msg = SyncSubstatesInRangeResponse {
+                    substates: page.substates.iter().map(Into::into).collect(),
+                    next: page.next.map(|next| next.as_bytes().to_vec()).unwrap_or_default(),
+                    block_id: block_id.clone(),
+                };
+                if sender.send(Ok(msg)).await.is_err() {
+                    debug!(
+                        target: LOG_TARGET,
+                        "Peer stream closed by client before completing. Aborting"
+                    );
+                    return;
+                }
+                match page.next {
+                    Some(next) => after = Some(next),
+                    None => return,
+                }
+            }
+        });
+
+        Ok(Streaming::new(receiver))
+    }
+
+    async fn invoke_read_method(
+        &self,
+        request: Request<InvokeReadMethodRequest>,
+    ) -> Result<Response<InvokeReadMethodResponse>, RpcStatus> {
+        let req = request.into_message();
+        let component_address = decode_exact::<ComponentAddress>(&req.component_address)
+            .map_err(|e| RpcStatus::bad_request(&format!("Invalid encoded component address: {}", e)))?;
+        let args = decode_exact::<Vec<Arg>>(&req.args)
+            .map_err(|e| RpcStatus::bad_request(&format!("Invalid encoded args: {}", e)))?;
+
+        let result = self
+            .dry_run_transaction_processor
+            .process_view_call(component_address, req.method, args)
+            .await
+            .map_err(|e| match e {
+                DryRunTransactionProcessorError::ViewCall(e) => RpcStatus::bad_request(&e.to_string()),
+                e @ DryRunTransactionProcessorError::TooManyViewCalls => RpcStatus::general(&e.to_string()),
+                e => RpcStatus::log_internal_error(LOG_TARGET)(e),
+            })?;
+
+        Ok(Response::new(InvokeReadMethodResponse {
+            result: encode(&result).map_err(|e| RpcStatus::general(&format!("Unable to encode result: {}", e)))?,
+        }))
+    }
+
+    async fn get_epoch_handoff(
+        &self,
+        request: Request<GetEpochHandoffRequest>,
+    ) -> Result<Response<GetEpochHandoffResponse>, RpcStatus> {
+        let peer_node_id = request.context().peer_node_id().clone();
+        let req = request.into_message();
+        let epoch = Epoch(req.epoch);
+
+        let current_epoch = self
+            .epoch_manager
+            .current_epoch()
+            .await
+            .map_err(RpcStatus::log_internal_error(LOG_TARGET))?;
+        // The requester may have seen the epoch change before this node has
+        if epoch != current_epoch && epoch != Epoch(current_epoch.as_u64() + 1) {
+            return Err(RpcStatus::bad_request(format!(
+                "Epoch handoff for epoch {} is not available in epoch {}",
+                epoch, current_epoch
+            )));
+        }
+        let prev_epoch = epoch
+            .checked_sub(Epoch(1))
+            .ok_or_else(|| RpcStatus::bad_request("No epoch handoff for epoch 0"))?;
+
+        let local_validator = self
+            .epoch_manager
+            .get_our_validator_node(prev_epoch)
+            .await
+            .optional()
+            .map_err(RpcStatus::log_internal_error(LOG_TARGET))?
+            .ok_or_else(|| {
+                RpcStatus::not_found(format!("This validator is not registered for epoch {}", prev_epoch))
+            })?;
+        let incoming_committee = self
+            .epoch_manager
+            .get_committee(epoch, local_validator.shard_key)
+            .await
+            .map_err(RpcStatus::log_internal_error(LOG_TARGET))?;
+        if !incoming_committee
+            .iter()
+            .any(|member| NodeId::from_public_key(member) == peer_node_id)
+        {
+            return Err(RpcStatus::bad_request(format!(
+                "Peer {} is not in the incoming committee for epoch {}",
+                peer_node_id, epoch
+            )));
+        }
+
+        let after = if req.after.is_empty() {
+            None
+        } else {
+            Some(TransactionId::try_from(req.after).map_err(|_| RpcStatus::bad_request("Invalid after id"))?)
+        };
+
+        let (pending_transactions, next) = self
+            .shard_state_store
+            .with_read_tx(|tx| {
+                let page = tx.transaction_pool_get_page(after.as_ref(), EPOCH_HANDOFF_PAGE_SIZE)?;
+                let next = if page.len() == EPOCH_HANDOFF_PAGE_SIZE {
+                    page.last().map(|rec| *rec.transaction_id())
+                } else {
+                    None
+                };
+                // Transactions that have not been prepared hold no locks and can be prepared by the next committee
+                let pending = page
+                    .into_iter()
+                    .filter(|rec| rec.current_stage().is_new())
+                    .map(|rec| *rec.transaction_id())
+                    .collect::<Vec<_>>();
+                let (pending, _) = TransactionRecord::get_any(tx, &pending)?;
+                Ok::<_, StorageError>((pending, next))
+            })
+            .map_err(RpcStatus::log_internal_error(LOG_TARGET))?;
+
+        debug!(
+            target: LOG_TARGET,
+            "🤝 Handing off {} pending transaction(s) for epoch {} to {}",
+            pending_transactions.len(),
+            epoch,
+            peer_node_id
+        );
+
+        Ok(Response::new(GetEpochHandoffResponse {
+            pending_transactions: pending_transactions.iter().map(|t| t.transaction().into()).collect(),
+            next: next.map(|id| id.as_bytes().to_vec()).unwrap_or_default(),
+        }))
+    }
+
+    async fn submit_dev_template(
+        &self,
+        request: Request<SubmitDevTemplateRequest>,
+    ) -> Result<Response<SubmitDevTemplateResponse>, RpcStatus> {
+        let req = request.into_message();
+        let signer_public_key = PublicKey::from_canonical_bytes(&req.signer_public_key)
+            .map_err(|_| RpcStatus::bad_request("Invalid signer public key"))?;
+        let signature: Signature = req
+            .signature
+            .ok_or_else(|| RpcStatus::bad_request("Missing signature"))?
+            .try_into()
+            .map_err(|e| RpcStatus::bad_request(&format!("Invalid signature: {}", e)))?;
+
+        let template_address = self
+            .dev_template_registrar
+            .accept(SignedDevTemplate {
+                template_name: req.template_name,
+                binary: req.binary,
+                signer_public_key,
+                signature,
+            })
+            .await
+            .map_err(|e| RpcStatus::bad_request(&format!("Development template rejected: {}", e)))?;
+
+        Ok(Response::new(SubmitDevTemplateResponse {
+            template_address: template_address.to_vec(),
+        }))
+    }
+
+    async fn get_template_binary(
+        &self,
+        request: Request<GetTemplateBinaryRequest>,
+    ) -> Result<Response<GetTemplateBinaryResponse>, RpcStatus> {
+        let req = request.into_message();
+        let template_address = TemplateAddress::try_from(req.template_address)
+            .map_err(|e| RpcStatus::bad_request(&format!("Invalid template address: {}", e)))?;
+
+        let template = self
+            .template_manager
+            .get_template(template_address)
+            .await
+            .map_err(|e| match e {
+                TemplateManagerError::TemplateNotFound { .. } | TemplateManagerError::TemplateUnavailable => {
+                    RpcStatus::not_found(&format!("Template {} is not available", template_address))
+                },
+                e => RpcStatus::log_internal_error(LOG_TARGET)(e),
+            })?;
+
+        let binary = match template.executable {
+            TemplateExecutable::CompiledWasm(binary) => binary,
+            TemplateExecutable::Flow(json) => json.into_bytes(),
+            TemplateExecutable::Manifest(manifest) => manifest.into_bytes(),
+        };
+
+        Ok(Response::new(GetTemplateBinaryResponse { binary }))
+    }
+}
+
+/// Loads the checkpoint for a snapshot: the given committed block, or the last executed block if no block is given
+fn load_snapshot_checkpoint(
+    store: &AnyStateStore<PublicKey>,
+    block_id: Option<BlockId>,
+) -> Result<(Block<PublicKey>, QuorumCertificate<PublicKey>), RpcStatus> {
+    match block_id {
+        Some(block_id) => store
+            .with_read_tx(|tx| {
+                let is_committed = Block::get(tx, &block_id)
+                    .optional()?
+                    .map_or(false, |block| block.is_committed());
+                if !is_committed {
+                    return Ok(None);
+                }
+                StateSnapshot::load_checkpoint(tx, &block_id).map(Some)
+            })
+            .map_err(RpcStatus::log_internal_error(LOG_TARGET))?
+            .ok_or_else(|| RpcStatus::not_found(&format!("Block {block_id} is not committed"))),
+        None => store
+            .with_read_tx(|tx| {
+                let last_executed = LastExecuted::get(tx)?;
+                if last_executed.height.is_zero() {
+                    return Ok(None);
+                }
+                StateSnapshot::load_checkpoint(tx, &last_executed.block_id).map(Some)
+            })
+            .map_err(RpcStatus::log_internal_error(LOG_TARGET))?
+            .ok_or_else(|| RpcStatus::not_found("No committed state to snapshot")),
+    }
+}
+
+fn parse_shard_range(start: Vec<u8>, end: Vec<u8>) -> Result<RangeInclusive<ShardId>, RpcStatus> {
+    let start = ShardId::try_from(start).map_err(|e| RpcStatus::bad_request(&format!("Invalid shard start: {}", e)))?;
+    let end = ShardId::try_from(end).map_err(|e| RpcStatus::bad_request(&format!("Invalid shard end: {}", e)))?;
+    if start > end {
+        return Err(RpcStatus::bad_request("Shard range start is after the end"));
+    }
+    Ok(start..=end)
 }
@@ -19,7 +19,7 @@ use tari_dan_storage::{
 };
 use tari_dan_storage_sqlite::{error::SqliteStorageError, global::SqliteGlobalDbAdapter};
 use tari_epoch_manager::{base_layer::EpochManagerHandle, EpochManagerError, EpochManagerReader};
-use tari_state_store_sqlite::SqliteStateStore;
+use tari_state_store_backend::AnyStateStore;
 use tari_validator_node_rpc::{
     client::{TariCommsValidatorNodeClientFactory, ValidatorNodeClientFactory},
     ValidatorNodeRpcClientError,
@@ -30,7 +30,7 @@ const LOG_TARGET: &str = "tari::dan::committee_state_sync";
 pub struct CommitteeStateSync {
     epoch_manager: EpochManagerHandle,
     validator_node_client_factory: TariCommsValidatorNodeClientFactory,
-    shard_store: SqliteStateStore<PublicKey>,
+    shard_store: AnyStateStore<PublicKey>,
     global_db: GlobalDb<SqliteGlobalDbAdapter>,
     node_public_key: CommsPublicKey,
 }
@@ -39,7 +39,7 @@ impl CommitteeStateSync {
     pub fn new(
         epoch_manager: EpochManagerHandle,
         validator_node_client_factory: TariCommsValidatorNodeClientFactory,
-        shard_store: SqliteStateStore<PublicKey>,
+        shard_store: AnyStateStore<PublicKey>,
         global_db: GlobalDb<SqliteGlobalDbAdapter>,
         node_public_key: CommsPublicKey,
     ) -> Self {
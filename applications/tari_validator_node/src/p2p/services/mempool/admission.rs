@@ -0,0 +1,432 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use std::{
+    collections::{HashMap, HashSet},
+    time::{Duration, Instant},
+};
+
+use serde::{Deserialize, Serialize};
+use tari_common::configuration::serializers;
+use tari_common_types::types::PublicKey;
+use tari_comms::types::CommsPublicKey;
+use tari_engine_types::instruction::Instruction;
+use tari_template_lib::{args::Arg, models::Amount};
+use tari_transaction::{Transaction, TransactionId};
+
+use crate::p2p::services::mempool::MempoolError;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MempoolConfig {
+    /// The maximum number of transactions that may be awaiting execution in the mempool. When full, the transaction
+    /// offering the lowest fee (oldest first) is evicted to make room for one that offers a higher fee.
+    pub max_pool_size: usize,
+    /// The maximum size in bytes of an encoded transaction
+    pub max_transaction_size: usize,
+    /// The number of new transactions per second that a peer may gossip to this node
+    pub peer_submissions_per_second: u32,
+    /// The number of new transactions that a peer may gossip in a burst before being rate limited
+    pub peer_submission_burst: u32,
+    /// The maximum number of transactions from a single signer that may be awaiting execution in the mempool
+    pub max_pending_per_signer: usize,
+    /// Transactions that have been awaiting execution for longer than this are evicted
+    #[serde(with = "serializers::seconds")]
+    pub stale_transaction_timeout: Duration,
+    /// The number of invalid transactions a peer may send within the invalid transaction window before it is banned
+    pub max_invalid_transactions_per_peer: u32,
+    /// The window over which invalid transactions from a peer are counted
+    #[serde(with = "serializers::seconds")]
+    pub invalid_transaction_window: Duration,
+    /// The time that a peer that floods invalid transactions is banned for
+    #[serde(with = "serializers::seconds")]
+    pub peer_ban_duration: Duration,
+}
+
+impl Default for MempoolConfig {
+    fn default() -> Self {
+        Self {
+            max_pool_size: 10_000,
+            max_transaction_size: 512 * 1024,
+            peer_submissions_per_second: 100,
+            peer_submission_burst: 500,
+            max_pending_per_signer: 100,
+            stale_transaction_timeout: Duration::from_secs(5 * 60),
+            max_invalid_transactions_per_peer: 20,
+            invalid_transaction_window: Duration::from_secs(60),
+            peer_ban_duration: Duration::from_secs(60 * 60),
+        }
+    }
+}
+
+/// Keeps track of the transactions admitted into the mempool and the peers that send them, enforcing the limits in
+/// [MempoolConfig].
+#[derive(Debug)]
+pub(super) struct MempoolAdmission {
+    config: MempoolConfig,
+    entries: HashMap<TransactionId, PoolEntry>,
+    pending_per_signer: HashMap<PublicKey, usize>,
+    peers: HashMap<CommsPublicKey, PeerState>,
+    evicted: HashSet<TransactionId>,
+}
+
+/// Returned when a transaction passes the admission checks. The ticket is tracked once the transaction is in the pool.
+#[derive(Debug)]
+pub(super) struct AdmissionTicket {
+    transaction_id: TransactionId,
+    signer: PublicKey,
+    fee: u64,
+    /// The transaction that must be evicted to make room for this one
+    evict: Option<TransactionId>,
+}
+
+impl AdmissionTicket {
+    pub fn take_eviction(&mut self) -> Option<TransactionId> {
+        self.evict.take()
+    }
+}
+
+#[derive(Debug)]
+struct PoolEntry {
+    signer: PublicKey,
+    fee: u64,
+    admitted_at: Instant,
+}
+
+#[derive(Debug)]
+struct PeerState {
+    tokens: f64,
+    last_refill: Instant,
+    invalid_count: u32,
+    invalid_window_start: Instant,
+}
+
+impl PeerState {
+    fn new(tokens: f64, now: Instant) -> Self {
+        Self {
+            tokens,
+            last_refill: now,
+            invalid_count: 0,
+            invalid_window_start: now,
+        }
+    }
+}
+
+impl MempoolAdmission {
+    pub fn new(config: MempoolConfig) -> Self {
+        Self {
+            config,
+            entries: HashMap::new(),
+            pending_per_signer: HashMap::new(),
+            peers: HashMap::new(),
+            evicted: HashSet::new(),
+        }
+    }
+
+    pub fn config(&self) -> &MempoolConfig {
+        &self.config
+    }
+
+    /// Takes a token from the peer's bucket, returning false if the peer has exceeded its submission rate.
+    pub fn try_acquire_peer_submission(&mut self, peer: &CommsPublicKey) -> bool {
+        let now = Instant::now();
+        let burst = f64::from(self.config.peer_submission_burst.max(1));
+        let rate = f64::from(self.config.peer_submissions_per_second);
+        let state = self
+            .peers
+            .entry(peer.clone())
+            .or_insert_with(|| PeerState::new(burst, now));
+
+        let elapsed = now.duration_since(state.last_refill).as_secs_f64();
+        state.tokens = (state.tokens + elapsed * rate).min(burst);
+        state.last_refill = now;
+        if state.tokens < 1.0 {
+            return false;
+        }
+        state.tokens -= 1.0;
+        true
+    }
+
+    /// Records an invalid transaction from the peer, returning true if the peer has exceeded the number of invalid
+    /// transactions allowed within the window and should be banned.
+    pub fn record_invalid_transaction(&mut self, peer: &CommsPublicKey) -> bool {
+        let now = Instant::now();
+        let burst = f64::from(self.config.peer_submission_burst.max(1));
+        let state = self
+            .peers
+            .entry(peer.clone())
+            .or_insert_with(|| PeerState::new(burst, now));
+
+        if now.duration_since(state.invalid_window_start) > self.config.invalid_transaction_window {
+            state.invalid_count = 0;
+            state.invalid_window_start = now;
+        }
+        state.invalid_count += 1;
+        if state.invalid_count > self.config.max_invalid_transactions_per_peer {
+            self.peers.remove(peer);
+            return true;
+        }
+        false
+    }
+
+    /// Checks the size and signer limits for the transaction. If the pool is full, the ticket contains the transaction
+    /// to evict in order to make room, or an error is returned if every transaction in the pool offers at least the
+    /// same fee.
+    pub fn check_transaction(&self, transaction: &Transaction) -> Result<AdmissionTicket, MempoolError> {
+        let size = tari_bor::encode(transaction)
+            .map_err(|e| MempoolError::InvalidTransactionEncoding {
+                transaction_id: *transaction.id(),
+                details: e.to_string(),
+            })?
+            .len();
+        if size > self.config.max_transaction_size {
+            return Err(MempoolError::TransactionTooLarge {
+                transaction_id: *transaction.id(),
+                size,
+                max_size: self.config.max_transaction_size,
+            });
+        }
+
+        let pending = self
+            .pending_per_signer
+            .get(transaction.signer_public_key())
+            .copied()
+            .unwrap_or(0);
+        if pending >= self.config.max_pending_per_signer {
+            return Err(MempoolError::SignerPendingLimitExceeded {
+                transaction_id: *transaction.id(),
+                signer: transaction.signer_public_key().clone(),
+                max_pending: self.config.max_pending_per_signer,
+            });
+        }
+
+        let mut ticket = AdmissionTicket {
+            transaction_id: *transaction.id(),
+            signer: transaction.signer_public_key().clone(),
+            fee: offered_fee(transaction),
+            evict: None,
+        };
+        if self.entries.len() < self.config.max_pool_size {
+            return Ok(ticket);
+        }
+
+        // Evict the transaction with the lowest fee, preferring the least recently admitted
+        let candidate = self
+            .entries
+            .iter()
+            .min_by_key(|(_, entry)| (entry.fee, entry.admitted_at))
+            .filter(|(_, entry)| entry.fee < ticket.fee)
+            .map(|(id, _)| *id);
+
+        match candidate {
+            Some(id) => {
+                ticket.evict = Some(id);
+                Ok(ticket)
+            },
+            None => Err(MempoolError::MempoolFull {
+                transaction_id: *transaction.id(),
+                max_pool_size: self.config.max_pool_size,
+            }),
+        }
+    }
+
+    pub fn track(&mut self, ticket: AdmissionTicket) {
+        let entry = PoolEntry {
+            signer: ticket.signer,
+            fee: ticket.fee,
+            admitted_at: Instant::now(),
+        };
+        *self.pending_per_signer.entry(entry.signer.clone()).or_default() += 1;
+        if let Some(prev) = self.entries.insert(ticket.transaction_id, entry) {
+            self.decrement_signer(&prev.signer);
+        }
+    }
+
+    pub fn untrack(&mut self, id: &TransactionId) {
+        if let Some(entry) = self.entries.remove(id) {
+            self.decrement_signer(&entry.signer);
+        }
+    }
+
+    /// Stops tracking the transaction and remembers that it was evicted so that its execution result is discarded
+    pub fn evict(&mut self, id: &TransactionId) {
+        self.untrack(id);
+        self.evicted.insert(*id);
+    }
+
+    /// Returns true (once) if the transaction was evicted while it was executing
+    pub fn take_evicted(&mut self, id: &TransactionId) -> bool {
+        self.evicted.remove(id)
+    }
+
+    /// Returns the transactions that have been awaiting execution for longer than the stale transaction timeout
+    pub fn stale_transactions(&self) -> Vec<TransactionId> {
+        let timeout = self.config.stale_transaction_timeout;
+        self.entries
+            .iter()
+            .filter(|(_, entry)| entry.admitted_at.elapsed() > timeout)
+            .map(|(id, _)| *id)
+            .collect()
+    }
+
+    /// Forgets peers that have a full bucket and no recent invalid transactions
+    pub fn prune_idle_peers(&mut self) {
+        let burst = f64::from(self.config.peer_submission_burst.max(1));
+        let rate = f64::from(self.config.peer_submissions_per_second);
+        let window = self.config.invalid_transaction_window;
+        self.peers.retain(|_, state| {
+            let tokens = state.tokens + state.last_refill.elapsed().as_secs_f64() * rate;
+            tokens < burst || (state.invalid_count > 0 && state.invalid_window_start.elapsed() <= window)
+        });
+    }
+
+    fn decrement_signer(&mut self, signer: &PublicKey) {
+        if let Some(count) = self.pending_per_signer.get_mut(signer) {
+            *count = count.saturating_sub(1);
+            if *count == 0 {
+                self.pending_per_signer.remove(signer);
+            }
+        }
+    }
+}
+
+/// The maximum fee offered by the transaction's `pay_fee` instructions, which are covered by the transaction signature.
+/// Fees paid confidentially are not known before execution and count as zero.
+fn offered_fee(transaction: &Transaction) -> u64 {
+    transaction
+        .fee_instructions()
+        .iter()
+        .filter_map(|instruction| match instruction {
+            Instruction::CallMethod { method, args, .. } if method == "pay_fee" => match args.first() {
+                Some(Arg::Literal(bytes)) => tari_bor::decode_exact::<Amount>(bytes).ok(),
+                _ => None,
+            },
+            _ => None,
+        })
+        .filter_map(|amount| amount.as_u64_checked())
+        .fold(0u64, |acc, fee| acc.saturating_add(fee))
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::OsRng;
+    use tari_common_types::types::PrivateKey;
+    use tari_crypto::keys::{PublicKey as _, SecretKey};
+    use tari_template_lib::models::ComponentAddress;
+
+    use super::*;
+
+    fn transaction_with_fee(signer: &PrivateKey, fee: i64) -> Transaction {
+        Transaction::builder()
+            .fee_transaction_pay_from_component(ComponentAddress::from_array([1u8; 32]), Amount(fee))
+            .sign(signer)
+            .build()
+    }
+
+    fn admit(admission: &mut MempoolAdmission, transaction: &Transaction) {
+        let ticket = admission.check_transaction(transaction).unwrap();
+        assert!(ticket.evict.is_none());
+        admission.track(ticket);
+        // Ensure that entries are admitted at distinct instants
+        std::thread::sleep(Duration::from_millis(2));
+    }
+
+    fn random_peer() -> CommsPublicKey {
+        CommsPublicKey::from_secret_key(&PrivateKey::random(&mut OsRng))
+    }
+
+    #[test]
+    fn it_evicts_the_oldest_transaction_offering_the_lowest_fee() {
+        let mut admission = MempoolAdmission::new(MempoolConfig {
+            max_pool_size: 3,
+            ..Default::default()
+        });
+        let signer = PrivateKey::random(&mut OsRng);
+        let high = transaction_with_fee(&signer, 100);
+        let oldest_low = transaction_with_fee(&signer, 10);
+        let newest_low = transaction_with_fee(&signer, 10);
+        admit(&mut admission, &high);
+        admit(&mut admission, &oldest_low);
+        admit(&mut admission, &newest_low);
+
+        let mut ticket = admission.check_transaction(&transaction_with_fee(&signer, 50)).unwrap();
+        assert_eq!(ticket.take_eviction(), Some(*oldest_low.id()));
+
+        // A transaction that does not offer more than the lowest fee in the pool is rejected
+        let err = admission
+            .check_transaction(&transaction_with_fee(&signer, 10))
+            .unwrap_err();
+        assert!(matches!(err, MempoolError::MempoolFull { .. }));
+
+        admission.evict(oldest_low.id());
+        assert!(admission.take_evicted(oldest_low.id()));
+        assert!(!admission.take_evicted(oldest_low.id()));
+        assert!(admission.check_transaction(&transaction_with_fee(&signer, 1)).is_ok());
+    }
+
+    #[test]
+    fn it_limits_the_pending_transactions_per_signer() {
+        let mut admission = MempoolAdmission::new(MempoolConfig {
+            max_pending_per_signer: 1,
+            ..Default::default()
+        });
+        let signer = PrivateKey::random(&mut OsRng);
+        let first = transaction_with_fee(&signer, 1);
+        admit(&mut admission, &first);
+
+        let err = admission
+            .check_transaction(&transaction_with_fee(&signer, 2))
+            .unwrap_err();
+        assert!(matches!(err, MempoolError::SignerPendingLimitExceeded { .. }));
+        let other_signer = PrivateKey::random(&mut OsRng);
+        assert!(admission
+            .check_transaction(&transaction_with_fee(&other_signer, 2))
+            .is_ok());
+
+        admission.untrack(first.id());
+        assert!(admission.check_transaction(&transaction_with_fee(&signer, 2)).is_ok());
+    }
+
+    #[test]
+    fn it_rate_limits_peer_submissions() {
+        let mut admission = MempoolAdmission::new(MempoolConfig {
+            peer_submissions_per_second: 0,
+            peer_submission_burst: 2,
+            ..Default::default()
+        });
+        let peer = random_peer();
+        assert!(admission.try_acquire_peer_submission(&peer));
+        assert!(admission.try_acquire_peer_submission(&peer));
+        assert!(!admission.try_acquire_peer_submission(&peer));
+        // Other peers have their own bucket
+        assert!(admission.try_acquire_peer_submission(&random_peer()));
+    }
+
+    #[test]
+    fn it_bans_peers_that_send_too_many_invalid_transactions() {
+        let mut admission = MempoolAdmission::new(MempoolConfig {
+            max_invalid_transactions_per_peer: 2,
+            invalid_transaction_window: Duration::from_secs(60),
+            ..Default::default()
+        });
+        let peer = random_peer();
+        assert!(!admission.record_invalid_transaction(&peer));
+        assert!(!admission.record_invalid_transaction(&peer));
+        assert!(admission.record_invalid_transaction(&peer));
+        // The peer's state is reset once it is banned
+        assert!(!admission.record_invalid_transaction(&peer));
+    }
+
+    #[test]
+    fn it_resets_the_invalid_transaction_count_after_the_window() {
+        let mut admission = MempoolAdmission::new(MempoolConfig {
+            max_invalid_transactions_per_peer: 1,
+            invalid_transaction_window: Duration::from_millis(10),
+            ..Default::default()
+        });
+        let peer = random_peer();
+        assert!(!admission.record_invalid_transaction(&peer));
+        std::thread::sleep(Duration::from_millis(20));
+        assert!(!admission.record_invalid_transaction(&peer));
+    }
+}
@@ -1,11 +1,12 @@
 //    Copyright 2023 The Tari Project
 //    SPDX-License-Identifier: BSD-3-Clause
 
+use tari_common_types::types::PublicKey;
 use tari_dan_app_utilities::{
     template_manager::interface::TemplateManagerError,
     transaction_executor::TransactionProcessorError,
 };
-use tari_dan_common_types::Epoch;
+use tari_dan_common_types::{Epoch, ShardId};
 use tari_dan_storage::{consensus_models::TransactionPoolError, StorageError};
 use tari_epoch_manager::EpochManagerError;
 use tari_transaction::TransactionId;
@@ -59,10 +60,68 @@ pub enum MempoolError {
     CurrentEpochLessThanMinimum { current_epoch: Epoch, min_epoch: Epoch },
     #[error("Current epoch ({current_epoch}) is greater than maximum epoch ({max_epoch}) required for transaction")]
     CurrentEpochGreaterThanMaximum { current_epoch: Epoch, max_epoch: Epoch },
+    #[error("Transaction {transaction_id} has an invalid signature")]
+    InvalidSignature { transaction_id: TransactionId },
+    #[error("Transaction {transaction_id} contains input {shard_id} more than once")]
+    DuplicateInput {
+        transaction_id: TransactionId,
+        shard_id: ShardId,
+    },
+    #[error("Transaction {transaction_id} has a minimum epoch {min_epoch} greater than its maximum epoch {max_epoch}")]
+    InvalidEpochRange {
+        transaction_id: TransactionId,
+        min_epoch: Epoch,
+        max_epoch: Epoch,
+    },
     #[error("Transaction {transaction_id} does not have any inputs")]
     NoInputs { transaction_id: TransactionId },
     #[error("Executed transaction {transaction_id} does not involved any shards")]
     NoInvolvedShards { transaction_id: TransactionId },
+    #[error("Transaction {transaction_id} could not be encoded: {details}")]
+    InvalidTransactionEncoding {
+        transaction_id: TransactionId,
+        details: String,
+    },
+    #[error("Transaction {transaction_id} is {size} bytes which exceeds the maximum of {max_size} bytes")]
+    TransactionTooLarge {
+        transaction_id: TransactionId,
+        size: usize,
+        max_size: usize,
+    },
+    #[error(
+        "Transaction {transaction_id} rejected because signer {signer} already has {max_pending} transactions pending"
+    )]
+    SignerPendingLimitExceeded {
+        transaction_id: TransactionId,
+        signer: PublicKey,
+        max_pending: usize,
+    },
+    #[error(
+        "Transaction {transaction_id} rejected because the mempool is full ({max_pool_size} transactions) and it does \
+         not offer a higher fee than any pending transaction"
+    )]
+    MempoolFull {
+        transaction_id: TransactionId,
+        max_pool_size: usize,
+    },
+}
+
+impl MempoolError {
+    /// Returns true if the error is due to the transaction itself being invalid, rather than a local failure or a
+    /// condition that may differ between nodes (e.g. the current epoch or a template that has not been synced yet).
+    pub fn is_invalid_transaction(&self) -> bool {
+        matches!(
+            self,
+            Self::NoFeeInstructions |
+                Self::ValidatorFeeClaimEpochInvalid { .. } |
+                Self::InvalidSignature { .. } |
+                Self::DuplicateInput { .. } |
+                Self::InvalidEpochRange { .. } |
+                Self::NoInputs { .. } |
+                Self::InvalidTransactionEncoding { .. } |
+                Self::TransactionTooLarge { .. }
+        )
+    }
 }
 
 impl From<mpsc::error::SendError<MempoolRequest>> for MempoolError {
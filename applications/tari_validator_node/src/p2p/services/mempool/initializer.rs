@@ -28,15 +28,23 @@ use tari_dan_app_utilities::transaction_executor::{TransactionExecutor, Transact
 use tari_dan_p2p::NewTransactionMessage;
 use tari_dan_storage::consensus_models::ExecutedTransaction;
 use tari_epoch_manager::base_layer::EpochManagerHandle;
-use tari_state_store_sqlite::SqliteStateStore;
+use tari_state_store_backend::AnyStateStore;
 use tari_transaction::{Transaction, TransactionId};
 use tokio::{sync::mpsc, task, task::JoinHandle};
 
 use crate::{
     consensus::ConsensusHandle,
     p2p::services::{
-        mempool::{handle::MempoolHandle, service::MempoolService, MempoolError, SubstateResolver, Validator},
+        mempool::{
+            handle::MempoolHandle,
+            service::MempoolService,
+            MempoolConfig,
+            MempoolError,
+            SubstateResolver,
+            Validator,
+        },
         messaging::OutboundMessaging,
+        networking::NetworkingHandle,
     },
     substate_resolver::SubstateResolverError,
 };
@@ -51,9 +59,11 @@ pub fn spawn<TExecutor, TValidator, TExecutedValidator, TSubstateResolver>(
     substate_resolver: TSubstateResolver,
     validator: TValidator,
     after_executed_validator: TExecutedValidator,
-    state_store: SqliteStateStore<PublicKey>,
+    state_store: AnyStateStore<PublicKey>,
     rx_consensus_to_mempool: mpsc::UnboundedReceiver<Transaction>,
     consensus_handle: ConsensusHandle,
+    config: MempoolConfig,
+    networking: NetworkingHandle,
 ) -> (MempoolHandle, JoinHandle<anyhow::Result<()>>)
 where
     TValidator: Validator<Transaction, Error = MempoolError> + Send + Sync + 'static,
@@ -77,6 +87,8 @@ where
         state_store,
         rx_consensus_to_mempool,
         consensus_handle,
+        config,
+        networking,
     );
     let handle = MempoolHandle::new(tx_mempool_request);
 
@@ -26,6 +26,9 @@ pub use handle::{MempoolHandle, MempoolRequest};
 mod initializer;
 pub use initializer::spawn;
 
+mod admission;
+pub use admission::MempoolConfig;
+
 mod error;
 mod executor;
 mod gossip;
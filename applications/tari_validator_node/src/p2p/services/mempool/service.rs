@@ -20,7 +20,7 @@
 //   WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 //   USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use std::{collections::HashSet, fmt::Display, iter, ops::DerefMut, sync::Arc};
+use std::{collections::HashSet, fmt::Display, iter, ops::DerefMut, sync::Arc, time::Duration};
 
 use futures::{future::BoxFuture, stream::FuturesUnordered, FutureExt, StreamExt};
 use log::*;
@@ -34,28 +34,40 @@ use tari_dan_storage::{
     StateStore,
 };
 use tari_epoch_manager::{base_layer::EpochManagerHandle, EpochManagerReader};
-use tari_state_store_sqlite::SqliteStateStore;
+use tari_state_store_backend::AnyStateStore;
 use tari_transaction::{Transaction, TransactionId};
-use tokio::sync::{mpsc, oneshot};
+use tokio::{
+    sync::{mpsc, oneshot},
+    time,
+};
 
 use super::MempoolError;
 use crate::{
     consensus::ConsensusHandle,
+    metrics,
     p2p::services::{
         mempool::{
+            admission::{AdmissionTicket, MempoolAdmission},
             executor::{execute_transaction, ExecutionResult},
             gossip::Gossip,
             handle::MempoolRequest,
             traits::SubstateResolver,
+            MempoolConfig,
+            TransactionSignatureValidator,
+            TransactionStructureValidator,
             Validator,
         },
         messaging::OutboundMessaging,
+        networking::{NetworkingHandle, NetworkingService},
     },
     substate_resolver::SubstateResolverError,
 };
 
 const LOG_TARGET: &str = "tari::validator_node::mempool::service";
 
+/// The time between checks for stale transactions
+const STALE_TRANSACTION_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
 /// Data returned from a pending execution.
 struct MempoolTransactionExecution {
     result: Result<ExecutionResult, MempoolError>,
@@ -75,11 +87,13 @@ pub struct MempoolService<TValidator, TExecutedValidator, TExecutor, TSubstateRe
     after_execute_validator: TExecutedValidator,
     transaction_executor: TExecutor,
     substate_resolver: TSubstateResolver,
-    state_store: SqliteStateStore<PublicKey>,
-    transaction_pool: TransactionPool<SqliteStateStore<PublicKey>>,
+    state_store: AnyStateStore<PublicKey>,
+    transaction_pool: TransactionPool<AnyStateStore<PublicKey>>,
     gossip: Gossip,
     rx_consensus_to_mempool: mpsc::UnboundedReceiver<Transaction>,
     consensus_handle: ConsensusHandle,
+    admission: MempoolAdmission,
+    networking: NetworkingHandle,
 }
 
 impl<TValidator, TExecutedValidator, TExecutor, TSubstateResolver>
@@ -102,9 +116,11 @@ where
         substate_resolver: TSubstateResolver,
         before_execute_validator: TValidator,
         after_execute_validator: TExecutedValidator,
-        state_store: SqliteStateStore<PublicKey>,
+        state_store: AnyStateStore<PublicKey>,
         rx_consensus_to_mempool: mpsc::UnboundedReceiver<Transaction>,
         consensus_handle: ConsensusHandle,
+        config: MempoolConfig,
+        networking: NetworkingHandle,
     ) -> Self {
         Self {
             gossip: Gossip::new(epoch_manager.clone(), outbound, node_identity.public_key().clone()),
@@ -122,10 +138,13 @@ where
             transaction_pool: TransactionPool::new(),
             rx_consensus_to_mempool,
             consensus_handle,
+            admission: MempoolAdmission::new(config),
+            networking,
         }
     }
 
     pub async fn run(mut self) -> anyhow::Result<()> {
+        let mut stale_check = time::interval(STALE_TRANSACTION_CHECK_INTERVAL);
         loop {
             tokio::select! {
                 Some(req) = self.mempool_requests.recv() => self.handle_request(req).await,
@@ -144,12 +163,18 @@ where
                         warn!(target: LOG_TARGET, "Mempool rejected transaction: {}", e);
                     }
                 }
+                _ = stale_check.tick() => {
+                    if let Err(e) = self.evict_stale_transactions() {
+                        error!(target: LOG_TARGET, "Failed to evict stale transactions: {}", e);
+                    }
+                }
 
                 else => {
                     info!(target: LOG_TARGET, "Mempool service shutting down");
                     break;
                 }
             }
+            metrics::MEMPOOL_SIZE.set(self.transactions.len() as i64);
         }
         Ok(())
     }
@@ -163,7 +188,7 @@ where
             } => {
                 handle(
                     reply,
-                    self.handle_new_transaction_from_local(*transaction, should_propagate)
+                    self.handle_new_transaction_from_client(*transaction, should_propagate)
                         .await,
                 );
             },
@@ -178,6 +203,67 @@ where
 
     fn remove_transaction(&mut self, id: &TransactionId) {
         self.transactions.remove(id);
+        self.admission.untrack(id);
+    }
+
+    /// Removes the transaction from the mempool and aborts it. If it is still executing, the execution result is
+    /// discarded.
+    fn evict_transaction(&mut self, id: &TransactionId, reason: &str) -> Result<(), MempoolError> {
+        info!(target: LOG_TARGET, "🗑️ Evicting transaction {} from mempool: {}", id, reason);
+        self.state_store.with_write_tx(|tx| {
+            let mut transaction = TransactionRecord::get(tx.deref_mut(), id)?;
+            // The transaction may have been finalized by sync while it was awaiting execution
+            if transaction.final_decision().is_none() {
+                transaction
+                    .set_abort(format!("Evicted from mempool: {}", reason))
+                    .update(tx)?;
+            }
+            Ok::<_, MempoolError>(())
+        })?;
+        self.transactions.remove(id);
+        self.admission.evict(id);
+        Ok(())
+    }
+
+    fn evict_stale_transactions(&mut self) -> Result<(), MempoolError> {
+        for id in self.admission.stale_transactions() {
+            self.evict_transaction(&id, "transaction is stale")?;
+        }
+        self.admission.prune_idle_peers();
+        Ok(())
+    }
+
+    async fn penalize_peer(&mut self, peer: CommsPublicKey, err: &MempoolError) {
+        if !self.admission.record_invalid_transaction(&peer) {
+            return;
+        }
+        let duration = self.admission.config().peer_ban_duration;
+        let reason = format!("Peer sent too many invalid transactions. Last error: {}", err);
+        if let Err(e) = self.networking.ban_peer(peer.clone(), duration, reason).await {
+            error!(target: LOG_TARGET, "Failed to ban peer {}: {}", peer, e);
+        }
+    }
+
+    /// Handles a transaction submitted to this node by a client, which is subject to the admission limits.
+    async fn handle_new_transaction_from_client(
+        &mut self,
+        transaction: Transaction,
+        should_propagate: bool,
+    ) -> Result<(), MempoolError> {
+        if self.transaction_exists(transaction.id())? {
+            return Ok(());
+        }
+        debug!(
+            target: LOG_TARGET,
+            "Received NEW transaction from client: {} {:?}",
+            transaction.id(),
+            transaction
+        );
+
+        let ticket = self.admission.check_transaction(&transaction)?;
+        self.handle_new_transaction(transaction, vec![], should_propagate, None, Some(ticket))
+            .await?;
+        Ok(())
     }
 
     async fn handle_new_transaction_from_local(
@@ -195,7 +281,7 @@ where
             transaction
         );
 
-        self.handle_new_transaction(transaction, vec![], should_propagate, None)
+        self.handle_new_transaction(transaction, vec![], should_propagate, None, None)
             .await?;
 
         Ok(())
@@ -220,9 +306,20 @@ where
             return Ok(());
         }
 
+        // Peers gossip the same transaction to us, so only new transactions count towards the submission rate
         if self.transaction_exists(transaction.id())? {
             return Ok(());
         }
+
+        if !self.admission.try_acquire_peer_submission(&from) {
+            debug!(
+                target: LOG_TARGET,
+                "🎱 Peer {} exceeded its transaction submission rate. Ignoring transaction {}",
+                from,
+                transaction.id()
+            );
+            return Ok(());
+        }
         debug!(
             target: LOG_TARGET,
             "Received NEW transaction from {}: {} {:?}",
@@ -258,12 +355,38 @@ where
             }
         }
 
-        self.handle_new_transaction(transaction, unverified_output_shards, true, maybe_sender_bucket)
-            .await?;
+        let ticket = match self.admission.check_transaction(&transaction) {
+            Ok(ticket) => ticket,
+            Err(e) => {
+                if e.is_invalid_transaction() {
+                    self.penalize_peer(from, &e).await;
+                }
+                return Err(e);
+            },
+        };
+
+        if let Err(e) = self
+            .handle_new_transaction(
+                transaction,
+                unverified_output_shards,
+                true,
+                maybe_sender_bucket,
+                Some(ticket),
+            )
+            .await
+        {
+            if e.is_invalid_transaction() {
+                self.penalize_peer(from, &e).await;
+            }
+            return Err(e);
+        }
 
         Ok(())
     }
 
+    /// Validates and queues the transaction for execution. If an admission ticket is given, the transaction counts
+    /// towards the admission limits while it is awaiting execution and any eviction needed to make room for it is only
+    /// made once it has passed validation.
     #[allow(clippy::too_many_lines)]
     async fn handle_new_transaction(
         &mut self,
@@ -271,7 +394,15 @@ where
         unverified_output_shards: Vec<ShardId>,
         should_propagate: bool,
         sender_bucket: Option<ShardBucket>,
+        ticket: Option<AdmissionTicket>,
     ) -> Result<(), MempoolError> {
+        // The checks that do not need any state run before the transaction is stored, so that a transaction with an
+        // invalid signature or structure is never recorded
+        TransactionSignatureValidator
+            .and_then(TransactionStructureValidator)
+            .validate(&transaction)
+            .await?;
+
         let mut transaction = TransactionRecord::new(transaction);
         self.state_store.with_write_tx(|tx| transaction.insert(tx))?;
 
@@ -287,23 +418,6 @@ where
         let transaction = transaction.into_transaction();
 
         let current_epoch = self.epoch_manager.current_epoch().await?;
-        if let Some(min_epoch) = transaction.min_epoch() {
-            if current_epoch < min_epoch {
-                return Err(MempoolError::CurrentEpochLessThanMinimum {
-                    current_epoch,
-                    min_epoch,
-                });
-            }
-        }
-
-        if let Some(max_epoch) = transaction.max_epoch() {
-            if current_epoch > max_epoch {
-                return Err(MempoolError::CurrentEpochGreaterThanMaximum {
-                    current_epoch,
-                    max_epoch,
-                });
-            }
-        }
 
         // Get the shards involved in claim fees.
         let fee_claims = transaction.fee_claims().collect::<Vec<_>>();
@@ -338,6 +452,12 @@ where
 
         if is_input_shard || is_output_shard {
             debug!(target: LOG_TARGET, "🎱 New transaction {} in mempool", transaction.id());
+            if let Some(mut ticket) = ticket {
+                if let Some(evict_id) = ticket.take_eviction() {
+                    self.evict_transaction(&evict_id, "replaced by a transaction offering a higher fee")?;
+                }
+                self.admission.track(ticket);
+            }
             self.transactions.insert(*transaction.id());
             self.queue_transaction_for_execution(transaction.clone(), current_epoch, should_propagate, sender_bucket);
 
@@ -455,6 +575,15 @@ where
         // This is due to a bug or possibly db failure only
         let (transaction_id, exec_result) = result?;
 
+        if self.admission.take_evicted(&transaction_id) {
+            debug!(
+                target: LOG_TARGET,
+                "🎱 Transaction {} was evicted while executing. Ignoring result",
+                transaction_id
+            );
+            return Ok(());
+        }
+
         // The avoids the case where:
         // 1. A transaction is received and start executing
         // 2. The node switches to sync mode
@@ -483,6 +612,7 @@ where
                     executed.result().finalize.result,
                     executed.execution_time()
                 );
+                metrics::MEMPOOL_EXECUTION_SECONDS.observe(executed.execution_time().as_secs_f64());
                 let has_involved_shards = executed.num_involved_shards() > 0;
 
                 match self.after_execute_validator.validate(&executed).await {
@@ -512,7 +642,7 @@ where
                             if is_consensus_running &&
                                 !SubstateRecord::exists_for_transaction(tx.deref_mut(), &transaction_id)?
                             {
-                                self.transaction_pool.insert(tx, executed.to_atom())?;
+                                self.transaction_pool.insert(tx, &executed)?;
                             }
                             Ok::<_, MempoolError>(())
                         })?;
@@ -542,7 +672,7 @@ where
                                 is_consensus_running &&
                                 !SubstateRecord::exists_for_transaction(tx.deref_mut(), &transaction_id)?
                             {
-                                self.transaction_pool.insert(tx, executed.to_atom())?;
+                                self.transaction_pool.insert(tx, &executed)?;
                             }
                             Ok::<_, MempoolError>(())
                         })?;
@@ -560,7 +690,7 @@ where
                         "Transaction {} has no involved shards after executing. Ignoring",
                         transaction_id
                     );
-                    self.transactions.remove(&transaction_id);
+                    self.remove_transaction(&transaction_id);
                     return Ok(());
                 }
 
@@ -579,7 +709,7 @@ where
                         .update(tx)
                 })?;
 
-                self.transactions.remove(&transaction_id);
+                self.remove_transaction(&transaction_id);
 
                 return Ok(());
             },
@@ -633,7 +763,7 @@ where
             );
         }
 
-        self.transactions.remove(&transaction_id);
+        self.remove_transaction(&transaction_id);
         Ok(())
     }
 
@@ -0,0 +1,62 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use async_trait::async_trait;
+use log::*;
+use tari_epoch_manager::EpochManagerReader;
+use tari_transaction::Transaction;
+
+use crate::p2p::services::mempool::{MempoolError, Validator};
+
+const LOG_TARGET: &str = "tari::dan::mempool::validators::epoch_range";
+
+/// Refuse to process the transaction if the current epoch is outside of its minimum and maximum epoch.
+#[derive(Debug)]
+pub struct EpochRangeValidator<TEpochManager> {
+    epoch_manager: TEpochManager,
+}
+
+impl<TEpochManager> EpochRangeValidator<TEpochManager> {
+    pub fn new(epoch_manager: TEpochManager) -> Self {
+        Self { epoch_manager }
+    }
+}
+
+#[async_trait]
+impl<TEpochManager: EpochManagerReader> Validator<Transaction> for EpochRangeValidator<TEpochManager> {
+    type Error = MempoolError;
+
+    async fn validate(&self, transaction: &Transaction) -> Result<(), Self::Error> {
+        let current_epoch = self.epoch_manager.current_epoch().await?;
+        if let Some(min_epoch) = transaction.min_epoch() {
+            if current_epoch < min_epoch {
+                debug!(
+                    target: LOG_TARGET,
+                    "EpochRangeValidator - FAIL: Current epoch {} is less than minimum epoch {}", current_epoch, min_epoch
+                );
+                return Err(MempoolError::CurrentEpochLessThanMinimum {
+                    current_epoch,
+                    min_epoch,
+                });
+            }
+        }
+
+        if let Some(max_epoch) = transaction.max_epoch() {
+            if current_epoch > max_epoch {
+                debug!(
+                    target: LOG_TARGET,
+                    "EpochRangeValidator - FAIL: Current epoch {} is greater than maximum epoch {}",
+                    current_epoch,
+                    max_epoch
+                );
+                return Err(MempoolError::CurrentEpochGreaterThanMaximum {
+                    current_epoch,
+                    max_epoch,
+                });
+            }
+        }
+
+        debug!(target: LOG_TARGET, "EpochRangeValidator - OK");
+        Ok(())
+    }
+}
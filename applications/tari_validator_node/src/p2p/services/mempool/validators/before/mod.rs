@@ -1,11 +1,17 @@
 //   Copyright 2023 The Tari Project
 //   SPDX-License-Identifier: BSD-3-Clause
 mod claim_fee_instructions;
+mod epoch_range;
 mod fee;
 mod has_inputs;
+mod signature;
+mod structure;
 mod template_exists;
 
 pub use claim_fee_instructions::*;
+pub use epoch_range::*;
 pub use fee::*;
 pub use has_inputs::*;
+pub use signature::*;
+pub use structure::*;
 pub use template_exists::*;
@@ -0,0 +1,35 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use async_trait::async_trait;
+use log::*;
+use tari_transaction::Transaction;
+
+use crate::p2p::services::mempool::{MempoolError, Validator};
+
+const LOG_TARGET: &str = "tari::dan::mempool::validators::signature";
+
+/// Refuse to process the transaction if its signature over the fee instructions and instructions is not valid for the
+/// signer's public key.
+#[derive(Debug)]
+pub struct TransactionSignatureValidator;
+
+#[async_trait]
+impl Validator<Transaction> for TransactionSignatureValidator {
+    type Error = MempoolError;
+
+    async fn validate(&self, transaction: &Transaction) -> Result<(), Self::Error> {
+        if !transaction
+            .signature()
+            .verify(transaction.fee_instructions(), transaction.instructions())
+        {
+            debug!(target: LOG_TARGET, "TransactionSignatureValidator - FAIL: Invalid signature");
+            return Err(MempoolError::InvalidSignature {
+                transaction_id: *transaction.id(),
+            });
+        }
+
+        debug!(target: LOG_TARGET, "TransactionSignatureValidator - OK");
+        Ok(())
+    }
+}
@@ -0,0 +1,55 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use std::collections::HashSet;
+
+use async_trait::async_trait;
+use log::*;
+use tari_transaction::Transaction;
+
+use crate::p2p::services::mempool::{MempoolError, Validator};
+
+const LOG_TARGET: &str = "tari::dan::mempool::validators::structure";
+
+/// Refuse to process the transaction if an input shard is listed more than once across its inputs, input refs and
+/// filled inputs, or if its epoch range is empty.
+#[derive(Debug)]
+pub struct TransactionStructureValidator;
+
+#[async_trait]
+impl Validator<Transaction> for TransactionStructureValidator {
+    type Error = MempoolError;
+
+    async fn validate(&self, transaction: &Transaction) -> Result<(), Self::Error> {
+        let mut seen = HashSet::with_capacity(transaction.num_involved_shards());
+        if let Some(shard_id) = transaction.all_inputs_iter().find(|s| !seen.insert(*s)) {
+            debug!(
+                target: LOG_TARGET,
+                "TransactionStructureValidator - FAIL: Duplicate input {}", shard_id
+            );
+            return Err(MempoolError::DuplicateInput {
+                transaction_id: *transaction.id(),
+                shard_id: *shard_id,
+            });
+        }
+
+        if let (Some(min_epoch), Some(max_epoch)) = (transaction.min_epoch(), transaction.max_epoch()) {
+            if min_epoch > max_epoch {
+                debug!(
+                    target: LOG_TARGET,
+                    "TransactionStructureValidator - FAIL: Minimum epoch {} is greater than maximum epoch {}",
+                    min_epoch,
+                    max_epoch
+                );
+                return Err(MempoolError::InvalidEpochRange {
+                    transaction_id: *transaction.id(),
+                    min_epoch,
+                    max_epoch,
+                });
+            }
+        }
+
+        debug!(target: LOG_TARGET, "TransactionStructureValidator - OK");
+        Ok(())
+    }
+}
@@ -20,14 +20,23 @@
 //   WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 //   USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
+use std::time::Duration;
+
 use anyhow::anyhow;
 use async_trait::async_trait;
+use tari_comms::types::CommsPublicKey;
 use tokio::sync::{mpsc, oneshot};
 
 use crate::p2p::services::networking::{NetworkingError, NetworkingService};
 
 pub enum NetworkingRequest {
     Announce(oneshot::Sender<Result<(), NetworkingError>>),
+    BanPeer {
+        public_key: CommsPublicKey,
+        duration: Duration,
+        reason: String,
+        reply: oneshot::Sender<Result<(), NetworkingError>>,
+    },
 }
 
 #[derive(Debug, Clone)]
@@ -51,4 +60,23 @@ impl NetworkingService for NetworkingHandle {
             .map_err(|_| anyhow!("Service has shutdown, cannot send request"))?;
         rx.await?
     }
+
+    async fn ban_peer(
+        &mut self,
+        public_key: CommsPublicKey,
+        duration: Duration,
+        reason: String,
+    ) -> Result<(), NetworkingError> {
+        let (tx, rx) = oneshot::channel();
+        self.tx_request
+            .send(NetworkingRequest::BanPeer {
+                public_key,
+                duration,
+                reason,
+                reply: tx,
+            })
+            .await
+            .map_err(|_| anyhow!("Service has shutdown, cannot send request"))?;
+        rx.await?
+    }
 }
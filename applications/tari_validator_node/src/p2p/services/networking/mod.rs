@@ -20,7 +20,7 @@
 //  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 //  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
 use async_trait::async_trait;
 use tari_comms::{
@@ -71,4 +71,11 @@ pub fn spawn(
 #[async_trait]
 pub trait NetworkingService {
     async fn announce(&mut self) -> Result<(), NetworkingError>;
+    /// Disconnects and bans the peer for the given duration
+    async fn ban_peer(
+        &mut self,
+        public_key: CommsPublicKey,
+        duration: Duration,
+        reason: String,
+    ) -> Result<(), NetworkingError>;
 }
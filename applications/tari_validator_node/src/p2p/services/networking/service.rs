@@ -157,6 +157,19 @@ impl Networking {
                     .await;
                 let _ignore = reply.send(res.map_err(Into::into));
             },
+            NetworkingRequest::BanPeer {
+                public_key,
+                duration,
+                reason,
+                reply,
+            } => {
+                warn!(target: LOG_TARGET, "🔨 Banning peer {} for {:.2?}: {}", public_key, duration, reason);
+                let res = self
+                    .connectivity
+                    .ban_peer_until(NodeId::from_public_key(&public_key), duration, reason)
+                    .await;
+                let _ignore = reply.send(res.map_err(Into::into));
+            },
         }
 
         Ok(())
@@ -156,7 +156,7 @@ async fn start(
                             error!(target: LOG_TARGET, "Auto-registration failed for epoch {} with error: {}", epoch, err);
                         }
                     },
-                    EpochManagerEvent::ThisValidatorIsRegistered {..} => {}
+                    EpochManagerEvent::ThisValidatorIsRegistered {..} | EpochManagerEvent::EpochEnding {..} => {}
                 }
             },
             _ = shutdown.wait() => break
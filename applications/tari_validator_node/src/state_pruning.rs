@@ -0,0 +1,190 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use std::time::Duration;
+
+use log::*;
+use serde::{Deserialize, Serialize};
+use tari_common::configuration::serializers;
+use tari_common_types::types::PublicKey;
+use tari_dan_common_types::{optional::Optional, Epoch};
+use tari_dan_storage::{
+    consensus_models::{LastExecuted, LockedBlock},
+    StateStore,
+    StateStoreWriteTransaction,
+    StorageError,
+};
+use tari_epoch_manager::{base_layer::EpochManagerHandle, EpochManagerError, EpochManagerReader};
+use tari_shutdown::ShutdownSignal;
+use tari_state_store_backend::AnyStateStore;
+use tokio::{task, task::JoinHandle, time};
+
+const LOG_TARGET: &str = "tari::dan::validator_node::state_pruning";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct StatePruningConfig {
+    /// Periodically remove consensus state that is no longer needed. Pruned substate history can no longer be queried
+    /// from this node, so this is off by default.
+    pub enabled: bool,
+    /// The time between pruning passes
+    #[serde(with = "serializers::seconds")]
+    pub interval: Duration,
+    /// The number of epochs that destroyed substates are kept for before they are removed
+    pub destroyed_substate_retention_epochs: u64,
+    /// The time that transaction pool history is kept for after the transaction has left the pool
+    #[serde(with = "serializers::seconds")]
+    pub transaction_pool_history_retention: Duration,
+    /// The maximum number of items deleted in each write transaction of a pruning pass
+    pub batch_size: usize,
+}
+
+impl Default for StatePruningConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval: Duration::from_secs(10 * 60),
+            destroyed_substate_retention_epochs: 10,
+            transaction_pool_history_retention: Duration::from_secs(24 * 60 * 60),
+            batch_size: 1000,
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum StatePruningError {
+    #[error("Storage error: {0}")]
+    StorageError(#[from] StorageError),
+    #[error("Epoch manager error: {0}")]
+    EpochManagerError(#[from] EpochManagerError),
+}
+
+#[derive(Debug, Default)]
+struct PruneStats {
+    votes: usize,
+    blocks: usize,
+    substates: usize,
+    pool_history: usize,
+}
+
+pub fn spawn(
+    config: StatePruningConfig,
+    state_store: AnyStateStore<PublicKey>,
+    epoch_manager: EpochManagerHandle,
+    shutdown: ShutdownSignal,
+) -> JoinHandle<Result<(), anyhow::Error>> {
+    info!(
+        target: LOG_TARGET,
+        "✂️ State pruning enabled (interval: {:.2?}, destroyed substate retention: {} epochs, transaction pool \
+         history retention: {:.2?}, batch size: {})",
+        config.interval,
+        config.destroyed_substate_retention_epochs,
+        config.transaction_pool_history_retention,
+        config.batch_size
+    );
+
+    task::spawn(async move {
+        start(config, state_store, epoch_manager, shutdown).await?;
+        Ok(())
+    })
+}
+
+async fn start(
+    config: StatePruningConfig,
+    state_store: AnyStateStore<PublicKey>,
+    epoch_manager: EpochManagerHandle,
+    mut shutdown: ShutdownSignal,
+) -> Result<(), StatePruningError> {
+    let mut interval = time::interval(config.interval);
+    interval.set_missed_tick_behavior(time::MissedTickBehavior::Delay);
+
+    loop {
+        tokio::select! {
+            _ = interval.tick() => {
+                if let Err(err) = prune(&config, &state_store, &epoch_manager).await {
+                    error!(target: LOG_TARGET, "State pruning failed: {}", err);
+                }
+            },
+            _ = shutdown.wait() => break
+        }
+    }
+
+    Ok(())
+}
+
+async fn prune(
+    config: &StatePruningConfig,
+    state_store: &AnyStateStore<PublicKey>,
+    epoch_manager: &EpochManagerHandle,
+) -> Result<(), StatePruningError> {
+    let current_epoch = epoch_manager.current_epoch().await?;
+    // Destroyed substates are kept for the retention period so that late requests for them can still be answered
+    let substate_epoch = current_epoch
+        .checked_sub(Epoch(config.destroyed_substate_retention_epochs))
+        .filter(|epoch| !epoch.is_zero());
+
+    let (locked, last_executed) = state_store.with_read_tx(|tx| {
+        Ok::<_, StorageError>((LockedBlock::get(tx).optional()?, LastExecuted::get(tx).optional()?))
+    })?;
+
+    let batch_size = config.batch_size.max(1);
+    let mut stats = PruneStats::default();
+    // Votes are only needed until the block they are for has been locked
+    if let Some(locked) = locked {
+        stats.votes = prune_in_batches(state_store, batch_size, |tx| {
+            tx.votes_prune_up_to_height(locked.height(), batch_size)
+        })
+        .await?;
+    }
+    // Any uncommitted block at or below the last executed height is on an abandoned fork
+    if let Some(last_executed) = last_executed {
+        stats.blocks = prune_in_batches(state_store, batch_size, |tx| {
+            tx.blocks_prune_uncommitted_up_to_height(last_executed.height, batch_size)
+        })
+        .await?;
+    }
+    if let Some(epoch) = substate_epoch {
+        stats.substates = prune_in_batches(state_store, batch_size, |tx| {
+            tx.substates_prune_destroyed_before_epoch(epoch, batch_size)
+        })
+        .await?;
+    }
+    stats.pool_history = prune_in_batches(state_store, batch_size, |tx| {
+        tx.transaction_pool_history_prune(config.transaction_pool_history_retention, batch_size)
+    })
+    .await?;
+
+    info!(
+        target: LOG_TARGET,
+        "✂️ Pruned {} vote(s), {} abandoned block(s), {} destroyed substate(s) and {} transaction pool history \
+         entries",
+        stats.votes,
+        stats.blocks,
+        stats.substates,
+        stats.pool_history
+    );
+
+    Ok(())
+}
+
+/// Calls `prune_batch` in a new write transaction until it deletes fewer than `batch_size` items, so that consensus is
+/// never blocked behind a single large pruning transaction. Returns the total number of items deleted.
+async fn prune_in_batches<F>(
+    state_store: &AnyStateStore<PublicKey>,
+    batch_size: usize,
+    mut prune_batch: F,
+) -> Result<usize, StorageError>
+where
+    F: FnMut(&mut <AnyStateStore<PublicKey> as StateStore>::WriteTransaction<'_>) -> Result<usize, StorageError>,
+{
+    let mut total = 0;
+    loop {
+        let num_deleted = state_store.with_write_tx(&mut prune_batch)?;
+        total += num_deleted;
+        if num_deleted < batch_size {
+            return Ok(total);
+        }
+        // Let other tasks, including consensus, take the write lock between batches
+        task::yield_now().await;
+    }
+}
@@ -6,16 +6,21 @@ use std::{collections::HashSet, time::Instant};
 use async_trait::async_trait;
 use log::*;
 use tari_comms::types::CommsPublicKey;
-use tari_dan_common_types::{Epoch, ShardId};
+use tari_dan_common_types::{optional::Optional, Epoch, ShardId};
 use tari_dan_engine::{runtime::VirtualSubstates, state_store::memory::MemoryStateStore};
 use tari_dan_storage::{consensus_models::SubstateRecord, StateStore, StorageError};
 use tari_engine_types::{
+    indexed_value::IndexedValueError,
     instruction::Instruction,
-    substate::SubstateAddress,
+    substate::{Substate, SubstateAddress},
     virtual_substate::{VirtualSubstate, VirtualSubstateAddress},
 };
 use tari_epoch_manager::{EpochManagerError, EpochManagerReader};
-use tari_indexer_lib::{error::IndexerError, substate_scanner::SubstateScanner};
+use tari_indexer_lib::{
+    error::IndexerError,
+    substate_decoder::fetch_substate_and_references,
+    substate_scanner::SubstateScanner,
+};
 use tari_transaction::Transaction;
 use tari_validator_node_rpc::client::{SubstateResult, ValidatorNodeClientFactory};
 
@@ -26,6 +31,10 @@ use crate::{
 
 const LOG_TARGET: &str = "tari::dan::substate_resolver";
 
+/// How many levels of references (e.g. component -> vault -> resource) are followed when resolving the substates for
+/// a view call
+const MAX_VIEW_CALL_SUBSTATE_DEPTH: usize = 2;
+
 #[derive(Debug, Clone)]
 pub struct TariSubstateResolver<TStateStore, TEpochManager, TValidatorNodeClientFactory> {
     store: TStateStore,
@@ -116,6 +125,63 @@ where
         Ok(())
     }
 
+    /// Resolves the latest version of a component and the substates that it references into `out`, preferring local
+    /// state and falling back to the committee that holds the substate. Referenced substates that cannot be found are
+    /// skipped, in which case the view call fails if it needs them.
+    pub async fn resolve_for_view_call(
+        &self,
+        component_address: SubstateAddress,
+        out: &MemoryStateStore,
+    ) -> Result<(), SubstateResolverError> {
+        let substates = fetch_substate_and_references(
+            component_address.clone(),
+            MAX_VIEW_CALL_SUBSTATE_DEPTH,
+            |address| async move {
+                let substate = self.get_latest_substate(&address).await?;
+                if substate.is_none() {
+                    warn!(target: LOG_TARGET, "Referenced substate {} not found for view call", address);
+                }
+                Ok::<_, SubstateResolverError>(substate)
+            },
+        )
+        .await?;
+        if !substates.contains_key(&component_address) {
+            return Err(SubstateResolverError::SubstateNotFound {
+                address: component_address,
+            });
+        }
+        out.set_all(substates);
+
+        Ok(())
+    }
+
+    pub fn current_epoch_virtual_substates(&self, current_epoch: Epoch) -> VirtualSubstates {
+        let mut virtual_substates = VirtualSubstates::new();
+        virtual_substates.insert(
+            VirtualSubstateAddress::CurrentEpoch,
+            VirtualSubstate::CurrentEpoch(current_epoch.as_u64()),
+        );
+        virtual_substates
+    }
+
+    async fn get_latest_substate(&self, address: &SubstateAddress) -> Result<Option<Substate>, SubstateResolverError> {
+        let latest = self
+            .store
+            .with_read_tx(|tx| SubstateRecord::get_latest_version(tx, address).optional())?;
+
+        let version_hint = match latest {
+            Some(substate) if !substate.is_destroyed() => return Ok(Some(substate.into_substate())),
+            // The next version may be held by another committee
+            Some(substate) => Some(substate.version() + 1),
+            None => None,
+        };
+
+        match self.scanner.get_substate(address, version_hint).await? {
+            SubstateResult::Up { substate, .. } => Ok(Some(substate)),
+            SubstateResult::Down { .. } | SubstateResult::DoesNotExist => Ok(None),
+        }
+    }
+
     async fn resolve_remote_virtual_substates(
         &self,
         claim_instructions: Vec<(Epoch, CommsPublicKey, ShardId)>,
@@ -189,11 +255,7 @@ where
             })
             .collect::<Vec<_>>();
 
-        let mut virtual_substates = VirtualSubstates::new();
-        virtual_substates.insert(
-            VirtualSubstateAddress::CurrentEpoch,
-            VirtualSubstate::CurrentEpoch(current_epoch.as_u64()),
-        );
+        let virtual_substates = self.current_epoch_virtual_substates(current_epoch);
 
         if claim_instructions.is_empty() {
             return Ok(virtual_substates);
@@ -245,12 +307,16 @@ pub enum SubstateResolverError {
     IndexerError(#[from] IndexerError),
     #[error("Input substate does not exist: {shard}")]
     InputSubstateDoesNotExist { shard: ShardId },
+    #[error("Substate {address} not found")]
+    SubstateNotFound { address: SubstateAddress },
     #[error("Input substate is downed: {address} (version: {version})")]
     InputSubstateDowned { address: SubstateAddress, version: u32 },
     #[error("Virtual substate error: {0}")]
     VirtualSubstateError(#[from] VirtualSubstateError),
     #[error("Epoch manager error: {0}")]
     EpochManagerError(#[from] EpochManagerError),
+    #[error("Failed to decode substate: {0}")]
+    IndexedValueError(#[from] IndexedValueError),
     #[error("Unauthorized fee claim: validator node {validator_address} (transaction signed by: {signer})")]
     UnauthorizedFeeClaim {
         validator_address: CommsPublicKey,
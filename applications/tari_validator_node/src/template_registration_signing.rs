@@ -26,6 +26,9 @@ use tari_common_types::types::{PrivateKey, PublicKey, Signature};
 use tari_core::{consensus::DomainSeparatedConsensusHasher, transactions::TransactionHashDomain};
 use tari_crypto::keys::PublicKey as PublicKeyT;
 
+const TEMPLATE_REGISTRATION_LABEL: &str = "template_registration";
+const DEV_TEMPLATE_REGISTRATION_LABEL: &str = "dev_template_registration";
+
 // TODO: Find a neat way to encapsulated this signature so that it can be used by the validator node and the base layer
 // TODO: Should we include more fields in the signature?
 // signature validation
@@ -34,16 +37,114 @@ pub fn sign_template_registration(private_key: &PrivateKey, binary_hash: Vec<u8>
     let public_key = PublicKey::from_secret_key(private_key);
     // TODO: epoch should be committed to, but this is currently not the case on the base node, so we leave it out for
     //       now so that the transaction passes validation.
-    let challenge = construct_challenge(&public_key, &public_nonce, &binary_hash, b"");
+    let challenge = construct_challenge(
+        TEMPLATE_REGISTRATION_LABEL,
+        &public_key,
+        &public_nonce,
+        &binary_hash,
+        b"",
+    );
     Signature::sign_raw_uniform(private_key, secret_nonce, &challenge)
         .expect("Sign cannot fail with 32-byte challenge and a RistrettoPublicKey")
 }
 
-fn construct_challenge(public_key: &PublicKey, public_nonce: &PublicKey, binary_hash: &[u8], msg: &[u8]) -> [u8; 64] {
-    DomainSeparatedConsensusHasher::<TransactionHashDomain, Blake2b<U64>>::new("template_registration")
+/// Signs a template registered directly with validator nodes on a development network. The challenge is domain
+/// separated from base layer template registrations so that a development signature can never be used as one.
+pub fn sign_dev_template_registration(private_key: &PrivateKey, binary_hash: &[u8], template_name: &str) -> Signature {
+    let (secret_nonce, public_nonce) = PublicKey::random_keypair(&mut OsRng);
+    let public_key = PublicKey::from_secret_key(private_key);
+    let challenge = construct_challenge(
+        DEV_TEMPLATE_REGISTRATION_LABEL,
+        &public_key,
+        &public_nonce,
+        binary_hash,
+        template_name.as_bytes(),
+    );
+    Signature::sign_raw_uniform(private_key, secret_nonce, &challenge)
+        .expect("Sign cannot fail with 32-byte challenge and a RistrettoPublicKey")
+}
+
+pub fn verify_dev_template_registration(
+    public_key: &PublicKey,
+    signature: &Signature,
+    binary_hash: &[u8],
+    template_name: &str,
+) -> bool {
+    let challenge = construct_challenge(
+        DEV_TEMPLATE_REGISTRATION_LABEL,
+        public_key,
+        signature.get_public_nonce(),
+        binary_hash,
+        template_name.as_bytes(),
+    );
+    signature.verify_raw_uniform(public_key, &challenge)
+}
+
+fn construct_challenge(
+    label: &'static str,
+    public_key: &PublicKey,
+    public_nonce: &PublicKey,
+    binary_hash: &[u8],
+    msg: &[u8],
+) -> [u8; 64] {
+    DomainSeparatedConsensusHasher::<TransactionHashDomain, Blake2b<U64>>::new(label)
         .chain(public_key)
         .chain(public_nonce)
         .chain(&binary_hash)
         .chain(&msg)
         .finalize()
 }
+
+#[cfg(test)]
+mod tests {
+    use tari_common_types::types::FixedHash;
+
+    use super::*;
+
+    fn keypair() -> (PrivateKey, PublicKey) {
+        PublicKey::random_keypair(&mut OsRng)
+    }
+
+    #[test]
+    fn it_verifies_a_dev_template_signature() {
+        let (secret, public) = keypair();
+        let binary_hash = FixedHash::zero();
+        let signature = sign_dev_template_registration(&secret, binary_hash.as_slice(), "counter");
+        assert!(verify_dev_template_registration(
+            &public,
+            &signature,
+            binary_hash.as_slice(),
+            "counter"
+        ));
+        assert!(!verify_dev_template_registration(
+            &public,
+            &signature,
+            binary_hash.as_slice(),
+            "other"
+        ));
+    }
+
+    #[test]
+    fn it_does_not_accept_dev_signatures_as_template_registrations() {
+        let (secret, public) = keypair();
+        let binary_hash = FixedHash::zero();
+
+        let dev_signature = sign_dev_template_registration(&secret, binary_hash.as_slice(), "");
+        let challenge = construct_challenge(
+            TEMPLATE_REGISTRATION_LABEL,
+            &public,
+            dev_signature.get_public_nonce(),
+            binary_hash.as_slice(),
+            b"",
+        );
+        assert!(!dev_signature.verify_raw_uniform(&public, &challenge));
+
+        let signature = sign_template_registration(&secret, binary_hash.to_vec());
+        assert!(!verify_dev_template_registration(
+            &public,
+            &signature,
+            binary_hash.as_slice(),
+            ""
+        ));
+    }
+}
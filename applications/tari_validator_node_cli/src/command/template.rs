@@ -22,6 +22,7 @@
 
 use std::{
     convert::{TryFrom, TryInto},
+    fs,
     path::{Path, PathBuf},
 };
 
@@ -31,7 +32,13 @@ use reqwest::Url;
 use tari_dan_engine::wasm::compile::compile_template;
 use tari_engine_types::{calculate_template_binary_hash, TemplateAddress};
 use tari_validator_node_client::{
-    types::{GetTemplateRequest, GetTemplateResponse, GetTemplatesRequest, TemplateRegistrationRequest},
+    types::{
+        GetTemplateRequest,
+        GetTemplateResponse,
+        GetTemplatesRequest,
+        RegisterDevTemplateRequest,
+        TemplateRegistrationRequest,
+    },
     ValidatorNodeClient,
 };
 
@@ -39,9 +46,14 @@ use crate::{from_hex::FromHex, prompt::Prompt, table::Table, table_row};
 
 #[derive(Debug, Subcommand, Clone)]
 pub enum TemplateSubcommand {
-    Get { template_address: FromHex<TemplateAddress> },
+    Get {
+        template_address: FromHex<TemplateAddress>,
+    },
     List,
     Publish(PublishTemplateArgs),
+    /// Registers a template directly with the validator node without a base layer registration. Only available on
+    /// the local development network.
+    PublishDev(PublishDevTemplateArgs),
 }
 
 #[derive(Debug, Args, Clone)]
@@ -62,6 +74,16 @@ pub struct PublishTemplateArgs {
     pub binary_url: Option<String>,
 }
 
+#[derive(Debug, Args, Clone)]
+pub struct PublishDevTemplateArgs {
+    /// Path to a compiled WASM file or to the root folder of a template crate
+    #[clap(long, short = 'p', alias = "path")]
+    pub template_path: PathBuf,
+
+    #[clap(long, alias = "template-name")]
+    pub template_name: Option<String>,
+}
+
 impl TemplateSubcommand {
     pub async fn handle(self, client: ValidatorNodeClient) -> Result<(), anyhow::Error> {
         #[allow(clippy::enum_glob_use)]
@@ -70,6 +92,7 @@ impl TemplateSubcommand {
             Get { template_address } => handle_get(template_address.into_inner(), client).await?,
             List => handle_list(client).await?,
             Publish(args) => handle_publish(args, client).await?,
+            PublishDev(args) => handle_publish_dev(args, client).await?,
         }
         Ok(())
     }
@@ -228,6 +251,39 @@ async fn handle_publish(args: PublishTemplateArgs, mut client: ValidatorNodeClie
     Ok(())
 }
 
+async fn handle_publish_dev(args: PublishDevTemplateArgs, mut client: ValidatorNodeClient) -> anyhow::Result<()> {
+    let path = args.template_path;
+    let (name, binary) = if path.is_dir() {
+        println!("⏳️ Compiling template...");
+        let wasm_module = compile_template(path.as_path(), &[])?;
+        let (_, cargo_name, _) = parse_cargo_file(path.as_path())?;
+        (cargo_name, wasm_module.code().to_vec())
+    } else {
+        let name = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or_default()
+            .to_string();
+        (name, fs::read(&path)?)
+    };
+    println!("WASM file size: {} bytes", binary.len());
+
+    let template_name = Prompt::new("Choose an user-friendly name for the template (max 32 characters):")
+        .with_default(name)
+        .with_value(args.template_name)
+        .ask()?;
+
+    let resp = client
+        .register_dev_template(RegisterDevTemplateRequest { template_name, binary })
+        .await?;
+    println!("✅ Development template registered");
+    println!();
+    println!("Template address: {}", resp.template_address);
+    println!("Committee members notified: {}", resp.num_peers_notified);
+
+    Ok(())
+}
+
 fn parse_cargo_file(root_path: &Path) -> anyhow::Result<(u16, String, String)> {
     let metadata = MetadataCommand::new()
         .manifest_path(root_path.join("Cargo.toml"))
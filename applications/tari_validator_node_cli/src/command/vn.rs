@@ -20,13 +20,20 @@
 //   WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 //   USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
+use std::path::PathBuf;
+
 use anyhow::anyhow;
 use clap::{Args, Subcommand};
 use tari_common_types::types::PublicKey;
 use tari_crypto::tari_utilities::ByteArray;
 use tari_dan_common_types::Epoch;
 use tari_template_lib::crypto::RistrettoPublicKeyBytes;
-use tari_validator_node_client::{types::GetValidatorFeesRequest, ValidatorNodeClient};
+use tari_validator_node_client::{
+    backup,
+    backup::BackupManifest,
+    types::{CreateBackupRequest, GetValidatorFeesRequest},
+    ValidatorNodeClient,
+};
 
 use crate::{cli_range::CliRange, from_hex::FromHex, table::Table, table_row};
 
@@ -35,6 +42,19 @@ pub enum VnSubcommand {
     Register(RegisterArgs),
     #[clap(alias = "get-fees")]
     GetFeeInfo(GetFeesArgs),
+    #[clap(subcommand)]
+    Backup(BackupSubcommand),
+}
+
+#[derive(Debug, Subcommand, Clone)]
+pub enum BackupSubcommand {
+    /// Takes a backup of the running validator node. The archive is written on the validator node's host.
+    Create(CreateBackupArgs),
+    /// Checks the integrity of a local backup archive
+    Verify(VerifyBackupArgs),
+    /// Restores a local backup archive into a fresh validator node base directory. The validator node should not be
+    /// running.
+    Restore(RestoreBackupArgs),
 }
 
 impl VnSubcommand {
@@ -49,11 +69,89 @@ impl VnSubcommand {
             VnSubcommand::GetFeeInfo(args) => {
                 handle_get_fee_info(args, &mut client).await?;
             },
+            VnSubcommand::Backup(cmd) => {
+                cmd.handle(client).await?;
+            },
+        }
+        Ok(())
+    }
+}
+
+impl BackupSubcommand {
+    pub async fn handle(self, mut client: ValidatorNodeClient) -> Result<(), anyhow::Error> {
+        match self {
+            BackupSubcommand::Create(args) => {
+                let resp = client
+                    .create_backup(CreateBackupRequest {
+                        output_dir: args.output_dir,
+                    })
+                    .await?;
+                println!("✅ Backup created at {}", resp.path.display());
+                print_manifest(&resp.manifest);
+            },
+            BackupSubcommand::Verify(args) => {
+                let manifest = backup::verify_backup(&args.path)?;
+                println!("✅ Backup {} is valid", args.path.display());
+                print_manifest(&manifest);
+            },
+            BackupSubcommand::Restore(args) => {
+                let data_dir = args
+                    .data_dir
+                    .unwrap_or_else(|| args.base_path.join("data").join("validator_node"));
+                let keys_dir = args.keys_dir.unwrap_or(args.base_path);
+                let manifest = backup::restore_backup(&args.path, &data_dir, &keys_dir)?;
+                println!(
+                    "✅ Restored backup into {} (keys: {})",
+                    data_dir.display(),
+                    keys_dir.display()
+                );
+                print_manifest(&manifest);
+            },
         }
         Ok(())
     }
 }
 
+fn print_manifest(manifest: &BackupManifest) {
+    println!("State store: {}", manifest.state_store_backend);
+    println!(
+        "Locked block: {} (height: {}, epoch: {})",
+        manifest.locked_block, manifest.locked_height, manifest.locked_epoch
+    );
+    println!(
+        "Global database: epoch {} (base layer height: {})",
+        manifest.global_db_epoch, manifest.global_db_height
+    );
+    println!("Files: {}", manifest.files.len());
+}
+
+#[derive(Debug, Args, Clone)]
+pub struct CreateBackupArgs {
+    /// The directory to write the archive into on the validator node's host, relative to the node's configured backup
+    /// directory. Defaults to the backup directory.
+    #[clap(long, short = 'o')]
+    output_dir: Option<PathBuf>,
+}
+
+#[derive(Debug, Args, Clone)]
+pub struct VerifyBackupArgs {
+    path: PathBuf,
+}
+
+#[derive(Debug, Args, Clone)]
+pub struct RestoreBackupArgs {
+    path: PathBuf,
+    /// The base directory of the validator node to restore into
+    #[clap(long, short = 'b')]
+    base_path: PathBuf,
+    /// Overrides the data directory to restore the state into (default: <base-path>/data/validator_node)
+    #[clap(long)]
+    data_dir: Option<PathBuf>,
+    /// Overrides the directory to restore the identity and key files into (default: <base-path>)
+    #[clap(long)]
+    keys_dir: Option<PathBuf>,
+}
+
 #[derive(Debug, Args, Clone)]
 pub struct RegisterArgs {
     claim_public_key: FromHex<RistrettoPublicKeyBytes>,
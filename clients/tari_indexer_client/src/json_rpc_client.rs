@@ -32,10 +32,16 @@ use crate::{
         AddAddressRequest,
         AddPeerRequest,
         AddPeerResponse,
+        CallViewMethodRequest,
+        CallViewMethodResponse,
         DeleteAddressRequest,
         GetEpochManagerStatsResponse,
         GetNonFungiblesRequest,
         GetNonFungiblesResponse,
+        GetSubstateAtRequest,
+        GetSubstateAtResponse,
+        GetSubstateHistoryRequest,
+        GetSubstateHistoryResponse,
         GetSubstateRequest,
         GetSubstateResponse,
         GetTransactionResultRequest,
@@ -86,6 +92,20 @@ impl IndexerJsonRpcClient {
         self.send_request("get_substate", req).await
     }
 
+    pub async fn get_substate_at(
+        &mut self,
+        req: GetSubstateAtRequest,
+    ) -> Result<GetSubstateAtResponse, IndexerClientError> {
+        self.send_request("get_substate_at", req).await
+    }
+
+    pub async fn get_substate_history(
+        &mut self,
+        req: GetSubstateHistoryRequest,
+    ) -> Result<GetSubstateHistoryResponse, IndexerClientError> {
+        self.send_request("get_substate_history", req).await
+    }
+
     pub async fn submit_transaction(
         &mut self,
         req: SubmitTransactionRequest,
@@ -93,6 +113,13 @@ impl IndexerJsonRpcClient {
         self.send_request("submit_transaction", req).await
     }
 
+    pub async fn call_view_method(
+        &mut self,
+        req: CallViewMethodRequest,
+    ) -> Result<CallViewMethodResponse, IndexerClientError> {
+        self.send_request("call_view_method", req).await
+    }
+
     pub async fn get_transaction_result(
         &mut self,
         req: GetTransactionResultRequest,
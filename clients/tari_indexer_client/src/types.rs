@@ -7,12 +7,14 @@ use serde_json::Value as JsonValue;
 use serde_with::{serde_as, DisplayFromStr};
 use tari_common_types::types::PublicKey;
 use tari_dan_common_types::Epoch;
-use tari_dan_storage::consensus_models::Decision;
+use tari_dan_storage::consensus_models::{Decision, SubstateQueryPoint, SubstateRecord};
 use tari_engine_types::{
     commit_result::ExecuteResult,
+    instruction_result::InstructionResult,
     serde_with as serde_tools,
     substate::{Substate, SubstateAddress},
 };
+use tari_template_lib::{args::Arg, models::ComponentAddress};
 use tari_transaction::{SubstateRequirement, Transaction, TransactionId};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -33,6 +35,33 @@ pub struct GetSubstateResponse {
     pub created_by_transaction: TransactionId,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GetSubstateAtRequest {
+    #[serde(with = "serde_tools::string")]
+    pub address: SubstateAddress,
+    pub at: SubstateQueryPoint,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GetSubstateAtResponse {
+    /// The version that was live at the query point, or None if the substate did not exist or was down at that point
+    pub substate: Option<SubstateRecord>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GetSubstateHistoryRequest {
+    #[serde(with = "serde_tools::string")]
+    pub address: SubstateAddress,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GetSubstateHistoryResponse {
+    /// All versions of the substate still held by the network, oldest first
+    pub versions: Vec<SubstateRecord>,
+    /// Set if versions destroyed before this epoch may have been pruned by the committees that held them
+    pub pruned_before_epoch: Option<Epoch>,
+}
+
 #[serde_as]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct InspectSubstateRequest {
@@ -56,6 +85,9 @@ pub struct SubmitTransactionRequest {
     pub transaction: Transaction,
     pub required_substates: Vec<SubstateRequirement>,
     pub is_dry_run: bool,
+    /// Include an execution trace in the dry run result
+    #[serde(default)]
+    pub include_execution_trace: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -64,6 +96,23 @@ pub struct SubmitTransactionResponse {
     pub result: IndexerTransactionFinalizedResult,
 }
 
+/// Calls a method that does not mutate state against the latest committed state of a component. The method is
+/// executed by the indexer and no transaction is submitted.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CallViewMethodRequest {
+    pub component_address: ComponentAddress,
+    pub method: String,
+    #[serde(default)]
+    pub args: Vec<Arg>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CallViewMethodResponse {
+    pub result: InstructionResult,
+    /// The return value of the method decoded as JSON
+    pub json_result: JsonValue,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GetTransactionResultRequest {
     pub transaction_id: TransactionId,
@@ -0,0 +1,338 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+//! The validator node backup archive format.
+//!
+//! An archive is a directory containing a `manifest.json` and two subdirectories:
+//! - `state/` holds the copies of the state store and the global database, restored into the node's data directory
+//! - `keys/` holds the node identity, tor identity and shard key files, restored into the node's base directory
+//!
+//! The manifest records the locked block that the state store copy was taken at, the epoch and base layer height that
+//! the global database copy was synced to, and the size and Blake2b hash of every file so that an archive can be
+//! checked before it is restored.
+
+use std::{
+    fs,
+    fs::File,
+    io,
+    io::Read,
+    path::{Path, PathBuf},
+};
+
+use blake2::{digest::consts::U32, Blake2b, Digest};
+use serde::{Deserialize, Serialize};
+use tari_dan_common_types::{Epoch, NodeHeight};
+use tari_dan_storage::consensus_models::BlockId;
+
+pub const BACKUP_MANIFEST_FILE: &str = "manifest.json";
+pub const BACKUP_STATE_DIR: &str = "state";
+pub const BACKUP_KEYS_DIR: &str = "keys";
+pub const BACKUP_FORMAT_VERSION: u32 = 2;
+
+#[derive(Debug, thiserror::Error)]
+pub enum BackupError {
+    #[error("IO error for {path}: {source}")]
+    Io { path: PathBuf, source: io::Error },
+    #[error("Invalid backup manifest: {details}")]
+    InvalidManifest { details: String },
+    #[error("Unsupported backup format version {version} (expected {BACKUP_FORMAT_VERSION})")]
+    UnsupportedFormatVersion { version: u32 },
+    #[error("Integrity check failed for {path}: {details}")]
+    IntegrityCheckFailed { path: PathBuf, details: String },
+    #[error("Restore target {path} is not empty")]
+    TargetNotEmpty { path: PathBuf },
+    #[error("Global database at epoch {global_db_epoch} is behind the state store locked at epoch {locked_epoch}")]
+    GlobalDbBehindStateStore {
+        global_db_epoch: Epoch,
+        locked_epoch: Epoch,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupManifest {
+    pub format_version: u32,
+    /// Unix timestamp in seconds
+    pub created_at: u64,
+    /// The state store backend that the archive was taken from (sqlite or lmdb)
+    pub state_store_backend: String,
+    pub locked_block: BlockId,
+    pub locked_height: NodeHeight,
+    pub locked_epoch: Epoch,
+    /// The current epoch recorded in the global database copy
+    pub global_db_epoch: Epoch,
+    /// The base layer block height that the global database copy was synced to
+    pub global_db_height: u64,
+    pub files: Vec<BackupFile>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupFile {
+    /// The path of the file relative to the archive directory
+    pub path: PathBuf,
+    pub size: u64,
+    /// Hex-encoded Blake2b-256 hash of the file contents
+    pub hash: String,
+}
+
+impl BackupManifest {
+    pub fn read(archive_dir: &Path) -> Result<Self, BackupError> {
+        let path = archive_dir.join(BACKUP_MANIFEST_FILE);
+        let contents = fs::read(&path).map_err(io_error(&path))?;
+        serde_json::from_slice(&contents).map_err(|e| BackupError::InvalidManifest { details: e.to_string() })
+    }
+
+    /// Hashes every file in the archive and writes the manifest into it
+    pub fn write(mut self, archive_dir: &Path) -> Result<Self, BackupError> {
+        self.files = list_files(archive_dir, archive_dir)?
+            .into_iter()
+            .filter(|path| path != Path::new(BACKUP_MANIFEST_FILE))
+            .map(|path| describe_file(archive_dir, path))
+            .collect::<Result<_, _>>()?;
+        let contents =
+            serde_json::to_vec_pretty(&self).map_err(|e| BackupError::InvalidManifest { details: e.to_string() })?;
+        let path = archive_dir.join(BACKUP_MANIFEST_FILE);
+        fs::write(&path, contents).map_err(io_error(&path))?;
+        Ok(self)
+    }
+}
+
+/// Checks that every file listed in the manifest is present with the recorded size and hash, that the archive
+/// contains no files that are not listed, and that the global database is not behind the state store. A node restored
+/// from a global database that has not reached the locked epoch would not know the committees of its own blocks.
+pub fn verify_backup(archive_dir: &Path) -> Result<BackupManifest, BackupError> {
+    let manifest = BackupManifest::read(archive_dir)?;
+    if manifest.format_version != BACKUP_FORMAT_VERSION {
+        return Err(BackupError::UnsupportedFormatVersion {
+            version: manifest.format_version,
+        });
+    }
+    if manifest.global_db_epoch < manifest.locked_epoch {
+        return Err(BackupError::GlobalDbBehindStateStore {
+            global_db_epoch: manifest.global_db_epoch,
+            locked_epoch: manifest.locked_epoch,
+        });
+    }
+
+    for file in &manifest.files {
+        if file.path.is_absolute() || file.path.components().any(|c| c == std::path::Component::ParentDir) {
+            return Err(BackupError::InvalidManifest {
+                details: format!("file path {} is outside of the archive", file.path.display()),
+            });
+        }
+        verify_file(archive_dir, file)?;
+    }
+
+    let unlisted = list_files(archive_dir, archive_dir)?
+        .into_iter()
+        .find(|path| path != Path::new(BACKUP_MANIFEST_FILE) && manifest.files.iter().all(|f| f.path != *path));
+    if let Some(path) = unlisted {
+        return Err(BackupError::IntegrityCheckFailed {
+            path,
+            details: "file is not listed in the manifest".to_string(),
+        });
+    }
+
+    Ok(manifest)
+}
+
+/// Verifies the archive and restores it, copying the state into `data_dir` and the keys into `keys_dir`. Both
+/// directories must be empty or not exist. The restored files are checked against the manifest once copied.
+pub fn restore_backup(archive_dir: &Path, data_dir: &Path, keys_dir: &Path) -> Result<BackupManifest, BackupError> {
+    let manifest = verify_backup(archive_dir)?;
+
+    ensure_empty(data_dir)?;
+    // The keys are usually restored into the base directory which contains the data directory
+    if !data_dir.starts_with(keys_dir) {
+        ensure_empty(keys_dir)?;
+    }
+    for file in &manifest.files {
+        let target = restore_target(file, data_dir, keys_dir)?;
+        if target.exists() {
+            return Err(BackupError::TargetNotEmpty { path: target });
+        }
+    }
+
+    for file in &manifest.files {
+        let target = restore_target(file, data_dir, keys_dir)?;
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent).map_err(io_error(parent))?;
+        }
+        let source = archive_dir.join(&file.path);
+        fs::copy(&source, &target).map_err(io_error(&source))?;
+        let copied = BackupFile {
+            path: target.clone(),
+            ..file.clone()
+        };
+        verify_file(Path::new(""), &copied)?;
+    }
+
+    Ok(manifest)
+}
+
+fn restore_target(file: &BackupFile, data_dir: &Path, keys_dir: &Path) -> Result<PathBuf, BackupError> {
+    if let Ok(path) = file.path.strip_prefix(BACKUP_STATE_DIR) {
+        return Ok(data_dir.join(path));
+    }
+    if let Ok(path) = file.path.strip_prefix(BACKUP_KEYS_DIR) {
+        return Ok(keys_dir.join(path));
+    }
+    Err(BackupError::InvalidManifest {
+        details: format!("unexpected file {} in archive", file.path.display()),
+    })
+}
+
+fn ensure_empty(dir: &Path) -> Result<(), BackupError> {
+    if !dir.exists() {
+        return Ok(());
+    }
+    let mut entries = fs::read_dir(dir).map_err(io_error(dir))?;
+    if entries.next().is_some() {
+        return Err(BackupError::TargetNotEmpty {
+            path: dir.to_path_buf(),
+        });
+    }
+    Ok(())
+}
+
+fn verify_file(archive_dir: &Path, file: &BackupFile) -> Result<(), BackupError> {
+    let path = archive_dir.join(&file.path);
+    let actual = describe_file(archive_dir, file.path.clone())?;
+    if actual.size != file.size {
+        return Err(BackupError::IntegrityCheckFailed {
+            path,
+            details: format!("expected {} bytes but found {}", file.size, actual.size),
+        });
+    }
+    if actual.hash != file.hash {
+        return Err(BackupError::IntegrityCheckFailed {
+            path,
+            details: "hash mismatch".to_string(),
+        });
+    }
+    Ok(())
+}
+
+fn describe_file(archive_dir: &Path, path: PathBuf) -> Result<BackupFile, BackupError> {
+    let full_path = archive_dir.join(&path);
+    let mut file = File::open(&full_path).map_err(io_error(&full_path))?;
+    let mut hasher = Blake2b::<U32>::new();
+    let mut buf = vec![0u8; 64 * 1024];
+    let mut size = 0u64;
+    loop {
+        let n = file.read(&mut buf).map_err(io_error(&full_path))?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+        size += n as u64;
+    }
+
+    Ok(BackupFile {
+        path,
+        size,
+        hash: hex::encode(hasher.finalize()),
+    })
+}
+
+/// Returns the paths, relative to `root`, of all files under `dir`
+fn list_files(root: &Path, dir: &Path) -> Result<Vec<PathBuf>, BackupError> {
+    let mut files = Vec::new();
+    for entry in fs::read_dir(dir).map_err(io_error(dir))? {
+        let path = entry.map_err(io_error(dir))?.path();
+        if path.is_dir() {
+            files.extend(list_files(root, &path)?);
+        } else {
+            files.push(path.strip_prefix(root).expect("path is under root").to_path_buf());
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+fn io_error(path: &Path) -> impl Fn(io::Error) -> BackupError + '_ {
+    move |source| BackupError::Io {
+        path: path.to_path_buf(),
+        source,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_archive(dir: &Path) -> BackupManifest {
+        fs::create_dir_all(dir.join(BACKUP_STATE_DIR).join("state_lmdb")).unwrap();
+        fs::create_dir_all(dir.join(BACKUP_KEYS_DIR)).unwrap();
+        fs::write(dir.join(BACKUP_STATE_DIR).join("state_lmdb").join("data.mdb"), b"state").unwrap();
+        fs::write(dir.join(BACKUP_STATE_DIR).join("global_storage.sqlite"), b"global").unwrap();
+        fs::write(dir.join(BACKUP_KEYS_DIR).join("validator_node_id.json"), b"{}").unwrap();
+        BackupManifest {
+            format_version: BACKUP_FORMAT_VERSION,
+            created_at: 0,
+            state_store_backend: "lmdb".to_string(),
+            locked_block: BlockId::genesis(),
+            locked_height: NodeHeight(10),
+            locked_epoch: Epoch(1),
+            global_db_epoch: Epoch(1),
+            global_db_height: 100,
+            files: vec![],
+        }
+        .write(dir)
+        .unwrap()
+    }
+
+    #[test]
+    fn it_restores_a_verified_archive() {
+        let archive = tempfile::tempdir().unwrap();
+        let manifest = create_archive(archive.path());
+        assert_eq!(manifest.files.len(), 3);
+
+        let target = tempfile::tempdir().unwrap();
+        let data_dir = target.path().join("data").join("validator_node");
+        restore_backup(archive.path(), &data_dir, target.path()).unwrap();
+
+        assert_eq!(
+            fs::read(data_dir.join("state_lmdb").join("data.mdb")).unwrap(),
+            b"state"
+        );
+        assert_eq!(fs::read(data_dir.join("global_storage.sqlite")).unwrap(), b"global");
+        assert_eq!(fs::read(target.path().join("validator_node_id.json")).unwrap(), b"{}");
+
+        // Restoring over existing state is refused
+        let err = restore_backup(archive.path(), &data_dir, target.path()).unwrap_err();
+        assert!(matches!(err, BackupError::TargetNotEmpty { .. }));
+    }
+
+    #[test]
+    fn it_rejects_a_tampered_archive() {
+        let archive = tempfile::tempdir().unwrap();
+        create_archive(archive.path());
+        fs::write(
+            archive.path().join(BACKUP_KEYS_DIR).join("validator_node_id.json"),
+            b"[]",
+        )
+        .unwrap();
+        let err = verify_backup(archive.path()).unwrap_err();
+        assert!(matches!(err, BackupError::IntegrityCheckFailed { .. }));
+
+        let archive = tempfile::tempdir().unwrap();
+        create_archive(archive.path());
+        fs::write(archive.path().join(BACKUP_STATE_DIR).join("extra"), b"").unwrap();
+        let err = verify_backup(archive.path()).unwrap_err();
+        assert!(matches!(err, BackupError::IntegrityCheckFailed { .. }));
+    }
+
+    #[test]
+    fn it_rejects_an_archive_with_a_global_db_behind_the_state_store() {
+        let archive = tempfile::tempdir().unwrap();
+        let manifest = create_archive(archive.path());
+        BackupManifest {
+            global_db_epoch: Epoch(0),
+            ..manifest
+        }
+        .write(archive.path())
+        .unwrap();
+        let err = verify_backup(archive.path()).unwrap_err();
+        assert!(matches!(err, BackupError::GlobalDbBehindStateStore { .. }));
+    }
+}
@@ -19,6 +19,7 @@
 //   SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
 //   WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 //   USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+pub mod backup;
 mod error;
 pub use error::ValidatorNodeClientError;
 pub mod types;
@@ -33,12 +34,21 @@ use tari_comms_logging::LoggedMessage;
 use crate::types::{
     AddPeerRequest,
     AddPeerResponse,
+    CallViewMethodRequest,
+    CallViewMethodResponse,
+    CreateBackupRequest,
+    CreateBackupResponse,
+    GetCommitteeHealthResponse,
     GetEpochManagerStatsResponse,
     GetIdentityResponse,
     GetRecentTransactionsRequest,
     GetRecentTransactionsResponse,
     GetStateRequest,
     GetStateResponse,
+    GetSubstateAtRequest,
+    GetSubstateAtResponse,
+    GetSubstateHistoryRequest,
+    GetSubstateHistoryResponse,
     GetSubstateRequest,
     GetSubstateResponse,
     GetTemplateRequest,
@@ -53,12 +63,16 @@ use crate::types::{
     GetValidatorFeesResponse,
     ListBlocksRequest,
     ListBlocksResponse,
+    RegisterDevTemplateRequest,
+    RegisterDevTemplateResponse,
     RegisterValidatorNodeRequest,
     RegisterValidatorNodeResponse,
     SubmitTransactionRequest,
     SubmitTransactionResponse,
     TemplateRegistrationRequest,
     TemplateRegistrationResponse,
+    VerifyBackupRequest,
+    VerifyBackupResponse,
 };
 
 #[derive(Debug, Clone)]
@@ -93,6 +107,10 @@ impl ValidatorNodeClient {
         self.send_request("get_epoch_manager_stats", json!({})).await
     }
 
+    pub async fn get_committee_health(&mut self) -> Result<GetCommitteeHealthResponse, ValidatorNodeClientError> {
+        self.send_request("get_committee_health", json!({})).await
+    }
+
     pub async fn register_validator_node(
         &mut self,
         claim_public_key: PublicKey,
@@ -112,6 +130,13 @@ impl ValidatorNodeClient {
         self.send_request("register_template", request).await
     }
 
+    pub async fn register_dev_template(
+        &mut self,
+        request: RegisterDevTemplateRequest,
+    ) -> Result<RegisterDevTemplateResponse, ValidatorNodeClientError> {
+        self.send_request("register_dev_template", request).await
+    }
+
     pub async fn get_active_templates(
         &mut self,
         request: GetTemplatesRequest,
@@ -130,6 +155,20 @@ impl ValidatorNodeClient {
         self.send_request("get_substate", request).await
     }
 
+    pub async fn get_substate_at(
+        &mut self,
+        request: GetSubstateAtRequest,
+    ) -> Result<GetSubstateAtResponse, ValidatorNodeClientError> {
+        self.send_request("get_substate_at", request).await
+    }
+
+    pub async fn get_substate_history(
+        &mut self,
+        request: GetSubstateHistoryRequest,
+    ) -> Result<GetSubstateHistoryResponse, ValidatorNodeClientError> {
+        self.send_request("get_substate_history", request).await
+    }
+
     pub async fn get_fees(
         &mut self,
         request: GetValidatorFeesRequest,
@@ -179,10 +218,31 @@ impl ValidatorNodeClient {
         self.send_request("submit_transaction", request).await
     }
 
+    pub async fn call_view_method(
+        &mut self,
+        request: CallViewMethodRequest,
+    ) -> Result<CallViewMethodResponse, ValidatorNodeClientError> {
+        self.send_request("call_view_method", request).await
+    }
+
     pub async fn add_peer(&mut self, request: AddPeerRequest) -> Result<AddPeerResponse, ValidatorNodeClientError> {
         self.send_request("add_peer", request).await
     }
 
+    pub async fn create_backup(
+        &mut self,
+        request: CreateBackupRequest,
+    ) -> Result<CreateBackupResponse, ValidatorNodeClientError> {
+        self.send_request("create_backup", request).await
+    }
+
+    pub async fn verify_backup(
+        &mut self,
+        request: VerifyBackupRequest,
+    ) -> Result<VerifyBackupResponse, ValidatorNodeClientError> {
+        self.send_request("verify_backup", request).await
+    }
+
     pub async fn get_message_logs(
         &mut self,
         message_tag: &str,
@@ -20,26 +20,45 @@
 //   WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 //   USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use std::ops::RangeInclusive;
+use std::{ops::RangeInclusive, path::PathBuf};
 
 use multiaddr::Multiaddr;
 use serde::{Deserialize, Serialize};
 use tari_common_types::{transaction::TxId, types::PublicKey};
-use tari_dan_common_types::{committee::CommitteeShard, shard_bucket::ShardBucket, Epoch, ShardId};
+use tari_dan_common_types::{
+    committee::CommitteeShard,
+    shard_bucket::ShardBucket,
+    Epoch,
+    NodeHeight,
+    ShardId,
+    View,
+};
 use tari_dan_storage::{
-    consensus_models::{Block, BlockId, ExecutedTransaction, QuorumDecision, SubstateRecord},
+    consensus_models::{
+        Block,
+        BlockId,
+        Decision,
+        ExecutedTransaction,
+        QuorumDecision,
+        SubstateQueryPoint,
+        SubstateRecord,
+    },
     global::models::ValidatorNode,
     Ordering,
 };
 use tari_engine_types::{
     commit_result::{ExecuteResult, FinalizeResult},
     fees::FeeCostBreakdown,
+    instruction_result::InstructionResult,
     serde_with,
     substate::{SubstateAddress, SubstateValue},
     TemplateAddress,
 };
+use tari_template_lib::{args::Arg, models::ComponentAddress};
 use tari_transaction::{Transaction, TransactionId};
 
+use crate::backup::BackupManifest;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GetIdentityResponse {
     pub node_id: String,
@@ -67,6 +86,24 @@ pub struct TemplateRegistrationResponse {
     pub transaction_id: u64,
 }
 
+/// Registers a WASM template directly with the validator node, without a base layer transaction. Only available on
+/// the local development network.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegisterDevTemplateRequest {
+    pub template_name: String,
+    #[serde(with = "serde_with::base64")]
+    pub binary: Vec<u8>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegisterDevTemplateResponse {
+    pub template_address: TemplateAddress,
+    #[serde(with = "serde_with::base64")]
+    pub binary_sha: Vec<u8>,
+    /// The number of other committee members that accepted the template
+    pub num_peers_notified: usize,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GetTemplateRequest {
     pub template_address: TemplateAddress,
@@ -141,6 +178,23 @@ pub struct DryRunTransactionFinalizeResult {
     pub fee_breakdown: Option<FeeCostBreakdown>,
 }
 
+/// Calls a method that does not mutate state against the latest committed state of a component. Nothing is submitted
+/// to consensus.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CallViewMethodRequest {
+    pub component_address: ComponentAddress,
+    pub method: String,
+    #[serde(default)]
+    pub args: Vec<Arg>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CallViewMethodResponse {
+    pub result: InstructionResult,
+    /// The return value of the method decoded as JSON
+    pub json_result: serde_json::Value,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GetTransactionRequest {
     pub transaction_id: TransactionId,
@@ -277,6 +331,57 @@ pub enum SubstateStatus {
     DoesNotExist,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GetSubstateAtRequest {
+    pub address: SubstateAddress,
+    pub at: SubstateQueryPoint,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GetSubstateAtResponse {
+    /// The version that was live at the query point, or None if the substate did not exist or was down at that point.
+    /// The `destroyed` field reflects the current state and may refer to a block after the query point.
+    pub substate: Option<SubstateRecord>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GetSubstateHistoryRequest {
+    pub address: SubstateAddress,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GetSubstateHistoryResponse {
+    /// All versions of the substate held by this node, oldest first. Versions that have been pruned are omitted.
+    pub versions: Vec<SubstateRecord>,
+    /// Set if this node has pruned versions destroyed before this epoch
+    pub pruned_before_epoch: Option<Epoch>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateBackupRequest {
+    /// The directory to write the archive into. Relative paths are relative to the node's configured backup directory
+    /// and the directory must be inside it. Defaults to the backup directory.
+    pub output_dir: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateBackupResponse {
+    /// The path of the created archive on the validator node's host
+    pub path: PathBuf,
+    pub manifest: BackupManifest,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VerifyBackupRequest {
+    /// The archive to verify, inside the node's configured backup directory
+    pub path: PathBuf,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VerifyBackupResponse {
+    pub manifest: BackupManifest,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AddPeerRequest {
     pub public_key: PublicKey,
@@ -295,6 +400,64 @@ pub struct GetEpochManagerStatsResponse {
     pub committee_shard: Option<CommitteeShard>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GetCommitteeHealthResponse {
+    pub epoch: Epoch,
+    /// The height of this node's high QC, against which members are checked for being in sync
+    pub local_height: NodeHeight,
+    pub summary: CommitteeHealthSummary,
+    pub members: Vec<CommitteeMemberHealth>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommitteeHealthSummary {
+    pub status: CommitteeHealthStatus,
+    pub committee_size: usize,
+    pub quorum_threshold: usize,
+    pub num_reachable: usize,
+    pub num_in_sync: usize,
+    /// Members that are unreachable, not in sync or whose last turn as leader timed out
+    pub unhealthy_members: Vec<PublicKey>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CommitteeHealthStatus {
+    /// All members are reachable and in sync
+    Healthy,
+    /// Enough members are in sync to reach quorum but some are not
+    Degraded,
+    /// Too few members are in sync to reach quorum so consensus cannot make progress
+    Stalled,
+}
+
+/// The health of a committee member. Votes, proposals and missed proposals are counted from when this node started.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommitteeMemberHealth {
+    pub public_key: PublicKey,
+    pub is_local_node: bool,
+    /// The last vote received by this node from the member. Votes are only sent to the next leader.
+    pub last_vote: Option<ConsensusObservation>,
+    pub last_proposal: Option<ConsensusObservation>,
+    /// Unix timestamp in seconds of the last leader timeout attributed to the member
+    pub last_missed_proposal: Option<u64>,
+    pub votes_received: u64,
+    pub proposals_received: u64,
+    pub proposals_missed: u64,
+    /// The round trip time of a request to the member, excluding the time taken to connect to it
+    pub latency_ms: Option<u64>,
+    pub high_qc_height: Option<NodeHeight>,
+    pub is_in_sync: bool,
+    /// The reason the member could not be reached
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct ConsensusObservation {
+    pub block_height: NodeHeight,
+    /// Unix timestamp in seconds
+    pub timestamp: u64,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RegisterValidatorNodeRequest {
     pub fee_claim_public_key: PublicKey,
@@ -353,3 +516,69 @@ pub struct GetBlocksRequest {
     pub offset: u64,
     pub ordering: Option<Ordering>,
 }
+
+/// The topics that may be subscribed to over the validator node's websocket endpoint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "topic", rename_all = "snake_case")]
+pub enum SubscriptionTopic {
+    /// Every block committed by the local committee
+    Blocks,
+    /// The finalization of a single transaction. The subscription is closed once the transaction is finalized.
+    Transaction { transaction_id: TransactionId },
+    /// Changes to any of the substates in the watch list
+    Substates { addresses: Vec<SubstateAddress> },
+    /// Consensus state transitions, leader timeouts and failures
+    ConsensusState,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubscribeResponse {
+    pub subscription_id: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnsubscribeRequest {
+    pub subscription_id: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnsubscribeResponse {
+    pub was_subscribed: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubscriptionNotification {
+    pub subscription_id: u64,
+    pub event: SubscriptionEvent,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SubscriptionEvent {
+    BlockCommitted {
+        block: Block<PublicKey>,
+    },
+    TransactionFinalized {
+        transaction_id: TransactionId,
+        final_decision: Decision,
+        result: Option<ExecuteResult>,
+    },
+    /// The latest version of the substate changed. `substate` is None if the substate does not exist.
+    SubstateChanged {
+        address: SubstateAddress,
+        substate: Option<SubstateRecord>,
+    },
+    ConsensusStateChanged {
+        state: String,
+    },
+    LeaderTimeout {
+        new_view: View,
+    },
+    ConsensusFailure {
+        message: String,
+    },
+    /// The subscriber fell behind and missed some events
+    Lagged {
+        missed: u64,
+    },
+}
@@ -106,6 +106,10 @@ use crate::{
         KeysSetActiveResponse,
         RevealFundsRequest,
         RevealFundsResponse,
+        SettingsChangePassphraseRequest,
+        SettingsChangePassphraseResponse,
+        SettingsSetPassphraseRequest,
+        SettingsSetPassphraseResponse,
         TransactionGetRequest,
         TransactionGetResponse,
         TransactionGetResultRequest,
@@ -114,6 +118,8 @@ use crate::{
         TransactionSubmitResponse,
         TransactionWaitResultRequest,
         TransactionWaitResultResponse,
+        WalletRestoreRequest,
+        WalletRestoreResponse,
     },
 };
 
@@ -216,6 +222,27 @@ impl WalletDaemonClient {
         self.send_request("keys.list", &KeysListRequest {}).await
     }
 
+    pub async fn set_passphrase(
+        &mut self,
+        req: SettingsSetPassphraseRequest,
+    ) -> Result<SettingsSetPassphraseResponse, WalletDaemonClientError> {
+        self.send_request("settings.set_passphrase", &req).await
+    }
+
+    pub async fn change_passphrase(
+        &mut self,
+        req: SettingsChangePassphraseRequest,
+    ) -> Result<SettingsChangePassphraseResponse, WalletDaemonClientError> {
+        self.send_request("settings.change_passphrase", &req).await
+    }
+
+    pub async fn restore_wallet(
+        &mut self,
+        req: WalletRestoreRequest,
+    ) -> Result<WalletRestoreResponse, WalletDaemonClientError> {
+        self.send_request("wallet.restore", &req).await
+    }
+
     pub async fn get_transaction<T: Borrow<TransactionGetRequest>>(
         &mut self,
         request: T,
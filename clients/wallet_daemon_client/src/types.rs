@@ -577,3 +577,41 @@ pub struct SettingsSetResponse {}
 pub struct SettingsGetResponse {
     pub indexer_url: String,
 }
+
+/// Sets the passphrase used to encrypt the wallet secrets of a wallet that does not have one. The wallet secrets
+/// cannot be recovered without the passphrase, so it must be entered twice.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct SettingsSetPassphraseRequest {
+    pub passphrase: String,
+    pub confirm_passphrase: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct SettingsSetPassphraseResponse {}
+
+/// Changes the passphrase used to encrypt the wallet secrets
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct SettingsChangePassphraseRequest {
+    pub current_passphrase: String,
+    pub new_passphrase: String,
+    pub confirm_new_passphrase: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct SettingsChangePassphraseResponse {}
+
+/// Restores the wallet seed from seed words and discovers the accounts owned by keys derived from it. The wallet must
+/// not have any accounts, unless it was already restored from the same seed words.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct WalletRestoreRequest {
+    pub seed_words: Vec<String>,
+    /// The optional passphrase that the seed words were created with
+    pub seed_passphrase: Option<String>,
+    /// The number of consecutive unused keys after which account discovery stops (default 20, at most 100)
+    pub gap_limit: Option<u64>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct WalletRestoreResponse {
+    pub accounts: Vec<AccountInfo>,
+}
@@ -51,6 +51,18 @@ pub fn vote_signature_hasher() -> TariHasher {
     dan_hasher("VoteSignature")
 }
 
+pub fn proposal_signature_hasher() -> TariHasher {
+    dan_hasher("ProposalSignature")
+}
+
+pub fn new_view_signature_hasher() -> TariHasher {
+    dan_hasher("NewViewSignature")
+}
+
+pub fn state_commitment_hasher() -> TariHasher {
+    dan_hasher("StateCommitment")
+}
+
 fn dan_hasher(label: &'static str) -> TariHasher {
     tari_hasher::<TariDanConsensusHashDomain>(label)
 }
@@ -9,6 +9,7 @@ pub use epoch::Epoch;
 pub mod committee;
 pub mod hasher;
 pub mod hashing;
+pub mod metrics;
 pub mod optional;
 
 mod node_height;
@@ -27,4 +28,8 @@ mod shard_id;
 pub use shard_id::ShardId;
 
 pub mod uint;
+
+mod view;
+pub use view::View;
+
 pub use tari_engine_types::serde_with;
@@ -0,0 +1,32 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use log::*;
+use prometheus::core::Collector;
+
+const LOG_TARGET: &str = "tari::dan::common_types::metrics";
+
+/// Registers the metric with the default registry and returns it. A metric with the same name may already be
+/// registered if more than one application is running in the same process (e.g. in the integration tests), in which
+/// case the metric is still usable but only the first one is exported.
+pub fn register<T: Collector + Clone + 'static>(metric: T) -> T {
+    match prometheus::register(Box::new(metric.clone())) {
+        Ok(()) => {},
+        Err(prometheus::Error::AlreadyReg) => {
+            debug!(target: LOG_TARGET, "Metric {} is already registered", metric_name(&metric));
+        },
+        Err(err) => {
+            error!(target: LOG_TARGET, "Failed to register metric {}: {}", metric_name(&metric), err);
+        },
+    }
+    metric
+}
+
+fn metric_name<T: Collector>(metric: &T) -> String {
+    metric
+        .desc()
+        .iter()
+        .map(|desc| desc.fq_name.as_str())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
@@ -0,0 +1,78 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use std::{
+    fmt::{Display, Formatter},
+    ops::{Add, AddAssign, Sub},
+};
+
+use serde::{Deserialize, Serialize};
+
+/// A consensus view (round) number. Views increase monotonically with each proposal or leader timeout and are
+/// independent of block height, since a failed leader advances the view without producing a block.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize, Default)]
+pub struct View(pub u64);
+
+impl View {
+    pub const fn as_u64(self) -> u64 {
+        self.0
+    }
+
+    pub const fn is_zero(self) -> bool {
+        self.0 == 0
+    }
+
+    pub const fn zero() -> Self {
+        Self(0)
+    }
+
+    pub const fn to_le_bytes(self) -> [u8; 8] {
+        self.0.to_le_bytes()
+    }
+
+    pub const fn next(self) -> Self {
+        Self(self.0 + 1)
+    }
+
+    pub const fn saturating_add(self, other: Self) -> Self {
+        Self(self.0.saturating_add(other.0))
+    }
+
+    pub const fn saturating_sub(self, other: Self) -> Self {
+        Self(self.0.saturating_sub(other.0))
+    }
+}
+
+impl Add for View {
+    type Output = View;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        View(self.0 + rhs.0)
+    }
+}
+
+impl AddAssign for View {
+    fn add_assign(&mut self, rhs: Self) {
+        self.0 += rhs.0;
+    }
+}
+
+impl Sub for View {
+    type Output = View;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        View(self.0 - rhs.0)
+    }
+}
+
+impl From<u64> for View {
+    fn from(view: u64) -> Self {
+        View(view)
+    }
+}
+
+impl Display for View {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "View({})", self.0)
+    }
+}
@@ -10,9 +10,39 @@
 //!  A->>B: CheckSync
 //!  B->>A: SyncStatus
 //! ```
+//!
+//! In snapshot mode, a node without any local state first fetches the substates of its shard range at a committed
+//! checkpoint block, then syncs the blocks after the checkpoint.
+//!
+//! ```mermaid
+//! sequenceDiagram
+//!     participant A as Client
+//!     participant B as Server
+//!     participant C as Committee
+//!  A->>B: SyncStateSnapshot
+//!  B->>A: Checkpoint block, QC and state commitment
+//!  B->>A: Substates
+//!  A->>C: GetStateCommitment
+//!  C->>A: State commitment
+//!  A->>B: SyncBlocks
+//! ```
+//!
+//! When a node's shard range changes at an epoch boundary, the live substates of its new range are fetched page by
+//! page from the committee that was responsible for the range in the previous epoch.
+//!
+//! ```mermaid
+//! sequenceDiagram
+//!     participant A as Client
+//!     participant B as Previous Committee
+//!  A->>B: SyncSubstatesInRange
+//!  B->>A: Substate pages
+//! ```
 
 mod error;
 mod manager;
+mod sync_mode;
+mod verify;
 
 pub use error::*;
 pub use manager::*;
+pub use sync_mode::*;
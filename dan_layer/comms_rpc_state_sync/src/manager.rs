@@ -1,21 +1,37 @@
 //   Copyright 2023 The Tari Project
 //   SPDX-License-Identifier: BSD-3-Clause
 
-use std::ops::DerefMut;
+use std::{
+    mem,
+    ops::{DerefMut, RangeInclusive},
+    sync::Arc,
+};
 
 use async_trait::async_trait;
 use futures::StreamExt;
 use log::*;
+use tari_common_types::types::FixedHash;
 use tari_comms::{protocol::rpc::RpcError, types::CommsPublicKey};
 use tari_consensus::traits::{SyncManager, SyncStatus};
-use tari_dan_common_types::{committee::Committee, optional::Optional, NodeHeight};
+use tari_dan_common_types::{
+    committee::Committee,
+    optional::Optional,
+    shard_bucket::ShardBucket,
+    Epoch,
+    NodeHeight,
+    ShardId,
+};
 use tari_dan_storage::{
     consensus_models::{
         Block,
+        BlockId,
         HighQc,
         LastExecuted,
         LockedBlock,
         QuorumCertificate,
+        StateCommitmentHasher,
+        StateSnapshot,
+        SubstateRecord,
         SubstateUpdate,
         TransactionPoolRecord,
         TransactionRecord,
@@ -23,24 +39,41 @@ use tari_dan_storage::{
     StateStore,
     StateStoreWriteTransaction,
 };
-use tari_epoch_manager::EpochManagerReader;
+use tari_epoch_manager::{EpochManagerEvent, EpochManagerReader};
 use tari_transaction::Transaction;
 use tari_validator_node_rpc::{
     client::{TariCommsValidatorNodeClientFactory, ValidatorNodeClientFactory},
-    proto::rpc::{GetHighQcRequest, SyncBlocksRequest},
+    proto::rpc::{
+        GetHighQcRequest,
+        GetStateCommitmentRequest,
+        SyncBlocksRequest,
+        SyncStateSnapshotRequest,
+        SyncSubstatesInRangeRequest,
+    },
     rpc_service::ValidatorNodeRpcClient,
 };
+use tokio::sync::{broadcast::error::RecvError, Mutex};
 
-use crate::error::CommsRpcConsensusSyncError;
+use crate::{
+    error::CommsRpcConsensusSyncError,
+    verify::{verify_checkpoint_qc, verify_state_commitment},
+    StateSyncMode,
+};
 
 const LOG_TARGET: &str = "tari::dan::comms_rpc_state_sync";
 
 const MAX_SUBSTATE_UPDATES: usize = 10000;
+const MAX_SNAPSHOT_SUBSTATES: u64 = 10_000_000;
+/// The number of snapshot substates written per transaction
+const SNAPSHOT_WRITE_PAGE_SIZE: usize = 1000;
 
+#[derive(Clone)]
 pub struct CommsRpcStateSyncManager<TEpochManager, TStateStore> {
     epoch_manager: TEpochManager,
     state_store: TStateStore,
     client_factory: TariCommsValidatorNodeClientFactory,
+    sync_mode: StateSyncMode,
+    last_handoff_epoch: Arc<Mutex<Option<Epoch>>>,
 }
 
 impl<TEpochManager, TStateStore> CommsRpcStateSyncManager<TEpochManager, TStateStore>
@@ -52,11 +85,14 @@ where
         epoch_manager: TEpochManager,
         state_store: TStateStore,
         client_factory: TariCommsValidatorNodeClientFactory,
+        sync_mode: StateSyncMode,
     ) -> Self {
         Self {
             epoch_manager,
             state_store,
             client_factory,
+            sync_mode,
+            last_handoff_epoch: Arc::new(Mutex::new(None)),
         }
     }
 
@@ -84,6 +120,485 @@ where
         Ok(())
     }
 
+    /// Returns true if this node has not yet locked any block after the zero block i.e. it has no local state.
+    fn has_no_local_state(&self) -> Result<bool, CommsRpcConsensusSyncError> {
+        let locked_block = self.state_store.with_read_tx(|tx| LockedBlock::get(tx).optional())?;
+        Ok(locked_block.map_or(true, |b| b.height().is_zero()))
+    }
+
+    async fn sync_snapshot(&self, committee: &Committee<CommsPublicKey>) -> Result<(), CommsRpcConsensusSyncError> {
+        let current_epoch = self.epoch_manager.current_epoch().await?;
+        let committee_shard = self.epoch_manager.get_local_committee_shard(current_epoch).await?;
+        let range = committee_shard
+            .bucket()
+            .to_shard_range(committee_shard.num_committees());
+
+        let mut last_error = None;
+        for addr in committee.iter() {
+            let mut import = ShardRangeImport::new(range.clone());
+            match self.import_snapshot_from_peer(addr, committee, &mut import).await {
+                Ok(()) => return Ok(()),
+                Err(err) => {
+                    warn!(target: LOG_TARGET, "Failed to import state snapshot from peer {}: {}", addr, err);
+                    // The substates written so far were never checked against the state commitment
+                    self.discard_shard_range_import(&mut import)?;
+                    last_error = Some(err);
+                },
+            }
+        }
+
+        Err(last_error.unwrap_or(CommsRpcConsensusSyncError::NoPeersAvailable {
+            committee_size: committee.len(),
+        }))
+    }
+
+    /// Imports the state snapshot from the peer. The checkpoint is verified before any substate is written, and the
+    /// substates are then written one page at a time as they are received. The checkpoint block is only applied once
+    /// the substates match the attested state commitment.
+    async fn import_snapshot_from_peer(
+        &self,
+        addr: &CommsPublicKey,
+        committee: &Committee<CommsPublicKey>,
+        import: &mut ShardRangeImport,
+    ) -> Result<(), CommsRpcConsensusSyncError> {
+        let mut rpc_client = self.client_factory.create_client(addr);
+        let mut client = rpc_client.client_connection().await?;
+        let mut stream = client
+            .sync_state_snapshot(SyncStateSnapshotRequest {
+                shard_start: import.range.start().as_bytes().to_vec(),
+                shard_end: import.range.end().as_bytes().to_vec(),
+            })
+            .await?;
+
+        let Some(resp) = stream.next().await else {
+            return Err(CommsRpcConsensusSyncError::InvalidResponse(anyhow::anyhow!(
+                "Peer closed session before sending snapshot checkpoint"
+            )));
+        };
+        let msg = resp.map_err(RpcError::from)?;
+        let checkpoint = msg.into_checkpoint().ok_or_else(|| {
+            CommsRpcConsensusSyncError::InvalidResponse(anyhow::anyhow!("Expected peer to return a checkpoint"))
+        })?;
+
+        if checkpoint.substate_count > MAX_SNAPSHOT_SUBSTATES {
+            return Err(CommsRpcConsensusSyncError::InvalidResponse(anyhow::anyhow!(
+                "Peer returned {} snapshot substates, but the maximum is {}",
+                checkpoint.substate_count,
+                MAX_SNAPSHOT_SUBSTATES,
+            )));
+        }
+
+        let block = checkpoint
+            .block
+            .map(Block::<CommsPublicKey>::try_from)
+            .transpose()
+            .map_err(CommsRpcConsensusSyncError::InvalidResponse)?
+            .ok_or_else(|| {
+                CommsRpcConsensusSyncError::InvalidResponse(anyhow::anyhow!("Checkpoint block not provided"))
+            })?;
+        let qc = checkpoint
+            .qc
+            .map(QuorumCertificate::<CommsPublicKey>::try_from)
+            .transpose()
+            .map_err(CommsRpcConsensusSyncError::InvalidResponse)?
+            .ok_or_else(|| {
+                CommsRpcConsensusSyncError::InvalidResponse(anyhow::anyhow!("Checkpoint QC not provided"))
+            })?;
+        let state_commitment = FixedHash::try_from(checkpoint.state_commitment)
+            .map_err(|e| CommsRpcConsensusSyncError::InvalidResponse(e.into()))?;
+
+        self.verify_snapshot_checkpoint(addr, committee, &block, &qc, &import.range, state_commitment)
+            .await?;
+        import.checkpoint = Some((*block.id(), state_commitment));
+
+        let mut after = import.after;
+        let mut page = Vec::new();
+        for _ in 0..checkpoint.substate_count {
+            let Some(resp) = stream.next().await else {
+                return Err(CommsRpcConsensusSyncError::InvalidResponse(anyhow::anyhow!(
+                    "Peer closed session before sending all snapshot substates"
+                )));
+            };
+            let msg = resp.map_err(RpcError::from)?;
+            let substate = msg.into_substate().ok_or_else(|| {
+                CommsRpcConsensusSyncError::InvalidResponse(anyhow::anyhow!("Expected peer to return a substate"))
+            })?;
+            let substate = SubstateRecord::try_from(substate).map_err(CommsRpcConsensusSyncError::InvalidResponse)?;
+            check_imported_substate(&import.range, &mut after, &substate)?;
+            page.push(substate);
+
+            if page.len() == SNAPSHOT_WRITE_PAGE_SIZE {
+                self.write_shard_range_import_page(import, mem::take(&mut page), after)?;
+            }
+        }
+        self.write_shard_range_import_page(import, page, after)?;
+        self.finish_shard_range_import(import)?;
+
+        info!(
+            target: LOG_TARGET,
+            "🌐 Applying state snapshot at block {} with {} substate(s) from peer '{}'",
+            block,
+            import.num_received,
+            addr
+        );
+        self.create_zero_block_if_required()?;
+        self.state_store
+            .with_write_tx(|tx| StateSnapshot::apply_checkpoint(&block, &qc, tx))?;
+        Ok(())
+    }
+
+    /// Checks that the checkpoint block is certified by a valid QC and that enough committee members attest to the
+    /// same state commitment at the checkpoint block that at least one of them is honest.
+    async fn verify_snapshot_checkpoint(
+        &self,
+        addr: &CommsPublicKey,
+        committee: &Committee<CommsPublicKey>,
+        block: &Block<CommsPublicKey>,
+        qc: &QuorumCertificate<CommsPublicKey>,
+        range: &RangeInclusive<ShardId>,
+        state_commitment: FixedHash,
+    ) -> Result<(), CommsRpcConsensusSyncError> {
+        let qc_committee = self.epoch_manager.get_committee(qc.epoch(), *range.start()).await?;
+        verify_checkpoint_qc(block, qc, &qc_committee)?;
+
+        // Blocks do not carry a state root, so the commitment is attested to by the committee. The peer that sent the
+        // snapshot counts as the first attestation.
+        let current_epoch = self.epoch_manager.current_epoch().await?;
+        let local_committee = self.epoch_manager.get_local_committee(current_epoch).await?;
+        let block_id = block.id();
+        verify_state_commitment(
+            committee,
+            Some(addr),
+            local_committee.max_failures() + 1,
+            block_id,
+            state_commitment,
+            |member| async move { self.get_state_commitment(&member, block_id, range).await },
+        )
+        .await
+    }
+
+    async fn get_state_commitment(
+        &self,
+        addr: &CommsPublicKey,
+        block_id: &BlockId,
+        range: &RangeInclusive<ShardId>,
+    ) -> Result<FixedHash, CommsRpcConsensusSyncError> {
+        let mut rpc_client = self.client_factory.create_client(addr);
+        let mut client = rpc_client.client_connection().await?;
+        let resp = client
+            .get_state_commitment(GetStateCommitmentRequest {
+                block_id: block_id.as_bytes().to_vec(),
+                shard_start: range.start().as_bytes().to_vec(),
+                shard_end: range.end().as_bytes().to_vec(),
+            })
+            .await?;
+        FixedHash::try_from(resp.state_commitment).map_err(|e| CommsRpcConsensusSyncError::InvalidResponse(e.into()))
+    }
+
+    /// Runs the shard range handoff each time the epoch changes, so that the substates of a new shard range are
+    /// fetched as soon as it is assigned. Returns when the epoch manager event stream closes.
+    pub async fn run_shard_range_handoff_on_epoch_change(self) -> Result<(), CommsRpcConsensusSyncError> {
+        let mut events = self.epoch_manager.subscribe().await?;
+        loop {
+            match events.recv().await {
+                Ok(EpochManagerEvent::EpochChanged(epoch)) => {
+                    if let Err(err) = self.sync_shard_range_handoff().await {
+                        warn!(target: LOG_TARGET, "Shard range handoff for epoch {} failed: {}", epoch, err);
+                    }
+                },
+                Ok(_) => {},
+                Err(RecvError::Lagged(n)) => {
+                    warn!(target: LOG_TARGET, "Shard range handoff missed {} epoch manager event(s)", n);
+                },
+                Err(RecvError::Closed) => return Ok(()),
+            }
+        }
+    }
+
+    /// When this node's shard range changes at an epoch boundary, fetches the live substates of the new range from the
+    /// committees that were responsible for it in the previous epoch. The substates of each previous committee's part
+    /// of the range are checked against a state commitment that enough members of that committee attest to that at
+    /// least one of them is honest, see `import_shard_range`. Substates that already exist locally are skipped.
+    async fn sync_shard_range_handoff(&self) -> Result<(), CommsRpcConsensusSyncError> {
+        // Held for the whole handoff so that the epoch change task and sync do not run it concurrently
+        let mut last_handoff_epoch = self.last_handoff_epoch.lock().await;
+        let current_epoch = self.epoch_manager.current_epoch().await?;
+        if *last_handoff_epoch == Some(current_epoch) {
+            return Ok(());
+        }
+        let Some(prev_epoch) = current_epoch.checked_sub(Epoch(1)) else {
+            return Ok(());
+        };
+        // A node that was not registered in the previous epoch has no range to hand over from and syncs from
+        // scratch instead
+        if !self
+            .epoch_manager
+            .is_this_validator_registered_for_epoch(prev_epoch)
+            .await?
+        {
+            return Ok(());
+        }
+
+        let range = self.get_local_shard_range(current_epoch).await?;
+        if self.get_local_shard_range(prev_epoch).await? == range {
+            *last_handoff_epoch = Some(current_epoch);
+            return Ok(());
+        }
+
+        info!(
+            target: LOG_TARGET,
+            "🌐 Shard range changed at epoch {} to {} to {}. Fetching substates from the committees of epoch {}",
+            current_epoch,
+            range.start(),
+            range.end(),
+            prev_epoch
+        );
+
+        let this_vn = self.epoch_manager.get_our_validator_node(current_epoch).await?;
+        let mut num_created = 0;
+        for sub_range in self.split_by_committee(prev_epoch, &range).await? {
+            let mut committee = self.epoch_manager.get_committee(prev_epoch, *sub_range.start()).await?;
+            let required = committee.max_failures() + 1;
+            committee.members.retain(|m| *m != this_vn.address);
+            committee.shuffle();
+
+            num_created += self.import_shard_range(&committee, required, sub_range).await?;
+        }
+
+        info!(
+            target: LOG_TARGET,
+            "🌐 Shard range handoff complete. {} substate(s) added", num_created
+        );
+        *last_handoff_epoch = Some(current_epoch);
+        Ok(())
+    }
+
+    async fn get_local_shard_range(&self, epoch: Epoch) -> Result<RangeInclusive<ShardId>, CommsRpcConsensusSyncError> {
+        let committee_shard = self.epoch_manager.get_local_committee_shard(epoch).await?;
+        Ok(committee_shard
+            .bucket()
+            .to_shard_range(committee_shard.num_committees()))
+    }
+
+    /// Splits the shard range into the parts that each committee of the given epoch was responsible for
+    async fn split_by_committee(
+        &self,
+        epoch: Epoch,
+        range: &RangeInclusive<ShardId>,
+    ) -> Result<Vec<RangeInclusive<ShardId>>, CommsRpcConsensusSyncError> {
+        let mut sub_ranges = vec![];
+        let mut start = *range.start();
+        loop {
+            let committee_shard = self.epoch_manager.get_committee_shard(epoch, start).await?;
+            let num_committees = committee_shard.num_committees();
+            let committee_range = committee_shard.bucket().to_shard_range(num_committees);
+            if committee_range.end() >= range.end() {
+                sub_ranges.push(start..=*range.end());
+                return Ok(sub_ranges);
+            }
+            sub_ranges.push(start..=*committee_range.end());
+            start = *ShardBucket::from(committee_shard.bucket().as_u32() + 1)
+                .to_shard_range(num_committees)
+                .start();
+        }
+    }
+
+    /// Imports the live substates of the shard range at a committed block from the committee, writing each page as it
+    /// is received. The first peer chooses the block and `required` members of the committee have to attest to the
+    /// state commitment at that block before any of its substates are written. A failed stream is resumed at the same
+    /// block from the next peer. Substates that do not match the attested state commitment are deleted again and the
+    /// range is fetched from the next peer. Returns the number of substates created.
+    async fn import_shard_range(
+        &self,
+        committee: &Committee<CommsPublicKey>,
+        required: usize,
+        range: RangeInclusive<ShardId>,
+    ) -> Result<usize, CommsRpcConsensusSyncError> {
+        let mut import = ShardRangeImport::new(range);
+        let mut last_error = None;
+        for addr in committee.iter() {
+            match self
+                .import_shard_range_from_peer(addr, committee, required, &mut import)
+                .await
+            {
+                Ok(()) => return Ok(import.created.len()),
+                Err(err) => {
+                    warn!(target: LOG_TARGET, "Failed to import shard range from peer {}: {}", addr, err);
+                    last_error = Some(err);
+                },
+            }
+        }
+
+        // The substates written so far were never checked against the state commitment
+        self.discard_shard_range_import(&mut import)?;
+        Err(last_error.unwrap_or(CommsRpcConsensusSyncError::NoPeersAvailable {
+            committee_size: committee.len(),
+        }))
+    }
+
+    async fn import_shard_range_from_peer(
+        &self,
+        addr: &CommsPublicKey,
+        committee: &Committee<CommsPublicKey>,
+        required: usize,
+        import: &mut ShardRangeImport,
+    ) -> Result<(), CommsRpcConsensusSyncError> {
+        let mut rpc_client = self.client_factory.create_client(addr);
+        let mut client = rpc_client.client_connection().await?;
+        let mut stream = client
+            .sync_substates_in_range(SyncSubstatesInRangeRequest {
+                shard_start: import.range.start().as_bytes().to_vec(),
+                shard_end: import.range.end().as_bytes().to_vec(),
+                after: import.after.map(|s| s.as_bytes().to_vec()).unwrap_or_default(),
+                page_size: 0,
+                block_id: import
+                    .checkpoint
+                    .map(|(id, _)| id.as_bytes().to_vec())
+                    .unwrap_or_default(),
+            })
+            .await?;
+
+        while let Some(resp) = stream.next().await {
+            let msg = resp.map_err(RpcError::from)?;
+            let msg_block_id =
+                BlockId::try_from(msg.block_id).map_err(|e| CommsRpcConsensusSyncError::InvalidResponse(e.into()))?;
+            let block_id = match import.checkpoint {
+                Some((block_id, _)) => block_id,
+                None => {
+                    let state_commitment = self
+                        .get_attested_state_commitment(addr, committee, required, &msg_block_id, &import.range)
+                        .await?;
+                    import.checkpoint = Some((msg_block_id, state_commitment));
+                    msg_block_id
+                },
+            };
+            if block_id != msg_block_id {
+                return Err(CommsRpcConsensusSyncError::InvalidResponse(anyhow::anyhow!(
+                    "Peer returned substates at block {} but block {} was requested",
+                    msg_block_id,
+                    block_id
+                )));
+            }
+
+            let mut after = import.after;
+            let mut page = Vec::with_capacity(msg.substates.len());
+            for substate in msg.substates {
+                let substate =
+                    SubstateRecord::try_from(substate).map_err(CommsRpcConsensusSyncError::InvalidResponse)?;
+                check_imported_substate(&import.range, &mut after, &substate)?;
+                page.push(substate);
+            }
+            self.write_shard_range_import_page(import, page, after)?;
+
+            if msg.next.is_empty() {
+                return self.finish_shard_range_import(import);
+            }
+        }
+
+        Err(CommsRpcConsensusSyncError::InvalidResponse(anyhow::anyhow!(
+            "Peer closed session before sending the last page of substates"
+        )))
+    }
+
+    /// Returns the state commitment of the shard range that the peer reports at the block, once enough members of the
+    /// committee attest to it that at least one of them is honest
+    async fn get_attested_state_commitment(
+        &self,
+        addr: &CommsPublicKey,
+        committee: &Committee<CommsPublicKey>,
+        required: usize,
+        block_id: &BlockId,
+        range: &RangeInclusive<ShardId>,
+    ) -> Result<FixedHash, CommsRpcConsensusSyncError> {
+        let state_commitment = self.get_state_commitment(addr, block_id, range).await?;
+        verify_state_commitment(
+            committee,
+            Some(addr),
+            required,
+            block_id,
+            state_commitment,
+            |member| async move { self.get_state_commitment(&member, block_id, range).await },
+        )
+        .await?;
+        Ok(state_commitment)
+    }
+
+    /// Writes a page of checked substates in its own transaction and adds them to the state commitment of the import.
+    /// The import only advances once the page is written, so that a failed write is fetched again.
+    fn write_shard_range_import_page(
+        &self,
+        import: &mut ShardRangeImport,
+        page: Vec<SubstateRecord>,
+        after: Option<ShardId>,
+    ) -> Result<(), CommsRpcConsensusSyncError> {
+        let num_received = import.num_received + page.len() as u64;
+        if num_received > MAX_SNAPSHOT_SUBSTATES {
+            return Err(CommsRpcConsensusSyncError::InvalidResponse(anyhow::anyhow!(
+                "Peer returned more than the maximum of {} substates",
+                MAX_SNAPSHOT_SUBSTATES,
+            )));
+        }
+
+        let mut hasher = import.hasher.clone();
+        page.iter().for_each(|substate| hasher.update(substate));
+        let created = self.state_store.with_write_tx(|tx| {
+            let mut created = Vec::new();
+            for substate in page {
+                let shard_id = substate.to_shard_id();
+                if !SubstateRecord::exists(tx.deref_mut(), &shard_id)? {
+                    substate.create(tx)?;
+                    created.push(shard_id);
+                }
+            }
+            Ok::<_, CommsRpcConsensusSyncError>(created)
+        })?;
+        import.after = after;
+        import.hasher = hasher;
+        import.num_received = num_received;
+        import.created.extend(created);
+        Ok(())
+    }
+
+    /// Checks the imported substates against the attested state commitment. If they do not match, they are deleted
+    /// so that the range can be fetched again from the next peer.
+    fn finish_shard_range_import(&self, import: &mut ShardRangeImport) -> Result<(), CommsRpcConsensusSyncError> {
+        let (block_id, state_commitment) = import
+            .checkpoint
+            .expect("finish_shard_range_import called before the checkpoint was attested");
+        let hasher = mem::replace(&mut import.hasher, StateCommitmentHasher::new(&import.range));
+        if hasher.result() == state_commitment {
+            return Ok(());
+        }
+
+        self.discard_shard_range_import(import)?;
+        Err(CommsRpcConsensusSyncError::InvalidResponse(anyhow::anyhow!(
+            "Substates do not match the state commitment {} attested at block {}",
+            state_commitment,
+            block_id
+        )))
+    }
+
+    /// Deletes the substates created by the import and starts it again from the beginning of the range at the same
+    /// block
+    fn discard_shard_range_import(&self, import: &mut ShardRangeImport) -> Result<(), CommsRpcConsensusSyncError> {
+        if !import.created.is_empty() {
+            let num_deleted = self
+                .state_store
+                .with_write_tx(|tx| SubstateRecord::delete_many(tx, import.created.drain(..)))?;
+            warn!(
+                target: LOG_TARGET,
+                "Discarded {} unverified substate(s) imported for shard range {} to {}",
+                num_deleted,
+                import.range.start(),
+                import.range.end()
+            );
+        }
+        import.after = None;
+        import.hasher = StateCommitmentHasher::new(&import.range);
+        import.num_received = 0;
+        Ok(())
+    }
+
     fn create_zero_block_if_required(&self) -> Result<(), CommsRpcConsensusSyncError> {
         let mut tx = self.state_store.create_write_tx()?;
 
@@ -309,6 +824,55 @@ where
     }
 }
 
+/// Checks that a received substate is live, within the shard range and follows the previously received substate
+fn check_imported_substate(
+    range: &RangeInclusive<ShardId>,
+    after: &mut Option<ShardId>,
+    substate: &SubstateRecord,
+) -> Result<(), CommsRpcConsensusSyncError> {
+    let shard_id = substate.to_shard_id();
+    if substate.is_destroyed() {
+        return Err(CommsRpcConsensusSyncError::InvalidResponse(anyhow::anyhow!(
+            "Peer returned destroyed substate {}",
+            shard_id
+        )));
+    }
+    if !range.contains(&shard_id) || after.map_or(false, |after| shard_id <= after) {
+        return Err(CommsRpcConsensusSyncError::InvalidResponse(anyhow::anyhow!(
+            "Peer returned substate {} that is out of order or outside of the requested shard range",
+            shard_id
+        )));
+    }
+    *after = Some(shard_id);
+    Ok(())
+}
+
+/// The progress of importing the substates of a shard range from a committee
+struct ShardRangeImport {
+    range: RangeInclusive<ShardId>,
+    /// The block that the substates are fetched at and the state commitment that the committee attested to at it
+    checkpoint: Option<(BlockId, FixedHash)>,
+    /// The last substate received
+    after: Option<ShardId>,
+    hasher: StateCommitmentHasher,
+    num_received: u64,
+    /// The substates written by the import, which are deleted again if they cannot be verified
+    created: Vec<ShardId>,
+}
+
+impl ShardRangeImport {
+    fn new(range: RangeInclusive<ShardId>) -> Self {
+        Self {
+            hasher: StateCommitmentHasher::new(&range),
+            range,
+            checkpoint: None,
+            after: None,
+            num_received: 0,
+            created: Vec::new(),
+        }
+    }
+}
+
 #[async_trait]
 impl<TEpochManager, TStateStore> SyncManager for CommsRpcStateSyncManager<TEpochManager, TStateStore>
 where
@@ -404,6 +968,17 @@ where
             return Ok(());
         }
 
+        if self.sync_mode.is_snapshot() && self.has_no_local_state()? {
+            if let Err(err) = self.sync_snapshot(&committee).await {
+                warn!(
+                    target: LOG_TARGET,
+                    "State snapshot sync failed: {}. Falling back to syncing all blocks", err
+                );
+            }
+        } else if let Err(err) = self.sync_shard_range_handoff().await {
+            warn!(target: LOG_TARGET, "Shard range handoff failed: {}", err);
+        }
+
         let mut sync_error = None;
         for member in committee {
             // Refresh the HighQC each time because a partial sync could have been achieved from a peer
@@ -0,0 +1,32 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StateSyncMode {
+    /// Replay every block from the zero block
+    #[default]
+    Blocks,
+    /// When the node has no local state, sync a verified substate snapshot at a committed checkpoint block and then
+    /// only the blocks after it
+    Snapshot,
+}
+
+impl StateSyncMode {
+    pub fn is_snapshot(&self) -> bool {
+        matches!(self, Self::Snapshot)
+    }
+}
+
+impl fmt::Display for StateSyncMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Blocks => write!(f, "blocks"),
+            Self::Snapshot => write!(f, "snapshot"),
+        }
+    }
+}
@@ -0,0 +1,298 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use std::future::Future;
+
+use log::*;
+use tari_common_types::types::FixedHash;
+use tari_comms::types::CommsPublicKey;
+use tari_consensus::traits::vote_challenge;
+use tari_dan_common_types::committee::Committee;
+use tari_dan_storage::consensus_models::{Block, BlockId, QuorumCertificate};
+
+use crate::error::CommsRpcConsensusSyncError;
+
+const LOG_TARGET: &str = "tari::dan::comms_rpc_state_sync::verify";
+
+/// Checks that the QC is an accept QC for the checkpoint block, signed by at least a quorum of the committee.
+pub(crate) fn verify_checkpoint_qc(
+    block: &Block<CommsPublicKey>,
+    qc: &QuorumCertificate<CommsPublicKey>,
+    committee: &Committee<CommsPublicKey>,
+) -> Result<(), CommsRpcConsensusSyncError> {
+    if qc.block_id() != block.id() || qc.block_height() != block.height() {
+        return Err(CommsRpcConsensusSyncError::InvalidResponse(anyhow::anyhow!(
+            "Checkpoint QC {} does not certify checkpoint block {}",
+            qc,
+            block
+        )));
+    }
+    if !qc.decision().is_accept() {
+        return Err(CommsRpcConsensusSyncError::InvalidResponse(anyhow::anyhow!(
+            "Checkpoint QC {} is not an accept QC",
+            qc
+        )));
+    }
+    if qc.signatures().len() != qc.leaf_hashes().len() {
+        return Err(CommsRpcConsensusSyncError::InvalidResponse(anyhow::anyhow!(
+            "Checkpoint QC {} has {} signatures but {} leaf hashes",
+            qc,
+            qc.signatures().len(),
+            qc.leaf_hashes().len()
+        )));
+    }
+
+    // Leaf hashes are sorted in the QC, so each signature is checked against the leaf hashes that have not already
+    // been used by another signature
+    let mut unused_leaf_hashes = qc.leaf_hashes().to_vec();
+    let mut signers = Vec::with_capacity(qc.signatures().len());
+    for signature in qc.signatures() {
+        let signer = signature.public_key();
+        if !committee.contains(signer) || signers.contains(&signer) {
+            continue;
+        }
+        let maybe_pos = unused_leaf_hashes
+            .iter()
+            .position(|leaf_hash| signature.verify(vote_challenge(leaf_hash, qc.block_id(), &qc.decision())));
+        if let Some(pos) = maybe_pos {
+            unused_leaf_hashes.swap_remove(pos);
+            signers.push(signer);
+        }
+    }
+
+    if signers.len() < committee.quorum_threshold() {
+        return Err(CommsRpcConsensusSyncError::InvalidResponse(anyhow::anyhow!(
+            "Checkpoint QC {} has {} valid committee signature(s) but the quorum threshold is {}",
+            qc,
+            signers.len(),
+            committee.quorum_threshold()
+        )));
+    }
+
+    Ok(())
+}
+
+/// Checks that at least `required` members of the committee attest to the state commitment at the committed block.
+/// `attested_by` is a member that has already attested to it, if any. `get_state_commitment` requests the state
+/// commitment from a member.
+pub(crate) async fn verify_state_commitment<F, Fut>(
+    committee: &Committee<CommsPublicKey>,
+    attested_by: Option<&CommsPublicKey>,
+    required: usize,
+    block_id: &BlockId,
+    state_commitment: FixedHash,
+    mut get_state_commitment: F,
+) -> Result<(), CommsRpcConsensusSyncError>
+where
+    F: FnMut(CommsPublicKey) -> Fut,
+    Fut: Future<Output = Result<FixedHash, CommsRpcConsensusSyncError>>,
+{
+    let mut num_attested = usize::from(attested_by.is_some());
+    for member in committee.iter().filter(|m| Some(*m) != attested_by) {
+        if num_attested >= required {
+            break;
+        }
+        match get_state_commitment(member.clone()).await {
+            Ok(commitment) if commitment == state_commitment => {
+                num_attested += 1;
+            },
+            Ok(commitment) => {
+                warn!(
+                    target: LOG_TARGET,
+                    "Peer {} reported state commitment {} for block {} but the substates have {}",
+                    member,
+                    commitment,
+                    block_id,
+                    state_commitment
+                );
+            },
+            Err(err) => {
+                debug!(target: LOG_TARGET, "Failed to get state commitment from peer {}: {}", member, err);
+            },
+        }
+    }
+
+    if num_attested < required {
+        return Err(CommsRpcConsensusSyncError::InvalidResponse(anyhow::anyhow!(
+            "State commitment {} for block {} was attested by {} committee member(s) but {} are required",
+            state_commitment,
+            block_id,
+            num_attested,
+            required
+        )));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use futures::executor::block_on;
+    use tari_common_types::types::PrivateKey;
+    use tari_crypto::{
+        keys::{PublicKey as _, SecretKey},
+        tari_utilities::ByteArray,
+    };
+    use tari_dan_common_types::{Epoch, NodeHeight, View};
+    use tari_dan_storage::consensus_models::{QuorumDecision, ValidatorSignature};
+
+    use super::*;
+
+    fn create_keys(n: usize) -> Vec<(PrivateKey, CommsPublicKey)> {
+        (0..n)
+            .map(|_| {
+                let secret = PrivateKey::random(&mut rand::rngs::OsRng);
+                let public = CommsPublicKey::from_secret_key(&secret);
+                (secret, public)
+            })
+            .collect()
+    }
+
+    fn create_block() -> Block<CommsPublicKey> {
+        let zero_block = Block::zero_block();
+        Block::new(
+            *zero_block.id(),
+            zero_block.justify().clone(),
+            None,
+            NodeHeight(1),
+            View(1),
+            Epoch(0),
+            None,
+            Default::default(),
+            Default::default(),
+            Default::default(),
+        )
+    }
+
+    /// Creates a QC for the block signed by the given validators, each voting with its own leaf hash
+    fn create_qc(
+        block: &Block<CommsPublicKey>,
+        signers: &[(PrivateKey, CommsPublicKey)],
+        decision: QuorumDecision,
+    ) -> QuorumCertificate<CommsPublicKey> {
+        let mut signatures = Vec::with_capacity(signers.len());
+        let mut leaf_hashes = Vec::with_capacity(signers.len());
+        for (secret, public) in signers {
+            let leaf_hash = FixedHash::try_from(public.as_bytes()).unwrap();
+            let challenge = vote_challenge(&leaf_hash, block.id(), &decision);
+            signatures.push(ValidatorSignature::sign(secret, challenge));
+            leaf_hashes.push(leaf_hash);
+        }
+        QuorumCertificate::new(
+            *block.id(),
+            block.height(),
+            block.epoch(),
+            signatures,
+            QuorumCertificate::<CommsPublicKey>::genesis().merged_proof().clone(),
+            leaf_hashes,
+            decision,
+        )
+    }
+
+    fn committee_of(keys: &[(PrivateKey, CommsPublicKey)]) -> Committee<CommsPublicKey> {
+        Committee::new(keys.iter().map(|(_, public)| public.clone()).collect())
+    }
+
+    #[test]
+    fn it_accepts_a_checkpoint_qc_signed_by_a_quorum() {
+        let keys = create_keys(4);
+        let committee = committee_of(&keys);
+        let block = create_block();
+        let qc = create_qc(&block, &keys[..3], QuorumDecision::Accept);
+        verify_checkpoint_qc(&block, &qc, &committee).unwrap();
+    }
+
+    #[test]
+    fn it_rejects_a_checkpoint_qc_without_a_quorum() {
+        let keys = create_keys(4);
+        let committee = committee_of(&keys);
+        let block = create_block();
+        let qc = create_qc(&block, &keys[..2], QuorumDecision::Accept);
+        verify_checkpoint_qc(&block, &qc, &committee).unwrap_err();
+    }
+
+    #[test]
+    fn it_rejects_a_forged_checkpoint_qc() {
+        let keys = create_keys(4);
+        let committee = committee_of(&keys);
+        let block = create_block();
+
+        // Signed by validators that are not in the committee
+        let outsiders = create_keys(3);
+        let qc = create_qc(&block, &outsiders, QuorumDecision::Accept);
+        verify_checkpoint_qc(&block, &qc, &committee).unwrap_err();
+
+        // Signed for another block
+        let other_block = Block::zero_block();
+        let qc = create_qc(&other_block, &keys, QuorumDecision::Accept);
+        verify_checkpoint_qc(&block, &qc, &committee).unwrap_err();
+
+        // Signatures for another block in a QC for this block
+        let signed_for_other = create_qc(&other_block, &keys, QuorumDecision::Accept);
+        let qc = QuorumCertificate::new(
+            *block.id(),
+            block.height(),
+            block.epoch(),
+            signed_for_other.signatures().to_vec(),
+            signed_for_other.merged_proof().clone(),
+            signed_for_other.leaf_hashes().to_vec(),
+            QuorumDecision::Accept,
+        );
+        verify_checkpoint_qc(&block, &qc, &committee).unwrap_err();
+
+        // A reject QC does not certify the block
+        let qc = create_qc(&block, &keys, QuorumDecision::Reject);
+        verify_checkpoint_qc(&block, &qc, &committee).unwrap_err();
+
+        // The same validator signing more than once counts once
+        let repeated = vec![keys[0].clone(), keys[0].clone(), keys[0].clone()];
+        let qc = create_qc(&block, &repeated, QuorumDecision::Accept);
+        verify_checkpoint_qc(&block, &qc, &committee).unwrap_err();
+    }
+
+    #[test]
+    fn it_requires_f_plus_one_attestations_of_the_state_commitment() {
+        let keys = create_keys(4);
+        let committee = committee_of(&keys);
+        let required = committee.max_failures() + 1;
+        let block_id = *create_block().id();
+        let commitment = FixedHash::from([1u8; 32]);
+        let forged = FixedHash::from([2u8; 32]);
+
+        let check = |sender: Option<&CommsPublicKey>, responses: HashMap<CommsPublicKey, FixedHash>| {
+            block_on(verify_state_commitment(
+                &committee,
+                sender,
+                required,
+                &block_id,
+                commitment,
+                |member| {
+                    let response = responses.get(&member).copied().ok_or_else(|| {
+                        CommsRpcConsensusSyncError::InvalidResponse(anyhow::anyhow!("Peer unavailable"))
+                    });
+                    async move { response }
+                },
+            ))
+        };
+
+        // The sender and one other member attest to it
+        let responses = HashMap::from([(keys[1].1.clone(), commitment)]);
+        check(Some(&keys[0].1), responses).unwrap();
+
+        // Only the sender attests to the commitment, the rest of the committee reports another one
+        let responses = keys[1..].iter().map(|(_, public)| (public.clone(), forged)).collect();
+        check(Some(&keys[0].1), responses).unwrap_err();
+
+        // Unreachable members do not count as attestations
+        check(Some(&keys[0].1), HashMap::new()).unwrap_err();
+
+        // Without a sender, f+1 members have to report it
+        let responses = keys[..required]
+            .iter()
+            .map(|(_, public)| (public.clone(), commitment))
+            .collect();
+        check(None, responses).unwrap();
+    }
+}
@@ -1,10 +1,13 @@
 //   Copyright 2023 The Tari Project
 //   SPDX-License-Identifier: BSD-3-Clause
 
-use tari_dan_common_types::{committee::Committee, NodeAddressable};
-use tari_dan_storage::consensus_models::Block;
+use tari_dan_common_types::{committee::Committee, NodeAddressable, NodeHeight};
+use tari_dan_storage::consensus_models::{Block, QuorumCertificate};
 
-use crate::{hotstuff::ProposalValidationError, traits::LeaderStrategy};
+use crate::{
+    hotstuff::{ConsensusConfig, ProposalValidationError},
+    traits::{LeaderStrategy, ValidatorSignatureService, VoteSignatureService},
+};
 
 pub fn check_hash_and_height<TAddr: NodeAddressable>(
     candidate_block: &Block<TAddr>,
@@ -28,12 +31,93 @@ pub fn check_hash_and_height<TAddr: NodeAddressable>(
     Ok(())
 }
 
+/// Checks that the block is within the limits that an honest leader adheres to when building a block.
+pub fn check_block_limits<TAddr: NodeAddressable>(
+    config: &ConsensusConfig,
+    candidate_block: &Block<TAddr>,
+) -> Result<(), ProposalValidationError> {
+    let num_commands = candidate_block.commands().len();
+    if num_commands > config.max_block_commands {
+        return Err(ProposalValidationError::TooManyCommands {
+            proposed_by: candidate_block.proposed_by().to_string(),
+            block_id: *candidate_block.id(),
+            num_commands,
+            max_commands: config.max_block_commands,
+        });
+    }
+
+    let mut size_bytes = 0usize;
+    for command in candidate_block.commands() {
+        let encoded = tari_bor::encode(command).map_err(|e| ProposalValidationError::CommandEncodingFailed {
+            block_id: *candidate_block.id(),
+            details: e.to_string(),
+        })?;
+        size_bytes += encoded.len();
+    }
+    if size_bytes > config.max_block_size_bytes {
+        return Err(ProposalValidationError::BlockTooLarge {
+            proposed_by: candidate_block.proposed_by().to_string(),
+            block_id: *candidate_block.id(),
+            size_bytes,
+            max_size_bytes: config.max_block_size_bytes,
+        });
+    }
+
+    Ok(())
+}
+
+/// Checks that the block carries the epoch cutoff of its parent or starts a new cutoff at its own height, and that it
+/// does not prepare new transactions once the epoch has a cutoff.
+///
+/// A cutoff started by the leader is accepted even if this node has not yet observed that the epoch is ending. Nodes
+/// scan the base layer at different times, so requiring each voter to have seen the end of the epoch would split the
+/// committee. A cutoff only stops new transactions from being prepared for the rest of the epoch, which a leader can
+/// already do by leaving them out of its blocks.
+pub fn check_epoch_cutoff<TAddr: NodeAddressable>(
+    parent_block: &Block<TAddr>,
+    candidate_block: &Block<TAddr>,
+) -> Result<(), ProposalValidationError> {
+    let inherited = if parent_block.epoch() == candidate_block.epoch() {
+        parent_block.epoch_cutoff()
+    } else {
+        None
+    };
+    let is_valid = match (inherited, candidate_block.epoch_cutoff()) {
+        (Some(inherited), cutoff) => cutoff == Some(inherited),
+        (None, None) => true,
+        (None, Some(cutoff)) => cutoff == candidate_block.height(),
+    };
+    if !is_valid {
+        return Err(ProposalValidationError::InvalidEpochCutoff {
+            proposed_by: candidate_block.proposed_by().to_string(),
+            block_id: *candidate_block.id(),
+            epoch: candidate_block.epoch(),
+            cutoff_height: candidate_block.epoch_cutoff(),
+            parent_cutoff_height: inherited,
+        });
+    }
+
+    if let Some(cutoff_height) = candidate_block.epoch_cutoff() {
+        if candidate_block.commands().iter().any(|cmd| cmd.prepare().is_some()) {
+            return Err(ProposalValidationError::PrepareAfterEpochCutoff {
+                proposed_by: candidate_block.proposed_by().to_string(),
+                block_id: *candidate_block.id(),
+                epoch: candidate_block.epoch(),
+                height: candidate_block.height(),
+                cutoff_height,
+            });
+        }
+    }
+
+    Ok(())
+}
+
 pub fn check_proposed_by_leader<TAddr: NodeAddressable, TLeaderStrategy: LeaderStrategy<TAddr>>(
     leader_strategy: &TLeaderStrategy,
     local_committee: &Committee<TAddr>,
     candidate_block: &Block<TAddr>,
 ) -> Result<(), ProposalValidationError> {
-    let leader = leader_strategy.get_leader(local_committee, candidate_block.height());
+    let leader = leader_strategy.get_leader(local_committee, candidate_block.view());
     if leader != candidate_block.proposed_by() {
         return Err(ProposalValidationError::NotLeader {
             proposed_by: candidate_block.proposed_by().to_string(),
@@ -44,9 +128,12 @@ pub fn check_proposed_by_leader<TAddr: NodeAddressable, TLeaderStrategy: LeaderS
     Ok(())
 }
 
-pub fn check_quorum_certificate<TAddr: NodeAddressable>(
-    _local_committee: &Committee<TAddr>,
+/// Checks that the candidate block extends the block that its QC justifies and that the QC is signed by a quorum of
+/// `justify_committee`, the local committee in the epoch of the QC.
+pub fn check_quorum_certificate<TAddr: NodeAddressable, TSignatureService: VoteSignatureService<TAddr>>(
+    justify_committee: &Committee<TAddr>,
     candidate_block: &Block<TAddr>,
+    signing_service: &TSignatureService,
 ) -> Result<(), ProposalValidationError> {
     if candidate_block.height() < candidate_block.justify().block_height() {
         return Err(ProposalValidationError::CandidateBlockNotHigherThanJustify {
@@ -54,7 +141,123 @@ pub fn check_quorum_certificate<TAddr: NodeAddressable>(
             candidate_block_height: candidate_block.height(),
         });
     }
-    // TODO: validate QC
+
+    // Blocks always extend the block that they justify
+    if candidate_block.parent() != candidate_block.justify().block_id() ||
+        candidate_block.height() != candidate_block.justify().block_height() + NodeHeight(1)
+    {
+        return Err(ProposalValidationError::CandidateBlockDoesNotExtendJustify {
+            justify_block_height: candidate_block.justify().block_height(),
+            candidate_block_height: candidate_block.height(),
+        });
+    }
+
+    check_quorum_certificate_signatures(justify_committee, candidate_block.justify(), signing_service)
+}
+
+/// Checks that the QC is signed by a quorum of distinct members of the committee. Each signature must be a vote for the
+/// QC's block and decision using one of the QC's leaf hashes, and each leaf hash may only be used once. The genesis QC
+/// has no signatures and is always valid.
+pub fn check_quorum_certificate_signatures<TAddr: NodeAddressable, TSignatureService: VoteSignatureService<TAddr>>(
+    committee: &Committee<TAddr>,
+    qc: &QuorumCertificate<TAddr>,
+    signing_service: &TSignatureService,
+) -> Result<(), ProposalValidationError> {
+    if qc.is_genesis() {
+        return Ok(());
+    }
+
+    let mut unused_leaf_hashes = qc.leaf_hashes().to_vec();
+    let mut signers = Vec::with_capacity(qc.signatures().len());
+    for signature in qc.signatures() {
+        let signer = signature.public_key();
+        if !committee.contains(signer) || signers.contains(&signer) {
+            continue;
+        }
+        let maybe_pos = unused_leaf_hashes
+            .iter()
+            .position(|leaf_hash| signing_service.verify(signature, leaf_hash, qc.block_id(), &qc.decision()));
+        if let Some(pos) = maybe_pos {
+            unused_leaf_hashes.swap_remove(pos);
+            signers.push(signer);
+        }
+    }
+
+    if signers.len() < committee.quorum_threshold() {
+        return Err(ProposalValidationError::InvalidQuorumCertificate {
+            qc_id: *qc.id(),
+            block_id: *qc.block_id(),
+            details: format!(
+                "{} valid committee signature(s) but the quorum threshold is {}",
+                signers.len(),
+                committee.quorum_threshold()
+            ),
+        });
+    }
+
+    Ok(())
+}
+
+pub fn check_timeout_certificate<TAddr: NodeAddressable, TSignatureService: ValidatorSignatureService<TAddr>>(
+    local_committee: &Committee<TAddr>,
+    candidate_block: &Block<TAddr>,
+    signing_service: &TSignatureService,
+) -> Result<(), ProposalValidationError> {
+    let Some(tc) = candidate_block.timeout_certificate() else {
+        return Ok(());
+    };
+
+    if tc.epoch() != candidate_block.epoch() || tc.new_view() != candidate_block.view() {
+        return Err(ProposalValidationError::InvalidTimeoutCertificate {
+            proposed_by: candidate_block.proposed_by().to_string(),
+            block_id: *candidate_block.id(),
+            details: format!(
+                "{} does not match block epoch {} and view {}",
+                tc,
+                candidate_block.epoch(),
+                candidate_block.view()
+            ),
+        });
+    }
+
+    if !tc.is_quorum_of(local_committee) {
+        return Err(ProposalValidationError::InvalidTimeoutCertificate {
+            proposed_by: candidate_block.proposed_by().to_string(),
+            block_id: *candidate_block.id(),
+            details: format!(
+                "{} is not signed by a quorum of {} distinct committee members",
+                tc,
+                local_committee.quorum_threshold()
+            ),
+        });
+    }
+
+    if let Some(invalid) = tc
+        .new_views()
+        .iter()
+        .find(|nv| !signing_service.verify_signed_new_view(tc.epoch(), tc.new_view(), nv))
+    {
+        return Err(ProposalValidationError::InvalidTimeoutCertificate {
+            proposed_by: candidate_block.proposed_by().to_string(),
+            block_id: *candidate_block.id(),
+            details: format!("{} contains an invalid NEWVIEW signature from {}", tc, invalid.signer()),
+        });
+    }
+
+    // The leader must extend the highest QC that the committee has seen, otherwise a block that a quorum may have
+    // locked on could be abandoned
+    if candidate_block.justify().block_height() < tc.max_high_qc_height() {
+        return Err(ProposalValidationError::InvalidTimeoutCertificate {
+            proposed_by: candidate_block.proposed_by().to_string(),
+            block_id: *candidate_block.id(),
+            details: format!(
+                "block justifies height {} but {} contains a high QC at height {}",
+                candidate_block.justify().block_height(),
+                tc,
+                tc.max_high_qc_height()
+            ),
+        });
+    }
 
     Ok(())
 }
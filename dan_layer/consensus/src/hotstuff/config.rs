@@ -0,0 +1,34 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use std::time::Duration;
+
+#[derive(Debug, Clone)]
+pub struct ConsensusConfig {
+    /// The maximum number of commands that a leader may include in a block
+    pub max_block_commands: usize,
+    /// The maximum total encoded size of the commands in a block, in bytes
+    pub max_block_size_bytes: usize,
+    /// The maximum number of new transactions from a single signer that a leader will include in a block
+    pub max_block_transactions_per_signer: usize,
+    /// The target time between blocks
+    pub block_time: Duration,
+    /// The time to wait for a proposal, in addition to the block time, before the leader is considered to have failed
+    pub leader_timeout: Duration,
+    /// The upper bound of the exponential backoff added to the leader timeout after consecutive leader failures
+    pub max_delta: Duration,
+}
+
+impl Default for ConsensusConfig {
+    fn default() -> Self {
+        Self {
+            max_block_commands: 1000,
+            max_block_size_bytes: 4 * 1024 * 1024,
+            max_block_transactions_per_signer: 100,
+            // We're starting slow with 10s but should be 1s in the future
+            block_time: Duration::from_secs(10),
+            leader_timeout: Duration::from_secs(2),
+            max_delta: Duration::from_secs(300),
+        }
+    }
+}
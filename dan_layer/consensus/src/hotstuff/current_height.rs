@@ -8,6 +8,8 @@ use std::{
 
 use tari_dan_common_types::NodeHeight;
 
+use crate::metrics;
+
 #[derive(Debug, Clone)]
 pub struct CurrentHeight {
     height: Arc<AtomicU64>,
@@ -20,10 +22,6 @@ impl CurrentHeight {
         }
     }
 
-    pub fn next_height(&self) -> NodeHeight {
-        self.height.fetch_add(1, atomic::Ordering::SeqCst).into()
-    }
-
     pub fn get(&self) -> NodeHeight {
         self.height.load(atomic::Ordering::SeqCst).into()
     }
@@ -41,6 +39,7 @@ impl CurrentHeight {
 
     pub fn set(&self, height: NodeHeight) {
         self.height.store(height.as_u64(), atomic::Ordering::SeqCst);
+        metrics::CURRENT_HEIGHT.set(height.as_u64() as i64);
     }
 }
 
@@ -0,0 +1,56 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use std::{
+    fmt::Display,
+    sync::{atomic, atomic::AtomicU64, Arc},
+};
+
+use tari_dan_common_types::View;
+
+use crate::metrics;
+
+#[derive(Debug, Clone)]
+pub struct CurrentView {
+    view: Arc<AtomicU64>,
+}
+
+impl CurrentView {
+    pub fn new() -> Self {
+        Self {
+            view: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// Moves to the next view and returns it
+    pub fn next_view(&self) -> View {
+        let view = self.view.fetch_add(1, atomic::Ordering::SeqCst) + 1;
+        metrics::CURRENT_VIEW.set(view as i64);
+        View(view)
+    }
+
+    pub fn get(&self) -> View {
+        self.view.load(atomic::Ordering::SeqCst).into()
+    }
+
+    /// Updates the view if the new view is greater than the current view.
+    /// Returns true if the view was updated, otherwise false.
+    pub fn update(&self, view: View) -> bool {
+        let is_updated = self.view.fetch_max(view.as_u64(), atomic::Ordering::SeqCst) < view.as_u64();
+        if is_updated {
+            metrics::CURRENT_VIEW.set(view.as_u64() as i64);
+        }
+        is_updated
+    }
+
+    pub fn set(&self, view: View) {
+        self.view.store(view.as_u64(), atomic::Ordering::SeqCst);
+        metrics::CURRENT_VIEW.set(view.as_u64() as i64);
+    }
+}
+
+impl Display for CurrentView {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.get())
+    }
+}
@@ -0,0 +1,40 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use tari_dan_common_types::{Epoch, NodeHeight};
+use tari_dan_storage::consensus_models::Block;
+
+/// The number of blocks after the epoch cutoff in which transactions that have already been prepared can still be
+/// finalized. From then on, blocks abort the prepared transactions that have not reached LocalPrepared.
+pub const EPOCH_CUTOFF_FINALIZE_BLOCKS: u64 = 10;
+
+/// Returns the epoch cutoff that a block at `height` in `epoch` that extends `parent` must carry. The cutoff is
+/// inherited from a parent in the same epoch. Otherwise, it is set to `height` if `ending_epoch` (the epoch that this
+/// node has observed to be ending on the base layer) is `epoch`.
+///
+/// Nodes observe the end of an epoch at different times, but the cutoff is part of the block, so all nodes agree on it
+/// once the block is committed.
+pub fn next_epoch_cutoff<TAddr>(
+    parent: &Block<TAddr>,
+    epoch: Epoch,
+    height: NodeHeight,
+    ending_epoch: Option<Epoch>,
+) -> Option<NodeHeight> {
+    if parent.epoch() == epoch && parent.epoch_cutoff().is_some() {
+        return parent.epoch_cutoff();
+    }
+
+    if ending_epoch == Some(epoch) {
+        Some(height)
+    } else {
+        None
+    }
+}
+
+/// Returns true if prepared transactions that have not been finalized are aborted by a block at `height` with the
+/// given epoch cutoff.
+pub fn is_finalize_period_over(epoch_cutoff: Option<NodeHeight>, height: NodeHeight) -> bool {
+    epoch_cutoff.map_or(false, |cutoff| {
+        height >= cutoff + NodeHeight(EPOCH_CUTOFF_FINALIZE_BLOCKS)
+    })
+}
@@ -1,9 +1,9 @@
 //   Copyright 2023 The Tari Project
 //   SPDX-License-Identifier: BSD-3-Clause
 
-use tari_dan_common_types::{Epoch, NodeHeight};
+use tari_dan_common_types::{Epoch, NodeHeight, View};
 use tari_dan_storage::{
-    consensus_models::{BlockId, LeafBlock, LockedBlock, TransactionPoolError},
+    consensus_models::{BlockId, LeafBlock, LockedBlock, QcId, TransactionPoolError},
     StorageError,
 };
 use tari_epoch_manager::EpochManagerError;
@@ -40,6 +40,8 @@ pub enum HotStuffError {
     StateManagerError(anyhow::Error),
     #[error("Invalid vote signature from {signer_public_key} (unauthenticated)")]
     InvalidVoteSignature { signer_public_key: String },
+    #[error("Invalid NEWVIEW signature from {from}: {details}")]
+    InvalidNewViewSignature { from: String, details: String },
     #[error("Transaction pool error: {0}")]
     TransactionPoolError(#[from] TransactionPoolError),
     #[error("Transaction {transaction_id} does not exist")]
@@ -55,14 +57,6 @@ pub enum HotStuffError {
     },
     #[error("Pacemaker channel dropped: {details}")]
     PacemakerChannelDropped { details: String },
-    #[error(
-        "Bad new view message: HighQC height {high_qc_height}, received new height {received_new_height}: {details}"
-    )]
-    BadNewViewMessage {
-        high_qc_height: NodeHeight,
-        received_new_height: NodeHeight,
-        details: String,
-    },
     #[error("BUG Invariant error occurred: {0}")]
     InvariantError(String),
     #[error("Sync error: {0}")]
@@ -100,12 +94,24 @@ pub enum ProposalValidationError {
         block_description: String,
         justify_block: BlockId,
     },
+    #[error("Parent block {parent_block} for proposed block {block_description} by {proposed_by} not found")]
+    ParentBlockNotFound {
+        proposed_by: String,
+        block_description: String,
+        parent_block: BlockId,
+    },
     #[error("QC in block {block_id} that was proposed by {proposed_by} is invalid: {details}")]
     JustifyBlockInvalid {
         proposed_by: String,
         block_id: BlockId,
         details: String,
     },
+    #[error("QC {qc_id} for block {block_id} is invalid: {details}")]
+    InvalidQuorumCertificate {
+        qc_id: QcId,
+        block_id: BlockId,
+        details: String,
+    },
     #[error("Candidate block {candidate_block_height} is not higher than justify {justify_block_height}")]
     CandidateBlockNotHigherThanJustify {
         justify_block_height: NodeHeight,
@@ -142,4 +148,76 @@ pub enum ProposalValidationError {
     },
     #[error("Proposed block {block_id} {height} already has been processed")]
     BlockAlreadyProcessed { block_id: BlockId, height: NodeHeight },
+    #[error("Signature for block {block_id} proposed by {proposed_by} is invalid: {details}")]
+    InvalidProposalSignature {
+        proposed_by: String,
+        block_id: BlockId,
+        details: String,
+    },
+    #[error("Timeout certificate in block {block_id} proposed by {proposed_by} is invalid: {details}")]
+    InvalidTimeoutCertificate {
+        proposed_by: String,
+        block_id: BlockId,
+        details: String,
+    },
+    #[error(
+        "Block {block_id} proposed by {proposed_by} has epoch cutoff {cutoff_height:?} for epoch {epoch} but its \
+         parent has {parent_cutoff_height:?}"
+    )]
+    InvalidEpochCutoff {
+        proposed_by: String,
+        block_id: BlockId,
+        epoch: Epoch,
+        cutoff_height: Option<NodeHeight>,
+        parent_cutoff_height: Option<NodeHeight>,
+    },
+    #[error(
+        "Block {block_id} proposed by {proposed_by} at height {height} prepares new transactions after the cutoff \
+         height {cutoff_height} for epoch {epoch}"
+    )]
+    PrepareAfterEpochCutoff {
+        proposed_by: String,
+        block_id: BlockId,
+        epoch: Epoch,
+        height: NodeHeight,
+        cutoff_height: NodeHeight,
+    },
+    #[error(
+        "Block {block_id} proposed by {proposed_by} has {num_commands} command(s) but the maximum is {max_commands}"
+    )]
+    TooManyCommands {
+        proposed_by: String,
+        block_id: BlockId,
+        num_commands: usize,
+        max_commands: usize,
+    },
+    #[error(
+        "Commands in block {block_id} proposed by {proposed_by} are {size_bytes} bytes but the maximum is \
+         {max_size_bytes} bytes"
+    )]
+    BlockTooLarge {
+        proposed_by: String,
+        block_id: BlockId,
+        size_bytes: usize,
+        max_size_bytes: usize,
+    },
+    #[error("Failed to encode command in block {block_id}: {details}")]
+    CommandEncodingFailed { block_id: BlockId, details: String },
+    #[error(
+        "Block {block_id} proposed by {proposed_by} in {view} does not justify the previous view (justify view: \
+         {justify_view}) and has no timeout certificate"
+    )]
+    MissingTimeoutCertificate {
+        proposed_by: String,
+        block_id: BlockId,
+        view: View,
+        justify_view: View,
+    },
+    #[error("Block {block_id} proposed by {proposed_by} in {view} is not higher than justify view {justify_view}")]
+    CandidateBlockViewNotHigherThanJustify {
+        proposed_by: String,
+        block_id: BlockId,
+        view: View,
+        justify_view: View,
+    },
 }
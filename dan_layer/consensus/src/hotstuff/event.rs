@@ -1,7 +1,7 @@
 //    Copyright 2023 The Tari Project
 //    SPDX-License-Identifier: BSD-3-Clause
 
-use tari_dan_common_types::NodeHeight;
+use tari_dan_common_types::{NodeHeight, View};
 use tari_dan_storage::consensus_models::BlockId;
 
 #[derive(Debug, Clone)]
@@ -11,5 +11,5 @@ pub enum HotstuffEvent {
     /// A critical failure occurred in consensus
     Failure { message: String },
     /// A leader has timed out
-    LeaderTimeout { new_height: NodeHeight },
+    LeaderTimeout { new_view: View },
 }
@@ -1,7 +1,10 @@
 //   Copyright 2023 The Tari Project
 //   SPDX-License-Identifier: BSD-3-Clause
 mod common;
+mod config;
 mod current_height;
+mod current_view;
+mod epoch_cutoff;
 mod error;
 mod event;
 mod on_beat;
@@ -21,12 +24,18 @@ mod on_sync_request;
 // mod on_sync_response;
 mod pacemaker;
 mod pacemaker_handle;
+mod participation;
 mod proposer;
 mod state_machine;
+mod state_recovery;
 mod vote_receiver;
 mod worker;
 
+pub use config::*;
+pub use epoch_cutoff::*;
 pub use error::*;
 pub use event::*;
+pub use participation::*;
 pub use state_machine::*;
+pub use state_recovery::*;
 pub use worker::*;
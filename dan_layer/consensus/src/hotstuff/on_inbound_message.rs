@@ -7,7 +7,7 @@ use std::{
 };
 
 use log::*;
-use tari_dan_common_types::{NodeAddressable, NodeHeight};
+use tari_dan_common_types::{NodeAddressable, NodeHeight, View};
 use tari_dan_storage::{
     consensus_models::{Block, TransactionRecord},
     StateStore,
@@ -19,10 +19,16 @@ use tari_transaction::TransactionId;
 use tokio::{sync::mpsc, time};
 
 use crate::{
-    block_validations::{check_hash_and_height, check_proposed_by_leader, check_quorum_certificate},
-    hotstuff::{error::HotStuffError, pacemaker_handle::PaceMakerHandle},
-    messages::{HotstuffMessage, ProposalMessage, RequestMissingTransactionsMessage},
-    traits::ConsensusSpec,
+    block_validations::{
+        check_block_limits,
+        check_hash_and_height,
+        check_proposed_by_leader,
+        check_quorum_certificate,
+        check_timeout_certificate,
+    },
+    hotstuff::{error::HotStuffError, pacemaker_handle::PaceMakerHandle, ConsensusConfig, ProposalValidationError},
+    messages::{HotstuffMessage, NewViewMessage, ProposalMessage, RequestMissingTransactionsMessage},
+    traits::{ConsensusSpec, ValidatorSignatureService},
 };
 
 const LOG_TARGET: &str = "tari::dan::consensus::hotstuff::inbound_messages";
@@ -30,9 +36,11 @@ const LOG_TARGET: &str = "tari::dan::consensus::hotstuff::inbound_messages";
 pub type IncomingMessageResult<TAddr> = Result<Option<(TAddr, HotstuffMessage<TAddr>)>, NeedsSync<TAddr>>;
 
 pub struct OnInboundMessage<TConsensusSpec: ConsensusSpec> {
+    config: ConsensusConfig,
     store: TConsensusSpec::StateStore,
     epoch_manager: TConsensusSpec::EpochManager,
     leader_strategy: TConsensusSpec::LeaderStrategy,
+    signing_service: TConsensusSpec::VoteSignatureService,
     pacemaker: PaceMakerHandle,
     rx_hotstuff_message: mpsc::Receiver<(TConsensusSpec::Addr, HotstuffMessage<TConsensusSpec::Addr>)>,
     tx_outbound_message: mpsc::Sender<(TConsensusSpec::Addr, HotstuffMessage<TConsensusSpec::Addr>)>,
@@ -45,10 +53,13 @@ pub struct OnInboundMessage<TConsensusSpec: ConsensusSpec> {
 impl<TConsensusSpec> OnInboundMessage<TConsensusSpec>
 where TConsensusSpec: ConsensusSpec
 {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
+        config: ConsensusConfig,
         store: TConsensusSpec::StateStore,
         epoch_manager: TConsensusSpec::EpochManager,
         leader_strategy: TConsensusSpec::LeaderStrategy,
+        signing_service: TConsensusSpec::VoteSignatureService,
         pacemaker: PaceMakerHandle,
         rx_hotstuff_message: mpsc::Receiver<(TConsensusSpec::Addr, HotstuffMessage<TConsensusSpec::Addr>)>,
         tx_outbound_message: mpsc::Sender<(TConsensusSpec::Addr, HotstuffMessage<TConsensusSpec::Addr>)>,
@@ -57,9 +68,11 @@ where TConsensusSpec: ConsensusSpec
     ) -> Self {
         let (tx_msg_ready, rx_msg_ready) = mpsc::unbounded_channel();
         Self {
+            config,
             store,
             epoch_manager,
             leader_strategy,
+            signing_service,
             pacemaker,
             rx_hotstuff_message,
             tx_outbound_message,
@@ -70,25 +83,29 @@ where TConsensusSpec: ConsensusSpec
         }
     }
 
-    pub async fn next(&mut self, current_height: NodeHeight) -> IncomingMessageResult<TConsensusSpec::Addr> {
+    pub async fn next(
+        &mut self,
+        current_view: View,
+        current_height: NodeHeight,
+    ) -> IncomingMessageResult<TConsensusSpec::Addr> {
         loop {
             tokio::select! {
                 biased;
 
                 _ = self.shutdown.wait() => { break Ok(None); }
 
-                msg_or_sync = self.message_buffer.next(current_height) => {
+                msg_or_sync = self.message_buffer.next(current_view, current_height) => {
                     break msg_or_sync;
                 },
 
                 Some((from, msg)) = self.rx_hotstuff_message.recv() => {
-                    if let Err(err) = self.handle_hotstuff_message(current_height, from, msg).await {
+                    if let Err(err) = self.handle_hotstuff_message(current_view, from, msg).await {
                         error!(target: LOG_TARGET, "Error handling message: {}", err);
                     }
                 },
 
                  Some(tx_id) = self.rx_new_transactions.recv() => {
-                    if let Err(err) = self.check_if_parked_blocks_ready(current_height, &tx_id).await {
+                    if let Err(err) = self.check_if_parked_blocks_ready(current_view, &tx_id).await {
                         error!(target: LOG_TARGET, "Error checking parked blocks: {}", err);
                     }
                 },
@@ -114,56 +131,71 @@ where TConsensusSpec: ConsensusSpec
 
     pub async fn handle_hotstuff_message(
         &self,
-        current_height: NodeHeight,
+        current_view: View,
         from: TConsensusSpec::Addr,
         msg: HotstuffMessage<TConsensusSpec::Addr>,
     ) -> Result<(), HotStuffError> {
         match msg {
             HotstuffMessage::Proposal(msg) => {
-                self.process_proposal(current_height, msg).await?;
+                self.process_proposal(current_view, msg).await?;
+            },
+            msg => {
+                // Check signatures before any further processing
+                match &msg {
+                    HotstuffMessage::ForeignProposal(proposal) => self.check_proposal_signature(proposal)?,
+                    HotstuffMessage::NewView(new_view) => self.check_new_view_signature(&from, new_view)?,
+                    _ => {},
+                }
+                self.tx_msg_ready
+                    .send((from, msg))
+                    .map_err(|_| HotStuffError::InternalChannelClosed {
+                        context: "tx_msg_ready in InboundMessageWorker::handle_hotstuff_message",
+                    })?;
             },
-            msg => self
-                .tx_msg_ready
-                .send((from, msg))
-                .map_err(|_| HotStuffError::InternalChannelClosed {
-                    context: "tx_msg_ready in InboundMessageWorker::handle_hotstuff_message",
-                })?,
         }
         Ok(())
     }
 
     async fn process_proposal(
         &self,
-        current_height: NodeHeight,
+        current_view: View,
         proposal: ProposalMessage<TConsensusSpec::Addr>,
     ) -> Result<(), HotStuffError> {
+        self.check_proposal_signature(&proposal)?;
         let ProposalMessage { block } = proposal;
 
         info!(
             target: LOG_TARGET,
-            "📜 new unvalidated PROPOSAL message {} from {} (current height = {})",
+            "📜 new unvalidated PROPOSAL message {} from {} (current view = {})",
             block,
             block.proposed_by(),
-            current_height,
+            current_view,
         );
 
-        if block.height() < current_height {
+        if block.view() < current_view {
             debug!(
                 target: LOG_TARGET,
-                "🔥 Block {} is lower than current height {}. Ignoring.",
+                "🔥 Block {} is for a view lower than current {}. Ignoring.",
                 block,
-                current_height
+                current_view
             );
             return Ok(());
         }
 
         check_hash_and_height(&block)?;
+        check_block_limits(&self.config, &block)?;
         let committee_for_block = self
             .epoch_manager
             .get_committee_by_validator_address(block.epoch(), block.proposed_by())
             .await?;
         check_proposed_by_leader(&self.leader_strategy, &committee_for_block, &block)?;
-        check_quorum_certificate(&committee_for_block, &block)?;
+        let justify_committee = if block.justify().epoch() == block.epoch() {
+            committee_for_block.clone()
+        } else {
+            self.epoch_manager.get_local_committee(block.justify().epoch()).await?
+        };
+        check_quorum_certificate(&justify_committee, &block, &self.signing_service)?;
+        check_timeout_certificate(&committee_for_block, &block, &self.signing_service)?;
 
         let Some(ready_block) = self.handle_missing_transactions(block).await? else {
             // Block not ready
@@ -175,19 +207,86 @@ where TConsensusSpec: ConsensusSpec
         Ok(())
     }
 
+    /// Checks that the block was signed by the validator that proposed it. Proposals are relayed during sync, so the
+    /// signer is not necessarily the sender of the message.
+    fn check_proposal_signature(&self, proposal: &ProposalMessage<TConsensusSpec::Addr>) -> Result<(), HotStuffError> {
+        let block = &proposal.block;
+        let Some(signature) = block.signature() else {
+            return Err(ProposalValidationError::InvalidProposalSignature {
+                proposed_by: block.proposed_by().to_string(),
+                block_id: *block.id(),
+                details: "Block is not signed".to_string(),
+            }
+            .into());
+        };
+
+        if signature.public_key() != block.proposed_by() {
+            return Err(ProposalValidationError::InvalidProposalSignature {
+                proposed_by: block.proposed_by().to_string(),
+                block_id: *block.id(),
+                details: format!("Block is signed by {} instead of the proposer", signature.public_key()),
+            }
+            .into());
+        }
+
+        if !self.signing_service.verify_proposal(signature, block.id()) {
+            return Err(ProposalValidationError::InvalidProposalSignature {
+                proposed_by: block.proposed_by().to_string(),
+                block_id: *block.id(),
+                details: "Signature is invalid".to_string(),
+            }
+            .into());
+        }
+
+        Ok(())
+    }
+
+    fn check_new_view_signature(
+        &self,
+        from: &TConsensusSpec::Addr,
+        message: &NewViewMessage<TConsensusSpec::Addr>,
+    ) -> Result<(), HotStuffError> {
+        if message.signature.public_key() != from {
+            return Err(HotStuffError::InvalidNewViewSignature {
+                from: from.to_string(),
+                details: format!(
+                    "NEWVIEW is signed by a different node {}",
+                    message.signature.public_key()
+                ),
+            });
+        }
+
+        let is_valid = self.signing_service.verify_new_view(
+            &message.signature,
+            message.epoch,
+            message.new_view,
+            message.high_qc.id(),
+            message.high_qc.block_height(),
+            message.last_vote.as_ref().map(|vote| &vote.block_id),
+        );
+        if !is_valid {
+            return Err(HotStuffError::InvalidNewViewSignature {
+                from: from.to_string(),
+                details: "Signature is invalid".to_string(),
+            });
+        }
+
+        Ok(())
+    }
+
     async fn check_if_parked_blocks_ready(
         &self,
-        current_height: NodeHeight,
+        current_view: View,
         transaction_id: &TransactionId,
     ) -> Result<(), HotStuffError> {
         debug!(
             target: LOG_TARGET,
-            "🚀 Consensus (height={}) READY for new transaction with id: {}",current_height,
+            "🚀 Consensus ({}) READY for new transaction with id: {}",current_view,
             transaction_id
         );
         let maybe_unparked_block = self
             .store
-            .with_write_tx(|tx| tx.missing_transactions_remove(current_height, transaction_id))?;
+            .with_write_tx(|tx| tx.missing_transactions_remove(current_view, transaction_id))?;
 
         if let Some(unparked_block) = maybe_unparked_block {
             info!(target: LOG_TARGET, "♻️ all transactions for block {unparked_block} have been executed");
@@ -300,7 +399,7 @@ impl<TAddr: NodeAddressable> MessageBuffer<TAddr> {
         }
     }
 
-    pub async fn next(&mut self, current_height: NodeHeight) -> IncomingMessageResult<TAddr> {
+    pub async fn next(&mut self, current_view: View, current_height: NodeHeight) -> IncomingMessageResult<TAddr> {
         // Clear buffer with lower heights
         self.buffer = self.buffer.split_off(&current_height);
 
@@ -312,7 +411,14 @@ impl<TAddr: NodeAddressable> MessageBuffer<TAddr> {
         }
 
         while let Some((from, msg)) = self.next_message_or_sync(current_height).await? {
-            match msg_height(&msg) {
+            // Proposals for a previous view are stale, even if they are at the current height
+            if let Some(proposal) = msg.proposal() {
+                if proposal.block.view() < current_view {
+                    debug!(target: LOG_TARGET, "Discard message {} is for previous view {}. Current view {}", msg, proposal.block.view(), current_view);
+                    continue;
+                }
+            }
+            match msg_height(&msg, current_height) {
                 // Discard old message
                 Some(h) if h < current_height => {
                     debug!(target: LOG_TARGET, "Discard message {} is for previous height {}. Current height {}", msg, h, current_height);
@@ -385,9 +491,11 @@ pub struct NeedsSync<TAddr: NodeAddressable> {
     pub qc_height: NodeHeight,
 }
 
-fn msg_height<TAddr>(msg: &HotstuffMessage<TAddr>) -> Option<NodeHeight> {
+fn msg_height<TAddr>(msg: &HotstuffMessage<TAddr>, current_height: NodeHeight) -> Option<NodeHeight> {
     match msg {
-        HotstuffMessage::Proposal(msg) => Some(msg.block.height()),
+        // Proposals that follow a leader failure may be at or below the current height, so they are only buffered if
+        // they are for a future height
+        HotstuffMessage::Proposal(msg) => Some(msg.block.height()).filter(|h| *h > current_height),
         // Votes for block 2, occur at current height 3
         HotstuffMessage::Vote(msg) => Some(msg.block_height.saturating_add(NodeHeight(1))),
         _ => None,
@@ -2,7 +2,7 @@
 //  SPDX-License-Identifier: BSD-3-Clause
 
 use log::*;
-use tari_dan_common_types::{optional::Optional, NodeHeight};
+use tari_dan_common_types::{optional::Optional, View};
 use tari_dan_storage::{
     consensus_models::{HighQc, LastSentVote},
     StateStore,
@@ -13,7 +13,7 @@ use tokio::sync::mpsc;
 use crate::{
     hotstuff::HotStuffError,
     messages::{HotstuffMessage, NewViewMessage, VoteMessage},
-    traits::{ConsensusSpec, LeaderStrategy},
+    traits::{ConsensusSpec, LeaderStrategy, ValidatorSignatureService},
 };
 
 const LOG_TARGET: &str = "tari::dan::consensus::hotstuff::on_next_sync_view";
@@ -23,6 +23,7 @@ pub struct OnNextSyncViewHandler<TConsensusSpec: ConsensusSpec> {
     tx_leader: mpsc::Sender<(TConsensusSpec::Addr, HotstuffMessage<TConsensusSpec::Addr>)>,
     leader_strategy: TConsensusSpec::LeaderStrategy,
     epoch_manager: TConsensusSpec::EpochManager,
+    signing_service: TConsensusSpec::VoteSignatureService,
 }
 
 impl<TConsensusSpec: ConsensusSpec> OnNextSyncViewHandler<TConsensusSpec> {
@@ -31,18 +32,20 @@ impl<TConsensusSpec: ConsensusSpec> OnNextSyncViewHandler<TConsensusSpec> {
         tx_leader: mpsc::Sender<(TConsensusSpec::Addr, HotstuffMessage<TConsensusSpec::Addr>)>,
         leader_strategy: TConsensusSpec::LeaderStrategy,
         epoch_manager: TConsensusSpec::EpochManager,
+        signing_service: TConsensusSpec::VoteSignatureService,
     ) -> Self {
         Self {
             store,
             tx_leader,
             leader_strategy,
             epoch_manager,
+            signing_service,
         }
     }
 
-    pub async fn handle(&mut self, new_height: NodeHeight) -> Result<(), HotStuffError> {
+    pub async fn handle(&mut self, new_view: View) -> Result<(), HotStuffError> {
         let current_epoch = self.epoch_manager.current_epoch().await?;
-        info!(target: LOG_TARGET, "⚠️ Leader failure: NEXTSYNCVIEW for epoch {} and node height {}", current_epoch, new_height);
+        info!(target: LOG_TARGET, "⚠️ Leader failure: NEXTSYNCVIEW for epoch {} and {}", current_epoch, new_view);
         // Is the VN registered?
         if !self.epoch_manager.is_epoch_active(current_epoch).await? {
             info!(
@@ -61,16 +64,22 @@ impl<TConsensusSpec: ConsensusSpec> OnNextSyncViewHandler<TConsensusSpec> {
         })?;
 
         let local_committee = self.epoch_manager.get_local_committee(current_epoch).await?;
-        let next_leader = self
-            .leader_strategy
-            .get_leader_for_next_block(&local_committee, new_height);
+        let next_leader = self.leader_strategy.get_leader(&local_committee, new_view);
 
-        info!(target: LOG_TARGET, "🌟 Send NEWVIEW {new_height} HighQC: {} to {next_leader}", high_qc);
+        info!(target: LOG_TARGET, "🌟 Send NEWVIEW {new_view} HighQC: {} to {next_leader}", high_qc);
+        let signature = self.signing_service.sign_new_view(
+            current_epoch,
+            new_view,
+            high_qc.id(),
+            high_qc.block_height(),
+            last_sent_vote.as_ref().map(|vote| &vote.block_id),
+        );
         let message = NewViewMessage {
             high_qc,
-            new_height,
             epoch: current_epoch,
+            new_view,
             last_vote: last_sent_vote.map(VoteMessage::from),
+            signature,
         };
 
         self.tx_leader
@@ -9,15 +9,19 @@ use tari_dan_common_types::{
     optional::Optional,
     Epoch,
     NodeHeight,
+    View,
 };
 use tari_dan_storage::{
     consensus_models::{
         Block,
         Command,
+        Decision,
         HighQc,
         LastProposed,
         LeafBlock,
         QuorumCertificate,
+        TimeoutCertificate,
+        TransactionAtom,
         TransactionPool,
         TransactionPoolStage,
     },
@@ -29,70 +33,95 @@ use tokio::sync::mpsc;
 
 use super::common::CommitteeAndMessage;
 use crate::{
-    hotstuff::{common::EXHAUST_DIVISOR, error::HotStuffError},
+    hotstuff::{
+        common::EXHAUST_DIVISOR,
+        error::HotStuffError,
+        is_finalize_period_over,
+        next_epoch_cutoff,
+        ConsensusConfig,
+    },
     messages::{HotstuffMessage, ProposalMessage},
-    traits::ConsensusSpec,
+    traits::{ConsensusSpec, ValidatorSignatureService},
 };
 
 const LOG_TARGET: &str = "tari::dan::consensus::hotstuff::on_propose_locally";
 
 pub struct OnPropose<TConsensusSpec: ConsensusSpec> {
+    config: ConsensusConfig,
     store: TConsensusSpec::StateStore,
     epoch_manager: TConsensusSpec::EpochManager,
     transaction_pool: TransactionPool<TConsensusSpec::StateStore>,
     tx_broadcast: mpsc::Sender<CommitteeAndMessage<TConsensusSpec::Addr>>,
+    signing_service: TConsensusSpec::VoteSignatureService,
+    ending_epoch: Option<Epoch>,
 }
 
 impl<TConsensusSpec> OnPropose<TConsensusSpec>
 where TConsensusSpec: ConsensusSpec
 {
     pub fn new(
+        config: ConsensusConfig,
         store: TConsensusSpec::StateStore,
         epoch_manager: TConsensusSpec::EpochManager,
         transaction_pool: TransactionPool<TConsensusSpec::StateStore>,
         tx_broadcast: mpsc::Sender<CommitteeAndMessage<TConsensusSpec::Addr>>,
+        signing_service: TConsensusSpec::VoteSignatureService,
     ) -> Self {
         Self {
+            config,
             store,
             epoch_manager,
             transaction_pool,
             tx_broadcast,
+            signing_service,
+            ending_epoch: None,
         }
     }
 
+    /// Starts the epoch cutoff in the next block proposed in `epoch`, from which new transactions are no longer
+    /// prepared. New transactions are left in the pool to be handed off to the incoming committee.
+    pub fn set_ending_epoch(&mut self, epoch: Epoch) {
+        self.ending_epoch = Some(epoch);
+    }
+
     pub async fn handle(
         &self,
         epoch: Epoch,
         local_committee: Committee<TConsensusSpec::Addr>,
         leaf_block: LeafBlock,
-        is_newview_propose: bool,
+        view: View,
+        timeout_certificate: Option<TimeoutCertificate<TConsensusSpec::Addr>>,
     ) -> Result<(), HotStuffError> {
-        if let Some(last_proposed) = self.store.with_read_tx(|tx| LastProposed::get(tx)).optional()? {
-            if last_proposed.height > leaf_block.height {
-                // is_newview_propose means that a NEWVIEW has reached quorum and nodes are expecting us to propose.
-                // Re-broadcast the previous proposal
-                if is_newview_propose {
-                    if let Some(next_block) = self.store.with_read_tx(|tx| last_proposed.get_block(tx)).optional()? {
-                        info!(
-                            target: LOG_TARGET,
-                            "🌿 RE-BROADCASTING locally block {}({}) to {} validators. {} command(s), justify: {} ({}), parent: {}",
-                            next_block.id(),
-                            next_block.height(),
-                            local_committee.len(),
-                            next_block.commands().len(),
-                            next_block.justify().block_id(),
-                            next_block.justify().block_height(),
-                            next_block.parent(),
-                        );
-                        self.broadcast_proposal_locally(next_block, local_committee).await?;
-                        return Ok(());
-                    }
+        // A timeout certificate means that a NEWVIEW has reached quorum and nodes are expecting us to propose.
+        let is_newview_propose = timeout_certificate.is_some();
+        if let Some(last_proposed) = self
+            .store
+            .with_read_tx(|tx| LastProposed::get(tx)?.get_block(tx))
+            .optional()?
+        {
+            if last_proposed.view() >= view {
+                // Re-broadcast the previous proposal for this view
+                if is_newview_propose && last_proposed.view() == view {
+                    info!(
+                        target: LOG_TARGET,
+                        "🌿 RE-BROADCASTING locally block {}({}) to {} validators. {} command(s), justify: {} ({}), parent: {}",
+                        last_proposed.id(),
+                        last_proposed.height(),
+                        local_committee.len(),
+                        last_proposed.commands().len(),
+                        last_proposed.justify().block_id(),
+                        last_proposed.justify().block_height(),
+                        last_proposed.parent(),
+                    );
+                    self.broadcast_proposal_locally(last_proposed, local_committee).await?;
+                    return Ok(());
                 }
 
                 info!(
                     target: LOG_TARGET,
-                    "⤵️ SKIPPING propose for leaf {} because we already proposed block {}",
+                    "⤵️ SKIPPING propose for leaf {} in {} because we already proposed block {}",
                     leaf_block,
+                    view,
                     last_proposed,
                 );
 
@@ -115,6 +144,8 @@ where TConsensusSpec: ConsensusSpec
                 epoch,
                 &leaf_block,
                 high_qc,
+                timeout_certificate,
+                view,
                 validator.address,
                 &local_committee_shard,
                 // TODO: This just avoids issues with proposed transactions causing leader failures. Not sure if this
@@ -181,27 +212,47 @@ where TConsensusSpec: ConsensusSpec
         epoch: Epoch,
         parent_block: &LeafBlock,
         high_qc: QuorumCertificate<TConsensusSpec::Addr>,
+        timeout_certificate: Option<TimeoutCertificate<TConsensusSpec::Addr>>,
+        view: View,
         proposed_by: <TConsensusSpec::EpochManager as EpochManagerReader>::Addr,
         local_committee_shard: &CommitteeShard,
         empty_block: bool,
     ) -> Result<Block<TConsensusSpec::Addr>, HotStuffError> {
-        // TODO: Configure
-        const TARGET_BLOCK_SIZE: usize = 1000;
-        let batch = if empty_block {
+        let mut batch = if empty_block {
             vec![]
         } else {
-            self.transaction_pool.get_batch_for_next_block(tx, TARGET_BLOCK_SIZE)?
+            self.transaction_pool.get_batch_for_next_block(
+                tx,
+                self.config.max_block_commands,
+                self.config.max_block_size_bytes,
+                self.config.max_block_transactions_per_signer,
+            )?
         };
+        let next_height = parent_block.height() + NodeHeight(1);
+        let epoch_cutoff = next_epoch_cutoff(&parent_block.get_block(tx)?, epoch, next_height, self.ending_epoch);
+        if epoch_cutoff.is_some() {
+            batch.retain(|t| !t.current_stage().is_new());
+        }
+        let abort_prepared = is_finalize_period_over(epoch_cutoff, next_height);
 
         let mut total_leader_fee = 0;
-        let commands = batch
-            .into_iter()
-            .map(|t| match t.current_stage() {
+        let mut total_size_bytes = 0usize;
+        let mut commands = BTreeSet::new();
+        for t in batch {
+            let (command, leader_fee) = match t.current_stage() {
                 // If the transaction is New, propose to Prepare it
-                TransactionPoolStage::New => Ok(Command::Prepare(t.get_local_transaction_atom())),
+                TransactionPoolStage::New => (Command::Prepare(t.get_local_transaction_atom()), 0),
+                // The epoch is ending and the transaction was not finalized in time, so we propose to abort it
+                TransactionPoolStage::Prepared if abort_prepared => (
+                    Command::LocalPrepared(TransactionAtom {
+                        decision: Decision::Abort,
+                        ..t.get_local_transaction_atom()
+                    }),
+                    0,
+                ),
                 // The transaction is Prepared, this stage is only _ready_ once we know that all local nodes
                 // accepted Prepared so we propose LocalPrepared
-                TransactionPoolStage::Prepared => Ok(Command::LocalPrepared(t.get_local_transaction_atom())),
+                TransactionPoolStage::Prepared => (Command::LocalPrepared(t.get_local_transaction_atom()), 0),
                 // The transaction is LocalPrepared, meaning that we know that all foreign and local nodes have
                 // prepared. We can now propose to Accept it. We also propose the decision change which everyone should
                 // agree with if they received the same foreign LocalPrepare.
@@ -214,8 +265,7 @@ where TConsensusSpec: ConsensusSpec
                         ))
                     })?;
                     let leader_fee = t.calculate_leader_fee(involved, EXHAUST_DIVISOR);
-                    total_leader_fee += leader_fee;
-                    Ok(Command::Accept(t.get_final_transaction_atom(leader_fee)))
+                    (Command::Accept(t.get_final_transaction_atom(leader_fee)), leader_fee)
                 },
                 // Not reachable as there is nothing to propose for these stages. To confirm that all local nodes agreed
                 // with the Accept, more (possibly empty) blocks with QCs will be proposed and accepted,
@@ -226,8 +276,27 @@ where TConsensusSpec: ConsensusSpec
                         t.current_stage()
                     )
                 },
-            })
-            .collect::<Result<BTreeSet<_>, HotStuffError>>()?;
+            };
+
+            let size = tari_bor::encode(&command)
+                .map(|b| b.len())
+                .map_err(|e| HotStuffError::InvariantError(format!("Failed to encode command {}: {}", command, e)))?;
+            if total_size_bytes + size > self.config.max_block_size_bytes {
+                // A smaller command later in the batch may still fit
+                debug!(
+                    target: LOG_TARGET,
+                    "Command {} ({} bytes) does not fit in the block ({}/{} bytes used). Skipping.",
+                    command,
+                    size,
+                    total_size_bytes,
+                    self.config.max_block_size_bytes,
+                );
+                continue;
+            }
+            total_size_bytes += size;
+            total_leader_fee += leader_fee;
+            commands.insert(command);
+        }
 
         debug!(
             target: LOG_TARGET,
@@ -235,15 +304,20 @@ where TConsensusSpec: ConsensusSpec
             commands.iter().map(|c| c.to_string()).collect::<Vec<_>>().join(",")
         );
 
-        let next_block = Block::new(
+        let mut next_block = Block::new(
             *parent_block.block_id(),
             high_qc,
-            parent_block.height() + NodeHeight(1),
+            timeout_certificate,
+            next_height,
+            view,
             epoch,
+            epoch_cutoff,
             proposed_by,
             commands,
             total_leader_fee,
         );
+        let signature = self.signing_service.sign_proposal(next_block.id());
+        next_block.set_signature(signature);
 
         Ok(next_block)
     }
@@ -40,8 +40,15 @@ use tokio::sync::{
 
 use super::proposer::Proposer;
 use crate::{
-    hotstuff::{common::EXHAUST_DIVISOR, error::HotStuffError, event::HotstuffEvent, ProposalValidationError},
+    hotstuff::{
+        common::EXHAUST_DIVISOR,
+        error::HotStuffError,
+        event::HotstuffEvent,
+        is_finalize_period_over,
+        ProposalValidationError,
+    },
     messages::{HotstuffMessage, VoteMessage},
+    metrics,
     traits::{ConsensusSpec, LeaderStrategy, StateManager, VoteSignatureService},
 };
 
@@ -280,27 +287,28 @@ where TConsensusSpec: ConsensusSpec
         Ok(())
     }
 
-    /// if b_new .height > vheight && (b_new extends b_lock || b_new .justify.node.height > b_lock .height)
+    /// if b_new .view > vview && (b_new extends b_lock || b_new .justify.node.view > b_lock .view)
     ///
-    /// If we have not previously voted on this block and the node extends the current locked node, then we vote
+    /// If we have not previously voted in this view and the node extends the current locked node, then we vote
     fn should_vote(
         &self,
         tx: &mut <TConsensusSpec::StateStore as StateStore>::ReadTransaction<'_>,
         block: &Block<TConsensusSpec::Addr>,
     ) -> Result<bool, ProposalValidationError> {
         let Some(last_voted) = LastVoted::get(tx).optional()? else {
-            // Never voted, then validated.block.height() > last_voted.height (0)
+            // Never voted, then validated.block.view() > last_voted.view (0)
             return Ok(true);
         };
+        let last_voted_view = Block::get(tx, &last_voted.block_id)?.view();
 
-        // if b_new .height > vheight And ...
-        if block.height() <= last_voted.height {
+        // if b_new .view > vview And ...
+        if block.view() <= last_voted_view {
             info!(
                 target: LOG_TARGET,
-                "❌ NOT voting on block {}, height {}. Block height is not greater than last voted height {}",
+                "❌ NOT voting on block {}, {}. Block view is not greater than last voted {}",
                 block.id(),
-                block.height(),
-                last_voted.height,
+                block.view(),
+                last_voted_view,
             );
             return Ok(false);
         }
@@ -316,7 +324,7 @@ where TConsensusSpec: ConsensusSpec
     ) -> Result<(), HotStuffError> {
         let leader = self
             .leader_strategy
-            .get_leader_for_next_block(local_committee, block.height());
+            .get_leader_for_next_view(local_committee, block.view());
         info!(
             target: LOG_TARGET,
             "🔥 VOTE {:?} for block {} proposed by {} to next leader {:.4}",
@@ -492,6 +500,13 @@ where TConsensusSpec: ConsensusSpec
                         );
                         return Ok(None);
                     }
+                    // Transactions that are still only Prepared once the epoch's finalize period is over are aborted
+                    if tx_rec.current_stage().is_prepared() &&
+                        is_finalize_period_over(block.epoch_cutoff(), block.height())
+                    {
+                        tx_rec.update_local_decision(tx, Decision::Abort)?;
+                    }
+
                     // We check that the leader decision is the same as our local decision.
                     // We disregard the remote decision because not all validators may have received the foreign
                     // LocalPrepared yet. We will never accept a decision disagreement for the Accept command.
@@ -874,6 +889,7 @@ where TConsensusSpec: ConsensusSpec
                 block,
                 last_executed.height
             );
+            metrics::COMMITTED_HEIGHT.set(block.height().as_u64() as i64);
             self.publish_event(HotstuffEvent::BlockCommitted {
                 block_id: *block.id(),
                 height: block.height(),
@@ -919,7 +935,7 @@ where TConsensusSpec: ConsensusSpec
             let local_committee = self.epoch_manager.get_local_committee(block.epoch()).await?;
             let is_leader = self
                 .leader_strategy
-                .is_leader(&self.validator_addr, &local_committee, block.height());
+                .is_leader(&self.validator_addr, &local_committee, block.view());
             // TODO: This will be changed to different strategy where not only leader is responsible for foreign block
             // proposal.
             if is_leader {
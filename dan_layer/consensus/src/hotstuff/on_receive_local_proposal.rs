@@ -6,7 +6,7 @@
 // Complete
 
 use log::*;
-use tari_dan_common_types::{committee::Committee, optional::Optional, NodeHeight};
+use tari_dan_common_types::optional::Optional;
 use tari_dan_storage::{
     consensus_models::{Block, HighQc, TransactionPool, ValidBlock},
     StateStore,
@@ -16,15 +16,17 @@ use tokio::sync::{broadcast, mpsc};
 
 use super::proposer::Proposer;
 use crate::{
+    block_validations::check_epoch_cutoff,
     hotstuff::{
         error::HotStuffError,
         on_ready_to_vote_on_local_block::OnReadyToVoteOnLocalBlock,
         pacemaker_handle::PaceMakerHandle,
         HotstuffEvent,
+        ParticipationTracker,
         ProposalValidationError,
     },
     messages::{HotstuffMessage, ProposalMessage},
-    traits::{ConsensusSpec, LeaderStrategy},
+    traits::ConsensusSpec,
 };
 
 const LOG_TARGET: &str = "tari::dan::consensus::hotstuff::on_receive_local_proposal";
@@ -32,8 +34,8 @@ const LOG_TARGET: &str = "tari::dan::consensus::hotstuff::on_receive_local_propo
 pub struct OnReceiveProposalHandler<TConsensusSpec: ConsensusSpec> {
     store: TConsensusSpec::StateStore,
     epoch_manager: TConsensusSpec::EpochManager,
-    leader_strategy: TConsensusSpec::LeaderStrategy,
     pacemaker: PaceMakerHandle,
+    participation: ParticipationTracker<TConsensusSpec::Addr>,
     on_ready_to_vote_on_local_block: OnReadyToVoteOnLocalBlock<TConsensusSpec>,
 }
 
@@ -50,12 +52,13 @@ impl<TConsensusSpec: ConsensusSpec> OnReceiveProposalHandler<TConsensusSpec> {
         transaction_pool: TransactionPool<TConsensusSpec::StateStore>,
         tx_events: broadcast::Sender<HotstuffEvent>,
         proposer: Proposer<TConsensusSpec>,
+        participation: ParticipationTracker<TConsensusSpec::Addr>,
     ) -> Self {
         Self {
             store: store.clone(),
             epoch_manager: epoch_manager.clone(),
-            leader_strategy: leader_strategy.clone(),
             pacemaker,
+            participation,
             on_ready_to_vote_on_local_block: OnReadyToVoteOnLocalBlock::new(
                 validator_addr,
                 store,
@@ -71,7 +74,11 @@ impl<TConsensusSpec: ConsensusSpec> OnReceiveProposalHandler<TConsensusSpec> {
         }
     }
 
-    pub async fn handle(&self, message: ProposalMessage<TConsensusSpec::Addr>) -> Result<(), HotStuffError> {
+    pub async fn handle(
+        &self,
+        from: TConsensusSpec::Addr,
+        message: ProposalMessage<TConsensusSpec::Addr>,
+    ) -> Result<(), HotStuffError> {
         let ProposalMessage { block } = message;
 
         debug!(
@@ -81,12 +88,16 @@ impl<TConsensusSpec: ConsensusSpec> OnReceiveProposalHandler<TConsensusSpec> {
             block.proposed_by()
         );
 
-        self.process_block(block).await?;
+        self.process_block(&from, block).await?;
 
         Ok(())
     }
 
-    async fn process_block(&self, block: Block<TConsensusSpec::Addr>) -> Result<(), HotStuffError> {
+    async fn process_block(
+        &self,
+        from: &TConsensusSpec::Addr,
+        block: Block<TConsensusSpec::Addr>,
+    ) -> Result<(), HotStuffError> {
         if !self.epoch_manager.is_epoch_active(block.epoch()).await? {
             return Err(HotStuffError::EpochNotActive {
                 epoch: block.epoch(),
@@ -95,11 +106,12 @@ impl<TConsensusSpec: ConsensusSpec> OnReceiveProposalHandler<TConsensusSpec> {
         }
 
         if let Some(valid_block) = self.validate_block(block).await? {
-            // Save the block as soon as it is valid to ensure we have a valid pacemaker height.
+            // Save the block as soon as it is valid to ensure we have a valid pacemaker height and view.
             let high_qc = self.save_block(&valid_block)?;
             info!(target: LOG_TARGET, "✅ Block {} is valid and persisted. HighQc({})", valid_block, high_qc);
+            self.record_proposal(from, valid_block.block()).await?;
             self.pacemaker
-                .update_view(valid_block.height(), high_qc.block_height())
+                .update_view(valid_block.height(), valid_block.view())
                 .await?;
 
             self.on_ready_to_vote_on_local_block.handle(valid_block).await?;
@@ -110,10 +122,30 @@ impl<TConsensusSpec: ConsensusSpec> OnReceiveProposalHandler<TConsensusSpec> {
         Ok(())
     }
 
+    /// Records a valid proposal for the committee health report if it was sent to us by the local committee member that
+    /// proposed it. Proposals relayed by other nodes are not attributed to the proposer.
+    async fn record_proposal(
+        &self,
+        from: &TConsensusSpec::Addr,
+        block: &Block<TConsensusSpec::Addr>,
+    ) -> Result<(), HotStuffError> {
+        if from != block.proposed_by() {
+            return Ok(());
+        }
+        if !self
+            .epoch_manager
+            .is_validator_in_local_committee(from, block.epoch())
+            .await?
+        {
+            return Ok(());
+        }
+        self.participation.record_proposal(from, block.height());
+        Ok(())
+    }
+
     fn save_block(&self, valid_block: &ValidBlock<TConsensusSpec::Addr>) -> Result<HighQc, HotStuffError> {
         self.store.with_write_tx(|tx| {
             valid_block.block().justify().save(tx)?;
-            valid_block.save_all_dummy_blocks(tx)?;
             valid_block.block().save(tx)?;
             let high_qc = valid_block.block().justify().update_high_qc(tx)?;
             Ok(high_qc)
@@ -124,13 +156,8 @@ impl<TConsensusSpec: ConsensusSpec> OnReceiveProposalHandler<TConsensusSpec> {
         &self,
         block: Block<TConsensusSpec::Addr>,
     ) -> Result<Option<ValidBlock<TConsensusSpec::Addr>>, HotStuffError> {
-        let local_committee = self
-            .epoch_manager
-            .get_committee_by_validator_address(block.epoch(), block.proposed_by())
-            .await?;
-        // First save the block in one db transaction
         self.store.with_read_tx(|tx| {
-            match self.validate_local_proposed_block(tx, block, &local_committee) {
+            match self.validate_local_proposed_block(tx, block) {
                 Ok(validated) => Ok(Some(validated)),
                 // Validation errors should not cause a FAILURE state transition
                 Err(HotStuffError::ProposalValidationError(err)) => {
@@ -149,7 +176,6 @@ impl<TConsensusSpec: ConsensusSpec> OnReceiveProposalHandler<TConsensusSpec> {
         &self,
         tx: &mut <TConsensusSpec::StateStore as StateStore>::ReadTransaction<'_>,
         candidate_block: Block<TConsensusSpec::Addr>,
-        local_committee: &Committee<TConsensusSpec::Addr>,
     ) -> Result<ValidBlock<TConsensusSpec::Addr>, HotStuffError> {
         if Block::has_been_processed(tx, candidate_block.id())? {
             return Err(ProposalValidationError::BlockAlreadyProcessed {
@@ -183,6 +209,22 @@ impl<TConsensusSpec: ConsensusSpec> OnReceiveProposalHandler<TConsensusSpec> {
             .into());
         }
 
+        let parent_block = if candidate_block.parent() == justify_block.id() {
+            justify_block.clone()
+        } else {
+            let Some(parent_block) = Block::get(tx, candidate_block.parent()).optional()? else {
+                // This will trigger a sync
+                return Err(ProposalValidationError::ParentBlockNotFound {
+                    proposed_by: candidate_block.proposed_by().to_string(),
+                    block_description: candidate_block.to_string(),
+                    parent_block: *candidate_block.parent(),
+                }
+                .into());
+            };
+            parent_block
+        };
+        check_epoch_cutoff(&parent_block, &candidate_block)?;
+
         // Special case for genesis block
         if candidate_block.parent().is_genesis() && candidate_block.justify().is_genesis() {
             return Ok(ValidBlock::new(candidate_block));
@@ -196,51 +238,29 @@ impl<TConsensusSpec: ConsensusSpec> OnReceiveProposalHandler<TConsensusSpec> {
             .into());
         }
 
-        let justify_block_height = justify_block.height();
-
-        if justify_block.id() != candidate_block.parent() {
-            let mut dummy_blocks =
-                Vec::with_capacity((candidate_block.height().as_u64() - justify_block_height.as_u64() - 1) as usize);
-            dummy_blocks.push(justify_block);
-            let mut last_dummy_block = dummy_blocks.last().unwrap();
-            // if the block parent is not the justify parent, then we have experienced a leader failure
-            // and should make dummy blocks to fill in the gaps.
-            while last_dummy_block.id() != candidate_block.parent() {
-                if last_dummy_block.height() > candidate_block.height() {
-                    warn!(target: LOG_TARGET, "🔥 Bad proposal, dummy block height {} is greater than new height {}", last_dummy_block, candidate_block);
-                    return Err(ProposalValidationError::CandidateBlockDoesNotExtendJustify {
-                        justify_block_height,
-                        candidate_block_height: candidate_block.height(),
-                    }
-                    .into());
-                }
-
-                let next_height = last_dummy_block.height() + NodeHeight(1);
-                let leader = self.leader_strategy.get_leader(local_committee, next_height);
-
-                // TODO: replace with actual leader's propose
-                dummy_blocks.push(Block::dummy_block(
-                    *last_dummy_block.id(),
-                    leader.clone(),
-                    next_height,
-                    candidate_block.justify().clone(),
-                    candidate_block.epoch(),
-                ));
-                last_dummy_block = dummy_blocks.last().unwrap();
-                debug!(target: LOG_TARGET, "🍼 DUMMY BLOCK: {}. Leader: {}", last_dummy_block, leader);
+        if candidate_block.view() <= justify_block.view() {
+            return Err(ProposalValidationError::CandidateBlockViewNotHigherThanJustify {
+                proposed_by: candidate_block.proposed_by().to_string(),
+                block_id: *candidate_block.id(),
+                view: candidate_block.view(),
+                justify_view: justify_block.view(),
             }
+            .into());
+        }
 
-            // The logic for not checking is_safe is as follows:
-            // We can't without adding the dummy blocks to the DB
-            // We know that justify_block is safe because we have added it to our chain
-            // We know that each dummy block is built in a chain from the justify block to the candidate block
-            // We know that last dummy block is the parent of candidate block
-            // Therefore we know that candidate block is safe
-            return Ok(ValidBlock::with_dummy_blocks(candidate_block, dummy_blocks));
+        // If the previous view did not produce a QC, the leader must prove that a quorum of the committee timed out
+        // and moved on to this view. The certificate itself has already been validated.
+        if candidate_block.view() != justify_block.view().next() && candidate_block.timeout_certificate().is_none() {
+            return Err(ProposalValidationError::MissingTimeoutCertificate {
+                proposed_by: candidate_block.proposed_by().to_string(),
+                block_id: *candidate_block.id(),
+                view: candidate_block.view(),
+                justify_view: justify_block.view(),
+            }
+            .into());
         }
 
-        // Now that we have all dummy blocks (if any) in place, we can check if the candidate block is safe.
-        // Specifically, it should extend the locked block via the dummy blocks.
+        // The candidate block should extend the locked block or justify a block in a higher view than the locked block.
         if !candidate_block.is_safe(tx)? {
             return Err(ProposalValidationError::NotSafeBlock {
                 proposed_by: candidate_block.proposed_by().to_string(),
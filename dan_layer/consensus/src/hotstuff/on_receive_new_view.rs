@@ -1,22 +1,20 @@
 //   Copyright 2023 The Tari Project
 //   SPDX-License-Identifier: BSD-3-Clause
 
-use std::{
-    collections::{HashMap, HashSet},
-    ops::DerefMut,
-};
+use std::{collections::HashMap, ops::DerefMut};
 
 use log::*;
-use tari_dan_common_types::NodeHeight;
+use tari_dan_common_types::{optional::Optional, View};
 use tari_dan_storage::{
-    consensus_models::{Block, BlockId, LeafBlock, LockedBlock, QuorumCertificate},
+    consensus_models::{Block, LeafBlock, LockedBlock, SignedNewView, TimeoutCertificate},
     StateStore,
 };
 use tari_epoch_manager::EpochManagerReader;
 
 use super::vote_receiver::VoteReceiver;
 use crate::{
-    hotstuff::{common::calculate_dummy_blocks, error::HotStuffError, pacemaker_handle::PaceMakerHandle},
+    block_validations::check_quorum_certificate_signatures,
+    hotstuff::{error::HotStuffError, pacemaker_handle::PaceMakerHandle},
     messages::NewViewMessage,
     traits::{ConsensusSpec, LeaderStrategy},
 };
@@ -27,9 +25,12 @@ pub struct OnReceiveNewViewHandler<TConsensusSpec: ConsensusSpec> {
     store: TConsensusSpec::StateStore,
     leader_strategy: TConsensusSpec::LeaderStrategy,
     epoch_manager: TConsensusSpec::EpochManager,
-    newview_message_counts: HashMap<(NodeHeight, BlockId), HashSet<TConsensusSpec::Addr>>,
+    /// The signed NEWVIEWs received for each view. These are included in the timeout certificate once a quorum is
+    /// reached.
+    newview_messages: HashMap<View, HashMap<TConsensusSpec::Addr, SignedNewView<TConsensusSpec::Addr>>>,
     pacemaker: PaceMakerHandle,
     vote_receiver: VoteReceiver<TConsensusSpec>,
+    vote_signature_service: TConsensusSpec::VoteSignatureService,
 }
 
 impl<TConsensusSpec> OnReceiveNewViewHandler<TConsensusSpec>
@@ -41,32 +42,31 @@ where TConsensusSpec: ConsensusSpec
         epoch_manager: TConsensusSpec::EpochManager,
         pacemaker: PaceMakerHandle,
         vote_receiver: VoteReceiver<TConsensusSpec>,
+        vote_signature_service: TConsensusSpec::VoteSignatureService,
     ) -> Self {
         Self {
             store,
             leader_strategy,
             epoch_manager,
-            newview_message_counts: HashMap::default(),
+            newview_messages: HashMap::default(),
             pacemaker,
             vote_receiver,
+            vote_signature_service,
         }
     }
 
     pub(super) fn clear_new_views(&mut self) {
-        self.newview_message_counts.clear();
+        self.newview_messages.clear();
     }
 
     fn collect_new_views(
         &mut self,
         from: TConsensusSpec::Addr,
-        new_height: NodeHeight,
-        high_qc: &QuorumCertificate<TConsensusSpec::Addr>,
+        new_view: View,
+        signed_new_view: SignedNewView<TConsensusSpec::Addr>,
     ) -> usize {
-        let entry = self
-            .newview_message_counts
-            .entry((new_height, *high_qc.block_id()))
-            .or_default();
-        entry.insert(from);
+        let entry = self.newview_messages.entry(new_view).or_default();
+        entry.entry(from).or_insert(signed_new_view);
         entry.len()
     }
 
@@ -75,24 +75,25 @@ where TConsensusSpec: ConsensusSpec
         &mut self,
         from: TConsensusSpec::Addr,
         message: NewViewMessage<TConsensusSpec::Addr>,
-    ) -> Result<(), HotStuffError> {
+    ) -> Result<Option<TimeoutCertificate<TConsensusSpec::Addr>>, HotStuffError> {
         let NewViewMessage {
             high_qc,
-            new_height,
             epoch,
+            new_view,
             last_vote,
+            signature,
         } = message;
         debug!(
             target: LOG_TARGET,
-            "🌟 Received NEWVIEW for qc {} new height {} from {}",
+            "🌟 Received NEWVIEW for qc {} new view {} from {}",
             high_qc.id(),
-            new_height,
+            new_view,
             from
         );
 
         if !self.epoch_manager.is_this_validator_registered_for_epoch(epoch).await? {
             warn!(target: LOG_TARGET, "❌ Ignoring NEWVIEW for epoch {} because the epoch is invalid or we are not registered for that epoch", epoch);
-            return Ok(());
+            return Ok(None);
         }
 
         if !self.epoch_manager.is_validator_in_local_committee(&from, epoch).await? {
@@ -103,20 +104,30 @@ where TConsensusSpec: ConsensusSpec
             });
         }
 
-        // We can never accept NEWVIEWS for heights that are lower than the locked block height
-        let locked = self.store.with_read_tx(|tx| LockedBlock::get(tx))?;
-        if new_height < locked.height() {
-            warn!(target: LOG_TARGET, "❌ Ignoring NEWVIEW for height less than the locked block, locked block: {} new height: {}", locked, new_height);
-            return Ok(());
+        // We can never accept NEWVIEWS for views that have already passed
+        let current_view = self.pacemaker.current_view();
+        if new_view < current_view {
+            warn!(target: LOG_TARGET, "❌ Ignoring NEWVIEW for view {} that is less than the current view {}", new_view, current_view);
+            return Ok(None);
+        }
+
+        let locked = self.store.with_read_tx(|tx| LockedBlock::get(tx)?.get_block(tx))?;
+        if new_view <= locked.view() {
+            warn!(target: LOG_TARGET, "❌ Ignoring NEWVIEW for view less than the locked block, locked block: {} new view: {}", locked, new_view);
+            return Ok(None);
         }
 
-        self.validate_qc(&high_qc)?;
+        let qc_committee = self.epoch_manager.get_local_committee(high_qc.epoch()).await?;
+        if let Err(err) = check_quorum_certificate_signatures(&qc_committee, &high_qc, &self.vote_signature_service) {
+            warn!(target: LOG_TARGET, "❌ Ignoring NEWVIEW from {} with an invalid high QC: {}", from, err);
+            return Ok(None);
+        }
 
         // Sync if we do not have the block for this valid QC
-        let exists = self
+        let qc_block = self
             .store
-            .with_read_tx(|tx| Block::record_exists(tx, high_qc.block_id()))?;
-        if !exists {
+            .with_read_tx(|tx| Block::get(tx, high_qc.block_id()).optional())?;
+        let Some(qc_block) = qc_block else {
             let leaf = self
                 .store
                 .with_read_tx(|tx| LeafBlock::get(tx))
@@ -126,24 +137,42 @@ where TConsensusSpec: ConsensusSpec
                 local_height: leaf.height(),
                 qc_height: high_qc.block_height(),
             });
+        };
+        // The QC signatures do not cover the height, so it is checked against the block that we have
+        if qc_block.height() != high_qc.block_height() {
+            warn!(
+                target: LOG_TARGET,
+                "❌ Ignoring NEWVIEW from {} with high QC {} at height {} but the block is at height {}",
+                from,
+                high_qc.id(),
+                high_qc.block_height(),
+                qc_block.height()
+            );
+            return Ok(None);
         }
 
         let local_committee = self.epoch_manager.get_local_committee(epoch).await?;
-        let leader = self
-            .leader_strategy
-            .get_leader_for_next_block(&local_committee, new_height);
+        let leader = self.leader_strategy.get_leader(&local_committee, new_view);
         let our_node = self.epoch_manager.get_our_validator_node(epoch).await?;
 
         if *leader != our_node.address {
-            warn!(target: LOG_TARGET, "❌ New View failed, leader is {} at height:{}", leader, new_height);
+            warn!(target: LOG_TARGET, "❌ New View failed, leader is {} for {}", leader, new_view);
             return Err(HotStuffError::NotTheLeader {
                 details: format!(
-                    "Received NEWVIEW height {} but this not is not the leader for that height",
-                    new_height
+                    "Received NEWVIEW for {} but this node is not the leader for that view",
+                    new_view
                 ),
             });
         }
 
+        // The signature was checked when the message was received
+        let signed_new_view = SignedNewView {
+            high_qc_id: *high_qc.id(),
+            high_qc_height: high_qc.block_height(),
+            last_vote_block_id: last_vote.as_ref().map(|vote| vote.block_id),
+            signature,
+        };
+
         if let Some(vote) = last_vote {
             debug!(
                 target: LOG_TARGET,
@@ -152,17 +181,8 @@ where TConsensusSpec: ConsensusSpec
             self.vote_receiver.handle(vote, false).await?;
         }
 
-        // Are nodes requesting to create more than the minimum number of dummy blocks?
-        if high_qc.block_height().saturating_sub(new_height).as_u64() > local_committee.len() as u64 {
-            return Err(HotStuffError::BadNewViewMessage {
-                details: format!("Validator {from} requested an invalid number of dummy blocks"),
-                high_qc_height: high_qc.block_height(),
-                received_new_height: new_height,
-            });
-        }
-
         // Take note of unique NEWVIEWs so that we can count them
-        let newview_count = self.collect_new_views(from, new_height, &high_qc);
+        let newview_count = self.collect_new_views(from, new_view, signed_new_view);
 
         let high_qc = self.store.with_write_tx(|tx| {
             high_qc.save(tx)?;
@@ -170,45 +190,32 @@ where TConsensusSpec: ConsensusSpec
             high_qc.get_quorum_certificate(tx.deref_mut())
         })?;
 
-        // if checked_high_qc.block_height() > high_qc.block_height() {
-        //     warn!(target: LOG_TARGET, "❌ Ignoring NEWVIEW for because high QC is not higher than previous high QC,
-        // given high QC: {} current high QC: {}", high_qc.as_high_qc(), checked_high_qc);     return Ok(());
-        // }
-
         let threshold = self.epoch_manager.get_local_threshold_for_epoch(epoch).await?;
 
         info!(
             target: LOG_TARGET,
-            "🌟 Received NEWVIEW for height {} (QC: {}) has {} votes out of {}",
-            new_height,
+            "🌟 Received NEWVIEW for {} (QC: {}) has {} votes out of {}",
+            new_view,
             high_qc,
             newview_count,
             threshold,
         );
-        // Once we have received enough (quorum) NEWVIEWS, we can create the dummy block(s) and propose the next block.
-        // Any subsequent NEWVIEWs for this height/view are ignored.
+        // Once we have received enough (quorum) NEWVIEWS, we can form a timeout certificate and propose a block for
+        // the new view. Any subsequent NEWVIEWs for this view are ignored.
         if newview_count == threshold {
-            info!(target: LOG_TARGET, "🌟✅ NEWVIEW for block {} (high_qc: {}) has reached quorum ({}/{})", new_height, high_qc.as_high_qc(), newview_count, threshold);
-
-            // Determine how many missing blocks we must fill without actually creating them.
-            // This node, as well as all other replicas, will create the blocks in on_receive_proposal.
-            let dummy_blocks =
-                calculate_dummy_blocks(epoch, &high_qc, new_height, &self.leader_strategy, &local_committee);
-            // Set the last voted block so that we do not vote on other conflicting blocks
-            if let Some(last_dummy) = dummy_blocks.last() {
-                debug!(target: LOG_TARGET, "🍼 dummy leaf block {}", last_dummy);
-                // Force beat so that a block is proposed even if there are no transactions
-                self.pacemaker.force_beat(last_dummy.as_leaf_block());
-            } else {
-                warn!(target: LOG_TARGET, "❌ No dummy blocks were created for height {}", new_height);
-            }
+            info!(target: LOG_TARGET, "🌟✅ NEWVIEW for {} (high_qc: {}) has reached quorum ({}/{})", new_view, high_qc.as_high_qc(), newview_count, threshold);
+
+            let new_views = self
+                .newview_messages
+                .get(&new_view)
+                .map(|new_views| new_views.values().cloned().collect())
+                .unwrap_or_default();
+            let timeout_certificate = TimeoutCertificate::new(epoch, new_view, new_views);
+            debug!(target: LOG_TARGET, "⏰ Formed {}", timeout_certificate);
+            self.pacemaker.enter_view(new_view);
+            return Ok(Some(timeout_certificate));
         }
 
-        Ok(())
-    }
-
-    fn validate_qc(&self, _qc: &QuorumCertificate<TConsensusSpec::Addr>) -> Result<(), HotStuffError> {
-        // TODO
-        Ok(())
+        Ok(None)
     }
 }
@@ -6,37 +6,42 @@ use std::{
 };
 
 use log::*;
-use tari_dan_common_types::NodeHeight;
 use tokio::sync::mpsc;
 
 use crate::hotstuff::{
     current_height::CurrentHeight,
+    current_view::CurrentView,
     on_beat::OnBeat,
     on_force_beat::OnForceBeat,
     on_leader_timeout::OnLeaderTimeout,
     pacemaker_handle::{PaceMakerHandle, PacemakerRequest},
+    ConsensusConfig,
     HotStuffError,
 };
 
 const LOG_TARGET: &str = "tari::dan::consensus::hotstuff::pacemaker";
-const MAX_DELTA: Duration = Duration::from_secs(300);
 
 pub struct PaceMaker {
     pace_maker_handle: PaceMakerHandle,
     handle_receiver: mpsc::Receiver<PacemakerRequest>,
     block_time: Duration,
+    leader_timeout: Duration,
+    max_delta: Duration,
     current_height: CurrentHeight,
-    current_high_qc_height: NodeHeight,
+    current_view: CurrentView,
+    /// The number of leader failures since the last successful view. Used to back off the leader timeout.
+    consecutive_timeouts: u32,
 }
 
 impl PaceMaker {
-    pub fn new() -> Self {
+    pub fn new(config: &ConsensusConfig) -> Self {
         let (sender, receiver) = mpsc::channel(100);
 
         let on_beat = OnBeat::new();
         let on_force_beat = OnForceBeat::new();
         let on_leader_timeout = OnLeaderTimeout::new();
         let current_height = CurrentHeight::new();
+        let current_view = CurrentView::new();
 
         Self {
             handle_receiver: receiver,
@@ -46,11 +51,14 @@ impl PaceMaker {
                 on_force_beat,
                 on_leader_timeout,
                 current_height.clone(),
+                current_view.clone(),
             ),
-            // TODO: make network constant. We're starting slow with 10s but should be 1s in the future
-            block_time: Duration::from_secs(10),
+            block_time: config.block_time,
+            leader_timeout: config.leader_timeout,
+            max_delta: config.max_delta,
             current_height,
-            current_high_qc_height: NodeHeight(0),
+            current_view,
+            consecutive_timeouts: 0,
         }
     }
 
@@ -91,26 +99,26 @@ impl PaceMaker {
                 maybe_req = self.handle_receiver.recv() => {
                     if let Some(req) = maybe_req {
                         match req {
-                           PacemakerRequest::ResetLeaderTimeout { high_qc_height } => {
+                           PacemakerRequest::ResetLeaderTimeout => {
                                 if !started {
                                     continue;
                                 }
 
-                                self.current_high_qc_height = high_qc_height;
+                                self.consecutive_timeouts = 0;
                                 let delta = self.delta_time();
-                                info!(target: LOG_TARGET, "Reset! Current height: {}, Delta: {:.2?}", self.current_height, delta);
+                                info!(target: LOG_TARGET, "Reset! Current view: {}, height: {}, Delta: {:.2?}", self.current_view, self.current_height, delta);
                                 leader_timeout.as_mut().reset(tokio::time::Instant::now() + delta);
                                 // set a timer for when we must send a block...
                                 block_timer.as_mut().reset(tokio::time::Instant::now() + self.block_time);
                            },
-                            PacemakerRequest::Start { high_qc_height } => {
-                                info!(target: LOG_TARGET, "🚀 Starting pacemaker at leaf height {} and high QC: {}", self.current_height, high_qc_height);
+                            PacemakerRequest::Start => {
+                                info!(target: LOG_TARGET, "🚀 Starting pacemaker at view {} and height {}", self.current_view, self.current_height);
                                 if started {
                                     continue;
                                 }
-                                self.current_high_qc_height = high_qc_height;
+                                self.consecutive_timeouts = 0;
                                 let delta = self.delta_time();
-                                info!(target: LOG_TARGET, "Reset! Current height: {}, Delta: {:.2?}", self.current_height, delta);
+                                info!(target: LOG_TARGET, "Reset! Current view: {}, height: {}, Delta: {:.2?}", self.current_view, self.current_height, delta);
                                 leader_timeout.as_mut().reset(tokio::time::Instant::now() + delta);
                                 block_timer.as_mut().reset(tokio::time::Instant::now() + self.block_time);
                                 on_beat.beat();
@@ -131,16 +139,17 @@ impl PaceMaker {
                 },
                 () = &mut block_timer => {
                     block_timer.as_mut().reset(tokio::time::Instant::now() + self.block_time);
-                    on_force_beat.beat(None);
+                    on_force_beat.beat();
                 }
                 () = &mut leader_timeout => {
                     block_timer.as_mut().reset(tokio::time::Instant::now() + self.block_time);
 
+                    self.consecutive_timeouts = self.consecutive_timeouts.saturating_add(1);
                     let delta = self.delta_time();
                     leader_timeout.as_mut().reset(tokio::time::Instant::now() + delta);
-                    info!(target: LOG_TARGET, "⚠️ Leader timeout! Current height: {}, Delta: {:.2?}", self.current_height, delta);
-                    self.current_height.next_height();
-                    on_leader_timeout.leader_timed_out(self.current_height.get());
+                    let new_view = self.current_view.next_view();
+                    info!(target: LOG_TARGET, "⚠️ Leader timeout! New view: {}, Delta: {:.2?}", new_view, delta);
+                    on_leader_timeout.leader_timed_out(new_view);
                 },
 
             }
@@ -149,27 +158,20 @@ impl PaceMaker {
         Ok(())
     }
 
-    /// Current delta time defined as 2^n where n is the difference in height between the last seen block height and the
-    /// high QC height. This is always greater than the block time.
-    /// Ensure that current_height and current_high_qc_height are set before calling this function.
+    /// Current delta time defined as 2^n where n is the number of consecutive leader timeouts since the last successful
+    /// view. This is always greater than the block time.
     fn delta_time(&self) -> Duration {
-        let current_height = self.current_height.get();
-        if current_height.is_zero() {
+        if self.current_height.get().is_zero() {
             // Allow extra time for the first block
             return self.block_time * 2;
         }
-        let exp = u32::try_from(cmp::min(
-            u64::from(u32::MAX),
-            cmp::max(1, current_height.saturating_sub(self.current_high_qc_height).as_u64()),
-        ))
-        .unwrap_or(u32::MAX);
+        let exp = cmp::max(1, self.consecutive_timeouts);
         let delta = cmp::min(
-            MAX_DELTA,
-            2u64.checked_pow(exp).map(Duration::from_secs).unwrap_or(MAX_DELTA),
+            self.max_delta,
+            2u64.checked_pow(exp).map(Duration::from_secs).unwrap_or(self.max_delta),
         );
-        // TODO: get real avg latency
-        let avg_latency = Duration::from_secs(2);
-        self.block_time + delta + avg_latency
+        // TODO: include real avg latency
+        self.block_time + delta + self.leader_timeout
     }
 }
 
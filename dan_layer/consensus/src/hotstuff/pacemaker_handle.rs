@@ -1,12 +1,12 @@
 //  Copyright 2022 The Tari Project
 //  SPDX-License-Identifier: BSD-3-Clause
 
-use tari_dan_common_types::NodeHeight;
-use tari_dan_storage::consensus_models::LeafBlock;
+use tari_dan_common_types::{NodeHeight, View};
 use tokio::sync::mpsc;
 
 use crate::hotstuff::{
     current_height::CurrentHeight,
+    current_view::CurrentView,
     on_beat::OnBeat,
     on_force_beat::OnForceBeat,
     on_leader_timeout::OnLeaderTimeout,
@@ -14,8 +14,8 @@ use crate::hotstuff::{
 };
 
 pub enum PacemakerRequest {
-    ResetLeaderTimeout { high_qc_height: NodeHeight },
-    Start { high_qc_height: NodeHeight },
+    ResetLeaderTimeout,
+    Start,
     Stop,
 }
 
@@ -26,6 +26,7 @@ pub struct PaceMakerHandle {
     on_force_beat: OnForceBeat,
     on_leader_timeout: OnLeaderTimeout,
     current_height: CurrentHeight,
+    current_view: CurrentView,
 }
 
 impl PaceMakerHandle {
@@ -35,6 +36,7 @@ impl PaceMakerHandle {
         on_force_beat: OnForceBeat,
         on_leader_timeout: OnLeaderTimeout,
         current_height: CurrentHeight,
+        current_view: CurrentView,
     ) -> Self {
         Self {
             sender,
@@ -42,14 +44,16 @@ impl PaceMakerHandle {
             on_force_beat,
             on_leader_timeout,
             current_height,
+            current_view,
         }
     }
 
     /// Start the pacemaker if it hasn't already been started. If it has, this is a no-op
-    pub async fn start(&self, current_height: NodeHeight, high_qc_height: NodeHeight) -> Result<(), HotStuffError> {
+    pub async fn start(&self, current_view: View, current_height: NodeHeight) -> Result<(), HotStuffError> {
+        self.current_view.update(current_view);
         self.current_height.update(current_height);
         self.sender
-            .send(PacemakerRequest::Start { high_qc_height })
+            .send(PacemakerRequest::Start)
             .await
             .map_err(|e| HotStuffError::PacemakerChannelDropped { details: e.to_string() })
     }
@@ -68,8 +72,8 @@ impl PaceMakerHandle {
     }
 
     /// Signal the pacemaker trigger a forced beat. If the pacemaker has not been started, this is a no-op
-    pub fn force_beat(&self, parent_block: LeafBlock) {
-        self.on_force_beat.beat(Some(parent_block));
+    pub fn force_beat(&self) {
+        self.on_force_beat.beat();
     }
 
     pub fn get_on_beat(&self) -> OnBeat {
@@ -84,28 +88,29 @@ impl PaceMakerHandle {
         self.on_leader_timeout.clone()
     }
 
-    /// Reset the leader timeout. This should be called when a valid leader proposal is received.
-    pub async fn update_view(
-        &self,
-        last_seen_height: NodeHeight,
-        high_qc_height: NodeHeight,
-    ) -> Result<(), HotStuffError> {
-        // Update current height here to prevent possibility of race conditions
+    /// Reset the leader timeout and move to the view after `block_view`. This should be called when a valid leader
+    /// proposal is received or a new QC is formed for a block.
+    pub async fn update_view(&self, last_seen_height: NodeHeight, block_view: View) -> Result<(), HotStuffError> {
+        // Update current height and view here to prevent possibility of race conditions
         self.current_height.update(last_seen_height);
+        self.current_view.update(block_view.next());
         self.sender
-            .send(PacemakerRequest::ResetLeaderTimeout { high_qc_height })
+            .send(PacemakerRequest::ResetLeaderTimeout)
             .await
             .map_err(|e| HotStuffError::PacemakerChannelDropped { details: e.to_string() })
     }
 
-    pub async fn reset_view(
-        &self,
-        last_seen_height: NodeHeight,
-        high_qc_height: NodeHeight,
-    ) -> Result<(), HotStuffError> {
+    /// Enter the given view if we are not already in it or past it, without resetting the leader timeout. This is
+    /// called by the leader when a timeout certificate for the view has been formed.
+    pub fn enter_view(&self, view: View) {
+        self.current_view.update(view);
+    }
+
+    pub async fn reset_view(&self, last_seen_height: NodeHeight, block_view: View) -> Result<(), HotStuffError> {
         self.current_height.set(last_seen_height);
+        self.current_view.set(block_view.next());
         self.sender
-            .send(PacemakerRequest::ResetLeaderTimeout { high_qc_height })
+            .send(PacemakerRequest::ResetLeaderTimeout)
             .await
             .map_err(|e| HotStuffError::PacemakerChannelDropped { details: e.to_string() })
     }
@@ -113,4 +118,9 @@ impl PaceMakerHandle {
     pub fn current_height(&self) -> NodeHeight {
         self.current_height.get()
     }
+
+    /// The view in which we expect the next proposal
+    pub fn current_view(&self) -> View {
+        self.current_view.get()
+    }
 }
@@ -0,0 +1,104 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use std::{
+    collections::HashMap,
+    hash::Hash,
+    sync::{Arc, RwLock},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use tari_dan_common_types::NodeHeight;
+
+/// Records what this node has seen of each committee member's participation in consensus in the current epoch.
+/// Votes are sent to the next leader only, so the last vote of a member is the last vote that this node received
+/// from it.
+#[derive(Debug, Clone)]
+pub struct ParticipationTracker<TAddr> {
+    members: Arc<RwLock<HashMap<TAddr, MemberParticipation>>>,
+}
+
+impl<TAddr: Eq + Hash + Clone> ParticipationTracker<TAddr> {
+    pub fn new() -> Self {
+        Self {
+            members: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    pub fn record_vote(&self, member: &TAddr, block_height: NodeHeight) {
+        self.update(member, |participation| {
+            participation.votes_received += 1;
+            participation.last_vote = Some(Observation::now(block_height));
+        });
+    }
+
+    pub fn record_proposal(&self, member: &TAddr, block_height: NodeHeight) {
+        self.update(member, |participation| {
+            participation.proposals_received += 1;
+            participation.last_proposal = Some(Observation::now(block_height));
+        });
+    }
+
+    /// Records that the leader timed out without this node receiving a proposal from `member`
+    pub fn record_missed_proposal(&self, member: &TAddr) {
+        self.update(member, |participation| {
+            participation.proposals_missed += 1;
+            participation.last_missed_proposal = Some(unix_timestamp());
+        });
+    }
+
+    pub fn get(&self, member: &TAddr) -> MemberParticipation {
+        self.members.read().unwrap().get(member).cloned().unwrap_or_default()
+    }
+
+    /// Forgets the participation of all members. Called when the epoch changes, since the committee may change with
+    /// it.
+    pub fn clear(&self) {
+        self.members.write().unwrap().clear();
+    }
+
+    fn update<F: FnOnce(&mut MemberParticipation)>(&self, member: &TAddr, f: F) {
+        let mut members = self.members.write().unwrap();
+        f(members.entry(member.clone()).or_default());
+    }
+}
+
+impl<TAddr: Eq + Hash + Clone> Default for ParticipationTracker<TAddr> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct MemberParticipation {
+    pub last_vote: Option<Observation>,
+    pub last_proposal: Option<Observation>,
+    /// Unix timestamp in seconds of the last leader timeout attributed to the member
+    pub last_missed_proposal: Option<u64>,
+    pub votes_received: u64,
+    pub proposals_received: u64,
+    pub proposals_missed: u64,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Observation {
+    pub block_height: NodeHeight,
+    /// Unix timestamp in seconds
+    pub timestamp: u64,
+}
+
+impl Observation {
+    fn now(block_height: NodeHeight) -> Self {
+        Self {
+            block_height,
+            timestamp: unix_timestamp(),
+        }
+    }
+}
+
+fn unix_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
@@ -90,7 +90,7 @@ where TSpec: ConsensusSpec
                     Ok(None)
                 }
             },
-            EpochManagerEvent::ThisValidatorIsRegistered { .. } => Ok(None),
+            EpochManagerEvent::ThisValidatorIsRegistered { .. } | EpochManagerEvent::EpochEnding { .. } => Ok(None),
         }
     }
 }
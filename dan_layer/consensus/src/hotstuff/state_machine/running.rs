@@ -41,6 +41,7 @@ where TSpec: ConsensusSpec
                 Ok(ConsensusStateEvent::NotRegisteredForEpoch { epoch })
             },
             Err(err @ HotStuffError::ProposalValidationError(ProposalValidationError::JustifyBlockNotFound { .. })) |
+            Err(err @ HotStuffError::ProposalValidationError(ProposalValidationError::ParentBlockNotFound { .. })) |
             Err(err @ HotStuffError::FallenBehind { .. }) => {
                 info!(target: LOG_TARGET, "Behind peers, starting sync ({err})");
                 Ok(ConsensusStateEvent::NeedSync)
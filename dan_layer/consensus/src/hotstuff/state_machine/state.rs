@@ -32,6 +32,21 @@ impl ConsensusCurrentState {
     }
 }
 
+impl Display for ConsensusCurrentState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        #[allow(clippy::enum_glob_use)]
+        use ConsensusCurrentState::*;
+        match self {
+            Idle => write!(f, "Idle"),
+            CheckSync => write!(f, "CheckSync"),
+            Syncing => write!(f, "Syncing"),
+            Running => write!(f, "Running"),
+            Sleeping => write!(f, "Sleeping"),
+            Shutdown => write!(f, "Shutdown"),
+        }
+    }
+}
+
 impl<TSpec> ConsensusState<TSpec> {
     pub fn is_shutdown(&self) -> bool {
         matches!(self, ConsensusState::Shutdown)
@@ -0,0 +1,484 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+//! Startup checks for the consensus state pointers.
+//!
+//! The leaf block, locked block, last executed block and high QC are updated together when a block is processed. If
+//! the node died part way through, or the database was modified externally, these can disagree with each other or with
+//! the substates. The recovery pass checks the invariants between them before consensus starts, repairs the cases
+//! that can be repaired from local data and otherwise refuses to start with a description of the inconsistency.
+
+use std::{fmt::Display, ops::DerefMut};
+
+use log::*;
+use tari_dan_common_types::{optional::Optional, NodeAddressable, NodeHeight, ShardId};
+use tari_dan_storage::{
+    consensus_models::{
+        Block,
+        BlockId,
+        Command,
+        Decision,
+        ExecutedTransaction,
+        HighQc,
+        LastExecuted,
+        LeafBlock,
+        LockedBlock,
+        LockedOutput,
+        QuorumCertificate,
+        SubstateLockFlag,
+        SubstateLockState,
+        SubstateRecord,
+    },
+    StateStore,
+    StateStoreReadTransaction,
+    StateStoreWriteTransaction,
+    StorageError,
+};
+
+use crate::traits::StateManager;
+
+const LOG_TARGET: &str = "tari::dan::consensus::state_recovery";
+
+#[derive(Debug, thiserror::Error)]
+pub enum StateRecoveryError {
+    #[error("Storage error: {0}")]
+    StorageError(#[from] StorageError),
+    #[error("State manager error: {0}")]
+    StateManagerError(anyhow::Error),
+    #[error(
+        "State store is inconsistent: {details}. Restore the node from a backup or delete the state store to resync \
+         from peers"
+    )]
+    Inconsistent { details: String },
+}
+
+impl StateRecoveryError {
+    fn inconsistent<T: Display>(details: T) -> Self {
+        Self::Inconsistent {
+            details: details.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct RecoveryReport {
+    /// Committed blocks whose substate changes were missing and have been applied
+    pub blocks_reapplied: usize,
+    /// Committed blocks that were already applied but were not recorded as the last executed block
+    pub blocks_marked_executed: usize,
+    /// Output locks that were held for outputs that already exist or for blocks that no longer exist
+    pub stale_locks_released: usize,
+    /// Whether the leaf block was behind the locked block and was reset to it
+    pub leaf_reset: bool,
+}
+
+impl RecoveryReport {
+    pub fn is_clean(&self) -> bool {
+        self.blocks_reapplied == 0 &&
+            self.blocks_marked_executed == 0 &&
+            self.stale_locks_released == 0 &&
+            !self.leaf_reset
+    }
+}
+
+/// Checks the consensus state pointers and repairs recoverable inconsistencies. Substate changes that are missing are
+/// applied with the given state manager. Returns an error describing the inconsistency if the state store cannot be
+/// repaired.
+pub fn check_and_recover<TStateStore, TStateManager>(
+    state_store: &TStateStore,
+    state_manager: &TStateManager,
+) -> Result<RecoveryReport, StateRecoveryError>
+where
+    TStateStore: StateStore,
+    TStateManager: StateManager<TStateStore>,
+{
+    let report = state_store.with_write_tx(|tx| recover(tx, state_manager))?;
+    if report.is_clean() {
+        info!(target: LOG_TARGET, "🩺 State store consistency check passed");
+    } else {
+        warn!(
+            target: LOG_TARGET,
+            "🩺 Recovered state store: {} block(s) re-applied, {} block(s) marked as executed, {} stale output \
+             lock(s) released, leaf reset: {}",
+            report.blocks_reapplied,
+            report.blocks_marked_executed,
+            report.stale_locks_released,
+            report.leaf_reset
+        );
+    }
+    Ok(report)
+}
+
+fn recover<TStateStore, TStateManager>(
+    tx: &mut TStateStore::WriteTransaction<'_>,
+    state_manager: &TStateManager,
+) -> Result<RecoveryReport, StateRecoveryError>
+where
+    TStateStore: StateStore,
+    TStateManager: StateManager<TStateStore>,
+{
+    let mut report = RecoveryReport::default();
+
+    let leaf = LeafBlock::get(tx.deref_mut()).optional()?;
+    let locked = LockedBlock::get(tx.deref_mut()).optional()?;
+    let last_executed = LastExecuted::get(tx.deref_mut()).optional()?;
+    let high_qc = HighQc::get(tx.deref_mut()).optional()?;
+    let (leaf, locked, last_executed, high_qc) = match (leaf, locked, last_executed, high_qc) {
+        (Some(leaf), Some(locked), Some(last_executed), Some(high_qc)) => (leaf, locked, last_executed, high_qc),
+        // Consensus has not started yet
+        (None, None, None, None) => return Ok(report),
+        (leaf, locked, last_executed, high_qc) => {
+            return Err(StateRecoveryError::inconsistent(format!(
+                "only some consensus pointers are set (leaf block: {}, locked block: {}, last executed: {}, high QC: \
+                 {})",
+                is_set(&leaf),
+                is_set(&locked),
+                is_set(&last_executed),
+                is_set(&high_qc)
+            )))
+        },
+    };
+
+    let locked_block = get_pointer_block(tx, "locked block", locked.block_id(), locked.height())?;
+    let last_executed_block =
+        get_pointer_block(tx, "last executed block", &last_executed.block_id, last_executed.height)?;
+    if last_executed.height > locked.height {
+        return Err(StateRecoveryError::inconsistent(format!(
+            "last executed block {} at height {} is above the locked block {} at height {}",
+            last_executed.block_id, last_executed.height, locked.block_id, locked.height
+        )));
+    }
+
+    // The leaf block must extend the locked block
+    if leaf.height() < locked.height() {
+        warn!(
+            target: LOG_TARGET,
+            "Leaf block {} at height {} is below the locked block {} at height {}. Resetting the leaf to the locked \
+             block",
+            leaf.block_id(),
+            leaf.height(),
+            locked.block_id(),
+            locked.height()
+        );
+        locked_block.as_leaf_block().set(tx)?;
+        report.leaf_reset = true;
+    } else {
+        let leaf_block = get_pointer_block(tx, "leaf block", leaf.block_id(), leaf.height())?;
+        let ancestor = get_ancestor_at_height(tx, leaf_block, locked.height())?;
+        if ancestor.id() != locked.block_id() {
+            return Err(StateRecoveryError::inconsistent(format!(
+                "leaf block {} does not extend the locked block {}",
+                leaf.block_id(),
+                locked.block_id()
+            )));
+        }
+    }
+
+    let qc = QuorumCertificate::get(tx.deref_mut(), &high_qc.qc_id)
+        .optional()?
+        .ok_or_else(|| StateRecoveryError::inconsistent(format!("high QC {} does not exist", high_qc.qc_id)))?;
+    if *qc.block_id() != high_qc.block_id || qc.block_height() != high_qc.block_height {
+        return Err(StateRecoveryError::inconsistent(format!(
+            "high QC {} certifies block {} at height {} but the high QC pointer refers to block {} at height {}",
+            high_qc.qc_id,
+            qc.block_id(),
+            qc.block_height(),
+            high_qc.block_id,
+            high_qc.block_height
+        )));
+    }
+    if high_qc.block_height < locked.height() {
+        return Err(StateRecoveryError::inconsistent(format!(
+            "high QC at height {} is below the locked block at height {}",
+            high_qc.block_height,
+            locked.height()
+        )));
+    }
+
+    recover_committed_blocks(tx, state_manager, &mut report, locked_block, &last_executed_block)?;
+    release_stale_locks(tx, &mut report)?;
+
+    Ok(report)
+}
+
+/// Walks the chain from the locked block down to the last executed block. Any blocks above the last executed block
+/// that are marked as committed must form an unbroken chain from the last executed block. Their substate changes are
+/// applied if they are missing and the last executed pointer is moved to the highest of them.
+fn recover_committed_blocks<TStateStore, TStateManager>(
+    tx: &mut TStateStore::WriteTransaction<'_>,
+    state_manager: &TStateManager,
+    report: &mut RecoveryReport,
+    locked_block: Block<TStateStore::Addr>,
+    last_executed_block: &Block<TStateStore::Addr>,
+) -> Result<(), StateRecoveryError>
+where
+    TStateStore: StateStore,
+    TStateManager: StateManager<TStateStore>,
+{
+    let mut chain = Vec::new();
+    let mut current = locked_block;
+    while current.height() > last_executed_block.height() {
+        let parent = get_parent(tx, &current)?;
+        chain.push(current);
+        current = parent;
+    }
+    if current.id() != last_executed_block.id() {
+        return Err(StateRecoveryError::inconsistent(format!(
+            "locked block does not extend the last executed block {}",
+            last_executed_block.id()
+        )));
+    }
+
+    if !last_executed_block.height().is_zero() && !last_executed_block.is_committed() {
+        // The last executed pointer was moved without the block being marked as committed
+        ensure_applied(tx, state_manager, report, last_executed_block)?;
+        last_executed_block.commit(tx)?;
+    }
+
+    // Committed blocks must be a prefix of the chain above the last executed block
+    chain.reverse();
+    let num_committed = chain.iter().take_while(|b| b.is_committed()).count();
+    if let Some(block) = chain[num_committed..].iter().find(|b| b.is_committed()) {
+        return Err(StateRecoveryError::inconsistent(format!(
+            "block {} is committed but its parent chain down to the last executed block is not",
+            block
+        )));
+    }
+
+    for block in &chain[..num_committed] {
+        warn!(
+            target: LOG_TARGET,
+            "Block {} is committed but is above the last executed block {}",
+            block,
+            last_executed_block
+        );
+        ensure_applied(tx, state_manager, report, block)?;
+        block.as_last_executed().set(tx)?;
+        report.blocks_marked_executed += 1;
+    }
+
+    Ok(())
+}
+
+/// Applies the substate changes of a committed block if none of them have been applied. A block that is only
+/// partially applied cannot be repaired.
+fn ensure_applied<TStateStore, TStateManager>(
+    tx: &mut TStateStore::WriteTransaction<'_>,
+    state_manager: &TStateManager,
+    report: &mut RecoveryReport,
+    block: &Block<TStateStore::Addr>,
+) -> Result<(), StateRecoveryError>
+where
+    TStateStore: StateStore,
+    TStateManager: StateManager<TStateStore>,
+{
+    let mut applied = Vec::new();
+    let mut unapplied = Vec::new();
+    for cmd in block.commands() {
+        let Command::Accept(atom) = cmd else {
+            continue;
+        };
+        if !atom.decision.is_commit() {
+            continue;
+        }
+        let executed = atom.get_transaction(tx.deref_mut()).optional()?.ok_or_else(|| {
+            StateRecoveryError::inconsistent(format!(
+                "committed block {} contains transaction {} which has not been executed",
+                block, atom.id
+            ))
+        })?;
+        if is_applied(tx, &executed)? {
+            applied.push(executed);
+        } else {
+            unapplied.push(executed);
+        }
+    }
+
+    if unapplied.is_empty() {
+        return Ok(());
+    }
+    if !applied.is_empty() {
+        return Err(StateRecoveryError::inconsistent(format!(
+            "committed block {} is partially applied ({} of {} transaction(s) applied)",
+            block,
+            applied.len(),
+            applied.len() + unapplied.len()
+        )));
+    }
+
+    warn!(
+        target: LOG_TARGET,
+        "Re-applying {} transaction(s) of committed block {}",
+        unapplied.len(),
+        block
+    );
+    for mut executed in unapplied {
+        commit_transaction(tx, state_manager, block, &executed)?;
+        LockedOutput::try_release_all(tx, executed.resulting_outputs())?;
+        tx.transaction_pool_remove(executed.id()).optional()?;
+        executed.set_final_decision(Decision::Commit).update(tx)?;
+    }
+    report.blocks_reapplied += 1;
+
+    Ok(())
+}
+
+/// Returns true if all outputs of the transaction exist and all of its inputs are destroyed
+fn is_applied<TTx>(tx: &mut TTx, executed: &ExecutedTransaction) -> Result<bool, StateRecoveryError>
+where
+    TTx: StateStoreWriteTransaction + DerefMut,
+    TTx::Target: StateStoreReadTransaction<Addr = TTx::Addr>,
+{
+    let Some(diff) = executed.result().finalize.result.accept() else {
+        return Ok(true);
+    };
+    for (address, substate) in diff.up_iter() {
+        let shard_id = ShardId::from_address(address, substate.version());
+        if !SubstateRecord::exists(tx.deref_mut(), &shard_id)? {
+            return Ok(false);
+        }
+    }
+    for (address, version) in diff.down_iter() {
+        let shard_id = ShardId::from_address(address, *version);
+        let is_destroyed = SubstateRecord::get(tx.deref_mut(), &shard_id)
+            .optional()?
+            .map_or(true, |s| s.is_destroyed());
+        if !is_destroyed {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+/// Commits the transaction's substate changes with the state manager, as consensus does when the block is committed.
+/// The state manager requires the inputs to be write locked, so they are locked again unless they are still held from
+/// before the node stopped, and released once the transaction is committed.
+fn commit_transaction<TStateStore, TStateManager>(
+    tx: &mut TStateStore::WriteTransaction<'_>,
+    state_manager: &TStateManager,
+    block: &Block<TStateStore::Addr>,
+    executed: &ExecutedTransaction,
+) -> Result<(), StateRecoveryError>
+where
+    TStateStore: StateStore,
+    TStateManager: StateManager<TStateStore>,
+{
+    let Some(diff) = executed.result().finalize.result.accept() else {
+        return Ok(());
+    };
+    let inputs = diff
+        .down_iter()
+        .map(|(address, version)| ShardId::from_address(address, *version))
+        .collect::<Vec<_>>();
+
+    match SubstateRecord::try_lock_all(tx, executed.id(), &inputs, SubstateLockFlag::Write)? {
+        SubstateLockState::LockAcquired | SubstateLockState::SomeAlreadyWriteLocked => {},
+        state => {
+            return Err(StateRecoveryError::inconsistent(format!(
+                "the inputs of transaction {} in committed block {} cannot be locked ({:?})",
+                executed.id(),
+                block,
+                state
+            )));
+        },
+    }
+    state_manager
+        .commit_transaction(tx, block, executed)
+        .map_err(|e| StateRecoveryError::StateManagerError(e.into()))?;
+    SubstateRecord::try_unlock_many(tx, executed.id(), &inputs, SubstateLockFlag::Write)?;
+
+    Ok(())
+}
+
+/// Releases output locks for outputs that already exist or whose locking block no longer exists. These are left
+/// behind if a block is committed without its outputs being unlocked.
+fn release_stale_locks<TTx>(tx: &mut TTx, report: &mut RecoveryReport) -> Result<(), StateRecoveryError>
+where
+    TTx: StateStoreWriteTransaction + DerefMut,
+    TTx::Target: StateStoreReadTransaction<Addr = TTx::Addr>,
+    TTx::Addr: NodeAddressable,
+{
+    let mut stale = Vec::new();
+    for lock in tx.deref_mut().locked_outputs_get_all()? {
+        if SubstateRecord::exists(tx.deref_mut(), &lock.shard_id)? ||
+            !Block::<TTx::Addr>::record_exists(tx.deref_mut(), &lock.block_id)?
+        {
+            debug!(
+                target: LOG_TARGET,
+                "Releasing stale output lock on {} held by transaction {} in block {}",
+                lock.shard_id,
+                lock.transaction_id,
+                lock.block_id
+            );
+            stale.push(lock.shard_id);
+        }
+    }
+
+    if !stale.is_empty() {
+        report.stale_locks_released = stale.len();
+        LockedOutput::try_release_all(tx, stale)?;
+    }
+    Ok(())
+}
+
+fn get_pointer_block<TTx>(
+    tx: &mut TTx,
+    name: &str,
+    block_id: &BlockId,
+    height: NodeHeight,
+) -> Result<Block<TTx::Addr>, StateRecoveryError>
+where
+    TTx: StateStoreWriteTransaction + DerefMut,
+    TTx::Target: StateStoreReadTransaction<Addr = TTx::Addr>,
+    TTx::Addr: NodeAddressable,
+{
+    let block = Block::get(tx.deref_mut(), block_id)
+        .optional()?
+        .ok_or_else(|| StateRecoveryError::inconsistent(format!("{} {} does not exist", name, block_id)))?;
+    if block.height() != height {
+        return Err(StateRecoveryError::inconsistent(format!(
+            "{} {} is recorded at height {} but the block is at height {}",
+            name,
+            block_id,
+            height,
+            block.height()
+        )));
+    }
+    Ok(block)
+}
+
+fn get_ancestor_at_height<TTx>(
+    tx: &mut TTx,
+    mut block: Block<TTx::Addr>,
+    height: NodeHeight,
+) -> Result<Block<TTx::Addr>, StateRecoveryError>
+where
+    TTx: StateStoreWriteTransaction + DerefMut,
+    TTx::Target: StateStoreReadTransaction<Addr = TTx::Addr>,
+    TTx::Addr: NodeAddressable,
+{
+    while block.height() > height {
+        block = get_parent(tx, &block)?;
+    }
+    Ok(block)
+}
+
+fn get_parent<TTx>(tx: &mut TTx, block: &Block<TTx::Addr>) -> Result<Block<TTx::Addr>, StateRecoveryError>
+where
+    TTx: StateStoreWriteTransaction + DerefMut,
+    TTx::Target: StateStoreReadTransaction<Addr = TTx::Addr>,
+    TTx::Addr: NodeAddressable,
+{
+    block
+        .get_parent(tx.deref_mut())
+        .optional()?
+        .ok_or_else(|| StateRecoveryError::inconsistent(format!("parent of block {} does not exist", block)))
+}
+
+fn is_set<T>(value: &Option<T>) -> &'static str {
+    if value.is_some() {
+        "set"
+    } else {
+        "missing"
+    }
+}
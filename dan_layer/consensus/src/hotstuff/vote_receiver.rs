@@ -1,7 +1,12 @@
 //   Copyright 2023 The Tari Project
 //   SPDX-License-Identifier: BSD-3-Clause
 
-use std::ops::DerefMut;
+use std::{
+    collections::HashMap,
+    ops::DerefMut,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
 
 use log::*;
 use tari_common_types::types::FixedHash;
@@ -12,19 +17,23 @@ use tari_dan_common_types::{
     NodeAddressable,
 };
 use tari_dan_storage::{
-    consensus_models::{Block, QuorumCertificate, QuorumDecision, ValidatorSignature, Vote},
+    consensus_models::{Block, BlockId, QuorumCertificate, QuorumDecision, ValidatorSignature, Vote},
     StateStore,
 };
 use tari_epoch_manager::EpochManagerReader;
 
 use crate::{
-    hotstuff::{error::HotStuffError, pacemaker_handle::PaceMakerHandle},
+    hotstuff::{error::HotStuffError, pacemaker_handle::PaceMakerHandle, ParticipationTracker},
     messages::VoteMessage,
+    metrics,
     traits::{ConsensusSpec, LeaderStrategy, VoteSignatureService},
 };
 
 const LOG_TARGET: &str = "tari::dan::consensus::hotstuff::on_receive_vote";
 
+/// Blocks that have not formed a QC within this time are no longer tracked for the QC formation latency metric
+const FIRST_VOTE_RETENTION: Duration = Duration::from_secs(5 * 60);
+
 #[derive(Clone)]
 pub struct VoteReceiver<TConsensusSpec: ConsensusSpec> {
     store: TConsensusSpec::StateStore,
@@ -32,6 +41,8 @@ pub struct VoteReceiver<TConsensusSpec: ConsensusSpec> {
     epoch_manager: TConsensusSpec::EpochManager,
     vote_signature_service: TConsensusSpec::VoteSignatureService,
     pacemaker: PaceMakerHandle,
+    participation: ParticipationTracker<TConsensusSpec::Addr>,
+    first_vote_received_at: Arc<Mutex<HashMap<BlockId, Instant>>>,
 }
 
 impl<TConsensusSpec> VoteReceiver<TConsensusSpec>
@@ -43,6 +54,7 @@ where TConsensusSpec: ConsensusSpec
         epoch_manager: TConsensusSpec::EpochManager,
         vote_signature_service: TConsensusSpec::VoteSignatureService,
         pacemaker: PaceMakerHandle,
+        participation: ParticipationTracker<TConsensusSpec::Addr>,
     ) -> Self {
         Self {
             store,
@@ -50,6 +62,8 @@ where TConsensusSpec: ConsensusSpec
             epoch_manager,
             pacemaker,
             vote_signature_service,
+            participation,
+            first_vote_received_at: Arc::new(Mutex::new(HashMap::new())),
         }
     }
 
@@ -111,6 +125,10 @@ where TConsensusSpec: ConsensusSpec
         let sender_leaf_hash = sender_vn.node_hash();
 
         self.validate_vote_message(&message, &sender_leaf_hash)?;
+        metrics::VOTES_RECEIVED.inc();
+        self.record_first_vote(message.block_id);
+        self.participation
+            .record_vote(&message.signature.public_key, message.block_height);
 
         let from = message.signature.public_key.clone();
 
@@ -158,7 +176,7 @@ where TConsensusSpec: ConsensusSpec
             if check_leadership &&
                 !self
                     .leader_strategy
-                    .is_leader_for_next_block(&vn.address, &committee, block.height())
+                    .is_leader_for_next_view(&vn.address, &committee, block.view())
             {
                 return Err(HotStuffError::NotTheLeader {
                     details: format!(
@@ -227,15 +245,29 @@ where TConsensusSpec: ConsensusSpec
             .await?;
 
         let block_height = vote_data.block.height();
+        let block_view = vote_data.block.view();
         let qc = create_qc(vote_data, merged_proof);
         info!(target: LOG_TARGET, "🔥 New QC {}", qc);
-        let high_qc = self.store.with_write_tx(|tx| qc.update_high_qc(tx))?;
+        if let Some(first_vote_at) = self.take_first_vote(qc.block_id()) {
+            metrics::QC_FORMATION_SECONDS.observe(first_vote_at.elapsed().as_secs_f64());
+        }
+        self.store.with_write_tx(|tx| qc.update_high_qc(tx))?;
 
-        self.pacemaker.update_view(block_height, high_qc.block_height).await?;
+        self.pacemaker.update_view(block_height, block_view).await?;
 
         Ok(true)
     }
 
+    fn record_first_vote(&self, block_id: BlockId) {
+        let mut first_votes = self.first_vote_received_at.lock().unwrap();
+        first_votes.retain(|_, received_at| received_at.elapsed() < FIRST_VOTE_RETENTION);
+        first_votes.entry(block_id).or_insert_with(Instant::now);
+    }
+
+    fn take_first_vote(&self, block_id: &BlockId) -> Option<Instant> {
+        self.first_vote_received_at.lock().unwrap().remove(block_id)
+    }
+
     fn calculate_threshold_decision(
         votes: &[Vote<TConsensusSpec::Addr>],
         local_committee_shard: &CommitteeShard,
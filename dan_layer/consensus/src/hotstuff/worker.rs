@@ -8,10 +8,23 @@ use std::{
 };
 
 use log::*;
-use tari_dan_common_types::{optional::Optional, NodeHeight};
+use tari_dan_common_types::{optional::Optional, Epoch, NodeHeight, View};
 use tari_dan_storage::{
-    consensus_models::{Block, HighQc, LastSentVote, LastVoted, LeafBlock, TransactionPool},
+    consensus_models::{
+        Block,
+        HighQc,
+        LastSentVote,
+        LastVoted,
+        LeafBlock,
+        LockedOutput,
+        SubstateLockFlag,
+        SubstateRecord,
+        TimeoutCertificate,
+        TransactionPool,
+        TransactionRecord,
+    },
     StateStore,
+    StateStoreReadTransaction,
     StateStoreWriteTransaction,
 };
 use tari_epoch_manager::{EpochManagerEvent, EpochManagerReader};
@@ -37,13 +50,18 @@ use crate::{
         pacemaker::PaceMaker,
         pacemaker_handle::PaceMakerHandle,
         vote_receiver::VoteReceiver,
+        ConsensusConfig,
+        ParticipationTracker,
     },
     messages::{HotstuffMessage, SyncRequestMessage},
+    metrics,
     traits::{ConsensusSpec, LeaderStrategy},
 };
 
 const LOG_TARGET: &str = "tari::dan::consensus::hotstuff::worker";
 
+const ABORT_TRANSACTIONS_PAGE_SIZE: usize = 100;
+
 pub struct HotstuffWorker<TConsensusSpec: ConsensusSpec> {
     validator_addr: TConsensusSpec::Addr,
 
@@ -68,11 +86,13 @@ pub struct HotstuffWorker<TConsensusSpec: ConsensusSpec> {
     epoch_manager: TConsensusSpec::EpochManager,
     pacemaker_worker: Option<PaceMaker>,
     pacemaker: PaceMakerHandle,
+    participation: ParticipationTracker<TConsensusSpec::Addr>,
     shutdown: ShutdownSignal,
 }
 impl<TConsensusSpec: ConsensusSpec> HotstuffWorker<TConsensusSpec> {
     #[allow(clippy::too_many_arguments)]
     pub fn new(
+        config: ConsensusConfig,
         validator_addr: TConsensusSpec::Addr,
         rx_new_transactions: mpsc::Receiver<TransactionId>,
         rx_hs_message: mpsc::Receiver<(TConsensusSpec::Addr, HotstuffMessage<TConsensusSpec::Addr>)>,
@@ -88,13 +108,15 @@ impl<TConsensusSpec: ConsensusSpec> HotstuffWorker<TConsensusSpec> {
         tx_mempool: mpsc::UnboundedSender<Transaction>,
         shutdown: ShutdownSignal,
     ) -> Self {
-        let pacemaker = PaceMaker::new();
+        let pacemaker = PaceMaker::new(&config);
+        let participation = ParticipationTracker::new();
         let vote_receiver = VoteReceiver::new(
             state_store.clone(),
             leader_strategy.clone(),
             epoch_manager.clone(),
             signing_service.clone(),
             pacemaker.clone_handle(),
+            participation.clone(),
         );
         let proposer =
             Proposer::<TConsensusSpec>::new(state_store.clone(), epoch_manager.clone(), tx_broadcast.clone());
@@ -103,9 +125,11 @@ impl<TConsensusSpec: ConsensusSpec> HotstuffWorker<TConsensusSpec> {
             tx_events: tx_events.clone(),
             tx_leader: tx_leader.clone(),
             inbound_message_worker: OnInboundMessage::new(
+                config.clone(),
                 state_store.clone(),
                 epoch_manager.clone(),
                 leader_strategy.clone(),
+                signing_service.clone(),
                 pacemaker.clone_handle(),
                 rx_hs_message,
                 tx_leader.clone(),
@@ -118,6 +142,7 @@ impl<TConsensusSpec: ConsensusSpec> HotstuffWorker<TConsensusSpec> {
                 tx_leader.clone(),
                 leader_strategy.clone(),
                 epoch_manager.clone(),
+                signing_service.clone(),
             ),
             on_receive_local_proposal: OnReceiveProposalHandler::new(
                 validator_addr,
@@ -126,11 +151,12 @@ impl<TConsensusSpec: ConsensusSpec> HotstuffWorker<TConsensusSpec> {
                 leader_strategy.clone(),
                 pacemaker.clone_handle(),
                 tx_leader.clone(),
-                signing_service,
+                signing_service.clone(),
                 state_manager,
                 transaction_pool.clone(),
                 tx_events,
                 proposer.clone(),
+                participation.clone(),
             ),
             on_receive_foreign_proposal: OnReceiveForeignProposalHandler::new(
                 state_store.clone(),
@@ -145,6 +171,7 @@ impl<TConsensusSpec: ConsensusSpec> HotstuffWorker<TConsensusSpec> {
                 epoch_manager.clone(),
                 pacemaker.clone_handle(),
                 vote_receiver,
+                signing_service.clone(),
             ),
             on_receive_request_missing_txs: OnReceiveRequestMissingTransactions::new(
                 state_store.clone(),
@@ -152,10 +179,12 @@ impl<TConsensusSpec: ConsensusSpec> HotstuffWorker<TConsensusSpec> {
             ),
             on_receive_requested_txs: OnReceiveRequestedTransactions::new(tx_mempool),
             on_propose: OnPropose::new(
+                config,
                 state_store.clone(),
                 epoch_manager.clone(),
                 transaction_pool.clone(),
                 tx_broadcast,
+                signing_service,
             ),
 
             on_sync_request: OnSyncRequest::new(state_store.clone(), tx_leader),
@@ -167,28 +196,39 @@ impl<TConsensusSpec: ConsensusSpec> HotstuffWorker<TConsensusSpec> {
 
             pacemaker: pacemaker.clone_handle(),
             pacemaker_worker: Some(pacemaker),
+            participation,
             shutdown,
         }
     }
+
+    /// Returns a handle to the participation of committee members as seen by this node
+    pub fn participation_tracker(&self) -> ParticipationTracker<TConsensusSpec::Addr> {
+        self.participation.clone()
+    }
 }
 impl<TConsensusSpec> HotstuffWorker<TConsensusSpec>
 where TConsensusSpec: ConsensusSpec
 {
     pub async fn start(&mut self) -> Result<(), HotStuffError> {
         self.create_genesis_block_if_required()?;
-        let (current_height, high_qc) = self.state_store.with_read_tx(|tx| {
-            let leaf = LeafBlock::get(tx)?;
-            let last_voted = LastVoted::get(tx)?;
-            Ok::<_, HotStuffError>((cmp::max(leaf.height(), last_voted.height()), HighQc::get(tx)?))
+        let (current_view, current_height, high_qc) = self.state_store.with_read_tx(|tx| {
+            let leaf = LeafBlock::get(tx)?.get_block(tx)?;
+            let last_voted = LastVoted::get(tx)?.get_block(tx)?;
+            Ok::<_, HotStuffError>((
+                cmp::max(leaf.view(), last_voted.view()),
+                cmp::max(leaf.height(), last_voted.height()),
+                HighQc::get(tx)?,
+            ))
         })?;
         info!(
             target: LOG_TARGET,
-            "🚀 Pacemaker starting leaf_block: {}, high_qc: {}",
+            "🚀 Pacemaker starting {}, leaf_block: {}, high_qc: {}",
+            current_view,
             current_height,
             high_qc
         );
 
-        self.pacemaker.start(current_height, high_qc.block_height()).await?;
+        self.pacemaker.start(current_view.next(), current_height).await?;
 
         self.run().await?;
         Ok(())
@@ -209,16 +249,18 @@ where TConsensusSpec: ConsensusSpec
         self.request_initial_catch_up_sync().await?;
 
         loop {
+            let current_view = self.pacemaker.current_view();
             let current_height = self.pacemaker.current_height() + NodeHeight(1);
 
             debug!(
                 target: LOG_TARGET,
-                "🔥 Current height #{}",
+                "🔥 Current {}, height #{}",
+                current_view,
                 current_height.as_u64()
             );
 
             tokio::select! {
-                msg_or_sync = self.inbound_message_worker.next(current_height) => {
+                msg_or_sync = self.inbound_message_worker.next(current_view, current_height) => {
                     if let Err(e) = self.on_new_hs_message(msg_or_sync).await {
                         self.on_failure("on_new_hs_message", &e).await;
                         return Err(e);
@@ -236,15 +278,15 @@ where TConsensusSpec: ConsensusSpec
                     }
                 },
 
-                maybe_leaf_block = on_force_beat.wait() => {
-                    if let Err(e) = self.propose_if_leader(maybe_leaf_block).await {
+                _ = on_force_beat.wait() => {
+                    if let Err(e) = self.propose_if_leader(None).await {
                         self.on_failure("propose_if_leader", &e).await;
                         return Err(e);
                     }
                 },
 
-                new_height = on_leader_timeout.wait() => {
-                    if let Err(e) = self.on_leader_timeout(new_height).await {
+                new_view = on_leader_timeout.wait() => {
+                    if let Err(e) = self.on_leader_timeout(new_view).await {
                         self.on_failure("on_leader_timeout", &e).await;
                         return Err(e);
                     }
@@ -267,9 +309,12 @@ where TConsensusSpec: ConsensusSpec
         Ok(())
     }
 
-    async fn handle_epoch_manager_event(&self, event: EpochManagerEvent) -> Result<(), HotStuffError> {
+    async fn handle_epoch_manager_event(&mut self, event: EpochManagerEvent) -> Result<(), HotStuffError> {
         match event {
             EpochManagerEvent::EpochChanged(epoch) => {
+                self.participation.clear();
+                self.abort_unfinalized_transactions(epoch)?;
+
                 if !self.epoch_manager.is_this_validator_registered_for_epoch(epoch).await? {
                     info!(
                         target: LOG_TARGET,
@@ -287,9 +332,13 @@ where TConsensusSpec: ConsensusSpec
                         last_voted
                     );
                     let local_committee = self.epoch_manager.get_local_committee(epoch).await?;
+                    let last_voted_view = self
+                        .state_store
+                        .with_read_tx(|tx| Block::get(tx, &last_voted.block_id))?
+                        .view();
                     let leader = self
                         .leader_strategy
-                        .get_leader_for_next_block(&local_committee, last_voted.block_height);
+                        .get_leader_for_next_view(&local_committee, last_voted_view);
                     self.tx_leader
                         .send((leader.clone(), HotstuffMessage::Vote(last_voted.into())))
                         .await
@@ -298,6 +347,18 @@ where TConsensusSpec: ConsensusSpec
                         })?;
                 }
             },
+            EpochManagerEvent::EpochEnding {
+                epoch,
+                blocks_remaining,
+            } => {
+                info!(
+                    target: LOG_TARGET,
+                    "⏳ Epoch {} ends in {} base layer block(s). No new transactions will be prepared from the next block.",
+                    epoch,
+                    blocks_remaining,
+                );
+                self.on_propose.set_ending_epoch(epoch);
+            },
             EpochManagerEvent::ThisValidatorIsRegistered { .. } => {},
         }
 
@@ -333,9 +394,106 @@ where TConsensusSpec: ConsensusSpec
         self.inbound_message_worker.discard().await;
     }
 
-    async fn on_leader_timeout(&mut self, new_height: NodeHeight) -> Result<(), HotStuffError> {
-        self.on_next_sync_view.handle(new_height).await?;
-        self.publish_event(HotstuffEvent::LeaderTimeout { new_height });
+    async fn on_leader_timeout(&mut self, new_view: View) -> Result<(), HotStuffError> {
+        metrics::LEADER_TIMEOUTS.inc();
+        if let Err(err) = self.record_missed_proposal(new_view).await {
+            warn!(target: LOG_TARGET, "Failed to record missed proposal before view {}: {}", new_view, err);
+        }
+        self.on_next_sync_view.handle(new_view).await?;
+        self.publish_event(HotstuffEvent::LeaderTimeout { new_view });
+        Ok(())
+    }
+
+    /// Aborts the transactions that were prepared in the previous epoch but not finalized before the epoch ended, and
+    /// releases their substate locks. The outgoing committee no longer proposes blocks for these transactions and the
+    /// incoming committee does not take over their locks.
+    async fn abort_unfinalized_transactions(&self, epoch: Epoch) -> Result<(), HotStuffError> {
+        let Some(prev_epoch) = epoch.checked_sub(Epoch(1)) else {
+            return Ok(());
+        };
+        let Some(local_committee_shard) = self
+            .epoch_manager
+            .get_local_committee_shard(prev_epoch)
+            .await
+            .optional()?
+        else {
+            return Ok(());
+        };
+
+        let num_aborted = self.state_store.with_write_tx(|tx| {
+            let mut num_aborted = 0;
+            let mut after = None;
+            loop {
+                let page = tx
+                    .deref_mut()
+                    .transaction_pool_get_page(after.as_ref(), ABORT_TRANSACTIONS_PAGE_SIZE)?;
+                let is_last_page = page.len() < ABORT_TRANSACTIONS_PAGE_SIZE;
+                after = page.last().map(|rec| *rec.transaction_id());
+
+                for rec in page {
+                    if rec.current_stage().is_new() {
+                        continue;
+                    }
+                    let mut transaction = TransactionRecord::get(tx.deref_mut(), rec.transaction_id())?;
+                    let inputs = transaction
+                        .transaction()
+                        .inputs()
+                        .iter()
+                        .chain(transaction.transaction().filled_inputs());
+                    SubstateRecord::try_unlock_many(
+                        tx,
+                        transaction.id(),
+                        local_committee_shard.filter(inputs),
+                        SubstateLockFlag::Write,
+                    )?;
+                    SubstateRecord::try_unlock_many(
+                        tx,
+                        transaction.id(),
+                        local_committee_shard.filter(transaction.transaction().input_refs()),
+                        SubstateLockFlag::Read,
+                    )?;
+                    LockedOutput::try_release_all(tx, local_committee_shard.filter(transaction.resulting_outputs()))?;
+
+                    transaction.set_abort(format!(
+                        "Transaction was not finalized before the end of epoch {}",
+                        prev_epoch
+                    ));
+                    transaction.update(tx)?;
+                    rec.remove(tx)?;
+                    num_aborted += 1;
+                }
+
+                if is_last_page {
+                    break;
+                }
+            }
+            Ok::<_, HotStuffError>(num_aborted)
+        })?;
+
+        if num_aborted > 0 {
+            info!(
+                target: LOG_TARGET,
+                "🗑️ Aborted {} transaction(s) that were not finalized before the end of epoch {}",
+                num_aborted,
+                prev_epoch
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Attributes the timeout to the leader of the view that timed out
+    async fn record_missed_proposal(&self, new_view: View) -> Result<(), HotStuffError> {
+        let Some(timed_out_view) = new_view.as_u64().checked_sub(1).map(View::from) else {
+            return Ok(());
+        };
+        let current_epoch = self.epoch_manager.current_epoch().await?;
+        let committee = self.epoch_manager.get_local_committee(current_epoch).await?;
+        if committee.is_empty() {
+            return Ok(());
+        }
+        let leader = self.leader_strategy.get_leader(&committee, timed_out_view);
+        self.participation.record_missed_proposal(leader);
         Ok(())
     }
 
@@ -353,31 +511,49 @@ where TConsensusSpec: ConsensusSpec
         Ok(())
     }
 
-    async fn propose_if_leader(&mut self, leaf_block: Option<LeafBlock>) -> Result<(), HotStuffError> {
-        let is_newview_propose = leaf_block.is_some();
-        let leaf_block = match leaf_block {
-            Some(leaf_block) => leaf_block,
-            None => self.state_store.with_read_tx(|tx| LeafBlock::get(tx))?,
-        };
+    async fn propose_if_leader(
+        &mut self,
+        timeout_certificate: Option<TimeoutCertificate<TConsensusSpec::Addr>>,
+    ) -> Result<(), HotStuffError> {
+        let is_newview_propose = timeout_certificate.is_some();
+        let view = timeout_certificate
+            .as_ref()
+            .map(|tc| tc.new_view())
+            .unwrap_or_else(|| self.pacemaker.current_view());
+        let (leaf_block, leaf_view) = self.state_store.with_read_tx(|tx| {
+            let leaf_block = LeafBlock::get(tx)?;
+            let leaf_view = leaf_block.get_block(tx)?.view();
+            Ok::<_, HotStuffError>((leaf_block, leaf_view))
+        })?;
         let current_epoch = self.epoch_manager.current_epoch().await?;
         let local_committee = self.epoch_manager.get_local_committee(current_epoch).await?;
 
-        let is_leader =
-            self.leader_strategy
-                .is_leader_for_next_block(&self.validator_addr, &local_committee, leaf_block.height);
+        let is_leader = self
+            .leader_strategy
+            .is_leader(&self.validator_addr, &local_committee, view);
         info!(
             target: LOG_TARGET,
-            "🔥 [on_beat{}] {} Is leader: {:?}, leaf_block: {}, local_committee: {}",
+            "🔥 [on_beat{}] {} Is leader for {}: {:?}, leaf_block: {}, local_committee: {}",
             if is_newview_propose { " (NEWVIEW)"} else { "" },
             self.validator_addr,
+            view,
             is_leader,
             leaf_block,
             local_committee
                 .len(),
         );
+        // Without a timeout certificate, we can only propose in the view directly after the leaf block
+        if !is_newview_propose && leaf_view.next() != view {
+            debug!(
+                target: LOG_TARGET,
+                "[on_beat] Leaf block {} is not from the view before {}. Waiting for a QC or a timeout.", leaf_block, view
+            );
+            return Ok(());
+        }
+
         if is_leader {
             self.on_propose
-                .handle(current_epoch, local_committee, leaf_block, is_newview_propose)
+                .handle(current_epoch, local_committee, leaf_block, view, timeout_certificate)
                 .await?;
         } else if is_newview_propose {
             // We can make this a warm/error in future, but for now I want to be sure this never happens
@@ -433,13 +609,20 @@ where TConsensusSpec: ConsensusSpec
         // TODO: check the message comes from a local committee member (except foreign proposals which must come from a
         //       registered node)
         match msg {
-            HotstuffMessage::NewView(message) => log_err(
-                "on_receive_new_view",
-                self.on_receive_new_view.handle(from, message).await,
-            ),
+            HotstuffMessage::NewView(message) => {
+                let maybe_tc = log_err(
+                    "on_receive_new_view",
+                    self.on_receive_new_view.handle(from, message).await,
+                )?;
+                if let Some(tc) = maybe_tc {
+                    metrics::VIEW_CHANGES.inc();
+                    self.propose_if_leader(Some(tc)).await?;
+                }
+                Ok(())
+            },
             HotstuffMessage::Proposal(msg) => log_err(
                 "on_receive_local_proposal",
-                self.on_receive_local_proposal.handle(msg).await,
+                self.on_receive_local_proposal.handle(from, msg).await,
             ),
             HotstuffMessage::ForeignProposal(msg) => log_err(
                 "on_receive_foreign_proposal",
@@ -485,9 +668,11 @@ where TConsensusSpec: ConsensusSpec
             self.pacemaker.current_height()
         );
 
-        self.pacemaker
-            .reset_view(high_qc.block_height(), high_qc.block_height())
-            .await?;
+        let high_qc_view = self
+            .state_store
+            .with_read_tx(|tx| Block::get(tx, high_qc.block_id()))?
+            .view();
+        self.pacemaker.reset_view(high_qc.block_height(), high_qc_view).await?;
 
         let current_epoch = self.epoch_manager.current_epoch().await?;
         // Send the request message
@@ -60,8 +60,8 @@ impl<TAddr> HotstuffMessage<TAddr> {
 impl<TAddr> Display for HotstuffMessage<TAddr> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            HotstuffMessage::NewView(msg) => write!(f, "NewView({})", msg.new_height),
-            HotstuffMessage::Proposal(msg) => write!(f, "Proposal({})", msg.block.height()),
+            HotstuffMessage::NewView(msg) => write!(f, "NewView({})", msg.new_view),
+            HotstuffMessage::Proposal(msg) => write!(f, "Proposal({}, {})", msg.block.height(), msg.block.view()),
             HotstuffMessage::ForeignProposal(msg) => write!(f, "ForeignProposal({})", msg.block.height()),
             HotstuffMessage::Vote(msg) => write!(f, "Vote({}, {}, {})", msg.block_height, msg.block_id, msg.decision),
             HotstuffMessage::RequestMissingTransactions(msg) => {
@@ -2,8 +2,8 @@
 //   SPDX-License-Identifier: BSD-3-Clause
 
 use serde::Serialize;
-use tari_dan_common_types::{Epoch, NodeHeight};
-use tari_dan_storage::consensus_models::QuorumCertificate;
+use tari_dan_common_types::{Epoch, View};
+use tari_dan_storage::consensus_models::{QuorumCertificate, ValidatorSignature};
 
 use super::VoteMessage;
 
@@ -11,6 +11,9 @@ use super::VoteMessage;
 pub struct NewViewMessage<TAddr> {
     pub high_qc: QuorumCertificate<TAddr>,
     pub epoch: Epoch,
-    pub new_height: NodeHeight,
+    /// The view that the sender is moving to after the leader for the previous view timed out
+    pub new_view: View,
     pub last_vote: Option<VoteMessage<TAddr>>,
+    /// The sender's signature of the NEWVIEW
+    pub signature: ValidatorSignature<TAddr>,
 }
@@ -0,0 +1,43 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+//! Consensus metrics, registered with the default prometheus registry
+
+use lazy_static::lazy_static;
+use prometheus::{Histogram, HistogramOpts, IntCounter, IntGauge};
+use tari_dan_common_types::metrics::register;
+
+lazy_static! {
+    pub static ref CURRENT_HEIGHT: IntGauge = register(
+        IntGauge::new(
+            "consensus_current_height",
+            "The height of the last block seen by consensus"
+        )
+        .unwrap()
+    );
+    pub static ref CURRENT_VIEW: IntGauge =
+        register(IntGauge::new("consensus_current_view", "The view that consensus is currently in").unwrap());
+    pub static ref COMMITTED_HEIGHT: IntGauge =
+        register(IntGauge::new("consensus_committed_height", "The height of the last committed block").unwrap());
+    pub static ref VIEW_CHANGES: IntCounter = register(
+        IntCounter::new(
+            "consensus_view_changes_total",
+            "The number of views entered through a timeout certificate"
+        )
+        .unwrap()
+    );
+    pub static ref LEADER_TIMEOUTS: IntCounter =
+        register(IntCounter::new("consensus_leader_timeouts_total", "The number of leader timeouts").unwrap());
+    pub static ref VOTES_RECEIVED: IntCounter =
+        register(IntCounter::new("consensus_votes_received_total", "The number of valid votes received").unwrap());
+    pub static ref QC_FORMATION_SECONDS: Histogram = register(
+        Histogram::with_opts(
+            HistogramOpts::new(
+                "consensus_qc_formation_seconds",
+                "The time between receiving the first vote for a block and forming its quorum certificate"
+            )
+            .buckets(vec![0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0])
+        )
+        .unwrap()
+    );
+}
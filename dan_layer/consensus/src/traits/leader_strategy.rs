@@ -20,13 +20,13 @@
 //  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 //  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use tari_dan_common_types::{committee::Committee, NodeAddressable, NodeHeight};
+use tari_dan_common_types::{committee::Committee, NodeAddressable, View};
 
 pub trait LeaderStrategy<TAddr: NodeAddressable> {
-    fn calculate_leader(&self, committee: &Committee<TAddr>, height: NodeHeight) -> u32;
+    fn calculate_leader(&self, committee: &Committee<TAddr>, view: View) -> u32;
 
-    fn is_leader(&self, validator_addr: &TAddr, committee: &Committee<TAddr>, height: NodeHeight) -> bool {
-        let position = self.calculate_leader(committee, height);
+    fn is_leader(&self, validator_addr: &TAddr, committee: &Committee<TAddr>, view: View) -> bool {
+        let position = self.calculate_leader(committee, view);
         if let Some(vn) = committee.members.get(position as usize) {
             vn == validator_addr
         } else {
@@ -34,22 +34,16 @@ pub trait LeaderStrategy<TAddr: NodeAddressable> {
         }
     }
 
-    fn is_leader_for_next_block(
-        &self,
-        validator_addr: &TAddr,
-        committee: &Committee<TAddr>,
-        // block: &BlockId,
-        height: NodeHeight,
-    ) -> bool {
-        self.is_leader(validator_addr, committee, height + NodeHeight(1))
+    fn is_leader_for_next_view(&self, validator_addr: &TAddr, committee: &Committee<TAddr>, view: View) -> bool {
+        self.is_leader(validator_addr, committee, view.next())
     }
 
-    fn get_leader<'b>(&self, committee: &'b Committee<TAddr>, height: NodeHeight) -> &'b TAddr {
-        let index = self.calculate_leader(committee, height);
+    fn get_leader<'b>(&self, committee: &'b Committee<TAddr>, view: View) -> &'b TAddr {
+        let index = self.calculate_leader(committee, view);
         committee.members.get(index as usize).unwrap()
     }
 
-    fn get_leader_for_next_block<'b>(&self, committee: &'b Committee<TAddr>, height: NodeHeight) -> &'b TAddr {
-        self.get_leader(committee, height + NodeHeight(1))
+    fn get_leader_for_next_view<'b>(&self, committee: &'b Committee<TAddr>, view: View) -> &'b TAddr {
+        self.get_leader(committee, view.next())
     }
 }
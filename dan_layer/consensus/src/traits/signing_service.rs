@@ -2,13 +2,101 @@
 //   SPDX-License-Identifier: BSD-3-Clause
 
 use tari_common_types::types::FixedHash;
-use tari_dan_common_types::{hashing::vote_signature_hasher, NodeAddressable};
-use tari_dan_storage::consensus_models::{BlockId, QuorumDecision, ValidatorSchnorrSignature, ValidatorSignature};
+use tari_dan_common_types::{
+    hashing::{new_view_signature_hasher, proposal_signature_hasher, vote_signature_hasher},
+    Epoch,
+    NodeAddressable,
+    NodeHeight,
+    View,
+};
+use tari_dan_storage::consensus_models::{
+    BlockId,
+    QcId,
+    QuorumDecision,
+    SignedNewView,
+    ValidatorSchnorrSignature,
+    ValidatorSignature,
+};
 
 pub trait ValidatorSignatureService<TAddr> {
     fn sign<M: AsRef<[u8]>>(&self, message: M) -> ValidatorSchnorrSignature;
 
     fn public_key(&self) -> &TAddr;
+
+    /// Returns true if the signature is a valid signature of the message by the signature's public key
+    fn verify_signature<M: AsRef<[u8]>>(&self, signature: &ValidatorSignature<TAddr>, message: M) -> bool;
+
+    fn create_proposal_challenge(&self, block_id: &BlockId) -> FixedHash {
+        proposal_signature_hasher().chain(block_id).result()
+    }
+
+    fn sign_proposal(&self, block_id: &BlockId) -> ValidatorSignature<TAddr>
+    where TAddr: NodeAddressable {
+        let challenge = self.create_proposal_challenge(block_id);
+        ValidatorSignature::new(self.public_key().clone(), self.sign(challenge))
+    }
+
+    fn verify_proposal(&self, signature: &ValidatorSignature<TAddr>, block_id: &BlockId) -> bool {
+        let challenge = self.create_proposal_challenge(block_id);
+        self.verify_signature(signature, challenge)
+    }
+
+    fn create_new_view_challenge(
+        &self,
+        epoch: Epoch,
+        new_view: View,
+        high_qc_id: &QcId,
+        high_qc_height: NodeHeight,
+        last_vote_block_id: Option<&BlockId>,
+    ) -> FixedHash {
+        new_view_signature_hasher()
+            .chain(&epoch)
+            .chain(&new_view)
+            .chain(high_qc_id)
+            .chain(&high_qc_height)
+            .chain(&last_vote_block_id)
+            .result()
+    }
+
+    fn sign_new_view(
+        &self,
+        epoch: Epoch,
+        new_view: View,
+        high_qc_id: &QcId,
+        high_qc_height: NodeHeight,
+        last_vote_block_id: Option<&BlockId>,
+    ) -> ValidatorSignature<TAddr>
+    where
+        TAddr: NodeAddressable,
+    {
+        let challenge = self.create_new_view_challenge(epoch, new_view, high_qc_id, high_qc_height, last_vote_block_id);
+        ValidatorSignature::new(self.public_key().clone(), self.sign(challenge))
+    }
+
+    fn verify_new_view(
+        &self,
+        signature: &ValidatorSignature<TAddr>,
+        epoch: Epoch,
+        new_view: View,
+        high_qc_id: &QcId,
+        high_qc_height: NodeHeight,
+        last_vote_block_id: Option<&BlockId>,
+    ) -> bool {
+        let challenge = self.create_new_view_challenge(epoch, new_view, high_qc_id, high_qc_height, last_vote_block_id);
+        self.verify_signature(signature, challenge)
+    }
+
+    /// Verifies a NEWVIEW signature that is included in a timeout certificate for the given epoch and view
+    fn verify_signed_new_view(&self, epoch: Epoch, new_view: View, signed_new_view: &SignedNewView<TAddr>) -> bool {
+        self.verify_new_view(
+            &signed_new_view.signature,
+            epoch,
+            new_view,
+            &signed_new_view.high_qc_id,
+            signed_new_view.high_qc_height,
+            signed_new_view.last_vote_block_id.as_ref(),
+        )
+    }
 }
 
 pub trait VoteSignatureService<TAddr: NodeAddressable>: ValidatorSignatureService<TAddr> {
@@ -18,11 +106,7 @@ pub trait VoteSignatureService<TAddr: NodeAddressable>: ValidatorSignatureServic
         block_id: &BlockId,
         decision: &QuorumDecision,
     ) -> FixedHash {
-        vote_signature_hasher()
-            .chain(voter_leaf_hash)
-            .chain(block_id)
-            .chain(decision)
-            .result()
+        vote_challenge(voter_leaf_hash, block_id, decision)
     }
 
     fn sign_vote(
@@ -44,3 +128,13 @@ pub trait VoteSignatureService<TAddr: NodeAddressable>: ValidatorSignatureServic
         decision: &QuorumDecision,
     ) -> bool;
 }
+
+/// Returns the challenge that a validator signs when voting for a block. This allows the votes in a QC to be checked
+/// without a signature service.
+pub fn vote_challenge(voter_leaf_hash: &FixedHash, block_id: &BlockId, decision: &QuorumDecision) -> FixedHash {
+    vote_signature_hasher()
+        .chain(voter_leaf_hash)
+        .chain(block_id)
+        .chain(decision)
+        .result()
+}
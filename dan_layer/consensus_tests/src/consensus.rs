@@ -7,13 +7,16 @@
 //!
 //! Use `Test::builder().debug_sql("/tmp/test{}.db")...` to create a database file for each validator
 //! where {} is replaced with the node address.
+//!
+//! Tests use the SQLite state store unless `CONSENSUS_TEST_STATE_STORE=lmdb` is set, in which case every validator uses
+//! an LMDB state store in a temporary directory.
 
 use std::time::Duration;
 
-use tari_consensus::hotstuff::HotStuffError;
+use tari_consensus::{hotstuff::HotStuffError, traits::ValidatorSignatureService};
 use tari_dan_common_types::{Epoch, NodeHeight};
 use tari_dan_storage::{
-    consensus_models::{BlockId, Decision},
+    consensus_models::{Block, BlockId, Decision, HighQc},
     StateStore,
     StateStoreReadTransaction,
 };
@@ -24,9 +27,13 @@ use crate::support::{
     build_transaction_from,
     change_decision,
     logging::setup_logger,
+    ByzantineBehaviour,
+    FaultPlan,
+    LinkFault,
     Test,
     TestAddress,
     TestNetworkDestination,
+    TestVoteSignatureService,
 };
 
 // Although these tests will pass with a single thread, we enable multi threaded mode so that any unhandled race
@@ -404,3 +411,312 @@ async fn leader_failure_node_goes_down() {
     log::info!("total messages sent: {}", test.network().total_messages_sent());
     test.assert_clean_shutdown().await;
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn leader_failure_forged_timeout_certificate_is_rejected() {
+    setup_logger();
+    let failure_node = TestAddress::new("2");
+    let byzantine_node = TestAddress::new("3");
+    let mut test = Test::builder()
+        .with_test_timeout(Duration::from_secs(60))
+        .with_fault_plan(FaultPlan::new(0x7c).with_byzantine("3", ByzantineBehaviour::ForgesTimeoutCertificates))
+        .add_committee(0, vec!["1", "2", "3", "4", "5", "6", "7"])
+        .start()
+        .await;
+
+    for _ in 0..5 {
+        test.send_transaction_to_all(Decision::Commit, 1, 2).await;
+    }
+    test.wait_all_have_at_least_n_new_transactions_in_pool(5).await;
+    test.start_epoch(Epoch(0)).await;
+
+    loop {
+        let (_, committed_height) = test.on_block_committed().await;
+
+        if committed_height == NodeHeight(1) {
+            log::info!("😴 Node 2 goes offline");
+            test.network()
+                .go_offline(TestNetworkDestination::Address(failure_node.clone()))
+                .await;
+        }
+
+        if test
+            .validators()
+            .filter(|vn| vn.address != failure_node && vn.address != byzantine_node)
+            .all(|v| v.get_transaction_pool_count() == 0)
+        {
+            break;
+        }
+
+        if committed_height > NodeHeight(40) {
+            panic!("Not all transaction committed after {} blocks", committed_height);
+        }
+    }
+
+    let signing_service = TestVoteSignatureService::new(TestAddress::new("1"));
+    let mut num_timeout_certificates = 0;
+    for v in test
+        .validators()
+        .filter(|vn| vn.address != failure_node && vn.address != byzantine_node)
+    {
+        let blocks = v
+            .state_store
+            .with_read_tx(|tx| {
+                let count = Block::get_count(tx)?;
+                Block::get_paginated(tx, count as u64, 0, None)
+            })
+            .unwrap();
+        for block in blocks {
+            // Every proposal from the byzantine node carries a forged TC, so none of them may be accepted
+            assert_ne!(
+                *block.proposed_by(),
+                byzantine_node,
+                "{} accepted block {} with a forged timeout certificate",
+                v.address,
+                block
+            );
+            if let Some(tc) = block.timeout_certificate() {
+                num_timeout_certificates += 1;
+                let is_valid = |nv| signing_service.verify_signed_new_view(tc.epoch(), tc.new_view(), nv);
+                assert!(tc.new_views().iter().all(is_valid), "{} has an invalid signature", tc);
+            }
+        }
+    }
+    // The failed leader(s) were skipped using valid timeout certificates
+    assert!(num_timeout_certificates > 0);
+
+    test.assert_no_conflicting_commits_except(&[failure_node, byzantine_node])
+        .await;
+    log::info!("total messages sent: {}", test.network().total_messages_sent());
+    test.assert_clean_shutdown().await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn leader_failure_forged_high_qc_is_rejected() {
+    setup_logger();
+    let failure_node = TestAddress::new("2");
+    let byzantine_node = TestAddress::new("3");
+    let mut test = Test::builder()
+        .with_test_timeout(Duration::from_secs(60))
+        .with_fault_plan(FaultPlan::new(0x9c).with_byzantine("3", ByzantineBehaviour::ForgesHighQc))
+        .add_committee(0, vec!["1", "2", "3", "4", "5", "6", "7"])
+        .start()
+        .await;
+
+    for _ in 0..5 {
+        test.send_transaction_to_all(Decision::Commit, 1, 2).await;
+    }
+    test.wait_all_have_at_least_n_new_transactions_in_pool(5).await;
+    test.start_epoch(Epoch(0)).await;
+
+    loop {
+        let (_, committed_height) = test.on_block_committed().await;
+
+        if committed_height == NodeHeight(1) {
+            log::info!("😴 Node 2 goes offline");
+            test.network()
+                .go_offline(TestNetworkDestination::Address(failure_node.clone()))
+                .await;
+        }
+
+        if test
+            .validators()
+            .filter(|vn| vn.address != failure_node && vn.address != byzantine_node)
+            .all(|v| v.get_transaction_pool_count() == 0)
+        {
+            break;
+        }
+
+        if committed_height > NodeHeight(40) {
+            panic!("Not all transaction committed after {} blocks", committed_height);
+        }
+    }
+
+    for v in test
+        .validators()
+        .filter(|vn| vn.address != failure_node && vn.address != byzantine_node)
+    {
+        // Every NEWVIEW from the byzantine node carries a forged high QC, so none of them may be saved
+        let (high_qc, block) = v
+            .state_store
+            .with_read_tx(|tx| {
+                let high_qc = HighQc::get(tx)?;
+                let block = high_qc.get_block(tx)?;
+                Ok::<_, HotStuffError>((high_qc, block))
+            })
+            .unwrap();
+        assert_eq!(
+            high_qc.block_height(),
+            block.height(),
+            "{} accepted a forged high QC for block {}",
+            v.address,
+            block
+        );
+    }
+
+    test.assert_no_conflicting_commits_except(&[failure_node, byzantine_node])
+        .await;
+    log::info!("total messages sent: {}", test.network().total_messages_sent());
+    test.assert_clean_shutdown().await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn byzantine_equivocating_leader() {
+    setup_logger();
+    let byzantine_node = TestAddress::new("2");
+    let mut test = Test::builder()
+        .with_test_timeout(Duration::from_secs(60))
+        .with_fault_plan(FaultPlan::new(0x5eed).with_byzantine("2", ByzantineBehaviour::EquivocatingLeader))
+        .add_committee(0, vec!["1", "2", "3", "4"])
+        .start()
+        .await;
+
+    for _ in 0..5 {
+        test.send_transaction_to_all(Decision::Commit, 1, 2).await;
+    }
+    test.wait_all_have_at_least_n_new_transactions_in_pool(5).await;
+    test.start_epoch(Epoch(0)).await;
+
+    loop {
+        let (_, committed_height) = test.on_block_committed().await;
+
+        if test
+            .validators()
+            .filter(|vn| vn.address != byzantine_node)
+            .all(|v| v.get_transaction_pool_count() == 0)
+        {
+            break;
+        }
+
+        if committed_height > NodeHeight(40) {
+            panic!("Not all transaction committed after {} blocks", committed_height);
+        }
+    }
+
+    test.assert_no_conflicting_commits_except(&[]).await;
+    log::info!("total messages sent: {}", test.network().total_messages_sent());
+    test.assert_clean_shutdown().await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn byzantine_validator_votes_for_invalid_blocks_over_lossy_links() {
+    setup_logger();
+    let byzantine_node = TestAddress::new("4");
+    let lossy_link = LinkFault::lossy(0.1).with_jitter(Duration::from_millis(50));
+    let mut test = Test::builder()
+        .with_test_timeout(Duration::from_secs(60))
+        .with_fault_plan(
+            FaultPlan::new(0xbad)
+                .with_byzantine("4", ByzantineBehaviour::VoteForInvalidBlocks)
+                .with_link_fault(
+                    TestNetworkDestination::Address(byzantine_node.clone()),
+                    TestNetworkDestination::All,
+                    lossy_link,
+                )
+                .with_link_fault(
+                    TestNetworkDestination::All,
+                    TestNetworkDestination::Address(byzantine_node.clone()),
+                    lossy_link,
+                )
+                // Honest links deliver everything, but may reorder messages
+                .with_link_fault(
+                    TestNetworkDestination::All,
+                    TestNetworkDestination::All,
+                    LinkFault::delayed(Duration::from_millis(5)).with_jitter(Duration::from_millis(20)),
+                ),
+        )
+        .add_committee(0, vec!["1", "2", "3", "4"])
+        .start()
+        .await;
+
+    for _ in 0..5 {
+        test.send_transaction_to_all(Decision::Commit, 1, 2).await;
+    }
+    test.wait_all_have_at_least_n_new_transactions_in_pool(5).await;
+    test.start_epoch(Epoch(0)).await;
+
+    loop {
+        let (_, committed_height) = test.on_block_committed().await;
+
+        if test
+            .validators()
+            .filter(|vn| vn.address != byzantine_node)
+            .all(|v| v.get_transaction_pool_count() == 0)
+        {
+            break;
+        }
+
+        if committed_height > NodeHeight(40) {
+            panic!("Not all transaction committed after {} blocks", committed_height);
+        }
+    }
+
+    test.assert_all_validators_at_same_height_except(&[byzantine_node.clone()])
+        .await;
+    test.assert_no_conflicting_commits_except(&[]).await;
+    log::info!("total messages sent: {}", test.network().total_messages_sent());
+    test.assert_clean_shutdown().await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn network_partition_and_crashed_node_recover() {
+    setup_logger();
+    let mut test = Test::builder()
+        .with_test_timeout(Duration::from_secs(60))
+        .with_fault_plan(FaultPlan::new(42))
+        .add_committee(0, vec!["1", "2", "3", "4"])
+        .start()
+        .await;
+
+    let crashed_node = TestAddress::new("3");
+    let isolated_node = TestAddress::new("4");
+
+    for _ in 0..10 {
+        test.send_transaction_to_all(Decision::Commit, 1, 2).await;
+    }
+    test.wait_all_have_at_least_n_new_transactions_in_pool(10).await;
+    test.start_epoch(Epoch(0)).await;
+
+    let mut has_healed = false;
+    loop {
+        let (_, committed_height) = test.on_block_committed().await;
+
+        if committed_height == NodeHeight(1) {
+            log::info!("🚧 Node 4 is partitioned from the rest of the committee");
+            test.network()
+                .partition(vec![isolated_node.clone()], vec![
+                    TestAddress::new("1"),
+                    TestAddress::new("2"),
+                    crashed_node.clone(),
+                ])
+                .await;
+        }
+
+        if committed_height == NodeHeight(3) && !has_healed {
+            // Heal the partition before taking another node down, otherwise the remaining two nodes cannot reach quorum
+            has_healed = true;
+            test.network().heal_partitions().await;
+            test.crash(&crashed_node).await;
+        }
+
+        if committed_height == NodeHeight(6) {
+            test.restart(&crashed_node).await;
+        }
+
+        if test
+            .validators()
+            .filter(|vn| vn.address != crashed_node && vn.address != isolated_node)
+            .all(|v| v.get_transaction_pool_count() == 0)
+        {
+            break;
+        }
+
+        if committed_height > NodeHeight(40) {
+            panic!("Not all transaction committed after {} blocks", committed_height);
+        }
+    }
+
+    test.assert_no_conflicting_commits_except(&[]).await;
+    log::info!("total messages sent: {}", test.network().total_messages_sent());
+    test.assert_clean_shutdown().await;
+}
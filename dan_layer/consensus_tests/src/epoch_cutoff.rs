@@ -0,0 +1,139 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use std::time::Duration;
+
+use tari_consensus::hotstuff::{is_finalize_period_over, next_epoch_cutoff, EPOCH_CUTOFF_FINALIZE_BLOCKS};
+use tari_dan_common_types::{Epoch, NodeHeight, View};
+use tari_dan_storage::{
+    consensus_models::{Block, Decision, TransactionRecord},
+    StateStore,
+};
+
+use crate::support::{build_transaction, logging::setup_logger, Test, TestAddress, TestNetworkDestination};
+
+fn block(height: u64, epoch: Epoch, epoch_cutoff: Option<NodeHeight>) -> Block<TestAddress> {
+    let zero_block = Block::<TestAddress>::zero_block();
+    Block::new(
+        *zero_block.id(),
+        zero_block.justify().clone(),
+        None,
+        NodeHeight(height),
+        View(height),
+        epoch,
+        epoch_cutoff,
+        TestAddress::new("leader"),
+        Default::default(),
+        0,
+    )
+}
+
+#[test]
+fn it_starts_the_cutoff_once_the_epoch_is_ending() {
+    let parent = block(9, Epoch(1), None);
+    assert_eq!(next_epoch_cutoff(&parent, Epoch(1), NodeHeight(10), None), None);
+    assert_eq!(
+        next_epoch_cutoff(&parent, Epoch(1), NodeHeight(10), Some(Epoch(0))),
+        None
+    );
+    assert_eq!(
+        next_epoch_cutoff(&parent, Epoch(1), NodeHeight(10), Some(Epoch(1))),
+        Some(NodeHeight(10))
+    );
+}
+
+#[test]
+fn it_inherits_the_cutoff_from_a_parent_in_the_same_epoch() {
+    let parent = block(12, Epoch(1), Some(NodeHeight(10)));
+    // The cutoff is inherited even if this node has not observed the end of the epoch
+    assert_eq!(
+        next_epoch_cutoff(&parent, Epoch(1), NodeHeight(13), None),
+        Some(NodeHeight(10))
+    );
+    assert_eq!(
+        next_epoch_cutoff(&parent, Epoch(1), NodeHeight(13), Some(Epoch(1))),
+        Some(NodeHeight(10))
+    );
+    assert_eq!(next_epoch_cutoff(&parent, Epoch(2), NodeHeight(13), None), None);
+}
+
+#[test]
+fn it_ends_the_finalize_period_after_the_cutoff() {
+    let cutoff = Some(NodeHeight(10));
+    let end = 10 + EPOCH_CUTOFF_FINALIZE_BLOCKS;
+    assert!(!is_finalize_period_over(None, NodeHeight(100)));
+    assert!(!is_finalize_period_over(cutoff, NodeHeight(end - 1)));
+    assert!(is_finalize_period_over(cutoff, NodeHeight(end)));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn all_validators_agree_on_aborts_once_the_epoch_ends() {
+    setup_logger();
+    let mut test = Test::builder()
+        .with_test_timeout(Duration::from_secs(60))
+        .add_committee(0, vec!["1", "2", "3", "4"])
+        .start()
+        .await;
+
+    let transactions = (0..5)
+        .map(|_| build_transaction(Decision::Commit, 1, 5, 1))
+        .collect::<Vec<_>>();
+    for transaction in &transactions {
+        test.network()
+            .send_transaction(TestNetworkDestination::All, transaction.clone())
+            .await;
+    }
+    test.wait_until_new_pool_count(transactions.len()).await;
+    test.start_epoch(Epoch(0)).await;
+
+    // End the epoch while the transactions are still in flight
+    test.on_block_committed().await;
+    test.end_epoch(Epoch(0), 5);
+
+    loop {
+        let (_, height) = test.on_block_committed().await;
+        if test.is_transaction_pool_empty() {
+            break;
+        }
+        if height > NodeHeight(30 + EPOCH_CUTOFF_FINALIZE_BLOCKS) {
+            panic!("Not all transactions were finalized after {} blocks", height);
+        }
+    }
+
+    test.with_all_validators(|v| {
+        let leaf = v.get_leaf_block();
+        let leaf_block = v.state_store.with_read_tx(|tx| Block::get(tx, &leaf.block_id)).unwrap();
+        assert!(
+            leaf_block.epoch_cutoff().is_some(),
+            "Validator {} did not adopt the epoch cutoff",
+            v.address
+        );
+    });
+
+    for transaction in &transactions {
+        let mut decisions = test.validators().map(|v| {
+            let decision = v
+                .state_store
+                .with_read_tx(|tx| TransactionRecord::get(tx, transaction.id()))
+                .unwrap()
+                .final_decision()
+                .unwrap_or_else(|| panic!("Validator {} has no decision for {}", v.address, transaction.id()));
+            (v.address.clone(), decision)
+        });
+        let (_, expected) = decisions.next().unwrap();
+        for (address, decision) in decisions {
+            assert_eq!(
+                decision,
+                expected,
+                "Validator {} decided {} for transaction {} but another validator decided {}",
+                address,
+                decision,
+                transaction.id(),
+                expected
+            );
+        }
+    }
+
+    test.assert_no_conflicting_commits_except(&[]).await;
+    test.assert_clean_shutdown().await;
+}
@@ -3,4 +3,10 @@
 #[cfg(test)]
 mod consensus;
 #[cfg(test)]
+mod epoch_cutoff;
+#[cfg(test)]
+mod participation;
+#[cfg(test)]
+mod state_recovery;
+#[cfg(test)]
 mod support;
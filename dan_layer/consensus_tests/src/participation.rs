@@ -0,0 +1,107 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use tari_consensus::hotstuff::ParticipationTracker;
+use tari_dan_common_types::{Epoch, NodeHeight};
+use tari_dan_storage::consensus_models::Decision;
+
+use crate::support::{logging::setup_logger, Test, TestAddress};
+
+#[test]
+fn it_records_votes_and_proposals_per_member() {
+    let tracker = ParticipationTracker::new();
+    let member = TestAddress::new("1");
+    let other = TestAddress::new("2");
+
+    tracker.record_vote(&member, NodeHeight(1));
+    tracker.record_vote(&member, NodeHeight(2));
+    tracker.record_proposal(&member, NodeHeight(3));
+
+    let participation = tracker.get(&member);
+    assert_eq!(participation.votes_received, 2);
+    assert_eq!(participation.last_vote.unwrap().block_height, NodeHeight(2));
+    assert_eq!(participation.proposals_received, 1);
+    assert_eq!(participation.last_proposal.unwrap().block_height, NodeHeight(3));
+    assert_eq!(participation.proposals_missed, 0);
+    assert!(participation.last_missed_proposal.is_none());
+
+    let participation = tracker.get(&other);
+    assert_eq!(participation.votes_received, 0);
+    assert!(participation.last_vote.is_none());
+    assert!(participation.last_proposal.is_none());
+}
+
+#[test]
+fn it_records_missed_proposals() {
+    let tracker = ParticipationTracker::new();
+    let member = TestAddress::new("1");
+
+    tracker.record_missed_proposal(&member);
+    tracker.record_missed_proposal(&member);
+
+    let participation = tracker.get(&member);
+    assert_eq!(participation.proposals_missed, 2);
+    assert!(participation.last_missed_proposal.is_some());
+    assert_eq!(participation.proposals_received, 0);
+}
+
+#[test]
+fn it_shares_participation_between_clones() {
+    let tracker = ParticipationTracker::new();
+    let member = TestAddress::new("1");
+
+    tracker.clone().record_vote(&member, NodeHeight(1));
+    assert_eq!(tracker.get(&member).votes_received, 1);
+}
+
+#[test]
+fn it_forgets_participation_when_cleared() {
+    let tracker = ParticipationTracker::new();
+    let member = TestAddress::new("1");
+
+    tracker.record_vote(&member, NodeHeight(1));
+    tracker.record_missed_proposal(&member);
+    tracker.clone().clear();
+
+    let participation = tracker.get(&member);
+    assert_eq!(participation.votes_received, 0);
+    assert_eq!(participation.proposals_missed, 0);
+    assert!(participation.last_vote.is_none());
+    assert!(participation.last_missed_proposal.is_none());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn it_records_valid_proposals_from_committee_members() {
+    setup_logger();
+    let mut test = Test::builder().add_committee(0, vec!["1", "2", "3", "4"]).start().await;
+    test.send_transaction_to_all(Decision::Commit, 1, 1).await;
+    test.wait_until_new_pool_count(1).await;
+    test.start_epoch(Epoch(0)).await;
+
+    loop {
+        test.on_block_committed().await;
+
+        if test.is_transaction_pool_empty() {
+            break;
+        }
+        let leaf = test.get_validator(&TestAddress::new("1")).get_leaf_block();
+        if leaf.height >= NodeHeight(20) {
+            panic!("Not all transaction committed after {} blocks", leaf.height);
+        }
+    }
+
+    let members = ["1", "2", "3", "4"].map(TestAddress::new);
+    test.with_all_validators(|v| {
+        let num_proposals = members
+            .iter()
+            .map(|m| v.participation.get(m).proposals_received)
+            .sum::<u64>();
+        assert!(
+            num_proposals > 0,
+            "Validator {} did not record any proposals",
+            v.address
+        );
+    });
+
+    test.assert_clean_shutdown().await;
+}
@@ -0,0 +1,134 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use tari_common_types::types::{PrivateKey, PublicKey};
+use tari_consensus::hotstuff::check_and_recover;
+use tari_dan_common_types::{Epoch, NodeHeight, ShardId, View};
+use tari_dan_storage::{
+    consensus_models::{
+        Block,
+        Command,
+        Decision,
+        ExecutedTransaction,
+        LastExecuted,
+        QuorumCertificate,
+        QuorumDecision,
+    },
+    StateStore,
+};
+use tari_engine_types::{
+    commit_result::TransactionResult,
+    fee_claim::{FeeClaim, FeeClaimAddress},
+    substate::{Substate, SubstateAddress, SubstateDiff},
+};
+use tari_state_store_backend::AnyStateStore;
+use tari_transaction::Transaction;
+
+use crate::support::{build_transaction_with_result, NoopStateManager, TestAddress};
+
+/// Sets up the zero block as the worker does on first start and a locked block on top of it containing a transaction
+/// that creates a substate. The block is neither committed nor executed.
+fn setup() -> (AnyStateStore<TestAddress>, Block<TestAddress>, ExecutedTransaction) {
+    let store = AnyStateStore::<TestAddress>::connect_sqlite(":memory:").unwrap();
+
+    let address = SubstateAddress::FeeClaim(FeeClaimAddress::from_addr(0, b"validator"));
+    let mut diff = SubstateDiff::new();
+    diff.up(
+        address.clone(),
+        Substate::new(0, FeeClaim {
+            epoch: 0,
+            validator_public_key: PublicKey::default(),
+            amount: 100u64.try_into().unwrap(),
+        }),
+    );
+    let executed = build_transaction_with_result(
+        Transaction::builder().sign(&PrivateKey::default()).build(),
+        TransactionResult::Accept(diff),
+        100,
+        vec![ShardId::from_address(&address, 0)],
+    );
+
+    let zero_block = Block::<TestAddress>::zero_block();
+    let block = Block::new(
+        *zero_block.id(),
+        zero_block.justify().clone(),
+        None,
+        NodeHeight(1),
+        View(1),
+        Epoch(0),
+        None,
+        TestAddress::new("leader"),
+        [Command::Accept(executed.to_atom())].into_iter().collect(),
+        0,
+    );
+    let qc = QuorumCertificate::new(
+        *block.id(),
+        block.height(),
+        block.epoch(),
+        vec![],
+        zero_block.justify().merged_proof().clone(),
+        vec![],
+        QuorumDecision::Accept,
+    );
+
+    store
+        .with_write_tx(|tx| {
+            zero_block.justify().insert(tx)?;
+            zero_block.insert(tx)?;
+            zero_block.as_locked_block().set(tx)?;
+            zero_block.as_leaf_block().set(tx)?;
+            zero_block.as_last_executed().set(tx)?;
+            zero_block.as_last_voted().set(tx)?;
+            zero_block.justify().as_high_qc().set(tx)?;
+            zero_block.commit(tx)?;
+
+            executed.insert(tx)?;
+            block.insert(tx)?;
+            qc.insert(tx)?;
+            qc.as_high_qc().set(tx)?;
+            block.as_leaf_block().set(tx)?;
+            block.as_locked_block().set(tx)
+        })
+        .unwrap();
+
+    (store, block, executed)
+}
+
+#[test]
+fn it_reapplies_a_committed_block_when_the_commit_was_interrupted() {
+    let (store, block, executed) = setup();
+    // The block was marked as committed but the node stopped before its substate changes were applied
+    store.with_write_tx(|tx| block.commit(tx)).unwrap();
+
+    let state_manager = NoopStateManager::new();
+    let report = check_and_recover(&store, &state_manager).unwrap();
+    assert_eq!(report.blocks_reapplied, 1);
+    assert_eq!(report.blocks_marked_executed, 1);
+    assert!(state_manager.is_committed());
+
+    let mut tx = store.create_read_tx().unwrap();
+    let last_executed = LastExecuted::get(&mut tx).unwrap();
+    assert_eq!(last_executed.block_id, *block.id());
+    assert_eq!(last_executed.height, block.height());
+    let executed = ExecutedTransaction::get(&mut tx, executed.id()).unwrap();
+    assert_eq!(executed.final_decision(), Some(Decision::Commit));
+}
+
+#[test]
+fn it_commits_the_last_executed_block_when_it_was_not_marked_as_committed() {
+    let (store, block, executed) = setup();
+    // The last executed pointer was moved but the block was not committed
+    store.with_write_tx(|tx| block.as_last_executed().set(tx)).unwrap();
+
+    let state_manager = NoopStateManager::new();
+    let report = check_and_recover(&store, &state_manager).unwrap();
+    assert_eq!(report.blocks_reapplied, 1);
+    assert_eq!(report.blocks_marked_executed, 0);
+    assert!(state_manager.is_committed());
+
+    let mut tx = store.create_read_tx().unwrap();
+    let block = Block::get(&mut tx, block.id()).unwrap();
+    assert!(block.is_committed());
+    let executed = ExecutedTransaction::get(&mut tx, executed.id()).unwrap();
+    assert_eq!(executed.final_decision(), Some(Decision::Commit));
+}
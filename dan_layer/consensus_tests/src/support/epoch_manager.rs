@@ -51,6 +51,15 @@ impl TestEpochManager {
         self
     }
 
+    pub fn set_epoch_ending(&self, epoch: Epoch, blocks_remaining: u64) -> &Self {
+        let _ = self.tx_epoch_events.send(EpochManagerEvent::EpochEnding {
+            epoch,
+            blocks_remaining,
+        });
+
+        self
+    }
+
     pub async fn state_lock(&self) -> MutexGuard<TestEpochManagerState> {
         self.inner.lock().await
     }
@@ -0,0 +1,383 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+//! Fault injection for the simulated network.
+//!
+//! A [FaultPlan] describes the adversarial behaviour for a test run: faulty links (dropped, delayed and reordered
+//! messages) and byzantine validators. Every random decision is made from an RNG that is seeded from the plan's seed
+//! and the link it applies to, so a failing run can be replayed by reusing the seed that is logged when the network
+//! starts. Partitions and crashes are controlled at runtime through the [TestNetwork](super::TestNetwork).
+
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    time::Duration,
+};
+
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+use tari_consensus::{
+    messages::{HotstuffMessage, NewViewMessage, ProposalMessage},
+    traits::ValidatorSignatureService,
+};
+use tari_crypto::hash_domain;
+use tari_dan_common_types::{committee::Committee, hasher::tari_hasher, shard_bucket::ShardBucket, NodeHeight};
+use tari_dan_storage::consensus_models::{
+    Block,
+    BlockId,
+    QuorumCertificate,
+    SignedNewView,
+    TimeoutCertificate,
+    ValidatorSignature,
+};
+
+use crate::support::{address::TestAddress, TestNetworkDestination, TestVoteSignatureService};
+
+hash_domain!(FaultPlanHashDomain, "com.tari.dan.consensus_tests.faults", 0);
+
+#[derive(Debug, Clone)]
+pub struct FaultPlan {
+    seed: u64,
+    link_faults: Vec<(TestNetworkDestination, TestNetworkDestination, LinkFault)>,
+    byzantine: HashMap<TestAddress, ByzantineBehaviour>,
+}
+
+impl FaultPlan {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            link_faults: Vec::new(),
+            byzantine: HashMap::new(),
+        }
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Applies the fault to all messages sent from `from` to `to`. If more than one fault matches a link, the first one
+    /// that was added is used.
+    pub fn with_link_fault(
+        mut self,
+        from: TestNetworkDestination,
+        to: TestNetworkDestination,
+        fault: LinkFault,
+    ) -> Self {
+        self.link_faults.push((from, to, fault));
+        self
+    }
+
+    pub fn with_byzantine<T: Into<String>>(mut self, address: T, behaviour: ByzantineBehaviour) -> Self {
+        self.byzantine.insert(TestAddress::new(address), behaviour);
+        self
+    }
+
+    pub fn is_byzantine(&self, address: &TestAddress) -> bool {
+        self.byzantine.contains_key(address)
+    }
+
+    fn link_fault(
+        &self,
+        from: &TestAddress,
+        from_bucket: ShardBucket,
+        to: &TestAddress,
+        to_bucket: ShardBucket,
+    ) -> Option<&LinkFault> {
+        self.link_faults
+            .iter()
+            .find(|(f, t, _)| f.is_for(from, from_bucket) && t.is_for(to, to_bucket))
+            .map(|(_, _, fault)| fault)
+    }
+}
+
+impl Default for FaultPlan {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LinkFault {
+    /// Probability in the range [0, 1] that a message on the link is dropped
+    pub drop_probability: f64,
+    /// Fixed delay applied to every delivered message
+    pub delay: Duration,
+    /// Random additional delay in the range [0, jitter). Messages on the same link may overtake each other.
+    pub jitter: Duration,
+}
+
+impl LinkFault {
+    pub fn lossy(drop_probability: f64) -> Self {
+        Self {
+            drop_probability,
+            ..Default::default()
+        }
+    }
+
+    pub fn delayed(delay: Duration) -> Self {
+        Self {
+            delay,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_jitter(mut self, jitter: Duration) -> Self {
+        self.jitter = jitter;
+        self
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ByzantineBehaviour {
+    /// When leader, sends a conflicting proposal for the same view to a random subset of the committee.
+    EquivocatingLeader,
+    /// Every vote sent by the validator is for a block that does not exist.
+    VoteForInvalidBlocks,
+    /// When leader, proposes blocks with a timeout certificate that claims NEWVIEWs from the whole committee. Only the
+    /// validator's own NEWVIEW signature is valid.
+    ForgesTimeoutCertificates,
+    /// Every NEWVIEW sent by the validator carries a copy of its high QC that claims a much greater block height.
+    ForgesHighQc,
+}
+
+/// Runtime faults that are set by the test while the network is running.
+#[derive(Debug, Default)]
+pub struct NetworkFaults {
+    partitions: Vec<(HashSet<TestAddress>, HashSet<TestAddress>)>,
+    crashed: HashSet<TestAddress>,
+}
+
+impl NetworkFaults {
+    pub fn partition(&mut self, side_a: HashSet<TestAddress>, side_b: HashSet<TestAddress>) {
+        self.partitions.push((side_a, side_b));
+    }
+
+    pub fn heal_partitions(&mut self) {
+        self.partitions.clear();
+    }
+
+    pub fn crash(&mut self, address: TestAddress) {
+        self.crashed.insert(address);
+    }
+
+    pub fn restart(&mut self, address: &TestAddress) {
+        self.crashed.remove(address);
+    }
+
+    /// Returns true if a message from `from` can reach `to`. Crashed nodes neither send nor receive messages.
+    pub fn can_reach(&self, from: &TestAddress, to: &TestAddress) -> bool {
+        if self.crashed.contains(from) || self.crashed.contains(to) {
+            return false;
+        }
+        !self
+            .partitions
+            .iter()
+            .any(|(a, b)| (a.contains(from) && b.contains(to)) || (b.contains(from) && a.contains(to)))
+    }
+}
+
+pub struct FaultInjector {
+    plan: FaultPlan,
+    link_rngs: HashMap<(TestAddress, TestAddress), StdRng>,
+}
+
+impl FaultInjector {
+    pub fn new(plan: FaultPlan) -> Self {
+        Self {
+            plan,
+            link_rngs: HashMap::new(),
+        }
+    }
+
+    /// Returns the message that `to` receives and the delay before it is delivered, or None if the message is dropped.
+    pub fn apply(
+        &mut self,
+        from: &TestAddress,
+        from_bucket: ShardBucket,
+        to: &TestAddress,
+        to_bucket: ShardBucket,
+        msg: &HotstuffMessage<TestAddress>,
+        equivocation: Option<&HotstuffMessage<TestAddress>>,
+    ) -> Option<(Duration, HotstuffMessage<TestAddress>)> {
+        // Validators always receive their own messages
+        if from == to {
+            return Some((Duration::ZERO, msg.clone()));
+        }
+
+        let fault = self.plan.link_fault(from, from_bucket, to, to_bucket).copied();
+        let behaviour = self.plan.byzantine.get(from).copied();
+        if fault.is_none() && behaviour.is_none() {
+            return Some((Duration::ZERO, msg.clone()));
+        }
+
+        let rng = self.link_rng(from, to);
+        let mut msg = match (behaviour, equivocation) {
+            (Some(ByzantineBehaviour::EquivocatingLeader), Some(conflicting)) if rng.gen_bool(0.5) => {
+                log::info!("😈 {} sends conflicting {} to {}", from, conflicting, to);
+                conflicting.clone()
+            },
+            _ => msg.clone(),
+        };
+
+        if let (Some(ByzantineBehaviour::VoteForInvalidBlocks), HotstuffMessage::Vote(vote)) = (behaviour, &mut msg) {
+            vote.block_id = BlockId::new(rng.gen::<[u8; 32]>());
+            log::info!("😈 {} votes for invalid block {} (sent to {})", from, vote.block_id, to);
+        }
+
+        if let (Some(ByzantineBehaviour::ForgesHighQc), HotstuffMessage::NewView(new_view)) = (behaviour, &mut msg) {
+            forge_high_qc(from, new_view);
+            log::info!("😈 {} sends NEWVIEW with forged {} to {}", from, new_view.high_qc, to);
+        }
+
+        let Some(fault) = fault else {
+            return Some((Duration::ZERO, msg));
+        };
+
+        if fault.drop_probability > 0.0 && rng.gen_bool(fault.drop_probability.min(1.0)) {
+            log::info!("🕳️ Dropped {} from {} to {}", msg, from, to);
+            return None;
+        }
+
+        let mut delay = fault.delay;
+        if !fault.jitter.is_zero() {
+            delay += fault.jitter.mul_f64(rng.gen::<f64>());
+        }
+        Some((delay, msg))
+    }
+
+    /// Returns a conflicting proposal if `from` is an equivocating leader and `msg` is a proposal.
+    pub fn equivocate(
+        &self,
+        from: &TestAddress,
+        msg: &HotstuffMessage<TestAddress>,
+    ) -> Option<HotstuffMessage<TestAddress>> {
+        if self.plan.byzantine.get(from) != Some(&ByzantineBehaviour::EquivocatingLeader) {
+            return None;
+        }
+        let HotstuffMessage::Proposal(ProposalMessage { block }) = msg else {
+            return None;
+        };
+
+        // Prefer a conflicting block that is valid (an empty block extending the same parent). If the proposal is
+        // already empty, the only way to get a different block id is to claim a different leader fee, which honest
+        // validators will refuse to vote for.
+        let total_leader_fee = if block.commands().is_empty() {
+            block.total_leader_fee() + 1
+        } else {
+            0
+        };
+        let mut conflicting = Block::new(
+            *block.parent(),
+            block.justify().clone(),
+            block.timeout_certificate().cloned(),
+            block.height(),
+            block.view(),
+            block.epoch(),
+            block.epoch_cutoff(),
+            block.proposed_by().clone(),
+            BTreeSet::new(),
+            total_leader_fee,
+        );
+        // The byzantine leader signs both blocks
+        conflicting.set_signature(TestVoteSignatureService::new(from.clone()).sign_proposal(conflicting.id()));
+        Some(HotstuffMessage::Proposal(ProposalMessage { block: conflicting }))
+    }
+
+    /// Replaces the timeout certificate of a proposal if `from` forges timeout certificates. The proposal is signed
+    /// again, so that the forged certificate is the only thing that is wrong with it.
+    pub fn forge_timeout_certificate(
+        &self,
+        from: &TestAddress,
+        committee: &Committee<TestAddress>,
+        msg: HotstuffMessage<TestAddress>,
+    ) -> HotstuffMessage<TestAddress> {
+        if self.plan.byzantine.get(from) != Some(&ByzantineBehaviour::ForgesTimeoutCertificates) {
+            return msg;
+        }
+        let block = match msg {
+            HotstuffMessage::Proposal(ProposalMessage { block }) => block,
+            msg => return msg,
+        };
+
+        let signing_service = TestVoteSignatureService::new(from.clone());
+        let justify = block.justify();
+        let challenge = signing_service.create_new_view_challenge(
+            block.epoch(),
+            block.view(),
+            justify.id(),
+            justify.block_height(),
+            None,
+        );
+        let new_views = committee
+            .iter()
+            .map(|member| SignedNewView {
+                high_qc_id: *justify.id(),
+                high_qc_height: justify.block_height(),
+                last_vote_block_id: None,
+                signature: ValidatorSignature::new(member.clone(), signing_service.sign(challenge)),
+            })
+            .collect();
+        let timeout_certificate = TimeoutCertificate::new(block.epoch(), block.view(), new_views);
+        let mut forged = Block::new(
+            *block.parent(),
+            block.justify().clone(),
+            Some(timeout_certificate),
+            block.height(),
+            block.view(),
+            block.epoch(),
+            block.epoch_cutoff(),
+            block.proposed_by().clone(),
+            block.commands().clone(),
+            block.total_leader_fee(),
+        );
+        forged.set_signature(signing_service.sign_proposal(forged.id()));
+        log::info!("😈 {} proposes {} with a forged timeout certificate", from, forged);
+        HotstuffMessage::Proposal(ProposalMessage { block: forged })
+    }
+
+    /// Shuffles the recipients of a broadcast so that delivery order depends on the seed.
+    pub fn shuffle_recipients(&mut self, from: &TestAddress, recipients: &mut [TestAddress]) {
+        if self.plan.link_faults.is_empty() && !self.plan.is_byzantine(from) {
+            return;
+        }
+        let rng = self.link_rng(from, from);
+        recipients.shuffle(rng);
+    }
+
+    fn link_rng(&mut self, from: &TestAddress, to: &TestAddress) -> &mut StdRng {
+        let seed = self.plan.seed;
+        self.link_rngs.entry((from.clone(), to.clone())).or_insert_with(|| {
+            // The std hashers are not guaranteed to be stable across Rust versions, which would break replaying a seed
+            let hash = tari_hasher::<FaultPlanHashDomain>("link_rng")
+                .chain(&seed)
+                .chain(from)
+                .chain(to)
+                .result();
+            let mut rng_seed = [0u8; 32];
+            rng_seed.copy_from_slice(hash.as_slice());
+            StdRng::from_seed(rng_seed)
+        })
+    }
+}
+
+/// Replaces the high QC of a NEWVIEW with a copy that claims a much greater block height. The vote signatures do not
+/// cover the height, so they are still valid. The NEWVIEW is signed again so that the forged QC is the only thing that
+/// is wrong with it.
+fn forge_high_qc(from: &TestAddress, new_view: &mut NewViewMessage<TestAddress>) {
+    let qc = &new_view.high_qc;
+    let forged = QuorumCertificate::new(
+        *qc.block_id(),
+        qc.block_height() + NodeHeight(100),
+        qc.epoch(),
+        qc.signatures().to_vec(),
+        qc.merged_proof().clone(),
+        qc.leaf_hashes().to_vec(),
+        qc.decision(),
+    );
+    new_view.signature = TestVoteSignatureService::new(from.clone()).sign_new_view(
+        new_view.epoch,
+        new_view.new_view,
+        forged.id(),
+        forged.block_height(),
+        new_view.last_vote.as_ref().map(|vote| &vote.block_id),
+    );
+    new_view.high_qc = forged;
+}
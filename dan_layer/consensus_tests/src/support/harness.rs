@@ -2,7 +2,7 @@
 //   SPDX-License-Identifier: BSD-3-Clause
 
 use std::{
-    collections::{hash_map, HashMap},
+    collections::{hash_map, HashMap, HashSet},
     time::Duration,
 };
 
@@ -16,12 +16,14 @@ use tari_dan_storage::{
 };
 use tari_epoch_manager::EpochManagerReader;
 use tari_shutdown::{Shutdown, ShutdownSignal};
+use tari_state_store_backend::StateStoreBackend;
 use tari_transaction::TransactionId;
 use tokio::{sync::broadcast, task};
 
 use crate::support::{
     address::TestAddress,
     epoch_manager::TestEpochManager,
+    faults::FaultPlan,
     network::{spawn_network, TestNetwork, TestNetworkDestination},
     transaction::build_transaction,
     validator::Validator,
@@ -31,6 +33,7 @@ use crate::support::{
 
 pub struct Test {
     validators: HashMap<TestAddress, Validator>,
+    crashed: HashSet<TestAddress>,
     network: TestNetwork,
     _leader_strategy: RoundRobinLeaderStrategy,
     epoch_manager: TestEpochManager,
@@ -62,8 +65,10 @@ impl Test {
     }
 
     pub async fn on_hotstuff_event(&mut self) -> HotstuffEvent {
+        let crashed = &self.crashed;
         self.validators
             .values_mut()
+            .filter(|v| !crashed.contains(&v.address))
             .map(|v| v.events.recv())
             .collect::<FuturesUnordered<_>>()
             .next()
@@ -84,8 +89,8 @@ impl Test {
             match event {
                 HotstuffEvent::BlockCommitted { block_id, height } => return (block_id, height),
                 HotstuffEvent::Failure { message } => panic!("Consensus failure: {}", message),
-                HotstuffEvent::LeaderTimeout { new_height } => {
-                    log::info!("Leader timeout. New height {new_height}");
+                HotstuffEvent::LeaderTimeout { new_view } => {
+                    log::info!("Leader timeout. New {new_view}");
                     continue;
                 },
             }
@@ -96,6 +101,35 @@ impl Test {
         &mut self.network
     }
 
+    /// Simulates a crash: the validator's consensus worker is stopped without a clean shutdown and it neither sends
+    /// nor receives messages until it is restarted.
+    pub async fn crash(&mut self, address: &TestAddress) {
+        self.network.crash(address.clone()).await;
+        self.get_validator(address).handle.abort();
+        self.crashed.insert(address.clone());
+    }
+
+    /// Starts a new consensus worker for a crashed validator from the validator's database.
+    pub async fn restart(&mut self, address: &TestAddress) {
+        assert!(self.crashed.remove(address), "Validator {} has not crashed", address);
+        let crashed = self
+            .validators
+            .remove(address)
+            .unwrap_or_else(|| panic!("No validator with address {}", address));
+        let (channels, mut validator) = Validator::builder()
+            .with_address(crashed.address.clone())
+            .with_shard(crashed.shard)
+            .with_bucket(crashed.bucket)
+            .with_epoch_manager(crashed.epoch_manager.clone())
+            .with_leader_strategy(crashed.leader_strategy)
+            .with_state_store(crashed.state_store.clone())
+            .with_state_manager(crashed.state_manager.clone())
+            .spawn(self.shutdown.to_signal());
+        validator._state_dir = crashed._state_dir;
+        self.validators.insert(address.clone(), validator);
+        self.network.restart(channels).await;
+    }
+
     pub async fn start_epoch(&mut self, epoch: Epoch) {
         for validator in self.validators.values() {
             // Fire off initial epoch change event so that the pacemaker starts
@@ -104,6 +138,12 @@ impl Test {
         self.network.start();
     }
 
+    /// Tells every validator that `epoch` ends in `blocks_remaining` base layer blocks, as the base layer scanner
+    /// would.
+    pub fn end_epoch(&self, epoch: Epoch, blocks_remaining: u64) {
+        self.epoch_manager.set_epoch_ending(epoch, blocks_remaining);
+    }
+
     #[allow(dead_code)]
     pub fn get_validator_mut(&mut self, addr: &TestAddress) -> &mut Validator {
         self.validators.get_mut(addr).unwrap()
@@ -259,6 +299,50 @@ impl Test {
         }
     }
 
+    /// Asserts that no two validators in the same committee (excluding `except`) have committed different blocks at
+    /// the same height.
+    pub async fn assert_no_conflicting_commits_except(&self, except: &[TestAddress]) {
+        let committees = self.epoch_manager.all_committees().await;
+        for committee in committees.values() {
+            let mut committed_by_height = HashMap::<NodeHeight, (TestAddress, BlockId)>::new();
+            let validators = self
+                .validators
+                .values()
+                .filter(|vn| committee.members.contains(&vn.address))
+                .filter(|vn| !except.contains(&vn.address));
+            for v in validators {
+                let blocks = v
+                    .state_store
+                    .with_read_tx(|tx| {
+                        let count = Block::get_count(tx)?;
+                        Block::get_paginated(tx, count as u64, 0, None)
+                    })
+                    .unwrap();
+                for block in blocks.into_iter().filter(|b| b.is_committed()) {
+                    match committed_by_height.entry(block.height()) {
+                        hash_map::Entry::Occupied(entry) => {
+                            let (addr, block_id) = entry.get();
+                            assert_eq!(
+                                block_id,
+                                block.id(),
+                                "Safety violation: validator {} committed block {} at height {} but validator {} \
+                                 committed block {}",
+                                addr,
+                                block_id,
+                                block.height(),
+                                v.address,
+                                block.id()
+                            );
+                        },
+                        hash_map::Entry::Vacant(entry) => {
+                            entry.insert((v.address.clone(), *block.id()));
+                        },
+                    }
+                }
+            }
+        }
+    }
+
     pub fn assert_all_validators_committed(&self) {
         assert!(self.validators.values().all(|v| v.state_manager().is_committed()));
     }
@@ -270,6 +354,9 @@ impl Test {
     pub async fn assert_clean_shutdown(mut self) {
         self.shutdown.trigger();
         for v in self.validators.into_values() {
+            if self.crashed.contains(&v.address) {
+                continue;
+            }
             v.handle.await.unwrap();
         }
     }
@@ -280,6 +367,8 @@ pub struct TestBuilder {
     sql_address: String,
     timeout: Option<Duration>,
     debug_sql_file: Option<String>,
+    fault_plan: FaultPlan,
+    state_store_backend: StateStoreBackend,
 }
 
 impl TestBuilder {
@@ -289,6 +378,8 @@ impl TestBuilder {
             sql_address: ":memory:".to_string(),
             timeout: Some(Duration::from_secs(10)),
             debug_sql_file: None,
+            fault_plan: FaultPlan::default(),
+            state_store_backend: default_state_store_backend(),
         }
     }
 
@@ -315,6 +406,19 @@ impl TestBuilder {
         self
     }
 
+    pub fn with_fault_plan(&mut self, fault_plan: FaultPlan) -> &mut Self {
+        self.fault_plan = fault_plan;
+        self
+    }
+
+    /// Runs every validator on the given state store backend, overriding the `CONSENSUS_TEST_STATE_STORE` environment
+    /// variable.
+    #[allow(dead_code)]
+    pub fn with_state_store_backend(&mut self, backend: StateStoreBackend) -> &mut Self {
+        self.state_store_backend = backend;
+        self
+    }
+
     pub fn add_committee<T: Into<ShardBucket>>(&mut self, bucket: T, addresses: Vec<&'static str>) -> &mut Self {
         self.committees
             .insert(bucket.into(), addresses.into_iter().map(TestAddress::new).collect());
@@ -335,6 +439,7 @@ impl TestBuilder {
                 let sql_address = self.sql_address.replace("{}", &address.0);
                 let (channels, validator) = Validator::builder()
                     .with_sql_url(sql_address)
+                    .with_state_store_backend(self.state_store_backend)
                     .with_address(address.clone())
                     .with_shard(shard)
                     .with_bucket(bucket)
@@ -368,10 +473,11 @@ impl TestBuilder {
         let (channels, validators) = self
             .build_validators(&leader_strategy, &epoch_manager, shutdown.to_signal())
             .await;
-        let network = spawn_network(channels, shutdown.to_signal());
+        let network = spawn_network(channels, self.fault_plan.clone(), shutdown.to_signal());
 
         Test {
             validators,
+            crashed: HashSet::new(),
             network,
             _leader_strategy: leader_strategy,
             epoch_manager,
@@ -380,3 +486,12 @@ impl TestBuilder {
         }
     }
 }
+
+/// The state store backend used by tests that do not choose one explicitly. Set `CONSENSUS_TEST_STATE_STORE=lmdb` to
+/// run the whole suite against LMDB.
+fn default_state_store_backend() -> StateStoreBackend {
+    std::env::var("CONSENSUS_TEST_STATE_STORE")
+        .ok()
+        .map(|backend| backend.parse().expect("Invalid CONSENSUS_TEST_STATE_STORE"))
+        .unwrap_or_default()
+}
@@ -2,7 +2,7 @@
 //   SPDX-License-Identifier: BSD-3-Clause
 
 use tari_consensus::traits::LeaderStrategy;
-use tari_dan_common_types::{committee::Committee, NodeAddressable, NodeHeight};
+use tari_dan_common_types::{committee::Committee, NodeAddressable, View};
 
 #[derive(Debug, Clone, Copy, Default)]
 pub struct RoundRobinLeaderStrategy;
@@ -13,7 +13,7 @@ impl RoundRobinLeaderStrategy {
 }
 
 impl<TAddr: NodeAddressable> LeaderStrategy<TAddr> for RoundRobinLeaderStrategy {
-    fn calculate_leader(&self, committee: &Committee<TAddr>, height: NodeHeight) -> u32 {
-        (height.0 % committee.members.len() as u64) as u32
+    fn calculate_leader(&self, committee: &Committee<TAddr>, view: View) -> u32 {
+        (view.as_u64() % committee.members.len() as u64) as u32
     }
 }
@@ -6,6 +6,7 @@
 
 mod address;
 mod epoch_manager;
+mod faults;
 mod harness;
 mod helpers;
 mod leader_strategy;
@@ -20,6 +21,7 @@ mod validator;
 
 pub use address::*;
 pub use epoch_manager::*;
+pub use faults::*;
 pub use harness::*;
 pub use leader_strategy::*;
 pub use network::*;
@@ -15,7 +15,7 @@ use tari_dan_storage::{
     StateStore,
 };
 use tari_shutdown::ShutdownSignal;
-use tari_state_store_sqlite::SqliteStateStore;
+use tari_state_store_backend::AnyStateStore;
 use tari_transaction::{Transaction, TransactionId};
 use tokio::sync::{
     mpsc::{self},
@@ -23,9 +23,18 @@ use tokio::sync::{
     RwLock,
 };
 
-use crate::support::{address::TestAddress, ValidatorChannels};
+use crate::support::{
+    address::TestAddress,
+    faults::{FaultInjector, FaultPlan, NetworkFaults},
+    ValidatorChannels,
+};
 
-pub fn spawn_network(channels: Vec<ValidatorChannels>, shutdown_signal: ShutdownSignal) -> TestNetwork {
+pub fn spawn_network(
+    channels: Vec<ValidatorChannels>,
+    fault_plan: FaultPlan,
+    shutdown_signal: ShutdownSignal,
+) -> TestNetwork {
+    log::info!("🎲 Network fault plan seed: {}", fault_plan.seed());
     let tx_new_transactions = channels
         .iter()
         .map(|c| {
@@ -50,32 +59,39 @@ pub fn spawn_network(channels: Vec<ValidatorChannels>, shutdown_signal: Shutdown
         })
         .multiunzip();
     let (tx_new_transaction, rx_new_transaction) = mpsc::channel(100);
+    let (tx_restart, rx_restart) = mpsc::channel(10);
     let (tx_network_status, network_status) = watch::channel(NetworkStatus::Paused);
     let (tx_on_message, rx_on_message) = watch::channel(None);
     let num_sent_messages = Arc::new(AtomicUsize::new(0));
 
     let offline_destinations = Arc::new(RwLock::new(Vec::new()));
+    let faults = Arc::new(RwLock::new(NetworkFaults::default()));
 
     TestNetworkWorker {
         network_status,
         rx_new_transaction: Some(rx_new_transaction),
-        tx_new_transactions,
+        tx_new_transactions: Arc::new(RwLock::new(tx_new_transactions)),
         tx_hs_message,
         rx_broadcast: Some(rx_broadcast),
         rx_leader: Some(rx_leader),
         rx_mempool: Some(rx_mempool),
+        rx_restart: Some(rx_restart),
         on_message: tx_on_message,
         num_sent_messages: num_sent_messages.clone(),
         transaction_store: Arc::new(Default::default()),
         offline_destinations: offline_destinations.clone(),
+        fault_injector: FaultInjector::new(fault_plan),
+        faults: faults.clone(),
         shutdown_signal,
     }
     .spawn();
 
     TestNetwork {
         tx_new_transaction,
+        tx_restart,
         network_status: tx_network_status,
         offline_destinations,
+        faults,
         num_sent_messages,
         _on_message: rx_on_message,
     }
@@ -95,8 +111,10 @@ impl NetworkStatus {
 
 pub struct TestNetwork {
     tx_new_transaction: mpsc::Sender<(TestNetworkDestination, ExecutedTransaction)>,
+    tx_restart: mpsc::Sender<ValidatorChannels>,
     network_status: watch::Sender<NetworkStatus>,
     offline_destinations: Arc<RwLock<Vec<TestNetworkDestination>>>,
+    faults: Arc<RwLock<NetworkFaults>>,
     num_sent_messages: Arc<AtomicUsize>,
     _on_message: watch::Receiver<Option<HotstuffMessage<TestAddress>>>,
 }
@@ -114,6 +132,35 @@ impl TestNetwork {
         self
     }
 
+    /// Drops all messages between the two sets of validators until the partition is healed.
+    pub async fn partition<I: IntoIterator<Item = TestAddress>>(&self, side_a: I, side_b: I) -> &Self {
+        self.faults
+            .write()
+            .await
+            .partition(side_a.into_iter().collect(), side_b.into_iter().collect());
+        self
+    }
+
+    pub async fn heal_partitions(&self) -> &Self {
+        self.faults.write().await.heal_partitions();
+        self
+    }
+
+    /// Stops delivering messages to and from the validator. Use [Test::crash](super::Test::crash) to also stop the
+    /// validator.
+    pub async fn crash(&self, address: TestAddress) -> &Self {
+        log::info!("💥 {} crashed", address);
+        self.faults.write().await.crash(address);
+        self
+    }
+
+    /// Connects the channels of a restarted validator to the network and resumes delivering its messages.
+    pub async fn restart(&self, channels: ValidatorChannels) -> &Self {
+        log::info!("♻️ {} restarted", channels.address);
+        self.tx_restart.send(channels).await.unwrap();
+        self
+    }
+
     #[allow(dead_code)]
     pub async fn on_message(&mut self) -> Option<HotstuffMessage<TestAddress>> {
         self._on_message.changed().await.unwrap();
@@ -155,22 +202,27 @@ impl TestNetworkDestination {
     }
 }
 
+type NewTransactionChannels =
+    HashMap<TestAddress, (ShardBucket, mpsc::Sender<TransactionId>, AnyStateStore<TestAddress>)>;
+
 pub struct TestNetworkWorker {
     rx_new_transaction: Option<mpsc::Receiver<(TestNetworkDestination, ExecutedTransaction)>>,
-    tx_new_transactions:
-        HashMap<TestAddress, (ShardBucket, mpsc::Sender<TransactionId>, SqliteStateStore<TestAddress>)>,
+    tx_new_transactions: Arc<RwLock<NewTransactionChannels>>,
     tx_hs_message: HashMap<TestAddress, mpsc::Sender<(TestAddress, HotstuffMessage<TestAddress>)>>,
     #[allow(clippy::type_complexity)]
     rx_broadcast: Option<HashMap<TestAddress, mpsc::Receiver<(Committee<TestAddress>, HotstuffMessage<TestAddress>)>>>,
     #[allow(clippy::type_complexity)]
     rx_leader: Option<HashMap<TestAddress, mpsc::Receiver<(TestAddress, HotstuffMessage<TestAddress>)>>>,
     rx_mempool: Option<HashMap<TestAddress, mpsc::UnboundedReceiver<Transaction>>>,
+    rx_restart: Option<mpsc::Receiver<ValidatorChannels>>,
     network_status: watch::Receiver<NetworkStatus>,
     on_message: watch::Sender<Option<HotstuffMessage<TestAddress>>>,
     num_sent_messages: Arc<AtomicUsize>,
     transaction_store: Arc<RwLock<HashMap<TransactionId, ExecutedTransaction>>>,
 
     offline_destinations: Arc<RwLock<Vec<TestNetworkDestination>>>,
+    fault_injector: FaultInjector,
+    faults: Arc<RwLock<NetworkFaults>>,
     shutdown_signal: ShutdownSignal,
 }
 
@@ -183,6 +235,7 @@ impl TestNetworkWorker {
         let mut rx_broadcast = self.rx_broadcast.take().unwrap();
         let mut rx_leader = self.rx_leader.take().unwrap();
         let mut rx_mempool = self.rx_mempool.take().unwrap();
+        let mut rx_restart = self.rx_restart.take().unwrap();
 
         let mut rx_new_transaction = self.rx_new_transaction.take().unwrap();
         let tx_new_transactions = self.tx_new_transactions.clone();
@@ -195,21 +248,23 @@ impl TestNetworkWorker {
                     .write()
                     .await
                     .insert(*executed.transaction().id(), executed.clone());
-                for (addr, (bucket, tx_new_transaction_to_consensus, state_store)) in &tx_new_transactions {
+                for (addr, (bucket, tx_new_transaction_to_consensus, state_store)) in
+                    tx_new_transactions.read().await.iter()
+                {
                     if dest.is_for(addr, *bucket) {
                         state_store
                             .with_write_tx(|tx| {
                                 executed.upsert(tx)?;
-                                let atom = executed.to_atom();
-                                let pool = TransactionPool::<SqliteStateStore<TestAddress>>::new();
-                                if !pool.exists(tx, &atom.id)? {
-                                    pool.insert(tx, atom)?;
+                                let pool = TransactionPool::<AnyStateStore<TestAddress>>::new();
+                                if !pool.exists(tx, executed.id())? {
+                                    pool.insert(tx, &executed)?;
                                 }
                                 Ok::<_, anyhow::Error>(())
                             })
                             .unwrap();
                         log::info!("🐞 New transaction {}", executed.id());
-                        tx_new_transaction_to_consensus.send(*executed.id()).await.unwrap();
+                        // A crashed validator picks the transaction up from its pool when it restarts
+                        let _ignore = tx_new_transaction_to_consensus.send(*executed.id()).await;
                     }
                 }
             }
@@ -225,16 +280,17 @@ impl TestNetworkWorker {
         }
 
         loop {
-            let mut rx_broadcast = rx_broadcast
+            let mut restarted = None;
+            let mut broadcasts = rx_broadcast
                 .iter_mut()
                 .map(|(from, rx)| rx.recv().map(|r| (from.clone(), r)))
                 .collect::<FuturesUnordered<_>>();
-            let mut rx_leader = rx_leader
+            let mut leader_messages = rx_leader
                 .iter_mut()
                 .map(|(from, rx)| rx.recv().map(|r| (from.clone(), r)))
                 .collect::<FuturesUnordered<_>>();
 
-            let mut rx_mempool = rx_mempool
+            let mut mempool_messages = rx_mempool
                 .iter_mut()
                 .map(|(from, rx)| rx.recv().map(|r| (from.clone(), r)))
                 .collect::<FuturesUnordered<_>>();
@@ -258,9 +314,31 @@ impl TestNetworkWorker {
                     }
                 }
 
-                Some((from, Some((to, msg)))) = rx_broadcast.next() => self.handle_broadcast(from, to, msg).await,
-                Some((from, Some((to, msg)))) = rx_leader.next() => self.handle_leader(from, to, msg).await,
-                Some((from, Some(msg))) = rx_mempool.next() => self.handle_mempool(from, msg).await,
+                Some(channels) = rx_restart.recv() => {
+                    restarted = Some(channels);
+                }
+
+                Some((from, Some((to, msg)))) = broadcasts.next() => self.handle_broadcast(from, to, msg).await,
+                Some((from, Some((to, msg)))) = leader_messages.next() => self.handle_leader(from, to, msg).await,
+                Some((from, Some(msg))) = mempool_messages.next() => self.handle_mempool(from, msg).await,
+            }
+
+            drop(broadcasts);
+            drop(leader_messages);
+            drop(mempool_messages);
+
+            // Replace the channels of the previous (crashed) instance of the validator
+            if let Some(channels) = restarted {
+                let address = channels.address.clone();
+                rx_broadcast.insert(address.clone(), channels.rx_broadcast);
+                rx_leader.insert(address.clone(), channels.rx_leader);
+                rx_mempool.insert(address.clone(), channels.rx_mempool);
+                self.tx_hs_message.insert(address.clone(), channels.tx_hs_message);
+                self.tx_new_transactions.write().await.insert(
+                    address.clone(),
+                    (channels.bucket, channels.tx_new_transactions, channels.state_store),
+                );
+                self.faults.write().await.restart(&address);
             }
         }
     }
@@ -274,18 +352,17 @@ impl TestNetworkWorker {
         log::debug!("🌎️ Broadcast {} from {} to {}", msg, from, to.iter().join(", "));
         self.num_sent_messages
             .fetch_add(to.len(), std::sync::atomic::Ordering::Relaxed);
+        let msg = self.fault_injector.forge_timeout_certificate(&from, &to, msg);
+        let equivocation = self.fault_injector.equivocate(&from, &msg);
+        let mut to = to.into_iter().collect::<Vec<_>>();
+        self.fault_injector.shuffle_recipients(&from, &mut to);
         for vn in to {
             // TODO: support for taking a whole committee bucket offline
             if vn != from && self.is_offline_destination(&vn, u32::MAX.into()).await {
                 continue;
             }
 
-            self.tx_hs_message
-                .get(&vn)
-                .unwrap()
-                .send((from.clone(), msg.clone()))
-                .await
-                .unwrap();
+            self.deliver(&from, &vn, &msg, equivocation.as_ref()).await;
         }
         self.on_message.send(Some(msg.clone())).unwrap();
     }
@@ -298,7 +375,54 @@ impl TestNetworkWorker {
         self.on_message.send(Some(msg.clone())).unwrap();
         self.num_sent_messages
             .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
-        self.tx_hs_message.get(&to).unwrap().send((from, msg)).await.unwrap();
+        self.deliver(&from, &to, &msg, None).await;
+    }
+
+    async fn deliver(
+        &mut self,
+        from: &TestAddress,
+        to: &TestAddress,
+        msg: &HotstuffMessage<TestAddress>,
+        equivocation: Option<&HotstuffMessage<TestAddress>>,
+    ) {
+        // A crashed validator does not even receive its own messages
+        if !self.faults.read().await.can_reach(from, to) {
+            log::debug!("🚧 {} from {} to {} did not arrive", msg, from, to);
+            return;
+        }
+
+        let from_bucket = self.bucket_of(from).await;
+        let to_bucket = self.bucket_of(to).await;
+        let Some((delay, msg)) = self
+            .fault_injector
+            .apply(from, from_bucket, to, to_bucket, msg, equivocation)
+        else {
+            return;
+        };
+
+        let tx_hs_message = self.tx_hs_message.get(to).unwrap();
+        if delay.is_zero() {
+            // The validator may have crashed after the reachability check
+            let _ignore = tx_hs_message.send((from.clone(), msg)).await;
+            return;
+        }
+
+        let tx_hs_message = tx_hs_message.clone();
+        let from = from.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(delay).await;
+            // The validator may have shut down in the meantime
+            let _ignore = tx_hs_message.send((from, msg)).await;
+        });
+    }
+
+    async fn bucket_of(&self, addr: &TestAddress) -> ShardBucket {
+        self.tx_new_transactions
+            .read()
+            .await
+            .get(addr)
+            .map(|(bucket, _, _)| *bucket)
+            .unwrap_or_else(|| u32::MAX.into())
     }
 
     async fn is_offline_destination(&self, addr: &TestAddress, bucket: ShardBucket) -> bool {
@@ -310,7 +434,10 @@ impl TestNetworkWorker {
     async fn handle_mempool(&mut self, from: TestAddress, msg: Transaction) {
         let (_, sender, state_store) = self
             .tx_new_transactions
+            .read()
+            .await
             .get(&from)
+            .cloned()
             .unwrap_or_else(|| panic!("No new transaction channel for {}", from));
 
         // In the normal case, we need to provide the same execution results to consensus. In future we could add code
@@ -319,9 +446,9 @@ impl TestNetworkWorker {
         state_store
             .with_write_tx(|tx| {
                 existing_executed_tx.upsert(tx)?;
-                let pool = TransactionPool::<SqliteStateStore<TestAddress>>::new();
+                let pool = TransactionPool::<AnyStateStore<TestAddress>>::new();
                 if !pool.exists(tx, existing_executed_tx.id())? {
-                    pool.insert(tx, existing_executed_tx.to_atom())?;
+                    pool.insert(tx, &existing_executed_tx)?;
                 }
                 Ok::<_, anyhow::Error>(())
             })
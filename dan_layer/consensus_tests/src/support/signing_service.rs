@@ -1,13 +1,18 @@
 //   Copyright 2023 The Tari Project
 //   SPDX-License-Identifier: BSD-3-Clause
 
-use rand::rngs::OsRng;
+use rand::{
+    rngs::{OsRng, StdRng},
+    SeedableRng,
+};
 use tari_common_types::types::{FixedHash, PrivateKey, PublicKey};
 use tari_consensus::traits::{ValidatorSignatureService, VoteSignatureService};
-use tari_crypto::keys::PublicKey as _;
-use tari_dan_common_types::NodeAddressable;
+use tari_crypto::{hash_domain, keys::PublicKey as _};
+use tari_dan_common_types::{hasher::tari_hasher, NodeAddressable};
 use tari_dan_storage::consensus_models::{BlockId, QuorumDecision, ValidatorSchnorrSignature, ValidatorSignature};
 
+hash_domain!(TestKeyHashDomain, "com.tari.dan.consensus_tests.keys", 0);
+
 #[derive(Debug, Clone)]
 pub struct TestVoteSignatureService<TAddr> {
     pub public_key: TAddr,
@@ -15,9 +20,9 @@ pub struct TestVoteSignatureService<TAddr> {
     pub is_signature_valid: bool,
 }
 
-impl<TAddr> TestVoteSignatureService<TAddr> {
+impl<TAddr: NodeAddressable> TestVoteSignatureService<TAddr> {
     pub fn new(public_key: TAddr) -> Self {
-        let (secret_key, _public_key) = PublicKey::random_keypair(&mut OsRng);
+        let (secret_key, _public_key) = key_pair_for(&public_key);
         Self {
             public_key,
             secret_key,
@@ -26,7 +31,7 @@ impl<TAddr> TestVoteSignatureService<TAddr> {
     }
 }
 
-impl<TAddr> ValidatorSignatureService<TAddr> for TestVoteSignatureService<TAddr> {
+impl<TAddr: NodeAddressable> ValidatorSignatureService<TAddr> for TestVoteSignatureService<TAddr> {
     fn sign<M: AsRef<[u8]>>(&self, message: M) -> ValidatorSchnorrSignature {
         ValidatorSchnorrSignature::sign(&self.secret_key, message, &mut OsRng).unwrap()
     }
@@ -34,16 +39,36 @@ impl<TAddr> ValidatorSignatureService<TAddr> for TestVoteSignatureService<TAddr>
     fn public_key(&self) -> &TAddr {
         &self.public_key
     }
+
+    fn verify_signature<M: AsRef<[u8]>>(&self, signature: &ValidatorSignature<TAddr>, message: M) -> bool {
+        if !self.is_signature_valid {
+            return false;
+        }
+        let (_, public_key) = key_pair_for(signature.public_key());
+        signature.signature.verify(&public_key, message)
+    }
 }
 
 impl<TAddr: NodeAddressable> VoteSignatureService<TAddr> for TestVoteSignatureService<TAddr> {
     fn verify(
         &self,
-        _signature: &ValidatorSignature<TAddr>,
-        _leaf_hash: &FixedHash,
-        _block_id: &BlockId,
-        _decision: &QuorumDecision,
+        signature: &ValidatorSignature<TAddr>,
+        leaf_hash: &FixedHash,
+        block_id: &BlockId,
+        decision: &QuorumDecision,
     ) -> bool {
-        self.is_signature_valid
+        let challenge = self.create_challenge(leaf_hash, block_id, decision);
+        self.verify_signature(signature, challenge)
     }
 }
+
+/// Test addresses are not public keys, so every address is given a key pair that is derived from the address. This
+/// allows any validator to check signatures made by another.
+fn key_pair_for<TAddr: NodeAddressable>(address: &TAddr) -> (PrivateKey, PublicKey) {
+    let hash = tari_hasher::<TestKeyHashDomain>("key_pair")
+        .chain(address.as_bytes())
+        .result();
+    let mut seed = [0u8; 32];
+    seed.copy_from_slice(hash.as_slice());
+    PublicKey::random_keypair(&mut StdRng::from_seed(seed))
+}
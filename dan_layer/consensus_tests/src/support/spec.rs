@@ -2,7 +2,7 @@
 //   SPDX-License-Identifier: BSD-3-Clause
 
 use tari_consensus::traits::ConsensusSpec;
-use tari_state_store_sqlite::SqliteStateStore;
+use tari_state_store_backend::AnyStateStore;
 
 use crate::support::{
     address::TestAddress,
@@ -21,7 +21,7 @@ impl ConsensusSpec for TestConsensusSpec {
     type EpochManager = TestEpochManager;
     type LeaderStrategy = RoundRobinLeaderStrategy;
     type StateManager = NoopStateManager;
-    type StateStore = SqliteStateStore<Self::Addr>;
+    type StateStore = AnyStateStore<Self::Addr>;
     type SyncManager = AlwaysSyncedSyncManager;
     type VoteSignatureService = TestVoteSignatureService<Self::Addr>;
 }
@@ -20,25 +20,29 @@ pub fn build_transaction_from(
     decision: Decision,
     fee: u64,
     resulting_outputs: Vec<ShardId>,
+) -> ExecutedTransaction {
+    let result = if decision.is_commit() {
+        TransactionResult::Accept(SubstateDiff::new())
+    } else {
+        TransactionResult::Reject(RejectReason::ExecutionFailure("Test failure".to_string()))
+    };
+    build_transaction_with_result(tx, result, fee, resulting_outputs)
+}
+
+pub fn build_transaction_with_result(
+    tx: Transaction,
+    result: TransactionResult,
+    fee: u64,
+    resulting_outputs: Vec<ShardId>,
 ) -> ExecutedTransaction {
     let tx_id = *tx.id();
     ExecutedTransaction::new(
         tx,
         ExecuteResult {
-            finalize: FinalizeResult::new(
-                tx_id.into_array().into(),
-                vec![],
-                vec![],
-                if decision.is_commit() {
-                    TransactionResult::Accept(SubstateDiff::new())
-                } else {
-                    TransactionResult::Reject(RejectReason::ExecutionFailure("Test failure".to_string()))
-                },
-                FeeCostBreakdown {
-                    total_fees_charged: fee.try_into().unwrap(),
-                    breakdown: vec![],
-                },
-            ),
+            finalize: FinalizeResult::new(tx_id.into_array().into(), vec![], vec![], result, FeeCostBreakdown {
+                total_fees_charged: fee.try_into().unwrap(),
+                breakdown: vec![],
+            }),
             fee_receipt: Some(FeeReceipt {
                 total_fee_payment: fee.try_into().unwrap(),
                 total_fees_paid: fee.try_into().unwrap(),
@@ -1,11 +1,11 @@
 //   Copyright 2023 The Tari Project
 //   SPDX-License-Identifier: BSD-3-Clause
 
-use tari_consensus::hotstuff::{ConsensusWorker, ConsensusWorkerContext, HotstuffWorker};
+use tari_consensus::hotstuff::{ConsensusConfig, ConsensusWorker, ConsensusWorkerContext, HotstuffWorker};
 use tari_dan_common_types::{shard_bucket::ShardBucket, ShardId};
 use tari_dan_storage::consensus_models::TransactionPool;
 use tari_shutdown::ShutdownSignal;
-use tari_state_store_sqlite::SqliteStateStore;
+use tari_state_store_backend::{AnyStateStore, StateStoreBackend};
 use tokio::sync::{broadcast, mpsc, watch};
 
 use crate::support::{
@@ -25,8 +25,11 @@ pub struct ValidatorBuilder {
     pub shard: ShardId,
     pub bucket: ShardBucket,
     pub sql_url: String,
+    pub state_store_backend: StateStoreBackend,
     pub leader_strategy: RoundRobinLeaderStrategy,
     pub epoch_manager: Option<TestEpochManager>,
+    pub state_store: Option<AnyStateStore<TestAddress>>,
+    pub state_manager: Option<NoopStateManager>,
 }
 
 impl ValidatorBuilder {
@@ -36,8 +39,11 @@ impl ValidatorBuilder {
             shard: ShardId::zero(),
             bucket: ShardBucket::from(0),
             sql_url: ":memory".to_string(),
+            state_store_backend: StateStoreBackend::Sqlite,
             leader_strategy: RoundRobinLeaderStrategy::new(),
             epoch_manager: None,
+            state_store: None,
+            state_manager: None,
         }
     }
 
@@ -66,11 +72,28 @@ impl ValidatorBuilder {
         self
     }
 
+    pub fn with_state_store_backend(&mut self, backend: StateStoreBackend) -> &mut Self {
+        self.state_store_backend = backend;
+        self
+    }
+
     pub fn with_leader_strategy(&mut self, leader_strategy: RoundRobinLeaderStrategy) -> &mut Self {
         self.leader_strategy = leader_strategy;
         self
     }
 
+    /// Uses an existing state store instead of creating a new one. This is used to restart a validator with its
+    /// database intact.
+    pub fn with_state_store(&mut self, state_store: AnyStateStore<TestAddress>) -> &mut Self {
+        self.state_store = Some(state_store);
+        self
+    }
+
+    pub fn with_state_manager(&mut self, state_manager: NoopStateManager) -> &mut Self {
+        self.state_manager = Some(state_manager);
+        self
+    }
+
     pub fn spawn(&self, shutdown_signal: ShutdownSignal) -> (ValidatorChannels, Validator) {
         let (tx_broadcast, rx_broadcast) = mpsc::channel(100);
         let (tx_new_transactions, rx_new_transactions) = mpsc::channel(100);
@@ -78,10 +101,17 @@ impl ValidatorBuilder {
         let (tx_leader, rx_leader) = mpsc::channel(100);
         let (tx_mempool, rx_mempool) = mpsc::unbounded_channel();
 
-        let store = SqliteStateStore::connect(&self.sql_url).unwrap();
+        let (store, state_dir) = match (self.state_store.clone(), self.state_store_backend) {
+            (Some(store), _) => (store, None),
+            (None, StateStoreBackend::Sqlite) => (AnyStateStore::connect_sqlite(&self.sql_url).unwrap(), None),
+            (None, StateStoreBackend::Lmdb) => {
+                let state_dir = tempfile::tempdir().unwrap();
+                (AnyStateStore::open_lmdb(state_dir.path()).unwrap(), Some(state_dir))
+            },
+        };
         let signing_service = TestVoteSignatureService::new(self.address.clone());
         let transaction_pool = TransactionPool::new();
-        let noop_state_manager = NoopStateManager::new();
+        let noop_state_manager = self.state_manager.clone().unwrap_or_else(NoopStateManager::new);
         let (tx_events, _) = broadcast::channel(100);
 
         let epoch_manager = self
@@ -90,6 +120,7 @@ impl ValidatorBuilder {
             .unwrap()
             .clone_for(self.address.clone(), self.shard);
         let worker = HotstuffWorker::<TestConsensusSpec>::new(
+            ConsensusConfig::default(),
             self.address.clone(),
             rx_new_transactions,
             rx_hs_message,
@@ -106,6 +137,7 @@ impl ValidatorBuilder {
             shutdown_signal.clone(),
         );
 
+        let participation = worker.participation_tracker();
         let (tx_current_state, _) = watch::channel(Default::default());
         let context = ConsensusWorkerContext {
             epoch_manager: epoch_manager.clone(),
@@ -131,12 +163,15 @@ impl ValidatorBuilder {
         let validator = Validator {
             address: self.address.clone(),
             shard: self.shard,
+            bucket: self.bucket,
             state_store: store,
             epoch_manager,
             state_manager: noop_state_manager,
+            participation,
             leader_strategy: self.leader_strategy,
             events: tx_events.subscribe(),
             handle,
+            _state_dir: state_dir,
         };
         (channels, validator)
     }
@@ -1,11 +1,15 @@
 //   Copyright 2023 The Tari Project
 //   SPDX-License-Identifier: BSD-3-Clause
 
-use tari_consensus::{hotstuff::HotstuffEvent, messages::HotstuffMessage};
+use tari_consensus::{
+    hotstuff::{HotstuffEvent, ParticipationTracker},
+    messages::HotstuffMessage,
+};
 use tari_dan_common_types::{committee::Committee, shard_bucket::ShardBucket, ShardId};
 use tari_dan_storage::{consensus_models::LeafBlock, StateStore, StateStoreReadTransaction};
-use tari_state_store_sqlite::SqliteStateStore;
+use tari_state_store_backend::AnyStateStore;
 use tari_transaction::{Transaction, TransactionId};
+use tempfile::TempDir;
 use tokio::{
     sync::{broadcast, mpsc},
     task::JoinHandle,
@@ -22,7 +26,7 @@ use crate::support::{
 pub struct ValidatorChannels {
     pub address: TestAddress,
     pub bucket: ShardBucket,
-    pub state_store: SqliteStateStore<TestAddress>,
+    pub state_store: AnyStateStore<TestAddress>,
 
     pub tx_new_transactions: mpsc::Sender<TransactionId>,
     pub tx_hs_message: mpsc::Sender<(TestAddress, HotstuffMessage<TestAddress>)>,
@@ -34,14 +38,18 @@ pub struct ValidatorChannels {
 pub struct Validator {
     pub address: TestAddress,
     pub shard: ShardId,
+    pub bucket: ShardBucket,
 
-    pub state_store: SqliteStateStore<TestAddress>,
+    pub state_store: AnyStateStore<TestAddress>,
     pub epoch_manager: TestEpochManager,
     pub leader_strategy: RoundRobinLeaderStrategy,
     pub events: broadcast::Receiver<HotstuffEvent>,
     pub state_manager: NoopStateManager,
+    pub participation: ParticipationTracker<TestAddress>,
 
     pub handle: JoinHandle<()>,
+    /// Keeps the LMDB state directory alive for as long as the validator exists
+    pub(crate) _state_dir: Option<TempDir>,
 }
 
 impl Validator {
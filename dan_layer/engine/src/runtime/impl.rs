@@ -33,6 +33,7 @@ use tari_engine_types::{
     component::ComponentHeader,
     confidential::{get_commitment_factory, get_range_proof_service, ConfidentialClaim, ConfidentialOutput},
     events::Event,
+    execution_trace::{ExecutionTrace, TraceEvent},
     fees::FeeReceipt,
     indexed_value::IndexedValue,
     lock::LockFlag,
@@ -157,6 +158,9 @@ impl<TTemplateProvider: TemplateProvider<Template = LoadedTemplate>> RuntimeInte
     }
 
     fn invoke_modules_on_runtime_call(&self, function: &'static str) -> Result<(), RuntimeError> {
+        self.tracker.record_trace(|| TraceEvent::EngineCall {
+            call: function.to_string(),
+        });
         for module in &self.modules {
             module.on_runtime_call(&self.tracker, function)?;
         }
@@ -1593,15 +1597,17 @@ impl<TTemplateProvider: TemplateProvider<Template = LoadedTemplate>> RuntimeInte
             fee_receipt,
             events,
             logs,
+            execution_trace,
         } = self.tracker.finalize(substates_to_persist)?;
 
-        let finalized = FinalizeResult::new(
+        let mut finalized = FinalizeResult::new(
             self.tracker.transaction_hash(),
             logs,
             events,
             result,
             fee_receipt.to_cost_breakdown(),
         );
+        finalized.execution_trace = execution_trace;
 
         Ok(StateFinalize { finalized, fee_receipt })
     }
@@ -1625,6 +1631,14 @@ impl<TTemplateProvider: TemplateProvider<Template = LoadedTemplate>> RuntimeInte
         self.tracker.pop_call_frame()?;
         Ok(())
     }
+
+    fn record_trace(&self, event: TraceEvent) {
+        self.tracker.record_trace(|| event);
+    }
+
+    fn take_execution_trace(&self) -> Option<ExecutionTrace> {
+        self.tracker.take_execution_trace()
+    }
 }
 
 fn validate_component_access_rule_methods(
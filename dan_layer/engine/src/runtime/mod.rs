@@ -38,6 +38,9 @@ pub use actions::*;
 mod module;
 pub use module::{RuntimeModule, RuntimeModuleError};
 
+mod read_only_module;
+pub use read_only_module::ReadOnlyModule;
+
 mod fee_state;
 mod tracker;
 
@@ -60,6 +63,7 @@ use tari_dan_common_types::Epoch;
 use tari_engine_types::{
     component::ComponentHeader,
     confidential::{ConfidentialClaim, ConfidentialOutput},
+    execution_trace::{ExecutionTrace, TraceEvent},
     indexed_value::IndexedValue,
     lock::LockFlag,
     substate::{SubstateAddress, SubstateValue},
@@ -176,6 +180,10 @@ pub trait RuntimeInterface: Send + Sync {
 
     fn push_call_frame(&self, frame: PushCallFrame) -> Result<(), RuntimeError>;
     fn pop_call_frame(&self) -> Result<(), RuntimeError>;
+
+    /// Records the event in the execution trace. This is a no-op unless execution tracing is enabled.
+    fn record_trace(&self, event: TraceEvent);
+    fn take_execution_trace(&self) -> Option<ExecutionTrace>;
 }
 
 #[derive(Clone)]
@@ -28,4 +28,11 @@ pub trait RuntimeModule: Send + Sync {
 pub enum RuntimeModuleError {
     #[error("BOR error: {0}")]
     Bor(#[from] tari_bor::BorError),
+    #[error("State modification not permitted in read-only execution: {address} ({num_changes} substate(s) changed)")]
+    StateModificationNotPermitted {
+        address: SubstateAddress,
+        num_changes: usize,
+    },
+    #[error("Runtime call limit of {limit} exceeded in read-only execution")]
+    RuntimeCallLimitExceeded { limit: usize },
 }
@@ -0,0 +1,53 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use indexmap::IndexMap;
+use tari_engine_types::substate::{SubstateAddress, SubstateValue};
+
+use crate::runtime::{RuntimeModule, RuntimeModuleError, StateTracker};
+
+/// Fails execution if any substate was created or mutated, or if more than `max_runtime_calls` runtime calls are made.
+/// Used to execute view calls against committed state, where the result is never committed and no fees are charged
+/// to bound the work done.
+#[derive(Debug, Default)]
+pub struct ReadOnlyModule {
+    max_runtime_calls: usize,
+    num_runtime_calls: AtomicUsize,
+}
+
+impl ReadOnlyModule {
+    pub fn new(max_runtime_calls: usize) -> Self {
+        Self {
+            max_runtime_calls,
+            num_runtime_calls: AtomicUsize::new(0),
+        }
+    }
+}
+
+impl RuntimeModule for ReadOnlyModule {
+    fn on_runtime_call(&self, _track: &StateTracker, _call: &'static str) -> Result<(), RuntimeModuleError> {
+        let num_calls = self.num_runtime_calls.fetch_add(1, Ordering::Relaxed) + 1;
+        if num_calls > self.max_runtime_calls {
+            return Err(RuntimeModuleError::RuntimeCallLimitExceeded {
+                limit: self.max_runtime_calls,
+            });
+        }
+        Ok(())
+    }
+
+    fn on_before_finalize(
+        &self,
+        _track: &StateTracker,
+        changes: &IndexMap<SubstateAddress, SubstateValue>,
+    ) -> Result<(), RuntimeModuleError> {
+        if let Some(address) = changes.keys().next() {
+            return Err(RuntimeModuleError::StateModificationNotPermitted {
+                address: address.clone(),
+                num_changes: changes.len(),
+            });
+        }
+        Ok(())
+    }
+}
@@ -33,6 +33,7 @@ use tari_engine_types::{
     component::{ComponentBody, ComponentHeader},
     confidential::UnclaimedConfidentialOutput,
     events::Event,
+    execution_trace::{ExecutionTrace, TraceEvent},
     fees::{FeeReceipt, FeeSource},
     indexed_value::{IndexedValue, IndexedWellKnownTypes},
     lock::LockFlag,
@@ -68,6 +69,7 @@ pub struct FinalizeData {
     pub events: Vec<Event>,
     pub fee_receipt: FeeReceipt,
     pub logs: Vec<LogEntry>,
+    pub execution_trace: Option<ExecutionTrace>,
 }
 
 #[derive(Debug, Clone)]
@@ -123,6 +125,18 @@ impl StateTracker {
         self.write_with(|state| state.take_logs())
     }
 
+    pub fn enable_execution_trace(&self) {
+        self.write_with(|state| state.enable_execution_trace());
+    }
+
+    pub fn record_trace<F: FnOnce() -> TraceEvent>(&self, event: F) {
+        self.write_with(|state| state.trace(event));
+    }
+
+    pub fn take_execution_trace(&self) -> Option<ExecutionTrace> {
+        self.write_with(|state| state.take_execution_trace())
+    }
+
     pub fn get_template_address(&self) -> Result<TemplateAddress, RuntimeError> {
         self.read_with(|state| state.current_template().map(|(a, _)| *a))
     }
@@ -278,6 +292,7 @@ impl StateTracker {
         self.write_with(|state| {
             debug!(target: LOG_TARGET, "Add fee: source: {:?}, amount: {}", source, amount);
             state.fee_state_mut().fee_charges.push((source, amount));
+            state.trace(|| TraceEvent::FeeCharged { source, amount });
         })
     }
 
@@ -304,7 +319,11 @@ impl StateTracker {
 
         let result = match result {
             Ok(substate_diff) => TransactionResult::Accept(substate_diff),
-            Err(err) => TransactionResult::Reject(RejectReason::ExecutionFailure(err.to_string())),
+            Err(err) => {
+                let reason = err.to_string();
+                state.trace(|| TraceEvent::Failed { reason: reason.clone() });
+                TransactionResult::Reject(RejectReason::ExecutionFailure(reason))
+            },
         };
 
         Ok(FinalizeData {
@@ -312,6 +331,7 @@ impl StateTracker {
             events: state.take_events(),
             fee_receipt,
             logs: state.take_logs(),
+            execution_trace: state.take_execution_trace(),
         })
     }
 
@@ -330,9 +350,12 @@ impl StateTracker {
         let fee_state = self.read_with(|state| state.fee_state().clone());
         if let Some(checkpoint) = checkpoint.take() {
             self.write_with(|state| {
+                let execution_trace = state.take_execution_trace();
                 *state = checkpoint;
-                // Preserve fee state across resets
+                // Preserve fee state and the execution trace across resets
                 *state.fee_state_mut() = fee_state;
+                state.set_execution_trace(execution_trace);
+                state.trace(|| TraceEvent::ResetToFeeCheckpoint);
             });
             Ok(())
         } else {
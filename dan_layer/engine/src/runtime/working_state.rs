@@ -16,6 +16,7 @@ use tari_engine_types::{
     bucket::Bucket,
     component::ComponentHeader,
     events::Event,
+    execution_trace::{ExecutionTrace, TraceEvent},
     fee_claim::{FeeClaim, FeeClaimAddress},
     fees::FeeReceipt,
     indexed_value::{IndexedValue, IndexedWellKnownTypes},
@@ -87,6 +88,7 @@ pub(super) struct WorkingState {
     base_call_scope: CallScope,
 
     fee_state: FeeState,
+    execution_trace: Option<ExecutionTrace>,
 }
 
 impl WorkingState {
@@ -115,6 +117,7 @@ impl WorkingState {
             call_frames: Vec::new(),
             base_call_scope,
             fee_state: FeeState::new(),
+            execution_trace: None,
         }
     }
 
@@ -148,6 +151,10 @@ impl WorkingState {
         lock_flag: LockFlag,
     ) -> Result<LockedSubstate, RuntimeError> {
         let lock_id = self.store.try_lock(addr, lock_flag)?;
+        self.trace(|| TraceEvent::SubstateLocked {
+            address: addr.clone(),
+            lock_flag,
+        });
         Ok(LockedSubstate::new(addr.clone(), lock_id, lock_flag))
     }
 
@@ -378,9 +385,15 @@ impl WorkingState {
             return Err(RuntimeError::BucketNotFound { bucket_id });
         }
         self.current_call_scope_mut()?.remove_bucket_from_scope(bucket_id);
-        self.buckets
+        let bucket = self
+            .buckets
             .remove(&bucket_id)
-            .ok_or(RuntimeError::BucketNotFound { bucket_id })
+            .ok_or(RuntimeError::BucketNotFound { bucket_id })?;
+        self.trace(|| TraceEvent::BucketTaken {
+            bucket_id,
+            amount: bucket.amount(),
+        });
+        Ok(bucket)
     }
 
     pub fn burn_bucket(&mut self, bucket: Bucket) -> Result<(), RuntimeError> {
@@ -546,6 +559,11 @@ impl WorkingState {
             }
         }
 
+        self.trace(|| TraceEvent::BucketCreated {
+            bucket_id,
+            resource_address: *resource.resource_address(),
+            amount: resource.amount(),
+        });
         let bucket = Bucket::new(bucket_id, resource);
         if self.buckets.insert(bucket_id, bucket).is_some() {
             return Err(RuntimeError::DuplicateBucket { bucket_id });
@@ -913,6 +931,26 @@ impl WorkingState {
         &self.logs
     }
 
+    pub fn enable_execution_trace(&mut self) {
+        self.execution_trace.get_or_insert_with(ExecutionTrace::new);
+    }
+
+    /// Records an entry at the current call depth if execution tracing is enabled
+    pub fn trace<F: FnOnce() -> TraceEvent>(&mut self, event: F) {
+        let depth = self.call_frame_depth();
+        if let Some(trace) = self.execution_trace.as_mut() {
+            trace.push(depth, event());
+        }
+    }
+
+    pub fn take_execution_trace(&mut self) -> Option<ExecutionTrace> {
+        self.execution_trace.take()
+    }
+
+    pub fn set_execution_trace(&mut self, execution_trace: Option<ExecutionTrace>) {
+        self.execution_trace = execution_trace;
+    }
+
     pub fn generate_substate_diff(
         &self,
         transaction_receipt: TransactionReceipt,
@@ -27,6 +27,7 @@ use tari_bor::to_value;
 use tari_dan_common_types::{services::template_provider::TemplateProvider, Epoch};
 use tari_engine_types::{
     commit_result::{ExecuteResult, FinalizeResult, RejectReason, TransactionResult},
+    execution_trace::TraceEvent,
     indexed_value::{IndexedValue, IndexedWellKnownTypes},
     instruction::Instruction,
     instruction_result::InstructionResult,
@@ -38,6 +39,7 @@ use tari_template_lib::{
     invoke_args,
     models::ComponentAddress,
     prelude::TemplateAddress,
+    Hash,
 };
 use tari_transaction::{id_provider::IdProvider, Transaction};
 
@@ -69,6 +71,7 @@ pub struct TransactionProcessor<TTemplateProvider> {
     auth_params: AuthParams,
     virtual_substates: VirtualSubstates,
     modules: Vec<Arc<dyn RuntimeModule>>,
+    execution_trace: bool,
 }
 
 impl<TTemplateProvider: TemplateProvider<Template = LoadedTemplate> + 'static> TransactionProcessor<TTemplateProvider> {
@@ -85,9 +88,17 @@ impl<TTemplateProvider: TemplateProvider<Template = LoadedTemplate> + 'static> T
             auth_params,
             virtual_substates,
             modules,
+            execution_trace: false,
         }
     }
 
+    /// Record an [ExecutionTrace](tari_engine_types::execution_trace::ExecutionTrace) of the transaction in the
+    /// finalize result
+    pub fn with_execution_trace(mut self, enabled: bool) -> Self {
+        self.execution_trace = enabled;
+        self
+    }
+
     pub fn execute(self, transaction: Transaction) -> Result<ExecuteResult, TransactionError> {
         let id_provider = IdProvider::new(transaction.hash(), 1000);
         let Self {
@@ -96,10 +107,14 @@ impl<TTemplateProvider: TemplateProvider<Template = LoadedTemplate> + 'static> T
             auth_params,
             virtual_substates,
             modules,
+            execution_trace,
         } = self;
 
         let initial_auth_scope = AuthorizationScope::new(auth_params.initial_ownership_proofs);
         let tracker = StateTracker::new(state_db, id_provider, virtual_substates, initial_auth_scope);
+        if execution_trace {
+            tracker.enable_execution_trace();
+        }
         let runtime_interface = RuntimeInterfaceImpl::initialize(
             tracker,
             template_provider.clone(),
@@ -120,8 +135,7 @@ impl<TTemplateProvider: TemplateProvider<Template = LoadedTemplate> + 'static> T
                 // Checkpoint the tracker state after the fee instructions have been executed in case of transaction
                 // failure.
                 if let Err(err) = runtime.interface().fee_checkpoint() {
-                    let mut finalize =
-                        FinalizeResult::new_rejected(transaction_hash, RejectReason::ExecutionFailure(err.to_string()));
+                    let mut finalize = Self::reject_with_trace(&runtime, transaction_hash, err.to_string());
                     finalize.execution_results = execution_results;
                     return Ok(ExecuteResult {
                         fee_receipt: None,
@@ -133,10 +147,7 @@ impl<TTemplateProvider: TemplateProvider<Template = LoadedTemplate> + 'static> T
             Err(err) => {
                 return Ok(ExecuteResult {
                     fee_receipt: None,
-                    finalize: FinalizeResult::new_rejected(
-                        transaction_hash,
-                        RejectReason::ExecutionFailure(err.to_string()),
-                    ),
+                    finalize: Self::reject_with_trace(&runtime, transaction_hash, err.to_string()),
                 });
             },
         };
@@ -180,6 +191,9 @@ impl<TTemplateProvider: TemplateProvider<Template = LoadedTemplate> + 'static> T
             },
             // This can happen e.g if you have dangling buckets after running the instructions
             Err(err) => {
+                runtime.interface().record_trace(TraceEvent::Failed {
+                    reason: err.to_string(),
+                });
                 // Reset the state to when the state at the end of the fee instructions. The fee charges for the
                 // successful instructions are still charged even though the transaction failed.
                 runtime.interface().reset_to_fee_checkpoint()?;
@@ -206,6 +220,15 @@ impl<TTemplateProvider: TemplateProvider<Template = LoadedTemplate> + 'static> T
         }
     }
 
+    fn reject_with_trace(runtime: &Runtime, transaction_hash: Hash, reason: String) -> FinalizeResult {
+        runtime
+            .interface()
+            .record_trace(TraceEvent::Failed { reason: reason.clone() });
+        let mut finalize = FinalizeResult::new_rejected(transaction_hash, RejectReason::ExecutionFailure(reason));
+        finalize.execution_trace = runtime.interface().take_execution_trace();
+        finalize
+    }
+
     fn process_instructions(
         template_provider: &TTemplateProvider,
         runtime: &Runtime,
@@ -298,6 +321,10 @@ impl<TTemplateProvider: TemplateProvider<Template = LoadedTemplate> + 'static> T
         function: &str,
         args: Vec<Arg>,
     ) -> Result<InstructionResult, TransactionError> {
+        runtime.interface().record_trace(TraceEvent::CallFunction {
+            template_address: *template_address,
+            function: function.to_string(),
+        });
         let template = template_provider
             .get_template_module(template_address)
             .map_err(|e| TransactionError::FailedToLoadTemplate {
@@ -342,6 +369,10 @@ impl<TTemplateProvider: TemplateProvider<Template = LoadedTemplate> + 'static> T
         method: &str,
         args: Vec<Arg>,
     ) -> Result<InstructionResult, TransactionError> {
+        runtime.interface().record_trace(TraceEvent::CallMethod {
+            component_address: *component_address,
+            method: method.to_string(),
+        });
         let component = runtime.interface().load_component(component_address)?;
         let template_address = component.template_address;
 
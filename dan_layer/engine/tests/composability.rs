@@ -6,6 +6,7 @@ mod support;
 use tari_dan_engine::{runtime::ActionIdent, transaction::MAX_CALL_DEPTH};
 use tari_engine_types::{
     commit_result::{ExecuteResult, RejectReason},
+    execution_trace::{ExecutionTrace, TraceEvent},
     instruction::Instruction,
 };
 use tari_template_lib::{
@@ -270,6 +271,92 @@ fn it_fails_on_invalid_calls() {
     assert!(matches!(reason, RejectReason::ExecutionFailure(_)));
 }
 
+/// Returns the depth of the first call to the method in the trace
+fn find_method_call(trace: &ExecutionTrace, name: &str) -> Option<usize> {
+    trace.entries.iter().find_map(|entry| match &entry.event {
+        TraceEvent::CallMethod { method, .. } if method == name => Some(entry.depth),
+        _ => None,
+    })
+}
+
+#[test]
+fn it_records_an_execution_trace_of_nested_calls() {
+    let mut test = setup();
+    let components = initialize_composability(&mut test);
+    let (_, _, private_key) = test.template_test.create_owned_account();
+    test.template_test.enable_execution_trace();
+
+    let result = test
+        .template_test
+        .try_execute(
+            Transaction::builder()
+                .call_method(
+                    components.composability_component,
+                    "increase_inner_state_component",
+                    args![],
+                )
+                .sign(&private_key)
+                .build(),
+            vec![],
+        )
+        .unwrap();
+    result.expect_success();
+
+    let trace = result
+        .finalize
+        .execution_trace
+        .as_ref()
+        .expect("execution trace was not recorded");
+    assert_eq!(find_method_call(trace, "increase_inner_state_component"), Some(0));
+    assert_eq!(find_method_call(trace, "get"), Some(1));
+    assert_eq!(find_method_call(trace, "set"), Some(1));
+    assert!(trace
+        .entries
+        .iter()
+        .any(|entry| matches!(entry.event, TraceEvent::EngineCall { ref call } if call == "call_invoke")));
+    assert!(!trace
+        .entries
+        .iter()
+        .any(|entry| matches!(entry.event, TraceEvent::Failed { .. })));
+}
+
+#[test]
+fn it_records_where_execution_failed_in_the_execution_trace() {
+    let mut test = setup();
+    let components = initialize_composability(&mut test);
+    let (_, _, private_key) = test.template_test.create_owned_account();
+    test.template_test.enable_execution_trace();
+
+    let result = test
+        .template_test
+        .try_execute(
+            Transaction::builder()
+                .call_method(components.composability_component, "invalid_state_call", args![])
+                .sign(&private_key)
+                .build(),
+            vec![],
+        )
+        .unwrap();
+    result.expect_transaction_failure();
+
+    let trace = result
+        .finalize
+        .execution_trace
+        .as_ref()
+        .expect("execution trace was not recorded");
+    assert_eq!(find_method_call(trace, "invalid_method"), Some(1));
+    let failed_at = trace
+        .entries
+        .iter()
+        .position(|entry| matches!(entry.event, TraceEvent::Failed { .. }))
+        .expect("failure was not recorded");
+    assert_eq!(trace.entries[failed_at].depth, 1);
+    assert!(matches!(
+        trace.entries[failed_at + 1].event,
+        TraceEvent::ResetToFeeCheckpoint
+    ));
+}
+
 #[test]
 fn it_does_not_propagate_permissions() {
     let mut test = setup();
@@ -23,6 +23,8 @@ use std::iter;
 
 use tari_dan_engine::{
     packager::{PackageError, TemplateModuleLoader},
+    runtime::{RuntimeError, RuntimeModuleError},
+    transaction::TransactionError,
     wasm::{compile::compile_template, WasmExecutionError},
 };
 use tari_engine_types::{
@@ -122,6 +124,61 @@ fn test_composed() {
     assert_eq!(value, new_value);
 }
 
+#[test]
+fn read_only_execution_rejects_state_changes() {
+    let mut template_test = TemplateTest::new(vec!["tests/templates/state"]);
+    let component_address: ComponentAddress = template_test.call_function("State", "new", args![], vec![]);
+    template_test.call_method::<()>(component_address, "set", args![123u32], vec![]);
+
+    template_test.enable_read_only(100);
+    let value: u32 = template_test.call_method(component_address, "get", args![], vec![]);
+    assert_eq!(value, 123);
+
+    let err = template_test
+        .try_execute_instructions(
+            vec![],
+            vec![Instruction::CallMethod {
+                component_address,
+                method: "set".to_string(),
+                args: args![1u32],
+            }],
+            vec![],
+        )
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        TransactionError::RuntimeError(RuntimeError::ModuleError(
+            RuntimeModuleError::StateModificationNotPermitted { .. }
+        ))
+    ));
+}
+
+#[test]
+fn read_only_execution_limits_runtime_calls() {
+    let mut template_test = TemplateTest::new(vec!["tests/templates/state"]);
+    let component_address: ComponentAddress = template_test.call_function("State", "new", args![], vec![]);
+
+    // Loading the component is a runtime call
+    template_test.enable_read_only(0);
+    let err = template_test
+        .try_execute_instructions(
+            vec![],
+            vec![Instruction::CallMethod {
+                component_address,
+                method: "get".to_string(),
+                args: args![],
+            }],
+            vec![],
+        )
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        TransactionError::RuntimeError(RuntimeError::ModuleError(
+            RuntimeModuleError::RuntimeCallLimitExceeded { limit: 0 }
+        ))
+    ));
+}
+
 #[test]
 fn test_buggy_template() {
     let err = compile_template("tests/templates/buggy", &["return_null_abi"])
@@ -27,6 +27,7 @@ use tari_template_lib::Hash;
 
 use crate::{
     events::Event,
+    execution_trace::ExecutionTrace,
     fees::{FeeCostBreakdown, FeeReceipt},
     instruction_result::InstructionResult,
     logs::LogEntry,
@@ -101,6 +102,9 @@ pub struct FinalizeResult {
     pub execution_results: Vec<InstructionResult>,
     pub result: TransactionResult,
     pub cost_breakdown: Option<FeeCostBreakdown>,
+    /// Only recorded when execution tracing is enabled
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub execution_trace: Option<ExecutionTrace>,
 }
 
 impl FinalizeResult {
@@ -118,6 +122,7 @@ impl FinalizeResult {
             execution_results: Vec::new(),
             result,
             cost_breakdown: Some(cost_breakdown),
+            execution_trace: None,
         }
     }
 
@@ -129,6 +134,7 @@ impl FinalizeResult {
             execution_results: Vec::new(),
             result: TransactionResult::Reject(reason),
             cost_breakdown: None,
+            execution_trace: None,
         }
     }
 
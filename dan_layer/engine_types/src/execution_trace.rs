@@ -0,0 +1,72 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use serde::{Deserialize, Serialize};
+use tari_template_lib::models::{Amount, BucketId, ComponentAddress, ResourceAddress};
+
+use crate::{fees::FeeSource, lock::LockFlag, substate::SubstateAddress, TemplateAddress};
+
+/// A record of what the engine did while executing a transaction, in the order that it happened. Entries carry the
+/// call depth at which they occurred, so the call tree can be reconstructed from the `CallFunction` and `CallMethod`
+/// entries.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ExecutionTrace {
+    pub entries: Vec<TraceEntry>,
+}
+
+impl ExecutionTrace {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, depth: usize, event: TraceEvent) {
+        self.entries.push(TraceEntry { depth, event });
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TraceEntry {
+    /// The number of call frames on the stack when the entry was recorded. Top-level instructions run at depth 0 and
+    /// the body of a call runs at depth 1 more than the caller.
+    pub depth: usize,
+    pub event: TraceEvent,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum TraceEvent {
+    CallFunction {
+        template_address: TemplateAddress,
+        function: String,
+    },
+    CallMethod {
+        component_address: ComponentAddress,
+        method: String,
+    },
+    /// An engine operation invoked by the running template, e.g. `vault_invoke`
+    EngineCall {
+        call: String,
+    },
+    SubstateLocked {
+        address: SubstateAddress,
+        lock_flag: LockFlag,
+    },
+    BucketCreated {
+        bucket_id: BucketId,
+        resource_address: ResourceAddress,
+        amount: Amount,
+    },
+    /// The bucket was taken out of the call scope, e.g. to deposit it into a vault or to burn it
+    BucketTaken {
+        bucket_id: BucketId,
+        amount: Amount,
+    },
+    FeeCharged {
+        source: FeeSource,
+        amount: u64,
+    },
+    /// All state changes made after the fee instructions were discarded because the transaction failed
+    ResetToFeeCheckpoint,
+    Failed {
+        reason: String,
+    },
+}
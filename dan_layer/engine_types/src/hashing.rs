@@ -178,6 +178,7 @@ pub enum EngineHashDomainLabel {
     TransactionReceipt,
     FeeClaimAddress,
     QuorumCertificate,
+    DevTemplateAddress,
 }
 
 impl EngineHashDomainLabel {
@@ -201,6 +202,7 @@ impl EngineHashDomainLabel {
             Self::TransactionReceipt => "TransactionReceipt",
             Self::FeeClaimAddress => "FeeClaimAddress",
             Self::QuorumCertificate => "QuorumCertificate",
+            Self::DevTemplateAddress => "DevTemplateAddress",
         }
     }
 }
@@ -7,6 +7,7 @@ pub mod commit_result;
 pub mod component;
 pub mod confidential;
 pub mod events;
+pub mod execution_trace;
 pub mod fee_claim;
 pub mod fees;
 pub mod hashing;
@@ -27,7 +28,12 @@ pub mod vault;
 pub mod virtual_substate;
 
 mod template;
-pub use template::{calculate_template_binary_hash, parse_template_address, TemplateAddress};
+pub use template::{
+    calculate_dev_template_address,
+    calculate_template_binary_hash,
+    parse_template_address,
+    TemplateAddress,
+};
 
 mod argument_parser;
 pub use argument_parser::parse_arg;
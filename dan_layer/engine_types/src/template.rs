@@ -44,3 +44,39 @@ pub fn calculate_template_binary_hash(wasm_code: &[u8]) -> FixedHash {
     let hash = hasher32(EngineHashDomainLabel::Template).chain(wasm_code).result();
     FixedHash::from(hash.into_array())
 }
+
+/// Calculates the address of a template that is registered directly with validator nodes on a development network.
+/// The address only depends on the binary, so registering the same binary on any node results in the same address.
+pub fn calculate_dev_template_address(binary_hash: &FixedHash) -> TemplateAddress {
+    hasher32(EngineHashDomainLabel::DevTemplateAddress)
+        .chain(binary_hash.as_slice())
+        .result()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_derives_the_same_dev_template_address_for_the_same_binary() {
+        let binary_hash = calculate_template_binary_hash(b"template binary");
+        assert_eq!(
+            calculate_dev_template_address(&binary_hash),
+            calculate_dev_template_address(&calculate_template_binary_hash(b"template binary"))
+        );
+    }
+
+    #[test]
+    fn it_derives_different_dev_template_addresses_for_different_binaries() {
+        let a = calculate_dev_template_address(&calculate_template_binary_hash(b"template a"));
+        let b = calculate_dev_template_address(&calculate_template_binary_hash(b"template b"));
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn it_domain_separates_the_dev_template_address_from_the_binary_hash() {
+        let binary_hash = calculate_template_binary_hash(b"template binary");
+        let address = calculate_dev_template_address(&binary_hash);
+        assert_ne!(address.as_ref(), binary_hash.as_slice());
+    }
+}
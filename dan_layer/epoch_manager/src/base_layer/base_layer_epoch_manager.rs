@@ -27,7 +27,7 @@ use std::{
 };
 
 use log::*;
-use tari_base_node_client::{grpc::GrpcBaseNodeClient, types::BaseLayerConsensusConstants, BaseNodeClient};
+use tari_base_node_client::{types::BaseLayerConsensusConstants, BaseNodeClient};
 use tari_common_types::types::{FixedHash, PublicKey};
 use tari_comms::types::CommsPublicKey;
 use tari_core::{blocks::BlockHeader, transactions::transaction_components::ValidatorNodeRegistration};
@@ -40,8 +40,7 @@ use tari_dan_common_types::{
     Epoch,
     ShardId,
 };
-use tari_dan_storage::global::{models::ValidatorNode, DbEpoch, GlobalDb, MetadataKey};
-use tari_dan_storage_sqlite::global::SqliteGlobalDbAdapter;
+use tari_dan_storage::global::{models::ValidatorNode, DbEpoch, GlobalDb, GlobalDbAdapter, MetadataKey};
 use tari_mmr::MergedBalancedBinaryMerkleProof;
 use tokio::sync::broadcast;
 
@@ -61,13 +60,19 @@ pub struct BaseLayerEpochManager<TGlobalStore, TBaseNodeClient> {
     current_shard_key: Option<ShardId>,
     base_layer_consensus_constants: Option<BaseLayerConsensusConstants>,
     is_initial_base_layer_sync_complete: bool,
+    last_epoch_ending_published: Option<Epoch>,
 }
 
-impl BaseLayerEpochManager<SqliteGlobalDbAdapter, GrpcBaseNodeClient> {
+impl<TGlobalStore, TBaseNodeClient> BaseLayerEpochManager<TGlobalStore, TBaseNodeClient>
+where
+    TGlobalStore: GlobalDbAdapter,
+    TBaseNodeClient: BaseNodeClient,
+    EpochManagerError: From<TGlobalStore::Error>,
+{
     pub fn new(
         config: EpochManagerConfig,
-        global_db: GlobalDb<SqliteGlobalDbAdapter>,
-        base_node_client: GrpcBaseNodeClient,
+        global_db: GlobalDb<TGlobalStore>,
+        base_node_client: TBaseNodeClient,
         tx_events: broadcast::Sender<EpochManagerEvent>,
         node_public_key: CommsPublicKey,
     ) -> Self {
@@ -82,6 +87,7 @@ impl BaseLayerEpochManager<SqliteGlobalDbAdapter, GrpcBaseNodeClient> {
             current_shard_key: None,
             base_layer_consensus_constants: None,
             is_initial_base_layer_sync_complete: false,
+            last_epoch_ending_published: None,
         }
     }
 
@@ -107,6 +113,7 @@ impl BaseLayerEpochManager<SqliteGlobalDbAdapter, GrpcBaseNodeClient> {
         self.update_current_block_height(block_height)?;
         if self.current_epoch >= epoch {
             // no need to update the epoch
+            self.publish_epoch_ending_if_required(block_height, &base_layer_constants);
             return Ok(());
         }
 
@@ -127,6 +134,24 @@ impl BaseLayerEpochManager<SqliteGlobalDbAdapter, GrpcBaseNodeClient> {
         Ok(())
     }
 
+    fn publish_epoch_ending_if_required(&mut self, block_height: u64, constants: &BaseLayerConsensusConstants) {
+        if !self.is_initial_base_layer_sync_complete || self.last_epoch_ending_published == Some(self.current_epoch) {
+            return;
+        }
+        let next_epoch_height = constants.epoch_to_height(self.current_epoch + Epoch(1));
+        let blocks_remaining = next_epoch_height.saturating_sub(block_height);
+        if blocks_remaining > self.config.epoch_transition_blocks {
+            return;
+        }
+
+        info!(target: LOG_TARGET, "⏳ Epoch {} ends in {} block(s)", self.current_epoch, blocks_remaining);
+        self.last_epoch_ending_published = Some(self.current_epoch);
+        self.publish_event(EpochManagerEvent::EpochEnding {
+            epoch: self.current_epoch,
+            blocks_remaining,
+        });
+    }
+
     fn assign_validators_for_epoch(&mut self) -> Result<(), EpochManagerError> {
         let (start_epoch, end_epoch) = self.get_epoch_range(self.current_epoch)?;
         let mut tx = self.global_db.create_transaction()?;
@@ -139,7 +164,7 @@ impl BaseLayerEpochManager<SqliteGlobalDbAdapter, GrpcBaseNodeClient> {
         for vn in &vns {
             validator_nodes.set_committee_bucket(vn.shard_key, vn.shard_key.to_committee_bucket(num_committees))?;
         }
-        tx.commit()?;
+        self.global_db.commit(tx)?;
 
         if let Some(vn) = vns.iter().find(|vn| vn.address == self.node_public_key) {
             self.publish_event(EpochManagerEvent::ThisValidatorIsRegistered {
@@ -219,7 +244,7 @@ impl BaseLayerEpochManager<SqliteGlobalDbAdapter, GrpcBaseNodeClient> {
             );
         }
 
-        tx.commit()?;
+        self.global_db.commit(tx)?;
 
         Ok(())
     }
@@ -238,7 +263,7 @@ impl BaseLayerEpochManager<SqliteGlobalDbAdapter, GrpcBaseNodeClient> {
             .metadata(&mut tx)
             .set_metadata(MetadataKey::EpochManagerCurrentEpoch, &epoch)?;
 
-        tx.commit()?;
+        self.global_db.commit(tx)?;
         self.current_epoch = epoch;
         Ok(())
     }
@@ -251,7 +276,7 @@ impl BaseLayerEpochManager<SqliteGlobalDbAdapter, GrpcBaseNodeClient> {
         self.global_db
             .metadata(&mut tx)
             .set_metadata(MetadataKey::BaseLayerConsensusConstants, &base_layer_constants)?;
-        tx.commit()?;
+        self.global_db.commit(tx)?;
         self.base_layer_consensus_constants = Some(base_layer_constants);
         Ok(())
     }
@@ -261,7 +286,7 @@ impl BaseLayerEpochManager<SqliteGlobalDbAdapter, GrpcBaseNodeClient> {
         self.global_db
             .metadata(&mut tx)
             .set_metadata(MetadataKey::EpochManagerCurrentBlockHeight, &block_height)?;
-        tx.commit()?;
+        self.global_db.commit(tx)?;
         self.current_block_height = block_height;
         Ok(())
     }
@@ -332,7 +357,7 @@ impl BaseLayerEpochManager<SqliteGlobalDbAdapter, GrpcBaseNodeClient> {
         self.global_db
             .metadata(&mut tx)
             .set_metadata(MetadataKey::EpochManagerLastEpochRegistration, &epoch)?;
-        tx.commit()?;
+        self.global_db.commit(tx)?;
         Ok(())
     }
 
@@ -475,7 +500,7 @@ impl BaseLayerEpochManager<SqliteGlobalDbAdapter, GrpcBaseNodeClient> {
         let vn_bmt = ValidatorNodeBalancedMerkleTree::create(vn_bmt_leaves);
         let mut tx = self.global_db.create_transaction()?;
         self.global_db.bmt(&mut tx).insert_bmt(epoch.as_u64(), vn_bmt.clone())?;
-        tx.commit()?;
+        self.global_db.commit(tx)?;
         Ok(vn_bmt)
     }
 
@@ -647,7 +672,7 @@ impl BaseLayerEpochManager<SqliteGlobalDbAdapter, GrpcBaseNodeClient> {
         let mut tx = self.global_db.create_transaction()?;
         let mut metadata = self.global_db.metadata(&mut tx);
         metadata.set_metadata(MetadataKey::EpochManagerFeeClaimPublicKey, &public_key)?;
-        tx.commit()?;
+        self.global_db.commit(tx)?;
         Ok(())
     }
 
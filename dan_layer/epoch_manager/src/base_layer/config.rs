@@ -5,4 +5,7 @@
 pub struct EpochManagerConfig {
     pub base_layer_confirmations: u64,
     pub committee_size: u32,
+    /// The number of base layer blocks before the end of an epoch at which `EpochManagerEvent::EpochEnding` is
+    /// published
+    pub epoch_transition_blocks: u64,
 }
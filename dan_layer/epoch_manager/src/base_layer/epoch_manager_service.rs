@@ -21,10 +21,9 @@
 //  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
 use log::error;
-use tari_base_node_client::grpc::GrpcBaseNodeClient;
+use tari_base_node_client::BaseNodeClient;
 use tari_common_types::types::PublicKey;
-use tari_dan_storage::global::GlobalDb;
-use tari_dan_storage_sqlite::global::SqliteGlobalDbAdapter;
+use tari_dan_storage::global::{GlobalDb, GlobalDbAdapter};
 use tari_shutdown::ShutdownSignal;
 use tokio::{
     sync::{broadcast, mpsc::Receiver, oneshot},
@@ -49,13 +48,18 @@ pub struct EpochManagerService<TGlobalStore, TBaseNodeClient> {
     events: broadcast::Sender<EpochManagerEvent>,
 }
 
-impl EpochManagerService<SqliteGlobalDbAdapter, GrpcBaseNodeClient> {
+impl<TGlobalStore, TBaseNodeClient> EpochManagerService<TGlobalStore, TBaseNodeClient>
+where
+    TGlobalStore: GlobalDbAdapter + 'static,
+    TBaseNodeClient: BaseNodeClient + 'static,
+    EpochManagerError: From<TGlobalStore::Error>,
+{
     pub fn spawn(
         config: EpochManagerConfig,
         rx_request: Receiver<EpochManagerRequest>,
         shutdown: ShutdownSignal,
-        global_db: GlobalDb<SqliteGlobalDbAdapter>,
-        base_node_client: GrpcBaseNodeClient,
+        global_db: GlobalDb<TGlobalStore>,
+        base_node_client: TBaseNodeClient,
         node_public_key: PublicKey,
     ) -> JoinHandle<anyhow::Result<()>> {
         tokio::spawn(async move {
@@ -20,22 +20,29 @@
 //  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 //  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use tari_base_node_client::grpc::GrpcBaseNodeClient;
+use tari_base_node_client::BaseNodeClient;
 use tari_common_types::types::PublicKey;
-use tari_dan_storage::global::GlobalDb;
-use tari_dan_storage_sqlite::global::SqliteGlobalDbAdapter;
+use tari_dan_storage::global::{GlobalDb, GlobalDbAdapter};
 use tari_shutdown::ShutdownSignal;
 use tokio::{sync::mpsc, task::JoinHandle};
 
-use crate::base_layer::{config::EpochManagerConfig, epoch_manager_service::EpochManagerService, EpochManagerHandle};
+use crate::{
+    base_layer::{config::EpochManagerConfig, epoch_manager_service::EpochManagerService, EpochManagerHandle},
+    EpochManagerError,
+};
 
-pub fn spawn_service(
+pub fn spawn_service<TGlobalStore, TBaseNodeClient>(
     config: EpochManagerConfig,
-    global_db: GlobalDb<SqliteGlobalDbAdapter>,
-    base_node_client: GrpcBaseNodeClient,
+    global_db: GlobalDb<TGlobalStore>,
+    base_node_client: TBaseNodeClient,
     node_public_key: PublicKey,
     shutdown: ShutdownSignal,
-) -> (EpochManagerHandle, JoinHandle<anyhow::Result<()>>) {
+) -> (EpochManagerHandle, JoinHandle<anyhow::Result<()>>)
+where
+    TGlobalStore: GlobalDbAdapter + 'static,
+    TBaseNodeClient: BaseNodeClient + 'static,
+    EpochManagerError: From<TGlobalStore::Error>,
+{
     let (tx_request, rx_request) = mpsc::channel(10);
     let epoch_manager = EpochManagerHandle::new(tx_request);
     let handle = EpochManagerService::spawn(
@@ -3,6 +3,7 @@
 
 use tari_common_types::types::PublicKey;
 use tari_dan_common_types::{optional::IsNotFoundError, Epoch, ShardId};
+use tari_dan_storage::StorageError;
 
 #[derive(thiserror::Error, Debug)]
 pub enum EpochManagerError {
@@ -22,6 +23,8 @@ pub enum EpochManagerError {
     UnexpectedResponse,
     #[error("SQLite Storage error: {0}")]
     SqlLiteStorageError(anyhow::Error),
+    #[error("Storage error: {0}")]
+    StorageError(#[from] StorageError),
     #[error("No validator nodes found for current shard key")]
     ValidatorNodesNotFound,
     #[error("No committee VNs found for shard {shard_id} and epoch {epoch}")]
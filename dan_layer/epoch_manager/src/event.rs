@@ -6,5 +6,14 @@ use tari_dan_common_types::{Epoch, ShardId};
 #[derive(Debug, Clone)]
 pub enum EpochManagerEvent {
     EpochChanged(Epoch),
-    ThisValidatorIsRegistered { epoch: Epoch, shard_key: ShardId },
+    /// The current epoch will end within the configured number of base layer blocks. This is published at most once
+    /// per epoch.
+    EpochEnding {
+        epoch: Epoch,
+        blocks_remaining: u64,
+    },
+    ThisValidatorIsRegistered {
+        epoch: Epoch,
+        shard_key: ShardId,
+    },
 }
@@ -0,0 +1,223 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+#![cfg(feature = "base_layer")]
+
+use async_trait::async_trait;
+use rand::rngs::OsRng;
+use tari_base_node_client::{
+    types::{BaseLayerConsensusConstants, BaseLayerMetadata, SideChainUtxos, ValidatorNode},
+    BaseNodeClient,
+    BaseNodeClientError,
+};
+use tari_common_types::types::{FixedHash, PublicKey};
+use tari_core::{
+    blocks::BlockHeader,
+    transactions::{tari_amount::MicroMinotari, transaction_components::CodeTemplateRegistration},
+};
+use tari_crypto::{keys::PublicKey as _, tari_utilities::ByteArray};
+use tari_dan_common_types::{Epoch, ShardId};
+use tari_dan_storage::global::{GlobalDb, MemoryGlobalDbAdapter};
+use tari_epoch_manager::{
+    base_layer::{BaseLayerEpochManager, EpochManagerConfig},
+    EpochManagerEvent,
+};
+use tokio::sync::broadcast;
+
+const EPOCH_LENGTH: u64 = 10;
+
+#[derive(Debug, Clone, Default)]
+struct TestBaseNodeClient;
+
+#[async_trait]
+impl BaseNodeClient for TestBaseNodeClient {
+    async fn test_connection(&mut self) -> Result<(), BaseNodeClientError> {
+        Ok(())
+    }
+
+    async fn get_tip_info(&mut self) -> Result<BaseLayerMetadata, BaseNodeClientError> {
+        Ok(BaseLayerMetadata {
+            height_of_longest_chain: 0,
+            tip_hash: FixedHash::zero(),
+        })
+    }
+
+    async fn get_validator_nodes(&mut self, _height: u64) -> Result<Vec<ValidatorNode>, BaseNodeClientError> {
+        Ok(vec![])
+    }
+
+    async fn get_shard_key(
+        &mut self,
+        _height: u64,
+        public_key: &PublicKey,
+    ) -> Result<Option<ShardId>, BaseNodeClientError> {
+        Ok(Some(derived_shard_id(public_key)))
+    }
+
+    async fn get_template_registrations(
+        &mut self,
+        _start_hash: Option<FixedHash>,
+        _count: u64,
+    ) -> Result<Vec<CodeTemplateRegistration>, BaseNodeClientError> {
+        Ok(vec![])
+    }
+
+    async fn get_header_by_hash(&mut self, _block_hash: FixedHash) -> Result<BlockHeader, BaseNodeClientError> {
+        Ok(BlockHeader::new(0))
+    }
+
+    async fn get_consensus_constants(&mut self, _tip: u64) -> Result<BaseLayerConsensusConstants, BaseNodeClientError> {
+        Ok(BaseLayerConsensusConstants {
+            validator_node_registration_expiry: 100,
+            epoch_length: EPOCH_LENGTH,
+            validator_node_registration_min_deposit_amount: MicroMinotari(0),
+        })
+    }
+
+    async fn get_sidechain_utxos(
+        &mut self,
+        _start_hash: Option<FixedHash>,
+        _count: u64,
+    ) -> Result<Vec<SideChainUtxos>, BaseNodeClientError> {
+        Ok(vec![])
+    }
+}
+
+type TestEpochManager = BaseLayerEpochManager<MemoryGlobalDbAdapter, TestBaseNodeClient>;
+
+fn new_public_key() -> PublicKey {
+    PublicKey::random_keypair(&mut OsRng).1
+}
+
+fn derived_shard_id(public_key: &PublicKey) -> ShardId {
+    ShardId::from_bytes(public_key.as_bytes()).unwrap()
+}
+
+fn create_epoch_manager(
+    adapter: MemoryGlobalDbAdapter,
+    node_public_key: PublicKey,
+) -> (TestEpochManager, broadcast::Receiver<EpochManagerEvent>) {
+    let (tx_events, rx_events) = broadcast::channel(10);
+    let config = EpochManagerConfig {
+        base_layer_confirmations: 0,
+        committee_size: 2,
+        epoch_transition_blocks: 2,
+    };
+    let epoch_manager = BaseLayerEpochManager::new(
+        config,
+        GlobalDb::new(adapter),
+        TestBaseNodeClient,
+        tx_events,
+        node_public_key,
+    );
+    (epoch_manager, rx_events)
+}
+
+fn insert_validator_nodes(adapter: &MemoryGlobalDbAdapter, public_keys: &[PublicKey], epoch: Epoch) {
+    let db = GlobalDb::new(adapter.clone());
+    let mut tx = db.create_transaction().unwrap();
+    let mut validator_nodes = db.validator_nodes(&mut tx);
+    for public_key in public_keys {
+        validator_nodes
+            .insert_validator_node(
+                public_key.clone(),
+                derived_shard_id(public_key),
+                epoch,
+                public_key.clone(),
+            )
+            .unwrap();
+    }
+    db.commit(tx).unwrap();
+}
+
+#[tokio::test]
+async fn it_reloads_the_persisted_epoch_state() {
+    let adapter = MemoryGlobalDbAdapter::new();
+    let node_public_key = new_public_key();
+    let (mut epoch_manager, _rx_events) = create_epoch_manager(adapter.clone(), node_public_key.clone());
+    epoch_manager.load_initial_state().await.unwrap();
+    assert_eq!(epoch_manager.current_epoch(), Epoch(0));
+
+    epoch_manager
+        .update_epoch(2 * EPOCH_LENGTH + 1, FixedHash::zero())
+        .await
+        .unwrap();
+    assert_eq!(epoch_manager.current_epoch(), Epoch(2));
+    drop(epoch_manager);
+
+    let (mut epoch_manager, _rx_events) = create_epoch_manager(adapter, node_public_key);
+    epoch_manager.load_initial_state().await.unwrap();
+    assert_eq!(epoch_manager.current_epoch(), Epoch(2));
+    assert_eq!(epoch_manager.current_block_height(), 2 * EPOCH_LENGTH + 1);
+}
+
+#[tokio::test]
+async fn it_assigns_committee_buckets_when_the_epoch_changes() {
+    let adapter = MemoryGlobalDbAdapter::new();
+    let public_keys = (0..4).map(|_| new_public_key()).collect::<Vec<_>>();
+    insert_validator_nodes(&adapter, &public_keys, Epoch(1));
+
+    let (mut epoch_manager, mut rx_events) = create_epoch_manager(adapter, public_keys[0].clone());
+    epoch_manager.load_initial_state().await.unwrap();
+    epoch_manager
+        .update_epoch(EPOCH_LENGTH, FixedHash::zero())
+        .await
+        .unwrap();
+
+    assert_eq!(epoch_manager.get_number_of_committees(Epoch(1)).unwrap(), 2);
+    let vns = epoch_manager.get_validator_nodes_per_epoch(Epoch(1)).unwrap();
+    assert_eq!(vns.len(), 4);
+    assert!(vns.iter().all(|vn| vn.committee_bucket.is_some()));
+
+    let EpochManagerEvent::ThisValidatorIsRegistered { epoch, shard_key } = rx_events.try_recv().unwrap() else {
+        panic!("Expected ThisValidatorIsRegistered event");
+    };
+    assert_eq!(epoch, Epoch(1));
+    assert_eq!(shard_key, derived_shard_id(&public_keys[0]));
+}
+
+#[tokio::test]
+async fn it_publishes_epoch_events_once_the_initial_scan_is_complete() {
+    let (mut epoch_manager, mut rx_events) = create_epoch_manager(MemoryGlobalDbAdapter::new(), new_public_key());
+    epoch_manager.load_initial_state().await.unwrap();
+
+    epoch_manager
+        .update_epoch(EPOCH_LENGTH, FixedHash::zero())
+        .await
+        .unwrap();
+    assert!(rx_events.try_recv().is_err());
+
+    epoch_manager.on_scanning_complete().await.unwrap();
+    assert!(matches!(
+        rx_events.try_recv().unwrap(),
+        EpochManagerEvent::EpochChanged(Epoch(1))
+    ));
+
+    epoch_manager
+        .update_epoch(2 * EPOCH_LENGTH - 2, FixedHash::zero())
+        .await
+        .unwrap();
+    assert!(matches!(
+        rx_events.try_recv().unwrap(),
+        EpochManagerEvent::EpochEnding {
+            epoch: Epoch(1),
+            blocks_remaining: 2
+        }
+    ));
+
+    // The epoch ending event is only published once per epoch
+    epoch_manager
+        .update_epoch(2 * EPOCH_LENGTH - 1, FixedHash::zero())
+        .await
+        .unwrap();
+    assert!(rx_events.try_recv().is_err());
+
+    epoch_manager
+        .update_epoch(2 * EPOCH_LENGTH, FixedHash::zero())
+        .await
+        .unwrap();
+    assert!(matches!(
+        rx_events.try_recv().unwrap(),
+        EpochManagerEvent::EpochChanged(Epoch(2))
+    ));
+}
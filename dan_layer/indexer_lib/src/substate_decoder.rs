@@ -20,7 +20,10 @@
 //  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 //  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use std::collections::HashSet;
+use std::{
+    collections::{HashMap, HashSet},
+    future::Future,
+};
 
 use log::*;
 use tari_engine_types::{
@@ -73,3 +76,38 @@ pub fn find_related_substates(substate: &Substate) -> Result<Vec<SubstateAddress
         _ => Ok(vec![]),
     }
 }
+
+/// Fetches the substate at `address` and the substates that it references, following references at most `max_depth`
+/// levels deep (e.g. component -> vault -> resource). Substates for which `fetch` returns None are skipped, so the
+/// result does not contain `address` if it was not found.
+pub async fn fetch_substate_and_references<F, Fut, E>(
+    address: SubstateAddress,
+    max_depth: usize,
+    mut fetch: F,
+) -> Result<HashMap<SubstateAddress, Substate>, E>
+where
+    F: FnMut(SubstateAddress) -> Fut,
+    Fut: Future<Output = Result<Option<Substate>, E>>,
+    E: From<IndexedValueError>,
+{
+    let mut found = HashMap::new();
+    let mut visited = HashSet::new();
+    let mut pending = vec![address];
+
+    for _ in 0..=max_depth {
+        let mut next = Vec::new();
+        for address in pending {
+            if !visited.insert(address.clone()) {
+                continue;
+            }
+            let Some(substate) = fetch(address.clone()).await? else {
+                continue;
+            };
+            next.extend(find_related_substates(&substate)?);
+            found.insert(address, substate);
+        }
+        pending = next;
+    }
+
+    Ok(found)
+}
@@ -50,7 +50,6 @@ where
 
         // scan the network to fetch all the substates for each required input
         // TODO: perform this loop concurrently by spawning a tokio task for each scan
-        let mut input_shards = vec![];
         let mut found_substates = HashMap::new();
         for r in &substate_requirements {
             let scan_res = match r.version() {
@@ -80,11 +79,10 @@ where
                     substate.version()
                 );
                 let shard = ShardId::from_address(&address, substate.version());
-                if autofilled_transaction.all_inputs_iter().any(|s| *s == shard) {
-                    // Shard is already an input (TODO: what a waste)
+                // Requirements may refer to the same substate more than once
+                if !autofilled_transaction.add_filled_input(shard) {
                     continue;
                 }
-                input_shards.push(shard);
                 found_substates.insert(address, substate);
             } else {
                 warn!(
@@ -95,7 +93,6 @@ where
         }
 
         info!(target: LOG_TARGET, "✏️️ Found {} input substates", found_substates.len());
-        autofilled_transaction.filled_inputs_mut().extend(input_shards);
 
         // let mut found_this_round = 0;
 
@@ -106,7 +103,6 @@ where
             // TODO: perform this loop concurrently by spawning a tokio task for each scan
             // TODO: we are going to only check the first level of recursion, for composability we may want to do it
             // recursively (with a recursion limit)
-            let mut autofilled_inputs = vec![];
             let related_addresses: Vec<Vec<SubstateAddress>> = found_substates
                 .values()
                 .map(find_related_substates)
@@ -134,11 +130,10 @@ where
                         substate.version()
                     );
                     let shard = ShardId::from_address(&address, substate.version());
-                    if autofilled_transaction.all_inputs_iter().any(|s| *s == shard) {
-                        // Shard is already an input (TODO: what a waste)
+                    // Several substates may refer to the same related substate
+                    if !autofilled_transaction.add_filled_input(shard) {
                         continue;
                     }
-                    autofilled_inputs.push(ShardId::from_address(&address, substate.version()));
                     found_substates.insert(address, substate);
                 //       found_this_round += 1;
                 } else {
@@ -149,7 +144,6 @@ where
                 }
             }
 
-            autofilled_transaction.filled_inputs_mut().extend(autofilled_inputs);
             //   if found_this_round == 0 {
             //      break;
             // }
@@ -0,0 +1,35 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use std::{fmt, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StateStoreBackend {
+    #[default]
+    Sqlite,
+    Lmdb,
+}
+
+impl fmt::Display for StateStoreBackend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Sqlite => write!(f, "sqlite"),
+            Self::Lmdb => write!(f, "lmdb"),
+        }
+    }
+}
+
+impl FromStr for StateStoreBackend {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "sqlite" => Ok(Self::Sqlite),
+            "lmdb" => Ok(Self::Lmdb),
+            _ => Err(format!("Unknown state store backend '{}'. Expected sqlite or lmdb", s)),
+        }
+    }
+}
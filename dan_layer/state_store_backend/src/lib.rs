@@ -0,0 +1,15 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+//! A state store that can be backed by either the SQLite or the LMDB state store, selected at runtime.
+
+mod backend;
+mod metrics;
+mod reader;
+mod store;
+mod writer;
+
+pub use backend::StateStoreBackend;
+pub use reader::AnyStateStoreReadTransaction;
+pub use store::AnyStateStore;
+pub use writer::AnyStateStoreWriteTransaction;
@@ -0,0 +1,22 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use lazy_static::lazy_static;
+use prometheus::{HistogramOpts, HistogramVec};
+use tari_dan_common_types::metrics::register;
+
+lazy_static! {
+    pub static ref COMMIT_SECONDS: HistogramVec = register(
+        HistogramVec::new(
+            HistogramOpts::new(
+                "state_store_commit_seconds",
+                "The time taken to commit a state store write transaction"
+            )
+            .buckets(vec![
+                0.0005, 0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0
+            ]),
+            &["backend"]
+        )
+        .unwrap()
+    );
+}
@@ -0,0 +1,406 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use std::{borrow::Borrow, collections::HashSet, ops::RangeInclusive};
+
+use serde::{de::DeserializeOwned, Serialize};
+use tari_common_types::types::FixedHash;
+use tari_dan_common_types::{Epoch, NodeAddressable, NodeHeight, ShardId};
+use tari_dan_storage::{
+    consensus_models::{
+        Block,
+        BlockId,
+        HighQc,
+        LastExecuted,
+        LastProposed,
+        LastSentVote,
+        LastVoted,
+        LeafBlock,
+        LockedBlock,
+        LockedOutput,
+        QcId,
+        QuorumCertificate,
+        SubstateLockFlag,
+        SubstateLockState,
+        SubstatePruneMark,
+        SubstateRecord,
+        TransactionPoolRecord,
+        TransactionPoolStage,
+        TransactionRecord,
+        Vote,
+    },
+    Ordering,
+    StateStoreReadTransaction,
+    StorageError,
+};
+use tari_engine_types::substate::SubstateAddress;
+use tari_state_store_lmdb::{LmdbStateStoreReadTransaction, LmdbStateStoreWriteTransaction};
+use tari_state_store_sqlite::{SqliteStateStoreReadTransaction, SqliteStateStoreWriteTransaction};
+use tari_transaction::TransactionId;
+
+/// A read transaction on either backend. The write variants are used by
+/// [AnyStateStoreWriteTransaction](crate::AnyStateStoreWriteTransaction), which must deref to this type.
+pub enum AnyStateStoreReadTransaction<'a, TAddr> {
+    SqliteRead(SqliteStateStoreReadTransaction<'a, TAddr>),
+    SqliteWrite(SqliteStateStoreWriteTransaction<'a, TAddr>),
+    LmdbRead(LmdbStateStoreReadTransaction<'a, TAddr>),
+    LmdbWrite(LmdbStateStoreWriteTransaction<'a, TAddr>),
+}
+
+/// Calls the same read transaction method on whichever backend transaction is active
+macro_rules! dispatch_read {
+    ($self:ident, |$tx:ident| $body:expr) => {
+        match $self {
+            AnyStateStoreReadTransaction::SqliteRead($tx) => $body,
+            AnyStateStoreReadTransaction::SqliteWrite(write_tx) => {
+                let $tx = &mut **write_tx;
+                $body
+            },
+            AnyStateStoreReadTransaction::LmdbRead($tx) => $body,
+            AnyStateStoreReadTransaction::LmdbWrite(write_tx) => {
+                let $tx = &mut **write_tx;
+                $body
+            },
+        }
+    };
+}
+
+impl<TAddr: NodeAddressable + Serialize + DeserializeOwned> StateStoreReadTransaction
+    for AnyStateStoreReadTransaction<'_, TAddr>
+{
+    type Addr = TAddr;
+
+    fn last_sent_vote_get(&mut self) -> Result<LastSentVote<Self::Addr>, StorageError> {
+        dispatch_read!(self, |tx| tx.last_sent_vote_get())
+    }
+
+    fn last_voted_get(&mut self) -> Result<LastVoted, StorageError> {
+        dispatch_read!(self, |tx| tx.last_voted_get())
+    }
+
+    fn last_executed_get(&mut self) -> Result<LastExecuted, StorageError> {
+        dispatch_read!(self, |tx| tx.last_executed_get())
+    }
+
+    fn last_proposed_get(&mut self) -> Result<LastProposed, StorageError> {
+        dispatch_read!(self, |tx| tx.last_proposed_get())
+    }
+
+    fn locked_block_get(&mut self) -> Result<LockedBlock, StorageError> {
+        dispatch_read!(self, |tx| tx.locked_block_get())
+    }
+
+    fn leaf_block_get(&mut self) -> Result<LeafBlock, StorageError> {
+        dispatch_read!(self, |tx| tx.leaf_block_get())
+    }
+
+    fn high_qc_get(&mut self) -> Result<HighQc, StorageError> {
+        dispatch_read!(self, |tx| tx.high_qc_get())
+    }
+
+    fn transactions_get(&mut self, tx_id: &TransactionId) -> Result<TransactionRecord, StorageError> {
+        dispatch_read!(self, |tx| tx.transactions_get(tx_id))
+    }
+
+    fn transactions_exists(&mut self, tx_id: &TransactionId) -> Result<bool, StorageError> {
+        dispatch_read!(self, |tx| tx.transactions_exists(tx_id))
+    }
+
+    fn transactions_get_any<'a, I: IntoIterator<Item = &'a TransactionId>>(
+        &mut self,
+        tx_ids: I,
+    ) -> Result<Vec<TransactionRecord>, StorageError> {
+        dispatch_read!(self, |tx| tx.transactions_get_any(tx_ids))
+    }
+
+    fn transactions_get_paginated(
+        &mut self,
+        limit: u64,
+        offset: u64,
+        asc_desc_created_at: Option<Ordering>,
+    ) -> Result<Vec<TransactionRecord>, StorageError> {
+        dispatch_read!(self, |tx| tx.transactions_get_paginated(
+            limit,
+            offset,
+            asc_desc_created_at
+        ))
+    }
+
+    fn blocks_get(&mut self, block_id: &BlockId) -> Result<Block<Self::Addr>, StorageError> {
+        dispatch_read!(self, |tx| tx.blocks_get(block_id))
+    }
+
+    fn blocks_get_tip(&mut self) -> Result<Block<Self::Addr>, StorageError> {
+        dispatch_read!(self, |tx| tx.blocks_get_tip())
+    }
+
+    fn blocks_get_all_between(
+        &mut self,
+        start_block_id_exclusive: &BlockId,
+        end_block_id_inclusive: &BlockId,
+    ) -> Result<Vec<Block<Self::Addr>>, StorageError> {
+        dispatch_read!(self, |tx| tx
+            .blocks_get_all_between(start_block_id_exclusive, end_block_id_inclusive))
+    }
+
+    fn blocks_exists(&mut self, block_id: &BlockId) -> Result<bool, StorageError> {
+        dispatch_read!(self, |tx| tx.blocks_exists(block_id))
+    }
+
+    fn blocks_is_ancestor(&mut self, descendant: &BlockId, ancestor: &BlockId) -> Result<bool, StorageError> {
+        dispatch_read!(self, |tx| tx.blocks_is_ancestor(descendant, ancestor))
+    }
+
+    fn blocks_get_all_by_parent(&mut self, parent: &BlockId) -> Result<Vec<Block<Self::Addr>>, StorageError> {
+        dispatch_read!(self, |tx| tx.blocks_get_all_by_parent(parent))
+    }
+
+    fn blocks_get_parent_chain(
+        &mut self,
+        block_id: &BlockId,
+        limit: usize,
+    ) -> Result<Vec<Block<Self::Addr>>, StorageError> {
+        dispatch_read!(self, |tx| tx.blocks_get_parent_chain(block_id, limit))
+    }
+
+    fn blocks_get_pending_transactions(&mut self, block_id: &BlockId) -> Result<Vec<TransactionId>, StorageError> {
+        dispatch_read!(self, |tx| tx.blocks_get_pending_transactions(block_id))
+    }
+
+    fn blocks_get_total_leader_fee_for_epoch(
+        &mut self,
+        epoch: Epoch,
+        validator_public_key: &Self::Addr,
+    ) -> Result<u64, StorageError> {
+        dispatch_read!(self, |tx| tx
+            .blocks_get_total_leader_fee_for_epoch(epoch, validator_public_key))
+    }
+
+    fn blocks_get_any_with_epoch_range(
+        &mut self,
+        epoch_range: RangeInclusive<Epoch>,
+        validator_public_key: Option<&Self::Addr>,
+    ) -> Result<Vec<Block<Self::Addr>>, StorageError> {
+        dispatch_read!(self, |tx| tx
+            .blocks_get_any_with_epoch_range(epoch_range, validator_public_key))
+    }
+
+    fn blocks_get_paginated(
+        &mut self,
+        limit: u64,
+        offset: u64,
+        asc_desc_created_at: Option<Ordering>,
+    ) -> Result<Vec<Block<Self::Addr>>, StorageError> {
+        dispatch_read!(self, |tx| tx.blocks_get_paginated(limit, offset, asc_desc_created_at))
+    }
+
+    fn blocks_get_count(&mut self) -> Result<i64, StorageError> {
+        dispatch_read!(self, |tx| tx.blocks_get_count())
+    }
+
+    fn blocks_max_height(&mut self) -> Result<NodeHeight, StorageError> {
+        dispatch_read!(self, |tx| tx.blocks_max_height())
+    }
+
+    fn parked_blocks_exists(&mut self, block_id: &BlockId) -> Result<bool, StorageError> {
+        dispatch_read!(self, |tx| tx.parked_blocks_exists(block_id))
+    }
+
+    fn quorum_certificates_get(&mut self, qc_id: &QcId) -> Result<QuorumCertificate<Self::Addr>, StorageError> {
+        dispatch_read!(self, |tx| tx.quorum_certificates_get(qc_id))
+    }
+
+    fn quorum_certificates_get_all<'a, I: IntoIterator<Item = &'a QcId>>(
+        &mut self,
+        qc_ids: I,
+    ) -> Result<Vec<QuorumCertificate<Self::Addr>>, StorageError> {
+        dispatch_read!(self, |tx| tx.quorum_certificates_get_all(qc_ids))
+    }
+
+    fn quorum_certificates_get_by_block_id(
+        &mut self,
+        block_id: &BlockId,
+    ) -> Result<QuorumCertificate<Self::Addr>, StorageError> {
+        dispatch_read!(self, |tx| tx.quorum_certificates_get_by_block_id(block_id))
+    }
+
+    fn transaction_pool_get(
+        &mut self,
+        from_block_id: &BlockId,
+        to_block_id: &BlockId,
+        transaction_id: &TransactionId,
+    ) -> Result<TransactionPoolRecord, StorageError> {
+        dispatch_read!(self, |tx| tx.transaction_pool_get(
+            from_block_id,
+            to_block_id,
+            transaction_id
+        ))
+    }
+
+    fn transaction_pool_exists(&mut self, transaction_id: &TransactionId) -> Result<bool, StorageError> {
+        dispatch_read!(self, |tx| tx.transaction_pool_exists(transaction_id))
+    }
+
+    fn transaction_pool_get_in_progress(&mut self) -> Result<Vec<TransactionPoolRecord>, StorageError> {
+        dispatch_read!(self, |tx| tx.transaction_pool_get_in_progress())
+    }
+
+    fn transaction_pool_get_new_by_fee_priority(
+        &mut self,
+        after: Option<(u64, &TransactionId)>,
+        limit: usize,
+    ) -> Result<Vec<TransactionPoolRecord>, StorageError> {
+        dispatch_read!(self, |tx| tx.transaction_pool_get_new_by_fee_priority(after, limit))
+    }
+
+    fn transaction_pool_get_page(
+        &mut self,
+        after: Option<&TransactionId>,
+        limit: usize,
+    ) -> Result<Vec<TransactionPoolRecord>, StorageError> {
+        dispatch_read!(self, |tx| tx.transaction_pool_get_page(after, limit))
+    }
+
+    fn transaction_pool_count(
+        &mut self,
+        stage: Option<TransactionPoolStage>,
+        is_ready: Option<bool>,
+        has_foreign_data: Option<bool>,
+    ) -> Result<usize, StorageError> {
+        dispatch_read!(self, |tx| tx.transaction_pool_count(stage, is_ready, has_foreign_data))
+    }
+
+    fn transactions_fetch_involved_shards(
+        &mut self,
+        transaction_ids: HashSet<TransactionId>,
+    ) -> Result<HashSet<ShardId>, StorageError> {
+        dispatch_read!(self, |tx| tx.transactions_fetch_involved_shards(transaction_ids))
+    }
+
+    fn votes_get_by_block_and_sender(
+        &mut self,
+        block_id: &BlockId,
+        sender_leaf_hash: &FixedHash,
+    ) -> Result<Vote<Self::Addr>, StorageError> {
+        dispatch_read!(self, |tx| tx.votes_get_by_block_and_sender(block_id, sender_leaf_hash))
+    }
+
+    fn votes_count_for_block(&mut self, block_id: &BlockId) -> Result<u64, StorageError> {
+        dispatch_read!(self, |tx| tx.votes_count_for_block(block_id))
+    }
+
+    fn votes_get_for_block(&mut self, block_id: &BlockId) -> Result<Vec<Vote<Self::Addr>>, StorageError> {
+        dispatch_read!(self, |tx| tx.votes_get_for_block(block_id))
+    }
+
+    fn substates_get(&mut self, substate_id: &ShardId) -> Result<SubstateRecord, StorageError> {
+        dispatch_read!(self, |tx| tx.substates_get(substate_id))
+    }
+
+    fn substates_get_any(&mut self, substate_ids: &HashSet<ShardId>) -> Result<Vec<SubstateRecord>, StorageError> {
+        dispatch_read!(self, |tx| tx.substates_get_any(substate_ids))
+    }
+
+    fn substates_any_exist<I, S>(&mut self, substates: I) -> Result<bool, StorageError>
+    where
+        I: IntoIterator<Item = S>,
+        S: Borrow<ShardId>,
+    {
+        dispatch_read!(self, |tx| tx.substates_any_exist(substates))
+    }
+
+    fn substates_exists_for_transaction(&mut self, transaction_id: &TransactionId) -> Result<bool, StorageError> {
+        dispatch_read!(self, |tx| tx.substates_exists_for_transaction(transaction_id))
+    }
+
+    fn substates_get_many_within_range(
+        &mut self,
+        start: &ShardId,
+        end: &ShardId,
+        exclude_shards: &[ShardId],
+    ) -> Result<Vec<SubstateRecord>, StorageError> {
+        dispatch_read!(self, |tx| tx.substates_get_many_within_range(
+            start,
+            end,
+            exclude_shards
+        ))
+    }
+
+    fn substates_get_live_page_within_range(
+        &mut self,
+        start: &ShardId,
+        end: &ShardId,
+        after: Option<&ShardId>,
+        limit: usize,
+    ) -> Result<Vec<SubstateRecord>, StorageError> {
+        dispatch_read!(self, |tx| tx
+            .substates_get_live_page_within_range(start, end, after, limit))
+    }
+
+    fn substates_get_page_within_range(
+        &mut self,
+        start: &ShardId,
+        end: &ShardId,
+        after: Option<&ShardId>,
+        limit: usize,
+    ) -> Result<Vec<SubstateRecord>, StorageError> {
+        dispatch_read!(self, |tx| tx.substates_get_page_within_range(start, end, after, limit))
+    }
+
+    fn substates_get_many_by_created_transaction(
+        &mut self,
+        tx_id: &TransactionId,
+    ) -> Result<Vec<SubstateRecord>, StorageError> {
+        dispatch_read!(self, |tx| tx.substates_get_many_by_created_transaction(tx_id))
+    }
+
+    fn substates_get_many_by_destroyed_transaction(
+        &mut self,
+        tx_id: &TransactionId,
+    ) -> Result<Vec<SubstateRecord>, StorageError> {
+        dispatch_read!(self, |tx| tx.substates_get_many_by_destroyed_transaction(tx_id))
+    }
+
+    fn substates_get_all_for_block(&mut self, block_id: &BlockId) -> Result<Vec<SubstateRecord>, StorageError> {
+        dispatch_read!(self, |tx| tx.substates_get_all_for_block(block_id))
+    }
+
+    fn substates_get_all_for_transaction(
+        &mut self,
+        transaction_id: &TransactionId,
+    ) -> Result<Vec<SubstateRecord>, StorageError> {
+        dispatch_read!(self, |tx| tx.substates_get_all_for_transaction(transaction_id))
+    }
+
+    fn substates_get_all_versions(&mut self, address: &SubstateAddress) -> Result<Vec<SubstateRecord>, StorageError> {
+        dispatch_read!(self, |tx| tx.substates_get_all_versions(address))
+    }
+
+    fn substates_get_latest_version(&mut self, address: &SubstateAddress) -> Result<SubstateRecord, StorageError> {
+        dispatch_read!(self, |tx| tx.substates_get_latest_version(address))
+    }
+
+    fn substates_get_prune_mark(&mut self) -> Result<Option<SubstatePruneMark>, StorageError> {
+        dispatch_read!(self, |tx| tx.substates_get_prune_mark())
+    }
+
+    fn substates_check_lock_many<'a, I: IntoIterator<Item = &'a ShardId>>(
+        &mut self,
+        objects: I,
+        lock_flag: SubstateLockFlag,
+    ) -> Result<SubstateLockState, StorageError> {
+        dispatch_read!(self, |tx| tx.substates_check_lock_many(objects, lock_flag))
+    }
+
+    fn locked_outputs_check_all<I, B>(&mut self, output_shards: I) -> Result<SubstateLockState, StorageError>
+    where
+        I: IntoIterator<Item = B>,
+        B: Borrow<ShardId>,
+    {
+        dispatch_read!(self, |tx| tx.locked_outputs_check_all(output_shards))
+    }
+
+    fn locked_outputs_get_all(&mut self) -> Result<Vec<LockedOutput>, StorageError> {
+        dispatch_read!(self, |tx| tx.locked_outputs_get_all())
+    }
+}
@@ -0,0 +1,94 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use std::{fmt, path::Path};
+
+use serde::{de::DeserializeOwned, Serialize};
+use tari_dan_common_types::NodeAddressable;
+use tari_dan_storage::{StateStore, StorageError};
+use tari_state_store_lmdb::LmdbStateStore;
+use tari_state_store_sqlite::SqliteStateStore;
+
+use crate::{backend::StateStoreBackend, reader::AnyStateStoreReadTransaction, writer::AnyStateStoreWriteTransaction};
+
+pub enum AnyStateStore<TAddr> {
+    Sqlite(SqliteStateStore<TAddr>),
+    Lmdb(LmdbStateStore<TAddr>),
+}
+
+impl<TAddr> AnyStateStore<TAddr> {
+    pub fn connect_sqlite(url: &str) -> Result<Self, StorageError> {
+        Ok(Self::Sqlite(SqliteStateStore::connect(url)?))
+    }
+
+    pub fn open_lmdb<P: AsRef<Path>>(path: P) -> Result<Self, StorageError> {
+        Ok(Self::Lmdb(LmdbStateStore::open(path)?))
+    }
+
+    /// Writes a consistent copy of the store to `path`. For the SQLite backend `path` is the database file to
+    /// create and for the LMDB backend it is the directory to copy the environment into.
+    pub fn backup_to<P: AsRef<Path>>(&self, path: P) -> Result<(), StorageError> {
+        match self {
+            Self::Sqlite(store) => store.backup_to(path),
+            Self::Lmdb(store) => store.backup_to(path),
+        }
+    }
+
+    pub fn backend(&self) -> StateStoreBackend {
+        match self {
+            Self::Sqlite(_) => StateStoreBackend::Sqlite,
+            Self::Lmdb(_) => StateStoreBackend::Lmdb,
+        }
+    }
+}
+
+impl<TAddr> fmt::Debug for AnyStateStore<TAddr> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Sqlite(store) => fmt::Debug::fmt(store, f),
+            Self::Lmdb(store) => fmt::Debug::fmt(store, f),
+        }
+    }
+}
+
+impl<TAddr> From<SqliteStateStore<TAddr>> for AnyStateStore<TAddr> {
+    fn from(store: SqliteStateStore<TAddr>) -> Self {
+        Self::Sqlite(store)
+    }
+}
+
+impl<TAddr> From<LmdbStateStore<TAddr>> for AnyStateStore<TAddr> {
+    fn from(store: LmdbStateStore<TAddr>) -> Self {
+        Self::Lmdb(store)
+    }
+}
+
+impl<TAddr: NodeAddressable + Serialize + DeserializeOwned> StateStore for AnyStateStore<TAddr> {
+    type Addr = TAddr;
+    type ReadTransaction<'a> = AnyStateStoreReadTransaction<'a, Self::Addr> where TAddr: 'a;
+    type WriteTransaction<'a> = AnyStateStoreWriteTransaction<'a, Self::Addr> where TAddr: 'a;
+
+    fn create_read_tx(&self) -> Result<Self::ReadTransaction<'_>, StorageError> {
+        match self {
+            Self::Sqlite(store) => Ok(AnyStateStoreReadTransaction::SqliteRead(store.create_read_tx()?)),
+            Self::Lmdb(store) => Ok(AnyStateStoreReadTransaction::LmdbRead(store.create_read_tx()?)),
+        }
+    }
+
+    fn create_write_tx(&self) -> Result<Self::WriteTransaction<'_>, StorageError> {
+        let tx = match self {
+            Self::Sqlite(store) => AnyStateStoreReadTransaction::SqliteWrite(store.create_write_tx()?),
+            Self::Lmdb(store) => AnyStateStoreReadTransaction::LmdbWrite(store.create_write_tx()?),
+        };
+        Ok(AnyStateStoreWriteTransaction::new(tx))
+    }
+}
+
+impl<TAddr> Clone for AnyStateStore<TAddr> {
+    fn clone(&self) -> Self {
+        match self {
+            Self::Sqlite(store) => Self::Sqlite(store.clone()),
+            Self::Lmdb(store) => Self::Lmdb(store.clone()),
+        }
+    }
+}
@@ -0,0 +1,355 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use std::{
+    borrow::Borrow,
+    ops::{Deref, DerefMut},
+    time::{Duration, Instant},
+};
+
+use serde::{de::DeserializeOwned, Serialize};
+use tari_dan_common_types::{Epoch, NodeAddressable, NodeHeight, ShardId, View};
+use tari_dan_storage::{
+    consensus_models::{
+        Block,
+        BlockId,
+        Decision,
+        Evidence,
+        HighQc,
+        LastExecuted,
+        LastProposed,
+        LastSentVote,
+        LastVoted,
+        LeafBlock,
+        LockedBlock,
+        LockedOutput,
+        QcId,
+        QuorumCertificate,
+        SubstateLockFlag,
+        SubstateLockState,
+        SubstateRecord,
+        TransactionAtom,
+        TransactionFeeInfo,
+        TransactionPoolStage,
+        TransactionPoolStatusUpdate,
+        TransactionRecord,
+        Vote,
+    },
+    StateStoreWriteTransaction,
+    StorageError,
+};
+use tari_transaction::{Transaction, TransactionId};
+
+use crate::{metrics, reader::AnyStateStoreReadTransaction};
+
+pub struct AnyStateStoreWriteTransaction<'a, TAddr> {
+    /// Always one of the write variants
+    transaction: AnyStateStoreReadTransaction<'a, TAddr>,
+}
+
+impl<'a, TAddr> AnyStateStoreWriteTransaction<'a, TAddr> {
+    pub(crate) fn new(transaction: AnyStateStoreReadTransaction<'a, TAddr>) -> Self {
+        debug_assert!(matches!(
+            transaction,
+            AnyStateStoreReadTransaction::SqliteWrite(_) | AnyStateStoreReadTransaction::LmdbWrite(_)
+        ));
+        Self { transaction }
+    }
+}
+
+/// Calls the same write transaction method on whichever backend transaction is active
+macro_rules! dispatch_write {
+    ($self:ident, |$tx:ident| $body:expr) => {
+        match &mut $self.transaction {
+            AnyStateStoreReadTransaction::SqliteWrite($tx) => $body,
+            AnyStateStoreReadTransaction::LmdbWrite($tx) => $body,
+            _ => unreachable!("AnyStateStoreWriteTransaction does not contain a write transaction"),
+        }
+    };
+}
+
+impl<TAddr: NodeAddressable + Serialize + DeserializeOwned> StateStoreWriteTransaction
+    for AnyStateStoreWriteTransaction<'_, TAddr>
+{
+    type Addr = TAddr;
+
+    fn commit(self) -> Result<(), StorageError> {
+        let timer = Instant::now();
+        let (backend, result) = match self.transaction {
+            AnyStateStoreReadTransaction::SqliteWrite(tx) => ("sqlite", tx.commit()),
+            AnyStateStoreReadTransaction::LmdbWrite(tx) => ("lmdb", tx.commit()),
+            _ => unreachable!("AnyStateStoreWriteTransaction does not contain a write transaction"),
+        };
+        metrics::COMMIT_SECONDS
+            .with_label_values(&[backend])
+            .observe(timer.elapsed().as_secs_f64());
+        result
+    }
+
+    fn rollback(self) -> Result<(), StorageError> {
+        match self.transaction {
+            AnyStateStoreReadTransaction::SqliteWrite(tx) => tx.rollback(),
+            AnyStateStoreReadTransaction::LmdbWrite(tx) => tx.rollback(),
+            _ => unreachable!("AnyStateStoreWriteTransaction does not contain a write transaction"),
+        }
+    }
+
+    fn blocks_insert(&mut self, block: &Block<Self::Addr>) -> Result<(), StorageError> {
+        dispatch_write!(self, |tx| tx.blocks_insert(block))
+    }
+
+    fn blocks_set_flags(
+        &mut self,
+        block_id: &BlockId,
+        is_committed: Option<bool>,
+        is_processed: Option<bool>,
+    ) -> Result<(), StorageError> {
+        dispatch_write!(self, |tx| tx.blocks_set_flags(block_id, is_committed, is_processed))
+    }
+
+    fn quorum_certificates_insert(&mut self, qc: &QuorumCertificate<Self::Addr>) -> Result<(), StorageError> {
+        dispatch_write!(self, |tx| tx.quorum_certificates_insert(qc))
+    }
+
+    fn last_sent_vote_set(&mut self, last_sent_vote: &LastSentVote<Self::Addr>) -> Result<(), StorageError> {
+        dispatch_write!(self, |tx| tx.last_sent_vote_set(last_sent_vote))
+    }
+
+    fn last_voted_set(&mut self, last_voted: &LastVoted) -> Result<(), StorageError> {
+        dispatch_write!(self, |tx| tx.last_voted_set(last_voted))
+    }
+
+    fn last_votes_unset(&mut self, last_voted: &LastVoted) -> Result<(), StorageError> {
+        dispatch_write!(self, |tx| tx.last_votes_unset(last_voted))
+    }
+
+    fn last_executed_set(&mut self, last_exec: &LastExecuted) -> Result<(), StorageError> {
+        dispatch_write!(self, |tx| tx.last_executed_set(last_exec))
+    }
+
+    fn last_proposed_set(&mut self, last_proposed: &LastProposed) -> Result<(), StorageError> {
+        dispatch_write!(self, |tx| tx.last_proposed_set(last_proposed))
+    }
+
+    fn last_proposed_unset(&mut self, last_proposed: &LastProposed) -> Result<(), StorageError> {
+        dispatch_write!(self, |tx| tx.last_proposed_unset(last_proposed))
+    }
+
+    fn leaf_block_set(&mut self, leaf_node: &LeafBlock) -> Result<(), StorageError> {
+        dispatch_write!(self, |tx| tx.leaf_block_set(leaf_node))
+    }
+
+    fn locked_block_set(&mut self, locked_block: &LockedBlock) -> Result<(), StorageError> {
+        dispatch_write!(self, |tx| tx.locked_block_set(locked_block))
+    }
+
+    fn high_qc_set(&mut self, high_qc: &HighQc) -> Result<(), StorageError> {
+        dispatch_write!(self, |tx| tx.high_qc_set(high_qc))
+    }
+
+    fn transactions_insert(&mut self, transaction: &Transaction) -> Result<(), StorageError> {
+        dispatch_write!(self, |tx| tx.transactions_insert(transaction))
+    }
+
+    fn transactions_update(&mut self, transaction: &TransactionRecord) -> Result<(), StorageError> {
+        dispatch_write!(self, |tx| tx.transactions_update(transaction))
+    }
+
+    fn transactions_save_all<'a, I: IntoIterator<Item = &'a TransactionRecord>>(
+        &mut self,
+        transaction: I,
+    ) -> Result<(), StorageError> {
+        dispatch_write!(self, |tx| tx.transactions_save_all(transaction))
+    }
+
+    fn transaction_pool_insert(
+        &mut self,
+        transaction: TransactionAtom,
+        fee_info: &TransactionFeeInfo,
+        stage: TransactionPoolStage,
+        is_ready: bool,
+    ) -> Result<(), StorageError> {
+        dispatch_write!(self, |tx| tx.transaction_pool_insert(
+            transaction,
+            fee_info,
+            stage,
+            is_ready
+        ))
+    }
+
+    fn transaction_pool_add_pending_update(
+        &mut self,
+        pool_update: TransactionPoolStatusUpdate,
+    ) -> Result<(), StorageError> {
+        dispatch_write!(self, |tx| tx.transaction_pool_add_pending_update(pool_update))
+    }
+
+    fn transaction_pool_update(
+        &mut self,
+        transaction_id: &TransactionId,
+        local_decision: Option<Decision>,
+        remote_decision: Option<Decision>,
+        remote_evidence: Option<&Evidence>,
+    ) -> Result<(), StorageError> {
+        dispatch_write!(self, |tx| tx.transaction_pool_update(
+            transaction_id,
+            local_decision,
+            remote_decision,
+            remote_evidence
+        ))
+    }
+
+    fn transaction_pool_remove(&mut self, transaction_id: &TransactionId) -> Result<(), StorageError> {
+        dispatch_write!(self, |tx| tx.transaction_pool_remove(transaction_id))
+    }
+
+    fn transaction_pool_set_all_transitions<'a, I: IntoIterator<Item = &'a TransactionId>>(
+        &mut self,
+        locked_block: &LockedBlock,
+        new_locked_block: &LockedBlock,
+        tx_ids: I,
+    ) -> Result<(), StorageError> {
+        dispatch_write!(self, |tx| tx.transaction_pool_set_all_transitions(
+            locked_block,
+            new_locked_block,
+            tx_ids
+        ))
+    }
+
+    fn missing_transactions_insert<
+        'a,
+        IMissing: IntoIterator<Item = &'a TransactionId>,
+        IAwaiting: IntoIterator<Item = &'a TransactionId>,
+    >(
+        &mut self,
+        park_block: &Block<Self::Addr>,
+        missing_transaction_ids: IMissing,
+        awaiting_transaction_ids: IAwaiting,
+    ) -> Result<(), StorageError> {
+        dispatch_write!(self, |tx| tx.missing_transactions_insert(
+            park_block,
+            missing_transaction_ids,
+            awaiting_transaction_ids
+        ))
+    }
+
+    fn missing_transactions_remove(
+        &mut self,
+        current_view: View,
+        transaction_id: &TransactionId,
+    ) -> Result<Option<Block<Self::Addr>>, StorageError> {
+        dispatch_write!(self, |tx| tx.missing_transactions_remove(current_view, transaction_id))
+    }
+
+    fn votes_insert(&mut self, vote: &Vote<Self::Addr>) -> Result<(), StorageError> {
+        dispatch_write!(self, |tx| tx.votes_insert(vote))
+    }
+
+    fn substates_try_lock_many<'a, I: IntoIterator<Item = &'a ShardId>>(
+        &mut self,
+        locked_by_tx: &TransactionId,
+        objects: I,
+        lock_flag: SubstateLockFlag,
+    ) -> Result<SubstateLockState, StorageError> {
+        dispatch_write!(self, |tx| tx.substates_try_lock_many(locked_by_tx, objects, lock_flag))
+    }
+
+    fn substates_try_unlock_many<'a, I: IntoIterator<Item = &'a ShardId>>(
+        &mut self,
+        locked_by_tx: &TransactionId,
+        objects: I,
+        lock_flag: SubstateLockFlag,
+    ) -> Result<(), StorageError> {
+        dispatch_write!(self, |tx| tx.substates_try_unlock_many(
+            locked_by_tx,
+            objects,
+            lock_flag
+        ))
+    }
+
+    fn substate_down_many<I: IntoIterator<Item = ShardId>>(
+        &mut self,
+        shard_ids: I,
+        epoch: Epoch,
+        destroyed_block_id: &BlockId,
+        destroyed_transaction_id: &TransactionId,
+        destroyed_qc_id: &QcId,
+        require_locks: bool,
+    ) -> Result<(), StorageError> {
+        dispatch_write!(self, |tx| tx.substate_down_many(
+            shard_ids,
+            epoch,
+            destroyed_block_id,
+            destroyed_transaction_id,
+            destroyed_qc_id,
+            require_locks
+        ))
+    }
+
+    fn substates_create(&mut self, substate: SubstateRecord) -> Result<(), StorageError> {
+        dispatch_write!(self, |tx| tx.substates_create(substate))
+    }
+
+    fn substates_delete_many<I: IntoIterator<Item = ShardId>>(&mut self, shard_ids: I) -> Result<usize, StorageError> {
+        dispatch_write!(self, |tx| tx.substates_delete_many(shard_ids))
+    }
+
+    fn locked_outputs_acquire_all<I, B>(
+        &mut self,
+        block_id: &BlockId,
+        transaction_id: &TransactionId,
+        output_shards: I,
+    ) -> Result<SubstateLockState, StorageError>
+    where
+        I: IntoIterator<Item = B>,
+        B: Borrow<ShardId>,
+    {
+        dispatch_write!(self, |tx| tx.locked_outputs_acquire_all(
+            block_id,
+            transaction_id,
+            output_shards
+        ))
+    }
+
+    fn locked_outputs_release_all<I, B>(&mut self, output_shards: I) -> Result<Vec<LockedOutput>, StorageError>
+    where
+        I: IntoIterator<Item = B>,
+        B: Borrow<ShardId>,
+    {
+        dispatch_write!(self, |tx| tx.locked_outputs_release_all(output_shards))
+    }
+
+    fn votes_prune_up_to_height(&mut self, height: NodeHeight, limit: usize) -> Result<usize, StorageError> {
+        dispatch_write!(self, |tx| tx.votes_prune_up_to_height(height, limit))
+    }
+
+    fn blocks_prune_uncommitted_up_to_height(
+        &mut self,
+        height: NodeHeight,
+        limit: usize,
+    ) -> Result<usize, StorageError> {
+        dispatch_write!(self, |tx| tx.blocks_prune_uncommitted_up_to_height(height, limit))
+    }
+
+    fn substates_prune_destroyed_before_epoch(&mut self, epoch: Epoch, limit: usize) -> Result<usize, StorageError> {
+        dispatch_write!(self, |tx| tx.substates_prune_destroyed_before_epoch(epoch, limit))
+    }
+
+    fn transaction_pool_history_prune(&mut self, retention: Duration, limit: usize) -> Result<usize, StorageError> {
+        dispatch_write!(self, |tx| tx.transaction_pool_history_prune(retention, limit))
+    }
+}
+
+impl<'a, TAddr> Deref for AnyStateStoreWriteTransaction<'a, TAddr> {
+    type Target = AnyStateStoreReadTransaction<'a, TAddr>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.transaction
+    }
+}
+
+impl<'a, TAddr> DerefMut for AnyStateStoreWriteTransaction<'a, TAddr> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.transaction
+    }
+}
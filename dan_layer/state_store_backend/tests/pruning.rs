@@ -0,0 +1,298 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use std::{ops::DerefMut, time::Duration};
+
+use rand::{rngs::OsRng, RngCore};
+use tari_common_types::types::FixedHash;
+use tari_dan_common_types::{Epoch, NodeHeight, View};
+use tari_dan_storage::{
+    consensus_models::{
+        Block,
+        Decision,
+        QuorumDecision,
+        SubstateDestroyed,
+        SubstatePruneMark,
+        SubstateRecord,
+        TransactionAtom,
+        TransactionFeeInfo,
+        TransactionPoolStage,
+        ValidatorSignature,
+        Vote,
+    },
+    StateStore,
+    StateStoreReadTransaction,
+    StateStoreWriteTransaction,
+    StorageError,
+};
+use tari_engine_types::{resource::Resource, substate::SubstateAddress};
+use tari_state_store_backend::AnyStateStore;
+use tari_state_store_sqlite::SqliteStateStore;
+use tari_template_lib::{
+    auth::ResourceAccessRules,
+    constants::CONFIDENTIAL_TARI_RESOURCE_ADDRESS,
+    crypto::RistrettoPublicKeyBytes,
+    prelude::{OwnerRule, ResourceType},
+};
+use tari_transaction::TransactionId;
+
+/// Runs the test against each state store backend
+fn with_each_backend<F: Fn(AnyStateStore<String>)>(test: F) {
+    let sqlite = SqliteStateStore::connect(":memory:").unwrap();
+    // Need FK=off so that we do not have to insert the QCs for each block
+    sqlite.foreign_keys_off().unwrap();
+    test(sqlite.into());
+    let dir = tempfile::tempdir().unwrap();
+    test(AnyStateStore::open_lmdb(dir.path()).unwrap());
+}
+
+fn random_transaction_id() -> TransactionId {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    TransactionId::new(bytes)
+}
+
+fn create_block(parent: &Block<String>, height: u64, view: u64, epoch: u64) -> Block<String> {
+    Block::new(
+        *parent.id(),
+        parent.justify().clone(),
+        None,
+        NodeHeight(height),
+        View(view),
+        Epoch(epoch),
+        None,
+        Default::default(),
+        Default::default(),
+        Default::default(),
+    )
+}
+
+fn create_vote(block: &Block<String>) -> Vote<String> {
+    Vote {
+        epoch: Epoch(0),
+        block_id: *block.id(),
+        decision: QuorumDecision::Accept,
+        sender_leaf_hash: FixedHash::zero(),
+        signature: ValidatorSignature::new("a".to_string(), Default::default()),
+    }
+}
+
+fn create_substate(
+    address: &SubstateAddress,
+    version: u32,
+    created_at: &Block<String>,
+    destroyed_by: Option<&Block<String>>,
+) -> SubstateRecord {
+    let resource = Resource::new(
+        ResourceType::Confidential,
+        RistrettoPublicKeyBytes::default(),
+        OwnerRule::None,
+        ResourceAccessRules::new(),
+        Default::default(),
+    );
+    let mut substate = SubstateRecord::new(
+        address.clone(),
+        version,
+        resource.into(),
+        created_at.epoch(),
+        created_at.height(),
+        *created_at.id(),
+        random_transaction_id(),
+        *created_at.justify().id(),
+    );
+    substate.destroyed = destroyed_by.map(|block| SubstateDestroyed {
+        by_transaction: random_transaction_id(),
+        justify: *block.justify().id(),
+        by_block: *block.id(),
+        at_epoch: block.epoch(),
+    });
+    substate
+}
+
+#[test]
+fn it_prunes_votes_and_uncommitted_blocks_up_to_height() {
+    with_each_backend(|db| {
+        let mut tx = db.create_write_tx().unwrap();
+
+        let zero_block = Block::zero_block();
+        zero_block.insert(&mut tx).unwrap();
+        let committed = create_block(&zero_block, 1, 1, 0);
+        committed.insert(&mut tx).unwrap();
+        tx.blocks_set_flags(committed.id(), Some(true), None).unwrap();
+        // A competing block at the same height that was never committed
+        let abandoned = create_block(&zero_block, 1, 2, 0);
+        abandoned.insert(&mut tx).unwrap();
+        let next = create_block(&committed, 2, 3, 0);
+        next.insert(&mut tx).unwrap();
+
+        // A leaf pointer that has since moved on must not keep the abandoned block alive
+        abandoned.as_leaf_block().set(&mut tx).unwrap();
+        next.as_leaf_block().set(&mut tx).unwrap();
+
+        for block in [&committed, &abandoned, &next] {
+            tx.votes_insert(&create_vote(block)).unwrap();
+        }
+
+        assert_eq!(tx.votes_prune_up_to_height(NodeHeight(1), 100).unwrap(), 2);
+        assert_eq!(tx.votes_count_for_block(committed.id()).unwrap(), 0);
+        assert_eq!(tx.votes_count_for_block(next.id()).unwrap(), 1);
+
+        assert_eq!(tx.blocks_prune_uncommitted_up_to_height(NodeHeight(1), 100).unwrap(), 1);
+        assert!(!tx.blocks_exists(abandoned.id()).unwrap());
+        assert!(tx.blocks_exists(zero_block.id()).unwrap());
+        assert!(tx.blocks_exists(committed.id()).unwrap());
+        assert!(tx.blocks_exists(next.id()).unwrap());
+        assert_eq!(tx.leaf_block_get().unwrap().block_id, *next.id());
+
+        // Nothing left to prune
+        assert_eq!(tx.blocks_prune_uncommitted_up_to_height(NodeHeight(1), 100).unwrap(), 0);
+        tx.rollback().unwrap();
+    });
+}
+
+#[test]
+fn it_prunes_at_most_the_limit_per_call() {
+    with_each_backend(|db| {
+        let mut tx = db.create_write_tx().unwrap();
+        let address = SubstateAddress::Resource(CONFIDENTIAL_TARI_RESOURCE_ADDRESS);
+
+        let zero_block = Block::zero_block();
+        zero_block.justify().insert(&mut tx).unwrap();
+        zero_block.insert(&mut tx).unwrap();
+        // Competing blocks that were never committed, each with a vote and a substate version that it destroyed
+        let abandoned = (1..=3)
+            .map(|view| create_block(&zero_block, 1, view, 1))
+            .collect::<Vec<_>>();
+        for (version, block) in (0..).zip(&abandoned) {
+            block.insert(&mut tx).unwrap();
+            tx.votes_insert(&create_vote(block)).unwrap();
+            create_substate(&address, version, &zero_block, Some(block))
+                .create(&mut tx)
+                .unwrap();
+        }
+
+        assert_eq!(tx.votes_prune_up_to_height(NodeHeight(1), 2).unwrap(), 2);
+        assert_eq!(tx.votes_prune_up_to_height(NodeHeight(1), 2).unwrap(), 1);
+        assert_eq!(tx.votes_prune_up_to_height(NodeHeight(1), 2).unwrap(), 0);
+
+        assert_eq!(tx.substates_prune_destroyed_before_epoch(Epoch(2), 2).unwrap(), 2);
+        assert_eq!(tx.substates_prune_destroyed_before_epoch(Epoch(2), 2).unwrap(), 1);
+        assert_eq!(tx.substates_prune_destroyed_before_epoch(Epoch(2), 2).unwrap(), 0);
+        assert_eq!(
+            tx.substates_get_prune_mark().unwrap(),
+            Some(SubstatePruneMark {
+                before_epoch: Epoch(2),
+                destroyed_height: NodeHeight(1),
+            })
+        );
+
+        assert_eq!(tx.blocks_prune_uncommitted_up_to_height(NodeHeight(1), 2).unwrap(), 2);
+        assert_eq!(tx.blocks_prune_uncommitted_up_to_height(NodeHeight(1), 2).unwrap(), 1);
+        assert_eq!(tx.blocks_prune_uncommitted_up_to_height(NodeHeight(1), 2).unwrap(), 0);
+        for block in &abandoned {
+            assert!(!tx.blocks_exists(block.id()).unwrap());
+        }
+        assert!(tx.blocks_exists(zero_block.id()).unwrap());
+        tx.rollback().unwrap();
+    });
+}
+
+#[test]
+fn it_reports_pruned_substate_history_as_unavailable() {
+    with_each_backend(|db| {
+        let mut tx = db.create_write_tx().unwrap();
+        let address = SubstateAddress::Resource(CONFIDENTIAL_TARI_RESOURCE_ADDRESS);
+
+        let zero_block = Block::zero_block();
+        // Loading the destroying blocks requires the QC that they justify
+        zero_block.justify().insert(&mut tx).unwrap();
+        zero_block.insert(&mut tx).unwrap();
+        let block1 = create_block(&zero_block, 1, 1, 1);
+        let block3 = create_block(&block1, 3, 3, 3);
+        let block5 = create_block(&block3, 5, 5, 5);
+        for block in [&block1, &block3, &block5] {
+            block.insert(&mut tx).unwrap();
+            tx.blocks_set_flags(block.id(), Some(true), None).unwrap();
+        }
+
+        create_substate(&address, 0, &block1, Some(&block3))
+            .create(&mut tx)
+            .unwrap();
+        create_substate(&address, 1, &block3, Some(&block5))
+            .create(&mut tx)
+            .unwrap();
+        create_substate(&address, 2, &block5, None).create(&mut tx).unwrap();
+
+        assert_eq!(tx.substates_get_prune_mark().unwrap(), None);
+        // Nothing was destroyed before epoch 1, so the mark is not set
+        assert_eq!(tx.substates_prune_destroyed_before_epoch(Epoch(1), 100).unwrap(), 0);
+        assert_eq!(tx.substates_get_prune_mark().unwrap(), None);
+
+        assert_eq!(tx.substates_prune_destroyed_before_epoch(Epoch(4), 100).unwrap(), 1);
+        assert_eq!(
+            tx.substates_get_prune_mark().unwrap(),
+            Some(SubstatePruneMark {
+                before_epoch: Epoch(4),
+                destroyed_height: NodeHeight(3),
+            })
+        );
+
+        let history = SubstateRecord::get_history(tx.deref_mut(), &address).unwrap();
+        assert_eq!(history.versions.iter().map(|s| s.version).collect::<Vec<_>>(), vec![
+            1, 2
+        ]);
+        assert_eq!(history.pruned_before_epoch, Some(Epoch(4)));
+
+        let err = SubstateRecord::get_at_epoch(tx.deref_mut(), &address, Epoch(2)).unwrap_err();
+        assert!(matches!(err, StorageError::HistoryUnavailable { .. }));
+        let err = SubstateRecord::get_at_height(tx.deref_mut(), &address, NodeHeight(2)).unwrap_err();
+        assert!(matches!(err, StorageError::HistoryUnavailable { .. }));
+
+        // Versions that were kept are still answered
+        let at_epoch = SubstateRecord::get_at_epoch(tx.deref_mut(), &address, Epoch(3)).unwrap();
+        assert_eq!(at_epoch.map(|s| s.version), Some(1));
+        let at_height = SubstateRecord::get_at_height(tx.deref_mut(), &address, NodeHeight(4)).unwrap();
+        assert_eq!(at_height.map(|s| s.version), Some(1));
+        let at_height = SubstateRecord::get_at_height(tx.deref_mut(), &address, NodeHeight(5)).unwrap();
+        assert_eq!(at_height.map(|s| s.version), Some(2));
+
+        // A lower epoch never moves the mark back
+        tx.substates_prune_destroyed_before_epoch(Epoch(2), 100).unwrap();
+        assert_eq!(tx.substates_get_prune_mark().unwrap().unwrap().before_epoch, Epoch(4));
+        tx.rollback().unwrap();
+    });
+}
+
+#[test]
+fn it_keeps_transaction_pool_history_within_the_retention_period() {
+    with_each_backend(|db| {
+        let mut tx = db.create_write_tx().unwrap();
+        let zero_block = Block::<String>::zero_block();
+        zero_block.insert(&mut tx).unwrap();
+
+        let atom = TransactionAtom {
+            id: random_transaction_id(),
+            decision: Decision::Commit,
+            evidence: Default::default(),
+            transaction_fee: 0,
+            leader_fee: 0,
+        };
+        let id = atom.id;
+        let fee_info = TransactionFeeInfo {
+            signer: Default::default(),
+            size: 100,
+        };
+        tx.transaction_pool_insert(atom, &fee_info, TransactionPoolStage::New, true)
+            .unwrap();
+        tx.transaction_pool_update(&id, Some(Decision::Abort), None, None)
+            .unwrap();
+        tx.transaction_pool_remove(&id).unwrap();
+
+        assert_eq!(
+            tx.transaction_pool_history_prune(Duration::from_secs(60 * 60), 100)
+                .unwrap(),
+            0
+        );
+        tx.rollback().unwrap();
+    });
+}
@@ -0,0 +1,269 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use std::ops::{DerefMut, RangeInclusive};
+
+use rand::{rngs::OsRng, RngCore};
+use tari_common_types::types::FixedHash;
+use tari_dan_common_types::{Epoch, NodeHeight, ShardId, View};
+use tari_dan_storage::{
+    consensus_models::{
+        calculate_state_commitment,
+        Block,
+        QuorumCertificate,
+        QuorumDecision,
+        StateSnapshot,
+        SubstateDestroyed,
+        SubstateRecord,
+    },
+    StateStore,
+    StateStoreReadTransaction,
+    StateStoreWriteTransaction,
+};
+use tari_engine_types::{resource::Resource, substate::SubstateAddress};
+use tari_state_store_backend::AnyStateStore;
+use tari_state_store_sqlite::SqliteStateStore;
+use tari_template_lib::{
+    auth::ResourceAccessRules,
+    constants::CONFIDENTIAL_TARI_RESOURCE_ADDRESS,
+    crypto::RistrettoPublicKeyBytes,
+    prelude::{OwnerRule, ResourceType},
+};
+use tari_transaction::TransactionId;
+
+/// Runs the test against each state store backend. The test is given a function that opens a new empty store, so
+/// that a snapshot can be applied to a different store than the one it was loaded from.
+fn with_each_backend<F: Fn(&dyn Fn() -> AnyStateStore<String>)>(test: F) {
+    test(&|| {
+        let sqlite = SqliteStateStore::connect(":memory:").unwrap();
+        // Need FK=off so that we do not have to insert the parent blocks of the checkpoint
+        sqlite.foreign_keys_off().unwrap();
+        sqlite.into()
+    });
+    let dir = tempfile::tempdir().unwrap();
+    test(&|| AnyStateStore::open_lmdb(tempfile::tempdir_in(dir.path()).unwrap().into_path()).unwrap());
+}
+
+fn random_transaction_id() -> TransactionId {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    TransactionId::new(bytes)
+}
+
+fn full_range() -> RangeInclusive<ShardId> {
+    ShardId::zero()..=ShardId::new(FixedHash::from([0xff; 32]))
+}
+
+/// Sets up the zero block the same way a node does before syncing
+fn insert_zero_block<TTx>(tx: &mut TTx)
+where
+    TTx: StateStoreWriteTransaction<Addr = String> + DerefMut,
+    TTx::Target: StateStoreReadTransaction<Addr = String>,
+{
+    let zero_block = Block::zero_block();
+    zero_block.justify().insert(tx).unwrap();
+    zero_block.insert(tx).unwrap();
+    zero_block.as_locked_block().set(tx).unwrap();
+    zero_block.as_leaf_block().set(tx).unwrap();
+    zero_block.as_last_executed().set(tx).unwrap();
+    zero_block.as_last_voted().set(tx).unwrap();
+    zero_block.justify().as_high_qc().set(tx).unwrap();
+    zero_block.commit(tx).unwrap();
+}
+
+fn create_block(parent: &Block<String>, height: u64) -> Block<String> {
+    Block::new(
+        *parent.id(),
+        parent.justify().clone(),
+        None,
+        NodeHeight(height),
+        View(height),
+        Epoch(0),
+        None,
+        Default::default(),
+        Default::default(),
+        Default::default(),
+    )
+}
+
+fn create_qc(block: &Block<String>) -> QuorumCertificate<String> {
+    QuorumCertificate::new(
+        *block.id(),
+        block.height(),
+        block.epoch(),
+        vec![],
+        QuorumCertificate::<String>::genesis().merged_proof().clone(),
+        vec![],
+        QuorumDecision::Accept,
+    )
+}
+
+fn create_substate(
+    address: &SubstateAddress,
+    version: u32,
+    created_at: &Block<String>,
+    destroyed_by: Option<&Block<String>>,
+) -> SubstateRecord {
+    let resource = Resource::new(
+        ResourceType::Confidential,
+        RistrettoPublicKeyBytes::default(),
+        OwnerRule::None,
+        ResourceAccessRules::new(),
+        Default::default(),
+    );
+    let mut substate = SubstateRecord::new(
+        address.clone(),
+        version,
+        resource.into(),
+        created_at.epoch(),
+        created_at.height(),
+        *created_at.id(),
+        random_transaction_id(),
+        *created_at.justify().id(),
+    );
+    substate.destroyed = destroyed_by.map(|block| SubstateDestroyed {
+        by_transaction: random_transaction_id(),
+        justify: *block.justify().id(),
+        by_block: *block.id(),
+        at_epoch: block.epoch(),
+    });
+    substate
+}
+
+/// Commits three blocks and returns the second one as the checkpoint. The substates that are live as of the
+/// checkpoint are versions 1 and 2.
+fn create_checkpoint<TTx>(tx: &mut TTx) -> Block<String>
+where
+    TTx: StateStoreWriteTransaction<Addr = String> + DerefMut,
+    TTx::Target: StateStoreReadTransaction<Addr = String>,
+{
+    let address = SubstateAddress::Resource(CONFIDENTIAL_TARI_RESOURCE_ADDRESS);
+    insert_zero_block(tx);
+    let block1 = create_block(&Block::zero_block(), 1);
+    let block2 = create_block(&block1, 2);
+    let block3 = create_block(&block2, 3);
+    for block in [&block1, &block2, &block3] {
+        block.insert(tx).unwrap();
+        block.commit(tx).unwrap();
+    }
+    create_qc(&block2).insert(tx).unwrap();
+
+    // Destroyed at the checkpoint
+    create_substate(&address, 0, &block1, Some(&block2)).create(tx).unwrap();
+    // Destroyed after the checkpoint, so live in the snapshot
+    create_substate(&address, 1, &block1, Some(&block3)).create(tx).unwrap();
+    create_substate(&address, 2, &block2, None).create(tx).unwrap();
+    // Created after the checkpoint
+    create_substate(&address, 3, &block3, None).create(tx).unwrap();
+
+    block2
+}
+
+fn versions(substates: &[SubstateRecord]) -> Vec<u32> {
+    let mut versions = substates.iter().map(|s| s.version).collect::<Vec<_>>();
+    versions.sort_unstable();
+    versions
+}
+
+#[test]
+fn it_loads_the_substates_live_at_the_checkpoint() {
+    with_each_backend(|create_store| {
+        let db = create_store();
+        let mut tx = db.create_write_tx().unwrap();
+        let checkpoint = create_checkpoint(&mut tx);
+        let range = full_range();
+
+        let snapshot = StateSnapshot::load(tx.deref_mut(), checkpoint.id(), range.clone()).unwrap();
+        assert_eq!(snapshot.block.id(), checkpoint.id());
+        assert_eq!(snapshot.qc.block_id(), checkpoint.id());
+        assert_eq!(versions(&snapshot.substates), vec![1, 2]);
+        assert!(snapshot.substates.iter().all(|s| !s.is_destroyed()));
+
+        // Paging through the range one substate at a time returns the same substates
+        let mut paged = vec![];
+        let mut after = None;
+        loop {
+            let page = StateSnapshot::load_page(tx.deref_mut(), &checkpoint, &range, after.as_ref(), 1).unwrap();
+            paged.extend(page.substates);
+            match page.next {
+                Some(next) => after = Some(next),
+                None => break,
+            }
+        }
+        assert_eq!(versions(&paged), vec![1, 2]);
+
+        let summary = StateSnapshot::load_summary(tx.deref_mut(), &checkpoint, &range).unwrap();
+        assert_eq!(summary.substate_count, 2);
+        assert_eq!(summary.state_commitment, snapshot.state_commitment());
+        // The commitment does not depend on the order of the substates
+        assert_eq!(
+            calculate_state_commitment(&range, snapshot.substates.iter().rev()),
+            summary.state_commitment
+        );
+        tx.rollback().unwrap();
+    });
+}
+
+#[test]
+fn it_applies_a_snapshot_with_an_identical_state_commitment() {
+    with_each_backend(|create_store| {
+        let db = create_store();
+        let mut tx = db.create_write_tx().unwrap();
+        let checkpoint = create_checkpoint(&mut tx);
+        let range = full_range();
+        let snapshot = StateSnapshot::load(tx.deref_mut(), checkpoint.id(), range.clone()).unwrap();
+        tx.rollback().unwrap();
+
+        let synced = create_store();
+        let mut tx = synced.create_write_tx().unwrap();
+        insert_zero_block(&mut tx);
+        snapshot.clone().apply(&mut tx).unwrap();
+
+        assert_eq!(tx.locked_block_get().unwrap().block_id, *checkpoint.id());
+        assert_eq!(tx.last_executed_get().unwrap().block_id, *checkpoint.id());
+        assert_eq!(tx.high_qc_get().unwrap().block_id, *checkpoint.id());
+        let applied = StateSnapshot::load(tx.deref_mut(), checkpoint.id(), range).unwrap();
+        assert_eq!(versions(&applied.substates), vec![1, 2]);
+        assert_eq!(applied.state_commitment(), snapshot.state_commitment());
+        tx.rollback().unwrap();
+    });
+}
+
+#[test]
+fn it_changes_the_state_commitment_of_a_tampered_snapshot() {
+    with_each_backend(|create_store| {
+        let db = create_store();
+        let mut tx = db.create_write_tx().unwrap();
+        let checkpoint = create_checkpoint(&mut tx);
+        let snapshot = StateSnapshot::load(tx.deref_mut(), checkpoint.id(), full_range()).unwrap();
+        tx.rollback().unwrap();
+        let commitment = snapshot.state_commitment();
+
+        let mut tampered = snapshot.clone();
+        tampered.substates[0].substate_value = Resource::new(
+            ResourceType::Fungible,
+            RistrettoPublicKeyBytes::default(),
+            OwnerRule::None,
+            ResourceAccessRules::new(),
+            Default::default(),
+        )
+        .into();
+        assert_ne!(tampered.state_commitment(), commitment);
+
+        let mut tampered = snapshot.clone();
+        tampered.substates[0].version += 1;
+        assert_ne!(tampered.state_commitment(), commitment);
+
+        let mut tampered = snapshot.clone();
+        tampered.substates.pop();
+        assert_ne!(tampered.state_commitment(), commitment);
+
+        let mut tampered = snapshot.clone();
+        tampered.substates.push(tampered.substates[0].clone());
+        assert_ne!(tampered.state_commitment(), commitment);
+
+        let mut tampered = snapshot;
+        tampered.range = ShardId::zero()..=ShardId::new(FixedHash::from([0xee; 32]));
+        assert_ne!(tampered.state_commitment(), commitment);
+    });
+}
@@ -0,0 +1,230 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use std::ops::DerefMut;
+
+use rand::{rngs::OsRng, RngCore};
+use tari_dan_common_types::{Epoch, NodeHeight, View};
+use tari_dan_storage::{
+    consensus_models::{Block, SubstateDestroyed, SubstateRecord},
+    StateStore,
+    StateStoreReadTransaction,
+    StateStoreWriteTransaction,
+    StorageError,
+};
+use tari_engine_types::{resource::Resource, substate::SubstateAddress};
+use tari_state_store_backend::AnyStateStore;
+use tari_state_store_sqlite::SqliteStateStore;
+use tari_template_lib::{
+    auth::ResourceAccessRules,
+    constants::{CONFIDENTIAL_TARI_RESOURCE_ADDRESS, PUBLIC_IDENTITY_RESOURCE_ADDRESS},
+    crypto::RistrettoPublicKeyBytes,
+    prelude::{OwnerRule, ResourceType},
+};
+use tari_transaction::TransactionId;
+
+/// Runs the test against each state store backend
+fn with_each_backend<F: Fn(AnyStateStore<String>)>(test: F) {
+    let sqlite = SqliteStateStore::connect(":memory:").unwrap();
+    // Need FK=off so that we do not have to insert the blocks that substates were created in
+    sqlite.foreign_keys_off().unwrap();
+    test(sqlite.into());
+    let dir = tempfile::tempdir().unwrap();
+    test(AnyStateStore::open_lmdb(dir.path()).unwrap());
+}
+
+fn random_transaction_id() -> TransactionId {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    TransactionId::new(bytes)
+}
+
+fn create_block(parent: &Block<String>, height: u64) -> Block<String> {
+    Block::new(
+        *parent.id(),
+        parent.justify().clone(),
+        None,
+        NodeHeight(height),
+        View(height),
+        Epoch(height),
+        None,
+        Default::default(),
+        Default::default(),
+        Default::default(),
+    )
+}
+
+/// Creates the substate in `created_at`. If `destroyed_by` is given, the substate is destroyed in that block by the
+/// given transaction.
+fn create_substate(
+    address: &SubstateAddress,
+    version: u32,
+    created_at: &Block<String>,
+    created_by: TransactionId,
+    destroyed_by: Option<(&Block<String>, TransactionId)>,
+) -> SubstateRecord {
+    let resource = Resource::new(
+        ResourceType::Confidential,
+        RistrettoPublicKeyBytes::default(),
+        OwnerRule::None,
+        ResourceAccessRules::new(),
+        Default::default(),
+    );
+    let mut substate = SubstateRecord::new(
+        address.clone(),
+        version,
+        resource.into(),
+        created_at.epoch(),
+        created_at.height(),
+        *created_at.id(),
+        created_by,
+        *created_at.justify().id(),
+    );
+    substate.destroyed = destroyed_by.map(|(block, by_transaction)| SubstateDestroyed {
+        by_transaction,
+        justify: *block.justify().id(),
+        by_block: *block.id(),
+        at_epoch: block.epoch(),
+    });
+    substate
+}
+
+#[test]
+fn it_returns_all_versions_in_order() {
+    with_each_backend(|db| {
+        let mut tx = db.create_write_tx().unwrap();
+        let address = SubstateAddress::Resource(CONFIDENTIAL_TARI_RESOURCE_ADDRESS);
+
+        let block1 = create_block(&Block::zero_block(), 1);
+        let block3 = create_block(&block1, 3);
+        let block5 = create_block(&block3, 5);
+        let (tx3, tx5) = (random_transaction_id(), random_transaction_id());
+        create_substate(&address, 2, &block5, tx5, None)
+            .create(&mut tx)
+            .unwrap();
+        create_substate(&address, 0, &block1, random_transaction_id(), Some((&block3, tx3)))
+            .create(&mut tx)
+            .unwrap();
+        create_substate(&address, 1, &block3, tx3, Some((&block5, tx5)))
+            .create(&mut tx)
+            .unwrap();
+
+        let versions = tx.substates_get_all_versions(&address).unwrap();
+        assert_eq!(versions.iter().map(|s| s.version).collect::<Vec<_>>(), vec![0, 1, 2]);
+
+        let live_at = |epoch| SubstateRecord::find_live_at_epoch(versions.clone(), Epoch(epoch)).map(|s| s.version);
+        assert_eq!(live_at(0), None);
+        assert_eq!(live_at(2), Some(0));
+        assert_eq!(live_at(3), Some(1));
+        assert_eq!(live_at(5), Some(2));
+
+        let other = SubstateAddress::Resource(PUBLIC_IDENTITY_RESOURCE_ADDRESS);
+        assert!(tx.substates_get_all_versions(&other).unwrap().is_empty());
+        tx.rollback().unwrap();
+    });
+}
+
+#[test]
+fn it_gets_the_version_live_at_a_height() {
+    with_each_backend(|db| {
+        let mut tx = db.create_write_tx().unwrap();
+        let address = SubstateAddress::Resource(CONFIDENTIAL_TARI_RESOURCE_ADDRESS);
+
+        let zero_block = Block::zero_block();
+        // Loading the destroying blocks requires the QC that they justify
+        zero_block.justify().insert(&mut tx).unwrap();
+        zero_block.insert(&mut tx).unwrap();
+        let block1 = create_block(&zero_block, 1);
+        let block3 = create_block(&block1, 3);
+        for block in [&block1, &block3] {
+            block.insert(&mut tx).unwrap();
+            block.commit(&mut tx).unwrap();
+        }
+
+        let tx3 = random_transaction_id();
+        create_substate(&address, 0, &block1, random_transaction_id(), Some((&block3, tx3)))
+            .create(&mut tx)
+            .unwrap();
+        create_substate(&address, 1, &block3, tx3, None)
+            .create(&mut tx)
+            .unwrap();
+
+        for (height, expected) in [(0, None), (1, Some(0)), (2, Some(0)), (3, Some(1))] {
+            let substate = SubstateRecord::get_at_height(tx.deref_mut(), &address, NodeHeight(height)).unwrap();
+            assert_eq!(substate.map(|s| s.version), expected, "at height {}", height);
+        }
+        tx.rollback().unwrap();
+    });
+}
+
+#[test]
+fn it_gets_the_version_live_at_a_height_when_the_destroying_block_is_not_held() {
+    with_each_backend(|db| {
+        let mut tx = db.create_write_tx().unwrap();
+        let address = SubstateAddress::Resource(CONFIDENTIAL_TARI_RESOURCE_ADDRESS);
+
+        let zero_block = Block::zero_block();
+        zero_block.justify().insert(&mut tx).unwrap();
+        zero_block.insert(&mut tx).unwrap();
+        let block1 = create_block(&zero_block, 1);
+        block1.insert(&mut tx).unwrap();
+        block1.commit(&mut tx).unwrap();
+        // Never inserted, as is the case for a node that synced a snapshot taken after the block
+        let block3 = create_block(&block1, 3);
+
+        // The destroying transaction created the next version, so the height is known from it
+        let tx3 = random_transaction_id();
+        create_substate(&address, 0, &block1, random_transaction_id(), Some((&block3, tx3)))
+            .create(&mut tx)
+            .unwrap();
+        create_substate(&address, 1, &block3, tx3, None)
+            .create(&mut tx)
+            .unwrap();
+        let at_height = SubstateRecord::get_at_height(tx.deref_mut(), &address, NodeHeight(2)).unwrap();
+        assert_eq!(at_height.map(|s| s.version), Some(0));
+        let at_height = SubstateRecord::get_at_height(tx.deref_mut(), &address, NodeHeight(3)).unwrap();
+        assert_eq!(at_height.map(|s| s.version), Some(1));
+
+        // Nothing held here tells when this one was destroyed
+        let other = SubstateAddress::Resource(PUBLIC_IDENTITY_RESOURCE_ADDRESS);
+        create_substate(&other, 0, &block1, random_transaction_id(), Some((&block3, tx3)))
+            .create(&mut tx)
+            .unwrap();
+        let err = SubstateRecord::get_at_height(tx.deref_mut(), &other, NodeHeight(2)).unwrap_err();
+        assert!(matches!(err, StorageError::HistoryUnavailable { .. }));
+        tx.rollback().unwrap();
+    });
+}
+
+#[test]
+fn it_deletes_substates_with_their_history() {
+    with_each_backend(|db| {
+        let mut tx = db.create_write_tx().unwrap();
+        let address = SubstateAddress::Resource(CONFIDENTIAL_TARI_RESOURCE_ADDRESS);
+        let other = SubstateAddress::Resource(PUBLIC_IDENTITY_RESOURCE_ADDRESS);
+
+        let block1 = create_block(&Block::zero_block(), 1);
+        let block3 = create_block(&block1, 3);
+        let (tx1, tx3) = (random_transaction_id(), random_transaction_id());
+        let v0 = create_substate(&address, 0, &block1, tx1, Some((&block3, tx3)));
+        let v1 = create_substate(&address, 1, &block3, tx3, None);
+        let kept = create_substate(&other, 0, &block1, tx1, None);
+        let shard_ids = [v0.to_shard_id(), v1.to_shard_id()];
+        for substate in [v0, v1, kept.clone()] {
+            substate.create(&mut tx).unwrap();
+        }
+
+        let num_deleted = SubstateRecord::delete_many(&mut tx, shard_ids).unwrap();
+        assert_eq!(num_deleted, 2);
+        assert!(tx.substates_get_all_versions(&address).unwrap().is_empty());
+        assert!(tx.substates_get_all_for_transaction(&tx3).unwrap().is_empty());
+        assert!(tx.substates_get_all_for_block(block3.id()).unwrap().is_empty());
+        let remaining = tx.substates_get_all_for_transaction(&tx1).unwrap();
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].to_shard_id(), kept.to_shard_id());
+
+        // Substates that do not exist are skipped
+        assert_eq!(SubstateRecord::delete_many(&mut tx, shard_ids).unwrap(), 0);
+        tx.rollback().unwrap();
+    });
+}
@@ -0,0 +1,121 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use std::ops::DerefMut;
+
+use rand::{rngs::OsRng, RngCore};
+use tari_dan_common_types::{optional::Optional, Epoch, NodeHeight};
+use tari_dan_storage::{
+    consensus_models::{Block, SubstateDestroyed, SubstateRecord},
+    StateStore,
+    StateStoreWriteTransaction,
+};
+use tari_engine_types::{resource::Resource, substate::SubstateAddress};
+use tari_state_store_backend::AnyStateStore;
+use tari_template_lib::{
+    auth::ResourceAccessRules,
+    constants::CONFIDENTIAL_TARI_RESOURCE_ADDRESS,
+    crypto::RistrettoPublicKeyBytes,
+    prelude::{OwnerRule, ResourceType},
+};
+use tari_transaction::TransactionId;
+
+/// Runs the test against each state store backend
+fn with_each_backend<F: Fn(AnyStateStore<String>)>(test: F) {
+    test(AnyStateStore::connect_sqlite(":memory:").unwrap());
+    let dir = tempfile::tempdir().unwrap();
+    test(AnyStateStore::open_lmdb(dir.path()).unwrap());
+}
+
+fn random_transaction_id() -> TransactionId {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    TransactionId::new(bytes)
+}
+
+fn create_substate(address: &SubstateAddress, version: u32, destroyed_at_epoch: Option<u64>) -> SubstateRecord {
+    let block = Block::<String>::zero_block();
+    let resource = Resource::new(
+        ResourceType::Confidential,
+        RistrettoPublicKeyBytes::default(),
+        OwnerRule::None,
+        ResourceAccessRules::new(),
+        Default::default(),
+    );
+    let mut substate = SubstateRecord::new(
+        address.clone(),
+        version,
+        resource.into(),
+        Epoch(0),
+        NodeHeight(0),
+        *block.id(),
+        random_transaction_id(),
+        *block.justify().id(),
+    );
+    substate.destroyed = destroyed_at_epoch.map(|at_epoch| SubstateDestroyed {
+        by_transaction: random_transaction_id(),
+        justify: *block.justify().id(),
+        by_block: *block.id(),
+        at_epoch: Epoch(at_epoch),
+    });
+    substate
+}
+
+#[test]
+fn it_pages_live_substates_within_range() {
+    with_each_backend(|db| {
+        let mut tx = db.create_write_tx().unwrap();
+        let address = SubstateAddress::Resource(CONFIDENTIAL_TARI_RESOURCE_ADDRESS);
+
+        let mut live = vec![];
+        for version in 0..7 {
+            let destroyed = if version % 3 == 0 { Some(1) } else { None };
+            let substate = create_substate(&address, version, destroyed);
+            if destroyed.is_none() {
+                live.push(substate.to_shard_id());
+            }
+            substate.create(&mut tx).unwrap();
+        }
+        live.sort();
+        let range = live[0]..=live[live.len() - 1];
+
+        let mut found = vec![];
+        let mut after = None;
+        loop {
+            let page = SubstateRecord::get_live_page_within_range(tx.deref_mut(), &range, after.as_ref(), 2).unwrap();
+            assert!(page.substates.len() <= 2);
+            found.extend(page.substates.iter().map(|s| s.to_shard_id()));
+            after = page.next;
+            if after.is_none() {
+                break;
+            }
+        }
+        assert_eq!(found, live);
+
+        let page = SubstateRecord::get_live_page_within_range(tx.deref_mut(), &range, Some(&live[2]), 10).unwrap();
+        assert_eq!(page.substates.len(), live.len() - 3);
+        assert_eq!(page.next, None);
+        tx.rollback().unwrap();
+    });
+}
+
+#[test]
+fn it_gets_the_latest_version_of_a_substate() {
+    with_each_backend(|db| {
+        let mut tx = db.create_write_tx().unwrap();
+        let address = SubstateAddress::Resource(CONFIDENTIAL_TARI_RESOURCE_ADDRESS);
+
+        let latest = SubstateRecord::get_latest_version(tx.deref_mut(), &address)
+            .optional()
+            .unwrap();
+        assert!(latest.is_none());
+
+        // Insert out of order to check that the highest version is returned rather than the last inserted
+        for version in [1, 12, 0, 3] {
+            create_substate(&address, version, None).create(&mut tx).unwrap();
+        }
+        let latest = SubstateRecord::get_latest_version(tx.deref_mut(), &address).unwrap();
+        assert_eq!(latest.version(), 12);
+        tx.rollback().unwrap();
+    });
+}
@@ -0,0 +1,164 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use std::ops::DerefMut;
+
+use rand::{rngs::OsRng, RngCore};
+use tari_dan_common_types::{Epoch, NodeHeight, View};
+use tari_dan_storage::{
+    consensus_models::{
+        Block,
+        Command,
+        Decision,
+        TransactionAtom,
+        TransactionFeeInfo,
+        TransactionPoolStage,
+        TransactionPoolStatusUpdate,
+    },
+    StateStore,
+    StateStoreReadTransaction,
+    StateStoreWriteTransaction,
+};
+use tari_state_store_backend::AnyStateStore;
+use tari_state_store_sqlite::SqliteStateStore;
+use tari_transaction::TransactionId;
+
+/// Runs the test against each state store backend
+fn with_each_backend<F: Fn(AnyStateStore<String>)>(test: F) {
+    let sqlite = SqliteStateStore::connect(":memory:").unwrap();
+    // Need FK=off so that we do not have to insert the transactions and QCs
+    sqlite.foreign_keys_off().unwrap();
+    test(sqlite.into());
+    let dir = tempfile::tempdir().unwrap();
+    test(AnyStateStore::open_lmdb(dir.path()).unwrap());
+}
+
+fn create_tx_atom() -> TransactionAtom {
+    create_tx_atom_with_fee(0)
+}
+
+fn create_tx_atom_with_fee(transaction_fee: u64) -> TransactionAtom {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    TransactionAtom {
+        id: TransactionId::new(bytes),
+        decision: Decision::Commit,
+        evidence: Default::default(),
+        transaction_fee,
+        leader_fee: 0,
+    }
+}
+
+fn create_fee_info() -> TransactionFeeInfo {
+    TransactionFeeInfo {
+        signer: Default::default(),
+        size: 100,
+    }
+}
+
+#[test]
+fn it_pages_the_transaction_pool_in_transaction_id_order() {
+    with_each_backend(|db| {
+        let mut tx = db.create_write_tx().unwrap();
+        let zero_block = Block::<String>::zero_block();
+        zero_block.insert(&mut tx).unwrap();
+        zero_block.as_locked_block().set(&mut tx).unwrap();
+        zero_block.as_leaf_block().set(&mut tx).unwrap();
+
+        let mut ids = vec![];
+        for _ in 0..5 {
+            let atom = create_tx_atom();
+            ids.push(atom.id);
+            tx.transaction_pool_insert(atom, &create_fee_info(), TransactionPoolStage::New, true)
+                .unwrap();
+        }
+        ids.sort();
+
+        let mut paged = vec![];
+        let mut after = None;
+        loop {
+            let page = tx.deref_mut().transaction_pool_get_page(after.as_ref(), 2).unwrap();
+            assert!(page.len() <= 2);
+            let Some(last) = page.last() else {
+                break;
+            };
+            after = Some(*last.transaction_id());
+            paged.extend(page.iter().map(|rec| *rec.transaction_id()));
+        }
+        assert_eq!(paged, ids);
+
+        tx.rollback().unwrap();
+    });
+}
+
+#[test]
+fn it_pages_new_transactions_by_fee_priority_and_returns_proposed_transactions_as_in_progress() {
+    with_each_backend(|db| {
+        let mut tx = db.create_write_tx().unwrap();
+        let zero_block = Block::<String>::zero_block();
+        zero_block.insert(&mut tx).unwrap();
+        zero_block.as_locked_block().set(&mut tx).unwrap();
+
+        let mut atoms = vec![];
+        for fee in [10, 50, 30, 50, 20] {
+            let atom = create_tx_atom_with_fee(fee);
+            tx.transaction_pool_insert(atom.clone(), &create_fee_info(), TransactionPoolStage::New, true)
+                .unwrap();
+            atoms.push(atom);
+        }
+
+        // The transaction with the lowest fee is proposed in the leaf block
+        let proposed = atoms[0].clone();
+        let block1 = Block::new(
+            *zero_block.id(),
+            zero_block.justify().clone(),
+            None,
+            NodeHeight(1),
+            View(1),
+            Epoch(0),
+            None,
+            Default::default(),
+            [Command::Prepare(proposed.clone())].into_iter().collect(),
+            Default::default(),
+        );
+        block1.insert(&mut tx).unwrap();
+        block1.as_leaf_block().set(&mut tx).unwrap();
+        tx.transaction_pool_add_pending_update(TransactionPoolStatusUpdate {
+            block_id: *block1.id(),
+            block_height: NodeHeight(1),
+            transaction_id: proposed.id,
+            stage: TransactionPoolStage::Prepared,
+            evidence: Default::default(),
+            is_ready: true,
+            local_decision: Decision::Commit,
+        })
+        .unwrap();
+
+        atoms.sort_by(|a, b| b.transaction_fee.cmp(&a.transaction_fee).then_with(|| a.id.cmp(&b.id)));
+        let expected = atoms.iter().map(|atom| atom.id).collect::<Vec<_>>();
+
+        let mut paged = vec![];
+        let mut after = None;
+        loop {
+            let page = tx
+                .deref_mut()
+                .transaction_pool_get_new_by_fee_priority(after.as_ref().map(|(priority, id)| (*priority, id)), 2)
+                .unwrap();
+            assert!(page.len() <= 2);
+            let Some(last) = page.last() else {
+                break;
+            };
+            after = Some((last.fee_priority(), *last.transaction_id()));
+            paged.extend(page.iter().map(|rec| *rec.transaction_id()));
+        }
+        // The committed stage of the proposed transaction is still new, so it is paged along with the others
+        assert_eq!(paged, expected);
+
+        let in_progress = tx.deref_mut().transaction_pool_get_in_progress().unwrap();
+        assert_eq!(in_progress.len(), 1);
+        assert_eq!(*in_progress[0].transaction_id(), proposed.id);
+        assert!(in_progress[0].current_stage().is_prepared());
+
+        tx.rollback().unwrap();
+    });
+}
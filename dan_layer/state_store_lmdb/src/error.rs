@@ -0,0 +1,96 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use tari_dan_common_types::optional::IsNotFoundError;
+use tari_dan_storage::StorageError;
+use tari_storage::lmdb_store::LMDBError;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum LmdbStorageError {
+    #[error("Could not open LMDB database: {source}")]
+    OpenError {
+        #[from]
+        source: LMDBError,
+    },
+    #[error("Could not create database directory: {source}")]
+    IoError {
+        #[from]
+        source: std::io::Error,
+    },
+    #[error("LMDB error during operation {operation}: {source}")]
+    LmdbError {
+        source: lmdb_zero::Error,
+        operation: &'static str,
+    },
+    #[error("Failed to encode {item} in {operation}: {details}")]
+    EncodingError {
+        operation: &'static str,
+        item: &'static str,
+        details: String,
+    },
+    #[error("Malformed DB data in {operation}: {details}")]
+    MalformedDbData { operation: &'static str, details: String },
+    #[error("Database inconsistency for operation {operation}: {details}")]
+    DbInconsistency { operation: &'static str, details: String },
+    #[error("[{operation}] {item} with key {key} already exists")]
+    AlreadyExists {
+        operation: &'static str,
+        item: &'static str,
+        key: String,
+    },
+    #[error("[{operation}] Not all queried transactions were found: {details}")]
+    NotAllTransactionsFound { operation: &'static str, details: String },
+    #[error("[{operation}] Not all queried substates were found: {details}")]
+    NotAllSubstatesFound { operation: &'static str, details: String },
+    #[error("[{operation}] Not all {items} were found: {details}")]
+    NotAllItemsFound {
+        items: &'static str,
+        operation: &'static str,
+        details: String,
+    },
+    #[error("[{operation}] lock error: {details}")]
+    SubstatesUnlock { operation: &'static str, details: String },
+}
+
+impl From<LmdbStorageError> for StorageError {
+    fn from(source: LmdbStorageError) -> Self {
+        match source {
+            LmdbStorageError::OpenError { .. } | LmdbStorageError::IoError { .. } => StorageError::ConnectionError {
+                reason: source.to_string(),
+            },
+            LmdbStorageError::LmdbError { .. } | LmdbStorageError::AlreadyExists { .. } => StorageError::QueryError {
+                reason: source.to_string(),
+            },
+            LmdbStorageError::EncodingError {
+                operation,
+                item,
+                details,
+            } => StorageError::EncodingError {
+                operation,
+                item,
+                details,
+            },
+            LmdbStorageError::MalformedDbData { operation, details } => StorageError::DecodingError {
+                operation,
+                item: "lmdb value",
+                details,
+            },
+            LmdbStorageError::DbInconsistency { .. } => StorageError::DataInconsistency {
+                details: source.to_string(),
+            },
+            other => StorageError::General {
+                details: other.to_string(),
+            },
+        }
+    }
+}
+
+impl IsNotFoundError for LmdbStorageError {
+    fn is_not_found_error(&self) -> bool {
+        matches!(self, LmdbStorageError::LmdbError {
+            source: lmdb_zero::Error::Code(lmdb_zero::error::NOTFOUND),
+            ..
+        })
+    }
+}
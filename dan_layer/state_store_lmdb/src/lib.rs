@@ -0,0 +1,13 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+mod error;
+mod lmdb;
+mod models;
+mod reader;
+mod store;
+mod writer;
+
+pub use reader::LmdbStateStoreReadTransaction;
+pub use store::LmdbStateStore;
+pub use writer::LmdbStateStoreWriteTransaction;
@@ -0,0 +1,457 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+//! Thin helpers over lmdb-zero. lmdb-zero only allows one accessor per transaction at a time, so every helper
+//! obtains its own accessor and returns owned values.
+
+use std::ops::Deref;
+
+use lmdb_zero::{put, ConstTransaction, Database, LmdbResultExt, ReadTransaction, WriteTransaction};
+use serde::{de::DeserializeOwned, Serialize};
+use tari_engine_types::substate::SubstateAddress;
+use tari_transaction::TransactionId;
+
+use crate::error::LmdbStorageError;
+
+pub enum LmdbTransaction<'a> {
+    Read(ReadTransaction<'a>),
+    Write(WriteTransaction<'a>),
+}
+
+impl<'a> LmdbTransaction<'a> {
+    pub fn as_write(&self) -> Option<&WriteTransaction<'a>> {
+        match self {
+            Self::Read(_) => None,
+            Self::Write(tx) => Some(tx),
+        }
+    }
+
+    pub fn commit(self) -> Result<(), LmdbStorageError> {
+        match self {
+            // Read transactions are aborted when dropped
+            Self::Read(_) => Ok(()),
+            Self::Write(tx) => tx.commit().map_err(|source| LmdbStorageError::LmdbError {
+                source,
+                operation: "commit",
+            }),
+        }
+    }
+}
+
+impl<'a> Deref for LmdbTransaction<'a> {
+    type Target = ConstTransaction<'a>;
+
+    fn deref(&self) -> &Self::Target {
+        match self {
+            Self::Read(tx) => tx,
+            Self::Write(tx) => tx,
+        }
+    }
+}
+
+pub fn encode<T: Serialize + ?Sized>(
+    operation: &'static str,
+    item: &'static str,
+    value: &T,
+) -> Result<Vec<u8>, LmdbStorageError> {
+    tari_bor::encode(value).map_err(|e| LmdbStorageError::EncodingError {
+        operation,
+        item,
+        details: e.to_string(),
+    })
+}
+
+pub fn decode<T: DeserializeOwned>(operation: &'static str, bytes: &[u8]) -> Result<T, LmdbStorageError> {
+    tari_bor::decode(bytes).map_err(|e| LmdbStorageError::MalformedDbData {
+        operation,
+        details: e.to_string(),
+    })
+}
+
+pub fn get<T: DeserializeOwned>(
+    txn: &ConstTransaction<'_>,
+    db: &Database,
+    key: &[u8],
+    operation: &'static str,
+) -> Result<Option<T>, LmdbStorageError> {
+    let access = txn.access();
+    let value = access
+        .get::<_, [u8]>(db, key)
+        .to_opt()
+        .map_err(|source| LmdbStorageError::LmdbError { source, operation })?;
+    value.map(|v| decode(operation, v)).transpose()
+}
+
+/// Returns the undecoded value at `key`. Used for index databases whose values are keys of another database.
+pub fn get_raw(
+    txn: &ConstTransaction<'_>,
+    db: &Database,
+    key: &[u8],
+    operation: &'static str,
+) -> Result<Option<Vec<u8>>, LmdbStorageError> {
+    let access = txn.access();
+    let value = access
+        .get::<_, [u8]>(db, key)
+        .to_opt()
+        .map_err(|source| LmdbStorageError::LmdbError { source, operation })?;
+    Ok(value.map(|v| v.to_vec()))
+}
+
+pub fn exists(
+    txn: &ConstTransaction<'_>,
+    db: &Database,
+    key: &[u8],
+    operation: &'static str,
+) -> Result<bool, LmdbStorageError> {
+    let access = txn.access();
+    let value = access
+        .get::<_, [u8]>(db, key)
+        .to_opt()
+        .map_err(|source| LmdbStorageError::LmdbError { source, operation })?;
+    Ok(value.is_some())
+}
+
+/// Inserts or replaces the value at `key`
+pub fn put<T: Serialize + ?Sized>(
+    txn: &WriteTransaction<'_>,
+    db: &Database,
+    key: &[u8],
+    value: &T,
+    operation: &'static str,
+    item: &'static str,
+) -> Result<(), LmdbStorageError> {
+    let value = encode(operation, item, value)?;
+    put_raw(txn, db, key, &value, operation)
+}
+
+/// Inserts the value at `key`, returning an AlreadyExists error if the key exists
+pub fn insert<T: Serialize + ?Sized>(
+    txn: &WriteTransaction<'_>,
+    db: &Database,
+    key: &[u8],
+    value: &T,
+    operation: &'static str,
+    item: &'static str,
+) -> Result<(), LmdbStorageError> {
+    let value = encode(operation, item, value)?;
+    let mut access = txn.access();
+    match access.put(db, key, &value[..], put::NOOVERWRITE) {
+        Ok(()) => Ok(()),
+        Err(lmdb_zero::Error::Code(lmdb_zero::error::KEYEXIST)) => Err(LmdbStorageError::AlreadyExists {
+            operation,
+            item,
+            key: hex::encode(key),
+        }),
+        Err(source) => Err(LmdbStorageError::LmdbError { source, operation }),
+    }
+}
+
+pub fn put_raw(
+    txn: &WriteTransaction<'_>,
+    db: &Database,
+    key: &[u8],
+    value: &[u8],
+    operation: &'static str,
+) -> Result<(), LmdbStorageError> {
+    let mut access = txn.access();
+    access
+        .put(db, key, value, put::Flags::empty())
+        .map_err(|source| LmdbStorageError::LmdbError { source, operation })
+}
+
+/// Deletes the value at `key`. Returns true if the key existed.
+pub fn delete(
+    txn: &WriteTransaction<'_>,
+    db: &Database,
+    key: &[u8],
+    operation: &'static str,
+) -> Result<bool, LmdbStorageError> {
+    let mut access = txn.access();
+    let deleted = access
+        .del_key(db, key)
+        .to_opt()
+        .map_err(|source| LmdbStorageError::LmdbError { source, operation })?;
+    Ok(deleted.is_some())
+}
+
+/// Returns all keys and values where the key starts with `prefix`, in key order.
+pub fn prefix_scan<T: DeserializeOwned>(
+    txn: &ConstTransaction<'_>,
+    db: &Database,
+    prefix: &[u8],
+    operation: &'static str,
+) -> Result<Vec<(Vec<u8>, T)>, LmdbStorageError> {
+    range_scan(txn, db, Some(prefix), operation, |key| key.starts_with(prefix))
+}
+
+/// Returns all keys that start with `prefix`, in key order. Used for index databases that have empty values.
+pub fn prefix_keys(
+    txn: &ConstTransaction<'_>,
+    db: &Database,
+    prefix: &[u8],
+    operation: &'static str,
+) -> Result<Vec<Vec<u8>>, LmdbStorageError> {
+    range_keys(txn, db, Some(prefix), operation, |key| key.starts_with(prefix))
+}
+
+/// Returns true if any key starts with `prefix`
+pub fn prefix_exists(
+    txn: &ConstTransaction<'_>,
+    db: &Database,
+    prefix: &[u8],
+    operation: &'static str,
+) -> Result<bool, LmdbStorageError> {
+    let access = txn.access();
+    let mut cursor = txn
+        .cursor(db)
+        .map_err(|source| LmdbStorageError::LmdbError { source, operation })?;
+    let first = cursor
+        .seek_range_k::<[u8], [u8]>(&access, prefix)
+        .to_opt()
+        .map_err(|source| LmdbStorageError::LmdbError { source, operation })?;
+    Ok(first.map_or(false, |(key, _)| key.starts_with(prefix)))
+}
+
+/// Returns all keys from `start` (inclusive, or the first key if None) while `predicate` returns true for the key, in
+/// key order.
+pub fn range_keys<F: Fn(&[u8]) -> bool>(
+    txn: &ConstTransaction<'_>,
+    db: &Database,
+    start: Option<&[u8]>,
+    operation: &'static str,
+    predicate: F,
+) -> Result<Vec<Vec<u8>>, LmdbStorageError> {
+    let entries = range_raw(txn, db, start, operation, predicate)?;
+    Ok(entries.into_iter().map(|(k, _)| k).collect())
+}
+
+/// Returns all keys and values from `start` (inclusive, or the first key if None) while `predicate` returns true for
+/// the key, in key order.
+pub fn range_scan<T: DeserializeOwned, F: Fn(&[u8]) -> bool>(
+    txn: &ConstTransaction<'_>,
+    db: &Database,
+    start: Option<&[u8]>,
+    operation: &'static str,
+    predicate: F,
+) -> Result<Vec<(Vec<u8>, T)>, LmdbStorageError> {
+    let entries = range_raw(txn, db, start, operation, predicate)?;
+    entries
+        .into_iter()
+        .map(|(k, v)| Ok((k, decode(operation, &v)?)))
+        .collect()
+}
+
+/// Returns all keys and undecoded values from `start` (inclusive, or the first key if None) while `predicate` returns
+/// true for the key, in key order.
+pub fn range_raw<F: Fn(&[u8]) -> bool>(
+    txn: &ConstTransaction<'_>,
+    db: &Database,
+    start: Option<&[u8]>,
+    operation: &'static str,
+    predicate: F,
+) -> Result<Vec<(Vec<u8>, Vec<u8>)>, LmdbStorageError> {
+    let access = txn.access();
+    let mut cursor = txn
+        .cursor(db)
+        .map_err(|source| LmdbStorageError::LmdbError { source, operation })?;
+    let mut results = Vec::new();
+    let mut next = match start {
+        Some(start) => cursor.seek_range_k::<[u8], [u8]>(&access, start),
+        None => cursor.first::<[u8], [u8]>(&access),
+    }
+    .to_opt()
+    .map_err(|source| LmdbStorageError::LmdbError { source, operation })?;
+    while let Some((key, value)) = next {
+        if !predicate(key) {
+            break;
+        }
+        results.push((key.to_vec(), value.to_vec()));
+        next = cursor
+            .next::<[u8], [u8]>(&access)
+            .to_opt()
+            .map_err(|source| LmdbStorageError::LmdbError { source, operation })?;
+    }
+    Ok(results)
+}
+
+/// Returns up to `limit` decoded values from `start` (inclusive, or the first key if None) while `predicate` returns
+/// true for the key, in key order. Values for which `filter` returns false are skipped and do not count towards the
+/// limit.
+pub fn range_scan_limit<T: DeserializeOwned, P: Fn(&[u8]) -> bool, F: Fn(&[u8], &T) -> bool>(
+    txn: &ConstTransaction<'_>,
+    db: &Database,
+    start: Option<&[u8]>,
+    operation: &'static str,
+    limit: usize,
+    predicate: P,
+    filter: F,
+) -> Result<Vec<T>, LmdbStorageError> {
+    let access = txn.access();
+    let mut cursor = txn
+        .cursor(db)
+        .map_err(|source| LmdbStorageError::LmdbError { source, operation })?;
+    let mut results = Vec::new();
+    let mut next = match start {
+        Some(start) => cursor.seek_range_k::<[u8], [u8]>(&access, start),
+        None => cursor.first::<[u8], [u8]>(&access),
+    }
+    .to_opt()
+    .map_err(|source| LmdbStorageError::LmdbError { source, operation })?;
+    while let Some((key, value)) = next {
+        if results.len() >= limit || !predicate(key) {
+            break;
+        }
+        let value = decode(operation, value)?;
+        if filter(key, &value) {
+            results.push(value);
+        }
+        next = cursor
+            .next::<[u8], [u8]>(&access)
+            .to_opt()
+            .map_err(|source| LmdbStorageError::LmdbError { source, operation })?;
+    }
+    Ok(results)
+}
+
+/// Returns the first key after `after` (exclusive, or the first key if None), in key order. `after` does not have to
+/// exist, so this can be used to continue a scan from a key that has since been deleted.
+pub fn next_key(
+    txn: &ConstTransaction<'_>,
+    db: &Database,
+    after: Option<&[u8]>,
+    operation: &'static str,
+) -> Result<Option<Vec<u8>>, LmdbStorageError> {
+    let access = txn.access();
+    let mut cursor = txn
+        .cursor(db)
+        .map_err(|source| LmdbStorageError::LmdbError { source, operation })?;
+    let mut next = match after {
+        Some(after) => cursor.seek_range_k::<[u8], [u8]>(&access, after),
+        None => cursor.first::<[u8], [u8]>(&access),
+    }
+    .to_opt()
+    .map_err(|source| LmdbStorageError::LmdbError { source, operation })?;
+    if let (Some((key, _)), Some(after)) = (next, after) {
+        if key == after {
+            next = cursor
+                .next::<[u8], [u8]>(&access)
+                .to_opt()
+                .map_err(|source| LmdbStorageError::LmdbError { source, operation })?;
+        }
+    }
+    Ok(next.map(|(key, _)| key.to_vec()))
+}
+
+/// Returns all keys and values in the database, in key order.
+pub fn scan_all<T: DeserializeOwned>(
+    txn: &ConstTransaction<'_>,
+    db: &Database,
+    operation: &'static str,
+) -> Result<Vec<(Vec<u8>, T)>, LmdbStorageError> {
+    range_scan(txn, db, None, operation, |_| true)
+}
+
+/// Returns all keys and undecoded values in the database, in key order.
+pub fn scan_all_raw(
+    txn: &ConstTransaction<'_>,
+    db: &Database,
+    operation: &'static str,
+) -> Result<Vec<(Vec<u8>, Vec<u8>)>, LmdbStorageError> {
+    range_raw(txn, db, None, operation, |_| true)
+}
+
+/// Returns the number of entries in the database
+pub fn count(txn: &ConstTransaction<'_>, db: &Database, operation: &'static str) -> Result<usize, LmdbStorageError> {
+    let stat = txn
+        .db_stat(db)
+        .map_err(|source| LmdbStorageError::LmdbError { source, operation })?;
+    Ok(stat.entries)
+}
+
+/// Returns the last key and value in the database
+pub fn last<T: DeserializeOwned>(
+    txn: &ConstTransaction<'_>,
+    db: &Database,
+    operation: &'static str,
+) -> Result<Option<(Vec<u8>, T)>, LmdbStorageError> {
+    last_raw(txn, db, operation)?
+        .map(|(k, v)| Ok((k, decode(operation, &v)?)))
+        .transpose()
+}
+
+/// Returns the last key in the database
+pub fn last_key(
+    txn: &ConstTransaction<'_>,
+    db: &Database,
+    operation: &'static str,
+) -> Result<Option<Vec<u8>>, LmdbStorageError> {
+    Ok(last_raw(txn, db, operation)?.map(|(k, _)| k))
+}
+
+fn last_raw(
+    txn: &ConstTransaction<'_>,
+    db: &Database,
+    operation: &'static str,
+) -> Result<Option<(Vec<u8>, Vec<u8>)>, LmdbStorageError> {
+    let access = txn.access();
+    let mut cursor = txn
+        .cursor(db)
+        .map_err(|source| LmdbStorageError::LmdbError { source, operation })?;
+    let last = cursor
+        .last::<[u8], [u8]>(&access)
+        .to_opt()
+        .map_err(|source| LmdbStorageError::LmdbError { source, operation })?;
+    Ok(last.map(|(k, v)| (k.to_vec(), v.to_vec())))
+}
+
+/// Returns the next value of a u64 big-endian sequence key, i.e. one more than the last key in the database.
+pub fn next_sequence(
+    txn: &ConstTransaction<'_>,
+    db: &Database,
+    operation: &'static str,
+) -> Result<u64, LmdbStorageError> {
+    match last_key(txn, db, operation)? {
+        Some(key) => Ok(decode_u64(operation, &key)? + 1),
+        None => Ok(0),
+    }
+}
+
+pub fn decode_u64(operation: &'static str, bytes: &[u8]) -> Result<u64, LmdbStorageError> {
+    let bytes: [u8; 8] =
+        bytes
+            .get(..8)
+            .and_then(|b| b.try_into().ok())
+            .ok_or_else(|| LmdbStorageError::MalformedDbData {
+                operation,
+                details: format!("expected an 8 byte sequence key but got {} bytes", bytes.len()),
+            })?;
+    Ok(u64::from_be_bytes(bytes))
+}
+
+pub fn composite_key(parts: &[&[u8]]) -> Vec<u8> {
+    parts.concat()
+}
+
+/// Key of the `substates_by_address` index. The version is big-endian so that versions are iterated in order.
+pub fn substate_address_key(address: &SubstateAddress, version: u32) -> Vec<u8> {
+    composite_key(&[address.to_canonical_hash().as_ref(), &version.to_be_bytes()])
+}
+
+pub fn sequence_key(sequence: u64) -> [u8; 8] {
+    sequence.to_be_bytes()
+}
+
+/// Key of the `transaction_pool_new_by_fee` index. The fee priority is inverted so that the highest priority comes
+/// first in key order.
+pub fn fee_priority_key(fee_priority: u64, transaction_id: &TransactionId) -> Vec<u8> {
+    composite_key(&[&sequence_key(u64::MAX - fee_priority), transaction_id.as_bytes()])
+}
+
+/// Decodes an ID from a (part of a) key
+pub fn id_from_bytes<T>(operation: &'static str, bytes: &[u8]) -> Result<T, LmdbStorageError>
+where T: for<'b> TryFrom<&'b [u8]> {
+    T::try_from(bytes).map_err(|_| LmdbStorageError::MalformedDbData {
+        operation,
+        details: format!("Invalid ID in key: {}", hex::encode(bytes)),
+    })
+}
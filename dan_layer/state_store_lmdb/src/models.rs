@@ -0,0 +1,470 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use std::collections::BTreeSet;
+
+use serde::{Deserialize, Serialize};
+use tari_common_types::types::PublicKey;
+use tari_dan_common_types::{Epoch, NodeAddressable, NodeHeight, ShardId, View};
+use tari_dan_storage::{
+    consensus_models,
+    consensus_models::{
+        BlockId,
+        Command,
+        Decision,
+        Evidence,
+        HighQc,
+        LastExecuted,
+        LastProposed,
+        LastSentVote,
+        LastVoted,
+        LeafBlock,
+        LockedBlock,
+        LockedOutput,
+        QcId,
+        QuorumCertificate,
+        QuorumDecision,
+        SubstateRecord,
+        TimeoutCertificate,
+        TransactionAtom,
+        TransactionFeeInfo,
+        TransactionPoolStage,
+        TransactionPoolStatusUpdate,
+        ValidatorSignature,
+    },
+};
+use tari_transaction::TransactionId;
+use time::PrimitiveDateTime;
+
+use crate::error::LmdbStorageError;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Block<TAddr> {
+    pub block_id: BlockId,
+    pub parent_block_id: BlockId,
+    pub height: NodeHeight,
+    pub view: View,
+    pub epoch: Epoch,
+    pub epoch_cutoff: Option<NodeHeight>,
+    pub proposed_by: TAddr,
+    pub signature: Option<ValidatorSignature<TAddr>>,
+    pub qc_id: QcId,
+    pub timeout_certificate: Option<TimeoutCertificate<TAddr>>,
+    pub commands: BTreeSet<Command>,
+    pub total_leader_fee: u64,
+    pub is_committed: bool,
+    pub is_processed: bool,
+    pub created_at: PrimitiveDateTime,
+}
+
+impl<TAddr: NodeAddressable + Serialize> Block<TAddr> {
+    pub fn new(block: &consensus_models::Block<TAddr>, created_at: PrimitiveDateTime) -> Self {
+        Self {
+            block_id: *block.id(),
+            parent_block_id: *block.parent(),
+            height: block.height(),
+            view: block.view(),
+            epoch: block.epoch(),
+            epoch_cutoff: block.epoch_cutoff(),
+            proposed_by: block.proposed_by().clone(),
+            signature: block.signature().cloned(),
+            qc_id: *block.justify().id(),
+            timeout_certificate: block.timeout_certificate().cloned(),
+            commands: block.commands().clone(),
+            total_leader_fee: block.total_leader_fee(),
+            // Blocks are never inserted as committed
+            is_committed: false,
+            is_processed: block.is_processed(),
+            created_at,
+        }
+    }
+
+    pub fn command_count(&self) -> usize {
+        self.commands.len()
+    }
+
+    pub fn into_block(self, qc: QuorumCertificate<TAddr>) -> consensus_models::Block<TAddr> {
+        consensus_models::Block::load(
+            self.block_id,
+            self.parent_block_id,
+            qc,
+            self.timeout_certificate,
+            self.height,
+            self.view,
+            self.epoch,
+            self.epoch_cutoff,
+            self.proposed_by,
+            self.signature,
+            self.commands,
+            self.total_leader_fee,
+            self.is_processed,
+            self.is_committed,
+            self.created_at,
+        )
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ParkedBlock<TAddr> {
+    pub block: consensus_models::Block<TAddr>,
+    pub created_at: PrimitiveDateTime,
+}
+
+impl<TAddr: NodeAddressable + Serialize> ParkedBlock<TAddr> {
+    pub fn into_block(self) -> consensus_models::Block<TAddr> {
+        let block = self.block;
+        consensus_models::Block::load(
+            *block.id(),
+            *block.parent(),
+            block.justify().clone(),
+            block.timeout_certificate().cloned(),
+            block.height(),
+            block.view(),
+            block.epoch(),
+            block.epoch_cutoff(),
+            block.proposed_by().clone(),
+            block.signature().cloned(),
+            block.commands().clone(),
+            block.total_leader_fee(),
+            false,
+            false,
+            self.created_at,
+        )
+    }
+}
+
+/// Row used for all bookkeeping pointers that consist of a block id and height i.e. last voted, last executed, last
+/// proposed, locked block and leaf block.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlockPointer {
+    pub block_id: BlockId,
+    pub height: NodeHeight,
+}
+
+impl BlockPointer {
+    pub fn is_same(&self, block_id: &BlockId, height: NodeHeight) -> bool {
+        self.block_id == *block_id && self.height == height
+    }
+}
+
+impl From<&LastVoted> for BlockPointer {
+    fn from(value: &LastVoted) -> Self {
+        Self {
+            block_id: value.block_id,
+            height: value.height,
+        }
+    }
+}
+
+impl From<BlockPointer> for LastVoted {
+    fn from(value: BlockPointer) -> Self {
+        Self {
+            block_id: value.block_id,
+            height: value.height,
+        }
+    }
+}
+
+impl From<&LastExecuted> for BlockPointer {
+    fn from(value: &LastExecuted) -> Self {
+        Self {
+            block_id: value.block_id,
+            height: value.height,
+        }
+    }
+}
+
+impl From<BlockPointer> for LastExecuted {
+    fn from(value: BlockPointer) -> Self {
+        Self {
+            block_id: value.block_id,
+            height: value.height,
+        }
+    }
+}
+
+impl From<&LastProposed> for BlockPointer {
+    fn from(value: &LastProposed) -> Self {
+        Self {
+            block_id: value.block_id,
+            height: value.height,
+        }
+    }
+}
+
+impl From<BlockPointer> for LastProposed {
+    fn from(value: BlockPointer) -> Self {
+        Self {
+            block_id: value.block_id,
+            height: value.height,
+        }
+    }
+}
+
+impl From<&LockedBlock> for BlockPointer {
+    fn from(value: &LockedBlock) -> Self {
+        Self {
+            block_id: value.block_id,
+            height: value.height,
+        }
+    }
+}
+
+impl From<BlockPointer> for LockedBlock {
+    fn from(value: BlockPointer) -> Self {
+        Self {
+            block_id: value.block_id,
+            height: value.height,
+        }
+    }
+}
+
+impl From<&LeafBlock> for BlockPointer {
+    fn from(value: &LeafBlock) -> Self {
+        Self {
+            block_id: value.block_id,
+            height: value.height,
+        }
+    }
+}
+
+impl From<BlockPointer> for LeafBlock {
+    fn from(value: BlockPointer) -> Self {
+        Self {
+            block_id: value.block_id,
+            height: value.height,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HighQcRow {
+    pub block_id: BlockId,
+    pub block_height: NodeHeight,
+    pub qc_id: QcId,
+}
+
+impl From<&HighQc> for HighQcRow {
+    fn from(value: &HighQc) -> Self {
+        Self {
+            block_id: value.block_id,
+            block_height: value.block_height,
+            qc_id: value.qc_id,
+        }
+    }
+}
+
+impl From<HighQcRow> for HighQc {
+    fn from(value: HighQcRow) -> Self {
+        Self {
+            block_id: value.block_id,
+            block_height: value.block_height,
+            qc_id: value.qc_id,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LastSentVoteRow<TAddr> {
+    pub epoch: Epoch,
+    pub block_id: BlockId,
+    pub block_height: NodeHeight,
+    pub decision: QuorumDecision,
+    pub signature: ValidatorSignature<TAddr>,
+}
+
+impl<TAddr: Clone> From<&LastSentVote<TAddr>> for LastSentVoteRow<TAddr> {
+    fn from(value: &LastSentVote<TAddr>) -> Self {
+        Self {
+            epoch: value.epoch,
+            block_id: value.block_id,
+            block_height: value.block_height,
+            decision: value.decision,
+            signature: value.signature.clone(),
+        }
+    }
+}
+
+impl<TAddr> From<LastSentVoteRow<TAddr>> for LastSentVote<TAddr> {
+    fn from(value: LastSentVoteRow<TAddr>) -> Self {
+        Self {
+            epoch: value.epoch,
+            block_id: value.block_id,
+            block_height: value.block_height,
+            decision: value.decision,
+            signature: value.signature,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransactionPoolRecord {
+    pub transaction_id: TransactionId,
+    pub original_decision: Decision,
+    pub local_decision: Option<Decision>,
+    pub remote_decision: Option<Decision>,
+    pub evidence: Evidence,
+    pub remote_evidence: Option<Evidence>,
+    pub transaction_fee: u64,
+    pub leader_fee: u64,
+    pub stage: String,
+    // The stage of the last pending update. As in the SQLite store, this is only used for transaction_pool_count and
+    // is not given to TransactionPoolRecord::load.
+    pub pending_stage: Option<String>,
+    pub is_ready: bool,
+    pub updated_at: PrimitiveDateTime,
+    pub created_at: PrimitiveDateTime,
+    pub signer: PublicKey,
+    pub transaction_size: usize,
+}
+
+impl TransactionPoolRecord {
+    pub fn new(
+        transaction: TransactionAtom,
+        fee_info: &TransactionFeeInfo,
+        stage: TransactionPoolStage,
+        is_ready: bool,
+        created_at: PrimitiveDateTime,
+    ) -> Self {
+        Self {
+            transaction_id: transaction.id,
+            original_decision: transaction.decision,
+            local_decision: None,
+            remote_decision: None,
+            evidence: transaction.evidence,
+            remote_evidence: None,
+            transaction_fee: transaction.transaction_fee,
+            leader_fee: transaction.leader_fee,
+            stage: stage.to_string(),
+            pending_stage: None,
+            is_ready,
+            updated_at: created_at,
+            created_at,
+            signer: fee_info.signer.clone(),
+            transaction_size: fee_info.size,
+        }
+    }
+
+    pub fn fee_info(&self) -> TransactionFeeInfo {
+        TransactionFeeInfo {
+            signer: self.signer.clone(),
+            size: self.transaction_size,
+        }
+    }
+
+    /// Returns true if the committed stage of the transaction is New, ignoring pending updates
+    pub fn is_new(&self) -> bool {
+        self.stage == TransactionPoolStage::New.to_string()
+    }
+
+    pub fn fee_priority(&self) -> u64 {
+        self.fee_info().fee_priority(self.transaction_fee)
+    }
+
+    pub fn try_convert(
+        mut self,
+        update: Option<TransactionPoolStateUpdate>,
+    ) -> Result<consensus_models::TransactionPoolRecord, LmdbStorageError> {
+        let mut evidence = self.evidence;
+        let mut pending_stage = None;
+        if let Some(update) = update {
+            evidence.merge(update.evidence);
+            self.is_ready = update.is_ready;
+            pending_stage = Some(parse_stage("transaction_pool_record", &update.stage)?);
+        }
+
+        if let Some(remote_evidence) = self.remote_evidence {
+            evidence.merge(remote_evidence);
+        }
+
+        Ok(consensus_models::TransactionPoolRecord::load(
+            TransactionAtom {
+                id: self.transaction_id,
+                decision: self.original_decision,
+                evidence,
+                transaction_fee: self.transaction_fee,
+                leader_fee: self.leader_fee,
+            },
+            self.fee_info(),
+            parse_stage("transaction_pool_record", &self.stage)?,
+            pending_stage,
+            self.local_decision,
+            self.remote_decision,
+            self.is_ready,
+        ))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransactionPoolStateUpdate {
+    pub block_id: BlockId,
+    pub block_height: NodeHeight,
+    pub transaction_id: TransactionId,
+    pub stage: String,
+    pub evidence: Evidence,
+    pub is_ready: bool,
+    pub local_decision: Decision,
+}
+
+impl From<TransactionPoolStatusUpdate> for TransactionPoolStateUpdate {
+    fn from(value: TransactionPoolStatusUpdate) -> Self {
+        Self {
+            block_id: value.block_id,
+            block_height: value.block_height,
+            transaction_id: value.transaction_id,
+            stage: value.stage.to_string(),
+            evidence: value.evidence,
+            is_ready: value.is_ready,
+            local_decision: value.local_decision,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MissingTransaction {
+    pub block_view: View,
+    pub is_awaiting_execution: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Substate {
+    pub record: SubstateRecord,
+    pub read_locks: u32,
+    pub is_locked_w: bool,
+    pub locked_by: Option<TransactionId>,
+}
+
+impl Substate {
+    pub fn new(record: SubstateRecord) -> Self {
+        Self {
+            record,
+            read_locks: 0,
+            is_locked_w: false,
+            locked_by: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LockedOutputRow {
+    pub block_id: BlockId,
+    pub transaction_id: TransactionId,
+    pub shard_id: ShardId,
+}
+
+impl From<LockedOutputRow> for LockedOutput {
+    fn from(value: LockedOutputRow) -> Self {
+        Self {
+            block_id: value.block_id,
+            transaction_id: value.transaction_id,
+            shard_id: value.shard_id,
+        }
+    }
+}
+
+fn parse_stage(operation: &'static str, stage: &str) -> Result<TransactionPoolStage, LmdbStorageError> {
+    stage.parse().map_err(|_| LmdbStorageError::MalformedDbData {
+        operation,
+        details: format!("Invalid transaction pool stage '{}'", stage),
+    })
+}
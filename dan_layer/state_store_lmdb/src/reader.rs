@@ -0,0 +1,1272 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use std::{
+    borrow::Borrow,
+    collections::{BTreeSet, HashMap, HashSet},
+    marker::PhantomData,
+    ops::RangeInclusive,
+};
+
+use lmdb_zero::{ConstTransaction, WriteTransaction};
+use log::*;
+use serde::{de::DeserializeOwned, Serialize};
+use tari_common_types::types::FixedHash;
+use tari_dan_common_types::{Epoch, NodeAddressable, NodeHeight, ShardId};
+use tari_dan_storage::{
+    consensus_models::{
+        Block,
+        BlockId,
+        HighQc,
+        LastExecuted,
+        LastProposed,
+        LastSentVote,
+        LastVoted,
+        LeafBlock,
+        LockedBlock,
+        LockedOutput,
+        QcId,
+        QuorumCertificate,
+        SubstateLockFlag,
+        SubstateLockState,
+        SubstatePruneMark,
+        SubstateRecord,
+        TransactionPoolRecord,
+        TransactionPoolStage,
+        TransactionRecord,
+        Vote,
+    },
+    Ordering,
+    StateStoreReadTransaction,
+    StorageError,
+};
+use tari_engine_types::substate::SubstateAddress;
+use tari_transaction::TransactionId;
+
+use crate::{
+    error::LmdbStorageError,
+    lmdb,
+    lmdb::{fee_priority_key, LmdbTransaction},
+    models,
+    store::Databases,
+};
+
+const LOG_TARGET: &str = "tari::dan::storage::state_store_lmdb::reader";
+
+/// Maximum number of blocks walked when following parent links, matching the recursion limit of the SQLite store.
+const MAX_BLOCK_WALK: usize = 1000;
+
+pub struct LmdbStateStoreReadTransaction<'a, TAddr> {
+    transaction: LmdbTransaction<'a>,
+    databases: &'a Databases,
+    _addr: PhantomData<TAddr>,
+}
+
+impl<'a, TAddr> LmdbStateStoreReadTransaction<'a, TAddr> {
+    pub(crate) fn new(transaction: LmdbTransaction<'a>, databases: &'a Databases) -> Self {
+        Self {
+            transaction,
+            databases,
+            _addr: PhantomData,
+        }
+    }
+
+    pub(crate) fn txn(&self) -> &ConstTransaction<'a> {
+        &self.transaction
+    }
+
+    pub(crate) fn write_txn(&self) -> &WriteTransaction<'a> {
+        self.transaction
+            .as_write()
+            .expect("write_txn called on a read transaction. This is a bug")
+    }
+
+    pub(crate) fn databases(&self) -> &'a Databases {
+        self.databases
+    }
+
+    pub(crate) fn commit(self) -> Result<(), LmdbStorageError> {
+        self.transaction.commit()
+    }
+}
+
+impl<'a, TAddr: NodeAddressable + Serialize + DeserializeOwned> LmdbStateStoreReadTransaction<'a, TAddr> {
+    pub(crate) fn get_block_row(&self, block_id: &BlockId) -> Result<Option<models::Block<TAddr>>, LmdbStorageError> {
+        lmdb::get(self.txn(), &self.databases.blocks, block_id.as_bytes(), "blocks_get")
+    }
+
+    pub(crate) fn load_block(
+        &self,
+        operation: &'static str,
+        block: models::Block<TAddr>,
+    ) -> Result<Block<TAddr>, LmdbStorageError> {
+        let qc = lmdb::get::<QuorumCertificate<TAddr>>(
+            self.txn(),
+            &self.databases.quorum_certificates,
+            block.qc_id.as_bytes(),
+            operation,
+        )?
+        .ok_or_else(|| LmdbStorageError::DbInconsistency {
+            operation,
+            details: format!(
+                "block {} references non-existent quorum certificate {}",
+                block.block_id, block.qc_id
+            ),
+        })?;
+        Ok(block.into_block(qc))
+    }
+
+    fn load_blocks<I: IntoIterator<Item = models::Block<TAddr>>>(
+        &self,
+        operation: &'static str,
+        blocks: I,
+    ) -> Result<Vec<Block<TAddr>>, StorageError> {
+        blocks
+            .into_iter()
+            .map(|b| self.load_block(operation, b).map_err(StorageError::from))
+            .collect()
+    }
+
+    fn get_block_rows_by_index(
+        &self,
+        db: &lmdb_zero::Database,
+        prefix: &[u8],
+        operation: &'static str,
+    ) -> Result<Vec<models::Block<TAddr>>, LmdbStorageError> {
+        let keys = lmdb::prefix_keys(self.txn(), db, prefix, operation)?;
+        keys.iter()
+            .map(|key| {
+                let block_id = lmdb::id_from_bytes::<BlockId>(operation, &key[prefix.len()..])?;
+                self.get_block_row(&block_id)?
+                    .ok_or_else(|| LmdbStorageError::DbInconsistency {
+                        operation,
+                        details: format!("index references non-existent block {}", block_id),
+                    })
+            })
+            .collect()
+    }
+
+    /// Walks the parent links from `end_block` (inclusive) until `start_block` (inclusive) is reached, genesis is
+    /// reached or `MAX_BLOCK_WALK` blocks have been visited.
+    pub(crate) fn get_block_rows_between(
+        &self,
+        start_block: &BlockId,
+        end_block: &BlockId,
+    ) -> Result<Vec<models::Block<TAddr>>, LmdbStorageError> {
+        let mut blocks = Vec::new();
+        let mut current = *end_block;
+        while blocks.len() < MAX_BLOCK_WALK {
+            let Some(block) = self.get_block_row(&current)? else {
+                break;
+            };
+            let is_done = block.block_id == *start_block || block.block_id == block.parent_block_id;
+            current = block.parent_block_id;
+            blocks.push(block);
+            if is_done {
+                break;
+            }
+        }
+        Ok(blocks)
+    }
+
+    pub(crate) fn get_transaction_atom_state_updates_between_blocks<'i, ITx>(
+        &self,
+        from_block_id: &BlockId,
+        to_block_id: &BlockId,
+        transaction_ids: ITx,
+    ) -> Result<HashMap<TransactionId, models::TransactionPoolStateUpdate>, LmdbStorageError>
+    where
+        ITx: IntoIterator<Item = &'i TransactionId>,
+    {
+        let mut transaction_ids = transaction_ids.into_iter().peekable();
+        if transaction_ids.peek().is_none() {
+            return Ok(HashMap::new());
+        }
+
+        let applicable_block_ids = self
+            .get_block_rows_between(from_block_id, to_block_id)?
+            .into_iter()
+            .filter(|b| b.command_count() > 0)
+            .map(|b| b.block_id)
+            .collect::<HashSet<_>>();
+
+        debug!(
+            target: LOG_TARGET,
+            "get_transaction_atom_state_updates_between_blocks: from_block_id={}, to_block_id={}, len(applicable_block_ids)={}",
+            from_block_id,
+            to_block_id,
+            applicable_block_ids.len());
+
+        if applicable_block_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let mut updates = HashMap::new();
+        for transaction_id in transaction_ids {
+            let latest = lmdb::prefix_scan::<models::TransactionPoolStateUpdate>(
+                self.txn(),
+                &self.databases.transaction_pool_state_updates,
+                transaction_id.as_bytes(),
+                "get_transaction_atom_state_updates_between_blocks",
+            )?
+            .into_iter()
+            .map(|(_, update)| update)
+            .filter(|update| applicable_block_ids.contains(&update.block_id))
+            .max_by_key(|update| update.block_height);
+
+            if let Some(update) = latest {
+                updates.insert(*transaction_id, update);
+            }
+        }
+
+        Ok(updates)
+    }
+
+    pub(crate) fn get_substate_row(
+        &self,
+        shard_id: &ShardId,
+        operation: &'static str,
+    ) -> Result<Option<models::Substate>, LmdbStorageError> {
+        lmdb::get(self.txn(), &self.databases.substates, shard_id.as_bytes(), operation)
+    }
+
+    /// Returns the substate for a key of the substates by address index, which is the address hash followed by the big
+    /// endian version
+    fn get_substate_by_address_key(
+        &self,
+        address: &SubstateAddress,
+        key: &[u8],
+        operation: &'static str,
+    ) -> Result<SubstateRecord, LmdbStorageError> {
+        let version = key[address.to_canonical_hash().as_ref().len()..]
+            .try_into()
+            .map(u32::from_be_bytes)
+            .map_err(|_| LmdbStorageError::MalformedDbData {
+                operation,
+                details: format!("invalid substates_by_address key for {}", address),
+            })?;
+        let shard_id = ShardId::from_address(address, version);
+        let substate =
+            self.get_substate_row(&shard_id, operation)?
+                .ok_or_else(|| LmdbStorageError::DbInconsistency {
+                    operation,
+                    details: format!("index references non-existent substate {}:{}", address, version),
+                })?;
+        Ok(substate.record)
+    }
+
+    /// Returns the unique substates for the given shards, or a NotAllSubstatesFound error if any are missing
+    pub(crate) fn get_substate_rows_for_lock<'i, I: IntoIterator<Item = &'i ShardId>>(
+        &self,
+        objects: I,
+        lock_flag: SubstateLockFlag,
+    ) -> Result<Vec<models::Substate>, LmdbStorageError> {
+        let objects = objects.into_iter().collect::<HashSet<_>>();
+        let substates = objects
+            .iter()
+            .filter_map(|shard_id| self.get_substate_row(shard_id, "substates_try_lock_all").transpose())
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if substates.len() < objects.len() {
+            return Err(LmdbStorageError::NotAllSubstatesFound {
+                operation: "substates_try_lock_all",
+                details: format!(
+                    "{:?}: Found {} substates, but {} were requested",
+                    lock_flag,
+                    substates.len(),
+                    objects.len()
+                ),
+            });
+        }
+
+        Ok(substates)
+    }
+
+    fn get_substates_by_index(
+        &self,
+        db: &lmdb_zero::Database,
+        prefix: &[u8],
+        operation: &'static str,
+    ) -> Result<Vec<SubstateRecord>, LmdbStorageError> {
+        let keys = lmdb::prefix_keys(self.txn(), db, prefix, operation)?;
+        keys.iter()
+            .map(|key| {
+                let shard_id = lmdb::id_from_bytes::<ShardId>(operation, &key[prefix.len()..])?;
+                self.get_substate_row(&shard_id, operation)?
+                    .map(|s| s.record)
+                    .ok_or_else(|| LmdbStorageError::DbInconsistency {
+                        operation,
+                        details: format!("index references non-existent substate {}", shard_id),
+                    })
+            })
+            .collect()
+    }
+
+    fn last_row<T: DeserializeOwned>(
+        &self,
+        db: &lmdb_zero::Database,
+        operation: &'static str,
+        item: &'static str,
+    ) -> Result<T, StorageError> {
+        let (_, row) = lmdb::last::<T>(self.txn(), db, operation)?.ok_or_else(|| StorageError::NotFound {
+            item: item.to_string(),
+            key: "latest".to_string(),
+        })?;
+        Ok(row)
+    }
+
+    fn paginate_by_created<T, F>(
+        &self,
+        db: &lmdb_zero::Database,
+        limit: u64,
+        offset: u64,
+        ordering: Option<Ordering>,
+        operation: &'static str,
+        mut load: F,
+    ) -> Result<Vec<T>, StorageError>
+    where
+        F: FnMut(&[u8]) -> Result<T, StorageError>,
+    {
+        let ids = lmdb::scan_all_raw(self.txn(), db, operation)?;
+        let iter: Box<dyn Iterator<Item = _>> = match ordering {
+            Some(Ordering::Descending) => Box::new(ids.into_iter().rev()),
+            Some(Ordering::Ascending) | None => Box::new(ids.into_iter()),
+        };
+        iter.skip(offset as usize)
+            .take(limit as usize)
+            .map(|(_, id)| load(&id))
+            .collect()
+    }
+}
+
+impl<TAddr: NodeAddressable + Serialize + DeserializeOwned> StateStoreReadTransaction
+    for LmdbStateStoreReadTransaction<'_, TAddr>
+{
+    type Addr = TAddr;
+
+    fn last_sent_vote_get(&mut self) -> Result<LastSentVote<Self::Addr>, StorageError> {
+        let row = self.last_row::<models::LastSentVoteRow<TAddr>>(
+            &self.databases.last_sent_vote,
+            "last_sent_vote_get",
+            "last_sent_vote",
+        )?;
+        Ok(row.into())
+    }
+
+    fn last_voted_get(&mut self) -> Result<LastVoted, StorageError> {
+        let row = self.last_row::<models::BlockPointer>(&self.databases.last_voted, "last_voted_get", "last_voted")?;
+        Ok(row.into())
+    }
+
+    fn last_executed_get(&mut self) -> Result<LastExecuted, StorageError> {
+        let row =
+            self.last_row::<models::BlockPointer>(&self.databases.last_executed, "last_executed_get", "last_executed")?;
+        Ok(row.into())
+    }
+
+    fn last_proposed_get(&mut self) -> Result<LastProposed, StorageError> {
+        let row =
+            self.last_row::<models::BlockPointer>(&self.databases.last_proposed, "last_proposed_get", "last_proposed")?;
+        Ok(row.into())
+    }
+
+    fn locked_block_get(&mut self) -> Result<LockedBlock, StorageError> {
+        let row =
+            self.last_row::<models::BlockPointer>(&self.databases.locked_block, "locked_block_get", "locked_block")?;
+        Ok(row.into())
+    }
+
+    fn leaf_block_get(&mut self) -> Result<LeafBlock, StorageError> {
+        let row = self.last_row::<models::BlockPointer>(&self.databases.leaf_blocks, "leaf_block_get", "leaf_block")?;
+        Ok(row.into())
+    }
+
+    fn high_qc_get(&mut self) -> Result<HighQc, StorageError> {
+        let row = self.last_row::<models::HighQcRow>(&self.databases.high_qcs, "high_qc_get", "high_qc")?;
+        Ok(row.into())
+    }
+
+    fn transactions_get(&mut self, tx_id: &TransactionId) -> Result<TransactionRecord, StorageError> {
+        let transaction = lmdb::get::<TransactionRecord>(
+            self.txn(),
+            &self.databases.transactions,
+            tx_id.as_bytes(),
+            "transactions_get",
+        )?
+        .ok_or_else(|| StorageError::NotFound {
+            item: "transaction".to_string(),
+            key: tx_id.to_string(),
+        })?;
+        Ok(transaction)
+    }
+
+    fn transactions_exists(&mut self, tx_id: &TransactionId) -> Result<bool, StorageError> {
+        let exists = lmdb::exists(
+            self.txn(),
+            &self.databases.transactions,
+            tx_id.as_bytes(),
+            "transactions_exists",
+        )?;
+        Ok(exists)
+    }
+
+    fn transactions_get_any<'a, I: IntoIterator<Item = &'a TransactionId>>(
+        &mut self,
+        tx_ids: I,
+    ) -> Result<Vec<TransactionRecord>, StorageError> {
+        let mut transactions = Vec::new();
+        for tx_id in tx_ids {
+            if let Some(transaction) = lmdb::get::<TransactionRecord>(
+                self.txn(),
+                &self.databases.transactions,
+                tx_id.as_bytes(),
+                "transactions_get_any",
+            )? {
+                transactions.push(transaction);
+            }
+        }
+        Ok(transactions)
+    }
+
+    fn transactions_get_paginated(
+        &mut self,
+        limit: u64,
+        offset: u64,
+        asc_desc_created_at: Option<Ordering>,
+    ) -> Result<Vec<TransactionRecord>, StorageError> {
+        self.paginate_by_created(
+            &self.databases.transactions_by_created,
+            limit,
+            offset,
+            asc_desc_created_at,
+            "transactions_get_paginated",
+            |id| {
+                let tx_id = lmdb::id_from_bytes::<TransactionId>("transactions_get_paginated", id)?;
+                lmdb::get::<TransactionRecord>(
+                    self.txn(),
+                    &self.databases.transactions,
+                    tx_id.as_bytes(),
+                    "transactions_get_paginated",
+                )?
+                .ok_or_else(|| StorageError::NotFound {
+                    item: "transaction".to_string(),
+                    key: tx_id.to_string(),
+                })
+            },
+        )
+    }
+
+    fn blocks_get(&mut self, block_id: &BlockId) -> Result<Block<TAddr>, StorageError> {
+        let block = self.get_block_row(block_id)?.ok_or_else(|| StorageError::NotFound {
+            item: "block".to_string(),
+            key: block_id.to_string(),
+        })?;
+        Ok(self.load_block("blocks_get", block)?)
+    }
+
+    fn blocks_get_tip(&mut self) -> Result<Block<TAddr>, StorageError> {
+        let key = lmdb::last_key(self.txn(), &self.databases.blocks_by_height, "blocks_get_tip")?.ok_or_else(|| {
+            StorageError::NotFound {
+                item: "block".to_string(),
+                key: "tip".to_string(),
+            }
+        })?;
+        let block_id = lmdb::id_from_bytes::<BlockId>("blocks_get_tip", &key[8..])?;
+        self.blocks_get(&block_id)
+    }
+
+    fn blocks_get_all_between(
+        &mut self,
+        start_block_id_exclusive: &BlockId,
+        end_block_id_inclusive: &BlockId,
+    ) -> Result<Vec<Block<Self::Addr>>, StorageError> {
+        let mut blocks = self.get_block_rows_between(start_block_id_exclusive, end_block_id_inclusive)?;
+        if blocks.is_empty() {
+            return Ok(vec![]);
+        }
+
+        // Exclude start block
+        blocks.pop();
+        blocks.sort_by_key(|b| b.height);
+
+        self.load_blocks("blocks_get_all_between", blocks)
+    }
+
+    fn blocks_exists(&mut self, block_id: &BlockId) -> Result<bool, StorageError> {
+        let exists = lmdb::exists(self.txn(), &self.databases.blocks, block_id.as_bytes(), "blocks_exists")?;
+        Ok(exists)
+    }
+
+    fn blocks_is_ancestor(&mut self, descendant: &BlockId, ancestor: &BlockId) -> Result<bool, StorageError> {
+        if !self.blocks_exists(descendant)? {
+            return Err(StorageError::QueryError {
+                reason: format!("blocks_is_ancestor: descendant block {} does not exist", descendant),
+            });
+        }
+
+        if !self.blocks_exists(ancestor)? {
+            return Err(StorageError::QueryError {
+                reason: format!("blocks_is_ancestor: ancestor block {} does not exist", ancestor),
+            });
+        }
+
+        // TODO: this walks all the way to genesis for every query, same as the SQLite implementation
+        let mut current = *descendant;
+        while let Some(block) = self.get_block_row(&current)? {
+            if block.block_id == *ancestor {
+                return Ok(true);
+            }
+            if block.block_id == block.parent_block_id {
+                break;
+            }
+            current = block.parent_block_id;
+        }
+
+        Ok(false)
+    }
+
+    fn blocks_get_all_by_parent(&mut self, parent: &BlockId) -> Result<Vec<Block<Self::Addr>>, StorageError> {
+        let blocks = self
+            .get_block_rows_by_index(
+                &self.databases.blocks_by_parent,
+                parent.as_bytes(),
+                "blocks_get_all_by_parent",
+            )?
+            .into_iter()
+            // Exclude the genesis block
+            .filter(|b| b.block_id != b.parent_block_id);
+
+        self.load_blocks("blocks_get_all_by_parent", blocks)
+    }
+
+    fn blocks_get_parent_chain(
+        &mut self,
+        block_id: &BlockId,
+        limit: usize,
+    ) -> Result<Vec<Block<Self::Addr>>, StorageError> {
+        if !self.blocks_exists(block_id)? {
+            return Err(StorageError::QueryError {
+                reason: format!("blocks_get_parent_chain: descendant block {} does not exist", block_id),
+            });
+        }
+
+        let mut blocks = Vec::with_capacity(limit);
+        let mut current = *block_id;
+        while blocks.len() < limit {
+            let Some(block) = self.get_block_row(&current)? else {
+                break;
+            };
+            let is_genesis = block.block_id == block.parent_block_id;
+            current = block.parent_block_id;
+            blocks.push(block);
+            if is_genesis {
+                break;
+            }
+        }
+        blocks.sort_by(|a, b| b.height.cmp(&a.height));
+
+        self.load_blocks("blocks_get_parent_chain", blocks)
+    }
+
+    fn blocks_get_pending_transactions(&mut self, block_id: &BlockId) -> Result<Vec<TransactionId>, StorageError> {
+        let keys = lmdb::prefix_keys(
+            self.txn(),
+            &self.databases.missing_transactions,
+            block_id.as_bytes(),
+            "blocks_get_pending_transactions",
+        )?;
+        let tx_ids = keys
+            .iter()
+            .map(|key| lmdb::id_from_bytes("blocks_get_pending_transactions", &key[BlockId::byte_size()..]))
+            .collect::<Result<_, _>>()?;
+        Ok(tx_ids)
+    }
+
+    fn blocks_get_total_leader_fee_for_epoch(
+        &mut self,
+        epoch: Epoch,
+        validator_public_key: &Self::Addr,
+    ) -> Result<u64, StorageError> {
+        let total_fee = self
+            .get_block_rows_by_index(
+                &self.databases.blocks_by_epoch,
+                &epoch.as_u64().to_be_bytes(),
+                "blocks_get_total_leader_fee_for_epoch",
+            )?
+            .into_iter()
+            .filter(|b| b.proposed_by == *validator_public_key)
+            .map(|b| b.total_leader_fee)
+            .sum();
+
+        Ok(total_fee)
+    }
+
+    fn blocks_get_any_with_epoch_range(
+        &mut self,
+        epoch_range: RangeInclusive<Epoch>,
+        validator_public_key: Option<&Self::Addr>,
+    ) -> Result<Vec<Block<Self::Addr>>, StorageError> {
+        let end = epoch_range.end().as_u64();
+        let keys = lmdb::range_keys(
+            self.txn(),
+            &self.databases.blocks_by_epoch,
+            Some(&epoch_range.start().as_u64().to_be_bytes()[..]),
+            "blocks_get_any_with_epoch_range",
+            |key| lmdb::decode_u64("blocks_get_any_with_epoch_range", key).map_or(false, |epoch| epoch <= end),
+        )?;
+
+        let mut blocks = Vec::with_capacity(keys.len());
+        for key in keys {
+            let block_id = lmdb::id_from_bytes::<BlockId>("blocks_get_any_with_epoch_range", &key[8..])?;
+            let block = self
+                .get_block_row(&block_id)?
+                .ok_or_else(|| LmdbStorageError::DbInconsistency {
+                    operation: "blocks_get_any_with_epoch_range",
+                    details: format!("index references non-existent block {}", block_id),
+                })?;
+            if validator_public_key.map_or(true, |vn| block.proposed_by == *vn) {
+                blocks.push(block);
+            }
+        }
+
+        self.load_blocks("blocks_get_any_with_epoch_range", blocks)
+    }
+
+    fn blocks_get_paginated(
+        &mut self,
+        limit: u64,
+        offset: u64,
+        asc_desc_created_at: Option<Ordering>,
+    ) -> Result<Vec<Block<Self::Addr>>, StorageError> {
+        self.paginate_by_created(
+            &self.databases.blocks_by_created,
+            limit,
+            offset,
+            asc_desc_created_at,
+            "blocks_get_paginated",
+            |id| {
+                let block_id = lmdb::id_from_bytes::<BlockId>("blocks_get_paginated", id)?;
+                let block = self.get_block_row(&block_id)?.ok_or_else(|| StorageError::NotFound {
+                    item: "block".to_string(),
+                    key: block_id.to_string(),
+                })?;
+                Ok(self.load_block("blocks_get_paginated", block)?)
+            },
+        )
+    }
+
+    fn blocks_get_count(&mut self) -> Result<i64, StorageError> {
+        let count = lmdb::count(self.txn(), &self.databases.blocks, "blocks_get_count")?;
+        Ok(count as i64)
+    }
+
+    fn blocks_max_height(&mut self) -> Result<NodeHeight, StorageError> {
+        let last = lmdb::last_key(self.txn(), &self.databases.blocks_by_height, "blocks_max_height")?;
+        let height = last
+            .map(|key| lmdb::decode_u64("blocks_max_height", &key))
+            .transpose()?
+            .unwrap_or(0);
+        Ok(NodeHeight(height))
+    }
+
+    fn parked_blocks_exists(&mut self, block_id: &BlockId) -> Result<bool, StorageError> {
+        let exists = lmdb::exists(
+            self.txn(),
+            &self.databases.parked_blocks,
+            block_id.as_bytes(),
+            "parked_blocks_exists",
+        )?;
+        Ok(exists)
+    }
+
+    fn quorum_certificates_get(&mut self, qc_id: &QcId) -> Result<QuorumCertificate<Self::Addr>, StorageError> {
+        let qc = lmdb::get(
+            self.txn(),
+            &self.databases.quorum_certificates,
+            qc_id.as_bytes(),
+            "quorum_certificates_get",
+        )?
+        .ok_or_else(|| StorageError::NotFound {
+            item: "quorum_certificate".to_string(),
+            key: qc_id.to_string(),
+        })?;
+        Ok(qc)
+    }
+
+    fn quorum_certificates_get_all<'a, I: IntoIterator<Item = &'a QcId>>(
+        &mut self,
+        qc_ids: I,
+    ) -> Result<Vec<QuorumCertificate<Self::Addr>>, StorageError> {
+        let qc_ids = qc_ids.into_iter().collect::<Vec<_>>();
+        let qcs = qc_ids
+            .iter()
+            .filter_map(|qc_id| {
+                lmdb::get::<QuorumCertificate<TAddr>>(
+                    self.txn(),
+                    &self.databases.quorum_certificates,
+                    qc_id.as_bytes(),
+                    "quorum_certificates_get_all",
+                )
+                .transpose()
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if qcs.len() != qc_ids.len() {
+            return Err(LmdbStorageError::NotAllItemsFound {
+                items: "QCs",
+                operation: "quorum_certificates_get_all",
+                details: format!(
+                    "quorum_certificates_get_all: expected {} quorum certificates, got {}",
+                    qc_ids.len(),
+                    qcs.len()
+                ),
+            }
+            .into());
+        }
+
+        Ok(qcs)
+    }
+
+    fn quorum_certificates_get_by_block_id(
+        &mut self,
+        block_id: &BlockId,
+    ) -> Result<QuorumCertificate<Self::Addr>, StorageError> {
+        let qc_id = lmdb::get::<QcId>(
+            self.txn(),
+            &self.databases.quorum_certificates_by_block,
+            block_id.as_bytes(),
+            "quorum_certificates_get_by_block_id",
+        )?
+        .ok_or_else(|| StorageError::NotFound {
+            item: "quorum_certificate".to_string(),
+            key: block_id.to_string(),
+        })?;
+        self.quorum_certificates_get(&qc_id)
+    }
+
+    fn transaction_pool_get(
+        &mut self,
+        from_block_id: &BlockId,
+        to_block_id: &BlockId,
+        transaction_id: &TransactionId,
+    ) -> Result<TransactionPoolRecord, StorageError> {
+        let mut updates = self.get_transaction_atom_state_updates_between_blocks(
+            from_block_id,
+            to_block_id,
+            std::iter::once(transaction_id),
+        )?;
+
+        debug!(
+            target: LOG_TARGET,
+            "transaction_pool_get: from_block_id={}, to_block_id={}, transaction_id={}, updates={}",
+            from_block_id,
+            to_block_id,
+            transaction_id,
+            updates.len()
+        );
+
+        let rec = lmdb::get::<models::TransactionPoolRecord>(
+            self.txn(),
+            &self.databases.transaction_pool,
+            transaction_id.as_bytes(),
+            "transaction_pool_get",
+        )?
+        .ok_or_else(|| StorageError::NotFound {
+            item: "transaction_pool".to_string(),
+            key: transaction_id.to_string(),
+        })?;
+
+        Ok(rec.try_convert(updates.remove(transaction_id))?)
+    }
+
+    fn transaction_pool_exists(&mut self, transaction_id: &TransactionId) -> Result<bool, StorageError> {
+        let exists = lmdb::exists(
+            self.txn(),
+            &self.databases.transaction_pool,
+            transaction_id.as_bytes(),
+            "transaction_pool_exists",
+        )?;
+        Ok(exists)
+    }
+
+    fn transaction_pool_get_in_progress(&mut self) -> Result<Vec<TransactionPoolRecord>, StorageError> {
+        let locked = self.locked_block_get()?;
+        let leaf = self.leaf_block_get()?;
+
+        let mut transaction_ids = lmdb::scan_all_raw(
+            self.txn(),
+            &self.databases.transaction_pool_in_progress,
+            "transaction_pool_get_in_progress",
+        )?
+        .into_iter()
+        .map(|(key, _)| lmdb::id_from_bytes::<TransactionId>("transaction_pool_get_in_progress", &key))
+        .collect::<Result<BTreeSet<_>, _>>()?;
+        // Transactions that are new as of the locked block but were proposed in a later block are also in progress
+        for block in self.get_block_rows_between(&locked.block_id, &leaf.block_id)? {
+            transaction_ids.extend(block.commands.iter().map(|cmd| *cmd.transaction_id()));
+        }
+
+        let mut txs = Vec::with_capacity(transaction_ids.len());
+        for transaction_id in &transaction_ids {
+            let rec = lmdb::get::<models::TransactionPoolRecord>(
+                self.txn(),
+                &self.databases.transaction_pool,
+                transaction_id.as_bytes(),
+                "transaction_pool_get_in_progress",
+            )?;
+            // Transactions in committed blocks may have been finalized and removed from the pool
+            txs.extend(rec);
+        }
+
+        if txs.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut updates = self.get_transaction_atom_state_updates_between_blocks(
+            &locked.block_id,
+            &leaf.block_id,
+            txs.iter().map(|rec| &rec.transaction_id),
+        )?;
+
+        txs.into_iter()
+            .map(|rec| {
+                let maybe_update = updates.remove(&rec.transaction_id);
+                rec.try_convert(maybe_update).map_err(Into::into)
+            })
+            .collect()
+    }
+
+    fn transaction_pool_get_new_by_fee_priority(
+        &mut self,
+        after: Option<(u64, &TransactionId)>,
+        limit: usize,
+    ) -> Result<Vec<TransactionPoolRecord>, StorageError> {
+        let after = after.map(|(fee_priority, transaction_id)| fee_priority_key(fee_priority, transaction_id));
+        let transaction_ids = lmdb::range_scan_limit::<TransactionId, _, _>(
+            self.txn(),
+            &self.databases.transaction_pool_new_by_fee,
+            after.as_deref(),
+            "transaction_pool_get_new_by_fee_priority",
+            limit,
+            |_| true,
+            |key, _| Some(key) != after.as_deref(),
+        )?;
+
+        let mut txs = Vec::with_capacity(transaction_ids.len());
+        for transaction_id in &transaction_ids {
+            let rec = lmdb::get::<models::TransactionPoolRecord>(
+                self.txn(),
+                &self.databases.transaction_pool,
+                transaction_id.as_bytes(),
+                "transaction_pool_get_new_by_fee_priority",
+            )?
+            .ok_or_else(|| LmdbStorageError::DbInconsistency {
+                operation: "transaction_pool_get_new_by_fee_priority",
+                details: format!("Transaction {} is indexed but not in the pool", transaction_id),
+            })?;
+            txs.push(rec);
+        }
+
+        if txs.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let locked = self.locked_block_get()?;
+        let leaf = self.leaf_block_get()?;
+        let mut updates = self.get_transaction_atom_state_updates_between_blocks(
+            &locked.block_id,
+            &leaf.block_id,
+            txs.iter().map(|rec| &rec.transaction_id),
+        )?;
+
+        txs.into_iter()
+            .map(|rec| {
+                let maybe_update = updates.remove(&rec.transaction_id);
+                rec.try_convert(maybe_update).map_err(Into::into)
+            })
+            .collect()
+    }
+
+    fn transaction_pool_get_page(
+        &mut self,
+        after: Option<&TransactionId>,
+        limit: usize,
+    ) -> Result<Vec<TransactionPoolRecord>, StorageError> {
+        // Keys are transaction ids, so these are ordered by transaction id
+        let txs = lmdb::range_scan_limit::<models::TransactionPoolRecord, _, _>(
+            self.txn(),
+            &self.databases.transaction_pool,
+            after.map(|a| a.as_bytes()),
+            "transaction_pool_get_page",
+            limit,
+            |_| true,
+            |key, _| Some(key) != after.map(|a| a.as_bytes()),
+        )?;
+
+        if txs.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let locked = self.locked_block_get()?;
+        let leaf = self.leaf_block_get()?;
+        let mut updates = self.get_transaction_atom_state_updates_between_blocks(
+            &locked.block_id,
+            &leaf.block_id,
+            txs.iter().map(|rec| &rec.transaction_id),
+        )?;
+
+        txs.into_iter()
+            .map(|rec| {
+                let maybe_update = updates.remove(&rec.transaction_id);
+                rec.try_convert(maybe_update).map_err(Into::into)
+            })
+            .collect()
+    }
+
+    fn transaction_pool_count(
+        &mut self,
+        stage: Option<TransactionPoolStage>,
+        is_ready: Option<bool>,
+        has_foreign_data: Option<bool>,
+    ) -> Result<usize, StorageError> {
+        let stage = stage.map(|s| s.to_string());
+        let count = lmdb::scan_all::<models::TransactionPoolRecord>(
+            self.txn(),
+            &self.databases.transaction_pool,
+            "transaction_pool_count",
+        )?
+        .into_iter()
+        .filter(|(_, rec)| {
+            stage.as_ref().map_or(true, |stage| match rec.pending_stage {
+                Some(ref pending_stage) => pending_stage == stage,
+                None => rec.stage == *stage,
+            })
+        })
+        .filter(|(_, rec)| is_ready.map_or(true, |is_ready| rec.is_ready == is_ready))
+        .filter(|(_, rec)| has_foreign_data.map_or(true, |has| rec.remote_evidence.is_some() == has))
+        .count();
+
+        Ok(count)
+    }
+
+    fn transactions_fetch_involved_shards(
+        &mut self,
+        transaction_ids: HashSet<TransactionId>,
+    ) -> Result<HashSet<ShardId>, StorageError> {
+        let mut shards = HashSet::new();
+        for tx_id in transaction_ids {
+            if let Some(rec) = lmdb::get::<TransactionRecord>(
+                self.txn(),
+                &self.databases.transactions,
+                tx_id.as_bytes(),
+                "transactions_fetch_involved_shards",
+            )? {
+                shards.extend(rec.transaction.inputs().iter().copied());
+                shards.extend(rec.transaction.input_refs().iter().copied());
+            }
+        }
+        Ok(shards)
+    }
+
+    fn votes_get_by_block_and_sender(
+        &mut self,
+        block_id: &BlockId,
+        sender_leaf_hash: &FixedHash,
+    ) -> Result<Vote<Self::Addr>, StorageError> {
+        let vote = self
+            .votes_get_for_block(block_id)?
+            .into_iter()
+            .find(|v| v.sender_leaf_hash == *sender_leaf_hash)
+            .ok_or_else(|| StorageError::NotFound {
+                item: "vote".to_string(),
+                key: format!("{}:{}", block_id, sender_leaf_hash),
+            })?;
+        Ok(vote)
+    }
+
+    fn votes_count_for_block(&mut self, block_id: &BlockId) -> Result<u64, StorageError> {
+        let count = lmdb::prefix_keys(
+            self.txn(),
+            &self.databases.votes,
+            block_id.as_bytes(),
+            "votes_count_for_block",
+        )?
+        .len();
+        Ok(count as u64)
+    }
+
+    fn votes_get_for_block(&mut self, block_id: &BlockId) -> Result<Vec<Vote<Self::Addr>>, StorageError> {
+        let votes = lmdb::prefix_scan::<Vote<TAddr>>(
+            self.txn(),
+            &self.databases.votes,
+            block_id.as_bytes(),
+            "votes_get_for_block",
+        )?
+        .into_iter()
+        .map(|(_, vote)| vote)
+        .collect();
+        Ok(votes)
+    }
+
+    fn substates_get(&mut self, substate_id: &ShardId) -> Result<SubstateRecord, StorageError> {
+        let substate = self
+            .get_substate_row(substate_id, "substates_get")?
+            .ok_or_else(|| StorageError::NotFound {
+                item: "substate".to_string(),
+                key: substate_id.to_string(),
+            })?;
+        Ok(substate.record)
+    }
+
+    fn substates_get_any(&mut self, substate_ids: &HashSet<ShardId>) -> Result<Vec<SubstateRecord>, StorageError> {
+        let mut substates = Vec::with_capacity(substate_ids.len());
+        for shard_id in substate_ids {
+            if let Some(substate) = self.get_substate_row(shard_id, "substates_get_any")? {
+                substates.push(substate.record);
+            }
+        }
+        Ok(substates)
+    }
+
+    fn substates_any_exist<I, S>(&mut self, substates: I) -> Result<bool, StorageError>
+    where
+        I: IntoIterator<Item = S>,
+        S: Borrow<ShardId>,
+    {
+        for shard_id in substates {
+            if lmdb::exists(
+                self.txn(),
+                &self.databases.substates,
+                shard_id.borrow().as_bytes(),
+                "substates_any_exist",
+            )? {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    fn substates_exists_for_transaction(&mut self, transaction_id: &TransactionId) -> Result<bool, StorageError> {
+        let keys = lmdb::range_keys(
+            self.txn(),
+            &self.databases.substates_by_transaction,
+            Some(transaction_id.as_bytes()),
+            "substates_exists_for_transaction",
+            |key| key.starts_with(transaction_id.as_bytes()),
+        )?;
+        Ok(!keys.is_empty())
+    }
+
+    fn substates_get_many_within_range(
+        &mut self,
+        start: &ShardId,
+        end: &ShardId,
+        exclude_shards: &[ShardId],
+    ) -> Result<Vec<SubstateRecord>, StorageError> {
+        let substates = lmdb::range_scan::<models::Substate, _>(
+            self.txn(),
+            &self.databases.substates,
+            Some(start.as_bytes()),
+            "substates_get_many_within_range",
+            |key| key <= end.as_bytes(),
+        )?
+        .into_iter()
+        .map(|(_, substate)| substate.record)
+        .filter(|substate| !exclude_shards.contains(&substate.to_shard_id()))
+        .collect();
+        Ok(substates)
+    }
+
+    fn substates_get_live_page_within_range(
+        &mut self,
+        start: &ShardId,
+        end: &ShardId,
+        after: Option<&ShardId>,
+        limit: usize,
+    ) -> Result<Vec<SubstateRecord>, StorageError> {
+        let start = match after {
+            Some(after) if after >= start => after,
+            _ => start,
+        };
+        let substates = lmdb::range_scan_limit::<models::Substate, _, _>(
+            self.txn(),
+            &self.databases.substates,
+            Some(start.as_bytes()),
+            "substates_get_live_page_within_range",
+            limit,
+            |key| key <= end.as_bytes(),
+            |key, substate| Some(key) != after.map(|a| a.as_bytes()) && !substate.record.is_destroyed(),
+        )?
+        .into_iter()
+        .map(|substate| substate.record)
+        .collect();
+        Ok(substates)
+    }
+
+    fn substates_get_page_within_range(
+        &mut self,
+        start: &ShardId,
+        end: &ShardId,
+        after: Option<&ShardId>,
+        limit: usize,
+    ) -> Result<Vec<SubstateRecord>, StorageError> {
+        let start = match after {
+            Some(after) if after >= start => after,
+            _ => start,
+        };
+        let substates = lmdb::range_scan_limit::<models::Substate, _, _>(
+            self.txn(),
+            &self.databases.substates,
+            Some(start.as_bytes()),
+            "substates_get_page_within_range",
+            limit,
+            |key| key <= end.as_bytes(),
+            |key, _| Some(key) != after.map(|a| a.as_bytes()),
+        )?
+        .into_iter()
+        .map(|substate| substate.record)
+        .collect();
+        Ok(substates)
+    }
+
+    fn substates_get_many_by_created_transaction(
+        &mut self,
+        tx_id: &TransactionId,
+    ) -> Result<Vec<SubstateRecord>, StorageError> {
+        let substates = self
+            .get_substates_by_index(
+                &self.databases.substates_by_transaction,
+                tx_id.as_bytes(),
+                "substates_get_many_by_created_transaction",
+            )?
+            .into_iter()
+            .filter(|s| s.created_by_transaction == *tx_id)
+            .collect();
+        Ok(substates)
+    }
+
+    fn substates_get_many_by_destroyed_transaction(
+        &mut self,
+        tx_id: &TransactionId,
+    ) -> Result<Vec<SubstateRecord>, StorageError> {
+        let substates = self
+            .get_substates_by_index(
+                &self.databases.substates_by_transaction,
+                tx_id.as_bytes(),
+                "substates_get_many_by_destroyed_transaction",
+            )?
+            .into_iter()
+            .filter(|s| s.destroyed.as_ref().map_or(false, |d| d.by_transaction == *tx_id))
+            .collect();
+        Ok(substates)
+    }
+
+    fn substates_get_all_for_block(&mut self, block_id: &BlockId) -> Result<Vec<SubstateRecord>, StorageError> {
+        let substates = self.get_substates_by_index(
+            &self.databases.substates_by_block,
+            block_id.as_bytes(),
+            "substates_get_all_for_block",
+        )?;
+        Ok(substates)
+    }
+
+    fn substates_get_all_for_transaction(
+        &mut self,
+        transaction_id: &TransactionId,
+    ) -> Result<Vec<SubstateRecord>, StorageError> {
+        let substates = self.get_substates_by_index(
+            &self.databases.substates_by_transaction,
+            transaction_id.as_bytes(),
+            "substates_get_all_for_transaction",
+        )?;
+        Ok(substates)
+    }
+
+    fn substates_get_all_versions(&mut self, address: &SubstateAddress) -> Result<Vec<SubstateRecord>, StorageError> {
+        const OPERATION: &str = "substates_get_all_versions";
+        let hash = address.to_canonical_hash();
+        let keys = lmdb::prefix_keys(
+            self.txn(),
+            &self.databases.substates_by_address,
+            hash.as_ref(),
+            OPERATION,
+        )?;
+        let mut substates = Vec::with_capacity(keys.len());
+        for key in keys {
+            substates.push(self.get_substate_by_address_key(address, &key, OPERATION)?);
+        }
+        Ok(substates)
+    }
+
+    fn substates_get_latest_version(&mut self, address: &SubstateAddress) -> Result<SubstateRecord, StorageError> {
+        const OPERATION: &str = "substates_get_latest_version";
+        let hash = address.to_canonical_hash();
+        // Versions are big endian encoded so the last key is the latest version
+        let keys = lmdb::prefix_keys(
+            self.txn(),
+            &self.databases.substates_by_address,
+            hash.as_ref(),
+            OPERATION,
+        )?;
+        let key = keys.last().ok_or_else(|| StorageError::NotFound {
+            item: "substate".to_string(),
+            key: address.to_string(),
+        })?;
+        self.get_substate_by_address_key(address, key, OPERATION)
+    }
+
+    fn substates_get_prune_mark(&mut self) -> Result<Option<SubstatePruneMark>, StorageError> {
+        let row =
+            lmdb::last::<SubstatePruneMark>(self.txn(), &self.databases.substate_pruning, "substates_get_prune_mark")?;
+        Ok(row.map(|(_, mark)| mark))
+    }
+
+    fn substates_check_lock_many<'a, I: IntoIterator<Item = &'a ShardId>>(
+        &mut self,
+        objects: I,
+        lock_flag: SubstateLockFlag,
+    ) -> Result<SubstateLockState, StorageError> {
+        let substates = self.get_substate_rows_for_lock(objects, lock_flag)?;
+
+        if substates.iter().any(|s| s.is_locked_w) {
+            return Ok(SubstateLockState::SomeAlreadyWriteLocked);
+        }
+
+        if substates.iter().any(|s| s.record.destroyed.is_some()) {
+            return Ok(SubstateLockState::SomeDestroyed);
+        }
+
+        Ok(SubstateLockState::LockAcquired)
+    }
+
+    fn locked_outputs_check_all<I, B>(&mut self, output_shards: I) -> Result<SubstateLockState, StorageError>
+    where
+        I: IntoIterator<Item = B>,
+        B: Borrow<ShardId>,
+    {
+        for shard_id in output_shards {
+            if lmdb::exists(
+                self.txn(),
+                &self.databases.locked_outputs,
+                shard_id.borrow().as_bytes(),
+                "locked_outputs_check_all",
+            )? {
+                return Ok(SubstateLockState::SomeAlreadyWriteLocked);
+            }
+        }
+
+        Ok(SubstateLockState::LockAcquired)
+    }
+
+    fn locked_outputs_get_all(&mut self) -> Result<Vec<LockedOutput>, StorageError> {
+        let locked = lmdb::scan_all::<models::LockedOutputRow>(
+            self.txn(),
+            &self.databases.locked_outputs,
+            "locked_outputs_get_all",
+        )?
+        .into_iter()
+        .map(|(_, row)| row.into())
+        .collect();
+        Ok(locked)
+    }
+}
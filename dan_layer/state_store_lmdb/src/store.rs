@@ -0,0 +1,271 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use std::{fmt, fs, marker::PhantomData, path::Path, sync::Arc, time::Duration};
+
+use lmdb_zero::{db, ReadTransaction, WriteTransaction};
+use log::log;
+use serde::{de::DeserializeOwned, Serialize};
+use tari_dan_common_types::NodeAddressable;
+use tari_dan_storage::{consensus_models::BlockId, StateStore, StorageError};
+use tari_storage::lmdb_store::{DatabaseRef, LMDBBuilder, LMDBConfig, LMDBStore};
+use time::Instant;
+
+use crate::{
+    error::LmdbStorageError,
+    lmdb::{composite_key, LmdbTransaction},
+    reader::LmdbStateStoreReadTransaction,
+    writer::LmdbStateStoreWriteTransaction,
+};
+
+const LOG_TARGET: &str = "tari::dan::storage::lmdb::state_store";
+
+macro_rules! define_databases {
+    ($($name:ident),+ $(,)?) => {
+        pub(crate) struct Databases {
+            $(pub $name: DatabaseRef,)+
+        }
+
+        impl Databases {
+            const NAMES: &'static [&'static str] = &[$(stringify!($name)),+];
+
+            fn open(store: &LMDBStore) -> Result<Self, LmdbStorageError> {
+                Ok(Self {
+                    $($name: store
+                        .get_handle(stringify!($name))
+                        .ok_or_else(|| LmdbStorageError::DbInconsistency {
+                            operation: "open",
+                            details: format!("database {} was not created", stringify!($name)),
+                        })?
+                        .db(),)+
+                })
+            }
+        }
+    };
+}
+
+// Keys are the raw bytes of the IDs. Composite keys are the concatenation of their parts and sequence keys are big
+// endian u64s so that LMDB's lexicographical key order matches the sqlite ordering.
+define_databases!(
+    // block_id -> Block
+    blocks,
+    // parent_block_id ++ block_id -> ()
+    blocks_by_parent,
+    // height ++ block_id -> ()
+    blocks_by_height,
+    // epoch ++ block_id -> ()
+    blocks_by_epoch,
+    // sequence -> block_id
+    blocks_by_created,
+    // block_id -> sequence, the key of the block in blocks_by_created
+    blocks_created_sequence,
+    // height ++ block_id -> (), for the blocks that are not committed
+    blocks_uncommitted_by_height,
+    // block_id -> ParkedBlock
+    parked_blocks,
+    // qc_id -> QuorumCertificate
+    quorum_certificates,
+    // block_id -> qc_id
+    quorum_certificates_by_block,
+    // sequence -> LastSentVoteRow
+    last_sent_vote,
+    // sequence -> BlockPointer
+    last_voted,
+    // sequence -> BlockPointer
+    last_executed,
+    // sequence -> BlockPointer
+    last_proposed,
+    // sequence -> BlockPointer
+    locked_block,
+    // sequence -> BlockPointer
+    leaf_blocks,
+    // sequence -> HighQcRow
+    high_qcs,
+    // pointer_table ++ block_id ++ sequence -> (), for the rows of the tables listed in PointerTable
+    block_pointers_by_block,
+    // transaction_id -> TransactionRecord
+    transactions,
+    // sequence -> transaction_id
+    transactions_by_created,
+    // transaction_id -> TransactionPoolRecord
+    transaction_pool,
+    // fee_priority_key ++ transaction_id -> transaction_id, for the records whose committed stage is New
+    transaction_pool_new_by_fee,
+    // transaction_id -> (), for the records whose committed stage is not New
+    transaction_pool_in_progress,
+    // transaction_id ++ block_id -> TransactionPoolStateUpdate
+    transaction_pool_state_updates,
+    // block_id ++ transaction_id -> ()
+    transaction_pool_state_updates_by_block,
+    // block_id ++ transaction_id -> MissingTransaction
+    missing_transactions,
+    // transaction_id ++ block_id -> ()
+    missing_transactions_by_transaction,
+    // view ++ block_id ++ transaction_id -> ()
+    missing_transactions_by_view,
+    // block_id ++ sequence -> Vote
+    votes,
+    // shard_id -> Substate
+    substates,
+    // transaction_id ++ shard_id -> (), for the creating and destroying transactions
+    substates_by_transaction,
+    // block_id ++ shard_id -> (), for the creating and destroying blocks
+    substates_by_block,
+    // address_hash ++ version -> ()
+    substates_by_address,
+    // destroyed epoch ++ shard_id -> (), for the destroyed substates
+    substates_by_destroyed_epoch,
+    // sequence -> SubstatePruneMark
+    substate_pruning,
+    // shard_id -> LockedOutputRow
+    locked_outputs,
+    // block_id ++ shard_id -> ()
+    locked_outputs_by_block,
+);
+
+/// The bookkeeping tables whose rows point to a block. Rows are also indexed in `block_pointers_by_block` so that the
+/// rows for a block can be found without scanning the table.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum PointerTable {
+    LastSentVote,
+    LastVoted,
+    LastExecuted,
+    LastProposed,
+    LockedBlock,
+    LeafBlocks,
+    HighQcs,
+}
+
+impl PointerTable {
+    /// The tables from which rows pointing to pruned blocks are deleted. The latest row of each of these tables is
+    /// never pruned.
+    pub const PRUNABLE: [Self; 5] = [
+        Self::LeafBlocks,
+        Self::LockedBlock,
+        Self::LastExecuted,
+        Self::LastSentVote,
+        Self::HighQcs,
+    ];
+
+    pub fn db(self, databases: &Databases) -> &DatabaseRef {
+        match self {
+            Self::LastSentVote => &databases.last_sent_vote,
+            Self::LastVoted => &databases.last_voted,
+            Self::LastExecuted => &databases.last_executed,
+            Self::LastProposed => &databases.last_proposed,
+            Self::LockedBlock => &databases.locked_block,
+            Self::LeafBlocks => &databases.leaf_blocks,
+            Self::HighQcs => &databases.high_qcs,
+        }
+    }
+
+    /// Returns the `block_pointers_by_block` key prefix of the rows in this table that point to `block_id`
+    pub fn index_prefix(self, block_id: &BlockId) -> Vec<u8> {
+        composite_key(&[&[self as u8], block_id.as_bytes()])
+    }
+}
+
+pub struct LmdbStateStore<TAddr> {
+    env: Arc<lmdb_zero::Environment>,
+    env_config: LMDBConfig,
+    databases: Arc<Databases>,
+    _addr: PhantomData<TAddr>,
+}
+
+impl<TAddr> LmdbStateStore<TAddr> {
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, StorageError> {
+        fs::create_dir_all(&path).map_err(LmdbStorageError::from)?;
+
+        let mut builder = LMDBBuilder::new()
+            .set_path(path)
+            .set_max_number_of_databases(Databases::NAMES.len() as u32);
+        for name in Databases::NAMES {
+            builder = builder.add_database(name, db::CREATE);
+        }
+        let store = builder.build().map_err(LmdbStorageError::from)?;
+
+        Ok(Self {
+            env: store.env(),
+            env_config: store.env_config(),
+            databases: Arc::new(Databases::open(&store)?),
+            _addr: PhantomData,
+        })
+    }
+
+    /// Writes a compacted copy of the store to the empty directory `path`. The copy is taken from a single read
+    /// transaction, so it is consistent even while the store is being written to.
+    pub fn backup_to<P: AsRef<Path>>(&self, path: P) -> Result<(), StorageError> {
+        let path = path.as_ref();
+        fs::create_dir_all(path).map_err(LmdbStorageError::from)?;
+        let path = path.to_str().ok_or_else(|| StorageError::General {
+            details: format!("Backup path {} is not valid UTF-8", path.display()),
+        })?;
+        self.env
+            .copy(path, lmdb_zero::copy::COMPACT)
+            .map_err(|source| LmdbStorageError::LmdbError {
+                source,
+                operation: "backup_to",
+            })?;
+        Ok(())
+    }
+}
+
+impl<TAddr> fmt::Debug for LmdbStateStore<TAddr> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "LmdbStateStore")
+    }
+}
+
+impl<TAddr: NodeAddressable + Serialize + DeserializeOwned> StateStore for LmdbStateStore<TAddr> {
+    type Addr = TAddr;
+    type ReadTransaction<'a> = LmdbStateStoreReadTransaction<'a, Self::Addr> where TAddr: 'a;
+    type WriteTransaction<'a> = LmdbStateStoreWriteTransaction<'a, Self::Addr> where TAddr: 'a;
+
+    fn create_read_tx(&self) -> Result<Self::ReadTransaction<'_>, StorageError> {
+        let tx = ReadTransaction::new(self.env.clone()).map_err(|source| LmdbStorageError::LmdbError {
+            source,
+            operation: "create_read_tx",
+        })?;
+        Ok(LmdbStateStoreReadTransaction::new(
+            LmdbTransaction::Read(tx),
+            &self.databases,
+        ))
+    }
+
+    fn create_write_tx(&self) -> Result<Self::WriteTransaction<'_>, StorageError> {
+        let timer = Instant::now();
+        // Grow the memory map before starting the transaction, the map cannot be resized while a transaction is open
+        LMDBStore::resize_if_required(&self.env, &self.env_config).map_err(LmdbStorageError::from)?;
+        let tx = WriteTransaction::new(self.env.clone()).map_err(|source| LmdbStorageError::LmdbError {
+            source,
+            operation: "create_write_tx",
+        })?;
+        let tx = LmdbStateStoreWriteTransaction::new(LmdbStateStoreReadTransaction::new(
+            LmdbTransaction::Write(tx),
+            &self.databases,
+        ));
+        let elapsed = timer.elapsed();
+        let level = if elapsed > Duration::from_secs(1) {
+            log::Level::Warn
+        } else {
+            log::Level::Trace
+        };
+        log!(
+            target: LOG_TARGET,
+            level,
+            "Write transaction obtained in {:.2}", elapsed
+        );
+        Ok(tx)
+    }
+}
+
+impl<TAddr> Clone for LmdbStateStore<TAddr> {
+    fn clone(&self) -> Self {
+        Self {
+            env: self.env.clone(),
+            env_config: self.env_config.clone(),
+            databases: self.databases.clone(),
+            _addr: PhantomData,
+        }
+    }
+}
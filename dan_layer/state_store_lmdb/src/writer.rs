@@ -0,0 +1,1698 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use std::{
+    borrow::Borrow,
+    collections::HashSet,
+    iter,
+    ops::{Deref, DerefMut},
+    time::Duration,
+};
+
+use log::*;
+use serde::{de::DeserializeOwned, Serialize};
+use tari_dan_common_types::{optional::Optional, Epoch, NodeAddressable, NodeHeight, ShardId, View};
+use tari_dan_storage::{
+    consensus_models::{
+        Block,
+        BlockId,
+        Decision,
+        Evidence,
+        HighQc,
+        LastExecuted,
+        LastProposed,
+        LastSentVote,
+        LastVoted,
+        LeafBlock,
+        LockedBlock,
+        LockedOutput,
+        QcId,
+        QuorumCertificate,
+        SubstateDestroyed,
+        SubstateLockFlag,
+        SubstateLockState,
+        SubstatePruneMark,
+        SubstateRecord,
+        TransactionAtom,
+        TransactionFeeInfo,
+        TransactionPoolStage,
+        TransactionPoolStatusUpdate,
+        TransactionRecord,
+        Vote,
+    },
+    StateStoreReadTransaction,
+    StateStoreWriteTransaction,
+    StorageError,
+};
+use tari_transaction::{Transaction, TransactionId};
+use time::PrimitiveDateTime;
+
+use crate::{
+    error::LmdbStorageError,
+    lmdb,
+    lmdb::{composite_key, fee_priority_key, substate_address_key},
+    models,
+    reader::LmdbStateStoreReadTransaction,
+    store::PointerTable,
+};
+
+const LOG_TARGET: &str = "tari::dan::storage";
+
+pub struct LmdbStateStoreWriteTransaction<'a, TAddr> {
+    /// None indicates if the transaction has been explicitly committed/rolled back
+    transaction: Option<LmdbStateStoreReadTransaction<'a, TAddr>>,
+}
+
+impl<'a, TAddr: NodeAddressable + Serialize + DeserializeOwned> LmdbStateStoreWriteTransaction<'a, TAddr> {
+    pub(crate) fn new(transaction: LmdbStateStoreReadTransaction<'a, TAddr>) -> Self {
+        Self {
+            transaction: Some(transaction),
+        }
+    }
+
+    /// Appends a row that points to `block_id` to a bookkeeping database keyed by a sequence number
+    fn append<T: Serialize>(
+        &self,
+        table: PointerTable,
+        block_id: &BlockId,
+        row: &T,
+        operation: &'static str,
+        item: &'static str,
+    ) -> Result<(), LmdbStorageError> {
+        let db = table.db(self.databases());
+        let key = lmdb::sequence_key(lmdb::next_sequence(self.txn(), db, operation)?);
+        lmdb::insert(self.write_txn(), db, &key, row, operation, item)?;
+        lmdb::put_raw(
+            self.write_txn(),
+            &self.databases().block_pointers_by_block,
+            &composite_key(&[&table.index_prefix(block_id), &key]),
+            &[],
+            operation,
+        )
+    }
+
+    /// Deletes the rows of `table` that point to `block_id` and for which `predicate` returns true
+    fn delete_block_pointers<F: Fn(&[u8]) -> Result<bool, LmdbStorageError>>(
+        &self,
+        table: PointerTable,
+        block_id: &BlockId,
+        operation: &'static str,
+        predicate: F,
+    ) -> Result<(), LmdbStorageError> {
+        let db = table.db(self.databases());
+        let prefix = table.index_prefix(block_id);
+        let keys = lmdb::prefix_keys(
+            self.txn(),
+            &self.databases().block_pointers_by_block,
+            &prefix,
+            operation,
+        )?;
+        for index_key in keys {
+            let key = &index_key[prefix.len()..];
+            if predicate(key)? {
+                lmdb::delete(self.write_txn(), db, key, operation)?;
+                lmdb::delete(
+                    self.write_txn(),
+                    &self.databases().block_pointers_by_block,
+                    &index_key,
+                    operation,
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Deletes all block pointer rows that point to the given block and height
+    fn unset_block_pointer(
+        &self,
+        table: PointerTable,
+        block_id: &BlockId,
+        height: NodeHeight,
+        operation: &'static str,
+    ) -> Result<(), LmdbStorageError> {
+        let db = table.db(self.databases());
+        self.delete_block_pointers(table, block_id, operation, |key| {
+            let row = lmdb::get::<models::BlockPointer>(self.txn(), db, key, operation)?;
+            Ok(row.map_or(true, |row| row.is_same(block_id, height)))
+        })
+    }
+
+    /// Returns the IDs of blocks that must not be pruned because the latest block pointers still reference them. Blocks
+    /// referenced by a locked output are checked separately.
+    fn referenced_block_ids(&self, operation: &'static str) -> Result<HashSet<BlockId>, LmdbStorageError> {
+        let databases = self.databases();
+        let mut block_ids = HashSet::new();
+        for db in [
+            &databases.leaf_blocks,
+            &databases.locked_block,
+            &databases.last_executed,
+        ] {
+            if let Some((_, pointer)) = lmdb::last::<models::BlockPointer>(self.txn(), db, operation)? {
+                block_ids.insert(pointer.block_id);
+            }
+        }
+        if let Some((_, vote)) =
+            lmdb::last::<models::LastSentVoteRow<TAddr>>(self.txn(), &databases.last_sent_vote, operation)?
+        {
+            block_ids.insert(vote.block_id);
+        }
+        if let Some((_, high_qc)) = lmdb::last::<models::HighQcRow>(self.txn(), &databases.high_qcs, operation)? {
+            block_ids.insert(high_qc.block_id);
+        }
+        Ok(block_ids)
+    }
+
+    /// Deletes the block row along with its index entries
+    fn block_delete_row(&self, block: &models::Block<TAddr>, operation: &'static str) -> Result<(), LmdbStorageError> {
+        let databases = self.databases();
+        let block_id = &block.block_id;
+        let height = block.height.as_u64().to_be_bytes();
+        lmdb::delete(self.write_txn(), &databases.blocks, block_id.as_bytes(), operation)?;
+        lmdb::delete(
+            self.write_txn(),
+            &databases.blocks_by_parent,
+            &composite_key(&[block.parent_block_id.as_bytes(), block_id.as_bytes()]),
+            operation,
+        )?;
+        lmdb::delete(
+            self.write_txn(),
+            &databases.blocks_by_height,
+            &composite_key(&[&height, block_id.as_bytes()]),
+            operation,
+        )?;
+        lmdb::delete(
+            self.write_txn(),
+            &databases.blocks_uncommitted_by_height,
+            &composite_key(&[&height, block_id.as_bytes()]),
+            operation,
+        )?;
+        lmdb::delete(
+            self.write_txn(),
+            &databases.blocks_by_epoch,
+            &composite_key(&[&block.epoch.as_u64().to_be_bytes(), block_id.as_bytes()]),
+            operation,
+        )?;
+        if let Some(seq) = lmdb::get_raw(
+            self.txn(),
+            &databases.blocks_created_sequence,
+            block_id.as_bytes(),
+            operation,
+        )? {
+            lmdb::delete(self.write_txn(), &databases.blocks_by_created, &seq, operation)?;
+            lmdb::delete(
+                self.write_txn(),
+                &databases.blocks_created_sequence,
+                block_id.as_bytes(),
+                operation,
+            )?;
+        }
+        Ok(())
+    }
+
+    fn parked_blocks_remove(&self, block_id: &BlockId) -> Result<Block<TAddr>, StorageError> {
+        let databases = self.databases();
+        let block = lmdb::get::<models::ParkedBlock<TAddr>>(
+            self.txn(),
+            &databases.parked_blocks,
+            block_id.as_bytes(),
+            "parked_blocks_remove",
+        )?
+        .ok_or_else(|| StorageError::NotFound {
+            item: "parked_blocks".to_string(),
+            key: block_id.to_string(),
+        })?;
+
+        lmdb::delete(
+            self.write_txn(),
+            &databases.parked_blocks,
+            block_id.as_bytes(),
+            "parked_blocks_remove",
+        )?;
+
+        Ok(block.into_block())
+    }
+
+    fn parked_blocks_insert(&self, block: &Block<TAddr>) -> Result<(), StorageError> {
+        let databases = self.databases();
+        let block_id = block.id();
+
+        if lmdb::exists(
+            self.txn(),
+            &databases.blocks,
+            block_id.as_bytes(),
+            "parked_blocks_insert",
+        )? {
+            return Err(StorageError::QueryError {
+                reason: format!("Cannot park block {block_id} that already exists in blocks table"),
+            });
+        }
+
+        if lmdb::exists(
+            self.txn(),
+            &databases.parked_blocks,
+            block_id.as_bytes(),
+            "parked_blocks_insert",
+        )? {
+            return Ok(());
+        }
+
+        let parked = models::ParkedBlock {
+            block: block.clone(),
+            created_at: now(),
+        };
+        lmdb::insert(
+            self.write_txn(),
+            &databases.parked_blocks,
+            block_id.as_bytes(),
+            &parked,
+            "parked_blocks_insert",
+            "parked block",
+        )?;
+
+        Ok(())
+    }
+
+    /// Deletes the missing transaction row and its index entries
+    fn missing_transaction_delete(&self, block_id: &[u8], transaction_id: &[u8]) -> Result<(), LmdbStorageError> {
+        let databases = self.databases();
+        let key = composite_key(&[block_id, transaction_id]);
+        let Some(row) = lmdb::get::<models::MissingTransaction>(
+            self.txn(),
+            &databases.missing_transactions,
+            &key,
+            "missing_transactions_remove",
+        )?
+        else {
+            return Ok(());
+        };
+        lmdb::delete(
+            self.write_txn(),
+            &databases.missing_transactions,
+            &key,
+            "missing_transactions_remove",
+        )?;
+        lmdb::delete(
+            self.write_txn(),
+            &databases.missing_transactions_by_view,
+            &composite_key(&[&row.block_view.as_u64().to_be_bytes(), block_id, transaction_id]),
+            "missing_transactions_remove",
+        )?;
+        lmdb::delete(
+            self.write_txn(),
+            &databases.missing_transactions_by_transaction,
+            &composite_key(&[transaction_id, block_id]),
+            "missing_transactions_remove",
+        )?;
+        Ok(())
+    }
+
+    /// Deletes all missing transaction rows for the given transaction
+    fn missing_transactions_delete_for_transaction(&self, transaction_id: &TransactionId) -> Result<(), StorageError> {
+        let keys = lmdb::prefix_keys(
+            self.txn(),
+            &self.databases().missing_transactions_by_transaction,
+            transaction_id.as_bytes(),
+            "missing_transactions_remove",
+        )?;
+        for key in keys {
+            let (tx_id, block_id) = key.split_at(TransactionId::byte_size());
+            self.missing_transaction_delete(block_id, tx_id)?;
+        }
+        Ok(())
+    }
+
+    fn substate_put(&self, substate: &models::Substate, operation: &'static str) -> Result<(), LmdbStorageError> {
+        lmdb::put(
+            self.write_txn(),
+            &self.databases().substates,
+            substate.record.to_shard_id().as_bytes(),
+            substate,
+            operation,
+            "substate",
+        )
+    }
+
+    fn substate_index(
+        &self,
+        transaction_id: &TransactionId,
+        block_id: &BlockId,
+        shard_id: &ShardId,
+        operation: &'static str,
+    ) -> Result<(), LmdbStorageError> {
+        let databases = self.databases();
+        lmdb::put_raw(
+            self.write_txn(),
+            &databases.substates_by_transaction,
+            &composite_key(&[transaction_id.as_bytes(), shard_id.as_bytes()]),
+            &[],
+            operation,
+        )?;
+        lmdb::put_raw(
+            self.write_txn(),
+            &databases.substates_by_block,
+            &composite_key(&[block_id.as_bytes(), shard_id.as_bytes()]),
+            &[],
+            operation,
+        )
+    }
+
+    /// Deletes the substate stored under `key` along with its index entries
+    fn substate_delete_row(
+        &self,
+        key: &[u8],
+        record: &SubstateRecord,
+        operation: &'static str,
+    ) -> Result<(), LmdbStorageError> {
+        let databases = self.databases();
+        lmdb::delete(self.write_txn(), &databases.substates, key, operation)?;
+        let destroyed = record.destroyed();
+        for tx_id in iter::once(&record.created_by_transaction).chain(destroyed.map(|d| &d.by_transaction)) {
+            lmdb::delete(
+                self.write_txn(),
+                &databases.substates_by_transaction,
+                &composite_key(&[tx_id.as_bytes(), key]),
+                operation,
+            )?;
+        }
+        for block_id in iter::once(&record.created_block).chain(destroyed.map(|d| &d.by_block)) {
+            lmdb::delete(
+                self.write_txn(),
+                &databases.substates_by_block,
+                &composite_key(&[block_id.as_bytes(), key]),
+                operation,
+            )?;
+        }
+        lmdb::delete(
+            self.write_txn(),
+            &databases.substates_by_address,
+            &substate_address_key(&record.address, record.version),
+            operation,
+        )?;
+        if let Some(destroyed) = destroyed {
+            lmdb::delete(
+                self.write_txn(),
+                &databases.substates_by_destroyed_epoch,
+                &composite_key(&[&destroyed.at_epoch.as_u64().to_be_bytes(), key]),
+                operation,
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Adds the destroyed substate to the `substates_by_destroyed_epoch` index
+    fn substate_index_destroyed(
+        &self,
+        shard_id: &ShardId,
+        destroyed: &SubstateDestroyed,
+        operation: &'static str,
+    ) -> Result<(), LmdbStorageError> {
+        lmdb::put_raw(
+            self.write_txn(),
+            &self.databases().substates_by_destroyed_epoch,
+            &composite_key(&[&destroyed.at_epoch.as_u64().to_be_bytes(), shard_id.as_bytes()]),
+            &[],
+            operation,
+        )
+    }
+
+    /// Deletes the pending transaction pool update stored under `key` (transaction_id ++ block_id) along with its
+    /// index entry
+    fn transaction_pool_state_update_delete(
+        &self,
+        key: &[u8],
+        operation: &'static str,
+    ) -> Result<(), LmdbStorageError> {
+        let databases = self.databases();
+        let (transaction_id, block_id) = key.split_at(TransactionId::byte_size());
+        lmdb::delete(
+            self.write_txn(),
+            &databases.transaction_pool_state_updates,
+            key,
+            operation,
+        )?;
+        lmdb::delete(
+            self.write_txn(),
+            &databases.transaction_pool_state_updates_by_block,
+            &composite_key(&[block_id, transaction_id]),
+            operation,
+        )?;
+        Ok(())
+    }
+
+    /// Indexes the pool record by its committed stage, in `transaction_pool_new_by_fee` if it is new and in
+    /// `transaction_pool_in_progress` otherwise
+    fn transaction_pool_index(
+        &self,
+        record: &models::TransactionPoolRecord,
+        operation: &'static str,
+    ) -> Result<(), LmdbStorageError> {
+        let databases = self.databases();
+        if record.is_new() {
+            lmdb::put(
+                self.write_txn(),
+                &databases.transaction_pool_new_by_fee,
+                &fee_priority_key(record.fee_priority(), &record.transaction_id),
+                &record.transaction_id,
+                operation,
+                "transaction pool fee index",
+            )
+        } else {
+            lmdb::put_raw(
+                self.write_txn(),
+                &databases.transaction_pool_in_progress,
+                record.transaction_id.as_bytes(),
+                &[],
+                operation,
+            )
+        }
+    }
+
+    /// Deletes the index entry added for the pool record by `transaction_pool_index`
+    fn transaction_pool_unindex(
+        &self,
+        record: &models::TransactionPoolRecord,
+        operation: &'static str,
+    ) -> Result<(), LmdbStorageError> {
+        let databases = self.databases();
+        if record.is_new() {
+            lmdb::delete(
+                self.write_txn(),
+                &databases.transaction_pool_new_by_fee,
+                &fee_priority_key(record.fee_priority(), &record.transaction_id),
+                operation,
+            )?;
+        } else {
+            lmdb::delete(
+                self.write_txn(),
+                &databases.transaction_pool_in_progress,
+                record.transaction_id.as_bytes(),
+                operation,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl<TAddr: NodeAddressable + Serialize + DeserializeOwned> StateStoreWriteTransaction
+    for LmdbStateStoreWriteTransaction<'_, TAddr>
+{
+    type Addr = TAddr;
+
+    fn commit(mut self) -> Result<(), StorageError> {
+        // Take so that we mark this transaction as complete in the drop impl
+        self.transaction.take().unwrap().commit()?;
+        Ok(())
+    }
+
+    fn rollback(mut self) -> Result<(), StorageError> {
+        // Take so that we mark this transaction as complete in the drop impl. LMDB aborts the transaction on drop.
+        drop(self.transaction.take());
+        Ok(())
+    }
+
+    fn blocks_insert(&mut self, block: &Block<TAddr>) -> Result<(), StorageError> {
+        let databases = self.databases();
+        let block_id = block.id();
+        let row = models::Block::new(block, now());
+
+        lmdb::insert(
+            self.write_txn(),
+            &databases.blocks,
+            block_id.as_bytes(),
+            &row,
+            "blocks_insert",
+            "block",
+        )?;
+        lmdb::put_raw(
+            self.write_txn(),
+            &databases.blocks_by_parent,
+            &composite_key(&[block.parent().as_bytes(), block_id.as_bytes()]),
+            &[],
+            "blocks_insert",
+        )?;
+        lmdb::put_raw(
+            self.write_txn(),
+            &databases.blocks_by_height,
+            &composite_key(&[&block.height().as_u64().to_be_bytes(), block_id.as_bytes()]),
+            &[],
+            "blocks_insert",
+        )?;
+        lmdb::put_raw(
+            self.write_txn(),
+            &databases.blocks_by_epoch,
+            &composite_key(&[&block.epoch().as_u64().to_be_bytes(), block_id.as_bytes()]),
+            &[],
+            "blocks_insert",
+        )?;
+        let seq = lmdb::next_sequence(self.txn(), &databases.blocks_by_created, "blocks_insert")?;
+        lmdb::put_raw(
+            self.write_txn(),
+            &databases.blocks_by_created,
+            &lmdb::sequence_key(seq),
+            block_id.as_bytes(),
+            "blocks_insert",
+        )?;
+        lmdb::put_raw(
+            self.write_txn(),
+            &databases.blocks_created_sequence,
+            block_id.as_bytes(),
+            &lmdb::sequence_key(seq),
+            "blocks_insert",
+        )?;
+        if !block.is_committed() {
+            lmdb::put_raw(
+                self.write_txn(),
+                &databases.blocks_uncommitted_by_height,
+                &composite_key(&[&block.height().as_u64().to_be_bytes(), block_id.as_bytes()]),
+                &[],
+                "blocks_insert",
+            )?;
+        }
+
+        Ok(())
+    }
+
+    fn blocks_set_flags(
+        &mut self,
+        block_id: &BlockId,
+        is_committed: Option<bool>,
+        is_processed: Option<bool>,
+    ) -> Result<(), StorageError> {
+        let Some(mut block) = self.get_block_row(block_id)? else {
+            // Same as an UPDATE that matches no rows
+            return Ok(());
+        };
+        if let Some(is_committed) = is_committed {
+            let key = composite_key(&[&block.height.as_u64().to_be_bytes(), block_id.as_bytes()]);
+            let databases = self.databases();
+            if is_committed {
+                lmdb::delete(
+                    self.write_txn(),
+                    &databases.blocks_uncommitted_by_height,
+                    &key,
+                    "blocks_set_flags",
+                )?;
+            } else {
+                lmdb::put_raw(
+                    self.write_txn(),
+                    &databases.blocks_uncommitted_by_height,
+                    &key,
+                    &[],
+                    "blocks_set_flags",
+                )?;
+            }
+            block.is_committed = is_committed;
+        }
+        if let Some(is_processed) = is_processed {
+            block.is_processed = is_processed;
+        }
+        lmdb::put(
+            self.write_txn(),
+            &self.databases().blocks,
+            block_id.as_bytes(),
+            &block,
+            "blocks_set_flags",
+            "block",
+        )?;
+
+        Ok(())
+    }
+
+    fn missing_transactions_insert<
+        'a,
+        IMissing: IntoIterator<Item = &'a TransactionId>,
+        IAwaiting: IntoIterator<Item = &'a TransactionId>,
+    >(
+        &mut self,
+        block: &Block<Self::Addr>,
+        missing_transaction_ids: IMissing,
+        awaiting_transaction_ids: IAwaiting,
+    ) -> Result<(), StorageError> {
+        let databases = self.databases();
+        self.parked_blocks_insert(block)?;
+
+        let missing = missing_transaction_ids.into_iter().map(|tx_id| (tx_id, false));
+        let awaiting = awaiting_transaction_ids.into_iter().map(|tx_id| (tx_id, true));
+        for (tx_id, is_awaiting_execution) in missing.chain(awaiting) {
+            let row = models::MissingTransaction {
+                block_view: block.view(),
+                is_awaiting_execution,
+            };
+            lmdb::put(
+                self.write_txn(),
+                &databases.missing_transactions,
+                &composite_key(&[block.id().as_bytes(), tx_id.as_bytes()]),
+                &row,
+                "missing_transactions_insert",
+                "missing transaction",
+            )?;
+            lmdb::put_raw(
+                self.write_txn(),
+                &databases.missing_transactions_by_transaction,
+                &composite_key(&[tx_id.as_bytes(), block.id().as_bytes()]),
+                &[],
+                "missing_transactions_insert",
+            )?;
+            lmdb::put_raw(
+                self.write_txn(),
+                &databases.missing_transactions_by_view,
+                &composite_key(&[
+                    &block.view().as_u64().to_be_bytes(),
+                    block.id().as_bytes(),
+                    tx_id.as_bytes(),
+                ]),
+                &[],
+                "missing_transactions_insert",
+            )?;
+        }
+
+        Ok(())
+    }
+
+    fn missing_transactions_remove(
+        &mut self,
+        current_view: View,
+        transaction_id: &TransactionId,
+    ) -> Result<Option<Block<TAddr>>, StorageError> {
+        let databases = self.databases();
+
+        // delete all entries that are for previous views
+        let min_view = current_view.as_u64().saturating_sub(1);
+        let keys = lmdb::range_keys(
+            self.txn(),
+            &databases.missing_transactions_by_view,
+            None,
+            "missing_transactions_remove",
+            |key| lmdb::decode_u64("missing_transactions_remove", key).map_or(false, |view| view < min_view),
+        )?;
+        for key in keys {
+            let (row_block_id, row_tx_id) = key[8..].split_at(BlockId::byte_size());
+            self.missing_transaction_delete(row_block_id, row_tx_id)?;
+        }
+
+        let keys = lmdb::prefix_keys(
+            self.txn(),
+            &databases.missing_transactions_by_transaction,
+            transaction_id.as_bytes(),
+            "missing_transactions_remove",
+        )?;
+        let mut block_id = None;
+        for key in keys {
+            let row_block_id = &key[TransactionId::byte_size()..];
+            let row = lmdb::get::<models::MissingTransaction>(
+                self.txn(),
+                &databases.missing_transactions,
+                &composite_key(&[row_block_id, transaction_id.as_bytes()]),
+                "missing_transactions_remove",
+            )?;
+            if row.map_or(false, |row| row.block_view == current_view) {
+                block_id = Some(lmdb::id_from_bytes::<BlockId>(
+                    "missing_transactions_remove",
+                    row_block_id,
+                )?);
+                break;
+            }
+        }
+        let Some(block_id) = block_id else {
+            return Ok(None);
+        };
+
+        self.missing_transactions_delete_for_transaction(transaction_id)?;
+
+        let mut missing_transactions = self.blocks_get_pending_transactions(&block_id)?;
+        if missing_transactions.is_empty() {
+            return self.parked_blocks_remove(&block_id).map(Some);
+        }
+
+        // Make double sure that we dont have these transactions due to a race condition between inserting
+        // missing transactions and them completing execution.
+        let found_transaction_ids = self
+            .transactions_get_any(missing_transactions.iter())?
+            .into_iter()
+            .filter(|rec| rec.result.is_some())
+            .map(|rec| *rec.transaction.id())
+            .collect::<HashSet<_>>();
+
+        for found in &found_transaction_ids {
+            self.missing_transactions_delete_for_transaction(found)?;
+        }
+
+        missing_transactions.retain(|id| !found_transaction_ids.contains(id));
+
+        if missing_transactions.is_empty() {
+            return self.parked_blocks_remove(&block_id).map(Some);
+        }
+
+        Ok(None)
+    }
+
+    fn quorum_certificates_insert(&mut self, qc: &QuorumCertificate<Self::Addr>) -> Result<(), StorageError> {
+        let databases = self.databases();
+        lmdb::insert(
+            self.write_txn(),
+            &databases.quorum_certificates,
+            qc.id().as_bytes(),
+            qc,
+            "quorum_certificates_insert",
+            "quorum certificate",
+        )?;
+        lmdb::put(
+            self.write_txn(),
+            &databases.quorum_certificates_by_block,
+            qc.block_id().as_bytes(),
+            qc.id(),
+            "quorum_certificates_insert",
+            "quorum certificate id",
+        )?;
+
+        Ok(())
+    }
+
+    fn last_sent_vote_set(&mut self, last_sent_vote: &LastSentVote<Self::Addr>) -> Result<(), StorageError> {
+        self.append(
+            PointerTable::LastSentVote,
+            &last_sent_vote.block_id,
+            &models::LastSentVoteRow::from(last_sent_vote),
+            "last_sent_vote_set",
+            "last sent vote",
+        )?;
+        Ok(())
+    }
+
+    fn last_voted_set(&mut self, last_voted: &LastVoted) -> Result<(), StorageError> {
+        self.append(
+            PointerTable::LastVoted,
+            &last_voted.block_id,
+            &models::BlockPointer::from(last_voted),
+            "last_voted_set",
+            "last voted",
+        )?;
+        Ok(())
+    }
+
+    fn last_votes_unset(&mut self, last_voted: &LastVoted) -> Result<(), StorageError> {
+        self.unset_block_pointer(
+            PointerTable::LastVoted,
+            &last_voted.block_id,
+            last_voted.height,
+            "last_votes_unset",
+        )?;
+        Ok(())
+    }
+
+    fn last_executed_set(&mut self, last_exec: &LastExecuted) -> Result<(), StorageError> {
+        self.append(
+            PointerTable::LastExecuted,
+            &last_exec.block_id,
+            &models::BlockPointer::from(last_exec),
+            "last_executed_set",
+            "last executed",
+        )?;
+        Ok(())
+    }
+
+    fn last_proposed_set(&mut self, last_proposed: &LastProposed) -> Result<(), StorageError> {
+        self.append(
+            PointerTable::LastProposed,
+            &last_proposed.block_id,
+            &models::BlockPointer::from(last_proposed),
+            "last_proposed_set",
+            "last proposed",
+        )?;
+        Ok(())
+    }
+
+    fn last_proposed_unset(&mut self, last_proposed: &LastProposed) -> Result<(), StorageError> {
+        self.unset_block_pointer(
+            PointerTable::LastProposed,
+            &last_proposed.block_id,
+            last_proposed.height,
+            "last_proposed_unset",
+        )?;
+        Ok(())
+    }
+
+    fn leaf_block_set(&mut self, leaf_node: &LeafBlock) -> Result<(), StorageError> {
+        self.append(
+            PointerTable::LeafBlocks,
+            &leaf_node.block_id,
+            &models::BlockPointer::from(leaf_node),
+            "leaf_block_set",
+            "leaf block",
+        )?;
+        Ok(())
+    }
+
+    fn locked_block_set(&mut self, locked_block: &LockedBlock) -> Result<(), StorageError> {
+        if let Some(existing) = self.locked_block_get().optional()? {
+            if locked_block.height <= existing.height {
+                return Err(StorageError::QueryError {
+                    reason: format!(
+                        "Locked block height {} is not greater than existing height {}",
+                        locked_block.height, existing.height
+                    ),
+                });
+            }
+        }
+
+        self.append(
+            PointerTable::LockedBlock,
+            &locked_block.block_id,
+            &models::BlockPointer::from(locked_block),
+            "locked_block_set",
+            "locked block",
+        )?;
+        Ok(())
+    }
+
+    fn high_qc_set(&mut self, high_qc: &HighQc) -> Result<(), StorageError> {
+        self.append(
+            PointerTable::HighQcs,
+            &high_qc.block_id,
+            &models::HighQcRow::from(high_qc),
+            "high_qc_set",
+            "high qc",
+        )?;
+        Ok(())
+    }
+
+    fn transactions_insert(&mut self, transaction: &Transaction) -> Result<(), StorageError> {
+        let databases = self.databases();
+        lmdb::insert(
+            self.write_txn(),
+            &databases.transactions,
+            transaction.id().as_bytes(),
+            &TransactionRecord::new(transaction.clone()),
+            "transactions_insert",
+            "transaction",
+        )?;
+        let seq = lmdb::next_sequence(self.txn(), &databases.transactions_by_created, "transactions_insert")?;
+        lmdb::put_raw(
+            self.write_txn(),
+            &databases.transactions_by_created,
+            &lmdb::sequence_key(seq),
+            transaction.id().as_bytes(),
+            "transactions_insert",
+        )?;
+
+        Ok(())
+    }
+
+    fn transactions_update(&mut self, transaction_rec: &TransactionRecord) -> Result<(), StorageError> {
+        let transaction_id = transaction_rec.transaction.id();
+        if !self.transactions_exists(transaction_id)? {
+            return Err(StorageError::NotFound {
+                item: "transaction".to_string(),
+                key: transaction_id.to_string(),
+            });
+        }
+
+        lmdb::put(
+            self.write_txn(),
+            &self.databases().transactions,
+            transaction_id.as_bytes(),
+            transaction_rec,
+            "transactions_update",
+            "transaction",
+        )?;
+
+        Ok(())
+    }
+
+    fn transactions_save_all<'a, I: IntoIterator<Item = &'a TransactionRecord>>(
+        &mut self,
+        txs: I,
+    ) -> Result<(), StorageError> {
+        let databases = self.databases();
+        for rec in txs {
+            let transaction_id = rec.transaction.id();
+            // Insert or ignore
+            if self.transactions_exists(transaction_id)? {
+                continue;
+            }
+            let record = TransactionRecord {
+                transaction: rec.transaction.clone(),
+                result: rec.result.clone(),
+                execution_time: None,
+                resulting_outputs: rec.resulting_outputs.clone(),
+                final_decision: None,
+                abort_details: None,
+            };
+            lmdb::insert(
+                self.write_txn(),
+                &databases.transactions,
+                transaction_id.as_bytes(),
+                &record,
+                "transactions_save_all",
+                "transaction",
+            )?;
+            let seq = lmdb::next_sequence(self.txn(), &databases.transactions_by_created, "transactions_save_all")?;
+            lmdb::put_raw(
+                self.write_txn(),
+                &databases.transactions_by_created,
+                &lmdb::sequence_key(seq),
+                transaction_id.as_bytes(),
+                "transactions_save_all",
+            )?;
+        }
+
+        Ok(())
+    }
+
+    fn transaction_pool_insert(
+        &mut self,
+        transaction: TransactionAtom,
+        fee_info: &TransactionFeeInfo,
+        stage: TransactionPoolStage,
+        is_ready: bool,
+    ) -> Result<(), StorageError> {
+        let transaction_id = transaction.id;
+        let record = models::TransactionPoolRecord::new(transaction, fee_info, stage, is_ready, now());
+        lmdb::insert(
+            self.write_txn(),
+            &self.databases().transaction_pool,
+            transaction_id.as_bytes(),
+            &record,
+            "transaction_pool_insert",
+            "transaction pool record",
+        )?;
+        self.transaction_pool_index(&record, "transaction_pool_insert")?;
+
+        Ok(())
+    }
+
+    fn transaction_pool_add_pending_update(&mut self, update: TransactionPoolStatusUpdate) -> Result<(), StorageError> {
+        let databases = self.databases();
+        let update = models::TransactionPoolStateUpdate::from(update);
+
+        lmdb::put(
+            self.write_txn(),
+            &databases.transaction_pool_state_updates,
+            &composite_key(&[update.transaction_id.as_bytes(), update.block_id.as_bytes()]),
+            &update,
+            "transaction_pool_add_pending_update",
+            "transaction pool state update",
+        )?;
+        lmdb::put_raw(
+            self.write_txn(),
+            &databases.transaction_pool_state_updates_by_block,
+            &composite_key(&[update.block_id.as_bytes(), update.transaction_id.as_bytes()]),
+            &[],
+            "transaction_pool_add_pending_update",
+        )?;
+
+        // Set is_ready to the last value we set here. Bit of a hack to get has_uncommitted_transactions to return a
+        // more accurate value without querying the updates table
+        let record = lmdb::get::<models::TransactionPoolRecord>(
+            self.txn(),
+            &databases.transaction_pool,
+            update.transaction_id.as_bytes(),
+            "transaction_pool_add_pending_update",
+        )?;
+        if let Some(mut record) = record {
+            record.is_ready = update.is_ready;
+            record.pending_stage = Some(update.stage);
+            lmdb::put(
+                self.write_txn(),
+                &databases.transaction_pool,
+                update.transaction_id.as_bytes(),
+                &record,
+                "transaction_pool_add_pending_update",
+                "transaction pool record",
+            )?;
+        }
+
+        Ok(())
+    }
+
+    fn transaction_pool_update(
+        &mut self,
+        transaction_id: &TransactionId,
+        local_decision: Option<Decision>,
+        remote_decision: Option<Decision>,
+        remote_evidence: Option<&Evidence>,
+    ) -> Result<(), StorageError> {
+        let databases = self.databases();
+        let mut record = lmdb::get::<models::TransactionPoolRecord>(
+            self.txn(),
+            &databases.transaction_pool,
+            transaction_id.as_bytes(),
+            "transaction_pool_update",
+        )?
+        .ok_or_else(|| StorageError::NotFound {
+            item: "transaction".to_string(),
+            key: transaction_id.to_string(),
+        })?;
+
+        if let Some(remote_evidence) = remote_evidence {
+            record.remote_evidence = Some(remote_evidence.clone());
+        }
+        if let Some(local_decision) = local_decision {
+            record.local_decision = Some(local_decision);
+        }
+        if let Some(remote_decision) = remote_decision {
+            record.remote_decision = Some(remote_decision);
+        }
+        record.updated_at = now();
+
+        lmdb::put(
+            self.write_txn(),
+            &databases.transaction_pool,
+            transaction_id.as_bytes(),
+            &record,
+            "transaction_pool_update",
+            "transaction pool record",
+        )?;
+
+        Ok(())
+    }
+
+    fn transaction_pool_remove(&mut self, transaction_id: &TransactionId) -> Result<(), StorageError> {
+        let databases = self.databases();
+        let record = lmdb::get::<models::TransactionPoolRecord>(
+            self.txn(),
+            &databases.transaction_pool,
+            transaction_id.as_bytes(),
+            "transaction_pool_remove",
+        )?
+        .ok_or_else(|| StorageError::NotFound {
+            item: "transaction".to_string(),
+            key: transaction_id.to_string(),
+        })?;
+        lmdb::delete(
+            self.write_txn(),
+            &databases.transaction_pool,
+            transaction_id.as_bytes(),
+            "transaction_pool_remove",
+        )?;
+        self.transaction_pool_unindex(&record, "transaction_pool_remove")?;
+
+        let keys = lmdb::prefix_keys(
+            self.txn(),
+            &databases.transaction_pool_state_updates,
+            transaction_id.as_bytes(),
+            "transaction_pool_remove",
+        )?;
+        for key in keys {
+            self.transaction_pool_state_update_delete(&key, "transaction_pool_remove")?;
+        }
+
+        Ok(())
+    }
+
+    fn transaction_pool_set_all_transitions<'a, I: IntoIterator<Item = &'a TransactionId>>(
+        &mut self,
+        locked_block: &LockedBlock,
+        new_locked_block: &LockedBlock,
+        tx_ids: I,
+    ) -> Result<(), StorageError> {
+        let databases = self.databases();
+        let tx_ids = tx_ids.into_iter().collect::<Vec<_>>();
+
+        let mut count = 0;
+        for tx_id in &tx_ids {
+            if self.transaction_pool_exists(tx_id)? {
+                count += 1;
+            }
+        }
+
+        if count != tx_ids.len() {
+            return Err(LmdbStorageError::NotAllTransactionsFound {
+                operation: "transaction_pool_set_all_transitions",
+                details: format!("Found {} transactions, but {} were queried", count, tx_ids.len()),
+            }
+            .into());
+        }
+
+        let updates = self.get_transaction_atom_state_updates_between_blocks(
+            locked_block.block_id(),
+            new_locked_block.block_id(),
+            tx_ids.iter().copied(),
+        )?;
+
+        debug!(
+            target: LOG_TARGET,
+            "transaction_pool_set_all_transitions: locked_block={}, new_locked_block={}, {} transactions, {} updates",
+            locked_block,
+            new_locked_block,
+            tx_ids.len(),
+            updates.len()
+        );
+
+        for tx_id in &tx_ids {
+            let pending = lmdb::prefix_scan::<models::TransactionPoolStateUpdate>(
+                self.txn(),
+                &databases.transaction_pool_state_updates,
+                tx_id.as_bytes(),
+                "transaction_pool_set_all_transitions",
+            )?;
+            for (key, update) in pending {
+                if update.block_height <= new_locked_block.height() {
+                    self.transaction_pool_state_update_delete(&key, "transaction_pool_set_all_transitions")?;
+                }
+            }
+        }
+
+        for update in updates.into_values() {
+            let record = lmdb::get::<models::TransactionPoolRecord>(
+                self.txn(),
+                &databases.transaction_pool,
+                update.transaction_id.as_bytes(),
+                "transaction_pool_set_all_transitions",
+            )?;
+            let Some(mut record) = record else {
+                continue;
+            };
+            self.transaction_pool_unindex(&record, "transaction_pool_set_all_transitions")?;
+            record.stage = update.stage;
+            record.local_decision = Some(update.local_decision);
+            record.evidence = update.evidence;
+            record.is_ready = update.is_ready;
+            record.updated_at = now();
+            lmdb::put(
+                self.write_txn(),
+                &databases.transaction_pool,
+                update.transaction_id.as_bytes(),
+                &record,
+                "transaction_pool_set_all_transitions",
+                "transaction pool record",
+            )?;
+            self.transaction_pool_index(&record, "transaction_pool_set_all_transitions")?;
+        }
+
+        Ok(())
+    }
+
+    fn votes_insert(&mut self, vote: &Vote<Self::Addr>) -> Result<(), StorageError> {
+        // Votes are only ever deleted for a whole block at a time, so the number of votes for the block is the next
+        // sequence for the block
+        let seq = self.votes_count_for_block(&vote.block_id)?;
+        lmdb::insert(
+            self.write_txn(),
+            &self.databases().votes,
+            &composite_key(&[vote.block_id.as_bytes(), &lmdb::sequence_key(seq)]),
+            vote,
+            "votes_insert",
+            "vote",
+        )?;
+
+        Ok(())
+    }
+
+    fn substates_try_lock_many<'a, I: IntoIterator<Item = &'a ShardId>>(
+        &mut self,
+        locked_by_tx: &TransactionId,
+        objects: I,
+        lock_flag: SubstateLockFlag,
+    ) -> Result<SubstateLockState, StorageError> {
+        let substates = self.get_substate_rows_for_lock(objects, lock_flag)?;
+
+        if substates.iter().any(|s| s.is_locked_w) {
+            return Ok(SubstateLockState::SomeAlreadyWriteLocked);
+        }
+
+        if substates.iter().any(|s| s.record.destroyed.is_some()) {
+            return Ok(SubstateLockState::SomeDestroyed);
+        }
+
+        for mut substate in substates {
+            match lock_flag {
+                SubstateLockFlag::Write => {
+                    substate.is_locked_w = true;
+                    substate.locked_by = Some(*locked_by_tx);
+                },
+                SubstateLockFlag::Read => {
+                    substate.read_locks += 1;
+                },
+            }
+            self.substate_put(&substate, "substates_try_lock_many")?;
+        }
+
+        Ok(SubstateLockState::LockAcquired)
+    }
+
+    fn substates_try_unlock_many<'a, I: IntoIterator<Item = &'a ShardId>>(
+        &mut self,
+        locked_by_tx: &TransactionId,
+        objects: I,
+        lock_flag: SubstateLockFlag,
+    ) -> Result<(), StorageError> {
+        let objects = objects.into_iter().collect::<HashSet<_>>();
+
+        for shard_id in objects {
+            let Some(mut substate) = self.get_substate_row(shard_id, "substates_try_unlock_many")? else {
+                continue;
+            };
+            match lock_flag {
+                SubstateLockFlag::Write => {
+                    // Only the locking transaction can unlock the substates for write
+                    if substate.locked_by.as_ref() != Some(locked_by_tx) {
+                        continue;
+                    }
+                    substate.is_locked_w = false;
+                    substate.locked_by = None;
+                },
+                SubstateLockFlag::Read => {
+                    substate.read_locks = substate.read_locks.saturating_sub(1);
+                },
+            }
+            self.substate_put(&substate, "substates_try_unlock_many")?;
+        }
+
+        Ok(())
+    }
+
+    fn substate_down_many<I: IntoIterator<Item = ShardId>>(
+        &mut self,
+        shard_ids: I,
+        epoch: Epoch,
+        destroyed_block_id: &BlockId,
+        destroyed_transaction_id: &TransactionId,
+        destroyed_qc_id: &QcId,
+        require_locks: bool,
+    ) -> Result<(), StorageError> {
+        let shard_ids = shard_ids.into_iter().collect::<Vec<_>>();
+        let substates = shard_ids
+            .iter()
+            .filter_map(|shard_id| self.get_substate_row(shard_id, "substate_down").transpose())
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if substates.len() != shard_ids.len() {
+            return Err(LmdbStorageError::NotAllSubstatesFound {
+                operation: "substate_down",
+                details: format!(
+                    "Found {} substates, but {} were requested",
+                    substates.len(),
+                    shard_ids.len()
+                ),
+            }
+            .into());
+        }
+        if require_locks {
+            if let Some(substate) = substates.iter().find(|s| !s.is_locked_w) {
+                return Err(LmdbStorageError::SubstatesUnlock {
+                    operation: "substate_down",
+                    details: format!("Substate {} is not write locked", substate.record.address),
+                }
+                .into());
+            }
+        }
+
+        for mut substate in substates {
+            let destroyed = SubstateDestroyed {
+                by_transaction: *destroyed_transaction_id,
+                justify: *destroyed_qc_id,
+                by_block: *destroyed_block_id,
+                at_epoch: epoch,
+            };
+            self.substate_index_destroyed(&substate.record.to_shard_id(), &destroyed, "substate_down")?;
+            substate.record.destroyed = Some(destroyed);
+            self.substate_put(&substate, "substate_down")?;
+            self.substate_index(
+                destroyed_transaction_id,
+                destroyed_block_id,
+                &substate.record.to_shard_id(),
+                "substate_down",
+            )?;
+        }
+
+        Ok(())
+    }
+
+    fn substates_create(&mut self, substate: SubstateRecord) -> Result<(), StorageError> {
+        let shard_id = substate.to_shard_id();
+        let address = substate.address.clone();
+        let version = substate.version;
+        let created_by_transaction = substate.created_by_transaction;
+        let created_block = substate.created_block;
+        let destroyed = substate.destroyed.clone();
+
+        lmdb::insert(
+            self.write_txn(),
+            &self.databases().substates,
+            shard_id.as_bytes(),
+            &models::Substate::new(substate),
+            "substate_create",
+            "substate",
+        )?;
+        self.substate_index(&created_by_transaction, &created_block, &shard_id, "substate_create")?;
+        lmdb::put_raw(
+            self.write_txn(),
+            &self.databases().substates_by_address,
+            &substate_address_key(&address, version),
+            &[],
+            "substate_create",
+        )?;
+        if let Some(destroyed) = destroyed {
+            self.substate_index(
+                &destroyed.by_transaction,
+                &destroyed.by_block,
+                &shard_id,
+                "substate_create",
+            )?;
+            self.substate_index_destroyed(&shard_id, &destroyed, "substate_create")?;
+        }
+
+        Ok(())
+    }
+
+    fn substates_delete_many<I: IntoIterator<Item = ShardId>>(&mut self, shard_ids: I) -> Result<usize, StorageError> {
+        const OPERATION: &str = "substates_delete_many";
+
+        let mut num_deleted = 0;
+        for shard_id in shard_ids {
+            let Some(substate) =
+                lmdb::get::<models::Substate>(self.txn(), &self.databases().substates, shard_id.as_bytes(), OPERATION)?
+            else {
+                continue;
+            };
+            self.substate_delete_row(shard_id.as_bytes(), &substate.record, OPERATION)?;
+            num_deleted += 1;
+        }
+
+        Ok(num_deleted)
+    }
+
+    fn locked_outputs_acquire_all<I, B>(
+        &mut self,
+        block_id: &BlockId,
+        transaction_id: &TransactionId,
+        output_shards: I,
+    ) -> Result<SubstateLockState, StorageError>
+    where
+        I: IntoIterator<Item = B>,
+        B: Borrow<ShardId>,
+    {
+        let output_shards = output_shards.into_iter().map(|s| *s.borrow()).collect::<Vec<_>>();
+
+        // Nothing is inserted if any of the outputs are already locked
+        if !matches!(
+            self.locked_outputs_check_all(&output_shards)?,
+            SubstateLockState::LockAcquired
+        ) {
+            return Ok(SubstateLockState::SomeAlreadyWriteLocked);
+        }
+
+        for shard_id in output_shards {
+            let row = models::LockedOutputRow {
+                block_id: *block_id,
+                transaction_id: *transaction_id,
+                shard_id,
+            };
+            match lmdb::insert(
+                self.write_txn(),
+                &self.databases().locked_outputs,
+                shard_id.as_bytes(),
+                &row,
+                "locked_outputs_acquire_all",
+                "locked output",
+            ) {
+                Ok(()) => {},
+                // Duplicate shard within the same request
+                Err(LmdbStorageError::AlreadyExists { .. }) => return Ok(SubstateLockState::SomeAlreadyWriteLocked),
+                Err(e) => return Err(e.into()),
+            }
+            lmdb::put_raw(
+                self.write_txn(),
+                &self.databases().locked_outputs_by_block,
+                &composite_key(&[block_id.as_bytes(), shard_id.as_bytes()]),
+                &[],
+                "locked_outputs_acquire_all",
+            )?;
+        }
+
+        Ok(SubstateLockState::LockAcquired)
+    }
+
+    fn locked_outputs_release_all<I, B>(&mut self, output_shards: I) -> Result<Vec<LockedOutput>, StorageError>
+    where
+        I: IntoIterator<Item = B>,
+        B: Borrow<ShardId>,
+    {
+        let databases = self.databases();
+        let mut released = Vec::new();
+        for shard_id in output_shards {
+            let shard_id = shard_id.borrow();
+            let row = lmdb::get::<models::LockedOutputRow>(
+                self.txn(),
+                &databases.locked_outputs,
+                shard_id.as_bytes(),
+                "locked_outputs_release",
+            )?;
+            if let Some(row) = row {
+                lmdb::delete(
+                    self.write_txn(),
+                    &databases.locked_outputs,
+                    shard_id.as_bytes(),
+                    "locked_outputs_release",
+                )?;
+                lmdb::delete(
+                    self.write_txn(),
+                    &databases.locked_outputs_by_block,
+                    &composite_key(&[row.block_id.as_bytes(), shard_id.as_bytes()]),
+                    "locked_outputs_release",
+                )?;
+                released.push(row.into());
+            }
+        }
+
+        Ok(released)
+    }
+
+    fn votes_prune_up_to_height(&mut self, height: NodeHeight, limit: usize) -> Result<usize, StorageError> {
+        const OPERATION: &str = "votes_prune_up_to_height";
+        let databases = self.databases();
+
+        // Only the votes for blocks that are not yet locked are kept, so the scan skips over few of them. Votes are
+        // deleted for a whole block at a time (see votes_insert), so the last block may take the count over the limit.
+        let mut num_deleted = 0;
+        let mut after = None;
+        while num_deleted < limit {
+            let Some(key) = lmdb::next_key(self.txn(), &databases.votes, after.as_deref(), OPERATION)? else {
+                break;
+            };
+            let block_id = lmdb::id_from_bytes::<BlockId>(OPERATION, &key[..BlockId::byte_size()])?;
+            let keys = lmdb::prefix_keys(self.txn(), &databases.votes, block_id.as_bytes(), OPERATION)?;
+            let is_prunable = self.get_block_row(&block_id)?.map_or(false, |b| b.height <= height);
+            if is_prunable {
+                for key in &keys {
+                    lmdb::delete(self.write_txn(), &databases.votes, key, OPERATION)?;
+                }
+                num_deleted += keys.len();
+            }
+            after = keys.into_iter().last();
+        }
+
+        Ok(num_deleted)
+    }
+
+    fn blocks_prune_uncommitted_up_to_height(
+        &mut self,
+        height: NodeHeight,
+        limit: usize,
+    ) -> Result<usize, StorageError> {
+        const OPERATION: &str = "blocks_prune_uncommitted_up_to_height";
+        let databases = self.databases();
+
+        let referenced = self.referenced_block_ids(OPERATION)?;
+        let mut pruned = HashSet::new();
+        let mut after = None;
+        while pruned.len() < limit {
+            let Some(key) = lmdb::next_key(
+                self.txn(),
+                &databases.blocks_uncommitted_by_height,
+                after.as_deref(),
+                OPERATION,
+            )?
+            else {
+                break;
+            };
+            if lmdb::decode_u64(OPERATION, &key)? > height.as_u64() {
+                break;
+            }
+            let block_id = lmdb::id_from_bytes::<BlockId>(OPERATION, &key[8..])?;
+            after = Some(key);
+            if referenced.contains(&block_id) ||
+                lmdb::prefix_exists(
+                    self.txn(),
+                    &databases.locked_outputs_by_block,
+                    block_id.as_bytes(),
+                    OPERATION,
+                )?
+            {
+                continue;
+            }
+            let block = self
+                .get_block_row(&block_id)?
+                .ok_or_else(|| LmdbStorageError::DbInconsistency {
+                    operation: OPERATION,
+                    details: format!("Block {} is indexed as uncommitted but does not exist", block_id),
+                })?;
+            self.block_delete_row(&block, OPERATION)?;
+            // The block is not referenced by the latest row of any pointer table, so every row pointing to it is
+            // historical
+            for table in PointerTable::PRUNABLE {
+                self.delete_block_pointers(table, &block_id, OPERATION, |_| Ok(true))?;
+            }
+            let keys = lmdb::prefix_keys(
+                self.txn(),
+                &databases.transaction_pool_state_updates_by_block,
+                block_id.as_bytes(),
+                OPERATION,
+            )?;
+            for key in keys {
+                let transaction_id = &key[BlockId::byte_size()..];
+                self.transaction_pool_state_update_delete(
+                    &composite_key(&[transaction_id, block_id.as_bytes()]),
+                    OPERATION,
+                )?;
+            }
+            pruned.insert(block_id);
+        }
+
+        if pruned.is_empty() {
+            return Ok(0);
+        }
+
+        // Only blocks above the pruned height can still use a QC for a pruned block as their justify
+        let mut referenced_qcs = HashSet::new();
+        let keys = lmdb::range_keys(
+            self.txn(),
+            &databases.blocks_by_height,
+            Some(&(height.as_u64() + 1).to_be_bytes()[..]),
+            OPERATION,
+            |_| true,
+        )?;
+        for key in keys {
+            let block_id = lmdb::id_from_bytes::<BlockId>(OPERATION, &key[8..])?;
+            if let Some(block) = self.get_block_row(&block_id)? {
+                referenced_qcs.insert(block.qc_id);
+            }
+        }
+
+        for block_id in &pruned {
+            let qc_id = lmdb::get::<QcId>(
+                self.txn(),
+                &databases.quorum_certificates_by_block,
+                block_id.as_bytes(),
+                OPERATION,
+            )?;
+            let Some(qc_id) = qc_id else {
+                continue;
+            };
+            if referenced_qcs.contains(&qc_id) {
+                continue;
+            }
+            lmdb::delete(
+                self.write_txn(),
+                &databases.quorum_certificates,
+                qc_id.as_bytes(),
+                OPERATION,
+            )?;
+            lmdb::delete(
+                self.write_txn(),
+                &databases.quorum_certificates_by_block,
+                block_id.as_bytes(),
+                OPERATION,
+            )?;
+        }
+
+        Ok(pruned.len())
+    }
+
+    fn substates_prune_destroyed_before_epoch(&mut self, epoch: Epoch, limit: usize) -> Result<usize, StorageError> {
+        const OPERATION: &str = "substates_prune_destroyed_before_epoch";
+        let databases = self.databases();
+
+        let mut num_deleted = 0;
+        let mut destroyed_height = NodeHeight(0);
+        // Deleting a substate removes its index entry, so each iteration starts at the first remaining entry
+        while num_deleted < limit {
+            let Some(key) = lmdb::next_key(self.txn(), &databases.substates_by_destroyed_epoch, None, OPERATION)?
+            else {
+                break;
+            };
+            if lmdb::decode_u64(OPERATION, &key)? >= epoch.as_u64() {
+                break;
+            }
+            let shard_id = &key[8..];
+            let substate = lmdb::get::<models::Substate>(self.txn(), &databases.substates, shard_id, OPERATION)?
+                .ok_or_else(|| LmdbStorageError::DbInconsistency {
+                    operation: OPERATION,
+                    details: format!(
+                        "Substate {} is indexed as destroyed but does not exist",
+                        hex::encode(shard_id)
+                    ),
+                })?;
+            if let Some(block) = substate
+                .record
+                .destroyed()
+                .map(|destroyed| self.get_block_row(&destroyed.by_block))
+                .transpose()?
+                .flatten()
+            {
+                destroyed_height = destroyed_height.max(block.height);
+            }
+            self.substate_delete_row(shard_id, &substate.record, OPERATION)?;
+            num_deleted += 1;
+        }
+
+        if num_deleted > 0 {
+            let mark = self.substates_get_prune_mark()?;
+            let new_mark = SubstatePruneMark {
+                before_epoch: mark.map_or(epoch, |m| m.before_epoch.max(epoch)),
+                destroyed_height: mark.map_or(destroyed_height, |m| m.destroyed_height.max(destroyed_height)),
+            };
+            // Each batch advances the same mark, so only record it when it changes
+            if mark != Some(new_mark) {
+                let seq = lmdb::next_sequence(self.txn(), &databases.substate_pruning, OPERATION)?;
+                lmdb::insert(
+                    self.write_txn(),
+                    &databases.substate_pruning,
+                    &lmdb::sequence_key(seq),
+                    &new_mark,
+                    OPERATION,
+                    "substate prune mark",
+                )?;
+            }
+        }
+
+        Ok(num_deleted)
+    }
+
+    fn transaction_pool_history_prune(&mut self, _retention: Duration, _limit: usize) -> Result<usize, StorageError> {
+        // The transaction pool history is a debug table populated by a sqlite trigger. The LMDB store does not record
+        // one, so there is never anything to delete.
+        Ok(0)
+    }
+}
+
+impl<'a, TAddr> Deref for LmdbStateStoreWriteTransaction<'a, TAddr> {
+    type Target = LmdbStateStoreReadTransaction<'a, TAddr>;
+
+    fn deref(&self) -> &Self::Target {
+        self.transaction.as_ref().unwrap()
+    }
+}
+
+impl<'a, TAddr> DerefMut for LmdbStateStoreWriteTransaction<'a, TAddr> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.transaction.as_mut().unwrap()
+    }
+}
+
+impl<TAddr> Drop for LmdbStateStoreWriteTransaction<'_, TAddr> {
+    fn drop(&mut self) {
+        if self.transaction.is_some() {
+            warn!(
+                target: LOG_TARGET,
+                "Shard store write transaction was not committed/rolled back"
+            );
+        }
+    }
+}
+
+fn now() -> PrimitiveDateTime {
+    let now = time::OffsetDateTime::now_utc();
+    PrimitiveDateTime::new(now.date(), now.time())
+}
@@ -0,0 +1,200 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use rand::{rngs::OsRng, RngCore};
+use tari_dan_common_types::{Epoch, NodeHeight, View};
+use tari_dan_storage::{
+    consensus_models::{
+        Block,
+        Command,
+        Decision,
+        TransactionAtom,
+        TransactionFeeInfo,
+        TransactionPoolStage,
+        TransactionPoolStatusUpdate,
+    },
+    StateStore,
+    StateStoreReadTransaction,
+    StateStoreWriteTransaction,
+};
+use tari_state_store_lmdb::LmdbStateStore;
+use tari_transaction::TransactionId;
+use tempfile::TempDir;
+
+fn create_db() -> (LmdbStateStore<String>, TempDir) {
+    let dir = tempfile::tempdir().unwrap();
+    let db = LmdbStateStore::open(dir.path()).unwrap();
+    (db, dir)
+}
+
+fn create_tx_atom() -> TransactionAtom {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    TransactionAtom {
+        id: TransactionId::new(bytes),
+        decision: Decision::Commit,
+        evidence: Default::default(),
+        transaction_fee: 0,
+        leader_fee: 0,
+    }
+}
+
+fn create_fee_info() -> TransactionFeeInfo {
+    TransactionFeeInfo {
+        signer: Default::default(),
+        size: 100,
+    }
+}
+
+mod confirm_all_transitions {
+
+    use super::*;
+
+    #[test]
+    fn it_sets_pending_stage_to_stage() {
+        let (db, _dir) = create_db();
+        let mut tx = db.create_write_tx().unwrap();
+
+        let atom1 = create_tx_atom();
+        let atom2 = create_tx_atom();
+        let atom3 = create_tx_atom();
+
+        let zero_block = Block::zero_block();
+        zero_block.insert(&mut tx).unwrap();
+        let block1 = Block::new(
+            *zero_block.id(),
+            zero_block.justify().clone(),
+            None,
+            NodeHeight(1),
+            View(1),
+            Epoch(0),
+            None,
+            Default::default(),
+            // Need to have a command in, otherwise this block will not be included internally in the query because it
+            // cannot cause a state change without any commands
+            [Command::Prepare(atom1.clone())].into_iter().collect(),
+            Default::default(),
+        );
+        block1.insert(&mut tx).unwrap();
+
+        tx.transaction_pool_insert(atom1.clone(), &create_fee_info(), TransactionPoolStage::New, false)
+            .unwrap();
+        tx.transaction_pool_insert(atom2.clone(), &create_fee_info(), TransactionPoolStage::New, false)
+            .unwrap();
+        tx.transaction_pool_insert(atom3.clone(), &create_fee_info(), TransactionPoolStage::New, false)
+            .unwrap();
+        let block_id = *block1.id();
+
+        tx.transaction_pool_add_pending_update(TransactionPoolStatusUpdate {
+            block_id,
+            block_height: NodeHeight(1),
+            transaction_id: atom1.id,
+            stage: TransactionPoolStage::LocalPrepared,
+            evidence: Default::default(),
+            is_ready: false,
+            local_decision: Decision::Commit,
+        })
+        .unwrap();
+        tx.transaction_pool_add_pending_update(TransactionPoolStatusUpdate {
+            block_id,
+            block_height: NodeHeight(1),
+            transaction_id: atom2.id,
+            stage: TransactionPoolStage::Prepared,
+            evidence: Default::default(),
+            is_ready: false,
+            local_decision: Decision::Commit,
+        })
+        .unwrap();
+        tx.transaction_pool_add_pending_update(TransactionPoolStatusUpdate {
+            block_id,
+            block_height: NodeHeight(1),
+            transaction_id: atom3.id,
+            stage: TransactionPoolStage::Prepared,
+            evidence: Default::default(),
+            is_ready: false,
+            local_decision: Decision::Commit,
+        })
+        .unwrap();
+
+        let rec = tx.transaction_pool_get(zero_block.id(), &block_id, &atom1.id).unwrap();
+        assert!(rec.stage().is_new());
+        assert!(rec.pending_stage().unwrap().is_local_prepared());
+
+        let rec = tx.transaction_pool_get(zero_block.id(), &block_id, &atom2.id).unwrap();
+        assert!(rec.stage().is_new());
+        assert!(rec.pending_stage().unwrap().is_prepared());
+
+        tx.transaction_pool_set_all_transitions(&zero_block.as_locked_block(), &block1.as_locked_block(), &[
+            atom1.id, atom3.id,
+        ])
+        .unwrap();
+
+        let rec = tx.transaction_pool_get(zero_block.id(), &block_id, &atom1.id).unwrap();
+        assert!(rec.stage().is_local_prepared());
+        assert!(rec.pending_stage().is_none());
+
+        let rec = tx.transaction_pool_get(zero_block.id(), &block_id, &atom2.id).unwrap();
+        assert!(rec.stage().is_new());
+        assert!(rec.pending_stage().unwrap().is_prepared());
+
+        let rec = tx.transaction_pool_get(zero_block.id(), &block_id, &atom3.id).unwrap();
+        assert!(rec.stage().is_prepared());
+        assert!(rec.pending_stage().is_none());
+
+        tx.rollback().unwrap();
+    }
+}
+
+mod blocks {
+    use super::*;
+
+    #[test]
+    fn it_returns_the_parent_chain_in_descending_height_order() {
+        let (db, _dir) = create_db();
+        let mut tx = db.create_write_tx().unwrap();
+
+        let zero_block = Block::<String>::zero_block();
+        zero_block.insert(&mut tx).unwrap();
+        tx.quorum_certificates_insert(zero_block.justify()).unwrap();
+
+        let mut parent = zero_block.clone();
+        let mut blocks = vec![];
+        for height in 1..=3 {
+            let block = Block::new(
+                *parent.id(),
+                zero_block.justify().clone(),
+                None,
+                NodeHeight(height),
+                View(height),
+                Epoch(0),
+                None,
+                Default::default(),
+                Default::default(),
+                Default::default(),
+            );
+            block.insert(&mut tx).unwrap();
+            blocks.push(block.clone());
+            parent = block;
+        }
+
+        let tip = tx.blocks_get_tip().unwrap();
+        assert_eq!(tip.id(), blocks[2].id());
+        assert_eq!(tx.blocks_max_height().unwrap(), NodeHeight(3));
+
+        let chain = tx.blocks_get_parent_chain(blocks[2].id(), 2).unwrap();
+        assert_eq!(chain.len(), 2);
+        assert_eq!(chain[0].id(), blocks[2].id());
+        assert_eq!(chain[1].id(), blocks[1].id());
+
+        let between = tx.blocks_get_all_between(zero_block.id(), blocks[2].id()).unwrap();
+        assert_eq!(
+            between.iter().map(|b| *b.id()).collect::<Vec<_>>(),
+            blocks.iter().map(|b| *b.id()).collect::<Vec<_>>()
+        );
+
+        assert!(tx.blocks_is_ancestor(blocks[2].id(), blocks[0].id()).unwrap());
+        assert!(!tx.blocks_is_ancestor(blocks[0].id(), blocks[2].id()).unwrap());
+
+        tx.rollback().unwrap();
+    }
+}
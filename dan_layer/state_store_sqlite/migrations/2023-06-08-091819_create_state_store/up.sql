@@ -16,21 +16,28 @@ create table blocks
     block_id         text      not NULL,
     parent_block_id  text      not NULL,
     height           bigint    not NULL,
+    view             bigint    not NULL,
     epoch            bigint    not NULL,
+    epoch_cutoff     bigint    NULL,
     proposed_by      text      not NULL,
+    signature        text      NULL,
     qc_id            text      not NULL,
+    timeout_certificate text   NULL,
     command_count    bigint    not NULL,
     commands         text      not NULL,
     total_leader_fee bigint    not NULL,
     is_committed     boolean   not NULL default '0',
     is_processed     boolean   not NULL,
-    is_dummy         boolean   not NULL,
     created_at       timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP,
     FOREIGN KEY (qc_id) REFERENCES quorum_certificates (qc_id)
 );
 
 -- block_id must be unique. Optimise fetching by block_id
 create unique index blocks_uniq_idx_id on blocks (block_id);
+-- Used to find the justify QCs of the blocks above a height when pruning
+create index blocks_idx_height on blocks (height);
+-- Used to find the blocks that can be pruned
+create index blocks_idx_uncommitted_height on blocks (height) where is_committed = 0;
 
 create table parked_blocks
 (
@@ -38,9 +45,13 @@ create table parked_blocks
     block_id         text      not NULL,
     parent_block_id  text      not NULL,
     height           bigint    not NULL,
+    view             bigint    not NULL,
     epoch            bigint    not NULL,
+    epoch_cutoff     bigint    NULL,
     proposed_by      text      not NULL,
+    signature        text      NULL,
     justify          text      not NULL,
+    timeout_certificate text   NULL,
     command_count    bigint    not NULL,
     commands         text      not NULL,
     total_leader_fee bigint    not NULL,
@@ -85,9 +96,22 @@ create table substates
 
 -- All shard ids are unique
 create unique index substates_uniq_shard_id on substates (shard_id);
+-- querying for the version history of a substate address
+create index substates_idx_address_version on substates (address, version);
 -- querying for transaction ids that either Upd or Downd a substate
 create index substates_idx_created_by_transaction on substates (created_by_transaction);
 create index substates_idx_destroyed_by_transaction on substates (destroyed_by_transaction) where destroyed_by_transaction is not null;
+-- Used to find the destroyed substates that can be pruned
+create index substates_idx_destroyed_at_epoch on substates (destroyed_at_epoch) where destroyed_at_epoch is not null;
+
+-- How far back destroyed substates have been pruned. The latest row is the current mark.
+create table substate_pruning
+(
+    id               integer   not null primary key autoincrement,
+    before_epoch     bigint    not null,
+    destroyed_height bigint    not null,
+    created_at       timestamp NOT NULL default current_timestamp
+);
 
 create table high_qcs
 (
@@ -186,10 +210,15 @@ create table transaction_pool
     is_ready          boolean   not null,
     updated_at        timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP,
     created_at        timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    signer            text      not null,
+    transaction_size  bigint    not null,
+    fee_priority      bigint    not null,
     FOREIGN KEY (transaction_id) REFERENCES transactions (transaction_id)
 );
 create unique index transaction_pool_uniq_idx_transaction_id on transaction_pool (transaction_id);
 create index transaction_pool_idx_is_ready on transaction_pool (is_ready);
+-- Used to page through new transactions in descending order of fee-per-byte
+create index transaction_pool_idx_stage_fee_priority on transaction_pool (stage, fee_priority desc, transaction_id);
 
 create table transaction_pool_state_updates
 (
@@ -230,13 +259,15 @@ create table votes
     signature        text      not NULL,
     created_at       timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP
 );
+-- Used to find the votes for a block, including when pruning
+create index votes_idx_block_id on votes (block_id);
 
 
 CREATE TABLE missing_transactions
 (
     id                    integer   not NULL primary key AUTOINCREMENT,
     block_id              text      not NULL,
-    block_height          bigint    not NULL,
+    block_view            bigint    not NULL,
     transaction_id        text      not NULL,
     is_awaiting_execution boolean   not NULL,
     created_at            timestamp not NULL DEFAULT CURRENT_TIMESTAMP,
@@ -263,6 +294,8 @@ CREATE TABLE transaction_pool_history
     created_at        timestamp NOT NULL,
     change_time       DATETIME DEFAULT (STRFTIME('%Y-%m-%d %H:%M:%f', 'NOW'))
 );
+-- Used to find the history entries that can be pruned
+create index transaction_pool_history_idx_change_time on transaction_pool_history (change_time);
 
 CREATE TRIGGER copy_transaction_pool_history
     AFTER UPDATE
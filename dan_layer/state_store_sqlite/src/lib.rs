@@ -9,4 +9,6 @@ mod sqlite_transaction;
 mod store;
 mod writer;
 
+pub use reader::SqliteStateStoreReadTransaction;
 pub use store::SqliteStateStore;
+pub use writer::SqliteStateStoreWriteTransaction;
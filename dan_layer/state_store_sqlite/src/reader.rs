@@ -17,6 +17,7 @@ use diesel::{
     ExpressionMethods,
     JoinOnDsl,
     NullableExpressionMethods,
+    OptionalExtension,
     QueryDsl,
     QueryableByName,
     RunQueryDsl,
@@ -37,10 +38,12 @@ use tari_dan_storage::{
         LastVoted,
         LeafBlock,
         LockedBlock,
+        LockedOutput,
         QcId,
         QuorumCertificate,
         SubstateLockFlag,
         SubstateLockState,
+        SubstatePruneMark,
         SubstateRecord,
         TransactionPoolRecord,
         TransactionPoolStage,
@@ -51,6 +54,7 @@ use tari_dan_storage::{
     StateStoreReadTransaction,
     StorageError,
 };
+use tari_engine_types::substate::SubstateAddress;
 use tari_transaction::TransactionId;
 
 use crate::{
@@ -236,16 +240,16 @@ impl<'a, TAddr: NodeAddressable + Serialize + DeserializeOwned> SqliteStateStore
     ) -> Result<Vec<String>, SqliteStorageError> {
         let block_ids = sql_query(
             r#"
-            WITH RECURSIVE tree(bid, parent, is_dummy, command_count) AS (
-                SELECT block_id, parent_block_id, is_dummy, command_count FROM blocks where block_id = ?
+            WITH RECURSIVE tree(bid, parent, command_count) AS (
+                SELECT block_id, parent_block_id, command_count FROM blocks where block_id = ?
             UNION ALL
-                SELECT block_id, parent_block_id, blocks.is_dummy, blocks.command_count
+                SELECT block_id, parent_block_id, blocks.command_count
                 FROM blocks JOIN tree ON
                     block_id = tree.parent
                     AND tree.bid != ?
                 LIMIT 1000
             )
-            SELECT bid FROM tree where is_dummy = 0 AND command_count > 0"#,
+            SELECT bid FROM tree where command_count > 0"#,
         )
         .bind::<Text, _>(serialize_hex(end_block))
         .bind::<Text, _>(serialize_hex(start_block))
@@ -1039,56 +1043,140 @@ impl<TAddr: NodeAddressable + Serialize + DeserializeOwned> StateStoreReadTransa
         Ok(count > 0)
     }
 
-    fn transaction_pool_get_many_ready(&mut self, max_txs: usize) -> Result<Vec<TransactionPoolRecord>, StorageError> {
-        use crate::schema::transaction_pool;
+    fn transaction_pool_get_in_progress(&mut self) -> Result<Vec<TransactionPoolRecord>, StorageError> {
+        use crate::schema::{transaction_pool, transaction_pool_state_updates};
+
+        let locked = self.locked_block_get()?;
+        let leaf = self.leaf_block_get()?;
+        let applicable_block_ids = self.get_block_ids_that_change_state_between(&locked.block_id, &leaf.block_id)?;
+
+        // Transactions that are new as of the locked block but were proposed in a later block are also in progress
+        let proposed = transaction_pool_state_updates::table
+            .select(transaction_pool_state_updates::transaction_id)
+            .filter(transaction_pool_state_updates::block_id.eq_any(applicable_block_ids));
 
-        let ready_txs = transaction_pool::table
+        let txs = transaction_pool::table
+            .filter(
+                transaction_pool::stage
+                    .ne(TransactionPoolStage::New.to_string())
+                    .or(transaction_pool::transaction_id.eq_any(proposed)),
+            )
             .order_by(transaction_pool::transaction_id.asc())
             .get_results::<sql_models::TransactionPoolRecord>(self.connection())
             .map_err(|e| SqliteStorageError::DieselError {
-                operation: "transaction_pool_get_many_ready",
+                operation: "transaction_pool_get_in_progress",
+                source: e,
+            })?;
+
+        if txs.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut updates = self.get_transaction_atom_state_updates_between_blocks(
+            &locked.block_id,
+            &leaf.block_id,
+            txs.iter().map(|s| s.transaction_id.as_str()),
+        )?;
+
+        txs.into_iter()
+            .map(|rec| {
+                let maybe_update = updates.remove(&rec.transaction_id);
+                rec.try_convert(maybe_update)
+            })
+            .collect()
+    }
+
+    fn transaction_pool_get_new_by_fee_priority(
+        &mut self,
+        after: Option<(u64, &TransactionId)>,
+        limit: usize,
+    ) -> Result<Vec<TransactionPoolRecord>, StorageError> {
+        use crate::schema::transaction_pool;
+
+        let mut query = transaction_pool::table
+            .filter(transaction_pool::stage.eq(TransactionPoolStage::New.to_string()))
+            .into_boxed();
+        if let Some((fee_priority, transaction_id)) = after {
+            let fee_priority = fee_priority as i64;
+            query = query.filter(
+                transaction_pool::fee_priority
+                    .lt(fee_priority)
+                    .or(transaction_pool::fee_priority
+                        .eq(fee_priority)
+                        .and(transaction_pool::transaction_id.gt(serialize_hex(transaction_id)))),
+            );
+        }
+
+        let txs = query
+            .order_by((
+                transaction_pool::fee_priority.desc(),
+                transaction_pool::transaction_id.asc(),
+            ))
+            .limit(i64::try_from(limit).unwrap_or(i64::MAX))
+            .get_results::<sql_models::TransactionPoolRecord>(self.connection())
+            .map_err(|e| SqliteStorageError::DieselError {
+                operation: "transaction_pool_get_new_by_fee_priority",
                 source: e,
             })?;
 
-        if ready_txs.is_empty() {
+        if txs.is_empty() {
             return Ok(Vec::new());
         }
 
-        // Fetch all applicable block ids between the locked block and the given block
         let locked = self.locked_block_get()?;
         let leaf = self.leaf_block_get()?;
-
         let mut updates = self.get_transaction_atom_state_updates_between_blocks(
             &locked.block_id,
             &leaf.block_id,
-            ready_txs.iter().map(|s| s.transaction_id.as_str()),
+            txs.iter().map(|s| s.transaction_id.as_str()),
         )?;
 
-        debug!(
-            target: LOG_TARGET,
-            "transaction_pool_get: from_block_id={}, to_block_id={}, len(ready_txs)={}, updates={}",
-            locked.block_id,
-            leaf.block_id,
-            ready_txs.len(),
-            updates.len()
-        );
+        txs.into_iter()
+            .map(|rec| {
+                let maybe_update = updates.remove(&rec.transaction_id);
+                rec.try_convert(maybe_update)
+            })
+            .collect()
+    }
 
-        ready_txs
-            .into_iter()
-            .filter_map(|rec| {
+    fn transaction_pool_get_page(
+        &mut self,
+        after: Option<&TransactionId>,
+        limit: usize,
+    ) -> Result<Vec<TransactionPoolRecord>, StorageError> {
+        use crate::schema::transaction_pool;
+
+        let mut query = transaction_pool::table.into_boxed();
+        if let Some(after) = after {
+            query = query.filter(transaction_pool::transaction_id.gt(serialize_hex(after)));
+        }
+
+        let txs = query
+            .order_by(transaction_pool::transaction_id.asc())
+            .limit(i64::try_from(limit).unwrap_or(i64::MAX))
+            .get_results::<sql_models::TransactionPoolRecord>(self.connection())
+            .map_err(|e| SqliteStorageError::DieselError {
+                operation: "transaction_pool_get_page",
+                source: e,
+            })?;
+
+        if txs.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let locked = self.locked_block_get()?;
+        let leaf = self.leaf_block_get()?;
+        let mut updates = self.get_transaction_atom_state_updates_between_blocks(
+            &locked.block_id,
+            &leaf.block_id,
+            txs.iter().map(|s| s.transaction_id.as_str()),
+        )?;
+
+        txs.into_iter()
+            .map(|rec| {
                 let maybe_update = updates.remove(&rec.transaction_id);
-                match rec.try_convert(maybe_update) {
-                    Ok(rec) => {
-                        if rec.is_ready() || rec.stage().is_new() {
-                            Some(Ok(rec))
-                        } else {
-                            None
-                        }
-                    },
-                    Err(e) => Some(Err(e)),
-                }
+                rec.try_convert(maybe_update)
             })
-            .take(max_txs)
             .collect()
     }
 
@@ -1222,6 +1310,63 @@ impl<TAddr: NodeAddressable + Serialize + DeserializeOwned> StateStoreReadTransa
         substates.into_iter().map(TryInto::try_into).collect()
     }
 
+    fn substates_get_live_page_within_range(
+        &mut self,
+        start: &ShardId,
+        end: &ShardId,
+        after: Option<&ShardId>,
+        limit: usize,
+    ) -> Result<Vec<SubstateRecord>, StorageError> {
+        use crate::schema::substates;
+
+        let mut query = substates::table
+            .filter(substates::shard_id.between(serialize_hex(start), serialize_hex(end)))
+            .filter(substates::destroyed_by_transaction.is_null())
+            .into_boxed();
+        if let Some(after) = after {
+            query = query.filter(substates::shard_id.gt(serialize_hex(after)));
+        }
+
+        let substates = query
+            .order_by(substates::shard_id.asc())
+            .limit(i64::try_from(limit).unwrap_or(i64::MAX))
+            .get_results::<sql_models::SubstateRecord>(self.connection())
+            .map_err(|e| SqliteStorageError::DieselError {
+                operation: "substates_get_live_page_within_range",
+                source: e,
+            })?;
+
+        substates.into_iter().map(TryInto::try_into).collect()
+    }
+
+    fn substates_get_page_within_range(
+        &mut self,
+        start: &ShardId,
+        end: &ShardId,
+        after: Option<&ShardId>,
+        limit: usize,
+    ) -> Result<Vec<SubstateRecord>, StorageError> {
+        use crate::schema::substates;
+
+        let mut query = substates::table
+            .filter(substates::shard_id.between(serialize_hex(start), serialize_hex(end)))
+            .into_boxed();
+        if let Some(after) = after {
+            query = query.filter(substates::shard_id.gt(serialize_hex(after)));
+        }
+
+        let substates = query
+            .order_by(substates::shard_id.asc())
+            .limit(i64::try_from(limit).unwrap_or(i64::MAX))
+            .get_results::<sql_models::SubstateRecord>(self.connection())
+            .map_err(|e| SqliteStorageError::DieselError {
+                operation: "substates_get_page_within_range",
+                source: e,
+            })?;
+
+        substates.into_iter().map(TryInto::try_into).collect()
+    }
+
     fn substates_get_many_by_created_transaction(
         &mut self,
         tx_id: &TransactionId,
@@ -1256,6 +1401,55 @@ impl<TAddr: NodeAddressable + Serialize + DeserializeOwned> StateStoreReadTransa
         substates.into_iter().map(TryInto::try_into).collect()
     }
 
+    fn substates_get_all_versions(&mut self, address: &SubstateAddress) -> Result<Vec<SubstateRecord>, StorageError> {
+        use crate::schema::substates;
+
+        let substates = substates::table
+            .filter(substates::address.eq(address.to_string()))
+            .order_by(substates::version.asc())
+            .get_results::<sql_models::SubstateRecord>(self.connection())
+            .map_err(|e| SqliteStorageError::DieselError {
+                operation: "substates_get_all_versions",
+                source: e,
+            })?;
+
+        substates.into_iter().map(TryInto::try_into).collect()
+    }
+
+    fn substates_get_latest_version(&mut self, address: &SubstateAddress) -> Result<SubstateRecord, StorageError> {
+        use crate::schema::substates;
+
+        let substate = substates::table
+            .filter(substates::address.eq(address.to_string()))
+            .order_by(substates::version.desc())
+            .first::<sql_models::SubstateRecord>(self.connection())
+            .map_err(|e| SqliteStorageError::DieselError {
+                operation: "substates_get_latest_version",
+                source: e,
+            })?;
+
+        substate.try_into()
+    }
+
+    fn substates_get_prune_mark(&mut self) -> Result<Option<SubstatePruneMark>, StorageError> {
+        use crate::schema::substate_pruning;
+
+        let mark = substate_pruning::table
+            .select((substate_pruning::before_epoch, substate_pruning::destroyed_height))
+            .order_by(substate_pruning::id.desc())
+            .first::<(i64, i64)>(self.connection())
+            .optional()
+            .map_err(|e| SqliteStorageError::DieselError {
+                operation: "substates_get_prune_mark",
+                source: e,
+            })?;
+
+        Ok(mark.map(|(before_epoch, destroyed_height)| SubstatePruneMark {
+            before_epoch: Epoch(before_epoch as u64),
+            destroyed_height: NodeHeight(destroyed_height as u64),
+        }))
+    }
+
     fn substates_get_all_for_block(&mut self, block_id: &BlockId) -> Result<Vec<SubstateRecord>, StorageError> {
         use crate::schema::substates;
 
@@ -1379,6 +1573,20 @@ impl<TAddr: NodeAddressable + Serialize + DeserializeOwned> StateStoreReadTransa
             Ok(SubstateLockState::LockAcquired)
         }
     }
+
+    fn locked_outputs_get_all(&mut self) -> Result<Vec<LockedOutput>, StorageError> {
+        use crate::schema::locked_outputs;
+
+        let locked = locked_outputs::table
+            .order_by(locked_outputs::id.asc())
+            .get_results::<sql_models::LockedOutput>(self.connection())
+            .map_err(|e| SqliteStorageError::DieselError {
+                operation: "locked_outputs_get_all",
+                source: e,
+            })?;
+
+        locked.into_iter().map(TryInto::try_into).collect()
+    }
 }
 
 #[derive(QueryableByName)]
@@ -1388,7 +1596,7 @@ struct Count {
 }
 
 #[derive(QueryableByName)]
-struct BlockIdSqlValue {
+pub(crate) struct BlockIdSqlValue {
     #[diesel(sql_type = diesel::sql_types::Text)]
     pub bid: String,
 }
@@ -6,15 +6,18 @@ diesel::table! {
         block_id -> Text,
         parent_block_id -> Text,
         height -> BigInt,
+        view -> BigInt,
         epoch -> BigInt,
+        epoch_cutoff -> Nullable<BigInt>,
         proposed_by -> Text,
+        signature -> Nullable<Text>,
         qc_id -> Text,
+        timeout_certificate -> Nullable<Text>,
         command_count -> BigInt,
         commands -> Text,
         total_leader_fee -> BigInt,
         is_committed -> Bool,
         is_processed -> Bool,
-        is_dummy -> Bool,
         created_at -> Timestamp,
     }
 }
@@ -100,7 +103,7 @@ diesel::table! {
     missing_transactions (id) {
         id -> Integer,
         block_id -> Text,
-        block_height -> BigInt,
+        block_view -> BigInt,
         transaction_id -> Text,
         is_awaiting_execution -> Bool,
         created_at -> Timestamp,
@@ -113,9 +116,13 @@ diesel::table! {
         block_id -> Text,
         parent_block_id -> Text,
         height -> BigInt,
+        view -> BigInt,
         epoch -> BigInt,
+        epoch_cutoff -> Nullable<BigInt>,
         proposed_by -> Text,
+        signature -> Nullable<Text>,
         justify -> Text,
+        timeout_certificate -> Nullable<Text>,
         command_count -> BigInt,
         commands -> Text,
         total_leader_fee -> BigInt,
@@ -133,6 +140,15 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    substate_pruning (id) {
+        id -> Integer,
+        before_epoch -> BigInt,
+        destroyed_height -> BigInt,
+        created_at -> Timestamp,
+    }
+}
+
 diesel::table! {
     substates (id) {
         id -> Integer,
@@ -174,6 +190,9 @@ diesel::table! {
         is_ready -> Bool,
         updated_at -> Timestamp,
         created_at -> Timestamp,
+        signer -> Text,
+        transaction_size -> BigInt,
+        fee_priority -> BigInt,
     }
 }
 
@@ -259,6 +278,7 @@ diesel::allow_tables_to_appear_in_same_query!(
     missing_transactions,
     parked_blocks,
     quorum_certificates,
+    substate_pruning,
     substates,
     transaction_pool,
     transaction_pool_history,
@@ -3,7 +3,7 @@
 
 use diesel::{Queryable, QueryableByName};
 use serde::Serialize;
-use tari_dan_common_types::{Epoch, NodeAddressable, NodeHeight};
+use tari_dan_common_types::{Epoch, NodeAddressable, NodeHeight, View};
 use tari_dan_storage::{consensus_models, StorageError};
 use time::PrimitiveDateTime;
 
@@ -19,15 +19,18 @@ pub struct Block {
     pub block_id: String,
     pub parent_block_id: String,
     pub height: i64,
+    pub view: i64,
     pub epoch: i64,
+    pub epoch_cutoff: Option<i64>,
     pub proposed_by: String,
+    pub signature: Option<String>,
     pub qc_id: String,
+    pub timeout_certificate: Option<String>,
     pub command_count: i64,
     pub commands: String,
     pub total_leader_fee: i64,
     pub is_committed: bool,
     pub is_processed: bool,
-    pub is_dummy: bool,
     pub created_at: PrimitiveDateTime,
 }
 
@@ -40,16 +43,19 @@ impl Block {
             deserialize_hex_try_from(&self.block_id)?,
             deserialize_hex_try_from(&self.parent_block_id)?,
             qc.try_into()?,
+            self.timeout_certificate.as_deref().map(deserialize_json).transpose()?,
             NodeHeight(self.height as u64),
+            View(self.view as u64),
             Epoch(self.epoch as u64),
+            self.epoch_cutoff.map(|h| NodeHeight(h as u64)),
             TAddr::from_bytes(&deserialize_hex(&self.proposed_by)?).ok_or_else(|| StorageError::DecodingError {
                 operation: "try_convert",
                 item: "block",
                 details: format!("Block #{} proposed_by is malformed", self.id),
             })?,
+            self.signature.as_deref().map(deserialize_json).transpose()?,
             deserialize_json(&self.commands)?,
             self.total_leader_fee as u64,
-            self.is_dummy,
             self.is_processed,
             self.is_committed,
             self.created_at,
@@ -63,9 +69,13 @@ pub struct ParkedBlock {
     pub block_id: String,
     pub parent_block_id: String,
     pub height: i64,
+    pub view: i64,
     pub epoch: i64,
+    pub epoch_cutoff: Option<i64>,
     pub proposed_by: String,
+    pub signature: Option<String>,
     pub justify: String,
+    pub timeout_certificate: Option<String>,
     pub command_count: i64,
     pub commands: String,
     pub total_leader_fee: i64,
@@ -80,18 +90,21 @@ impl<TAddr: NodeAddressable> TryFrom<ParkedBlock> for consensus_models::Block<TA
             deserialize_hex_try_from(&value.block_id)?,
             deserialize_hex_try_from(&value.parent_block_id)?,
             deserialize_json(&value.justify)?,
+            value.timeout_certificate.as_deref().map(deserialize_json).transpose()?,
             NodeHeight(value.height as u64),
+            View(value.view as u64),
             Epoch(value.epoch as u64),
+            value.epoch_cutoff.map(|h| NodeHeight(h as u64)),
             TAddr::from_bytes(&deserialize_hex(&value.proposed_by)?).ok_or_else(|| StorageError::DecodingError {
                 operation: "try_convert",
                 item: "block",
                 details: format!("Block #{} proposed_by is malformed", value.id),
             })?,
+            value.signature.as_deref().map(deserialize_json).transpose()?,
             deserialize_json(&value.commands)?,
             value.total_leader_fee as u64,
             false,
             false,
-            false,
             value.created_at,
         ))
     }
@@ -2,14 +2,16 @@
 //    SPDX-License-Identifier: BSD-3-Clause
 
 use diesel::{Queryable, QueryableByName};
+use tari_common_types::types::PublicKey;
+use tari_dan_common_types::NodeAddressable;
 use tari_dan_storage::{
     consensus_models,
-    consensus_models::{Evidence, TransactionAtom},
+    consensus_models::{Evidence, TransactionAtom, TransactionFeeInfo},
     StorageError,
 };
 use time::PrimitiveDateTime;
 
-use crate::serialization::{deserialize_hex_try_from, deserialize_json, parse_from_string};
+use crate::serialization::{deserialize_hex, deserialize_hex_try_from, deserialize_json, parse_from_string};
 
 #[derive(Debug, Clone, Queryable)]
 pub struct TransactionPoolRecord {
@@ -30,6 +32,10 @@ pub struct TransactionPoolRecord {
     pub is_ready: bool,
     pub updated_at: PrimitiveDateTime,
     pub created_at: PrimitiveDateTime,
+    pub signer: String,
+    pub transaction_size: i64,
+    // Only used to order new transactions. It is calculated from the fee and size when the record is loaded.
+    pub fee_priority: i64,
 }
 
 impl TransactionPoolRecord {
@@ -57,6 +63,16 @@ impl TransactionPoolRecord {
                 transaction_fee: self.transaction_fee as u64,
                 leader_fee: self.leader_fee as u64,
             },
+            TransactionFeeInfo {
+                signer: PublicKey::from_bytes(&deserialize_hex(&self.signer)?).ok_or_else(|| {
+                    StorageError::DecodingError {
+                        operation: "try_convert",
+                        item: "transaction pool record",
+                        details: format!("Transaction pool record #{} signer is malformed", self.id),
+                    }
+                })?,
+                size: self.transaction_size as usize,
+            },
             parse_from_string(&self.stage)?,
             pending_stage,
             self.local_decision.as_deref().map(parse_from_string).transpose()?,
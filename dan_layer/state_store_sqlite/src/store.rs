@@ -4,11 +4,12 @@
 use std::{
     fmt,
     marker::PhantomData,
+    path::Path,
     sync::{Arc, Mutex},
     time::Duration,
 };
 
-use diesel::{sql_query, Connection, RunQueryDsl, SqliteConnection};
+use diesel::{sql_query, sql_types::Text, Connection, RunQueryDsl, SqliteConnection};
 use diesel_migrations::{embed_migrations, EmbeddedMigrations, MigrationHarness};
 use log::log;
 use serde::{de::DeserializeOwned, Serialize};
@@ -26,6 +27,7 @@ use crate::{
 const LOG_TARGET: &str = "tari::dan::storage::sqlite::state_store";
 
 pub struct SqliteStateStore<TAddr> {
+    url: String,
     connection: Arc<Mutex<SqliteConnection>>,
     _addr: PhantomData<TAddr>,
 }
@@ -46,12 +48,47 @@ impl<TAddr> SqliteStateStore<TAddr> {
                 operation: "set pragma",
             })?;
 
+        if !is_in_memory_url(url) {
+            // Allows other connections (e.g. backups) to read without blocking consensus writes
+            sql_query("PRAGMA journal_mode = WAL;")
+                .execute(&mut connection)
+                .map_err(|source| SqliteStorageError::DieselError {
+                    source,
+                    operation: "set pragma",
+                })?;
+        }
+
         Ok(Self {
+            url: url.to_string(),
             connection: Arc::new(Mutex::new(connection)),
             _addr: PhantomData,
         })
     }
 
+    /// Writes a copy of the database to `path`, which must not exist. The copy is taken from a single read
+    /// transaction on a separate connection, so it is consistent and does not block consensus while the store is
+    /// being written to.
+    pub fn backup_to<P: AsRef<Path>>(&self, path: P) -> Result<(), StorageError> {
+        let path = path.as_ref();
+        let path = path.to_str().ok_or_else(|| StorageError::General {
+            details: format!("Backup path {} is not valid UTF-8", path.display()),
+        })?;
+        if is_in_memory_url(&self.url) {
+            return Err(StorageError::General {
+                details: "Cannot back up an in-memory database".to_string(),
+            });
+        }
+        let mut connection = SqliteConnection::establish(&self.url).map_err(SqliteStorageError::from)?;
+        sql_query("VACUUM INTO ?")
+            .bind::<Text, _>(path)
+            .execute(&mut connection)
+            .map_err(|source| SqliteStorageError::DieselError {
+                source,
+                operation: "backup_to",
+            })?;
+        Ok(())
+    }
+
     pub fn foreign_keys_off(&self) -> Result<(), StorageError> {
         sql_query("PRAGMA foreign_keys = OFF;")
             .execute(&mut *self.connection.lock().unwrap())
@@ -102,8 +139,13 @@ impl<TAddr: NodeAddressable + Serialize + DeserializeOwned> StateStore for Sqlit
 impl<TAddr> Clone for SqliteStateStore<TAddr> {
     fn clone(&self) -> Self {
         Self {
+            url: self.url.clone(),
             connection: self.connection.clone(),
             _addr: PhantomData,
         }
     }
 }
+
+fn is_in_memory_url(url: &str) -> bool {
+    url.contains(":memory:") || url.contains("mode=memory")
+}
@@ -5,11 +5,22 @@ use std::{
     borrow::Borrow,
     collections::HashSet,
     ops::{Deref, DerefMut},
+    time::Duration,
 };
 
-use diesel::{AsChangeset, ExpressionMethods, OptionalExtension, QueryDsl, RunQueryDsl, SqliteConnection};
+use diesel::{
+    sql_query,
+    sql_types::{BigInt, Text},
+    AsChangeset,
+    ExpressionMethods,
+    NullableExpressionMethods,
+    OptionalExtension,
+    QueryDsl,
+    RunQueryDsl,
+    SqliteConnection,
+};
 use log::*;
-use tari_dan_common_types::{optional::Optional, Epoch, NodeAddressable, NodeHeight, ShardId};
+use tari_dan_common_types::{optional::Optional, Epoch, NodeAddressable, NodeHeight, ShardId, View};
 use tari_dan_storage::{
     consensus_models::{
         Block,
@@ -28,8 +39,10 @@ use tari_dan_storage::{
         QuorumCertificate,
         SubstateLockFlag,
         SubstateLockState,
+        SubstatePruneMark,
         SubstateRecord,
         TransactionAtom,
+        TransactionFeeInfo,
         TransactionPoolStage,
         TransactionPoolStatusUpdate,
         TransactionRecord,
@@ -44,7 +57,7 @@ use time::PrimitiveDateTime;
 
 use crate::{
     error::SqliteStorageError,
-    reader::SqliteStateStoreReadTransaction,
+    reader::{BlockIdSqlValue, SqliteStateStoreReadTransaction},
     serialization::{serialize_hex, serialize_json},
     sql_models,
     sqlite_transaction::SqliteTransaction,
@@ -136,12 +149,16 @@ impl<'a, TAddr: NodeAddressable> SqliteStateStoreWriteTransaction<'a, TAddr> {
             parked_blocks::block_id.eq(&block_id),
             parked_blocks::parent_block_id.eq(serialize_hex(block.parent())),
             parked_blocks::height.eq(block.height().as_u64() as i64),
+            parked_blocks::view.eq(block.view().as_u64() as i64),
             parked_blocks::epoch.eq(block.epoch().as_u64() as i64),
+            parked_blocks::epoch_cutoff.eq(block.epoch_cutoff().map(|h| h.as_u64() as i64)),
             parked_blocks::proposed_by.eq(serialize_hex(block.proposed_by().as_bytes())),
+            parked_blocks::signature.eq(block.signature().map(serialize_json).transpose()?),
             parked_blocks::command_count.eq(block.commands().len() as i64),
             parked_blocks::commands.eq(serialize_json(block.commands())?),
             parked_blocks::total_leader_fee.eq(block.total_leader_fee() as i64),
             parked_blocks::justify.eq(serialize_json(block.justify())?),
+            parked_blocks::timeout_certificate.eq(block.timeout_certificate().map(serialize_json).transpose()?),
         );
 
         diesel::insert_into(parked_blocks::table)
@@ -178,13 +195,16 @@ impl<TAddr: NodeAddressable> StateStoreWriteTransaction for SqliteStateStoreWrit
             blocks::block_id.eq(serialize_hex(block.id())),
             blocks::parent_block_id.eq(serialize_hex(block.parent())),
             blocks::height.eq(block.height().as_u64() as i64),
+            blocks::view.eq(block.view().as_u64() as i64),
             blocks::epoch.eq(block.epoch().as_u64() as i64),
+            blocks::epoch_cutoff.eq(block.epoch_cutoff().map(|h| h.as_u64() as i64)),
             blocks::proposed_by.eq(serialize_hex(block.proposed_by().as_bytes())),
+            blocks::signature.eq(block.signature().map(serialize_json).transpose()?),
             blocks::command_count.eq(block.commands().len() as i64),
             blocks::commands.eq(serialize_json(block.commands())?),
             blocks::total_leader_fee.eq(block.total_leader_fee() as i64),
             blocks::qc_id.eq(serialize_hex(block.justify().id())),
-            blocks::is_dummy.eq(block.is_dummy()),
+            blocks::timeout_certificate.eq(block.timeout_certificate().map(serialize_json).transpose()?),
             blocks::is_processed.eq(block.is_processed()),
         );
 
@@ -259,7 +279,7 @@ impl<TAddr: NodeAddressable> StateStoreWriteTransaction for SqliteStateStoreWrit
             .map(|tx_id| {
                 (
                     missing_transactions::block_id.eq(&block_id_hex),
-                    missing_transactions::block_height.eq(block.height().as_u64() as i64),
+                    missing_transactions::block_view.eq(block.view().as_u64() as i64),
                     missing_transactions::transaction_id.eq(tx_id),
                     missing_transactions::is_awaiting_execution.eq(false),
                 )
@@ -267,7 +287,7 @@ impl<TAddr: NodeAddressable> StateStoreWriteTransaction for SqliteStateStoreWrit
             .chain(awaiting_transaction_ids.iter().map(|tx_id| {
                 (
                     missing_transactions::block_id.eq(&block_id_hex),
-                    missing_transactions::block_height.eq(block.height().as_u64() as i64),
+                    missing_transactions::block_view.eq(block.view().as_u64() as i64),
                     missing_transactions::transaction_id.eq(tx_id),
                     missing_transactions::is_awaiting_execution.eq(true),
                 )
@@ -287,14 +307,14 @@ impl<TAddr: NodeAddressable> StateStoreWriteTransaction for SqliteStateStoreWrit
 
     fn missing_transactions_remove(
         &mut self,
-        current_height: NodeHeight,
+        current_view: View,
         transaction_id: &TransactionId,
     ) -> Result<Option<Block<TAddr>>, StorageError> {
         use crate::schema::{missing_transactions, transactions};
 
-        // delete all entries that are for previous heights
+        // delete all entries that are for previous views
         diesel::delete(missing_transactions::table)
-            .filter(missing_transactions::block_height.lt(current_height.as_u64().saturating_sub(1) as i64))
+            .filter(missing_transactions::block_view.lt(current_view.as_u64().saturating_sub(1) as i64))
             .execute(self.connection())
             .map_err(|e| SqliteStorageError::DieselError {
                 operation: "missing_transactions_remove",
@@ -305,7 +325,7 @@ impl<TAddr: NodeAddressable> StateStoreWriteTransaction for SqliteStateStoreWrit
         let block_id = missing_transactions::table
             .select(missing_transactions::block_id)
             .filter(missing_transactions::transaction_id.eq(&transaction_id))
-            .filter(missing_transactions::block_height.eq(current_height.as_u64() as i64))
+            .filter(missing_transactions::block_view.eq(current_view.as_u64() as i64))
             .first::<String>(self.connection())
             .optional()
             .map_err(|e| SqliteStorageError::DieselError {
@@ -674,11 +694,13 @@ impl<TAddr: NodeAddressable> StateStoreWriteTransaction for SqliteStateStoreWrit
     fn transaction_pool_insert(
         &mut self,
         transaction: TransactionAtom,
+        fee_info: &TransactionFeeInfo,
         stage: TransactionPoolStage,
         is_ready: bool,
     ) -> Result<(), StorageError> {
         use crate::schema::transaction_pool;
 
+        let fee_priority = fee_info.fee_priority(transaction.transaction_fee);
         let insert = (
             transaction_pool::transaction_id.eq(serialize_hex(transaction.id)),
             transaction_pool::original_decision.eq(transaction.decision.to_string()),
@@ -687,6 +709,9 @@ impl<TAddr: NodeAddressable> StateStoreWriteTransaction for SqliteStateStoreWrit
             transaction_pool::evidence.eq(serialize_json(&transaction.evidence)?),
             transaction_pool::stage.eq(stage.to_string()),
             transaction_pool::is_ready.eq(is_ready),
+            transaction_pool::signer.eq(serialize_hex(NodeAddressable::as_bytes(&fee_info.signer))),
+            transaction_pool::transaction_size.eq(i64::try_from(fee_info.size).unwrap_or(i64::MAX)),
+            transaction_pool::fee_priority.eq(fee_priority as i64),
         );
 
         diesel::insert_into(transaction_pool::table)
@@ -1187,6 +1212,21 @@ impl<TAddr: NodeAddressable> StateStoreWriteTransaction for SqliteStateStoreWrit
         Ok(())
     }
 
+    fn substates_delete_many<I: IntoIterator<Item = ShardId>>(&mut self, shard_ids: I) -> Result<usize, StorageError> {
+        use crate::schema::substates;
+
+        let shard_ids = shard_ids.into_iter().map(serialize_hex).collect::<Vec<_>>();
+        let num_deleted = diesel::delete(substates::table)
+            .filter(substates::shard_id.eq_any(&shard_ids))
+            .execute(self.connection())
+            .map_err(|e| SqliteStorageError::DieselError {
+                operation: "substates_delete_many",
+                source: e,
+            })?;
+
+        Ok(num_deleted)
+    }
+
     fn locked_outputs_acquire_all<I, B>(
         &mut self,
         block_id: &BlockId,
@@ -1273,6 +1313,206 @@ impl<TAddr: NodeAddressable> StateStoreWriteTransaction for SqliteStateStoreWrit
         // locked.into_iter().map(TryInto::try_into).collect()
         Ok(vec![])
     }
+
+    fn votes_prune_up_to_height(&mut self, height: NodeHeight, limit: usize) -> Result<usize, StorageError> {
+        let num_deleted = sql_query(
+            r#"
+            DELETE FROM votes
+            WHERE id IN (
+                SELECT votes.id FROM votes JOIN blocks ON blocks.block_id = votes.block_id
+                WHERE blocks.height <= ?
+                LIMIT ?
+            )"#,
+        )
+        .bind::<BigInt, _>(height.as_u64() as i64)
+        .bind::<BigInt, _>(limit as i64)
+        .execute(self.connection())
+        .map_err(|e| SqliteStorageError::DieselError {
+            operation: "votes_prune_up_to_height",
+            source: e,
+        })?;
+
+        Ok(num_deleted)
+    }
+
+    fn blocks_prune_uncommitted_up_to_height(
+        &mut self,
+        height: NodeHeight,
+        limit: usize,
+    ) -> Result<usize, StorageError> {
+        use crate::schema::{
+            blocks,
+            high_qcs,
+            last_executed,
+            last_sent_vote,
+            leaf_blocks,
+            locked_block,
+            quorum_certificates,
+            transaction_pool_state_updates,
+        };
+
+        // The latest pointer in each table is always kept, and any block it still references is not pruned
+        let block_ids = sql_query(
+            r#"
+            SELECT block_id AS bid FROM blocks
+            WHERE is_committed = 0 AND height <= ?
+                AND block_id NOT IN (
+                    SELECT block_id FROM leaf_blocks WHERE id = (SELECT MAX(id) FROM leaf_blocks)
+                    UNION SELECT block_id FROM locked_block WHERE id = (SELECT MAX(id) FROM locked_block)
+                    UNION SELECT block_id FROM last_executed WHERE id = (SELECT MAX(id) FROM last_executed)
+                    UNION SELECT block_id FROM last_sent_vote WHERE id = (SELECT MAX(id) FROM last_sent_vote)
+                    UNION SELECT block_id FROM high_qcs WHERE id = (SELECT MAX(id) FROM high_qcs)
+                    UNION SELECT block_id FROM locked_outputs
+                )
+            ORDER BY height
+            LIMIT ?"#,
+        )
+        .bind::<BigInt, _>(height.as_u64() as i64)
+        .bind::<BigInt, _>(limit as i64)
+        .get_results::<BlockIdSqlValue>(self.connection())
+        .map_err(|e| SqliteStorageError::DieselError {
+            operation: "blocks_prune_uncommitted_up_to_height",
+            source: e,
+        })?;
+
+        if block_ids.is_empty() {
+            return Ok(0);
+        }
+        let block_ids = block_ids.into_iter().map(|b| b.bid).collect::<Vec<_>>();
+
+        macro_rules! delete_for_blocks {
+            ($table:ident) => {
+                diesel::delete($table::table)
+                    .filter($table::block_id.eq_any(&block_ids))
+                    .execute(self.connection())
+                    .map_err(|e| SqliteStorageError::DieselError {
+                        operation: "blocks_prune_uncommitted_up_to_height",
+                        source: e,
+                    })?;
+            };
+        }
+        // None of the blocks are referenced by the latest pointer, so every pointer to them is historical
+        delete_for_blocks!(leaf_blocks);
+        delete_for_blocks!(locked_block);
+        delete_for_blocks!(last_executed);
+        delete_for_blocks!(last_sent_vote);
+        delete_for_blocks!(high_qcs);
+        delete_for_blocks!(transaction_pool_state_updates);
+
+        let num_deleted = diesel::delete(blocks::table)
+            .filter(blocks::block_id.eq_any(&block_ids))
+            .execute(self.connection())
+            .map_err(|e| SqliteStorageError::DieselError {
+                operation: "blocks_prune_uncommitted_up_to_height",
+                source: e,
+            })?;
+
+        // Only blocks above the pruned height can still use a QC for a pruned block as their justify
+        diesel::delete(quorum_certificates::table)
+            .filter(quorum_certificates::block_id.eq_any(&block_ids))
+            .filter(
+                quorum_certificates::qc_id.ne_all(
+                    blocks::table
+                        .select(blocks::qc_id)
+                        .filter(blocks::height.gt(height.as_u64() as i64)),
+                ),
+            )
+            .filter(quorum_certificates::qc_id.ne_all(high_qcs::table.select(high_qcs::qc_id)))
+            .execute(self.connection())
+            .map_err(|e| SqliteStorageError::DieselError {
+                operation: "blocks_prune_uncommitted_up_to_height",
+                source: e,
+            })?;
+
+        Ok(num_deleted)
+    }
+
+    fn substates_prune_destroyed_before_epoch(&mut self, epoch: Epoch, limit: usize) -> Result<usize, StorageError> {
+        use crate::schema::{blocks, substate_pruning, substates};
+
+        let ids = substates::table
+            .select(substates::id)
+            .filter(substates::destroyed_at_epoch.lt(epoch.as_u64() as i64))
+            .limit(limit as i64)
+            .get_results::<i32>(self.connection())
+            .map_err(|e| SqliteStorageError::DieselError {
+                operation: "substates_prune_destroyed_before_epoch",
+                source: e,
+            })?;
+
+        if ids.is_empty() {
+            return Ok(0);
+        }
+
+        let destroyed_height = blocks::table
+            .select(diesel::dsl::max(blocks::height))
+            .filter(
+                blocks::block_id.nullable().eq_any(
+                    substates::table
+                        .select(substates::destroyed_by_block)
+                        .filter(substates::id.eq_any(&ids)),
+                ),
+            )
+            .first::<Option<i64>>(self.connection())
+            .map_err(|e| SqliteStorageError::DieselError {
+                operation: "substates_prune_destroyed_before_epoch",
+                source: e,
+            })?;
+
+        let num_deleted = diesel::delete(substates::table)
+            .filter(substates::id.eq_any(&ids))
+            .execute(self.connection())
+            .map_err(|e| SqliteStorageError::DieselError {
+                operation: "substates_prune_destroyed_before_epoch",
+                source: e,
+            })?;
+
+        let mark = self.substates_get_prune_mark()?;
+        let new_mark = SubstatePruneMark {
+            before_epoch: mark.map_or(epoch, |m| m.before_epoch.max(epoch)),
+            destroyed_height: mark
+                .map_or(NodeHeight(0), |m| m.destroyed_height)
+                .max(NodeHeight(destroyed_height.unwrap_or(0) as u64)),
+        };
+        // Each batch advances the same mark, so only record it when it changes
+        if mark != Some(new_mark) {
+            diesel::insert_into(substate_pruning::table)
+                .values((
+                    substate_pruning::before_epoch.eq(new_mark.before_epoch.as_u64() as i64),
+                    substate_pruning::destroyed_height.eq(new_mark.destroyed_height.as_u64() as i64),
+                ))
+                .execute(self.connection())
+                .map_err(|e| SqliteStorageError::DieselError {
+                    operation: "substates_prune_destroyed_before_epoch",
+                    source: e,
+                })?;
+        }
+
+        Ok(num_deleted)
+    }
+
+    fn transaction_pool_history_prune(&mut self, retention: Duration, limit: usize) -> Result<usize, StorageError> {
+        // change_time is stored in the same format, so the cutoff can be compared as text
+        let num_deleted = sql_query(
+            r#"
+            DELETE FROM transaction_pool_history
+            WHERE history_id IN (
+                SELECT history_id FROM transaction_pool_history
+                WHERE change_time < STRFTIME('%Y-%m-%d %H:%M:%f', 'NOW', ?)
+                    AND transaction_id NOT IN (SELECT transaction_id FROM transaction_pool)
+                LIMIT ?
+            )"#,
+        )
+        .bind::<Text, _>(format!("-{} seconds", retention.as_secs()))
+        .bind::<BigInt, _>(limit as i64)
+        .execute(self.connection())
+        .map_err(|e| SqliteStorageError::DieselError {
+            operation: "transaction_pool_history_prune",
+            source: e,
+        })?;
+
+        Ok(num_deleted)
+    }
 }
 
 impl<'a, TAddr> Deref for SqliteStateStoreWriteTransaction<'a, TAddr> {
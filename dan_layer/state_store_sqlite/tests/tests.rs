@@ -2,9 +2,17 @@
 //   SPDX-License-Identifier: BSD-3-Clause
 
 use rand::{rngs::OsRng, RngCore};
-use tari_dan_common_types::{Epoch, NodeHeight};
+use tari_dan_common_types::{Epoch, NodeHeight, View};
 use tari_dan_storage::{
-    consensus_models::{Block, Command, Decision, TransactionAtom, TransactionPoolStage, TransactionPoolStatusUpdate},
+    consensus_models::{
+        Block,
+        Command,
+        Decision,
+        TransactionAtom,
+        TransactionFeeInfo,
+        TransactionPoolStage,
+        TransactionPoolStatusUpdate,
+    },
     StateStore,
     StateStoreReadTransaction,
     StateStoreWriteTransaction,
@@ -28,6 +36,13 @@ fn create_tx_atom() -> TransactionAtom {
     }
 }
 
+fn create_fee_info() -> TransactionFeeInfo {
+    TransactionFeeInfo {
+        signer: Default::default(),
+        size: 100,
+    }
+}
+
 mod confirm_all_transitions {
 
     use super::*;
@@ -48,8 +63,11 @@ mod confirm_all_transitions {
         let block1 = Block::new(
             *zero_block.id(),
             zero_block.justify().clone(),
+            None,
             NodeHeight(1),
+            View(1),
             Epoch(0),
+            None,
             Default::default(),
             // Need to have a command in, otherwise this block will not be included internally in the query because it
             // cannot cause a state change without any commands
@@ -58,11 +76,11 @@ mod confirm_all_transitions {
         );
         block1.insert(&mut tx).unwrap();
 
-        tx.transaction_pool_insert(atom1.clone(), TransactionPoolStage::New, false)
+        tx.transaction_pool_insert(atom1.clone(), &create_fee_info(), TransactionPoolStage::New, false)
             .unwrap();
-        tx.transaction_pool_insert(atom2.clone(), TransactionPoolStage::New, false)
+        tx.transaction_pool_insert(atom2.clone(), &create_fee_info(), TransactionPoolStage::New, false)
             .unwrap();
-        tx.transaction_pool_insert(atom3.clone(), TransactionPoolStage::New, false)
+        tx.transaction_pool_insert(atom3.clone(), &create_fee_info(), TransactionPoolStage::New, false)
             .unwrap();
         let block_id = *block1.id();
 
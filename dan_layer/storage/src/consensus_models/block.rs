@@ -10,7 +10,16 @@ use std::{
 use log::*;
 use serde::{Deserialize, Serialize};
 use tari_common_types::types::{FixedHash, FixedHashSizeError};
-use tari_dan_common_types::{hashing, optional::Optional, serde_with, Epoch, NodeAddressable, NodeHeight, ShardId};
+use tari_dan_common_types::{
+    hashing,
+    optional::Optional,
+    serde_with,
+    Epoch,
+    NodeAddressable,
+    NodeHeight,
+    ShardId,
+    View,
+};
 use tari_transaction::TransactionId;
 use time::PrimitiveDateTime;
 
@@ -26,7 +35,9 @@ use crate::{
         LockedBlock,
         SubstateCreatedProof,
         SubstateUpdate,
+        TimeoutCertificate,
         TransactionRecord,
+        ValidatorSignature,
         Vote,
     },
     Ordering,
@@ -43,18 +54,25 @@ pub struct Block<TAddr> {
     id: BlockId,
     parent: BlockId,
     justify: QuorumCertificate<TAddr>,
+    /// Present if this block was proposed after a leader failure, i.e. the justify block is not from the previous view
+    timeout_certificate: Option<TimeoutCertificate<TAddr>>,
     height: NodeHeight,
+    /// The view in which this block was proposed. Views advance on every proposal and leader timeout, so unlike
+    /// height, consecutive blocks in a chain may have a gap in their views.
+    view: View,
     epoch: Epoch,
+    /// The height from which this epoch no longer prepares new transactions. Once set by a block, every descendant in
+    /// the same epoch carries the same value.
+    epoch_cutoff: Option<NodeHeight>,
     proposed_by: TAddr,
+    /// The proposer's signature of the block ID. This is not part of the block hash.
+    signature: Option<ValidatorSignature<TAddr>>,
     total_leader_fee: u64,
 
     // Body
     merkle_root: FixedHash,
     // BTreeSet is used for the deterministic block hash, that is, transactions are always ordered by TransactionId.
     commands: BTreeSet<Command>,
-    /// If the block is a dummy block. This is metadata and not sent over
-    /// the wire or part of the block hash.
-    is_dummy: bool,
     /// Flag that indicates that the block locked objects and made transaction stage transitions.
     is_processed: bool,
     /// Flag that indicates that the block has been committed.
@@ -67,8 +85,11 @@ impl<TAddr: NodeAddressable + Serialize> Block<TAddr> {
     pub fn new(
         parent: BlockId,
         justify: QuorumCertificate<TAddr>,
+        timeout_certificate: Option<TimeoutCertificate<TAddr>>,
         height: NodeHeight,
+        view: View,
         epoch: Epoch,
+        epoch_cutoff: Option<NodeHeight>,
         proposed_by: TAddr,
         commands: BTreeSet<Command>,
         total_leader_fee: u64,
@@ -77,14 +98,17 @@ impl<TAddr: NodeAddressable + Serialize> Block<TAddr> {
             id: BlockId::genesis(),
             parent,
             justify,
+            timeout_certificate,
             height,
+            view,
             epoch,
+            epoch_cutoff,
             proposed_by,
+            signature: None,
             // TODO
             merkle_root: FixedHash::zero(),
             commands,
             total_leader_fee,
-            is_dummy: false,
             is_processed: false,
             is_committed: false,
             stored_at: None,
@@ -97,12 +121,15 @@ impl<TAddr: NodeAddressable + Serialize> Block<TAddr> {
         id: BlockId,
         parent: BlockId,
         justify: QuorumCertificate<TAddr>,
+        timeout_certificate: Option<TimeoutCertificate<TAddr>>,
         height: NodeHeight,
+        view: View,
         epoch: Epoch,
+        epoch_cutoff: Option<NodeHeight>,
         proposed_by: TAddr,
+        signature: Option<ValidatorSignature<TAddr>>,
         commands: BTreeSet<Command>,
         total_leader_fee: u64,
-        is_dummy: bool,
         is_processed: bool,
         is_committed: bool,
         created_at: PrimitiveDateTime,
@@ -111,14 +138,17 @@ impl<TAddr: NodeAddressable + Serialize> Block<TAddr> {
             id,
             parent,
             justify,
+            timeout_certificate,
             height,
+            view,
             epoch,
+            epoch_cutoff,
             proposed_by,
+            signature,
             // TODO
             merkle_root: FixedHash::zero(),
             commands,
             total_leader_fee,
-            is_dummy,
             is_processed,
             is_committed,
             stored_at: Some(created_at),
@@ -129,8 +159,11 @@ impl<TAddr: NodeAddressable + Serialize> Block<TAddr> {
         Self::new(
             BlockId::genesis(),
             QuorumCertificate::genesis(),
+            None,
             NodeHeight(0),
+            View(0),
             Epoch(0),
+            None,
             TAddr::zero(),
             Default::default(),
             0,
@@ -143,38 +176,31 @@ impl<TAddr: NodeAddressable + Serialize> Block<TAddr> {
             id: BlockId::genesis(),
             parent: BlockId::genesis(),
             justify: QuorumCertificate::genesis(),
+            timeout_certificate: None,
             height: NodeHeight(0),
+            view: View(0),
             epoch: Epoch(0),
+            epoch_cutoff: None,
             proposed_by: TAddr::zero(),
+            signature: None,
             merkle_root: FixedHash::zero(),
             commands: Default::default(),
             total_leader_fee: 0,
-            is_dummy: false,
             is_processed: false,
             is_committed: true,
             stored_at: None,
         }
     }
 
-    pub fn dummy_block(
-        parent: BlockId,
-        proposed_by: TAddr,
-        node_height: NodeHeight,
-        high_qc: QuorumCertificate<TAddr>,
-        epoch: Epoch,
-    ) -> Self {
-        let mut block = Self::new(parent, high_qc, node_height, epoch, proposed_by, Default::default(), 0);
-        block.is_dummy = true;
-        block.is_processed = false;
-        block
-    }
-
     pub fn calculate_hash(&self) -> FixedHash {
         hashing::block_hasher()
             .chain(&self.parent)
             .chain(&self.justify)
+            .chain(&self.timeout_certificate)
             .chain(&self.height)
+            .chain(&self.view)
             .chain(&self.epoch)
+            .chain(&self.epoch_cutoff)
             .chain(&self.proposed_by)
             .chain(&self.merkle_root)
             .chain(&self.commands)
@@ -242,14 +268,26 @@ impl<TAddr> Block<TAddr> {
         &self.justify
     }
 
+    pub fn timeout_certificate(&self) -> Option<&TimeoutCertificate<TAddr>> {
+        self.timeout_certificate.as_ref()
+    }
+
     pub fn height(&self) -> NodeHeight {
         self.height
     }
 
+    pub fn view(&self) -> View {
+        self.view
+    }
+
     pub fn epoch(&self) -> Epoch {
         self.epoch
     }
 
+    pub fn epoch_cutoff(&self) -> Option<NodeHeight> {
+        self.epoch_cutoff
+    }
+
     pub fn total_leader_fee(&self) -> u64 {
         self.total_leader_fee
     }
@@ -258,6 +296,15 @@ impl<TAddr> Block<TAddr> {
         &self.proposed_by
     }
 
+    pub fn signature(&self) -> Option<&ValidatorSignature<TAddr>> {
+        self.signature.as_ref()
+    }
+
+    pub fn set_signature(&mut self, signature: ValidatorSignature<TAddr>) -> &mut Self {
+        self.signature = Some(signature);
+        self
+    }
+
     pub fn merkle_root(&self) -> &FixedHash {
         &self.merkle_root
     }
@@ -270,10 +317,6 @@ impl<TAddr> Block<TAddr> {
         self.commands
     }
 
-    pub fn is_dummy(&self) -> bool {
-        self.is_dummy
-    }
-
     pub fn is_processed(&self) -> bool {
         self.is_processed
     }
@@ -555,14 +598,23 @@ impl<TAddr: NodeAddressable> Block<TAddr> {
         };
 
         let locked_block = LockedBlock::get(tx.deref_mut())?;
-        if precommit_node.height() > locked_block.height {
+        let locked_view = Block::get(tx.deref_mut(), &locked_block.block_id)?.view();
+        if precommit_node.view() > locked_view {
             on_lock_block(tx, &locked_block, &precommit_node, locked_blocks)?;
             precommit_node.as_locked_block().set(tx)?;
         }
 
         // b <- b'.justify.node
-        let prepare_node = precommit_node.justify().block_id();
-        if commit_node.parent() == precommit_node.id() && precommit_node.parent() == prepare_node {
+        let Some(prepare_node) = precommit_node.justify().get_block(tx.deref_mut()).optional()? else {
+            return Ok(high_qc);
+        };
+        // Without dummy blocks, a direct parent may have been proposed in an earlier view (i.e. there was a leader
+        // failure in between) so we also require the views of the 3-chain to be consecutive.
+        if commit_node.parent() == precommit_node.id() &&
+            precommit_node.parent() == prepare_node.id() &&
+            commit_node.view() == precommit_node.view().next() &&
+            precommit_node.view() == prepare_node.view().next()
+        {
             debug!(
                 target: LOG_TARGET,
                 "✅ Node {} {} forms a 3-chain b'' = {}, b' = {}, b = {}",
@@ -570,11 +622,10 @@ impl<TAddr: NodeAddressable> Block<TAddr> {
                 self.id(),
                 commit_node.id(),
                 precommit_node.id(),
-                prepare_node,
+                prepare_node.id(),
             );
 
             // Commit prepare_node (b)
-            let prepare_node = Block::get(tx.deref_mut(), prepare_node)?;
             let last_executed = LastExecuted::get(tx.deref_mut())?;
             on_commit(tx, &last_executed, &prepare_node)?;
             prepare_node.as_last_executed().set(tx)?;
@@ -586,7 +637,7 @@ impl<TAddr: NodeAddressable> Block<TAddr> {
                 self.id(),
                 commit_node.id(),
                 precommit_node.id(),
-                prepare_node,
+                prepare_node.id(),
                 self.id()
             );
         }
@@ -604,9 +655,10 @@ impl<TAddr: NodeAddressable> Block<TAddr> {
     pub fn is_safe<TTx: StateStoreReadTransaction<Addr = TAddr>>(&self, tx: &mut TTx) -> Result<bool, StorageError> {
         let locked = LockedBlock::get(tx)?;
         let locked_block = locked.get_block(tx)?;
+        let justify_block = self.justify().get_block(tx)?;
 
         // Liveness rules
-        if self.justify().block_height() > locked_block.height() {
+        if justify_block.view() > locked_block.view() {
             return Ok(true);
         }
 
@@ -629,8 +681,9 @@ impl<TAddr> Display for Block<TAddr> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "[{}, {}, {} command(s)]",
+            "[{}, {}, {}, {} command(s)]",
             self.height(),
+            self.view(),
             self.id(),
             self.commands().len()
         )
@@ -3,7 +3,12 @@
 
 use tari_dan_common_types::NodeHeight;
 
-use crate::{consensus_models::BlockId, StateStoreReadTransaction, StateStoreWriteTransaction, StorageError};
+use crate::{
+    consensus_models::{Block, BlockId},
+    StateStoreReadTransaction,
+    StateStoreWriteTransaction,
+    StorageError,
+};
 
 pub struct LastVoted {
     pub block_id: BlockId,
@@ -32,6 +37,10 @@ impl LastVoted {
     pub fn unset<TTx: StateStoreWriteTransaction>(&self, tx: &mut TTx) -> Result<(), StorageError> {
         tx.last_votes_unset(self)
     }
+
+    pub fn get_block<TTx: StateStoreReadTransaction>(&self, tx: &mut TTx) -> Result<Block<TTx::Addr>, StorageError> {
+        tx.blocks_get(&self.block_id)
+    }
 }
 
 impl std::fmt::Display for LastVoted {
@@ -14,7 +14,9 @@ mod locked_block;
 mod locked_output;
 mod quorum;
 mod quorum_certificate;
+mod state_snapshot;
 mod substate;
+mod timeout_certificate;
 mod transaction;
 mod transaction_decision;
 mod transaction_pool;
@@ -36,7 +38,9 @@ pub use locked_block::*;
 pub use locked_output::*;
 pub use quorum::*;
 pub use quorum_certificate::*;
+pub use state_snapshot::*;
 pub use substate::*;
+pub use timeout_certificate::*;
 pub use transaction::*;
 pub use transaction_decision::*;
 pub use transaction_pool::*;
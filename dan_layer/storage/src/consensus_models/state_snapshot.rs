@@ -0,0 +1,252 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use std::{
+    collections::HashMap,
+    ops::{DerefMut, RangeInclusive},
+};
+
+use tari_common_types::types::FixedHash;
+use tari_dan_common_types::{
+    hasher::TariHasher,
+    hashing::state_commitment_hasher,
+    NodeAddressable,
+    NodeHeight,
+    ShardId,
+};
+
+use crate::{
+    consensus_models::{Block, BlockId, QuorumCertificate, SubstatePage, SubstateRecord},
+    StateStoreReadTransaction,
+    StateStoreWriteTransaction,
+    StorageError,
+};
+
+/// The number of substates scanned per read when loading a snapshot
+const SNAPSHOT_PAGE_SIZE: usize = 1000;
+
+/// The live substates of a shard range as of a committed checkpoint block, along with the QC that certifies the
+/// checkpoint.
+#[derive(Debug, Clone)]
+pub struct StateSnapshot<TAddr> {
+    pub block: Block<TAddr>,
+    pub qc: QuorumCertificate<TAddr>,
+    pub range: RangeInclusive<ShardId>,
+    pub substates: Vec<SubstateRecord>,
+}
+
+/// The state commitment of a shard range as of a checkpoint block, along with the number of substates it commits to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StateSnapshotSummary {
+    pub state_commitment: FixedHash,
+    pub substate_count: u64,
+}
+
+impl<TAddr: NodeAddressable> StateSnapshot<TAddr> {
+    pub fn state_commitment(&self) -> FixedHash {
+        calculate_state_commitment(&self.range, &self.substates)
+    }
+
+    /// Loads the snapshot of the given shard range as of the committed block `block_id`. Substates that were created
+    /// or destroyed by blocks after the checkpoint are excluded/included accordingly, so the same snapshot is
+    /// returned for a checkpoint no matter how far the local chain has progressed since.
+    pub fn load<TTx: StateStoreReadTransaction<Addr = TAddr> + ?Sized>(
+        tx: &mut TTx,
+        block_id: &BlockId,
+        range: RangeInclusive<ShardId>,
+    ) -> Result<Self, StorageError> {
+        let (block, qc) = Self::load_checkpoint(tx, block_id)?;
+        let mut substates = Vec::new();
+        let mut after = None;
+        loop {
+            let page = Self::load_page(tx, &block, &range, after.as_ref(), SNAPSHOT_PAGE_SIZE)?;
+            substates.extend(page.substates);
+            match page.next {
+                Some(next) => after = Some(next),
+                None => break,
+            }
+        }
+
+        Ok(Self {
+            block,
+            qc,
+            range,
+            substates,
+        })
+    }
+
+    /// Loads the committed checkpoint block `block_id` and the QC that certifies it
+    pub fn load_checkpoint<TTx: StateStoreReadTransaction<Addr = TAddr> + ?Sized>(
+        tx: &mut TTx,
+        block_id: &BlockId,
+    ) -> Result<(Block<TAddr>, QuorumCertificate<TAddr>), StorageError> {
+        let block = tx.blocks_get(block_id)?;
+        if !block.is_committed() {
+            return Err(StorageError::DataInconsistency {
+                details: format!("Snapshot checkpoint block {} is not committed", block_id),
+            });
+        }
+        let qc = tx.quorum_certificates_get_by_block_id(block_id)?;
+        Ok((block, qc))
+    }
+
+    /// Returns a page of the substates of the shard range that were live as of the `checkpoint` block, in shard id
+    /// order. At most `limit` substates are scanned, so a page may hold fewer substates than `limit` while
+    /// `SubstatePage::next` is still set.
+    pub fn load_page<TTx: StateStoreReadTransaction<Addr = TAddr> + ?Sized>(
+        tx: &mut TTx,
+        checkpoint: &Block<TAddr>,
+        range: &RangeInclusive<ShardId>,
+        after: Option<&ShardId>,
+        limit: usize,
+    ) -> Result<SubstatePage, StorageError> {
+        let substates = tx.substates_get_page_within_range(range.start(), range.end(), after, limit)?;
+        let next = if limit > 0 && substates.len() == limit {
+            substates.last().map(|s| s.to_shard_id())
+        } else {
+            None
+        };
+        let substates = filter_live_at_height(tx, substates, checkpoint.height())?;
+        Ok(SubstatePage { substates, next })
+    }
+
+    /// Calculates the state commitment of the shard range as of the `checkpoint` block. The substates are read one
+    /// page at a time so that the range is never held in memory as a whole.
+    pub fn load_summary<TTx: StateStoreReadTransaction<Addr = TAddr> + ?Sized>(
+        tx: &mut TTx,
+        checkpoint: &Block<TAddr>,
+        range: &RangeInclusive<ShardId>,
+    ) -> Result<StateSnapshotSummary, StorageError> {
+        let mut hasher = StateCommitmentHasher::new(range);
+        let mut substate_count = 0u64;
+        let mut after = None;
+        loop {
+            let page = Self::load_page(tx, checkpoint, range, after.as_ref(), SNAPSHOT_PAGE_SIZE)?;
+            for substate in &page.substates {
+                hasher.update(substate);
+            }
+            substate_count += page.substates.len() as u64;
+            match page.next {
+                Some(next) => after = Some(next),
+                None => break,
+            }
+        }
+
+        Ok(StateSnapshotSummary {
+            state_commitment: hasher.result(),
+            substate_count,
+        })
+    }
+
+    /// Stores the snapshot and makes the checkpoint block the locked, leaf and last executed block so that block sync
+    /// can continue from it. The caller must have verified the snapshot.
+    pub fn apply<TTx>(self, tx: &mut TTx) -> Result<(), StorageError>
+    where
+        TTx: StateStoreWriteTransaction<Addr = TAddr> + DerefMut,
+        TTx::Target: StateStoreReadTransaction<Addr = TAddr>,
+    {
+        Self::apply_checkpoint(&self.block, &self.qc, tx)?;
+
+        for substate in self.substates {
+            substate.create(tx)?;
+        }
+
+        Ok(())
+    }
+
+    /// Stores the checkpoint block and its QC and makes the block the locked, leaf and last executed block. Used when
+    /// the substates of the snapshot are written separately. The caller must have verified the checkpoint.
+    pub fn apply_checkpoint<TTx>(
+        block: &Block<TAddr>,
+        qc: &QuorumCertificate<TAddr>,
+        tx: &mut TTx,
+    ) -> Result<(), StorageError>
+    where
+        TTx: StateStoreWriteTransaction<Addr = TAddr> + DerefMut,
+        TTx::Target: StateStoreReadTransaction<Addr = TAddr>,
+    {
+        block.justify().save(tx)?;
+        block.save(tx)?;
+        qc.save(tx)?;
+        block.commit(tx)?;
+        block.as_locked_block().set(tx)?;
+        block.as_leaf_block().set(tx)?;
+        block.as_last_executed().set(tx)?;
+        block.as_last_voted().set(tx)?;
+        qc.update_high_qc(tx)?;
+        Ok(())
+    }
+}
+
+/// Hashes the substates of a shard range into a state commitment one substate at a time. Substates must be added in
+/// shard id order.
+#[derive(Debug, Clone)]
+pub struct StateCommitmentHasher {
+    hasher: TariHasher,
+}
+
+impl StateCommitmentHasher {
+    pub fn new(range: &RangeInclusive<ShardId>) -> Self {
+        Self {
+            hasher: state_commitment_hasher().chain(range.start()).chain(range.end()),
+        }
+    }
+
+    pub fn update(&mut self, substate: &SubstateRecord) {
+        self.hasher.update(&substate.to_shard_id());
+        self.hasher.update(&substate.address);
+        self.hasher.update(&substate.version);
+        self.hasher.update(&substate.substate_value);
+    }
+
+    pub fn result(self) -> FixedHash {
+        self.hasher.result()
+    }
+}
+
+/// Returns the state commitment for the given substates of a shard range. The commitment is independent of the order
+/// of `substates`.
+pub fn calculate_state_commitment<'a, I: IntoIterator<Item = &'a SubstateRecord>>(
+    range: &RangeInclusive<ShardId>,
+    substates: I,
+) -> FixedHash {
+    let mut substates = substates.into_iter().collect::<Vec<_>>();
+    substates.sort_by_key(|s| s.to_shard_id());
+    let mut hasher = StateCommitmentHasher::new(range);
+    for substate in substates {
+        hasher.update(substate);
+    }
+    hasher.result()
+}
+
+/// Drops the substates that were not yet created or were already destroyed as of `height`
+fn filter_live_at_height<TTx: StateStoreReadTransaction + ?Sized>(
+    tx: &mut TTx,
+    substates: Vec<SubstateRecord>,
+    height: NodeHeight,
+) -> Result<Vec<SubstateRecord>, StorageError> {
+    let mut destroyed_heights = HashMap::new();
+    let mut live = Vec::with_capacity(substates.len());
+    for mut substate in substates {
+        if substate.created_height > height {
+            continue;
+        }
+        if let Some(destroyed) = substate.destroyed() {
+            let destroyed_height = match destroyed_heights.get(&destroyed.by_block) {
+                Some(h) => *h,
+                None => {
+                    let h = tx.blocks_get(&destroyed.by_block)?.height();
+                    destroyed_heights.insert(destroyed.by_block, h);
+                    h
+                },
+            };
+            if destroyed_height <= height {
+                continue;
+            }
+            // Destroyed after the checkpoint, so it is live as far as the snapshot is concerned
+            substate.destroyed = None;
+        }
+        live.push(substate);
+    }
+    Ok(live)
+}
@@ -16,7 +16,7 @@ use tari_engine_types::substate::{Substate, SubstateAddress, SubstateValue};
 use tari_transaction::TransactionId;
 
 use crate::{
-    consensus_models::{Block, BlockId, QcId, QuorumCertificate},
+    consensus_models::{Block, BlockId, LastExecuted, QcId, QuorumCertificate},
     StateStoreReadTransaction,
     StateStoreWriteTransaction,
     StorageError,
@@ -38,6 +38,18 @@ pub struct SubstateRecord {
     pub destroyed: Option<SubstateDestroyed>,
 }
 
+/// A point in the committed chain at which the state of a substate is queried
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SubstateQueryPoint {
+    /// The state once the given committed block was executed
+    Block(BlockId),
+    /// The state once the committed block at the given height was executed
+    Height(NodeHeight),
+    /// The state at the end of the given epoch
+    Epoch(Epoch),
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SubstateDestroyed {
     pub by_transaction: TransactionId,
@@ -46,6 +58,24 @@ pub struct SubstateDestroyed {
     pub at_epoch: Epoch,
 }
 
+/// How far back destroyed substate versions have been pruned from the store
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SubstatePruneMark {
+    /// Versions destroyed before this epoch have been deleted
+    pub before_epoch: Epoch,
+    /// The highest height at which a deleted version was destroyed
+    pub destroyed_height: NodeHeight,
+}
+
+/// The versions of a substate held by this node
+#[derive(Debug, Clone)]
+pub struct SubstateHistory {
+    /// Oldest version first
+    pub versions: Vec<SubstateRecord>,
+    /// Versions destroyed before this epoch may have been pruned and be missing from `versions`
+    pub pruned_before_epoch: Option<Epoch>,
+}
+
 impl SubstateRecord {
     pub fn new(
         address: SubstateAddress,
@@ -167,6 +197,13 @@ impl SubstateRecord {
         Ok(())
     }
 
+    pub fn delete_many<TTx: StateStoreWriteTransaction, I: IntoIterator<Item = ShardId>>(
+        tx: &mut TTx,
+        shard_ids: I,
+    ) -> Result<usize, StorageError> {
+        tx.substates_delete_many(shard_ids)
+    }
+
     pub fn exists<TTx: StateStoreReadTransaction + ?Sized>(
         tx: &mut TTx,
         shard: &ShardId,
@@ -217,6 +254,24 @@ impl SubstateRecord {
         tx.substates_get_many_within_range(bounds.borrow().start(), bounds.borrow().end(), excluded_shards)
     }
 
+    /// Returns a page of live substates within the shard range, starting after the `after` shard id if given. If the
+    /// page is full, `SubstatePage::next` is set to the shard id to pass as `after` to fetch the next page.
+    pub fn get_live_page_within_range<TTx: StateStoreReadTransaction, B: Borrow<RangeInclusive<ShardId>>>(
+        tx: &mut TTx,
+        bounds: B,
+        after: Option<&ShardId>,
+        limit: usize,
+    ) -> Result<SubstatePage, StorageError> {
+        let bounds = bounds.borrow();
+        let substates = tx.substates_get_live_page_within_range(bounds.start(), bounds.end(), after, limit)?;
+        let next = if limit > 0 && substates.len() == limit {
+            substates.last().map(|s| s.to_shard_id())
+        } else {
+            None
+        };
+        Ok(SubstatePage { substates, next })
+    }
+
     pub fn get_many_by_created_transaction<TTx: StateStoreReadTransaction>(
         tx: &mut TTx,
         transaction_id: &TransactionId,
@@ -231,6 +286,145 @@ impl SubstateRecord {
         tx.substates_get_many_by_destroyed_transaction(transaction_id)
     }
 
+    /// Returns the substates created or destroyed by the block
+    pub fn get_all_for_block<TTx: StateStoreReadTransaction + ?Sized>(
+        tx: &mut TTx,
+        block_id: &BlockId,
+    ) -> Result<Vec<SubstateRecord>, StorageError> {
+        tx.substates_get_all_for_block(block_id)
+    }
+
+    pub fn get_all_versions<TTx: StateStoreReadTransaction + ?Sized>(
+        tx: &mut TTx,
+        address: &SubstateAddress,
+    ) -> Result<Vec<SubstateRecord>, StorageError> {
+        tx.substates_get_all_versions(address)
+    }
+
+    /// Returns the versions of the substate at `address` held by this node, along with how far back destroyed
+    /// versions may have been pruned
+    pub fn get_history<TTx: StateStoreReadTransaction + ?Sized>(
+        tx: &mut TTx,
+        address: &SubstateAddress,
+    ) -> Result<SubstateHistory, StorageError> {
+        let versions = tx.substates_get_all_versions(address)?;
+        let pruned_before_epoch = tx.substates_get_prune_mark()?.map(|mark| mark.before_epoch);
+        Ok(SubstateHistory {
+            versions,
+            pruned_before_epoch,
+        })
+    }
+
+    pub fn get_latest_version<TTx: StateStoreReadTransaction + ?Sized>(
+        tx: &mut TTx,
+        address: &SubstateAddress,
+    ) -> Result<Self, StorageError> {
+        tx.substates_get_latest_version(address)
+    }
+
+    /// Returns the version of the substate at `address` that was live at the given point, or None if the substate did
+    /// not exist or was down at that point. A NotFound error is returned if the block or height has not been committed
+    /// and a HistoryUnavailable error if the version live at that point may have been pruned.
+    pub fn get_at<TTx: StateStoreReadTransaction + ?Sized>(
+        tx: &mut TTx,
+        address: &SubstateAddress,
+        at: SubstateQueryPoint,
+    ) -> Result<Option<SubstateRecord>, StorageError> {
+        match at {
+            SubstateQueryPoint::Block(block_id) => {
+                let block = tx.blocks_get(&block_id)?;
+                if !block.is_committed() {
+                    return Err(StorageError::NotFound {
+                        item: "committed block".to_string(),
+                        key: block_id.to_string(),
+                    });
+                }
+                Self::get_at_height(tx, address, block.height())
+            },
+            SubstateQueryPoint::Height(height) => {
+                let last_executed = LastExecuted::get(tx)?;
+                if height > last_executed.height {
+                    return Err(StorageError::NotFound {
+                        item: "committed block at height".to_string(),
+                        key: height.to_string(),
+                    });
+                }
+                Self::get_at_height(tx, address, height)
+            },
+            SubstateQueryPoint::Epoch(epoch) => Self::get_at_epoch(tx, address, epoch),
+        }
+    }
+
+    /// Returns the version of the substate at `address` that was live once the committed block at `height` was
+    /// executed, or None if the substate did not exist or had already been destroyed at that height. A
+    /// HistoryUnavailable error is returned if the height at which the candidate version was destroyed is not known
+    /// to this node.
+    pub fn get_at_height<TTx: StateStoreReadTransaction + ?Sized>(
+        tx: &mut TTx,
+        address: &SubstateAddress,
+        height: NodeHeight,
+    ) -> Result<Option<SubstateRecord>, StorageError> {
+        let versions = tx.substates_get_all_versions(address)?;
+        // A new version is only created once the previous one is destroyed, so the latest version created at or
+        // before the height is the only candidate. Pruning only deletes versions destroyed before any that are kept, so
+        // a candidate that was found is never stale.
+        let Some(pos) = versions.iter().rposition(|s| s.created_height <= height) else {
+            let mark = tx.substates_get_prune_mark()?;
+            if mark.map_or(false, |m| height < m.destroyed_height) {
+                return Err(history_pruned(address, SubstateQueryPoint::Height(height)));
+            }
+            return Ok(None);
+        };
+        let mut versions = versions.into_iter().skip(pos);
+        let substate = versions.next().expect("rposition returned a valid index");
+        if let Some(destroyed) = substate.destroyed() {
+            let destroyed_height = match tx.blocks_get(&destroyed.by_block).optional()? {
+                Some(block) => block.height(),
+                // The destroying block is not held by this node, for example because the node synced a snapshot
+                // taken after it. The transaction that destroyed the substate created the next version, if that
+                // version is held here.
+                None => versions
+                    .next()
+                    .filter(|next| next.created_by_transaction == destroyed.by_transaction)
+                    .map(|next| next.created_height)
+                    .ok_or_else(|| StorageError::HistoryUnavailable {
+                        details: format!(
+                            "substate {} v{} was destroyed by block {} which is not held by this node",
+                            address, substate.version, destroyed.by_block
+                        ),
+                    })?,
+            };
+            if destroyed_height <= height {
+                return Ok(None);
+            }
+        }
+        Ok(Some(substate))
+    }
+
+    /// Returns the version of the substate at `address` that was live at the end of `epoch`, or None if the substate
+    /// did not exist or had already been destroyed by then.
+    pub fn get_at_epoch<TTx: StateStoreReadTransaction + ?Sized>(
+        tx: &mut TTx,
+        address: &SubstateAddress,
+        epoch: Epoch,
+    ) -> Result<Option<SubstateRecord>, StorageError> {
+        let versions = tx.substates_get_all_versions(address)?;
+        let live = Self::find_live_at_epoch(versions, epoch);
+        if live.is_none() && tx.substates_get_prune_mark()?.map_or(false, |m| epoch < m.before_epoch) {
+            return Err(history_pruned(address, SubstateQueryPoint::Epoch(epoch)));
+        }
+        Ok(live)
+    }
+
+    /// Returns the version that was live at the end of `epoch` from the given versions of a substate
+    pub fn find_live_at_epoch<I: IntoIterator<Item = SubstateRecord>>(versions: I, epoch: Epoch) -> Option<Self> {
+        versions
+            .into_iter()
+            .filter(|s| s.created_at_epoch <= epoch)
+            .max_by_key(|s| s.version)
+            .filter(|s| s.destroyed().map_or(true, |d| d.at_epoch > epoch))
+    }
+
     pub fn get_created_quorum_certificate<TTx: StateStoreReadTransaction>(
         &self,
         tx: &mut TTx,
@@ -267,6 +461,22 @@ impl SubstateRecord {
     }
 }
 
+fn history_pruned(address: &SubstateAddress, at: SubstateQueryPoint) -> StorageError {
+    StorageError::HistoryUnavailable {
+        details: format!(
+            "the version of substate {} live at {:?} may have been pruned",
+            address, at
+        ),
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SubstatePage {
+    pub substates: Vec<SubstateRecord>,
+    /// The shard id to continue from, or None if this is the last page
+    pub next: Option<ShardId>,
+}
+
 #[derive(Debug, Clone)]
 pub struct SubstateCreatedProof<TAddr> {
     pub substate: SubstateData,
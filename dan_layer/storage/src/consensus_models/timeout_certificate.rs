@@ -0,0 +1,160 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use std::{collections::HashSet, fmt::Display};
+
+use serde::{Deserialize, Serialize};
+use tari_dan_common_types::{committee::Committee, Epoch, NodeAddressable, NodeHeight, View};
+
+use crate::consensus_models::{BlockId, QcId, ValidatorSignature};
+
+/// Aggregated NewView messages from a quorum of the local committee, proving that the leader(s) of the views before
+/// `new_view` failed. A leader includes this in a block that does not directly justify the previous view, which allows
+/// replicas to skip the failed view(s) without any intermediate (dummy) blocks.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct TimeoutCertificate<TAddr> {
+    epoch: Epoch,
+    new_view: View,
+    new_views: Vec<SignedNewView<TAddr>>,
+}
+
+impl<TAddr: NodeAddressable> TimeoutCertificate<TAddr> {
+    pub fn new(epoch: Epoch, new_view: View, mut new_views: Vec<SignedNewView<TAddr>>) -> Self {
+        // Deterministic ordering for the block hash
+        new_views.sort_by(|a, b| a.signer().cmp(b.signer()));
+        Self {
+            epoch,
+            new_view,
+            new_views,
+        }
+    }
+
+    pub fn epoch(&self) -> Epoch {
+        self.epoch
+    }
+
+    /// The view that the leader is permitted to propose in
+    pub fn new_view(&self) -> View {
+        self.new_view
+    }
+
+    /// The signed NEWVIEW messages that make up this certificate
+    pub fn new_views(&self) -> &[SignedNewView<TAddr>] {
+        &self.new_views
+    }
+
+    pub fn signers(&self) -> impl Iterator<Item = &TAddr> + '_ {
+        self.new_views.iter().map(|nv| nv.signer())
+    }
+
+    /// The highest QC that any of the signers had. A block that is proposed with this certificate must extend a QC that
+    /// is at least this high.
+    pub fn max_high_qc_height(&self) -> NodeHeight {
+        self.new_views
+            .iter()
+            .map(|nv| nv.high_qc_height)
+            .max()
+            .unwrap_or(NodeHeight(0))
+    }
+
+    /// Returns true if the signers are distinct members of the committee and reach the committee's quorum threshold.
+    /// The signatures are not checked.
+    pub fn is_quorum_of(&self, committee: &Committee<TAddr>) -> bool {
+        let mut seen = HashSet::with_capacity(self.new_views.len());
+        for signer in self.signers() {
+            if !committee.contains(signer) || !seen.insert(signer) {
+                return false;
+            }
+        }
+        seen.len() >= committee.quorum_threshold()
+    }
+}
+
+impl<TAddr> Display for TimeoutCertificate<TAddr> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "TC(epoch: {}, new_view: {}, {} signer(s))",
+            self.epoch,
+            self.new_view,
+            self.new_views.len()
+        )
+    }
+}
+
+/// A NEWVIEW message from a committee member, as included in a [TimeoutCertificate]. The signature covers the epoch and
+/// view of the certificate, the sender's high QC and the block that the sender last voted for.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct SignedNewView<TAddr> {
+    pub high_qc_id: QcId,
+    pub high_qc_height: NodeHeight,
+    pub last_vote_block_id: Option<BlockId>,
+    pub signature: ValidatorSignature<TAddr>,
+}
+
+impl<TAddr> SignedNewView<TAddr> {
+    pub fn signer(&self) -> &TAddr {
+        &self.signature.public_key
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::OsRng;
+    use tari_common_types::types::{PrivateKey, PublicKey};
+    use tari_crypto::keys::PublicKey as _;
+
+    use super::*;
+
+    fn new_view(secret: &PrivateKey, high_qc_height: u64) -> SignedNewView<PublicKey> {
+        SignedNewView {
+            high_qc_id: QcId::genesis(),
+            high_qc_height: NodeHeight(high_qc_height),
+            last_vote_block_id: None,
+            signature: ValidatorSignature::sign(secret, b"new view"),
+        }
+    }
+
+    fn keys(n: usize) -> Vec<(PrivateKey, PublicKey)> {
+        (0..n).map(|_| PublicKey::random_keypair(&mut OsRng)).collect()
+    }
+
+    fn committee(keys: &[(PrivateKey, PublicKey)]) -> Committee<PublicKey> {
+        Committee::new(keys.iter().map(|(_, pk)| pk.clone()).collect())
+    }
+
+    #[test]
+    fn it_requires_a_quorum_of_signers() {
+        let keys = keys(4);
+        let tc = TimeoutCertificate::new(Epoch(0), View(2), vec![
+            new_view(&keys[0].0, 1),
+            new_view(&keys[1].0, 1),
+        ]);
+        assert!(!tc.is_quorum_of(&committee(&keys)));
+        let tc = TimeoutCertificate::new(Epoch(0), View(2), vec![
+            new_view(&keys[0].0, 1),
+            new_view(&keys[1].0, 3),
+            new_view(&keys[2].0, 2),
+        ]);
+        assert!(tc.is_quorum_of(&committee(&keys)));
+        assert_eq!(tc.max_high_qc_height(), NodeHeight(3));
+    }
+
+    #[test]
+    fn it_rejects_duplicate_and_non_committee_signers() {
+        let keys = keys(5);
+        let committee = committee(&keys[..4]);
+        let tc = TimeoutCertificate::new(Epoch(0), View(2), vec![
+            new_view(&keys[0].0, 1),
+            new_view(&keys[0].0, 1),
+            new_view(&keys[1].0, 1),
+        ]);
+        assert!(!tc.is_quorum_of(&committee));
+        let tc = TimeoutCertificate::new(Epoch(0), View(2), vec![
+            new_view(&keys[0].0, 1),
+            new_view(&keys[1].0, 1),
+            new_view(&keys[4].0, 1),
+        ]);
+        assert!(!tc.is_quorum_of(&committee));
+    }
+}
@@ -2,6 +2,8 @@
 //   SPDX-License-Identifier: BSD-3-Clause
 
 use std::{
+    cmp,
+    collections::HashMap,
     fmt::{Display, Formatter},
     marker::PhantomData,
     num::NonZeroU64,
@@ -9,14 +11,23 @@ use std::{
 };
 
 use log::*;
+use tari_common_types::types::PublicKey;
 use tari_dan_common_types::{
     committee::CommitteeShard,
     optional::{IsNotFoundError, Optional},
 };
-use tari_transaction::TransactionId;
+use tari_transaction::{Transaction, TransactionId};
 
 use crate::{
-    consensus_models::{Decision, LeafBlock, LockedBlock, QcId, TransactionAtom, TransactionPoolStatusUpdate},
+    consensus_models::{
+        Decision,
+        ExecutedTransaction,
+        LeafBlock,
+        LockedBlock,
+        QcId,
+        TransactionAtom,
+        TransactionPoolStatusUpdate,
+    },
     StateStore,
     StateStoreReadTransaction,
     StateStoreWriteTransaction,
@@ -25,6 +36,11 @@ use crate::{
 
 const _LOG_TARGET: &str = "tari::dan::storage::transaction_pool";
 
+/// The number of new transactions read from the store at a time when selecting transactions for a block
+const NEW_TRANSACTION_PAGE_SIZE: usize = 100;
+/// The fee-per-byte of a transaction is scaled by this factor so that it can be stored and ordered as an integer
+const FEE_PRIORITY_SCALE: u128 = 1 << 16;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum TransactionPoolStage {
     /// Transaction has just come in and has never been proposed
@@ -148,19 +164,73 @@ impl<TStateStore: StateStore> TransactionPool<TStateStore> {
     pub fn insert(
         &self,
         tx: &mut TStateStore::WriteTransaction<'_>,
-        transaction: TransactionAtom,
+        transaction: &ExecutedTransaction,
     ) -> Result<(), TransactionPoolError> {
-        tx.transaction_pool_insert(transaction, TransactionPoolStage::New, true)?;
+        tx.transaction_pool_insert(
+            transaction.to_atom(),
+            &TransactionFeeInfo::from_transaction(transaction.transaction()),
+            TransactionPoolStage::New,
+            true,
+        )?;
         Ok(())
     }
 
+    /// Returns up to `max` ready transactions to propose in the next block, whose transaction atoms fit in
+    /// `max_size_bytes`. Transactions that are already in progress are always returned first, as they hold locks and
+    /// must progress to finalization. New transactions fill the remaining space in descending order of fee-per-byte,
+    /// with at most `max_per_signer` transactions from any one signer in progress or in the batch. New transactions
+    /// are read from the store one page at a time until the batch is full.
     pub fn get_batch_for_next_block(
         &self,
         tx: &mut TStateStore::ReadTransaction<'_>,
         max: usize,
+        max_size_bytes: usize,
+        max_per_signer: usize,
     ) -> Result<Vec<TransactionPoolRecord>, TransactionPoolError> {
-        let recs = tx.transaction_pool_get_many_ready(max)?;
-        Ok(recs)
+        let in_progress = tx.transaction_pool_get_in_progress()?;
+        let mut signer_counts = HashMap::new();
+        for rec in &in_progress {
+            *signer_counts.entry(rec.signer().clone()).or_insert(0) += 1;
+        }
+
+        let mut batch = Vec::with_capacity(cmp::min(max, in_progress.len() + NEW_TRANSACTION_PAGE_SIZE));
+        let mut remaining_bytes = max_size_bytes;
+        for rec in in_progress.into_iter().filter(|rec| rec.is_ready()) {
+            if batch.len() >= max || remaining_bytes == 0 {
+                return Ok(batch);
+            }
+            let size = rec.atom_size();
+            if size > remaining_bytes {
+                continue;
+            }
+            remaining_bytes -= size;
+            batch.push(rec);
+        }
+
+        let mut after = None;
+        while batch.len() < max && remaining_bytes > 0 {
+            let page = tx.transaction_pool_get_new_by_fee_priority(
+                after.as_ref().map(|(priority, id)| (*priority, id)),
+                NEW_TRANSACTION_PAGE_SIZE,
+            )?;
+            let is_last_page = page.len() < NEW_TRANSACTION_PAGE_SIZE;
+            after = page.last().map(|rec| (rec.fee_priority(), *rec.transaction_id()));
+            // Transactions that were proposed after they were committed as new are in progress and were counted above
+            let candidates = page.into_iter().filter(|rec| rec.current_stage().is_new());
+            select_by_fee_priority(
+                &mut batch,
+                candidates,
+                max,
+                &mut remaining_bytes,
+                max_per_signer,
+                &mut signer_counts,
+            );
+            if is_last_page {
+                break;
+            }
+        }
+
+        Ok(batch)
     }
 
     pub fn has_uncommitted_transactions(
@@ -215,9 +285,63 @@ impl<TStateStore: StateStore> TransactionPool<TStateStore> {
     }
 }
 
+/// The signer and encoded size of a transaction. These are stored with the pool record so that the store can order new
+/// transactions by fee-per-byte and the signer of each transaction in progress is known.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransactionFeeInfo {
+    pub signer: PublicKey,
+    pub size: usize,
+}
+
+impl TransactionFeeInfo {
+    pub fn from_transaction(transaction: &Transaction) -> Self {
+        Self {
+            signer: transaction.signer_public_key().clone(),
+            size: tari_bor::encode(transaction).map(|b| b.len()).unwrap_or(usize::MAX),
+        }
+    }
+
+    /// Returns the fee-per-byte of a transaction that pays `fee`, scaled so that it can be ordered as an integer. The
+    /// result is capped at `i64::MAX` so that every store can hold it.
+    pub fn fee_priority(&self, fee: u64) -> u64 {
+        let priority = u128::from(fee) * FEE_PRIORITY_SCALE / self.size.max(1) as u128;
+        cmp::min(priority, i64::MAX as u128) as u64
+    }
+}
+
+/// Adds `candidates` to the batch in the order given until it holds `max` transactions or `remaining_bytes` is used up.
+/// Candidates whose signer already has `max_per_signer` transactions (including those counted in `signer_counts`) or
+/// that do not fit in the remaining space are skipped.
+fn select_by_fee_priority<I: IntoIterator<Item = TransactionPoolRecord>>(
+    batch: &mut Vec<TransactionPoolRecord>,
+    candidates: I,
+    max: usize,
+    remaining_bytes: &mut usize,
+    max_per_signer: usize,
+    signer_counts: &mut HashMap<PublicKey, usize>,
+) {
+    for candidate in candidates {
+        if batch.len() >= max || *remaining_bytes == 0 {
+            break;
+        }
+        let atom_size = candidate.atom_size();
+        if atom_size > *remaining_bytes {
+            continue;
+        }
+        let count = signer_counts.entry(candidate.signer().clone()).or_insert(0);
+        if *count >= max_per_signer {
+            continue;
+        }
+        *count += 1;
+        *remaining_bytes -= atom_size;
+        batch.push(candidate);
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct TransactionPoolRecord {
     transaction: TransactionAtom,
+    fee_info: TransactionFeeInfo,
     stage: TransactionPoolStage,
     pending_stage: Option<TransactionPoolStage>,
     local_decision: Option<Decision>,
@@ -228,6 +352,7 @@ pub struct TransactionPoolRecord {
 impl TransactionPoolRecord {
     pub fn load(
         transaction: TransactionAtom,
+        fee_info: TransactionFeeInfo,
         stage: TransactionPoolStage,
         pending_stage: Option<TransactionPoolStage>,
         local_decision: Option<Decision>,
@@ -236,6 +361,7 @@ impl TransactionPoolRecord {
     ) -> Self {
         Self {
             transaction,
+            fee_info,
             stage,
             pending_stage,
             local_decision,
@@ -272,10 +398,31 @@ impl TransactionPoolRecord {
         &self.transaction.id
     }
 
+    /// The encoded size of the transaction atom. This is approximately the size of the command that a leader proposes
+    /// for the transaction.
+    pub fn atom_size(&self) -> usize {
+        tari_bor::encode(&self.transaction)
+            .map(|b| b.len())
+            .unwrap_or(usize::MAX)
+    }
+
     pub fn transaction(&self) -> &TransactionAtom {
         &self.transaction
     }
 
+    pub fn signer(&self) -> &PublicKey {
+        &self.fee_info.signer
+    }
+
+    pub fn fee_info(&self) -> &TransactionFeeInfo {
+        &self.fee_info
+    }
+
+    /// The fee-per-byte of the transaction that new transactions are ordered by, see `TransactionFeeInfo::fee_priority`
+    pub fn fee_priority(&self) -> u64 {
+        self.fee_info.fee_priority(self.transaction.transaction_fee)
+    }
+
     pub fn stage(&self) -> TransactionPoolStage {
         self.stage
     }
@@ -486,6 +633,10 @@ mod tests {
                     transaction_fee: fee,
                     leader_fee: 0,
                 },
+                fee_info: TransactionFeeInfo {
+                    signer: Default::default(),
+                    size: 0,
+                },
                 stage: TransactionPoolStage::New,
                 pending_stage: None,
                 local_decision: None,
@@ -525,4 +676,121 @@ mod tests {
             assert_eq!(fee, 9);
         }
     }
+
+    mod fee_priority {
+        use super::*;
+
+        fn fee_priority(fee: u64, size: usize) -> u64 {
+            TransactionFeeInfo {
+                signer: Default::default(),
+                size,
+            }
+            .fee_priority(fee)
+        }
+
+        #[test]
+        fn it_orders_by_fee_per_byte() {
+            assert!(fee_priority(150, 50) > fee_priority(100, 100));
+            assert_eq!(fee_priority(100, 100), fee_priority(1000, 1000));
+            assert!(fee_priority(1000, 1000) > fee_priority(100, 200));
+        }
+
+        #[test]
+        fn it_caps_the_priority() {
+            assert_eq!(fee_priority(u64::MAX, 1), i64::MAX as u64);
+            assert_eq!(fee_priority(100, 0), fee_priority(100, 1));
+        }
+    }
+
+    mod select_by_fee_priority {
+        use tari_common_types::types::PrivateKey;
+        use tari_crypto::keys::PublicKey as _;
+
+        use super::*;
+
+        fn create_candidate(id: u8, signer: u64, fee: u64) -> TransactionPoolRecord {
+            TransactionPoolRecord {
+                transaction: TransactionAtom {
+                    id: TransactionId::new([id; 32]),
+                    decision: Decision::Commit,
+                    evidence: Default::default(),
+                    transaction_fee: fee,
+                    leader_fee: 0,
+                },
+                fee_info: TransactionFeeInfo {
+                    signer: PublicKey::from_secret_key(&PrivateKey::from(signer)),
+                    size: 100,
+                },
+                stage: TransactionPoolStage::New,
+                pending_stage: None,
+                local_decision: None,
+                remote_decision: None,
+                is_ready: true,
+            }
+        }
+
+        fn select(
+            candidates: Vec<TransactionPoolRecord>,
+            max: usize,
+            max_size_bytes: usize,
+            max_per_signer: usize,
+            mut signer_counts: HashMap<PublicKey, usize>,
+        ) -> Vec<u8> {
+            let mut batch = vec![];
+            let mut remaining_bytes = max_size_bytes;
+            select_by_fee_priority(
+                &mut batch,
+                candidates,
+                max,
+                &mut remaining_bytes,
+                max_per_signer,
+                &mut signer_counts,
+            );
+            batch.iter().map(|r| r.transaction_id().as_bytes()[0]).collect()
+        }
+
+        #[test]
+        fn it_limits_the_batch_size() {
+            let candidates = vec![
+                create_candidate(1, 1, 30),
+                create_candidate(2, 2, 20),
+                create_candidate(3, 3, 10),
+            ];
+            assert_eq!(select(candidates, 2, usize::MAX, 10, HashMap::new()), vec![1, 2]);
+        }
+
+        #[test]
+        fn it_fills_the_byte_budget() {
+            let candidates = vec![
+                create_candidate(1, 1, 1),
+                create_candidate(2, 2, u64::MAX),
+                create_candidate(3, 3, 1),
+                create_candidate(4, 4, 1),
+            ];
+            // The atom of candidate 2 is larger because of its fee, so it does not fit after candidate 1 but candidate
+            // 3 does
+            let small = candidates[0].atom_size();
+            assert!(candidates[1].atom_size() > small);
+            assert_eq!(select(candidates, 10, 2 * small, 10, HashMap::new()), vec![1, 3]);
+        }
+
+        #[test]
+        fn it_caps_transactions_per_signer() {
+            let candidates = vec![
+                create_candidate(1, 1, 50),
+                create_candidate(2, 1, 40),
+                create_candidate(3, 1, 30),
+                create_candidate(4, 3, 20),
+                create_candidate(5, 2, 10),
+            ];
+            assert_eq!(select(candidates, 10, usize::MAX, 2, HashMap::new()), vec![1, 2, 4, 5]);
+
+            // Signer 3 already has a transaction in progress
+            let candidates = vec![create_candidate(1, 3, 50), create_candidate(2, 3, 40)];
+            let signer_counts = [(PublicKey::from_secret_key(&PrivateKey::from(3)), 1)]
+                .into_iter()
+                .collect();
+            assert_eq!(select(candidates, 10, usize::MAX, 2, signer_counts), vec![1]);
+        }
+    }
 }
@@ -1,32 +1,19 @@
 //   Copyright 2023 The Tari Project
 //   SPDX-License-Identifier: BSD-3-Clause
 
-use std::{fmt, fmt::Display, ops::DerefMut};
+use std::{fmt, fmt::Display};
 
-use tari_dan_common_types::{Epoch, NodeAddressable, NodeHeight};
+use tari_dan_common_types::{Epoch, NodeAddressable, NodeHeight, View};
 
-use crate::{
-    consensus_models::{Block, BlockId},
-    StateStoreReadTransaction,
-    StateStoreWriteTransaction,
-    StorageError,
-};
+use crate::consensus_models::{Block, BlockId};
 
 pub struct ValidBlock<TAddr> {
     block: Block<TAddr>,
-    dummy_blocks: Vec<Block<TAddr>>,
 }
 
 impl<TAddr> ValidBlock<TAddr> {
     pub fn new(block: Block<TAddr>) -> Self {
-        Self {
-            block,
-            dummy_blocks: vec![],
-        }
-    }
-
-    pub fn with_dummy_blocks(block: Block<TAddr>, dummy_blocks: Vec<Block<TAddr>>) -> Self {
-        Self { block, dummy_blocks }
+        Self { block }
     }
 
     pub fn block(&self) -> &Block<TAddr> {
@@ -41,6 +28,10 @@ impl<TAddr> ValidBlock<TAddr> {
         self.block.height()
     }
 
+    pub fn view(&self) -> View {
+        self.block.view()
+    }
+
     pub fn epoch(&self) -> Epoch {
         self.block.epoch()
     }
@@ -48,23 +39,6 @@ impl<TAddr> ValidBlock<TAddr> {
     pub fn proposed_by(&self) -> &TAddr {
         self.block.proposed_by()
     }
-
-    pub fn dummy_blocks(&self) -> &[Block<TAddr>] {
-        &self.dummy_blocks
-    }
-}
-
-impl<TAddr: NodeAddressable> ValidBlock<TAddr> {
-    pub fn save_all_dummy_blocks<TTx>(&self, tx: &mut TTx) -> Result<(), StorageError>
-    where
-        TTx: StateStoreWriteTransaction<Addr = TAddr> + DerefMut,
-        TTx::Target: StateStoreReadTransaction<Addr = TAddr>,
-    {
-        for block in &self.dummy_blocks {
-            block.save(tx)?;
-        }
-        Ok(())
-    }
 }
 
 impl<TAddr: NodeAddressable> Display for ValidBlock<TAddr> {
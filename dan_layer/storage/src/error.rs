@@ -58,8 +58,15 @@ pub enum StorageError {
     InvalidIntegerCast,
     #[error("Data inconsistency: {details}")]
     DataInconsistency { details: String },
+    #[error("History unavailable: {details}")]
+    HistoryUnavailable { details: String },
     #[error("General storage error: {details}")]
     General { details: String },
+    #[error(
+        "Database schema version {found} is not supported by this version of the software (supported: {supported}). \
+         Upgrade the software or use a new data directory."
+    )]
+    IncompatibleSchemaVersion { found: u32, supported: u32 },
 }
 
 impl IsNotFoundError for StorageError {
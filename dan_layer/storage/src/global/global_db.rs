@@ -24,10 +24,18 @@ use std::sync::Arc;
 
 use super::{validator_node_db::ValidatorNodeDb, BmtDb, EpochDb};
 use crate::{
-    global::{backend_adapter::GlobalDbAdapter, metadata_db::MetadataDb, template_db::TemplateDb},
+    global::{
+        backend_adapter::GlobalDbAdapter,
+        metadata_db::{MetadataDb, MetadataKey},
+        template_db::TemplateDb,
+    },
     StorageError,
 };
 
+/// The global database schema version supported by this software. This must be incremented whenever the global
+/// database changes in a way that older software cannot read.
+pub const GLOBAL_DB_SCHEMA_VERSION: u32 = 1;
+
 pub trait DbFactory: Sync + Send + 'static {
     type GlobalDbAdapter: GlobalDbAdapter;
 
@@ -98,4 +106,30 @@ impl<TGlobalDbAdapter: GlobalDbAdapter> GlobalDb<TGlobalDbAdapter> {
         self.adapter.commit(tx)?;
         Ok(())
     }
+
+    /// Checks that the schema version of the database is supported, returning an error if it was written by a newer
+    /// version of the software. A database without a version, or with an older one, is stamped with the current
+    /// version, so any backend migrations must be applied before calling this.
+    pub fn check_schema_version(&self) -> Result<(), StorageError>
+    where TGlobalDbAdapter::Error: Into<StorageError> {
+        let mut tx = self.create_transaction().map_err(Into::into)?;
+        let mut metadata = self.metadata(&mut tx);
+        let found = metadata
+            .get_metadata::<u32>(MetadataKey::GlobalDbSchemaVersion)
+            .map_err(Into::into)?;
+        match found {
+            Some(found) if found == GLOBAL_DB_SCHEMA_VERSION => return Ok(()),
+            Some(found) if found > GLOBAL_DB_SCHEMA_VERSION => {
+                return Err(StorageError::IncompatibleSchemaVersion {
+                    found,
+                    supported: GLOBAL_DB_SCHEMA_VERSION,
+                });
+            },
+            _ => {},
+        }
+        metadata
+            .set_metadata(MetadataKey::GlobalDbSchemaVersion, &GLOBAL_DB_SCHEMA_VERSION)
+            .map_err(Into::into)?;
+        self.commit(tx).map_err(Into::into)
+    }
 }
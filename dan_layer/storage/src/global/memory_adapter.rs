@@ -0,0 +1,435 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use std::{
+    collections::{HashMap, HashSet},
+    marker::PhantomData,
+    ops::RangeInclusive,
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+use serde::{de::DeserializeOwned, Serialize};
+use tari_common_types::types::PublicKey;
+use tari_dan_common_types::{
+    committee::Committee,
+    hashing::ValidatorNodeBalancedMerkleTree,
+    shard_bucket::ShardBucket,
+    Epoch,
+    ShardId,
+};
+use tari_utilities::{hex::to_hex, ByteArray};
+
+use crate::{
+    global::{
+        models::ValidatorNode,
+        DbEpoch,
+        DbTemplate,
+        DbTemplateUpdate,
+        GlobalDbAdapter,
+        MetadataKey,
+        TemplateStatus,
+    },
+    AtomicDb,
+    StorageError,
+};
+
+/// A global database adapter that keeps all data in memory. Writes made in a transaction are only visible to other
+/// transactions once it is committed. Each transaction works on a snapshot of the state and the lock is only held
+/// while taking the snapshot and while committing, so concurrent transactions do not block each other. Committing a
+/// write transaction fails if another write was committed after its snapshot was taken. Intended for tests.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryGlobalDbAdapter {
+    state: Arc<Mutex<MemoryGlobalDbState>>,
+}
+
+impl MemoryGlobalDbAdapter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock_state(&self) -> Result<MutexGuard<'_, MemoryGlobalDbState>, StorageError> {
+        self.state.lock().map_err(|_| StorageError::General {
+            details: "Memory global db lock poisoned".to_string(),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct MemoryGlobalDbState {
+    /// Incremented on every committed write
+    version: u64,
+    metadata: HashMap<&'static [u8], Vec<u8>>,
+    templates: Vec<DbTemplate>,
+    /// Validator node registrations in insertion order
+    validator_nodes: Vec<ValidatorNode<PublicKey>>,
+    epochs: HashMap<u64, DbEpoch>,
+    bmts: HashMap<u64, Vec<u8>>,
+}
+
+pub struct MemoryGlobalDbTransaction<'a> {
+    state: MemoryGlobalDbState,
+    is_dirty: bool,
+    _adapter: PhantomData<&'a MemoryGlobalDbAdapter>,
+}
+
+impl MemoryGlobalDbTransaction<'_> {
+    fn state_mut(&mut self) -> &mut MemoryGlobalDbState {
+        self.is_dirty = true;
+        &mut self.state
+    }
+}
+
+impl AtomicDb for MemoryGlobalDbAdapter {
+    type DbTransaction<'a> = MemoryGlobalDbTransaction<'a>;
+    type Error = StorageError;
+
+    fn create_transaction(&self) -> Result<Self::DbTransaction<'_>, Self::Error> {
+        let state = self.lock_state()?.clone();
+        Ok(MemoryGlobalDbTransaction {
+            state,
+            is_dirty: false,
+            _adapter: PhantomData,
+        })
+    }
+
+    fn commit(&self, mut transaction: Self::DbTransaction<'_>) -> Result<(), Self::Error> {
+        if !transaction.is_dirty {
+            return Ok(());
+        }
+        let mut guard = self.lock_state()?;
+        if guard.version != transaction.state.version {
+            return Err(StorageError::General {
+                details: format!(
+                    "Memory global db write conflict: transaction started at version {} but the current version is {}",
+                    transaction.state.version, guard.version
+                ),
+            });
+        }
+        transaction.state.version += 1;
+        *guard = transaction.state;
+        Ok(())
+    }
+}
+
+impl GlobalDbAdapter for MemoryGlobalDbAdapter {
+    fn get_metadata<T: DeserializeOwned>(
+        &self,
+        tx: &mut Self::DbTransaction<'_>,
+        key: &MetadataKey,
+    ) -> Result<Option<T>, Self::Error> {
+        tx.state
+            .metadata
+            .get(key.as_key_bytes())
+            .map(|value| {
+                tari_bor::decode(value).map_err(|e| StorageError::DecodingError {
+                    operation: "get_metadata",
+                    item: "metadata",
+                    details: e.to_string(),
+                })
+            })
+            .transpose()
+    }
+
+    fn set_metadata<T: Serialize>(
+        &self,
+        tx: &mut Self::DbTransaction<'_>,
+        key: MetadataKey,
+        value: &T,
+    ) -> Result<(), Self::Error> {
+        let value = tari_bor::encode(value).map_err(|e| StorageError::EncodingError {
+            operation: "set_metadata",
+            item: "metadata",
+            details: e.to_string(),
+        })?;
+        tx.state_mut().metadata.insert(key.as_key_bytes(), value);
+        Ok(())
+    }
+
+    fn template_exists(&self, tx: &mut Self::DbTransaction<'_>, key: &[u8]) -> Result<bool, Self::Error> {
+        Ok(tx.state.templates.iter().any(|t| t.template_address.as_slice() == key))
+    }
+
+    fn get_template(&self, tx: &mut Self::DbTransaction<'_>, key: &[u8]) -> Result<Option<DbTemplate>, Self::Error> {
+        Ok(tx
+            .state
+            .templates
+            .iter()
+            .find(|t| t.template_address.as_slice() == key)
+            .cloned())
+    }
+
+    fn get_templates(&self, tx: &mut Self::DbTransaction<'_>, limit: usize) -> Result<Vec<DbTemplate>, Self::Error> {
+        Ok(templates_with_status(&tx.state, TemplateStatus::Active, limit))
+    }
+
+    fn get_pending_templates(
+        &self,
+        tx: &mut Self::DbTransaction<'_>,
+        limit: usize,
+    ) -> Result<Vec<DbTemplate>, Self::Error> {
+        Ok(templates_with_status(&tx.state, TemplateStatus::Pending, limit))
+    }
+
+    fn insert_template(&self, tx: &mut Self::DbTransaction<'_>, template: DbTemplate) -> Result<(), Self::Error> {
+        if self.template_exists(tx, template.template_address.as_slice())? {
+            return Err(StorageError::QueryError {
+                reason: format!("Template {} already exists", template.template_address),
+            });
+        }
+        tx.state_mut().templates.push(template);
+        Ok(())
+    }
+
+    fn update_template(
+        &self,
+        tx: &mut Self::DbTransaction<'_>,
+        key: &[u8],
+        template: DbTemplateUpdate,
+    ) -> Result<(), Self::Error> {
+        let Some(existing) = tx
+            .state_mut()
+            .templates
+            .iter_mut()
+            .find(|t| t.template_address.as_slice() == key)
+        else {
+            return Ok(());
+        };
+        if let Some(compiled_code) = template.compiled_code {
+            existing.compiled_code = Some(compiled_code);
+        }
+        if let Some(flow_json) = template.flow_json {
+            existing.flow_json = Some(flow_json);
+        }
+        if let Some(manifest) = template.manifest {
+            existing.manifest = Some(manifest);
+        }
+        if let Some(status) = template.status {
+            existing.status = status;
+        }
+        Ok(())
+    }
+
+    fn insert_validator_node(
+        &self,
+        tx: &mut Self::DbTransaction<'_>,
+        public_key: PublicKey,
+        shard_key: ShardId,
+        epoch: Epoch,
+        fee_claim_public_key: PublicKey,
+    ) -> Result<(), Self::Error> {
+        // As with the SQLite adapter, all registrations for the validator node take on the latest fee claim key
+        let validator_nodes = &mut tx.state_mut().validator_nodes;
+        for vn in validator_nodes.iter_mut().filter(|vn| vn.address == public_key) {
+            vn.fee_claim_public_key = fee_claim_public_key.clone();
+        }
+        validator_nodes.push(ValidatorNode {
+            address: public_key,
+            shard_key,
+            epoch,
+            committee_bucket: None,
+            fee_claim_public_key,
+        });
+        Ok(())
+    }
+
+    fn get_validator_nodes_within_epochs(
+        &self,
+        tx: &mut Self::DbTransaction<'_>,
+        start_epoch: Epoch,
+        end_epoch: Epoch,
+    ) -> Result<Vec<ValidatorNode<PublicKey>>, Self::Error> {
+        Ok(distinct_validators_sorted(
+            validators_within_epochs(&tx.state, start_epoch, end_epoch).cloned(),
+        ))
+    }
+
+    fn get_validator_node(
+        &self,
+        tx: &mut Self::DbTransaction<'_>,
+        start_epoch: Epoch,
+        end_epoch: Epoch,
+        public_key: &[u8],
+    ) -> Result<ValidatorNode<PublicKey>, Self::Error> {
+        validators_within_epochs(&tx.state, start_epoch, end_epoch)
+            .filter(|vn| vn.address.as_bytes() == public_key)
+            // Last one inserted
+            .last()
+            .cloned()
+            .ok_or_else(|| StorageError::NotFound {
+                item: "ValidatorNode".to_string(),
+                key: to_hex(public_key),
+            })
+    }
+
+    fn validator_nodes_count(
+        &self,
+        tx: &mut Self::DbTransaction<'_>,
+        start_epoch: Epoch,
+        end_epoch: Epoch,
+    ) -> Result<u64, Self::Error> {
+        let count = validators_within_epochs(&tx.state, start_epoch, end_epoch)
+            .map(|vn| &vn.address)
+            .collect::<HashSet<_>>()
+            .len();
+        Ok(count as u64)
+    }
+
+    fn validator_nodes_count_for_bucket(
+        &self,
+        tx: &mut Self::DbTransaction<'_>,
+        start_epoch: Epoch,
+        end_epoch: Epoch,
+        bucket: ShardBucket,
+    ) -> Result<u64, Self::Error> {
+        let count = validators_within_epochs(&tx.state, start_epoch, end_epoch)
+            .filter(|vn| vn.committee_bucket == Some(bucket))
+            .map(|vn| &vn.address)
+            .collect::<HashSet<_>>()
+            .len();
+        Ok(count as u64)
+    }
+
+    fn validator_nodes_set_committee_bucket(
+        &self,
+        tx: &mut Self::DbTransaction<'_>,
+        shard_key: ShardId,
+        bucket: ShardBucket,
+    ) -> Result<(), Self::Error> {
+        let validator_nodes = &mut tx.state_mut().validator_nodes;
+        for vn in validator_nodes.iter_mut().filter(|vn| vn.shard_key == shard_key) {
+            vn.committee_bucket = Some(bucket);
+        }
+        Ok(())
+    }
+
+    fn validator_nodes_get_by_shard_range(
+        &self,
+        tx: &mut Self::DbTransaction<'_>,
+        start_epoch: Epoch,
+        end_epoch: Epoch,
+        shard_range: RangeInclusive<ShardId>,
+    ) -> Result<Vec<ValidatorNode<PublicKey>>, Self::Error> {
+        Ok(distinct_validators_sorted(
+            validators_within_epochs(&tx.state, start_epoch, end_epoch)
+                .filter(|vn| shard_range.contains(&vn.shard_key))
+                .cloned(),
+        ))
+    }
+
+    fn validator_nodes_get_by_buckets(
+        &self,
+        tx: &mut Self::DbTransaction<'_>,
+        start_epoch: Epoch,
+        end_epoch: Epoch,
+        buckets: HashSet<ShardBucket>,
+    ) -> Result<HashMap<ShardBucket, Committee<PublicKey>>, Self::Error> {
+        let validators = distinct_validators_sorted(
+            validators_within_epochs(&tx.state, start_epoch, end_epoch)
+                .filter(|vn| vn.committee_bucket.map_or(false, |b| buckets.contains(&b)))
+                .cloned(),
+        );
+
+        let mut committees = buckets
+            .into_iter()
+            .map(|bucket| (bucket, Committee::empty()))
+            .collect::<HashMap<_, _>>();
+        for validator in validators {
+            let Some(bucket) = validator.committee_bucket else {
+                continue;
+            };
+            committees.get_mut(&bucket).unwrap().members.push(validator.address);
+        }
+
+        Ok(committees)
+    }
+
+    fn insert_epoch(&self, tx: &mut Self::DbTransaction<'_>, epoch: DbEpoch) -> Result<(), Self::Error> {
+        if tx.state.epochs.contains_key(&epoch.epoch) {
+            return Err(StorageError::QueryError {
+                reason: format!("Epoch {} already exists", epoch.epoch),
+            });
+        }
+        tx.state_mut().epochs.insert(epoch.epoch, epoch);
+        Ok(())
+    }
+
+    fn get_epoch(&self, tx: &mut Self::DbTransaction<'_>, epoch: u64) -> Result<Option<DbEpoch>, Self::Error> {
+        Ok(tx.state.epochs.get(&epoch).cloned())
+    }
+
+    fn insert_bmt(
+        &self,
+        tx: &mut Self::DbTransaction<'_>,
+        epoch: u64,
+        bmt: ValidatorNodeBalancedMerkleTree,
+    ) -> Result<(), Self::Error> {
+        if tx.state.bmts.contains_key(&epoch) {
+            return Err(StorageError::QueryError {
+                reason: format!("BMT for epoch {} already exists", epoch),
+            });
+        }
+        let bmt = tari_bor::encode(&bmt).map_err(|e| StorageError::EncodingError {
+            operation: "insert_bmt",
+            item: "bmt",
+            details: e.to_string(),
+        })?;
+        tx.state_mut().bmts.insert(epoch, bmt);
+        Ok(())
+    }
+
+    fn get_bmt(
+        &self,
+        tx: &mut Self::DbTransaction<'_>,
+        epoch: Epoch,
+    ) -> Result<Option<ValidatorNodeBalancedMerkleTree>, Self::Error> {
+        tx.state
+            .bmts
+            .get(&epoch.as_u64())
+            .map(|bmt| {
+                tari_bor::decode(bmt).map_err(|e| StorageError::DecodingError {
+                    operation: "get_bmt",
+                    item: "bmt",
+                    details: e.to_string(),
+                })
+            })
+            .transpose()
+    }
+}
+
+fn templates_with_status(state: &MemoryGlobalDbState, status: TemplateStatus, limit: usize) -> Vec<DbTemplate> {
+    state
+        .templates
+        .iter()
+        .filter(|t| t.status == status)
+        .take(limit)
+        .cloned()
+        .collect()
+}
+
+fn validators_within_epochs(
+    state: &MemoryGlobalDbState,
+    start_epoch: Epoch,
+    end_epoch: Epoch,
+) -> impl Iterator<Item = &ValidatorNode<PublicKey>> + '_ {
+    state
+        .validator_nodes
+        .iter()
+        .filter(move |vn| vn.epoch >= start_epoch && vn.epoch <= end_epoch)
+}
+
+/// Keeps the last registration of each validator node and sorts them by shard key, matching the SQLite adapter
+fn distinct_validators_sorted<I: IntoIterator<Item = ValidatorNode<PublicKey>>>(
+    validators: I,
+) -> Vec<ValidatorNode<PublicKey>> {
+    let mut distinct = Vec::new();
+    let mut dedup_map = HashMap::new();
+    for vn in validators {
+        if let Some(idx) = dedup_map.insert(vn.address.clone(), distinct.len()) {
+            distinct[idx] = None;
+        }
+        distinct.push(Some(vn));
+    }
+    let mut validators = distinct.into_iter().flatten().collect::<Vec<_>>();
+    validators.sort_by(|a, b| a.shard_key.cmp(&b.shard_key));
+    validators
+}
@@ -20,7 +20,7 @@
 //  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 //  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 mod global_db;
-pub use global_db::{DbFactory, GlobalDb};
+pub use global_db::{DbFactory, GlobalDb, GLOBAL_DB_SCHEMA_VERSION};
 
 mod backend_adapter;
 pub use backend_adapter::GlobalDbAdapter;
@@ -40,4 +40,7 @@ pub use epoch_db::{DbEpoch, EpochDb};
 mod bmt_db;
 pub use bmt_db::{BmtDb, DbBmt};
 
+mod memory_adapter;
+pub use memory_adapter::{MemoryGlobalDbAdapter, MemoryGlobalDbTransaction};
+
 pub mod models;
@@ -5,11 +5,13 @@ use std::{
     borrow::Borrow,
     collections::HashSet,
     ops::{Deref, DerefMut, RangeInclusive},
+    time::Duration,
 };
 
 use serde::{Deserialize, Serialize};
 use tari_common_types::types::FixedHash;
-use tari_dan_common_types::{Epoch, NodeAddressable, NodeHeight, ShardId};
+use tari_dan_common_types::{Epoch, NodeAddressable, NodeHeight, ShardId, View};
+use tari_engine_types::substate::SubstateAddress;
 use tari_transaction::{Transaction, TransactionId};
 
 use crate::{
@@ -30,8 +32,10 @@ use crate::{
         QuorumCertificate,
         SubstateLockFlag,
         SubstateLockState,
+        SubstatePruneMark,
         SubstateRecord,
         TransactionAtom,
+        TransactionFeeInfo,
         TransactionPoolRecord,
         TransactionPoolStage,
         TransactionPoolStatusUpdate,
@@ -160,7 +164,26 @@ pub trait StateStoreReadTransaction {
         transaction_id: &TransactionId,
     ) -> Result<TransactionPoolRecord, StorageError>;
     fn transaction_pool_exists(&mut self, transaction_id: &TransactionId) -> Result<bool, StorageError>;
-    fn transaction_pool_get_many_ready(&mut self, max_txs: usize) -> Result<Vec<TransactionPoolRecord>, StorageError>;
+    /// Returns the transactions in the pool that are no longer new, because their stage has been committed or they
+    /// were proposed in a block after the locked block, in transaction id order. The stage of each transaction
+    /// includes updates from blocks that have not been committed.
+    fn transaction_pool_get_in_progress(&mut self) -> Result<Vec<TransactionPoolRecord>, StorageError>;
+    /// Returns up to `limit` transactions whose committed stage is new in descending order of fee priority, ties
+    /// broken by transaction id, starting after the `(fee_priority, transaction_id)` given in `after`. The stage of
+    /// each transaction includes updates from blocks that have not been committed, so a transaction that was proposed
+    /// since may be returned as in progress.
+    fn transaction_pool_get_new_by_fee_priority(
+        &mut self,
+        after: Option<(u64, &TransactionId)>,
+        limit: usize,
+    ) -> Result<Vec<TransactionPoolRecord>, StorageError>;
+    /// Returns up to `limit` transactions in the pool in transaction id order, starting after `after` if given. The
+    /// stage of each transaction includes updates from blocks that have not been committed.
+    fn transaction_pool_get_page(
+        &mut self,
+        after: Option<&TransactionId>,
+        limit: usize,
+    ) -> Result<Vec<TransactionPoolRecord>, StorageError>;
     fn transaction_pool_count(
         &mut self,
         stage: Option<TransactionPoolStage>,
@@ -197,6 +220,24 @@ pub trait StateStoreReadTransaction {
         end: &ShardId,
         exclude_shards: &[ShardId],
     ) -> Result<Vec<SubstateRecord>, StorageError>;
+    /// Returns up to `limit` live (not destroyed) substates with shard ids within `start..=end`, ordered by shard id.
+    /// If `after` is given, only substates with a shard id greater than it are returned.
+    fn substates_get_live_page_within_range(
+        &mut self,
+        start: &ShardId,
+        end: &ShardId,
+        after: Option<&ShardId>,
+        limit: usize,
+    ) -> Result<Vec<SubstateRecord>, StorageError>;
+    /// Returns up to `limit` substates, including destroyed ones, with shard ids within `start..=end`, ordered by
+    /// shard id. If `after` is given, only substates with a shard id greater than it are returned.
+    fn substates_get_page_within_range(
+        &mut self,
+        start: &ShardId,
+        end: &ShardId,
+        after: Option<&ShardId>,
+        limit: usize,
+    ) -> Result<Vec<SubstateRecord>, StorageError>;
     fn substates_get_many_by_created_transaction(
         &mut self,
         tx_id: &TransactionId,
@@ -211,6 +252,12 @@ pub trait StateStoreReadTransaction {
         &mut self,
         transaction_id: &TransactionId,
     ) -> Result<Vec<SubstateRecord>, StorageError>;
+    /// Returns all stored versions of the substate at `address`, ordered by version ascending
+    fn substates_get_all_versions(&mut self, address: &SubstateAddress) -> Result<Vec<SubstateRecord>, StorageError>;
+    /// Returns the highest stored version of the substate at `address`
+    fn substates_get_latest_version(&mut self, address: &SubstateAddress) -> Result<SubstateRecord, StorageError>;
+    /// Returns how far back destroyed substate versions have been pruned, or None if none have been
+    fn substates_get_prune_mark(&mut self) -> Result<Option<SubstatePruneMark>, StorageError>;
     fn substates_check_lock_many<'a, I: IntoIterator<Item = &'a ShardId>>(
         &mut self,
         objects: I,
@@ -221,6 +268,7 @@ pub trait StateStoreReadTransaction {
     where
         I: IntoIterator<Item = B>,
         B: Borrow<ShardId>;
+    fn locked_outputs_get_all(&mut self) -> Result<Vec<LockedOutput>, StorageError>;
 }
 
 pub trait StateStoreWriteTransaction {
@@ -263,6 +311,7 @@ pub trait StateStoreWriteTransaction {
     fn transaction_pool_insert(
         &mut self,
         transaction: TransactionAtom,
+        fee_info: &TransactionFeeInfo,
         stage: TransactionPoolStage,
         is_ready: bool,
     ) -> Result<(), StorageError>;
@@ -299,7 +348,7 @@ pub trait StateStoreWriteTransaction {
 
     fn missing_transactions_remove(
         &mut self,
-        current_height: NodeHeight,
+        current_view: View,
         transaction_id: &TransactionId,
     ) -> Result<Option<Block<Self::Addr>>, StorageError>;
 
@@ -331,6 +380,8 @@ pub trait StateStoreWriteTransaction {
         require_locks: bool,
     ) -> Result<(), StorageError>;
     fn substates_create(&mut self, substate: SubstateRecord) -> Result<(), StorageError>;
+    /// Deletes the given substates, skipping any that do not exist. Returns the number of substates deleted.
+    fn substates_delete_many<I: IntoIterator<Item = ShardId>>(&mut self, shard_ids: I) -> Result<usize, StorageError>;
     // -------------------------------- Locked Outputs -------------------------------- //
     fn locked_outputs_acquire_all<I, B>(
         &mut self,
@@ -346,6 +397,31 @@ pub trait StateStoreWriteTransaction {
     where
         I: IntoIterator<Item = B>,
         B: Borrow<ShardId>;
+
+    // -------------------------------- Pruning -------------------------------- //
+    // Each prune call is bounded by `limit` so that a pruning pass can be split into bounded write
+    // transactions. Callers commit and repeat the call until it returns fewer than `limit`.
+
+    /// Deletes the votes for blocks at or below the given height, stopping once `limit` votes have been deleted. A
+    /// backend may finish deleting the votes of the block it is on, so the count can exceed `limit`. Returns the number
+    /// of votes deleted.
+    fn votes_prune_up_to_height(&mut self, height: NodeHeight, limit: usize) -> Result<usize, StorageError>;
+    /// Deletes up to `limit` blocks at or below the given height that were never committed, along with the quorum
+    /// certificates and pending transaction pool updates that only they reference. Committed blocks are always kept.
+    /// Returns the number of blocks deleted.
+    fn blocks_prune_uncommitted_up_to_height(
+        &mut self,
+        height: NodeHeight,
+        limit: usize,
+    ) -> Result<usize, StorageError>;
+    /// Deletes up to `limit` substate versions that were destroyed before the given epoch and advances the prune mark
+    /// so that queries for the deleted history can be answered as unavailable. Returns the number of substates
+    /// deleted.
+    fn substates_prune_destroyed_before_epoch(&mut self, epoch: Epoch, limit: usize) -> Result<usize, StorageError>;
+    /// Deletes up to `limit` transaction pool history entries older than `retention` of transactions that are no
+    /// longer in the pool. A backend that does not record a transaction pool history has nothing to delete. Returns
+    /// the number of history entries deleted.
+    fn transaction_pool_history_prune(&mut self, retention: Duration, limit: usize) -> Result<usize, StorageError>;
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
@@ -0,0 +1,172 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use rand::rngs::OsRng;
+use tari_common_types::types::PublicKey;
+use tari_crypto::keys::PublicKey as _;
+use tari_dan_common_types::{shard_bucket::ShardBucket, Epoch, ShardId};
+use tari_dan_storage::{
+    global::{GlobalDb, MemoryGlobalDbAdapter, MetadataKey, ValidatorNodeDb, GLOBAL_DB_SCHEMA_VERSION},
+    StorageError,
+};
+use tari_utilities::ByteArray;
+
+fn create_db() -> GlobalDb<MemoryGlobalDbAdapter> {
+    GlobalDb::new(MemoryGlobalDbAdapter::new())
+}
+
+fn new_public_key() -> PublicKey {
+    PublicKey::random_keypair(&mut OsRng).1
+}
+
+fn derived_shard_id(public_key: &PublicKey) -> ShardId {
+    ShardId::from_bytes(public_key.as_bytes()).unwrap()
+}
+
+fn insert_vn_with_public_key(
+    validator_nodes: &mut ValidatorNodeDb<'_, '_, MemoryGlobalDbAdapter>,
+    public_key: PublicKey,
+    epoch: Epoch,
+) {
+    validator_nodes
+        .insert_validator_node(public_key.clone(), derived_shard_id(&public_key), epoch, public_key)
+        .unwrap()
+}
+
+#[test]
+fn insert_and_get_within_epoch_duplicate_public_keys() {
+    let db = create_db();
+    let mut tx = db.create_transaction().unwrap();
+    let mut validator_nodes = db.validator_nodes(&mut tx);
+    insert_vn_with_public_key(&mut validator_nodes, new_public_key(), Epoch(0));
+    insert_vn_with_public_key(&mut validator_nodes, new_public_key(), Epoch(10));
+    let pk = new_public_key();
+    insert_vn_with_public_key(&mut validator_nodes, pk.clone(), Epoch(0));
+    insert_vn_with_public_key(&mut validator_nodes, pk.clone(), Epoch(1));
+    // Outside of the epoch range
+    insert_vn_with_public_key(&mut validator_nodes, new_public_key(), Epoch(11));
+
+    let vns = validator_nodes.get_all_within_epochs(Epoch(0), Epoch(10)).unwrap();
+    assert_eq!(vns.len(), 3);
+    assert!(vns.windows(2).all(|w| w[0].shard_key <= w[1].shard_key));
+    assert_eq!(validator_nodes.count(Epoch(0), Epoch(10)).unwrap(), 3);
+
+    let vn = validator_nodes.get(Epoch(0), Epoch(10), pk.as_bytes()).unwrap();
+    assert_eq!(vn.epoch, Epoch(1));
+    let err = validator_nodes
+        .get(Epoch(0), Epoch(10), new_public_key().as_bytes())
+        .unwrap_err();
+    assert!(matches!(err, StorageError::NotFound { .. }));
+}
+
+#[test]
+fn get_committees_by_bucket() {
+    let db = create_db();
+    let mut tx = db.create_transaction().unwrap();
+    let mut validator_nodes = db.validator_nodes(&mut tx);
+    let pk1 = new_public_key();
+    let pk2 = new_public_key();
+    insert_vn_with_public_key(&mut validator_nodes, pk1.clone(), Epoch(0));
+    insert_vn_with_public_key(&mut validator_nodes, pk2.clone(), Epoch(0));
+    validator_nodes
+        .set_committee_bucket(derived_shard_id(&pk1), ShardBucket::from(0))
+        .unwrap();
+    validator_nodes
+        .set_committee_bucket(derived_shard_id(&pk2), ShardBucket::from(1))
+        .unwrap();
+
+    let committees = validator_nodes
+        .get_committees_by_buckets(Epoch(0), Epoch(0), [ShardBucket::from(0)].into_iter().collect())
+        .unwrap();
+    assert_eq!(committees.len(), 1);
+    assert_eq!(committees[&ShardBucket::from(0)].members, vec![pk1]);
+    assert_eq!(
+        validator_nodes
+            .count_in_bucket(Epoch(0), Epoch(0), ShardBucket::from(1))
+            .unwrap(),
+        1
+    );
+}
+
+#[test]
+fn uncommitted_writes_are_discarded() {
+    let db = create_db();
+    let mut tx = db.create_transaction().unwrap();
+    db.metadata(&mut tx)
+        .set_metadata(MetadataKey::EpochManagerCurrentEpoch, &Epoch(5))
+        .unwrap();
+    drop(tx);
+
+    let mut tx = db.create_transaction().unwrap();
+    let epoch = db
+        .metadata(&mut tx)
+        .get_metadata::<Epoch>(MetadataKey::EpochManagerCurrentEpoch)
+        .unwrap();
+    assert_eq!(epoch, None);
+
+    db.metadata(&mut tx)
+        .set_metadata(MetadataKey::EpochManagerCurrentEpoch, &Epoch(5))
+        .unwrap();
+    db.commit(tx).unwrap();
+
+    let mut tx = db.create_transaction().unwrap();
+    let epoch = db
+        .metadata(&mut tx)
+        .get_metadata::<Epoch>(MetadataKey::EpochManagerCurrentEpoch)
+        .unwrap();
+    assert_eq!(epoch, Some(Epoch(5)));
+}
+
+#[test]
+fn schema_version_is_stamped_and_checked() {
+    let db = create_db();
+    db.check_schema_version().unwrap();
+    let mut tx = db.create_transaction().unwrap();
+    let version = db
+        .metadata(&mut tx)
+        .get_metadata::<u32>(MetadataKey::GlobalDbSchemaVersion)
+        .unwrap();
+    assert_eq!(version, Some(GLOBAL_DB_SCHEMA_VERSION));
+
+    db.metadata(&mut tx)
+        .set_metadata(MetadataKey::GlobalDbSchemaVersion, &(GLOBAL_DB_SCHEMA_VERSION + 1))
+        .unwrap();
+    db.commit(tx).unwrap();
+
+    let err = db.check_schema_version().unwrap_err();
+    assert!(matches!(err, StorageError::IncompatibleSchemaVersion { .. }));
+}
+
+#[test]
+fn concurrent_transactions_do_not_block_and_conflicting_writes_are_rejected() {
+    let db = create_db();
+    let mut tx1 = db.create_transaction().unwrap();
+    let mut tx2 = db.create_transaction().unwrap();
+    let mut reader = db.create_transaction().unwrap();
+
+    db.metadata(&mut tx1)
+        .set_metadata(MetadataKey::EpochManagerCurrentEpoch, &Epoch(1))
+        .unwrap();
+    db.metadata(&mut tx2)
+        .set_metadata(MetadataKey::EpochManagerCurrentEpoch, &Epoch(2))
+        .unwrap();
+    db.commit(tx1).unwrap();
+
+    let err = db.commit(tx2).unwrap_err();
+    assert!(matches!(err, StorageError::General { .. }));
+
+    // Read-only transactions commit regardless of later writes
+    let epoch = db
+        .metadata(&mut reader)
+        .get_metadata::<Epoch>(MetadataKey::EpochManagerCurrentEpoch)
+        .unwrap();
+    assert_eq!(epoch, None);
+    db.commit(reader).unwrap();
+
+    let mut tx = db.create_transaction().unwrap();
+    let epoch = db
+        .metadata(&mut tx)
+        .get_metadata::<Epoch>(MetadataKey::EpochManagerCurrentEpoch)
+        .unwrap();
+    assert_eq!(epoch, Some(Epoch(1)));
+}
@@ -20,9 +20,12 @@
 //  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 //  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use std::{fs::create_dir_all, path::PathBuf};
+use std::{
+    fs::create_dir_all,
+    path::{Path, PathBuf},
+};
 
-use diesel::{sql_query, Connection, RunQueryDsl, SqliteConnection};
+use diesel::{sql_query, sql_types::Text, Connection, RunQueryDsl, SqliteConnection};
 use tari_dan_storage::{
     global::{DbFactory, GlobalDb},
     StorageError,
@@ -49,6 +52,22 @@ impl SqliteDbFactory {
         let connection = SqliteConnection::establish(&database_url).map_err(SqliteStorageError::from)?;
         Ok(connection)
     }
+
+    /// Writes a consistent copy of the global database to `path`, which must not exist
+    pub fn backup_global_db_to(&self, path: &Path) -> Result<(), StorageError> {
+        let path = path.to_str().ok_or_else(|| StorageError::General {
+            details: format!("Backup path {} is not valid UTF-8", path.display()),
+        })?;
+        let mut connection = self.connect()?;
+        sql_query("VACUUM INTO ?")
+            .bind::<Text, _>(path)
+            .execute(&mut connection)
+            .map_err(|source| SqliteStorageError::DieselError {
+                source,
+                operation: "backup global db".to_string(),
+            })?;
+        Ok(())
+    }
 }
 
 impl DbFactory for SqliteDbFactory {
@@ -68,6 +87,7 @@ impl DbFactory for SqliteDbFactory {
     fn migrate(&self) -> Result<(), StorageError> {
         let connection = self.get_or_create_global_db()?;
         connection.adapter().migrate()?;
+        connection.check_schema_version()?;
 
         Ok(())
     }
@@ -6,7 +6,10 @@ use rand::rngs::OsRng;
 use tari_common_types::types::PublicKey;
 use tari_crypto::keys::PublicKey as _;
 use tari_dan_common_types::{Epoch, ShardId};
-use tari_dan_storage::global::{GlobalDb, ValidatorNodeDb};
+use tari_dan_storage::{
+    global::{GlobalDb, MetadataKey, ValidatorNodeDb, GLOBAL_DB_SCHEMA_VERSION},
+    StorageError,
+};
 use tari_dan_storage_sqlite::global::SqliteGlobalDbAdapter;
 use tari_utilities::ByteArray;
 
@@ -103,3 +106,23 @@ fn insert_and_get_within_shard_range_duplicate_public_keys() {
     }
     assert_eq!(vns.len(), 2);
 }
+
+#[test]
+fn schema_version_is_stamped_and_checked() {
+    let db = create_db();
+    db.check_schema_version().unwrap();
+    let mut tx = db.create_transaction().unwrap();
+    let version = db
+        .metadata(&mut tx)
+        .get_metadata::<u32>(MetadataKey::GlobalDbSchemaVersion)
+        .unwrap();
+    assert_eq!(version, Some(GLOBAL_DB_SCHEMA_VERSION));
+
+    db.metadata(&mut tx)
+        .set_metadata(MetadataKey::GlobalDbSchemaVersion, &(GLOBAL_DB_SCHEMA_VERSION + 1))
+        .unwrap();
+    db.commit(tx).unwrap();
+
+    let err = db.check_schema_version().unwrap_err();
+    assert!(matches!(err, StorageError::IncompatibleSchemaVersion { .. }));
+}
@@ -21,7 +21,7 @@ use tari_dan_engine::{
     bootstrap_state,
     fees::{FeeModule, FeeTable},
     packager::{LoadedTemplate, Package, TemplateModuleLoader},
-    runtime::{AuthParams, RuntimeModule, VirtualSubstates},
+    runtime::{AuthParams, ReadOnlyModule, RuntimeModule, VirtualSubstates},
     state_store::{
         memory::{MemoryStateStore, MemoryWriteTransaction},
         AtomicDb,
@@ -71,6 +71,8 @@ pub struct TemplateTest {
     enable_fees: bool,
     fee_table: FeeTable,
     virtual_substates: VirtualSubstates,
+    execution_trace: bool,
+    read_only: Option<usize>,
 }
 
 impl TemplateTest {
@@ -131,6 +133,8 @@ impl TemplateTest {
             last_outputs: HashSet::new(),
             state_store,
             virtual_substates,
+            execution_trace: false,
+            read_only: None,
             enable_fees: false,
             fee_table: FeeTable {
                 per_module_call_cost: 1,
@@ -189,6 +193,18 @@ impl TemplateTest {
         self
     }
 
+    pub fn enable_execution_trace(&mut self) -> &mut Self {
+        self.execution_trace = true;
+        self
+    }
+
+    /// Execute transactions as view calls, which fail if any state is changed or more than `max_runtime_calls` runtime
+    /// calls are made
+    pub fn enable_read_only(&mut self, max_runtime_calls: usize) -> &mut Self {
+        self.read_only = Some(max_runtime_calls);
+        self
+    }
+
     pub fn fee_table(&self) -> &FeeTable {
         &self.fee_table
     }
@@ -397,6 +413,10 @@ impl TemplateTest {
             modules.push(Arc::new(FeeModule::new(0, self.fee_table.clone())));
         }
 
+        if let Some(max_runtime_calls) = self.read_only {
+            modules.push(Arc::new(ReadOnlyModule::new(max_runtime_calls)));
+        }
+
         let auth_params = AuthParams {
             initial_ownership_proofs: proofs,
         };
@@ -406,7 +426,8 @@ impl TemplateTest {
             auth_params,
             self.virtual_substates.clone(),
             modules,
-        );
+        )
+        .with_execution_trace(self.execution_trace);
 
         let tx_id = *transaction.id();
         eprintln!("START Transaction id = \"{}\"", tx_id);
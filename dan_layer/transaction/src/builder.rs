@@ -136,7 +136,11 @@ impl TransactionBuilder {
 
     pub fn sign(mut self, secret_key: &PrivateKey) -> Self {
         // TODO: create proper challenge that signs everything
-        self.signature = Some(TransactionSignature::sign(secret_key, &self.instructions));
+        self.signature = Some(TransactionSignature::sign(
+            secret_key,
+            &self.fee_instructions,
+            &self.instructions,
+        ));
         self
     }
 
@@ -24,11 +24,13 @@ impl TransactionSignature {
         Self { public_key, signature }
     }
 
-    pub fn sign(secret_key: &RistrettoSecretKey, instructions: &[Instruction]) -> Self {
+    pub fn sign(
+        secret_key: &RistrettoSecretKey,
+        fee_instructions: &[Instruction],
+        instructions: &[Instruction],
+    ) -> Self {
         let public_key = RistrettoPublicKey::from_secret_key(secret_key);
-        let challenge = hasher64(EngineHashDomainLabel::InstructionSignature)
-            .chain(instructions)
-            .result();
+        let challenge = Self::challenge(fee_instructions, instructions);
 
         Self {
             signature: Signature::sign(secret_key, challenge, &mut OsRng).unwrap(),
@@ -36,6 +38,20 @@ impl TransactionSignature {
         }
     }
 
+    /// Returns true if the signature was created by the signer's public key over the given fee instructions and
+    /// instructions
+    pub fn verify(&self, fee_instructions: &[Instruction], instructions: &[Instruction]) -> bool {
+        let challenge = Self::challenge(fee_instructions, instructions);
+        self.signature.verify(&self.public_key, challenge)
+    }
+
+    fn challenge(fee_instructions: &[Instruction], instructions: &[Instruction]) -> [u8; 64] {
+        hasher64(EngineHashDomainLabel::InstructionSignature)
+            .chain(fee_instructions)
+            .chain(instructions)
+            .result()
+    }
+
     pub fn signature(&self) -> &Signature {
         &self.signature
     }
@@ -44,3 +60,26 @@ impl TransactionSignature {
         &self.public_key
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use tari_crypto::keys::SecretKey;
+
+    use super::*;
+
+    #[test]
+    fn it_verifies_the_signed_instructions() {
+        let secret_key = RistrettoSecretKey::random(&mut OsRng);
+        let fee_instructions = vec![Instruction::PutLastInstructionOutputOnWorkspace { key: b"fee".to_vec() }];
+        let instructions = vec![Instruction::DropAllProofsInWorkspace];
+        let signature = TransactionSignature::sign(&secret_key, &fee_instructions, &instructions);
+        assert!(signature.verify(&fee_instructions, &instructions));
+        assert!(!signature.verify(&fee_instructions, &[]));
+        // The fee instructions cannot be replaced without invalidating the signature
+        assert!(!signature.verify(&[], &instructions));
+
+        let other_key = RistrettoPublicKey::from_secret_key(&RistrettoSecretKey::random(&mut OsRng));
+        let forged = TransactionSignature::new(other_key, signature.signature().clone());
+        assert!(!forged.verify(&fee_instructions, &instructions));
+    }
+}
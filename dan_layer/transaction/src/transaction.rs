@@ -138,6 +138,16 @@ impl Transaction {
         &mut self.filled_inputs
     }
 
+    /// Adds a filled input unless the shard is already an input, input ref or filled input of this transaction.
+    /// Returns false if the shard was already present.
+    pub fn add_filled_input(&mut self, shard_id: ShardId) -> bool {
+        if self.all_inputs_iter().any(|s| *s == shard_id) {
+            return false;
+        }
+        self.filled_inputs.push(shard_id);
+        true
+    }
+
     pub fn fee_claims(&self) -> impl Iterator<Item = (Epoch, PublicKey)> + '_ {
         self.instructions()
             .iter()
@@ -222,3 +232,30 @@ impl Display for SubstateRequirement {
 #[derive(Debug, thiserror::Error)]
 #[error("Failed to parse substate requirement {0}")]
 pub struct SubstateRequirementParseError(String);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_does_not_fill_inputs_that_are_already_present() {
+        let input = ShardId::from_hash(&[1u8; 32], 0);
+        let input_ref = ShardId::from_hash(&[2u8; 32], 0);
+        let related = ShardId::from_hash(&[3u8; 32], 0);
+        let mut transaction = Transaction::builder()
+            .add_input(input)
+            .add_input_ref(input_ref)
+            .sign(&Default::default())
+            .build();
+
+        assert!(!transaction.add_filled_input(input));
+        assert!(!transaction.add_filled_input(input_ref));
+        assert!(transaction.add_filled_input(related));
+        // The same related substate found through two different inputs
+        assert!(!transaction.add_filled_input(related));
+
+        assert_eq!(transaction.filled_inputs(), [related]);
+        let mut seen = std::collections::HashSet::new();
+        assert!(transaction.all_inputs_iter().all(|s| seen.insert(*s)));
+    }
+}
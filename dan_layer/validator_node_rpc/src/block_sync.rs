@@ -5,7 +5,15 @@ use crate::{
     proto,
     proto::{
         consensus::{Block, QuorumCertificate},
-        rpc::{sync_blocks_response::SyncData, QuorumCertificates, SubstateUpdate, Transactions},
+        rpc::{
+            sync_blocks_response::SyncData,
+            sync_state_snapshot_response::SnapshotData,
+            QuorumCertificates,
+            StateSnapshotCheckpoint,
+            SubstateRecord,
+            SubstateUpdate,
+            Transactions,
+        },
         transaction::Transaction,
     },
 };
@@ -46,3 +54,19 @@ impl proto::rpc::SyncBlocksResponse {
         }
     }
 }
+
+impl proto::rpc::SyncStateSnapshotResponse {
+    pub fn into_checkpoint(self) -> Option<StateSnapshotCheckpoint> {
+        match self.snapshot_data {
+            Some(SnapshotData::Checkpoint(checkpoint)) => Some(checkpoint),
+            _ => None,
+        }
+    }
+
+    pub fn into_substate(self) -> Option<SubstateRecord> {
+        match self.snapshot_data {
+            Some(SnapshotData::Substate(substate)) => Some(substate),
+            _ => None,
+        }
+    }
+}
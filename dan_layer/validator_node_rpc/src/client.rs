@@ -7,7 +7,7 @@ use anyhow::anyhow;
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use tari_bor::{decode, decode_exact, encode};
-use tari_common_types::types::PublicKey;
+use tari_common_types::types::{PublicKey, Signature};
 use tari_comms::{
     connectivity::ConnectivityRequester,
     peer_manager::{NodeId, PeerIdentityClaim},
@@ -16,23 +16,32 @@ use tari_comms::{
     PeerConnection,
 };
 use tari_crypto::tari_utilities::ByteArray;
-use tari_dan_common_types::{NodeAddressable, ShardId};
+use tari_dan_common_types::{Epoch, NodeAddressable, ShardId};
 use tari_dan_p2p::DanPeer;
-use tari_dan_storage::consensus_models::Decision;
+use tari_dan_storage::consensus_models::{Decision, SubstateHistory, SubstateQueryPoint, SubstateRecord};
 use tari_engine_types::{
     commit_result::ExecuteResult,
+    instruction_result::InstructionResult,
     substate::{Substate, SubstateAddress, SubstateValue},
     virtual_substate::{VirtualSubstate, VirtualSubstateAddress},
 };
+use tari_template_lib::{
+    args::Arg,
+    models::{ComponentAddress, TemplateAddress},
+};
 use tari_transaction::{Transaction, TransactionId};
 use tokio_stream::StreamExt;
 
 use crate::{
     proto,
     proto::rpc::{
+        GetEpochHandoffRequest,
         GetPeersRequest,
+        GetTemplateBinaryRequest,
         GetTransactionResultRequest,
+        InvokeReadMethodRequest,
         PayloadResultStatus,
+        SubmitDevTemplateRequest,
         SubmitTransactionRequest,
         SubstateStatus,
     },
@@ -62,6 +71,36 @@ pub trait ValidatorNodeRpcClient: Send + Sync {
 
     async fn get_substate(&mut self, shard: ShardId) -> Result<SubstateResult, Self::Error>;
     async fn get_virtual_substate(&mut self, address: VirtualSubstateAddress) -> Result<VirtualSubstate, Self::Error>;
+
+    async fn get_substate_history(&mut self, address: &SubstateAddress) -> Result<SubstateHistory, Self::Error>;
+
+    async fn get_substate_at(
+        &mut self,
+        address: &SubstateAddress,
+        at: SubstateQueryPoint,
+    ) -> Result<Option<SubstateRecord>, Self::Error>;
+
+    /// Calls a method that does not mutate state against the latest committed state of the component
+    async fn invoke_read_method(
+        &mut self,
+        component_address: ComponentAddress,
+        method: String,
+        args: Vec<Arg>,
+    ) -> Result<InstructionResult, Self::Error>;
+
+    /// Requests a page of the pending transactions from a member of the outgoing committee so that they can be picked
+    /// up by the committee for `epoch`. Pass the `next` value of the previous page as `after` to fetch the next page.
+    async fn get_epoch_handoff(
+        &mut self,
+        epoch: Epoch,
+        after: Option<TransactionId>,
+    ) -> Result<EpochHandoff, Self::Error>;
+
+    /// Submits a template registered with another validator node on a development network
+    async fn submit_dev_template(&mut self, template: SignedDevTemplate) -> Result<TemplateAddress, Self::Error>;
+
+    /// Fetches the binary of an active template. The caller is responsible for verifying the binary hash.
+    async fn get_template_binary(&mut self, template_address: TemplateAddress) -> Result<Vec<u8>, Self::Error>;
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -93,6 +132,23 @@ pub enum SubstateResult {
     },
 }
 
+#[derive(Debug, Clone)]
+pub struct EpochHandoff {
+    pub pending_transactions: Vec<Transaction>,
+    /// The transaction id to request the next page after, or None if this is the last page
+    pub next: Option<TransactionId>,
+}
+
+/// A WASM template registered directly with a validator node on a development network. The signature is made by the
+/// validator node over the hash of the binary and the template name.
+#[derive(Debug, Clone)]
+pub struct SignedDevTemplate {
+    pub template_name: String,
+    pub binary: Vec<u8>,
+    pub signer_public_key: PublicKey,
+    pub signature: Signature,
+}
+
 pub struct TariCommsValidatorNodeRpcClient {
     connectivity: ConnectivityRequester,
     address: PublicKey,
@@ -241,6 +297,137 @@ impl ValidatorNodeRpcClient for TariCommsValidatorNodeRpcClient {
         decode_exact(&resp.substate).map_err(|e| ValidatorNodeRpcClientError::InvalidResponse(anyhow!(e)))
     }
 
+    async fn get_substate_history(&mut self, address: &SubstateAddress) -> Result<SubstateHistory, Self::Error> {
+        let mut client = self.client_connection().await?;
+
+        let request = proto::rpc::GetSubstateHistoryRequest {
+            address: address.to_bytes(),
+        };
+        let resp = client.get_substate_history(request).await?;
+
+        let versions = resp
+            .versions
+            .into_iter()
+            .map(SubstateRecord::try_from)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(ValidatorNodeRpcClientError::InvalidResponse)?;
+        if versions.iter().any(|s| s.address != *address) {
+            return Err(ValidatorNodeRpcClientError::InvalidResponse(anyhow!(
+                "Node returned a substate for a different address"
+            )));
+        }
+
+        Ok(SubstateHistory {
+            versions,
+            pruned_before_epoch: Some(Epoch(resp.pruned_before_epoch)).filter(|epoch| !epoch.is_zero()),
+        })
+    }
+
+    async fn get_substate_at(
+        &mut self,
+        address: &SubstateAddress,
+        at: SubstateQueryPoint,
+    ) -> Result<Option<SubstateRecord>, Self::Error> {
+        let mut client = self.client_connection().await?;
+
+        let request = proto::rpc::GetSubstateAtRequest {
+            address: address.to_bytes(),
+            at: Some(at.into()),
+        };
+        let resp = client.get_substate_at(request).await?;
+
+        let substate = resp
+            .substate
+            .map(SubstateRecord::try_from)
+            .transpose()
+            .map_err(ValidatorNodeRpcClientError::InvalidResponse)?;
+        if substate.as_ref().map_or(false, |s| s.address != *address) {
+            return Err(ValidatorNodeRpcClientError::InvalidResponse(anyhow!(
+                "Node returned a substate for a different address"
+            )));
+        }
+
+        Ok(substate)
+    }
+
+    async fn invoke_read_method(
+        &mut self,
+        component_address: ComponentAddress,
+        method: String,
+        args: Vec<Arg>,
+    ) -> Result<InstructionResult, Self::Error> {
+        let mut client = self.client_connection().await?;
+
+        let request = InvokeReadMethodRequest {
+            component_address: encode(&component_address)?,
+            method,
+            args: encode(&args)?,
+        };
+        let resp = client.invoke_read_method(request).await?;
+        let result =
+            decode_exact(&resp.result).map_err(|e| ValidatorNodeRpcClientError::InvalidResponse(anyhow!(e)))?;
+
+        Ok(result)
+    }
+
+    async fn get_epoch_handoff(
+        &mut self,
+        epoch: Epoch,
+        after: Option<TransactionId>,
+    ) -> Result<EpochHandoff, Self::Error> {
+        let mut client = self.client_connection().await?;
+        let resp = client
+            .get_epoch_handoff(GetEpochHandoffRequest {
+                epoch: epoch.as_u64(),
+                after: after.map(|id| id.as_bytes().to_vec()).unwrap_or_default(),
+            })
+            .await?;
+
+        let pending_transactions = resp
+            .pending_transactions
+            .into_iter()
+            .map(Transaction::try_from)
+            .collect::<Result<_, _>>()
+            .map_err(ValidatorNodeRpcClientError::InvalidResponse)?;
+        let next = if resp.next.is_empty() {
+            None
+        } else {
+            Some(
+                TransactionId::try_from(resp.next)
+                    .map_err(|e| ValidatorNodeRpcClientError::InvalidResponse(anyhow!(e)))?,
+            )
+        };
+
+        Ok(EpochHandoff {
+            pending_transactions,
+            next,
+        })
+    }
+
+    async fn submit_dev_template(&mut self, template: SignedDevTemplate) -> Result<TemplateAddress, Self::Error> {
+        let mut client = self.client_connection().await?;
+        let request = SubmitDevTemplateRequest {
+            template_name: template.template_name,
+            binary: template.binary,
+            signer_public_key: template.signer_public_key.to_vec(),
+            signature: Some(template.signature.into()),
+        };
+        let resp = client.submit_dev_template(request).await?;
+        let template_address = TemplateAddress::try_from(resp.template_address)
+            .map_err(|e| ValidatorNodeRpcClientError::InvalidResponse(anyhow!(e)))?;
+
+        Ok(template_address)
+    }
+
+    async fn get_template_binary(&mut self, template_address: TemplateAddress) -> Result<Vec<u8>, Self::Error> {
+        let mut client = self.client_connection().await?;
+        let request = GetTemplateBinaryRequest {
+            template_address: template_address.to_vec(),
+        };
+        let resp = client.get_template_binary(request).await?;
+        Ok(resp.binary)
+    }
+
     async fn get_finalized_transaction_result(
         &mut self,
         transaction_id: TransactionId,
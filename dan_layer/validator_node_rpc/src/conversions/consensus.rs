@@ -37,7 +37,7 @@ use tari_consensus::messages::{
     VoteMessage,
 };
 use tari_crypto::tari_utilities::ByteArray;
-use tari_dan_common_types::{Epoch, NodeAddressable, NodeHeight, ValidatorMetadata};
+use tari_dan_common_types::{Epoch, NodeAddressable, NodeHeight, ValidatorMetadata, View};
 use tari_dan_storage::consensus_models::{
     BlockId,
     Command,
@@ -47,8 +47,10 @@ use tari_dan_storage::consensus_models::{
     QcId,
     QuorumCertificate,
     QuorumDecision,
+    SignedNewView,
     SubstateDestroyed,
     SubstateRecord,
+    TimeoutCertificate,
     TransactionAtom,
 };
 use tari_engine_types::substate::{SubstateAddress, SubstateValue};
@@ -115,9 +117,10 @@ impl<TAddr: NodeAddressable> From<&NewViewMessage<TAddr>> for proto::consensus::
     fn from(value: &NewViewMessage<TAddr>) -> Self {
         Self {
             high_qc: Some((&value.high_qc).into()),
-            new_height: value.new_height.0,
+            new_view: value.new_view.as_u64(),
             epoch: value.epoch.as_u64(),
             last_vote: value.last_vote.as_ref().map(|a| a.into()),
+            signature: Some((&value.signature).into()),
         }
     }
 }
@@ -128,12 +131,16 @@ impl<TAddr: NodeAddressable> TryFrom<proto::consensus::NewViewMessage> for NewVi
     fn try_from(value: proto::consensus::NewViewMessage) -> Result<Self, Self::Error> {
         Ok(NewViewMessage {
             high_qc: value.high_qc.ok_or_else(|| anyhow!("High QC is missing"))?.try_into()?,
-            new_height: value.new_height.into(),
+            new_view: View(value.new_view),
             epoch: Epoch(value.epoch),
             last_vote: value
                 .last_vote
                 .map(|a: proto::consensus::VoteMessage| a.try_into())
                 .transpose()?,
+            signature: value
+                .signature
+                .ok_or_else(|| anyhow!("Signature is missing"))?
+                .try_into()?,
         })
     }
 }
@@ -249,11 +256,15 @@ impl<TAddr: NodeAddressable> From<&tari_dan_storage::consensus_models::Block<TAd
     fn from(value: &tari_dan_storage::consensus_models::Block<TAddr>) -> Self {
         Self {
             height: value.height().as_u64(),
+            view: value.view().as_u64(),
             epoch: value.epoch().as_u64(),
+            epoch_cutoff: value.epoch_cutoff().map(|h| h.as_u64()).unwrap_or_default(),
             parent_id: value.parent().as_bytes().to_vec(),
             proposed_by: value.proposed_by().as_bytes().to_vec(),
             merkle_root: value.merkle_root().as_slice().to_vec(),
             justify: Some(value.justify().into()),
+            timeout_certificate: value.timeout_certificate().map(Into::into),
+            signature: value.signature().map(Into::into),
             total_leader_fee: value.total_leader_fee(),
             commands: value.commands().iter().map(Into::into).collect(),
         }
@@ -266,14 +277,17 @@ impl<TAddr: NodeAddressable + Serialize> TryFrom<proto::consensus::Block>
     type Error = anyhow::Error;
 
     fn try_from(value: proto::consensus::Block) -> Result<Self, Self::Error> {
-        Ok(Self::new(
+        let mut block = Self::new(
             value.parent_id.try_into()?,
             value
                 .justify
                 .ok_or_else(|| anyhow!("Block conversion: QC not provided"))?
                 .try_into()?,
+            value.timeout_certificate.map(TryInto::try_into).transpose()?,
             NodeHeight(value.height),
+            View(value.view),
             Epoch(value.epoch),
+            Some(NodeHeight(value.epoch_cutoff)).filter(|h| !h.is_zero()),
             TAddr::from_bytes(&value.proposed_by).ok_or_else(|| anyhow!("Block conversion: Invalid proposed_by"))?,
             value
                 .commands
@@ -281,10 +295,76 @@ impl<TAddr: NodeAddressable + Serialize> TryFrom<proto::consensus::Block>
                 .map(TryInto::try_into)
                 .collect::<Result<_, _>>()?,
             value.total_leader_fee,
+        );
+        if let Some(signature) = value.signature {
+            block.set_signature(signature.try_into()?);
+        }
+        Ok(block)
+    }
+}
+
+//---------------------------------- TimeoutCertificate --------------------------------------------//
+
+impl<TAddr: NodeAddressable> From<&TimeoutCertificate<TAddr>> for proto::consensus::TimeoutCertificate {
+    fn from(value: &TimeoutCertificate<TAddr>) -> Self {
+        Self {
+            epoch: value.epoch().as_u64(),
+            new_view: value.new_view().as_u64(),
+            new_views: value.new_views().iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl<TAddr: NodeAddressable> TryFrom<proto::consensus::TimeoutCertificate> for TimeoutCertificate<TAddr> {
+    type Error = anyhow::Error;
+
+    fn try_from(value: proto::consensus::TimeoutCertificate) -> Result<Self, Self::Error> {
+        Ok(TimeoutCertificate::new(
+            Epoch(value.epoch),
+            View(value.new_view),
+            value
+                .new_views
+                .into_iter()
+                .map(TryInto::try_into)
+                .collect::<Result<_, _>>()?,
         ))
     }
 }
 
+impl<TAddr: NodeAddressable> From<&SignedNewView<TAddr>> for proto::consensus::SignedNewView {
+    fn from(value: &SignedNewView<TAddr>) -> Self {
+        Self {
+            high_qc_id: value.high_qc_id.as_bytes().to_vec(),
+            high_qc_height: value.high_qc_height.as_u64(),
+            last_vote_block_id: value
+                .last_vote_block_id
+                .map(|id| id.as_bytes().to_vec())
+                .unwrap_or_default(),
+            signature: Some((&value.signature).into()),
+        }
+    }
+}
+
+impl<TAddr: NodeAddressable> TryFrom<proto::consensus::SignedNewView> for SignedNewView<TAddr> {
+    type Error = anyhow::Error;
+
+    fn try_from(value: proto::consensus::SignedNewView) -> Result<Self, Self::Error> {
+        Ok(SignedNewView {
+            high_qc_id: QcId::try_from(value.high_qc_id)?,
+            high_qc_height: NodeHeight(value.high_qc_height),
+            last_vote_block_id: if value.last_vote_block_id.is_empty() {
+                None
+            } else {
+                Some(BlockId::try_from(value.last_vote_block_id)?)
+            },
+            signature: value
+                .signature
+                .ok_or_else(|| anyhow!("TimeoutCertificate conversion: Signature is missing"))?
+                .try_into()?,
+        })
+    }
+}
+
 //---------------------------------- Command --------------------------------------------//
 
 impl From<&Command> for proto::consensus::Command {
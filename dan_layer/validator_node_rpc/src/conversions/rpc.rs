@@ -4,8 +4,17 @@
 use std::convert::{TryFrom, TryInto};
 
 use anyhow::anyhow;
-use tari_dan_common_types::NodeAddressable;
-use tari_dan_storage::consensus_models::{SubstateCreatedProof, SubstateData, SubstateUpdate};
+use tari_dan_common_types::{Epoch, NodeAddressable, NodeHeight};
+use tari_dan_storage::consensus_models::{
+    BlockId,
+    QcId,
+    SubstateCreatedProof,
+    SubstateData,
+    SubstateDestroyed,
+    SubstateQueryPoint,
+    SubstateRecord,
+    SubstateUpdate,
+};
 use tari_engine_types::substate::{SubstateAddress, SubstateValue};
 
 use crate::proto;
@@ -100,3 +109,85 @@ impl From<SubstateData> for proto::rpc::SubstateData {
         }
     }
 }
+
+impl TryFrom<proto::rpc::SubstateRecord> for SubstateRecord {
+    type Error = anyhow::Error;
+
+    fn try_from(value: proto::rpc::SubstateRecord) -> Result<Self, Self::Error> {
+        let mut record = Self::new(
+            SubstateAddress::from_bytes(&value.address)?,
+            value.version,
+            SubstateValue::from_bytes(&value.substate_value)?,
+            Epoch(value.created_epoch),
+            NodeHeight(value.created_height),
+            BlockId::try_from(value.created_block)?,
+            value.created_transaction.try_into()?,
+            QcId::try_from(value.created_justify)?,
+        );
+        record.destroyed = value.destroyed.map(TryInto::try_into).transpose()?;
+        Ok(record)
+    }
+}
+
+impl From<&SubstateRecord> for proto::rpc::SubstateRecord {
+    fn from(value: &SubstateRecord) -> Self {
+        Self {
+            address: value.address.to_bytes(),
+            version: value.version,
+            substate_value: value.substate_value.to_bytes(),
+            created_transaction: value.created_by_transaction.as_bytes().to_vec(),
+            created_justify: value.created_justify.as_bytes().to_vec(),
+            created_block: value.created_block.as_bytes().to_vec(),
+            created_height: value.created_height.as_u64(),
+            created_epoch: value.created_at_epoch.as_u64(),
+            destroyed: value.destroyed.as_ref().map(Into::into),
+        }
+    }
+}
+
+impl TryFrom<proto::rpc::SubstateDestroyed> for SubstateDestroyed {
+    type Error = anyhow::Error;
+
+    fn try_from(value: proto::rpc::SubstateDestroyed) -> Result<Self, Self::Error> {
+        Ok(Self {
+            by_transaction: value.by_transaction.try_into()?,
+            justify: QcId::try_from(value.justify)?,
+            by_block: BlockId::try_from(value.by_block)?,
+            at_epoch: Epoch(value.at_epoch),
+        })
+    }
+}
+
+impl From<&SubstateDestroyed> for proto::rpc::SubstateDestroyed {
+    fn from(value: &SubstateDestroyed) -> Self {
+        Self {
+            by_transaction: value.by_transaction.as_bytes().to_vec(),
+            justify: value.justify.as_bytes().to_vec(),
+            by_block: value.by_block.as_bytes().to_vec(),
+            at_epoch: value.at_epoch.as_u64(),
+        }
+    }
+}
+
+impl TryFrom<proto::rpc::get_substate_at_request::At> for SubstateQueryPoint {
+    type Error = anyhow::Error;
+
+    fn try_from(value: proto::rpc::get_substate_at_request::At) -> Result<Self, Self::Error> {
+        use proto::rpc::get_substate_at_request::At;
+        match value {
+            At::BlockId(block_id) => Ok(Self::Block(BlockId::try_from(block_id)?)),
+            At::Height(height) => Ok(Self::Height(NodeHeight(height))),
+            At::Epoch(epoch) => Ok(Self::Epoch(Epoch(epoch))),
+        }
+    }
+}
+
+impl From<SubstateQueryPoint> for proto::rpc::get_substate_at_request::At {
+    fn from(value: SubstateQueryPoint) -> Self {
+        match value {
+            SubstateQueryPoint::Block(block_id) => Self::BlockId(block_id.as_bytes().to_vec()),
+            SubstateQueryPoint::Height(height) => Self::Height(height.as_u64()),
+            SubstateQueryPoint::Epoch(epoch) => Self::Epoch(epoch.as_u64()),
+        }
+    }
+}
@@ -54,4 +54,58 @@ pub trait ValidatorNodeRpcService: Send + Sync + 'static {
         &self,
         request: Request<proto::GetHighQcRequest>,
     ) -> Result<Response<proto::GetHighQcResponse>, RpcStatus>;
+
+    #[rpc(method = 9)]
+    async fn sync_state_snapshot(
+        &self,
+        request: Request<proto::SyncStateSnapshotRequest>,
+    ) -> Result<Streaming<proto::SyncStateSnapshotResponse>, RpcStatus>;
+
+    #[rpc(method = 10)]
+    async fn get_state_commitment(
+        &self,
+        request: Request<proto::GetStateCommitmentRequest>,
+    ) -> Result<Response<proto::GetStateCommitmentResponse>, RpcStatus>;
+
+    #[rpc(method = 11)]
+    async fn get_substate_history(
+        &self,
+        request: Request<proto::GetSubstateHistoryRequest>,
+    ) -> Result<Response<proto::GetSubstateHistoryResponse>, RpcStatus>;
+
+    #[rpc(method = 12)]
+    async fn get_substate_at(
+        &self,
+        request: Request<proto::GetSubstateAtRequest>,
+    ) -> Result<Response<proto::GetSubstateAtResponse>, RpcStatus>;
+
+    #[rpc(method = 13)]
+    async fn sync_substates_in_range(
+        &self,
+        request: Request<proto::SyncSubstatesInRangeRequest>,
+    ) -> Result<Streaming<proto::SyncSubstatesInRangeResponse>, RpcStatus>;
+
+    #[rpc(method = 14)]
+    async fn invoke_read_method(
+        &self,
+        request: Request<proto::InvokeReadMethodRequest>,
+    ) -> Result<Response<proto::InvokeReadMethodResponse>, RpcStatus>;
+
+    #[rpc(method = 15)]
+    async fn get_epoch_handoff(
+        &self,
+        request: Request<proto::GetEpochHandoffRequest>,
+    ) -> Result<Response<proto::GetEpochHandoffResponse>, RpcStatus>;
+
+    #[rpc(method = 16)]
+    async fn submit_dev_template(
+        &self,
+        request: Request<proto::SubmitDevTemplateRequest>,
+    ) -> Result<Response<proto::SubmitDevTemplateResponse>, RpcStatus>;
+
+    #[rpc(method = 17)]
+    async fn get_template_binary(
+        &self,
+        request: Request<proto::GetTemplateBinaryRequest>,
+    ) -> Result<Response<proto::GetTemplateBinaryResponse>, RpcStatus>;
 }
@@ -25,7 +25,6 @@ impl<'a, TStore: WalletStore> ConfigApi<'a, TStore> {
 
     pub fn set<T: Serialize>(&self, key: ConfigKey, value: &T, is_encrypted: bool) -> Result<(), ConfigApiError> {
         let mut tx = self.store.create_write_tx()?;
-        // TODO: Actually encrypt if is_encrypted is true
         tx.config_set(key.as_key_str(), value, is_encrypted)?;
         tx.commit()?;
         Ok(())
@@ -4,14 +4,17 @@
 use blake2::Blake2b;
 use digest::consts::U64;
 use tari_common_types::types::PublicKey;
-use tari_crypto::keys::PublicKey as PublicKeyTrait;
 //
 use tari_crypto::ristretto::RistrettoPublicKey;
+use tari_crypto::{keys::PublicKey as PublicKeyTrait, tari_utilities::SafePassword};
 use tari_dan_common_types::optional::Optional;
 use tari_key_manager::{
     cipher_seed::CipherSeed,
     key_manager::{DerivedKey, KeyManager},
+    mnemonic::Mnemonic,
+    SeedWords,
 };
+use tari_utilities::hidden::Hidden;
 
 use crate::storage::{WalletStorageError, WalletStore, WalletStoreReader, WalletStoreWriter};
 
@@ -21,11 +24,11 @@ pub const TRANSACTION_BRANCH: &str = "transactions";
 
 pub struct KeyManagerApi<'a, TStore> {
     store: &'a TStore,
-    cipher_seed: &'a CipherSeed,
+    cipher_seed: CipherSeed,
 }
 
 impl<'a, TStore: WalletStore> KeyManagerApi<'a, TStore> {
-    pub(crate) fn new(store: &'a TStore, cipher_seed: &'a CipherSeed) -> Self {
+    pub(crate) fn new(store: &'a TStore, cipher_seed: CipherSeed) -> Self {
         Self { store, cipher_seed }
     }
 
@@ -77,6 +80,19 @@ impl<'a, TStore: WalletStore> KeyManagerApi<'a, TStore> {
         Ok(key)
     }
 
+    /// Records that the key at the given index is in use, so that `next_key` does not derive it again. This is used
+    /// when keys that were derived by another wallet with the same seed are recovered.
+    pub fn import_key_index(&self, branch: &str, index: u64) -> Result<(), KeyManagerApiError> {
+        let mut tx = self.store.create_write_tx()?;
+        if tx.key_manager_get_all(branch)?.iter().any(|(i, _)| *i == index) {
+            tx.rollback()?;
+        } else {
+            tx.key_manager_insert(branch, index)?;
+            tx.commit()?;
+        }
+        Ok(())
+    }
+
     pub fn set_active_key(&self, branch: &str, index: u64) -> Result<(), KeyManagerApiError> {
         let mut tx = self.store.create_write_tx()?;
         tx.key_manager_set_active_index(branch, index)?;
@@ -130,6 +146,25 @@ impl<'a, TStore: WalletStore> KeyManagerApi<'a, TStore> {
     }
 }
 
+/// Recovers a cipher seed from its seed words.
+pub fn cipher_seed_from_seed_words(
+    seed_words: Vec<String>,
+    passphrase: Option<SafePassword>,
+) -> Result<CipherSeed, KeyManagerApiError> {
+    let seed_words = SeedWords::new(seed_words.into_iter().map(Hidden::hide).collect());
+    let cipher_seed = CipherSeed::from_mnemonic(&seed_words, passphrase)?;
+    Ok(cipher_seed)
+}
+
+/// Derives the public key at the given index from a cipher seed without touching the wallet store. This is used to
+/// look up keys of a seed that the wallet does not (yet) use.
+pub fn derive_public_key(cipher_seed: &CipherSeed, branch: &str, index: u64) -> Result<PublicKey, KeyManagerApiError> {
+    let key = WalletKeyManager::from(cipher_seed.clone(), branch.to_string(), 0)
+        .derive_key(index)
+        .map_err(tari_key_manager::error::KeyManagerError::from)?;
+    Ok(PublicKey::from_secret_key(&key.key))
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum KeyManagerApiError {
     #[error("Store error: {0}")]
@@ -1,6 +1,7 @@
 //   Copyright 2023 The Tari Project
 //   SPDX-License-Identifier: BSD-3-Clause
 
+use tari_dan_common_types::optional::IsNotFoundError;
 use tari_template_lib::{models::ResourceAddress, prelude::NonFungibleId};
 use thiserror::Error;
 
@@ -61,3 +62,9 @@ pub enum NonFungibleTokensApiError {
     #[error("Store error: {0}")]
     StoreError(#[from] WalletStorageError),
 }
+
+impl IsNotFoundError for NonFungibleTokensApiError {
+    fn is_not_found_error(&self) -> bool {
+        matches!(self, Self::StoreError(e) if e.is_not_found_error())
+    }
+}
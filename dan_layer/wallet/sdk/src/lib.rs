@@ -8,7 +8,7 @@ mod byte_utils;
 pub mod confidential;
 pub mod models;
 mod sdk;
-pub use sdk::{DanWalletSdk, WalletSdkConfig};
+pub use sdk::{DanWalletSdk, WalletSdkConfig, WalletSdkError};
 pub mod network;
 
 pub use tari_key_manager::cipher_seed::CipherSeed;
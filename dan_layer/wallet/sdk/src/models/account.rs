@@ -2,6 +2,9 @@
 //   SPDX-License-Identifier: BSD-3-Clause
 
 use tari_engine_types::substate::SubstateAddress;
+use tari_transaction::TransactionId;
+
+use crate::models::VersionedSubstateAddress;
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct Account {
@@ -10,3 +13,11 @@ pub struct Account {
     pub key_index: u64,
     pub is_default: bool,
 }
+
+/// An account that was found on the network for a key derived from a restored wallet seed
+#[derive(Debug, Clone)]
+pub struct RestoredAccount {
+    pub key_index: u64,
+    pub address: VersionedSubstateAddress,
+    pub created_by_tx: TransactionId,
+}
@@ -1,9 +1,11 @@
 //   Copyright 2023 The Tari Project
 //   SPDX-License-Identifier: BSD-3-Clause
 
-use std::{sync::Arc, time::Duration};
+use std::{
+    sync::{Arc, RwLock},
+    time::Duration,
+};
 
-use tari_crypto::tari_utilities::SafePassword;
 use tari_dan_common_types::optional::{IsNotFoundError, Optional};
 use tari_key_manager::cipher_seed::CipherSeed;
 
@@ -14,19 +16,18 @@ use crate::{
         confidential_outputs::ConfidentialOutputsApi,
         config::{ConfigApi, ConfigApiError, ConfigKey},
         jwt::JwtApi,
-        key_manager::KeyManagerApi,
+        key_manager::{derive_public_key, KeyManagerApi, KeyManagerApiError, TRANSACTION_BRANCH},
         non_fungible_tokens::NonFungibleTokensApi,
         substate::SubstatesApi,
         transaction::TransactionApi,
     },
+    models::RestoredAccount,
     network::WalletNetworkInterface,
-    storage::{WalletStorageError, WalletStore},
+    storage::{WalletStorageError, WalletStore, WalletStoreReader, WalletStoreWriter},
 };
 
 #[derive(Debug, Clone)]
 pub struct WalletSdkConfig {
-    /// Encryption password for the wallet database. NOTE: Not yet implemented, this field is ignored
-    pub password: Option<SafePassword>,
     pub indexer_jrpc_endpoint: String,
     pub jwt_expiry: Duration,
     pub jwt_secret_key: String,
@@ -37,7 +38,7 @@ pub struct DanWalletSdk<TStore, TNetworkInterface> {
     store: TStore,
     network_interface: TNetworkInterface,
     config: WalletSdkConfig,
-    cipher_seed: Arc<CipherSeed>,
+    cipher_seed: Arc<RwLock<CipherSeed>>,
 }
 
 impl<TStore, TNetworkInterface> DanWalletSdk<TStore, TNetworkInterface>
@@ -57,7 +58,7 @@ where
             store,
             network_interface: indexer,
             config,
-            cipher_seed: Arc::new(cipher_seed),
+            cipher_seed: Arc::new(RwLock::new(cipher_seed)),
         })
     }
 
@@ -74,7 +75,7 @@ where
     }
 
     pub fn key_manager_api(&self) -> KeyManagerApi<'_, TStore> {
-        KeyManagerApi::new(&self.store, &self.cipher_seed)
+        KeyManagerApi::new(&self.store, self.cipher_seed.read().unwrap().clone())
     }
 
     pub fn transaction_api(&self) -> TransactionApi<'_, TStore, TNetworkInterface> {
@@ -110,6 +111,61 @@ where
         NonFungibleTokensApi::new(&self.store)
     }
 
+    /// Returns an error if the wallet cannot be restored from the given seed. Keys derived from the current seed can no
+    /// longer be used once the seed is replaced, so a wallet that has accounts can only be restored from the seed it
+    /// already uses. This allows an interrupted restore to be retried with the same seed words.
+    pub fn check_can_restore(&self, cipher_seed: &CipherSeed) -> Result<(), WalletSdkError> {
+        self.store
+            .with_read_tx(|tx| Self::check_can_restore_inner(tx, cipher_seed))
+    }
+
+    /// Replaces the wallet seed and adds the accounts that were discovered for it in a single database transaction, so
+    /// that a failed restore leaves the wallet unchanged. Accounts that the wallet already has are left as they are.
+    pub fn restore_wallet(&self, cipher_seed: CipherSeed, accounts: &[RestoredAccount]) -> Result<(), WalletSdkError> {
+        self.store.with_write_tx(|tx| {
+            Self::check_can_restore_inner(&mut **tx, &cipher_seed)?;
+
+            tx.config_set(ConfigKey::CipherSeed.as_key_str(), &cipher_seed, true)?;
+            let key_indexes = tx.key_manager_get_all(TRANSACTION_BRANCH)?;
+            let mut has_default = tx.accounts_get_default().optional()?.is_some();
+            for account in accounts {
+                if tx.accounts_get(&account.address.address).optional()?.is_some() {
+                    continue;
+                }
+                if key_indexes.iter().all(|(index, _)| *index != account.key_index) {
+                    tx.key_manager_insert(TRANSACTION_BRANCH, account.key_index)?;
+                }
+                tx.substates_remove(&account.address.address).optional()?;
+                tx.substates_insert_root(account.created_by_tx, account.address.clone(), None, None)?;
+                let name = format!("account-{}", account.key_index);
+                tx.accounts_insert(&name, &account.address.address, account.key_index, !has_default)?;
+                has_default = true;
+            }
+            Ok::<_, WalletSdkError>(())
+        })?;
+
+        *self.cipher_seed.write().unwrap() = cipher_seed;
+        Ok(())
+    }
+
+    fn check_can_restore_inner<TTx: WalletStoreReader>(
+        tx: &mut TTx,
+        cipher_seed: &CipherSeed,
+    ) -> Result<(), WalletSdkError> {
+        let num_accounts = tx.accounts_count()?;
+        if num_accounts == 0 {
+            return Ok(());
+        }
+        let current_seed = tx.config_get::<CipherSeed>(ConfigKey::CipherSeed.as_key_str())?.value;
+        // Seeds are compared by the first key they derive
+        let is_same_seed = derive_public_key(&current_seed, TRANSACTION_BRANCH, 0)? ==
+            derive_public_key(cipher_seed, TRANSACTION_BRANCH, 0)?;
+        if !is_same_seed {
+            return Err(WalletSdkError::WalletNotEmpty { num_accounts });
+        }
+        Ok(())
+    }
+
     fn get_or_create_cipher_seed(store: &TStore) -> Result<CipherSeed, WalletSdkError> {
         let config_api = ConfigApi::new(store);
         let maybe_cipher_seed = config_api.get(ConfigKey::CipherSeed).optional()?;
@@ -131,4 +187,8 @@ pub enum WalletSdkError {
     WalletStorageError(#[from] WalletStorageError),
     #[error("Config API error: {0}")]
     ConfigApiError(#[from] ConfigApiError),
+    #[error("Key manager API error: {0}")]
+    KeyManagerApiError(#[from] KeyManagerApiError),
+    #[error("Cannot restore the wallet from a different seed because it already has {num_accounts} account(s)")]
+    WalletNotEmpty { num_accounts: u64 },
 }
@@ -83,6 +83,18 @@ pub enum WalletStorageError {
         entity: String,
         key: String,
     },
+    #[error("The wallet is locked. It must be unlocked with the wallet passphrase to access encrypted data.")]
+    WalletLocked,
+    #[error("Incorrect wallet passphrase")]
+    InvalidPassphrase,
+    #[error("No passphrase has been set for this wallet")]
+    WalletNotEncrypted,
+    #[error("A passphrase has already been set for this wallet")]
+    WalletAlreadyEncrypted,
+    #[error("The passphrase and confirmation passphrase do not match")]
+    PassphraseMismatch,
+    #[error("Encryption failure for operation {operation}: {details}")]
+    EncryptionError { operation: &'static str, details: String },
 }
 
 impl IsNotFoundError for WalletStorageError {
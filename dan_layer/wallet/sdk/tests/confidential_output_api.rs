@@ -141,7 +141,6 @@ impl Test {
         store.run_migrations().unwrap();
 
         let sdk = DanWalletSdk::initialize(store.clone(), PanicIndexer, WalletSdkConfig {
-            password: None,
             indexer_jrpc_endpoint: "".to_string(),
             jwt_expiry: Duration::from_secs(60),
             jwt_secret_key: "secret_key".to_string(),
@@ -0,0 +1,267 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use std::{convert::Infallible, time::Duration};
+
+use async_trait::async_trait;
+use tari_common_types::types::PublicKey;
+use tari_crypto::keys::PublicKey as PublicKeyTrait;
+use tari_dan_wallet_sdk::{
+    apis::key_manager::{
+        cipher_seed_from_seed_words,
+        derive_public_key,
+        KeyManagerApiError,
+        WalletKeyManager,
+        TRANSACTION_BRANCH,
+    },
+    models::{RestoredAccount, VersionedSubstateAddress},
+    network::{SubstateQueryResult, TransactionQueryResult, WalletNetworkInterface},
+    CipherSeed,
+    DanWalletSdk,
+    WalletSdkConfig,
+    WalletSdkError,
+};
+use tari_dan_wallet_storage_sqlite::SqliteWalletStore;
+use tari_engine_types::substate::SubstateAddress;
+use tari_key_manager::mnemonic::{Mnemonic, MnemonicLanguage};
+use tari_transaction::{SubstateRequirement, Transaction, TransactionId};
+
+#[test]
+fn restored_seed_derives_the_same_keys() {
+    let test = Test::new();
+    let cipher_seed = CipherSeed::new();
+    test.sdk.restore_wallet(cipher_seed.clone(), &[]).unwrap();
+
+    let key_manager_api = test.sdk.key_manager_api();
+    for index in [0, 1, 5] {
+        let expected = WalletKeyManager::from(cipher_seed.clone(), TRANSACTION_BRANCH.to_string(), 0)
+            .derive_key(index)
+            .unwrap();
+        let public_key = key_manager_api.get_public_key(TRANSACTION_BRANCH, Some(index)).unwrap();
+        assert_eq!(public_key, PublicKey::from_secret_key(&expected.key));
+        assert_eq!(
+            derive_public_key(&cipher_seed, TRANSACTION_BRANCH, index).unwrap(),
+            public_key
+        );
+    }
+
+    // The restored seed is persisted
+    let sdk = DanWalletSdk::initialize(test.store.clone(), PanicIndexer, Test::config()).unwrap();
+    let public_key = sdk
+        .key_manager_api()
+        .get_public_key(TRANSACTION_BRANCH, Some(0))
+        .unwrap();
+    assert_eq!(
+        public_key,
+        derive_public_key(&cipher_seed, TRANSACTION_BRANCH, 0).unwrap()
+    );
+}
+
+#[test]
+fn seed_words_round_trip() {
+    let cipher_seed = CipherSeed::new();
+    let restored = cipher_seed_from_seed_words(to_seed_words(&cipher_seed), None).unwrap();
+    assert_eq!(
+        derive_public_key(&restored, TRANSACTION_BRANCH, 0).unwrap(),
+        derive_public_key(&cipher_seed, TRANSACTION_BRANCH, 0).unwrap()
+    );
+}
+
+#[test]
+fn restore_adds_discovered_accounts() {
+    let test = Test::new();
+    let cipher_seed = CipherSeed::new();
+    let accounts = [
+        restored_account(1, ACCOUNT_ADDRESS_1),
+        restored_account(4, ACCOUNT_ADDRESS_2),
+    ];
+    test.sdk.restore_wallet(cipher_seed, &accounts).unwrap();
+
+    let accounts_api = test.sdk.accounts_api();
+    let account = accounts_api.get_account_by_name("account-1").unwrap();
+    assert_eq!(account.key_index, 1);
+    assert!(account.is_default);
+    let account = accounts_api.get_account_by_name("account-4").unwrap();
+    assert_eq!(account.key_index, 4);
+    assert!(!account.is_default);
+
+    // Recovered keys are not handed out again
+    let next = test.sdk.key_manager_api().next_key(TRANSACTION_BRANCH).unwrap();
+    assert_eq!(next.key_index, 5);
+}
+
+#[test]
+fn restore_can_be_retried_with_the_same_seed() {
+    let test = Test::new();
+    let cipher_seed = CipherSeed::new();
+    test.sdk
+        .restore_wallet(cipher_seed.clone(), &[restored_account(0, ACCOUNT_ADDRESS_1)])
+        .unwrap();
+
+    test.sdk.check_can_restore(&cipher_seed).unwrap();
+    let accounts = [
+        restored_account(0, ACCOUNT_ADDRESS_1),
+        restored_account(2, ACCOUNT_ADDRESS_2),
+    ];
+    test.sdk.restore_wallet(cipher_seed, &accounts).unwrap();
+
+    let accounts_api = test.sdk.accounts_api();
+    assert_eq!(accounts_api.count().unwrap(), 2);
+    assert!(accounts_api.get_account_by_name("account-0").unwrap().is_default);
+    assert!(!accounts_api.get_account_by_name("account-2").unwrap().is_default);
+}
+
+#[test]
+fn restore_fails_if_wallet_has_accounts_for_another_seed() {
+    let test = Test::new();
+    let account_address = ACCOUNT_ADDRESS_1.parse::<SubstateAddress>().unwrap();
+    test.sdk
+        .accounts_api()
+        .add_account(Some("test"), &account_address, 0, true)
+        .unwrap();
+    let public_key = test
+        .sdk
+        .key_manager_api()
+        .get_public_key(TRANSACTION_BRANCH, Some(0))
+        .unwrap();
+
+    let cipher_seed = CipherSeed::new();
+    let err = test.sdk.check_can_restore(&cipher_seed).unwrap_err();
+    assert!(matches!(err, WalletSdkError::WalletNotEmpty { num_accounts: 1 }));
+    let err = test
+        .sdk
+        .restore_wallet(cipher_seed, &[restored_account(1, ACCOUNT_ADDRESS_2)])
+        .unwrap_err();
+    assert!(matches!(err, WalletSdkError::WalletNotEmpty { num_accounts: 1 }));
+
+    // Nothing was changed
+    assert_eq!(test.sdk.accounts_api().count().unwrap(), 1);
+    assert_eq!(
+        test.sdk
+            .key_manager_api()
+            .get_public_key(TRANSACTION_BRANCH, Some(0))
+            .unwrap(),
+        public_key
+    );
+}
+
+#[test]
+fn restore_fails_for_invalid_seed_words() {
+    let seed_words = vec!["not".to_string(), "seed".to_string(), "words".to_string()];
+    let err = cipher_seed_from_seed_words(seed_words, None).unwrap_err();
+    assert!(matches!(err, KeyManagerApiError::KeyManagerError(_)));
+}
+
+#[test]
+fn imported_key_index_is_not_derived_again() {
+    let test = Test::new();
+    let key_manager_api = test.sdk.key_manager_api();
+    key_manager_api.get_or_create_initial(TRANSACTION_BRANCH).unwrap();
+    key_manager_api.import_key_index(TRANSACTION_BRANCH, 3).unwrap();
+    key_manager_api.import_key_index(TRANSACTION_BRANCH, 3).unwrap();
+
+    let keys = key_manager_api.get_all_keys(TRANSACTION_BRANCH).unwrap();
+    assert_eq!(keys.len(), 2);
+    let next = key_manager_api.next_key(TRANSACTION_BRANCH).unwrap();
+    assert_eq!(next.key_index, 4);
+}
+
+// -------------------------------- Test Harness -------------------------------- //
+
+const ACCOUNT_ADDRESS_1: &str = "component_0dc41b5cc74b36d696c7b140323a40a2f98b71df5d60e5a6bf4c1a071d15f562";
+const ACCOUNT_ADDRESS_2: &str = "component_1f019e4d434cbf2b99c0af89ee212f422af86de7280a169d2e392dfb66ab34d4";
+
+fn restored_account(key_index: u64, address: &str) -> RestoredAccount {
+    RestoredAccount {
+        key_index,
+        address: VersionedSubstateAddress {
+            address: address.parse().unwrap(),
+            version: 0,
+        },
+        created_by_tx: TransactionId::default(),
+    }
+}
+
+fn to_seed_words(cipher_seed: &CipherSeed) -> Vec<String> {
+    let seed_words = cipher_seed.to_mnemonic(MnemonicLanguage::English, None).unwrap();
+    let seed_words = seed_words.join(" ");
+    seed_words.reveal().split(' ').map(|s| s.to_string()).collect()
+}
+
+struct Test {
+    store: SqliteWalletStore,
+    sdk: DanWalletSdk<SqliteWalletStore, PanicIndexer>,
+    _temp: tempfile::TempDir,
+}
+
+impl Test {
+    pub fn new() -> Self {
+        let temp = tempfile::tempdir().unwrap();
+        let store = SqliteWalletStore::try_open(temp.path().join("data/wallet.sqlite")).unwrap();
+        store.run_migrations().unwrap();
+        let sdk = DanWalletSdk::initialize(store.clone(), PanicIndexer, Self::config()).unwrap();
+
+        Self {
+            store,
+            sdk,
+            _temp: temp,
+        }
+    }
+
+    pub fn config() -> WalletSdkConfig {
+        WalletSdkConfig {
+            indexer_jrpc_endpoint: "".to_string(),
+            jwt_expiry: Duration::from_secs(60),
+            jwt_secret_key: "secret_key".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct PanicIndexer;
+
+#[async_trait]
+impl WalletNetworkInterface for PanicIndexer {
+    type Error = Infallible;
+
+    #[allow(clippy::diverging_sub_expression)]
+    async fn query_substate(
+        &self,
+        _address: &SubstateAddress,
+        _version: Option<u32>,
+        _local_search_only: bool,
+    ) -> Result<SubstateQueryResult, Self::Error> {
+        panic!("PanicIndexer called")
+    }
+
+    #[allow(clippy::diverging_sub_expression)]
+    async fn submit_transaction(
+        &self,
+        _transaction: Transaction,
+        _required_substates: Vec<SubstateRequirement>,
+    ) -> Result<TransactionId, Self::Error> {
+        panic!("PanicIndexer called")
+    }
+
+    #[allow(clippy::diverging_sub_expression)]
+    async fn submit_dry_run_transaction(
+        &self,
+        _transaction: Transaction,
+        _required_substates: Vec<SubstateRequirement>,
+    ) -> Result<TransactionQueryResult, Self::Error> {
+        panic!("PanicIndexer called")
+    }
+
+    #[allow(clippy::diverging_sub_expression)]
+    async fn query_transaction_result(
+        &self,
+        _transaction_id: TransactionId,
+    ) -> Result<TransactionQueryResult, Self::Error> {
+        panic!("PanicIndexer called")
+    }
+
+    #[allow(clippy::diverging_sub_expression)]
+    fn set_endpoint(&mut self, _endpoint: &str) -> Result<(), Self::Error> {
+        panic!("PanicIndexer called")
+    }
+}
@@ -0,0 +1,160 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use argon2::Argon2;
+use chacha20poly1305::{
+    aead::{rand_core::RngCore, Aead, OsRng, Payload},
+    AeadCore,
+    KeyInit,
+    XChaCha20Poly1305,
+    XNonce,
+};
+use serde::{Deserialize, Serialize};
+use tari_dan_wallet_sdk::storage::WalletStorageError;
+use tari_utilities::{hex::Hex, SafePassword};
+use zeroize::Zeroizing;
+
+/// The config key of the (unencrypted) encryption parameters. The presence of this key indicates that the wallet has
+/// a passphrase.
+pub(crate) const ENCRYPTION_PARAMS_KEY: &str = "encryption_params";
+
+const KEY_SIZE: usize = 32;
+const SALT_SIZE: usize = 16;
+const NONCE_SIZE: usize = 24;
+const MAIN_KEY_AAD: &[u8] = b"wallet_main_key";
+
+/// Parameters needed to recover the main encryption key from the wallet passphrase. The main key is randomly generated
+/// when the passphrase is first set and is encrypted with a key derived from the passphrase using Argon2, so that
+/// changing the passphrase does not require re-encrypting the wallet secrets.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct EncryptionParams {
+    /// Hex-encoded Argon2 salt
+    salt: String,
+    /// Hex-encoded main key, encrypted with the passphrase key
+    encrypted_main_key: String,
+}
+
+impl EncryptionParams {
+    pub fn new(passphrase: &SafePassword, main_key: &WalletCipher) -> Result<Self, WalletStorageError> {
+        let mut salt = [0u8; SALT_SIZE];
+        OsRng.fill_bytes(&mut salt);
+        let passphrase_key = WalletCipher::derive_from_passphrase(passphrase, &salt)?;
+        let encrypted_main_key = passphrase_key.encrypt(main_key.key.as_slice(), MAIN_KEY_AAD)?;
+
+        Ok(Self {
+            salt: salt.to_hex(),
+            encrypted_main_key: encrypted_main_key.to_hex(),
+        })
+    }
+
+    /// Decrypts the main key with the passphrase. Returns `InvalidPassphrase` if the passphrase is incorrect.
+    pub fn decrypt_main_key(&self, passphrase: &SafePassword) -> Result<WalletCipher, WalletStorageError> {
+        let salt = from_hex(&self.salt)?;
+        let passphrase_key = WalletCipher::derive_from_passphrase(passphrase, &salt)?;
+        let main_key = passphrase_key
+            .decrypt(&from_hex(&self.encrypted_main_key)?, MAIN_KEY_AAD)
+            .map_err(|_| WalletStorageError::InvalidPassphrase)?;
+        WalletCipher::from_bytes(&main_key)
+    }
+}
+
+/// Authenticated encryption (XChaCha20-Poly1305) of wallet secrets
+#[derive(Clone)]
+pub(crate) struct WalletCipher {
+    key: Zeroizing<[u8; KEY_SIZE]>,
+}
+
+impl WalletCipher {
+    pub fn random() -> Self {
+        let mut key = Zeroizing::new([0u8; KEY_SIZE]);
+        OsRng.fill_bytes(key.as_mut_slice());
+        Self { key }
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Result<Self, WalletStorageError> {
+        if bytes.len() != KEY_SIZE {
+            return Err(WalletStorageError::EncryptionError {
+                operation: "from_bytes",
+                details: format!("Expected a {} byte key but got {} bytes", KEY_SIZE, bytes.len()),
+            });
+        }
+        let mut key = Zeroizing::new([0u8; KEY_SIZE]);
+        key.copy_from_slice(bytes);
+        Ok(Self { key })
+    }
+
+    fn derive_from_passphrase(passphrase: &SafePassword, salt: &[u8]) -> Result<Self, WalletStorageError> {
+        let mut key = Zeroizing::new([0u8; KEY_SIZE]);
+        Argon2::default()
+            .hash_password_into(passphrase.reveal(), salt, key.as_mut_slice())
+            .map_err(|e| WalletStorageError::EncryptionError {
+                operation: "derive_from_passphrase",
+                details: e.to_string(),
+            })?;
+        Ok(Self { key })
+    }
+
+    /// Encrypts the plaintext, returning the nonce followed by the ciphertext. The associated data must be provided
+    /// for decryption.
+    pub fn encrypt(&self, plaintext: &[u8], aad: &[u8]) -> Result<Vec<u8>, WalletStorageError> {
+        let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = self
+            .aead()
+            .encrypt(&nonce, Payload { msg: plaintext, aad })
+            .map_err(|e| WalletStorageError::EncryptionError {
+                operation: "encrypt",
+                details: e.to_string(),
+            })?;
+
+        let mut data = Vec::with_capacity(NONCE_SIZE + ciphertext.len());
+        data.extend_from_slice(&nonce);
+        data.extend_from_slice(&ciphertext);
+        Ok(data)
+    }
+
+    pub fn decrypt(&self, data: &[u8], aad: &[u8]) -> Result<Zeroizing<Vec<u8>>, WalletStorageError> {
+        if data.len() < NONCE_SIZE {
+            return Err(WalletStorageError::EncryptionError {
+                operation: "decrypt",
+                details: "Encrypted data is too short".to_string(),
+            });
+        }
+        let (nonce, ciphertext) = data.split_at(NONCE_SIZE);
+        let plaintext = self
+            .aead()
+            .decrypt(XNonce::from_slice(nonce), Payload { msg: ciphertext, aad })
+            .map_err(|e| WalletStorageError::EncryptionError {
+                operation: "decrypt",
+                details: e.to_string(),
+            })?;
+        Ok(Zeroizing::new(plaintext))
+    }
+
+    /// Encrypts a serialized config value, returning the hex-encoded ciphertext. The config key is bound to the
+    /// ciphertext so that encrypted values cannot be swapped between keys.
+    pub fn encrypt_config_value(&self, key: &str, value: &str) -> Result<String, WalletStorageError> {
+        Ok(self.encrypt(value.as_bytes(), key.as_bytes())?.to_hex())
+    }
+
+    pub fn decrypt_config_value(&self, key: &str, value: &str) -> Result<Zeroizing<String>, WalletStorageError> {
+        let plaintext = self.decrypt(&from_hex(value)?, key.as_bytes())?;
+        let value = std::str::from_utf8(&plaintext).map_err(|e| WalletStorageError::DecodingError {
+            operation: "decrypt_config_value",
+            item: "config value",
+            details: e.to_string(),
+        })?;
+        Ok(Zeroizing::new(value.to_string()))
+    }
+
+    fn aead(&self) -> XChaCha20Poly1305 {
+        XChaCha20Poly1305::new(self.key.as_slice().into())
+    }
+}
+
+fn from_hex(s: &str) -> Result<Vec<u8>, WalletStorageError> {
+    Vec::from_hex(s).map_err(|e| WalletStorageError::DecodingError {
+        operation: "from_hex",
+        item: "encrypted data",
+        details: e.to_string(),
+    })
+}
@@ -3,6 +3,7 @@
 #[macro_use]
 extern crate diesel;
 
+mod encryption;
 mod models;
 mod reader;
 mod schema;
@@ -13,19 +14,27 @@ use std::{
     fmt::{Debug, Formatter},
     fs::create_dir_all,
     path::Path,
-    sync::{Arc, Mutex},
+    sync::{Arc, Mutex, RwLock},
 };
 
 use diesel::{sql_query, Connection, RunQueryDsl, SqliteConnection};
 use diesel_migrations::{embed_migrations, EmbeddedMigrations, MigrationHarness};
-use tari_dan_wallet_sdk::storage::{WalletStorageError, WalletStore};
+use tari_dan_common_types::optional::Optional;
+use tari_dan_wallet_sdk::storage::{WalletStorageError, WalletStore, WalletStoreReader, WalletStoreWriter};
+use tari_utilities::SafePassword;
 
-use crate::{reader::ReadTransaction, writer::WriteTransaction};
+use crate::{
+    encryption::{EncryptionParams, WalletCipher, ENCRYPTION_PARAMS_KEY},
+    reader::ReadTransaction,
+    writer::WriteTransaction,
+};
 
 #[derive(Clone)]
 pub struct SqliteWalletStore {
     // MUTEX: required to make Sync
     connection: Arc<Mutex<SqliteConnection>>,
+    /// Encrypts and decrypts wallet secrets. None until the wallet has been unlocked.
+    cipher: Arc<RwLock<Option<WalletCipher>>>,
 }
 
 impl SqliteWalletStore {
@@ -42,6 +51,7 @@ impl SqliteWalletStore {
 
         Ok(Self {
             connection: Arc::new(Mutex::new(connection)),
+            cipher: Arc::new(RwLock::new(None)),
         })
     }
 
@@ -52,6 +62,75 @@ impl SqliteWalletStore {
             .map_err(|source| WalletStorageError::general("migrate", source))?;
         Ok(())
     }
+
+    /// Returns true if a passphrase has been set for this wallet
+    pub fn is_encrypted(&self) -> Result<bool, WalletStorageError> {
+        let mut tx = self.create_read_tx()?;
+        tx.is_encryption_enabled()
+    }
+
+    /// Sets the passphrase for a wallet that does not have one and encrypts any secrets stored before this. The
+    /// passphrase must be entered twice, since the wallet secrets cannot be recovered if it is mistyped.
+    pub fn set_passphrase(
+        &self,
+        passphrase: &SafePassword,
+        confirm_passphrase: &SafePassword,
+    ) -> Result<(), WalletStorageError> {
+        if passphrase.reveal() != confirm_passphrase.reveal() {
+            return Err(WalletStorageError::PassphraseMismatch);
+        }
+        let mut tx = self.create_write_tx()?;
+        if tx
+            .config_get::<EncryptionParams>(ENCRYPTION_PARAMS_KEY)
+            .optional()?
+            .is_some()
+        {
+            return Err(WalletStorageError::WalletAlreadyEncrypted);
+        }
+
+        let cipher = WalletCipher::random();
+        let params = EncryptionParams::new(passphrase, &cipher)?;
+        tx.config_set(ENCRYPTION_PARAMS_KEY, &params, false)?;
+        tx.config_encrypt_plaintext_values(&cipher)?;
+        tx.commit()?;
+
+        *self.cipher.write().unwrap() = Some(cipher);
+        Ok(())
+    }
+
+    /// Unlocks the wallet secrets with the passphrase. Returns `WalletNotEncrypted` if no passphrase has been set for
+    /// this wallet.
+    pub fn unlock(&self, passphrase: &SafePassword) -> Result<(), WalletStorageError> {
+        let params = self
+            .with_read_tx(|tx| tx.config_get::<EncryptionParams>(ENCRYPTION_PARAMS_KEY))
+            .optional()?
+            .ok_or(WalletStorageError::WalletNotEncrypted)?;
+        let cipher = params.value.decrypt_main_key(passphrase)?;
+
+        *self.cipher.write().unwrap() = Some(cipher);
+        Ok(())
+    }
+
+    /// Changes the wallet passphrase. The wallet secrets remain encrypted with the same key, so only the key that
+    /// protects it is re-encrypted.
+    pub fn change_passphrase(
+        &self,
+        current_passphrase: &SafePassword,
+        new_passphrase: &SafePassword,
+    ) -> Result<(), WalletStorageError> {
+        let mut tx = self.create_write_tx()?;
+        let params = tx
+            .config_get::<EncryptionParams>(ENCRYPTION_PARAMS_KEY)
+            .optional()?
+            .ok_or(WalletStorageError::WalletNotEncrypted)?;
+        let cipher = params.value.decrypt_main_key(current_passphrase)?;
+        let new_params = EncryptionParams::new(new_passphrase, &cipher)?;
+        tx.config_set(ENCRYPTION_PARAMS_KEY, &new_params, false)?;
+        tx.commit()?;
+
+        *self.cipher.write().unwrap() = Some(cipher);
+        Ok(())
+    }
 }
 
 impl WalletStore for SqliteWalletStore {
@@ -63,7 +142,7 @@ impl WalletStore for SqliteWalletStore {
         sql_query("BEGIN")
             .execute(&mut *lock)
             .map_err(|e| WalletStorageError::general("BEGIN transaction", e))?;
-        Ok(ReadTransaction::new(lock))
+        Ok(ReadTransaction::new(lock, self.cipher.read().unwrap().clone()))
     }
 
     fn create_write_tx(&self) -> Result<Self::WriteTransaction<'_>, WalletStorageError> {
@@ -71,7 +150,7 @@ impl WalletStore for SqliteWalletStore {
         sql_query("BEGIN")
             .execute(&mut *lock)
             .map_err(|e| WalletStorageError::general("BEGIN transaction", e))?;
-        Ok(WriteTransaction::new(lock))
+        Ok(WriteTransaction::new(lock, self.cipher.read().unwrap().clone()))
     }
 }
 
@@ -40,23 +40,47 @@ use tari_template_lib::{
 use tari_transaction::TransactionId;
 use tari_utilities::hex::Hex;
 
-use crate::{diesel::ExpressionMethods, models, serialization::deserialize_json};
+use crate::{
+    diesel::ExpressionMethods,
+    encryption::{WalletCipher, ENCRYPTION_PARAMS_KEY},
+    models,
+    serialization::deserialize_json,
+};
 
 const LOG_TARGET: &str = "tari::dan::wallet_sdk::storage_sqlite::reader";
 
 pub struct ReadTransaction<'a> {
     connection: MutexGuard<'a, SqliteConnection>,
+    cipher: Option<WalletCipher>,
     is_done: bool,
 }
 
 impl<'a> ReadTransaction<'a> {
-    pub fn new(connection: MutexGuard<'a, SqliteConnection>) -> Self {
+    pub(crate) fn new(connection: MutexGuard<'a, SqliteConnection>, cipher: Option<WalletCipher>) -> Self {
         Self {
             connection,
+            cipher,
             is_done: false,
         }
     }
 
+    /// The cipher used to encrypt wallet secrets, or None if the wallet is locked or has no passphrase
+    pub(crate) fn cipher(&self) -> Option<&WalletCipher> {
+        self.cipher.as_ref()
+    }
+
+    /// Returns true if a passphrase has been set for the wallet
+    pub(crate) fn is_encryption_enabled(&mut self) -> Result<bool, WalletStorageError> {
+        use crate::schema::config;
+
+        config::table
+            .filter(config::key.eq(ENCRYPTION_PARAMS_KEY))
+            .count()
+            .get_result(self.connection())
+            .map(|count: i64| count > 0)
+            .map_err(|e| WalletStorageError::general("is_encryption_enabled", e))
+    }
+
     pub(super) fn is_done(&self) -> bool {
         self.is_done
     }
@@ -155,9 +179,25 @@ impl WalletStoreReader for ReadTransaction<'_> {
                 key: key.to_string(),
             })?;
 
+        // Values are only stored encrypted once a passphrase has been set. Before that, values marked as encrypted are
+        // stored in plaintext and are encrypted when the passphrase is set.
+        let value = if config.is_encrypted {
+            match self.cipher().cloned() {
+                Some(cipher) => {
+                    let encrypted = deserialize_json::<String>(&config.value)?;
+                    let plaintext = cipher.decrypt_config_value(&config.key, &encrypted)?;
+                    deserialize_json(&plaintext)?
+                },
+                None if self.is_encryption_enabled()? => return Err(WalletStorageError::WalletLocked),
+                None => deserialize_json(&config.value)?,
+            }
+        } else {
+            deserialize_json(&config.value)?
+        };
+
         Ok(Config {
             key: config.key,
-            value: deserialize_json(&config.value)?,
+            value,
             is_encrypted: config.is_encrypted,
             created_at: 0,
             updated_at: 0,
@@ -39,6 +39,7 @@ use tari_utilities::hex::Hex;
 
 use crate::{
     diesel::ExpressionMethods,
+    encryption::WalletCipher,
     models::{self, TransactionInputs},
     reader::ReadTransaction,
     schema::auth_status,
@@ -54,11 +55,33 @@ pub struct WriteTransaction<'a> {
 }
 
 impl<'a> WriteTransaction<'a> {
-    pub fn new(connection: MutexGuard<'a, SqliteConnection>) -> Self {
+    pub(crate) fn new(connection: MutexGuard<'a, SqliteConnection>, cipher: Option<WalletCipher>) -> Self {
         Self {
-            transaction: ReadTransaction::new(connection),
+            transaction: ReadTransaction::new(connection, cipher),
         }
     }
+
+    /// Encrypts all config values that are marked as encrypted but were stored in plaintext because no passphrase had
+    /// been set.
+    pub(crate) fn config_encrypt_plaintext_values(&mut self, cipher: &WalletCipher) -> Result<(), WalletStorageError> {
+        use crate::schema::config;
+
+        let records = config::table
+            .filter(config::is_encrypted.eq(true))
+            .get_results::<models::Config>(self.connection())
+            .map_err(|e| WalletStorageError::general("config_encrypt_plaintext_values", e))?;
+
+        for record in records {
+            let encrypted = cipher.encrypt_config_value(&record.key, &record.value)?;
+            diesel::update(config::table)
+                .filter(config::id.eq(record.id))
+                .set(config::value.eq(serialize_json(&encrypted)?))
+                .execute(self.connection())
+                .map_err(|e| WalletStorageError::general("config_encrypt_plaintext_values", e))?;
+        }
+
+        Ok(())
+    }
 }
 
 impl WalletStoreWriter for WriteTransaction<'_> {
@@ -219,6 +242,18 @@ impl WalletStoreWriter for WriteTransaction<'_> {
     fn config_set<T: Serialize>(&mut self, key: &str, value: &T, is_encrypted: bool) -> Result<(), WalletStorageError> {
         use crate::schema::config;
 
+        let value = serialize_json(value)?;
+        let value = if is_encrypted {
+            match self.cipher().cloned() {
+                Some(cipher) => serialize_json(&cipher.encrypt_config_value(key, &value)?)?,
+                None if self.is_encryption_enabled()? => return Err(WalletStorageError::WalletLocked),
+                // No passphrase has been set, the value is encrypted once it is
+                None => value,
+            }
+        } else {
+            value
+        };
+
         let exists = config::table
             .filter(config::key.eq(key))
             .limit(1)
@@ -229,14 +264,15 @@ impl WalletStoreWriter for WriteTransaction<'_> {
 
         if exists {
             sql_query("UPDATE config SET value = ?, is_encrypted = ?, updated_at = CURRENT_TIMESTAMP WHERE key = ?")
-                .bind::<Text, _>(serialize_json(value)?)
+                .bind::<Text, _>(value)
+                .bind::<Bool, _>(is_encrypted)
                 .bind::<Text, _>(key)
                 .execute(self.connection())
                 .map_err(|e| WalletStorageError::general("key_manager_set_index", e))?;
         } else {
             sql_query("INSERT INTO config (key, value, is_encrypted) VALUES (?, ?, ?)")
                 .bind::<Text, _>(key)
-                .bind::<Text, _>(serialize_json(value)?)
+                .bind::<Text, _>(value)
                 .bind::<Bool, _>(is_encrypted)
                 .execute(self.connection())
                 .map_err(|e| WalletStorageError::general("key_manager_set_index", e))?;
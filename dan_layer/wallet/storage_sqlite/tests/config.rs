@@ -2,8 +2,9 @@
 //   SPDX-License-Identifier: BSD-3-Clause
 
 use tari_dan_common_types::optional::Optional;
-use tari_dan_wallet_sdk::storage::{WalletStore, WalletStoreReader, WalletStoreWriter};
+use tari_dan_wallet_sdk::storage::{WalletStorageError, WalletStore, WalletStoreReader, WalletStoreWriter};
 use tari_dan_wallet_storage_sqlite::SqliteWalletStore;
+use tari_utilities::SafePassword;
 
 #[test]
 fn get_and_set_value() {
@@ -19,3 +20,100 @@ fn get_and_set_value() {
     let rec = tx.config_get::<u32>("dummy").unwrap();
     assert_eq!(rec.value, 123);
 }
+
+#[test]
+fn encrypted_values_require_unlock() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let path = temp_dir.path().join("wallet.sqlite");
+    let db = SqliteWalletStore::try_open(&path).unwrap();
+    db.run_migrations().unwrap();
+
+    // Secrets stored before a passphrase is set are encrypted when it is set
+    let mut tx = db.create_write_tx().unwrap();
+    tx.config_set("secret", &"seed words".to_string(), true).unwrap();
+    tx.config_set("public", &123u32, false).unwrap();
+    tx.commit().unwrap();
+    assert!(!db.is_encrypted().unwrap());
+
+    let passphrase = SafePassword::from("correct horse");
+    db.set_passphrase(&passphrase, &passphrase).unwrap();
+    assert!(db.is_encrypted().unwrap());
+    let mut tx = db.create_read_tx().unwrap();
+    assert_eq!(tx.config_get::<String>("secret").unwrap().value, "seed words");
+    drop(tx);
+
+    // A locked store can read unencrypted values only
+    let locked = SqliteWalletStore::try_open(&path).unwrap();
+    let mut tx = locked.create_write_tx().unwrap();
+    assert_eq!(tx.config_get::<u32>("public").unwrap().value, 123);
+    let err = tx.config_get::<String>("secret").unwrap_err();
+    assert!(matches!(err, WalletStorageError::WalletLocked));
+    let err = tx.config_set("secret", &"other".to_string(), true).unwrap_err();
+    assert!(matches!(err, WalletStorageError::WalletLocked));
+    tx.rollback().unwrap();
+
+    let err = locked.unlock(&SafePassword::from("battery staple")).unwrap_err();
+    assert!(matches!(err, WalletStorageError::InvalidPassphrase));
+    locked.unlock(&SafePassword::from("correct horse")).unwrap();
+    let mut tx = locked.create_read_tx().unwrap();
+    assert_eq!(tx.config_get::<String>("secret").unwrap().value, "seed words");
+}
+
+#[test]
+fn change_passphrase() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let path = temp_dir.path().join("wallet.sqlite");
+    let db = SqliteWalletStore::try_open(&path).unwrap();
+    db.run_migrations().unwrap();
+
+    let err = db
+        .change_passphrase(&SafePassword::from("old"), &SafePassword::from("new"))
+        .unwrap_err();
+    assert!(matches!(err, WalletStorageError::WalletNotEncrypted));
+
+    db.set_passphrase(&SafePassword::from("old"), &SafePassword::from("old"))
+        .unwrap();
+    let mut tx = db.create_write_tx().unwrap();
+    tx.config_set("secret", &"seed words".to_string(), true).unwrap();
+    tx.commit().unwrap();
+
+    let err = db
+        .change_passphrase(&SafePassword::from("wrong"), &SafePassword::from("new"))
+        .unwrap_err();
+    assert!(matches!(err, WalletStorageError::InvalidPassphrase));
+    db.change_passphrase(&SafePassword::from("old"), &SafePassword::from("new"))
+        .unwrap();
+
+    let reopened = SqliteWalletStore::try_open(&path).unwrap();
+    let err = reopened.unlock(&SafePassword::from("old")).unwrap_err();
+    assert!(matches!(err, WalletStorageError::InvalidPassphrase));
+    reopened.unlock(&SafePassword::from("new")).unwrap();
+    let mut tx = reopened.create_read_tx().unwrap();
+    assert_eq!(tx.config_get::<String>("secret").unwrap().value, "seed words");
+}
+
+#[test]
+fn set_passphrase_requires_confirmation() {
+    let db = SqliteWalletStore::try_open(":memory:").unwrap();
+    db.run_migrations().unwrap();
+
+    // Unlocking a wallet without a passphrase does not set one
+    let err = db.unlock(&SafePassword::from("correct horse")).unwrap_err();
+    assert!(matches!(err, WalletStorageError::WalletNotEncrypted));
+    assert!(!db.is_encrypted().unwrap());
+
+    let passphrase = SafePassword::from("correct horse");
+    let err = db
+        .set_passphrase(&passphrase, &SafePassword::from("correct hrose"))
+        .unwrap_err();
+    assert!(matches!(err, WalletStorageError::PassphraseMismatch));
+    assert!(!db.is_encrypted().unwrap());
+
+    db.set_passphrase(&passphrase, &passphrase).unwrap();
+    assert!(db.is_encrypted().unwrap());
+    let err = db
+        .set_passphrase(&SafePassword::from("other"), &SafePassword::from("other"))
+        .unwrap_err();
+    assert!(matches!(err, WalletStorageError::WalletAlreadyEncrypted));
+    db.unlock(&passphrase).unwrap();
+}